@@ -1,22 +1,244 @@
-use tauri_specta::{collect_commands, Builder};
+use tauri_specta::{collect_commands, collect_events, Builder};
 
 pub fn generate_bindings() -> Builder<tauri::Wry> {
-    use crate::commands::{notifications, preferences, quick_pane, recovery};
+    use crate::commands::{
+        allowed_paths, analytics, auth, backup, batch, cache, clipboard, clipboard_history,
+        crash_reports, data_location, db, default_handler, diagnostics, discovery, displays,
+        dock_menu, documents, downloads, encryption, event_queue, file_stream, file_watcher, files,
+        http, jump_list, kv, launch_at_login, locale, logging, menu, mini_window, notifications,
+        performance, permissions, power, preferences, quick_pane, quick_pane_history,
+        recent_documents, recovery, release_notes, search, secrets, share, sse, startup, streams,
+        sync, taskbar, tasks, temp_dirs, thumbnails, tray, undo, updater, uploads, websocket,
+        window,
+    };
+    use crate::types::{
+        AuthCompleted, ClipboardChanged, CloseRequested, CrashReportAvailable, DeepLinkOpened,
+        DownloadChanged, FileOpened, FilesDropped, FsChange, LanguageChanged, MenuItemSelected,
+        NotificationAction, NotificationClicked, NotificationPermissionChanged, PeerFound,
+        PeerLost, PowerChanged, QuickEntrySubmitted, RecentDocumentSelected, RecoveryCleaned,
+        RecoveryLoadFailed, RecoverySaved, SyncStatus, ThemeChanged, UndoStateChanged,
+        UpdateAvailable, UpdateProgress, WindowFocusChanged, WindowFullscreenChanged, WindowMoved,
+        WindowResized, WindowThemeChanged, WsEvent,
+    };
 
-    Builder::<tauri::Wry>::new().commands(collect_commands![
-        preferences::greet,
-        preferences::load_preferences,
-        preferences::save_preferences,
-        notifications::send_native_notification,
-        recovery::save_emergency_data,
-        recovery::load_emergency_data,
-        recovery::cleanup_old_recovery_files,
-        quick_pane::show_quick_pane,
-        quick_pane::dismiss_quick_pane,
-        quick_pane::toggle_quick_pane,
-        quick_pane::get_default_quick_pane_shortcut,
-        quick_pane::update_quick_pane_shortcut,
-    ])
+    Builder::<tauri::Wry>::new()
+        .commands(collect_commands![
+            preferences::greet,
+            preferences::load_preferences,
+            preferences::save_preferences,
+            notifications::send_native_notification,
+            notifications::check_notification_permission,
+            notifications::request_notification_permission,
+            notifications::open_notification_settings,
+            permissions::check_permission,
+            permissions::request_permission,
+            permissions::open_system_settings_pane,
+            recovery::save_emergency_data,
+            recovery::load_emergency_data,
+            recovery::cleanup_old_recovery_files,
+            recovery::export_recovery_archive,
+            backup::create_backup,
+            backup::restore_backup,
+            data_location::get_data_location,
+            data_location::move_app_data,
+            db::db_query,
+            db::db_execute,
+            db::list_notes,
+            db::create_note,
+            kv::kv_get,
+            kv::kv_set,
+            kv::kv_delete,
+            kv::kv_list,
+            search::index_document,
+            search::remove_document,
+            search::search,
+            cache::cache_put,
+            cache::cache_get,
+            cache::cache_delete,
+            cache::cache_clear,
+            documents::create_document,
+            documents::save_document,
+            documents::flush_documents,
+            documents::list_documents,
+            documents::load_document,
+            documents::delete_document,
+            documents::list_revisions,
+            documents::load_revision,
+            undo::push_undo_entry,
+            undo::undo,
+            undo::redo,
+            undo::get_undo_depth,
+            undo::clear_undo_history,
+            secrets::store_secret,
+            secrets::get_secret,
+            secrets::delete_secret,
+            encryption::set_local_data_encryption,
+            launch_at_login::get_launch_at_login,
+            launch_at_login::set_launch_at_login,
+            files::read_text_file,
+            files::read_binary_file,
+            files::write_text_file,
+            files::trash_file,
+            files::reveal_in_file_manager,
+            files::open_with_default_app,
+            files::preview_file,
+            files::hash_file,
+            tasks::cancel_task,
+            file_watcher::watch_path,
+            file_watcher::unwatch,
+            file_stream::stream_file,
+            streams::ack_stream,
+            streams::stop_stream,
+            thumbnails::generate_thumbnail,
+            allowed_paths::list_allowed_paths,
+            allowed_paths::add_allowed_path,
+            allowed_paths::revoke_allowed_path,
+            temp_dirs::create_temp_dir,
+            temp_dirs::remove_temp_dir,
+            temp_dirs::cleanup_temp_dirs,
+            diagnostics::get_debug_info,
+            diagnostics::export_diagnostics,
+            analytics::track_event,
+            analytics::purge_analytics_data,
+            crash_reports::report_frontend_error,
+            crash_reports::dismiss_crash_report,
+            quick_pane::show_quick_pane,
+            quick_pane::dismiss_quick_pane,
+            quick_pane::toggle_quick_pane,
+            quick_pane::submit_quick_pane_entry,
+            quick_pane::auto_dismiss_quick_pane,
+            quick_pane::set_quick_pane_pinned,
+            quick_pane::set_quick_pane_position,
+            quick_pane::recenter_quick_pane,
+            quick_pane::resize_quick_pane,
+            quick_pane::get_default_quick_pane_shortcut,
+            quick_pane::update_quick_pane_shortcut,
+            quick_pane_history::get_quick_pane_history,
+            quick_pane_history::clear_quick_pane_history,
+            recent_documents::add_recent_document,
+            recent_documents::get_recent_documents,
+            recent_documents::clear_recent_documents,
+            window::get_default_activate_app_shortcut,
+            window::update_activate_app_shortcut,
+            window::show_main_window,
+            window::open_preferences_window,
+            window::create_app_window,
+            window::set_window_dirty,
+            window::request_app_quit,
+            window::confirm_window_close,
+            window::cancel_window_close,
+            window::set_title_bar_style,
+            window::set_traffic_light_inset,
+            window::set_always_on_top,
+            window::set_window_level,
+            menu::create_app_menu,
+            dock_menu::set_dock_menu,
+            jump_list::set_jump_list_tasks,
+            menu::set_menu_item_enabled,
+            menu::set_menu_item_checked,
+            menu::set_menu_item_text,
+            menu::insert_menu_item,
+            menu::remove_menu_item,
+            mini_window::enter_mini_mode,
+            mini_window::exit_mini_mode,
+            mini_window::snap_mini_window,
+            startup::finish_startup,
+            event_queue::frontend_ready,
+            taskbar::set_progress,
+            taskbar::set_badge,
+            taskbar::set_unread_count,
+            taskbar::set_auto_unread_count,
+            tray::set_tray_menu,
+            tray::set_tray_title,
+            tray::set_tray_tooltip,
+            updater::check_for_update,
+            updater::download_and_install_update,
+            updater::restart_to_update,
+            updater::install_update_from_file,
+            updater::set_update_channel,
+            release_notes::get_release_notes,
+            performance::get_performance_metrics,
+            displays::get_displays,
+            displays::get_cursor_position,
+            share::share_items,
+            default_handler::is_default_handler_for,
+            default_handler::set_as_default_handler,
+            locale::get_locale_info,
+            http::fetch,
+            http::cancel_fetch,
+            uploads::upload_file,
+            sse::sse_subscribe,
+            sse::sse_unsubscribe,
+            downloads::start_download,
+            downloads::pause_download,
+            downloads::resume_download,
+            downloads::cancel_download,
+            downloads::list_downloads,
+            downloads::clear_finished_downloads,
+            websocket::ws_connect,
+            websocket::ws_send,
+            websocket::ws_close,
+            websocket::ws_connections,
+            auth::start_auth,
+            auth::cancel_auth,
+            auth::get_auth_session,
+            auth::sign_out,
+            sync::queue_sync_change,
+            sync::sync_now,
+            sync::get_sync_status,
+            discovery::start_advertising,
+            discovery::stop_advertising,
+            discovery::browse_services,
+            discovery::stop_browsing,
+            power::get_power_state,
+            power::prevent_sleep,
+            power::allow_sleep,
+            clipboard::write_clipboard,
+            clipboard::read_clipboard,
+            clipboard::paste_into_previous_app,
+            clipboard_history::get_clipboard_history,
+            clipboard_history::clear_clipboard_history,
+            logging::get_log_directory_size,
+            logging::follow_logs,
+            logging::get_log_level,
+            logging::set_log_level,
+            batch::batch,
+        ])
+        .events(collect_events![
+            RecoverySaved,
+            RecoveryCleaned,
+            RecoveryLoadFailed,
+            QuickEntrySubmitted,
+            RecentDocumentSelected,
+            CloseRequested,
+            WindowFocusChanged,
+            WindowMoved,
+            WindowResized,
+            WindowThemeChanged,
+            WindowFullscreenChanged,
+            MenuItemSelected,
+            NotificationClicked,
+            NotificationAction,
+            NotificationPermissionChanged,
+            UpdateAvailable,
+            UpdateProgress,
+            CrashReportAvailable,
+            UndoStateChanged,
+            FileOpened,
+            DeepLinkOpened,
+            FilesDropped,
+            FsChange,
+            PowerChanged,
+            ClipboardChanged,
+            DownloadChanged,
+            WsEvent,
+            AuthCompleted,
+            SyncStatus,
+            PeerFound,
+            PeerLost,
+            ThemeChanged,
+            LanguageChanged,
+        ])
 }
 
 /// Export TypeScript bindings to the frontend.
@@ -0,0 +1,264 @@
+//! Disk cache for downloaded data (images, API responses).
+//!
+//! Entries live in `cache.db` in the OS cache directory, separate from
+//! `app.db`, so they're never backed up and the OS may clear them. Each entry
+//! can have a time to live; expired entries are never returned. Eviction
+//! removes expired entries and then the least recently used ones until the
+//! cache is under its size limit.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::types::AppError;
+
+/// Total size the cache is trimmed to.
+pub const MAX_CACHE_BYTES: u64 = 200 * 1024 * 1024;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS cache_entries (
+        key          TEXT PRIMARY KEY NOT NULL,
+        data         BLOB NOT NULL,
+        size         INTEGER NOT NULL,
+        expires_at   INTEGER,
+        accessed_at  INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS cache_entries_accessed_at ON cache_entries (accessed_at);
+";
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+fn cache_error(e: rusqlite::Error) -> AppError {
+    AppError::internal(format!("Cache error: {e}"))
+}
+
+/// Disk cache as held in Tauri managed state.
+pub struct DiskCache {
+    conn: Mutex<Connection>,
+    max_bytes: u64,
+}
+
+impl DiskCache {
+    /// Opens (or creates) the cache at `path`.
+    pub fn open(path: &Path, max_bytes: u64) -> Result<Self, AppError> {
+        let conn = Connection::open(path)
+            .map_err(|e| AppError::internal(format!("Failed to open cache: {e}")))?;
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(|e| AppError::internal(format!("Failed to configure cache: {e}")))?;
+        Self::with_connection(conn, max_bytes)
+    }
+
+    /// Creates a cache backed by an in-memory database.
+    #[cfg(test)]
+    fn open_in_memory(max_bytes: u64) -> Result<Self, AppError> {
+        let conn = Connection::open_in_memory()
+            .map_err(|e| AppError::internal(format!("Failed to open cache: {e}")))?;
+        Self::with_connection(conn, max_bytes)
+    }
+
+    fn with_connection(conn: Connection, max_bytes: u64) -> Result<Self, AppError> {
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| AppError::internal(format!("Failed to initialize cache: {e}")))?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+            max_bytes,
+        })
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Connection>, AppError> {
+        self.conn
+            .lock()
+            .map_err(|e| AppError::internal(format!("Failed to lock cache: {e}")))
+    }
+
+    /// Stores `data` under `key`, replacing any existing entry. Without a
+    /// `ttl` the entry stays until it's evicted for space.
+    pub fn put(&self, key: &str, data: &[u8], ttl: Option<Duration>) -> Result<(), AppError> {
+        let now = now_millis();
+        let expires_at = ttl.map(|ttl| now.saturating_add(ttl.as_millis() as i64));
+        let over_limit = {
+            let conn = self.lock()?;
+            conn.execute(
+                "INSERT OR REPLACE INTO cache_entries (key, data, size, expires_at, accessed_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![key, data, data.len() as i64, expires_at, now],
+            )
+            .map_err(cache_error)?;
+            total_size(&conn)? > self.max_bytes
+        };
+
+        if over_limit {
+            self.evict()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the data stored under `key`, unless it's missing or expired.
+    /// Marks the entry as recently used.
+    pub fn get(&self, key: &str) -> Result<Option<Vec<u8>>, AppError> {
+        let conn = self.lock()?;
+        let now = now_millis();
+        let entry: Option<(Vec<u8>, Option<i64>)> = conn
+            .query_row(
+                "SELECT data, expires_at FROM cache_entries WHERE key = ?1",
+                params![key],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(cache_error)?;
+
+        match entry {
+            Some((_, Some(expires_at))) if expires_at <= now => {
+                conn.execute("DELETE FROM cache_entries WHERE key = ?1", params![key])
+                    .map_err(cache_error)?;
+                Ok(None)
+            }
+            Some((data, _)) => {
+                conn.execute(
+                    "UPDATE cache_entries SET accessed_at = ?2 WHERE key = ?1",
+                    params![key, now],
+                )
+                .map_err(cache_error)?;
+                Ok(Some(data))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Removes the entry for `key`. Returns whether there was one.
+    pub fn remove(&self, key: &str) -> Result<bool, AppError> {
+        let removed = self
+            .lock()?
+            .execute("DELETE FROM cache_entries WHERE key = ?1", params![key])
+            .map_err(cache_error)?;
+        Ok(removed > 0)
+    }
+
+    /// Removes every entry. Returns how many were removed.
+    pub fn clear(&self) -> Result<u32, AppError> {
+        let removed = self
+            .lock()?
+            .execute("DELETE FROM cache_entries", [])
+            .map_err(cache_error)?;
+        Ok(u32::try_from(removed).unwrap_or(u32::MAX))
+    }
+
+    /// Removes expired entries, then least recently used ones until the
+    /// cache fits in its size limit. Returns how many were removed.
+    pub fn evict(&self) -> Result<u32, AppError> {
+        let mut conn = self.lock()?;
+        let tx = conn.transaction().map_err(cache_error)?;
+
+        let mut removed = tx
+            .execute(
+                "DELETE FROM cache_entries WHERE expires_at IS NOT NULL AND expires_at <= ?1",
+                params![now_millis()],
+            )
+            .map_err(cache_error)?;
+
+        let mut size = total_size(&tx)?;
+        if size > self.max_bytes {
+            let mut oldest: Vec<(String, u64)> = Vec::new();
+            {
+                let mut stmt = tx
+                    .prepare("SELECT key, size FROM cache_entries ORDER BY accessed_at, key")
+                    .map_err(cache_error)?;
+                let mut rows = stmt.query([]).map_err(cache_error)?;
+                while size > self.max_bytes {
+                    let Some(row) = rows.next().map_err(cache_error)? else {
+                        break;
+                    };
+                    let entry_size = row.get::<_, i64>(1).map_err(cache_error)?.max(0) as u64;
+                    oldest.push((row.get(0).map_err(cache_error)?, entry_size));
+                    size = size.saturating_sub(entry_size);
+                }
+            }
+            for (key, _) in &oldest {
+                tx.execute("DELETE FROM cache_entries WHERE key = ?1", params![key])
+                    .map_err(cache_error)?;
+            }
+            removed += oldest.len();
+        }
+
+        tx.commit().map_err(cache_error)?;
+        if removed > 0 {
+            log::debug!("Evicted {removed} cache entries");
+        }
+        Ok(u32::try_from(removed).unwrap_or(u32::MAX))
+    }
+}
+
+fn total_size(conn: &Connection) -> Result<u64, AppError> {
+    conn.query_row(
+        "SELECT COALESCE(SUM(size), 0) FROM cache_entries",
+        [],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|size| size.max(0) as u64)
+    .map_err(cache_error)
+}
+
+/// Opens `cache.db` in the app cache directory, creating the directory if needed.
+pub fn open_app_cache(app: &AppHandle) -> Result<DiskCache, AppError> {
+    let cache_dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| AppError::internal(format!("Failed to get app cache directory: {e}")))?;
+
+    std::fs::create_dir_all(&cache_dir)
+        .map_err(|e| AppError::io(format!("Failed to create app cache directory: {e}")))?;
+
+    DiskCache::open(&cache_dir.join("cache.db"), MAX_CACHE_BYTES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_get_remove() {
+        let cache = DiskCache::open_in_memory(1024).unwrap();
+        assert_eq!(cache.get("a").unwrap(), None);
+
+        cache.put("a", b"hello", None).unwrap();
+        assert_eq!(cache.get("a").unwrap().as_deref(), Some(&b"hello"[..]));
+
+        assert!(cache.remove("a").unwrap());
+        assert!(!cache.remove("a").unwrap());
+    }
+
+    #[test]
+    fn test_expired_entries_are_not_returned() {
+        let cache = DiskCache::open_in_memory(1024).unwrap();
+        cache.put("a", b"hello", Some(Duration::ZERO)).unwrap();
+        assert_eq!(cache.get("a").unwrap(), None);
+
+        cache.put("b", b"hello", Some(Duration::ZERO)).unwrap();
+        assert_eq!(cache.evict().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_least_recently_used_is_evicted_first() {
+        let cache = DiskCache::open_in_memory(10).unwrap();
+        cache.put("a", b"1234", None).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        cache.put("b", b"1234", None).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        // Reading `a` makes `b` the least recently used
+        cache.get("a").unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+
+        // Over the limit, so the put evicts `b`
+        cache.put("c", b"1234", None).unwrap();
+        assert!(cache.get("a").unwrap().is_some());
+        assert!(cache.get("b").unwrap().is_none());
+        assert!(cache.get("c").unwrap().is_some());
+    }
+}
@@ -0,0 +1,435 @@
+//! Files and folders the user has given the app access to.
+//!
+//! Paths enter the filesystem scope when they're picked in a dialog, dropped
+//! on a window or opened with the app. Each one is recorded in
+//! `allowed-paths.json` in the app data directory and allowed again at the
+//! next launch, so access granted once lasts.
+//!
+//! The scope can't shrink at runtime, only forbid paths, and a forbidden path
+//! stays forbidden until the app restarts. `revoke_allowed_path` therefore
+//! removes the path from the saved list (so it isn't allowed at the next
+//! launch) and forbids it for the rest of this session.
+//!
+//! Earlier versions of the template used the persisted-scope plugin. The
+//! paths it saved are imported once, the first time the app launches
+//! without it.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::scope::fs::Event as ScopeEvent;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::DialogExt;
+use tauri_plugin_fs::FsExt;
+
+use super::files::write_atomic;
+use crate::types::{AllowedPath, AppError};
+
+/// Most paths kept. The oldest are dropped first, so files opened long ago
+/// lose access before folders the user chose recently.
+const MAX_ALLOWED_PATHS: usize = 1000;
+
+/// File the persisted-scope plugin saved its scope to, in the default app
+/// data directory.
+const LEGACY_SCOPE_FILE: &str = ".persisted-scope";
+
+/// Serializes read-modify-write cycles on the allowed paths file.
+static ALLOWED_PATHS_LOCK: Mutex<()> = Mutex::new(());
+
+fn get_allowed_paths_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = crate::data_dir::app_data_dir(app)?;
+    std::fs::create_dir_all(&app_data_dir)
+        .map_err(|e| AppError::io(format!("Failed to create app data directory: {e}")))?;
+    Ok(app_data_dir.join("allowed-paths.json"))
+}
+
+/// Reads the allowed paths, oldest first.
+fn read_allowed_paths(app: &AppHandle) -> Result<Vec<AllowedPath>, AppError> {
+    let path = get_allowed_paths_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::io(format!("Failed to read allowed paths: {e}")))?;
+    serde_json::from_str(&contents).map_err(|e| AppError::Serialization {
+        message: format!("Failed to parse allowed paths: {e}"),
+    })
+}
+
+/// Writes the allowed paths file using atomic write (temp file + rename).
+fn write_allowed_paths(app: &AppHandle, paths: &[AllowedPath]) -> Result<(), AppError> {
+    let path = get_allowed_paths_path(app)?;
+    let json_content =
+        serde_json::to_string_pretty(paths).map_err(|e| AppError::Serialization {
+            message: format!("Failed to serialize allowed paths: {e}"),
+        })?;
+
+    write_atomic(&path, json_content.as_bytes())
+        .map_err(|e| AppError::io(format!("Failed to write allowed paths: {e}")))
+}
+
+/// Adds `entry` unless it's already covered by a saved folder, dropping the
+/// oldest entries beyond `max_paths`. Returns whether the list changed.
+fn push_allowed_path(paths: &mut Vec<AllowedPath>, entry: AllowedPath, max_paths: usize) -> bool {
+    let covered = paths.iter().any(|existing| {
+        existing.path == entry.path
+            || (existing.is_directory && Path::new(&entry.path).starts_with(&existing.path))
+    });
+    if covered {
+        return false;
+    }
+
+    // A newly allowed folder covers anything saved inside it
+    if entry.is_directory {
+        paths.retain(|existing| !Path::new(&existing.path).starts_with(&entry.path));
+    }
+    paths.push(entry);
+    if paths.len() > max_paths {
+        paths.drain(..paths.len() - max_paths);
+    }
+    true
+}
+
+/// Saves a path that was just added to the scope.
+fn record_allowed_path(app: &AppHandle, path: &Path) -> Result<(), AppError> {
+    let entry = AllowedPath {
+        path: path.to_string_lossy().into_owned(),
+        is_directory: path.is_dir(),
+    };
+
+    let _guard = ALLOWED_PATHS_LOCK
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock allowed paths: {e}")))?;
+    let mut paths = read_allowed_paths(app)?;
+    if push_allowed_path(&mut paths, entry, MAX_ALLOWED_PATHS) {
+        write_allowed_paths(app, &paths)?;
+    }
+    Ok(())
+}
+
+/// Reads a little-endian u64 length, as bincode writes them.
+fn take_len(bytes: &mut &[u8]) -> Option<usize> {
+    let (len, rest) = bytes.split_first_chunk::<8>()?;
+    *bytes = rest;
+    usize::try_from(u64::from_le_bytes(*len)).ok()
+}
+
+/// Reads the allowed patterns from the persisted-scope plugin's state file:
+/// bincode-encoded sets of allowed and then forbidden patterns, each a count
+/// followed by length-prefixed strings. The forbidden patterns are ignored.
+fn parse_legacy_scope(mut bytes: &[u8]) -> Option<Vec<String>> {
+    let count = take_len(&mut bytes)?;
+    let mut patterns = Vec::new();
+    for _ in 0..count {
+        let len = take_len(&mut bytes)?;
+        if len > bytes.len() {
+            return None;
+        }
+        let (pattern, rest) = bytes.split_at(len);
+        patterns.push(String::from_utf8(pattern.to_vec()).ok()?);
+        bytes = rest;
+    }
+    Some(patterns)
+}
+
+/// Turns a scope pattern back into the path it allows. Folders end in `/**`
+/// (or `/*` without subfolders, which is widened to the whole folder), and
+/// glob characters in the path are escaped as `[*]`, `[?]`, `[[]` and `[]]`.
+fn legacy_pattern_to_entry(pattern: &str) -> Option<AllowedPath> {
+    let (path, is_directory) = match pattern
+        .strip_suffix("**")
+        .or_else(|| pattern.strip_suffix('*'))
+    {
+        Some(dir) => (dir.trim_end_matches(['/', '\\']), true),
+        None => (pattern, false),
+    };
+
+    let chars: Vec<char> = path.chars().collect();
+    let mut unescaped = String::with_capacity(path.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '[' && chars.get(i + 2) == Some(&']') {
+            unescaped.push(chars[i + 1]);
+            i += 3;
+        } else {
+            unescaped.push(chars[i]);
+            i += 1;
+        }
+    }
+
+    (!unescaped.is_empty()).then_some(AllowedPath {
+        path: unescaped,
+        is_directory,
+    })
+}
+
+/// Imports the paths saved by the persisted-scope plugin, if its state file
+/// is still there, then deletes the file. Paths that no longer exist are
+/// skipped. Returns how many were imported.
+fn import_legacy_scope(app: &AppHandle) -> Result<usize, AppError> {
+    let legacy_path = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::internal(format!("Failed to get app data directory: {e}")))?
+        .join(LEGACY_SCOPE_FILE);
+    let bytes = match std::fs::read(&legacy_path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(AppError::io(format!("Failed to read persisted scope: {e}"))),
+    };
+
+    let imported = match parse_legacy_scope(&bytes) {
+        Some(patterns) => {
+            let _guard = ALLOWED_PATHS_LOCK
+                .lock()
+                .map_err(|e| AppError::internal(format!("Failed to lock allowed paths: {e}")))?;
+            let mut paths = read_allowed_paths(app)?;
+            let mut imported = 0;
+            for entry in patterns.iter().filter_map(|p| legacy_pattern_to_entry(p)) {
+                if Path::new(&entry.path).exists()
+                    && push_allowed_path(&mut paths, entry, MAX_ALLOWED_PATHS)
+                {
+                    imported += 1;
+                }
+            }
+            write_allowed_paths(app, &paths)?;
+            imported
+        }
+        None => {
+            log::warn!("Persisted scope file is not in the expected format, not importing it");
+            0
+        }
+    };
+
+    // The plugin is gone, so the file is only ever read here
+    if let Err(e) = std::fs::remove_file(&legacy_path) {
+        log::warn!("Failed to remove persisted scope file: {e}");
+    }
+    Ok(imported)
+}
+
+/// Allows the saved paths again, then starts saving paths as they're added
+/// to the scope. Called during app setup, before anything adds to the scope.
+pub fn init(app: &AppHandle) {
+    match import_legacy_scope(app) {
+        Ok(0) => {}
+        Ok(count) => log::info!("Imported {count} path(s) from the persisted-scope plugin"),
+        Err(e) => log::warn!("Failed to import persisted scope: {e}"),
+    }
+
+    let scope = app.fs_scope();
+    match read_allowed_paths(app) {
+        Ok(paths) => {
+            for entry in &paths {
+                let result = if entry.is_directory {
+                    scope.allow_directory(&entry.path, true)
+                } else {
+                    scope.allow_file(&entry.path)
+                };
+                if let Err(e) = result {
+                    log::warn!("Failed to restore access to {}: {e}", entry.path);
+                }
+            }
+            log::debug!("Restored access to {} path(s)", paths.len());
+        }
+        Err(e) => log::warn!("Failed to load allowed paths: {e}"),
+    }
+
+    let app = app.clone();
+    scope.listen(move |event| {
+        if let ScopeEvent::PathAllowed(path) = event {
+            if let Err(e) = record_allowed_path(&app, path) {
+                log::warn!("Failed to save allowed path: {e}");
+            }
+        }
+    });
+}
+
+/// Lists the files and folders the app has been given access to, oldest
+/// first. Folders include everything inside them.
+#[tauri::command]
+#[specta::specta]
+pub fn list_allowed_paths(app: AppHandle) -> Result<Vec<AllowedPath>, AppError> {
+    let _guard = ALLOWED_PATHS_LOCK
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock allowed paths: {e}")))?;
+    read_allowed_paths(&app)
+}
+
+/// Shows a native folder picker, starting in `default_path` if given, and
+/// gives the app lasting access to the chosen folder. Returns None if the
+/// user cancelled. Access is only ever granted through the picker, so the
+/// frontend can't grant itself arbitrary paths.
+#[tauri::command]
+#[specta::specta]
+pub async fn add_allowed_path(
+    app: AppHandle,
+    default_path: Option<String>,
+) -> Result<Option<AllowedPath>, AppError> {
+    let mut dialog = app.dialog().file();
+    if let Some(default_path) = default_path {
+        dialog = dialog.set_directory(default_path);
+    }
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    dialog.pick_folder(move |folder| {
+        let _ = sender.send(folder);
+    });
+    let folder = tauri::async_runtime::spawn_blocking(move || receiver.recv())
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to wait for folder picker: {e}")))?
+        .map_err(|_| AppError::internal("Folder picker closed unexpectedly"))?;
+    let Some(folder) = folder else {
+        return Ok(None);
+    };
+    let folder = folder
+        .into_path()
+        .map_err(|e| AppError::validation(format!("Invalid folder: {e}")))?;
+
+    let scope = app.fs_scope();
+    if scope.is_forbidden(&folder) {
+        return Err(AppError::Permission {
+            message: "Access to this folder was revoked. Restart the app to grant it again."
+                .to_string(),
+        });
+    }
+    // Saved by the scope listener set up in `init`
+    scope
+        .allow_directory(&folder, true)
+        .map_err(|e| AppError::internal(format!("Failed to allow folder: {e}")))?;
+
+    log::info!("Granted access to {}", folder.display());
+    Ok(Some(AllowedPath {
+        path: folder.to_string_lossy().into_owned(),
+        is_directory: true,
+    }))
+}
+
+/// Removes access to a path returned by `list_allowed_paths`. Takes effect
+/// immediately; the path can't be granted again until the app restarts.
+/// Returns false if the path wasn't in the list.
+#[tauri::command]
+#[specta::specta]
+pub fn revoke_allowed_path(app: AppHandle, path: String) -> Result<bool, AppError> {
+    let removed = {
+        let _guard = ALLOWED_PATHS_LOCK
+            .lock()
+            .map_err(|e| AppError::internal(format!("Failed to lock allowed paths: {e}")))?;
+        let mut paths = read_allowed_paths(&app)?;
+        let Some(index) = paths.iter().position(|entry| entry.path == path) else {
+            return Ok(false);
+        };
+        let removed = paths.remove(index);
+        write_allowed_paths(&app, &paths)?;
+        removed
+    };
+
+    let scope = app.fs_scope();
+    let result = if removed.is_directory {
+        scope.forbid_directory(&removed.path, true)
+    } else {
+        scope.forbid_file(&removed.path)
+    };
+    result.map_err(|e| AppError::internal(format!("Failed to revoke access: {e}")))?;
+
+    log::info!("Revoked access to {path}");
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, is_directory: bool) -> AllowedPath {
+        AllowedPath {
+            path: path.to_string(),
+            is_directory,
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_push_allowed_path() {
+        let mut paths = Vec::new();
+        assert!(push_allowed_path(&mut paths, entry("/docs/a.md", false), 3));
+        assert!(push_allowed_path(
+            &mut paths,
+            entry("/photos/b.png", false),
+            3
+        ));
+        // Already saved
+        assert!(!push_allowed_path(
+            &mut paths,
+            entry("/docs/a.md", false),
+            3
+        ));
+
+        // A folder replaces the files inside it, and covers later ones
+        assert!(push_allowed_path(&mut paths, entry("/docs", true), 3));
+        assert_eq!(
+            paths,
+            vec![entry("/photos/b.png", false), entry("/docs", true)]
+        );
+        assert!(!push_allowed_path(
+            &mut paths,
+            entry("/docs/c.md", false),
+            3
+        ));
+
+        // The oldest entries go first
+        assert!(push_allowed_path(&mut paths, entry("/x.md", false), 3));
+        assert!(push_allowed_path(&mut paths, entry("/y.md", false), 3));
+        assert_eq!(
+            paths,
+            vec![
+                entry("/docs", true),
+                entry("/x.md", false),
+                entry("/y.md", false)
+            ]
+        );
+    }
+
+    fn encode(patterns: &[&str]) -> Vec<u8> {
+        let mut bytes = (patterns.len() as u64).to_le_bytes().to_vec();
+        for pattern in patterns {
+            bytes.extend((pattern.len() as u64).to_le_bytes());
+            bytes.extend(pattern.as_bytes());
+        }
+        // No forbidden patterns
+        bytes.extend(0u64.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_parse_legacy_scope() {
+        let patterns = ["/docs/**", "/photos/a[*].png"];
+        assert_eq!(
+            parse_legacy_scope(&encode(&patterns)),
+            Some(vec![patterns[0].to_string(), patterns[1].to_string()])
+        );
+        assert_eq!(parse_legacy_scope(&[]), None);
+        // A length running past the end
+        assert_eq!(parse_legacy_scope(&encode(&patterns)[..20]), None);
+    }
+
+    #[test]
+    fn test_legacy_pattern_to_entry() {
+        assert_eq!(
+            legacy_pattern_to_entry("/docs/**"),
+            Some(entry("/docs", true))
+        );
+        assert_eq!(
+            legacy_pattern_to_entry("/docs/*"),
+            Some(entry("/docs", true))
+        );
+        assert_eq!(
+            legacy_pattern_to_entry("/photos/a[*][[]1[]].png"),
+            Some(entry("/photos/a*[1].png", false))
+        );
+        assert_eq!(
+            legacy_pattern_to_entry(r"C:\Users\me\Notes\**"),
+            Some(entry(r"C:\Users\me\Notes", true))
+        );
+        assert_eq!(legacy_pattern_to_entry("/**"), None);
+    }
+}
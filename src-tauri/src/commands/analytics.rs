@@ -0,0 +1,454 @@
+//! Opt-in usage analytics.
+//!
+//! Nothing is recorded unless the user turns on `analytics_enabled` (off by
+//! default). `track_event` validates each event and appends it to
+//! `analytics-queue.jsonl` in the app data directory, one JSON object per
+//! line; the queue is sent to `ANALYTICS_ENDPOINT` in batches, and deleted
+//! when analytics are turned off or by `purge_analytics_data`. The opt-in is
+//! cached in memory, so tracking an event doesn't read preferences.
+//!
+//! Events carry only what the caller passes plus the app version and OS: no
+//! user or device identifiers. Props are flat and small, so they can't carry
+//! documents or other user content by accident.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{LazyLock, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+use super::files::write_atomic;
+use super::preferences::load_analytics_enabled;
+use crate::types::AppError;
+
+/// Where queued events are POSTed as JSON. Events stay queued on disk (up to
+/// `MAX_QUEUED_EVENTS`) until this is set.
+const ANALYTICS_ENDPOINT: Option<&str> = None;
+
+/// Events sent per request. Reaching this many queued also triggers a send.
+const BATCH_SIZE: usize = 50;
+
+/// Older events are dropped beyond this many. The queue is trimmed once it's
+/// `BATCH_SIZE` over, so a full queue isn't rewritten for every event.
+const MAX_QUEUED_EVENTS: usize = 1000;
+
+/// How often queued events are sent.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(15);
+
+const MAX_PROPS: usize = 20;
+const MAX_PROP_STRING_LENGTH: usize = 200;
+const MAX_EVENT_BYTES: usize = 4 * 1024;
+
+/// Event names and prop keys: lowercase snake_case, dots allowed for
+/// namespacing (e.g. `editor.document_opened`).
+static NAME_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[a-z][a-z0-9_.]{0,63}$").expect("valid regex"));
+
+/// Held while the queue file is read or written. Holds the number of queued
+/// events once it's known, so appending doesn't need to read the file.
+static QUEUE: Mutex<Option<usize>> = Mutex::new(None);
+
+/// Whether the user has opted in. Set at startup and when preferences are saved.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static FLUSHING: AtomicBool = AtomicBool::new(false);
+
+static NEXT_EVENT: AtomicU32 = AtomicU32::new(0);
+
+/// A queued event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AnalyticsEvent {
+    id: String,
+    name: String,
+    props: HashMap<String, Value>,
+    /// Unix timestamp in milliseconds
+    timestamp: u64,
+}
+
+/// The body POSTed to `ANALYTICS_ENDPOINT`.
+#[derive(Debug, Serialize)]
+struct AnalyticsBatch<'a> {
+    app_version: String,
+    os: String,
+    events: &'a [AnalyticsEvent],
+}
+
+/// Gets the path to the analytics queue file.
+fn get_queue_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = crate::data_dir::app_data_dir(app)?;
+
+    std::fs::create_dir_all(&app_data_dir)
+        .map_err(|e| AppError::io(format!("Failed to create app data directory: {e}")))?;
+
+    Ok(app_data_dir.join("analytics-queue.jsonl"))
+}
+
+/// Parses queued events, one per line. Unreadable lines, such as one cut
+/// short by a crash mid-append, are skipped.
+fn parse_queue(contents: &str) -> Vec<AnalyticsEvent> {
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            serde_json::from_str(line)
+                .inspect_err(|e| log::warn!("Skipping unreadable analytics event: {e}"))
+                .ok()
+        })
+        .collect()
+}
+
+/// Serializes an event as one line of the queue file.
+fn queue_line(event: &AnalyticsEvent) -> Result<String, AppError> {
+    serde_json::to_string(event)
+        .map(|json| json + "\n")
+        .map_err(|e| AppError::Serialization {
+            message: format!("Failed to serialize analytics event: {e}"),
+        })
+}
+
+/// Loads the queue. A missing queue is treated as empty.
+fn load_queue(app: &AppHandle) -> Result<Vec<AnalyticsEvent>, AppError> {
+    let path = get_queue_path(app)?;
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => Ok(parse_queue(&contents)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(AppError::io(format!("Failed to read analytics queue: {e}"))),
+    }
+}
+
+/// Replaces the queue with `events`, written atomically.
+fn save_queue(app: &AppHandle, events: &[AnalyticsEvent]) -> Result<(), AppError> {
+    let path = get_queue_path(app)?;
+    let lines = events
+        .iter()
+        .map(queue_line)
+        .collect::<Result<String, AppError>>()?;
+
+    write_atomic(&path, lines.as_bytes())
+        .map_err(|e| AppError::io(format!("Failed to save analytics queue: {e}")))
+}
+
+/// Adds an event to the end of the queue without rewriting it.
+fn append_event(app: &AppHandle, event: &AnalyticsEvent) -> Result<(), AppError> {
+    let path = get_queue_path(app)?;
+    let line = queue_line(event)?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(|e| AppError::io(format!("Failed to queue analytics event: {e}")))
+}
+
+/// Deletes the queue file, if any.
+fn delete_queue(app: &AppHandle) -> Result<(), AppError> {
+    let path = get_queue_path(app)?;
+    match std::fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(AppError::io(format!(
+            "Failed to delete analytics queue: {e}"
+        ))),
+    }
+}
+
+fn lock_queue() -> Result<MutexGuard<'static, Option<usize>>, AppError> {
+    QUEUE
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock analytics queue: {e}")))
+}
+
+/// Updates the cached opt-in. Called when preferences are saved.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Checks an event name and props against the allowed shape: a snake_case
+/// name, at most `MAX_PROPS` props with snake_case keys, values that are
+/// short strings, numbers, booleans or null, and a small total size.
+fn validate_event(name: &str, props: &HashMap<String, Value>) -> Result<(), AppError> {
+    if !NAME_PATTERN.is_match(name) {
+        return Err(AppError::validation(format!(
+            "Invalid event name {name:?}: use lowercase letters, digits, underscores and dots"
+        )));
+    }
+    if props.len() > MAX_PROPS {
+        return Err(AppError::validation(format!(
+            "Too many props ({}, max {MAX_PROPS})",
+            props.len()
+        )));
+    }
+
+    for (key, value) in props {
+        if !NAME_PATTERN.is_match(key) {
+            return Err(AppError::validation(format!(
+                "Invalid prop name {key:?}: use lowercase letters, digits, underscores and dots"
+            )));
+        }
+        match value {
+            Value::Null | Value::Bool(_) | Value::Number(_) => {}
+            Value::String(text) if text.chars().count() <= MAX_PROP_STRING_LENGTH => {}
+            Value::String(_) => {
+                return Err(AppError::validation(format!(
+                    "Prop {key:?} is too long (max {MAX_PROP_STRING_LENGTH} characters)"
+                )))
+            }
+            Value::Array(_) | Value::Object(_) => {
+                return Err(AppError::validation(format!(
+                    "Prop {key:?} must be a string, number, boolean or null"
+                )))
+            }
+        }
+    }
+
+    let size = name.len()
+        + serde_json::to_vec(props)
+            .map_err(|e| AppError::Serialization {
+                message: format!("Failed to serialize props: {e}"),
+            })?
+            .len();
+    if size > MAX_EVENT_BYTES {
+        return Err(AppError::validation(format!(
+            "Event too large ({size} bytes, max {MAX_EVENT_BYTES})"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Records an analytics event, if the user has turned analytics on. Does
+/// nothing otherwise, so callers don't need to check. Invalid events are
+/// rejected whether or not analytics are on, so mistakes show up in
+/// development.
+#[tauri::command]
+#[specta::specta]
+pub fn track_event(
+    app: AppHandle,
+    name: String,
+    props: Option<HashMap<String, Value>>,
+) -> Result<(), AppError> {
+    let props = props.unwrap_or_default();
+    validate_event(&name, &props)?;
+
+    if !ENABLED.load(Ordering::Relaxed) {
+        return Ok(());
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default();
+    let event = AnalyticsEvent {
+        id: format!("{timestamp}-{}", NEXT_EVENT.fetch_add(1, Ordering::SeqCst)),
+        name,
+        props,
+        timestamp,
+    };
+
+    let queued = {
+        let mut queued = lock_queue()?;
+        let count = match *queued {
+            Some(count) => count,
+            // Rewritten once per launch, so a line cut short by a crash
+            // can't swallow the next event
+            None => {
+                let queue = load_queue(&app)?;
+                save_queue(&app, &queue)?;
+                queue.len()
+            }
+        };
+        append_event(&app, &event)?;
+
+        let mut count = count + 1;
+        if count >= MAX_QUEUED_EVENTS + BATCH_SIZE {
+            let mut queue = load_queue(&app)?;
+            let excess = queue.len().saturating_sub(MAX_QUEUED_EVENTS);
+            queue.drain(..excess);
+            save_queue(&app, &queue)?;
+            count = queue.len();
+        }
+        *queued = Some(count);
+        count
+    };
+
+    if queued >= BATCH_SIZE {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move { flush(&app).await });
+    }
+    Ok(())
+}
+
+/// Deletes all queued analytics events. Called when the user turns analytics
+/// off, or to honour a request to delete collected data.
+#[tauri::command]
+#[specta::specta]
+pub fn purge_analytics_data(app: AppHandle) -> Result<(), AppError> {
+    let mut queued = lock_queue()?;
+    delete_queue(&app)?;
+    *queued = Some(0);
+    log::info!("Analytics data purged");
+    Ok(())
+}
+
+/// Sends queued events in batches. Drops the queue instead if analytics have
+/// been turned off since they were recorded. Events that fail to send stay
+/// queued for next time.
+async fn flush(app: &AppHandle) {
+    if FLUSHING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    if let Err(e) = flush_queue(app).await {
+        log::warn!("Failed to send analytics: {e}");
+    }
+    FLUSHING.store(false, Ordering::SeqCst);
+}
+
+async fn flush_queue(app: &AppHandle) -> Result<(), AppError> {
+    if !ENABLED.load(Ordering::Relaxed) {
+        let mut queued = lock_queue()?;
+        delete_queue(app)?;
+        *queued = Some(0);
+        return Ok(());
+    }
+    let Some(endpoint) = ANALYTICS_ENDPOINT else {
+        return Ok(());
+    };
+
+    // reqwest is built without a TLS provider (like the updater plugin); this
+    // fails harmlessly if one is already installed
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    let package = app.package_info();
+    let client = reqwest::Client::builder()
+        .user_agent(format!("{}/{}", package.name, package.version))
+        .timeout(SEND_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::internal(format!("Failed to create HTTP client: {e}")))?;
+
+    loop {
+        let batch: Vec<AnalyticsEvent> = {
+            let _guard = lock_queue()?;
+            load_queue(app)?.into_iter().take(BATCH_SIZE).collect()
+        };
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let body = AnalyticsBatch {
+            app_version: package.version.to_string(),
+            os: tauri_plugin_os::platform().to_string(),
+            events: &batch,
+        };
+        client
+            .post(endpoint)
+            .json(&body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| AppError::internal(format!("Failed to send analytics batch: {e}")))?;
+
+        remove_sent(app, &batch)?;
+        log::debug!("Sent {} analytics events", batch.len());
+    }
+}
+
+/// Removes sent events from the queue. It may have changed while they were
+/// being sent, so they're matched by id.
+fn remove_sent(app: &AppHandle, sent: &[AnalyticsEvent]) -> Result<(), AppError> {
+    let mut queued = lock_queue()?;
+    let mut queue = load_queue(app)?;
+    queue.retain(|event| !sent.iter().any(|sent| sent.id == event.id));
+    if queue.is_empty() {
+        delete_queue(app)?;
+    } else {
+        save_queue(app, &queue)?;
+    }
+    *queued = Some(queue.len());
+    Ok(())
+}
+
+/// Reads the opt-in, then sends queued events every `FLUSH_INTERVAL`, and
+/// drops them if analytics have been turned off.
+pub fn start_analytics_flusher(app: &AppHandle) {
+    set_enabled(load_analytics_enabled(app));
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(FLUSH_INTERVAL);
+        tauri::async_runtime::block_on(flush(&app));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn props(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_validate_event_accepts_flat_props() {
+        let props = props(json!({
+            "source": "menu",
+            "count": 3,
+            "enabled": true,
+            "theme": null,
+        }));
+        assert!(validate_event("editor.document_opened", &props).is_ok());
+        assert!(validate_event("app_launched", &HashMap::new()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_event_rejects_bad_names() {
+        let empty = HashMap::new();
+        assert!(validate_event("", &empty).is_err());
+        assert!(validate_event("Document Opened", &empty).is_err());
+        assert!(validate_event("1st_launch", &empty).is_err());
+        assert!(validate_event(&"a".repeat(65), &empty).is_err());
+        assert!(validate_event("ok", &props(json!({ "Bad Key": 1 }))).is_err());
+    }
+
+    #[test]
+    fn test_validate_event_rejects_nested_and_large_props() {
+        assert!(validate_event("ok", &props(json!({ "list": [1, 2] }))).is_err());
+        assert!(validate_event("ok", &props(json!({ "user": { "id": 1 } }))).is_err());
+        assert!(validate_event("ok", &props(json!({ "text": "x".repeat(201) }))).is_err());
+
+        let too_many: HashMap<String, Value> =
+            (0..21).map(|i| (format!("key_{i}"), json!(i))).collect();
+        assert!(validate_event("ok", &too_many).is_err());
+
+        let too_large: HashMap<String, Value> = (0..20)
+            .map(|i| (format!("key_{i}"), json!("x".repeat(200))))
+            .collect();
+        assert!(validate_event("ok", &too_large).is_err());
+    }
+
+    #[test]
+    fn test_queue_lines() {
+        let event = |id: &str| AnalyticsEvent {
+            id: id.to_string(),
+            name: "app_launched".to_string(),
+            props: HashMap::new(),
+            timestamp: 1,
+        };
+        let first = queue_line(&event("1")).unwrap();
+        let second = queue_line(&event("2")).unwrap();
+        assert!(first.ends_with('\n') && !first.trim_end().contains('\n'));
+
+        // A line cut short by a crash is skipped, and the rest still parse
+        let contents = format!("{first}{}\n{second}", &second[..10]);
+        let ids: Vec<String> = parse_queue(&contents).into_iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec!["1", "2"]);
+        assert!(parse_queue("").is_empty());
+    }
+}
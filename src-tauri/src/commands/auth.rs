@@ -0,0 +1,540 @@
+//! OAuth sign-in for desktop apps.
+//!
+//! `start_auth` runs the authorization code flow with PKCE (RFC 8252): it
+//! listens on a loopback port, opens the provider's sign-in page in the
+//! browser, waits for the redirect back with a code, and exchanges the code
+//! for tokens in Rust. Tokens are stored in the keychain (service `auth`, key
+//! the provider name) and never reach the webview; the frontend gets an
+//! `AuthSession` and an `auth-completed` event. Rust commands calling the
+//! provider's API use `access_token`, which refreshes expired tokens.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::net::Ipv4Addr;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+use tauri_specta::Event;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::commands::{http, secrets};
+use crate::db::now_millis;
+use crate::types::{
+    validate_string_input, AppError, AuthCompleted, AuthError, AuthRequest, AuthSession,
+    SecretError,
+};
+
+/// Keychain service for tokens, under the app's namespace. Reserved, so the
+/// `secrets` commands can't read it.
+pub(crate) const AUTH_SERVICE: &str = "auth";
+
+const CALLBACK_PATH: &str = "/callback";
+
+/// How long to wait for the browser to come back.
+const AUTH_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// How long a connection to the listener gets to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// Refresh access tokens this close to expiring, so they don't expire in
+/// flight.
+const EXPIRY_MARGIN_MS: i64 = 60_000;
+
+const MAX_PROVIDER_LENGTH: usize = 100;
+
+type AbortFn = Box<dyn Fn() + Send>;
+
+/// The sign-in in progress, if any: a unique id, so a finished sign-in
+/// doesn't clear a newer one, and how to abort it.
+static PENDING: Mutex<Option<(u32, AbortFn)>> = Mutex::new(None);
+
+static NEXT_AUTH_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Tokens as stored in the keychain, with what's needed to refresh them.
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredTokens {
+    access_token: String,
+    refresh_token: Option<String>,
+    /// Milliseconds since the Unix epoch
+    expires_at: Option<i64>,
+    scope: Option<String>,
+    token_url: String,
+    client_id: String,
+}
+
+impl StoredTokens {
+    fn session(&self, provider: &str) -> AuthSession {
+        AuthSession {
+            provider: provider.to_string(),
+            scope: self.scope.clone(),
+            expires_at: self.expires_at.map(|expires_at| expires_at as f64),
+            refreshable: self.refresh_token.is_some(),
+        }
+    }
+
+    fn expires_soon(&self, now: i64) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at - EXPIRY_MARGIN_MS <= now)
+    }
+}
+
+/// A successful response from the token endpoint.
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    /// Seconds
+    expires_in: Option<u64>,
+    scope: Option<String>,
+}
+
+/// An error from the token endpoint. Some providers (GitHub) send these with
+/// a 200 status.
+#[derive(Debug, Deserialize)]
+struct TokenErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+fn invalid_request(message: impl Into<String>) -> AuthError {
+    AuthError::InvalidRequest {
+        message: message.into(),
+    }
+}
+
+fn exchange_failed(message: impl Into<String>) -> AuthError {
+    AuthError::ExchangeFailed {
+        message: message.into(),
+    }
+}
+
+fn storage_error(e: SecretError) -> AuthError {
+    AuthError::StorageError {
+        message: e.to_string(),
+    }
+}
+
+fn validate_provider(provider: &str) -> Result<(), AuthError> {
+    if provider.is_empty() {
+        return Err(invalid_request("Provider cannot be empty"));
+    }
+    validate_string_input(provider, MAX_PROVIDER_LENGTH, "Provider").map_err(invalid_request)
+}
+
+/// A random URL-safe string, for the PKCE verifier and `state`.
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    BASE64_URL.encode(bytes)
+}
+
+/// The S256 PKCE challenge for `verifier`.
+fn code_challenge(verifier: &str) -> String {
+    BASE64_URL.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// Reads the stored tokens for `provider`, if signed in.
+fn load_tokens(app: &AppHandle, provider: &str) -> Result<Option<StoredTokens>, AuthError> {
+    let json = match secrets::entry(app, AUTH_SERVICE, provider)
+        .and_then(|entry| entry.get_password().map_err(secrets::secret_error))
+    {
+        Ok(json) => json,
+        Err(SecretError::NotFound) => return Ok(None),
+        Err(e) => return Err(storage_error(e)),
+    };
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| AuthError::StorageError {
+            message: format!("Failed to parse stored tokens: {e}"),
+        })
+}
+
+fn save_tokens(app: &AppHandle, provider: &str, tokens: &StoredTokens) -> Result<(), AuthError> {
+    let json = serde_json::to_string(tokens).map_err(|e| AuthError::StorageError {
+        message: format!("Failed to serialize tokens: {e}"),
+    })?;
+    secrets::entry(app, AUTH_SERVICE, provider)
+        .and_then(|entry| entry.set_password(&json).map_err(secrets::secret_error))
+        .map_err(storage_error)
+}
+
+/// Posts a form to the token endpoint and stores the result alongside what's
+/// needed to refresh it. Keeps `previous_refresh_token` if the provider
+/// doesn't send a new one.
+async fn request_tokens(
+    app: &AppHandle,
+    token_url: &str,
+    client_id: &str,
+    params: &[(&str, &str)],
+    previous_refresh_token: Option<String>,
+) -> Result<StoredTokens, AuthError> {
+    let url = http::parse_url(token_url).map_err(|e| invalid_request(e.to_string()))?;
+    let body = url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(params)
+        .append_pair("client_id", client_id)
+        .finish();
+
+    let response = http::client(app)
+        .map_err(|e| exchange_failed(e.to_string()))?
+        .post(url)
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .header("Accept", "application/json")
+        .timeout(Duration::from_secs(30))
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| exchange_failed(e.to_string()))?;
+    let status = response.status();
+    let text = response
+        .text()
+        .await
+        .map_err(|e| exchange_failed(e.to_string()))?;
+
+    if let Ok(error) = serde_json::from_str::<TokenErrorResponse>(&text) {
+        return Err(exchange_failed(match error.error_description {
+            Some(description) => format!("{}: {description}", error.error),
+            None => error.error,
+        }));
+    }
+    if !status.is_success() {
+        return Err(exchange_failed(format!("Token endpoint returned {status}")));
+    }
+    let tokens: TokenResponse = serde_json::from_str(&text)
+        .map_err(|e| exchange_failed(format!("Unexpected token response: {e}")))?;
+
+    Ok(StoredTokens {
+        access_token: tokens.access_token,
+        refresh_token: tokens.refresh_token.or(previous_refresh_token),
+        expires_at: tokens
+            .expires_in
+            .map(|seconds| now_millis() + seconds as i64 * 1000),
+        scope: tokens.scope,
+        token_url: token_url.to_string(),
+        client_id: client_id.to_string(),
+    })
+}
+
+/// Checks a request to the loopback listener. Returns None if it isn't the
+/// redirect for this sign-in (a favicon request, or a stale or forged
+/// `state`), otherwise the code or the provider's error.
+fn parse_callback(target: &str, state: &str) -> Option<Result<String, AuthError>> {
+    let url = reqwest::Url::parse(&format!("http://127.0.0.1{target}")).ok()?;
+    if url.path() != CALLBACK_PATH {
+        return None;
+    }
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+    if param("state").as_deref() != Some(state) {
+        return None;
+    }
+    if let Some(error) = param("error") {
+        return Some(Err(AuthError::Denied {
+            error,
+            description: param("error_description"),
+        }));
+    }
+    param("code").map(Ok)
+}
+
+/// Reads the request line from a connection and returns its target, e.g.
+/// `/callback?code=...`.
+async fn read_request_target(stream: &mut TcpStream) -> Option<String> {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut buffer).await.ok()?;
+        if read == 0 || request.len() + read > MAX_REQUEST_BYTES {
+            return None;
+        }
+        request.extend_from_slice(&buffer[..read]);
+    }
+    let request = String::from_utf8_lossy(&request);
+    let mut parts = request.lines().next()?.split(' ');
+    match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) => Some(target.to_string()),
+        _ => None,
+    }
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, title: &str, message: &str) {
+    let body = format!(
+        "<!doctype html><meta charset=\"utf-8\"><title>{title}</title>\
+         <body style=\"font-family: system-ui; text-align: center; margin-top: 20vh\">\
+         <h1>{title}</h1><p>{message}</p></body>"
+    );
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// Accepts connections until the provider redirects back with a code.
+async fn wait_for_code(
+    app: &AppHandle,
+    listener: &TcpListener,
+    state: &str,
+) -> Result<String, AuthError> {
+    let app_name = app.package_info().name.clone();
+    loop {
+        let (mut stream, _) = listener
+            .accept()
+            .await
+            .map_err(|e| invalid_request(format!("Failed to accept connection: {e}")))?;
+        let target = tokio::time::timeout(REQUEST_TIMEOUT, read_request_target(&mut stream))
+            .await
+            .ok()
+            .flatten();
+        match target.and_then(|target| parse_callback(&target, state)) {
+            Some(Ok(code)) => {
+                let message = format!("You can close this tab and return to {app_name}.");
+                respond(&mut stream, "200 OK", "Signed in", &message).await;
+                return Ok(code);
+            }
+            Some(Err(e)) => {
+                let message = format!("Return to {app_name} to try again.");
+                respond(&mut stream, "200 OK", "Sign-in failed", &message).await;
+                return Err(e);
+            }
+            None => respond(&mut stream, "404 Not Found", "Not found", "").await,
+        }
+    }
+}
+
+async fn sign_in(app: AppHandle, request: AuthRequest) -> Result<AuthSession, AuthError> {
+    validate_provider(&request.provider)?;
+    let mut authorization_url =
+        http::parse_url(&request.authorization_url).map_err(|e| invalid_request(e.to_string()))?;
+    http::parse_url(&request.token_url).map_err(|e| invalid_request(e.to_string()))?;
+
+    // 127.0.0.1 rather than localhost, which may resolve to IPv6 (RFC 8252)
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, request.port.unwrap_or(0)))
+        .await
+        .map_err(|e| invalid_request(format!("Failed to listen for the redirect: {e}")))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| invalid_request(format!("Failed to listen for the redirect: {e}")))?
+        .port();
+    let redirect_uri = format!("http://127.0.0.1:{port}{CALLBACK_PATH}");
+
+    let verifier = random_token();
+    let state = random_token();
+    {
+        let mut query = authorization_url.query_pairs_mut();
+        query
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &request.client_id)
+            .append_pair("redirect_uri", &redirect_uri)
+            .append_pair("code_challenge", &code_challenge(&verifier))
+            .append_pair("code_challenge_method", "S256")
+            .append_pair("state", &state);
+        if !request.scopes.is_empty() {
+            query.append_pair("scope", &request.scopes.join(" "));
+        }
+    }
+
+    log::info!("Signing in to {}", request.provider);
+    app.opener()
+        .open_url(authorization_url.as_str(), None::<&str>)
+        .map_err(|e| invalid_request(format!("Failed to open the browser: {e}")))?;
+
+    let code = tokio::time::timeout(AUTH_TIMEOUT, wait_for_code(&app, &listener, &state))
+        .await
+        .map_err(|_| AuthError::Timeout)??;
+    drop(listener);
+
+    let tokens = request_tokens(
+        &app,
+        &request.token_url,
+        &request.client_id,
+        &[
+            ("grant_type", "authorization_code"),
+            ("code", &code),
+            ("redirect_uri", &redirect_uri),
+            ("code_verifier", &verifier),
+        ],
+        None,
+    )
+    .await?;
+    save_tokens(&app, &request.provider, &tokens)?;
+    log::info!("Signed in to {}", request.provider);
+
+    let session = tokens.session(&request.provider);
+    if let Err(e) = (AuthCompleted {
+        session: session.clone(),
+    })
+    .emit(&app)
+    {
+        log::warn!("Failed to emit auth-completed: {e}");
+    }
+    Ok(session)
+}
+
+/// Returns a valid access token for `provider`, refreshing it first if it has
+/// expired. For Rust commands that call the provider's API, so tokens never
+/// pass through the webview.
+pub async fn access_token(app: &AppHandle, provider: &str) -> Result<String, AuthError> {
+    validate_provider(provider)?;
+    let tokens = load_tokens(app, provider)?.ok_or(AuthError::NotSignedIn)?;
+    if !tokens.expires_soon(now_millis()) {
+        return Ok(tokens.access_token);
+    }
+    let Some(refresh_token) = tokens.refresh_token else {
+        return Err(AuthError::NotSignedIn);
+    };
+
+    log::debug!("Refreshing access token for {provider}");
+    let refreshed = request_tokens(
+        app,
+        &tokens.token_url,
+        &tokens.client_id,
+        &[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", &refresh_token),
+        ],
+        Some(refresh_token.clone()),
+    )
+    .await?;
+    save_tokens(app, provider, &refreshed)?;
+    Ok(refreshed.access_token)
+}
+
+/// Signs in with an OAuth provider in the browser and stores the tokens in
+/// the keychain. Resolves once signed in (also sent as `auth-completed`), or
+/// fails after five minutes. Starting another sign-in cancels this one.
+#[tauri::command]
+#[specta::specta]
+pub async fn start_auth(app: AppHandle, request: AuthRequest) -> Result<AuthSession, AuthError> {
+    // Run as a task so `cancel_auth` can abort it, closing the listener
+    let id = NEXT_AUTH_ID.fetch_add(1, Ordering::Relaxed);
+    let task = {
+        let mut pending = PENDING
+            .lock()
+            .map_err(|e| invalid_request(format!("Failed to lock sign-in: {e}")))?;
+        if let Some((_, abort)) = pending.take() {
+            log::debug!("Cancelling previous sign-in");
+            abort();
+        }
+        let task = tauri::async_runtime::spawn(sign_in(app, request));
+        let abort = task.inner().abort_handle();
+        *pending = Some((id, Box::new(move || abort.abort())));
+        task
+    };
+
+    let result = task.await;
+    if let Ok(mut pending) = PENDING.lock() {
+        if pending
+            .as_ref()
+            .is_some_and(|(pending_id, _)| *pending_id == id)
+        {
+            *pending = None;
+        }
+    }
+    // The task only fails to complete if it was aborted
+    result.unwrap_or(Err(AuthError::Cancelled))
+}
+
+/// Cancels the sign-in in progress, which then fails with `Cancelled`.
+/// Returns false if there wasn't one.
+#[tauri::command]
+#[specta::specta]
+pub fn cancel_auth() -> Result<bool, AppError> {
+    let pending = PENDING
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock sign-in: {e}")))?
+        .take();
+    if let Some((_, abort)) = &pending {
+        log::debug!("Cancelling sign-in");
+        abort();
+    }
+    Ok(pending.is_some())
+}
+
+/// Returns the session for `provider`, or None if not signed in.
+#[tauri::command]
+#[specta::specta]
+pub fn get_auth_session(
+    app: AppHandle,
+    provider: String,
+) -> Result<Option<AuthSession>, AuthError> {
+    validate_provider(&provider)?;
+    Ok(load_tokens(&app, &provider)?.map(|tokens| tokens.session(&provider)))
+}
+
+/// Deletes the stored tokens for `provider`. Returns whether it was signed in.
+/// Doesn't revoke the tokens with the provider.
+#[tauri::command]
+#[specta::specta]
+pub fn sign_out(app: AppHandle, provider: String) -> Result<bool, AuthError> {
+    validate_provider(&provider)?;
+    let entry = secrets::entry(&app, AUTH_SERVICE, &provider).map_err(storage_error)?;
+    match entry.delete_credential() {
+        Ok(()) => {
+            log::info!("Signed out of {provider}");
+            Ok(true)
+        }
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(storage_error(secrets::secret_error(e))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_challenge() {
+        let verifier = "dBjftJeZ4CVP-mJ92K9qNrHoeLdSPqHBbNqX9NGj5Z0";
+        assert_eq!(
+            code_challenge(verifier),
+            "kDl1lRpKPRT-07BxtoLOB3ePC8GF24JcMq7M83w7Sjs"
+        );
+        // 32 bytes is 43 characters, the shortest verifier PKCE allows
+        assert_eq!(random_token().len(), 43);
+        assert_ne!(random_token(), random_token());
+    }
+
+    #[test]
+    fn test_parse_callback() {
+        assert!(matches!(
+            parse_callback("/callback?code=abc&state=xyz", "xyz"),
+            Some(Ok(code)) if code == "abc"
+        ));
+        assert!(matches!(
+            parse_callback("/callback?error=access_denied&state=xyz", "xyz"),
+            Some(Err(AuthError::Denied { error, description: None })) if error == "access_denied"
+        ));
+        assert!(parse_callback("/callback?code=abc&state=other", "xyz").is_none());
+        assert!(parse_callback("/callback?code=abc", "xyz").is_none());
+        assert!(parse_callback("/favicon.ico", "xyz").is_none());
+    }
+
+    #[test]
+    fn test_expires_soon() {
+        let tokens = |expires_at| StoredTokens {
+            access_token: String::new(),
+            refresh_token: None,
+            expires_at,
+            scope: None,
+            token_url: String::new(),
+            client_id: String::new(),
+        };
+        assert!(!tokens(None).expires_soon(1_000_000));
+        assert!(!tokens(Some(1_000_000)).expires_soon(900_000));
+        assert!(tokens(Some(1_000_000)).expires_soon(950_000));
+    }
+}
@@ -0,0 +1,414 @@
+//! Backup and restore of all app data.
+//!
+//! `create_backup` writes preferences, a snapshot of the database (documents,
+//! notes, the key-value store and the search index) and the recovery files
+//! into a zip with a `manifest.json`. `restore_backup` checks the manifest is
+//! compatible, stages the files in `restore-pending/` and restarts the app.
+//! `apply_pending_restore` then moves them into place during the next setup,
+//! before anything has them open.
+//!
+//! Only JSON recovery files are included, so recovery data kept by the SQLite
+//! recovery backend isn't backed up.
+
+use semver::Version;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, State};
+use zip::ZipArchive;
+
+use super::tasks::{self, CancellationToken};
+use crate::db::{migrations, Database};
+use crate::recovery_store::get_recovery_dir;
+use crate::types::{validate_filename, AppError, BackupManifest};
+
+/// Bump when the archive layout changes in a way older versions can't read.
+const BACKUP_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_NAME: &str = "manifest.json";
+const PREFERENCES_NAME: &str = "preferences.json";
+const DATABASE_NAME: &str = "app.db";
+const RECOVERY_PREFIX: &str = "recovery/";
+
+/// Directory in the app data dir holding a restore to apply at startup.
+const STAGING_DIR: &str = "restore-pending";
+
+fn unix_millis_now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as f64)
+        .unwrap_or(0.0)
+}
+
+fn validate_archive_path(path: &str) -> Result<PathBuf, AppError> {
+    let path = PathBuf::from(path);
+    if !path.is_absolute() {
+        return Err(AppError::validation("Backup path must be absolute"));
+    }
+    if path.extension().is_none_or(|ext| ext != "zip") {
+        return Err(AppError::validation(
+            "Backup path must have a .zip extension",
+        ));
+    }
+    Ok(path)
+}
+
+/// JSON files directly in the recovery directory, sorted.
+fn collect_recovery_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "json") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Whether an archive entry is one `restore_backup` should extract. Names are
+/// checked exactly, so entries can't escape the staging directory.
+fn is_restorable(name: &str) -> bool {
+    if name == PREFERENCES_NAME || name == DATABASE_NAME {
+        return true;
+    }
+    name.strip_prefix(RECOVERY_PREFIX)
+        .and_then(|file| file.strip_suffix(".json"))
+        .is_some_and(|key| validate_filename(key).is_ok())
+}
+
+/// Checks that this version of the app can restore a backup.
+fn check_compatibility(manifest: &BackupManifest, app_version: &str) -> Result<(), AppError> {
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        return Err(AppError::internal(
+            "This backup was made by a newer version of the app",
+        ));
+    }
+    if manifest.schema_version > migrations::latest_version() {
+        return Err(AppError::internal(format!(
+            "This backup's database (version {}) is newer than this app supports",
+            manifest.schema_version
+        )));
+    }
+
+    let backup_version = Version::parse(&manifest.app_version)
+        .map_err(|e| AppError::validation(format!("Invalid app version in backup: {e}")))?;
+    let current_version = Version::parse(app_version)
+        .map_err(|e| AppError::internal(format!("Invalid app version: {e}")))?;
+    if backup_version > current_version {
+        return Err(AppError::validation(format!(
+            "This backup is from version {backup_version}; update the app to restore it"
+        )));
+    }
+    Ok(())
+}
+
+fn write_backup(
+    archive_path: &Path,
+    files: &[(String, PathBuf)],
+    app_version: String,
+    token: &CancellationToken,
+) -> Result<BackupManifest, AppError> {
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    let archive_file = File::create(archive_path)
+        .map_err(|e| AppError::io(format!("Failed to create backup: {e}")))?;
+    let mut zip = zip::ZipWriter::new(archive_file);
+    let options = SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true);
+
+    for (name, path) in files {
+        let mut source = token.reader(
+            File::open(path)
+                .map_err(|e| AppError::io(format!("Failed to read {name} for backup: {e}")))?,
+        );
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| AppError::io(format!("Failed to write backup: {e}")))?;
+        std::io::copy(&mut source, &mut zip)
+            .map_err(|e| AppError::io(format!("Failed to write {name} to backup: {e}")))?;
+    }
+
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        created_at: unix_millis_now(),
+        app_version,
+        schema_version: migrations::latest_version(),
+        files: files.iter().map(|(name, _)| name.clone()).collect(),
+    };
+    let manifest_json =
+        serde_json::to_string_pretty(&manifest).map_err(|e| AppError::Serialization {
+            message: format!("Failed to serialize backup manifest: {e}"),
+        })?;
+
+    zip.start_file(MANIFEST_NAME, options)
+        .map_err(|e| AppError::io(format!("Failed to write backup: {e}")))?;
+    zip.write_all(manifest_json.as_bytes())
+        .map_err(|e| AppError::io(format!("Failed to write backup: {e}")))?;
+    zip.finish()
+        .map_err(|e| AppError::io(format!("Failed to write backup: {e}")))?;
+
+    Ok(manifest)
+}
+
+/// Backs up preferences, the database and recovery files into a zip at
+/// `dest_path` (absolute, ending in `.zip`). Pass a `task_id` to be able to
+/// stop it with `cancel_task`.
+#[tauri::command]
+#[specta::specta]
+pub async fn create_backup(
+    app: AppHandle,
+    database: State<'_, Database>,
+    dest_path: String,
+    task_id: Option<String>,
+) -> Result<BackupManifest, AppError> {
+    log::info!("Creating backup at: {dest_path}");
+    let dest_path = validate_archive_path(&dest_path)?;
+
+    // Commit pending document saves so the backup includes them
+    super::documents::flush_pending_documents(&app);
+
+    let snapshot_path = crate::data_dir::app_data_dir(&app)?.join("backup-snapshot.db");
+    let _ = std::fs::remove_file(&snapshot_path);
+    database.snapshot(&snapshot_path)?;
+
+    let mut files = vec![(DATABASE_NAME.to_string(), snapshot_path.clone())];
+    let preferences_path = super::preferences::get_preferences_path(&app)?;
+    if preferences_path.exists() {
+        files.push((PREFERENCES_NAME.to_string(), preferences_path));
+    }
+    let recovery_dir = get_recovery_dir(&app)?;
+    let recovery_files = collect_recovery_files(&recovery_dir)
+        .map_err(|e| AppError::io(format!("Failed to read recovery directory: {e}")))?;
+    for path in recovery_files {
+        if let Some(name) = path.file_name() {
+            let name = format!("{RECOVERY_PREFIX}{}", name.to_string_lossy());
+            files.push((name, path));
+        }
+    }
+
+    // Write to a temporary file first, then rename (atomic operation)
+    let temp_path = dest_path.with_extension("zip.tmp");
+    let app_version = app.package_info().version.to_string();
+    let task = tasks::register(task_id);
+    let result = write_backup(&temp_path, &files, app_version, task.token());
+
+    if let Err(e) = std::fs::remove_file(&snapshot_path) {
+        log::warn!("Failed to remove database snapshot: {e}");
+    }
+    let manifest = match result {
+        Ok(manifest) => manifest,
+        Err(_) if task.token().is_cancelled() => {
+            log::info!("Backup cancelled");
+            let _ = std::fs::remove_file(&temp_path);
+            return Err(AppError::internal("Backup cancelled"));
+        }
+        Err(e) => {
+            log::error!("{e}");
+            let _ = std::fs::remove_file(&temp_path);
+            return Err(e);
+        }
+    };
+
+    if let Err(rename_err) = std::fs::rename(&temp_path, &dest_path) {
+        log::error!("Failed to finalize backup: {rename_err}");
+        if let Err(remove_err) = std::fs::remove_file(&temp_path) {
+            log::warn!("Failed to remove temp file after rename failure: {remove_err}");
+        }
+        return Err(AppError::io(format!("Failed to save backup: {rename_err}")));
+    }
+
+    log::info!("Backed up {} files to {dest_path:?}", manifest.files.len());
+    Ok(manifest)
+}
+
+fn read_manifest(archive: &mut ZipArchive<File>) -> Result<BackupManifest, AppError> {
+    let file = archive
+        .by_name(MANIFEST_NAME)
+        .map_err(|_| AppError::validation("Not a backup: manifest.json is missing"))?;
+    serde_json::from_reader(file)
+        .map_err(|e| AppError::validation(format!("Invalid backup manifest: {e}")))
+}
+
+/// Extracts the restorable entries into `staging` and checks the database
+/// opens. Opening it also applies any migrations newer than the backup.
+fn stage_backup(archive: &mut ZipArchive<File>, staging: &Path) -> Result<(), AppError> {
+    std::fs::create_dir_all(staging.join(RECOVERY_PREFIX))
+        .map_err(|e| AppError::io(format!("Failed to create restore directory: {e}")))?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| AppError::io(format!("Failed to read backup: {e}")))?;
+        let name = entry.name().to_string();
+        if name == MANIFEST_NAME {
+            continue;
+        }
+        if !is_restorable(&name) {
+            log::warn!("Skipping unexpected file in backup: {name}");
+            continue;
+        }
+
+        let mut target = File::create(staging.join(&name))
+            .map_err(|e| AppError::io(format!("Failed to extract {name}: {e}")))?;
+        std::io::copy(&mut entry, &mut target)
+            .map_err(|e| AppError::io(format!("Failed to extract {name}: {e}")))?;
+    }
+
+    let database_path = staging.join(DATABASE_NAME);
+    if !database_path.exists() {
+        return Err(AppError::validation("Backup doesn't contain a database"));
+    }
+    Database::open(&database_path).map(drop)
+}
+
+/// Restores a backup made by `create_backup`, then restarts the app to load
+/// it. Fails without changing anything if the backup is from a newer version
+/// of the app. Unsaved document changes are discarded.
+#[tauri::command]
+#[specta::specta]
+pub async fn restore_backup(app: AppHandle, src_path: String) -> Result<BackupManifest, AppError> {
+    log::info!("Restoring backup from: {src_path}");
+    let src_path = validate_archive_path(&src_path)?;
+
+    let file =
+        File::open(&src_path).map_err(|e| AppError::io(format!("Failed to open backup: {e}")))?;
+    let mut archive =
+        ZipArchive::new(file).map_err(|e| AppError::io(format!("Not a valid backup: {e}")))?;
+    let manifest = read_manifest(&mut archive)?;
+    check_compatibility(&manifest, &app.package_info().version.to_string())?;
+
+    let app_data_dir = crate::data_dir::app_data_dir(&app)?;
+    let temp_staging = app_data_dir.join(format!("{STAGING_DIR}.tmp"));
+    let staging = app_data_dir.join(STAGING_DIR);
+    let _ = std::fs::remove_dir_all(&temp_staging);
+
+    if let Err(e) = stage_backup(&mut archive, &temp_staging) {
+        log::error!("Failed to stage backup: {e}");
+        let _ = std::fs::remove_dir_all(&temp_staging);
+        return Err(e);
+    }
+
+    // Renaming marks the restore as complete and ready to apply at startup
+    let _ = std::fs::remove_dir_all(&staging);
+    std::fs::rename(&temp_staging, &staging).map_err(|e| {
+        let _ = std::fs::remove_dir_all(&temp_staging);
+        AppError::Io {
+            message: format!("Failed to stage backup: {e}"),
+        }
+    })?;
+
+    // Pending saves would otherwise be written over the restored data on exit
+    super::documents::discard_pending_documents();
+
+    log::info!(
+        "Backup from app {} staged; restarting to apply it",
+        manifest.app_version
+    );
+    app.request_restart();
+    Ok(manifest)
+}
+
+/// Moves a staged restore into place. `staging` holds everything the backup
+/// contained; data it doesn't include (e.g. preferences that were never
+/// saved) is removed so the app matches the backup.
+fn apply_staged_files(
+    staging: &Path,
+    app_data_dir: &Path,
+    recovery_dir: &Path,
+) -> std::io::Result<()> {
+    let staged_preferences = staging.join(PREFERENCES_NAME);
+    let preferences_path = app_data_dir.join(PREFERENCES_NAME);
+    if staged_preferences.exists() {
+        std::fs::rename(&staged_preferences, &preferences_path)?;
+    } else if preferences_path.exists() {
+        std::fs::remove_file(&preferences_path)?;
+    }
+
+    // The old write-ahead log belongs to the old database
+    let database_path = app_data_dir.join(DATABASE_NAME);
+    for suffix in ["-wal", "-shm"] {
+        let _ = std::fs::remove_file(app_data_dir.join(format!("{DATABASE_NAME}{suffix}")));
+    }
+    std::fs::rename(staging.join(DATABASE_NAME), &database_path)?;
+
+    for path in collect_recovery_files(recovery_dir)? {
+        std::fs::remove_file(path)?;
+    }
+    for path in collect_recovery_files(&staging.join(RECOVERY_PREFIX))? {
+        if let Some(name) = path.file_name() {
+            std::fs::rename(&path, recovery_dir.join(name))?;
+        }
+    }
+    Ok(())
+}
+
+/// Applies a backup staged by `restore_backup`, if there is one. Must run in
+/// setup before the database and recovery store are opened.
+pub fn apply_pending_restore(app: &AppHandle) {
+    let (app_data_dir, recovery_dir) =
+        match (crate::data_dir::app_data_dir(app), get_recovery_dir(app)) {
+            (Ok(app_data_dir), Ok(recovery_dir)) => (app_data_dir, recovery_dir),
+            (Err(e), _) | (_, Err(e)) => {
+                log::error!("Failed to check for a pending restore: {e}");
+                return;
+            }
+        };
+
+    let staging = app_data_dir.join(STAGING_DIR);
+    if !staging.exists() {
+        return;
+    }
+
+    match apply_staged_files(&staging, &app_data_dir, &recovery_dir) {
+        Ok(()) => log::info!("Restored backup"),
+        Err(e) => log::error!("Failed to apply restored backup: {e}"),
+    }
+    if let Err(e) = std::fs::remove_dir_all(&staging) {
+        log::warn!("Failed to remove restore directory: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(app_version: &str, schema_version: u32) -> BackupManifest {
+        BackupManifest {
+            format_version: BACKUP_FORMAT_VERSION,
+            created_at: 0.0,
+            app_version: app_version.to_string(),
+            schema_version,
+            files: vec![],
+        }
+    }
+
+    #[test]
+    fn test_is_restorable() {
+        assert!(is_restorable("app.db"));
+        assert!(is_restorable("preferences.json"));
+        assert!(is_restorable("recovery/document-1.json"));
+
+        assert!(!is_restorable("manifest.json"));
+        assert!(!is_restorable("recovery/../app.db"));
+        assert!(!is_restorable("recovery/nested/file.json"));
+        assert!(!is_restorable("recovery/recovery.db"));
+        assert!(!is_restorable("/etc/passwd"));
+    }
+
+    #[test]
+    fn test_check_compatibility() {
+        let schema = migrations::latest_version();
+        assert!(check_compatibility(&manifest("1.0.0", schema), "1.0.0").is_ok());
+        assert!(check_compatibility(&manifest("0.9.0", 1), "1.0.0").is_ok());
+
+        assert!(check_compatibility(&manifest("1.1.0", schema), "1.0.0").is_err());
+        assert!(check_compatibility(&manifest("1.0.0", schema + 1), "1.0.0").is_err());
+        assert!(check_compatibility(&manifest("not a version", schema), "1.0.0").is_err());
+
+        let mut newer_format = manifest("1.0.0", schema);
+        newer_format.format_version = BACKUP_FORMAT_VERSION + 1;
+        assert!(check_compatibility(&newer_format, "1.0.0").is_err());
+    }
+}
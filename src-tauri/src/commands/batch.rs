@@ -0,0 +1,122 @@
+//! Running several commands in one IPC round trip.
+//!
+//! At startup the frontend loads preferences, recovery files, recent
+//! documents and the like at once. `batch` takes a list of
+//! [`BatchRequest`]s and returns a [`BatchResponse`] for each, in the same
+//! order, so that's one round trip instead of one per command:
+//!
+//! ```typescript
+//! const result = await commands.batch(
+//!   [{ command: 'LoadPreferences' }, { command: 'GetRecentDocuments' }],
+//!   true
+//! )
+//! ```
+//!
+//! Only the commands in `BatchRequest` can be batched. They only read, so
+//! they're safe to run in parallel. A failed request becomes an `Error`
+//! response and doesn't affect the others.
+//!
+//! Batched commands run directly, not through the invoke handler, so command
+//! middleware (logging, rate limits, guards) sees only the `batch` call.
+//! Don't add commands that a `Guard` or `RateLimit` protects.
+
+use tauri::{AppHandle, Manager};
+
+use super::{kv, launch_at_login, locale, power, preferences, recent_documents, recovery};
+use crate::types::{AppError, BatchRequest, BatchResponse};
+
+/// Requests accepted in one batch.
+const MAX_BATCH_SIZE: usize = 50;
+
+/// Runs one request, turning a failure into an `Error` response.
+async fn run(app: AppHandle, request: BatchRequest) -> BatchResponse {
+    let response = match request {
+        BatchRequest::LoadPreferences => preferences::load_preferences(app)
+            .await
+            .map(|data| BatchResponse::LoadPreferences { data }),
+        BatchRequest::KvGet { key } => {
+            kv::kv_get(app.state(), key).map(|data| BatchResponse::KvGet { data })
+        }
+        BatchRequest::ListRecoveryFiles => recovery::list_recovery_files(&app)
+            .map(|data| BatchResponse::ListRecoveryFiles { data }),
+        BatchRequest::GetRecentDocuments => recent_documents::get_recent_documents(app)
+            .map(|data| BatchResponse::GetRecentDocuments { data }),
+        BatchRequest::GetLaunchAtLogin => launch_at_login::get_launch_at_login(app)
+            .map(|data| BatchResponse::GetLaunchAtLogin { data }),
+        BatchRequest::GetLocaleInfo => locale::get_locale_info()
+            .await
+            .map(|data| BatchResponse::GetLocaleInfo { data }),
+        BatchRequest::GetPowerState => Ok(BatchResponse::GetPowerState {
+            data: power::get_power_state(),
+        }),
+    };
+    response.unwrap_or_else(|error| BatchResponse::Error { error })
+}
+
+/// Runs `requests` and returns their responses in the same order. With
+/// `parallel`, requests run concurrently; otherwise one after another.
+#[tauri::command]
+#[specta::specta]
+pub async fn batch(
+    app: AppHandle,
+    requests: Vec<BatchRequest>,
+    parallel: bool,
+) -> Result<Vec<BatchResponse>, AppError> {
+    if requests.len() > MAX_BATCH_SIZE {
+        return Err(AppError::Validation {
+            message: format!("A batch can have at most {MAX_BATCH_SIZE} requests"),
+        });
+    }
+    log::debug!("Running batch of {} requests", requests.len());
+
+    let mut responses = Vec::with_capacity(requests.len());
+    if parallel {
+        let tasks: Vec<_> = requests
+            .into_iter()
+            .map(|request| tauri::async_runtime::spawn(run(app.clone(), request)))
+            .collect();
+        for task in tasks {
+            responses.push(task.await.unwrap_or_else(|e| BatchResponse::Error {
+                error: AppError::Internal {
+                    message: format!("Batch request failed: {e}"),
+                },
+            }));
+        }
+    } else {
+        for request in requests {
+            responses.push(run(app.clone(), request).await);
+        }
+    }
+    Ok(responses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_json() {
+        let requests: Vec<BatchRequest> = serde_json::from_str(
+            r#"[{ "command": "LoadPreferences" }, { "command": "KvGet", "key": "draft" }]"#,
+        )
+        .unwrap();
+        assert!(matches!(requests[0], BatchRequest::LoadPreferences));
+        assert!(matches!(&requests[1], BatchRequest::KvGet { key } if key == "draft"));
+
+        // Unknown commands aren't accepted
+        assert!(serde_json::from_str::<BatchRequest>(r#"{ "command": "DeleteNote" }"#).is_err());
+
+        let response = BatchResponse::Error {
+            error: AppError::NotFound {
+                message: "Missing".to_string(),
+            },
+        };
+        assert_eq!(
+            serde_json::to_value(response).unwrap(),
+            serde_json::json!({
+                "type": "Error",
+                "error": { "type": "NotFound", "message": "Missing" }
+            })
+        );
+    }
+}
@@ -0,0 +1,85 @@
+//! Disk cache commands.
+//!
+//! For data that can be fetched again (images, API responses). Entries go
+//! in `cache.db` in the OS cache directory (see `crate::cache`) and are
+//! evicted when they expire or the cache grows past 200MB. Use the key-value
+//! store for anything that can't be recreated.
+
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::cache::DiskCache;
+use crate::types::{validate_string_input, AppError};
+
+/// Long enough for URLs used as keys.
+const MAX_KEY_LENGTH: usize = 2048;
+const MAX_ENTRY_SIZE: usize = 10 * 1024 * 1024;
+/// How often expired and least recently used entries are removed.
+const EVICTION_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+fn validate_key(key: &str) -> Result<(), AppError> {
+    if key.is_empty() {
+        return Err(AppError::validation("Key cannot be empty"));
+    }
+    validate_string_input(key, MAX_KEY_LENGTH, "Key").map_err(AppError::validation)
+}
+
+/// Evicts cache entries at startup and then every `EVICTION_INTERVAL`.
+pub fn start_cache_eviction(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        if let Err(e) = app.state::<DiskCache>().evict() {
+            log::warn!("Failed to evict cache entries: {e}");
+        }
+        std::thread::sleep(EVICTION_INTERVAL);
+    });
+}
+
+/// Stores `bytes` under `key`, replacing any existing entry. With
+/// `ttl_seconds` the entry expires after that long; otherwise it stays
+/// until it's evicted for space. Entries are limited to 10MB.
+#[tauri::command]
+#[specta::specta]
+pub fn cache_put(
+    cache: State<'_, DiskCache>,
+    key: String,
+    bytes: Vec<u8>,
+    ttl_seconds: Option<u32>,
+) -> Result<(), AppError> {
+    validate_key(&key)?;
+    if bytes.len() > MAX_ENTRY_SIZE {
+        return Err(AppError::validation(format!(
+            "Cache entry too large (max {MAX_ENTRY_SIZE} bytes)"
+        )));
+    }
+
+    let ttl = ttl_seconds.map(|seconds| Duration::from_secs(seconds.into()));
+    cache.put(&key, &bytes, ttl)?;
+    log::debug!("Cached '{key}' ({} bytes)", bytes.len());
+    Ok(())
+}
+
+/// Gets the bytes cached under `key`, or null if they're missing or expired.
+#[tauri::command]
+#[specta::specta]
+pub fn cache_get(cache: State<'_, DiskCache>, key: String) -> Result<Option<Vec<u8>>, AppError> {
+    validate_key(&key)?;
+    cache.get(&key)
+}
+
+/// Removes `key` from the cache. Returns whether it was cached.
+#[tauri::command]
+#[specta::specta]
+pub fn cache_delete(cache: State<'_, DiskCache>, key: String) -> Result<bool, AppError> {
+    validate_key(&key)?;
+    cache.remove(&key)
+}
+
+/// Removes every cache entry. Returns how many were removed.
+#[tauri::command]
+#[specta::specta]
+pub fn cache_clear(cache: State<'_, DiskCache>) -> Result<u32, AppError> {
+    let removed = cache.clear()?;
+    log::info!("Cleared {removed} cache entries");
+    Ok(removed)
+}
@@ -0,0 +1,545 @@
+//! Rich clipboard reads and writes.
+//!
+//! The clipboard-manager plugin handles plain text and images one at a time.
+//! `write_clipboard` puts several formats on the clipboard at once (plain
+//! text, HTML, RTF, a PNG image and file paths), so whichever app the user
+//! pastes into can pick the richest one it understands. `read_clipboard`
+//! returns every format that's present.
+//!
+//! Linux clipboards are served by the app itself, and arboard only serves
+//! one format at a time, so there the richest format given is written (HTML
+//! along with its plain text) and RTF isn't supported.
+//!
+//! `paste_into_previous_app` (macOS) dismisses a quick pane, returns focus to
+//! the app the user was in and presses Cmd+V there, to finish quick-capture
+//! flows like a clipboard manager or snippet picker.
+
+use image::{ImageFormat, RgbaImage};
+use std::io::Cursor;
+use tauri::AppHandle;
+
+use super::files::resolve_scoped_path;
+use crate::types::{validate_string_input, AppError, ClipboardData};
+
+/// Largest text, HTML or RTF accepted by `write_clipboard`, in characters.
+const MAX_TEXT_LENGTH: usize = 10 * 1024 * 1024;
+
+/// Largest PNG accepted by `write_clipboard`, in bytes.
+const MAX_PNG_SIZE: usize = 50 * 1024 * 1024;
+
+/// How long the previous app gets to come forward before Cmd+V is sent.
+#[cfg(target_os = "macos")]
+const PASTE_DELAY: std::time::Duration = std::time::Duration::from_millis(150);
+
+/// Checks sizes, and that there's something to write.
+fn validate_clipboard_data(data: &ClipboardData) -> Result<(), AppError> {
+    let has_files = data.files.as_ref().is_some_and(|files| !files.is_empty());
+    if data.text.is_none()
+        && data.html.is_none()
+        && data.rtf.is_none()
+        && data.png.is_none()
+        && !has_files
+    {
+        return Err(AppError::validation("Nothing to write to the clipboard"));
+    }
+
+    for (value, name) in [
+        (&data.text, "Text"),
+        (&data.html, "HTML"),
+        (&data.rtf, "RTF"),
+    ] {
+        if let Some(value) = value {
+            validate_string_input(value, MAX_TEXT_LENGTH, name).map_err(AppError::validation)?;
+        }
+    }
+    if data
+        .png
+        .as_ref()
+        .is_some_and(|png| png.len() > MAX_PNG_SIZE)
+    {
+        return Err(AppError::validation(format!(
+            "Image too large (max {MAX_PNG_SIZE} bytes)"
+        )));
+    }
+    Ok(())
+}
+
+fn decode_png(png: &[u8]) -> Result<RgbaImage, AppError> {
+    image::load_from_memory_with_format(png, ImageFormat::Png)
+        .map(|image| image.into_rgba8())
+        .map_err(|e| AppError::validation(format!("Invalid PNG image: {e}")))
+}
+
+fn encode_png(image: &RgbaImage) -> Result<Vec<u8>, AppError> {
+    let mut bytes = Cursor::new(Vec::new());
+    image
+        .write_to(&mut bytes, ImageFormat::Png)
+        .map_err(|e| AppError::internal(format!("Failed to encode clipboard image: {e}")))?;
+    Ok(bytes.into_inner())
+}
+
+/// Reads an image in any format the plugin understands (e.g. TIFF on macOS,
+/// bitmaps on Windows) and converts it to PNG.
+fn read_image_as_png(app: &AppHandle) -> Option<Vec<u8>> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    let image = app.clipboard().read_image().ok()?;
+    let image = RgbaImage::from_raw(image.width(), image.height(), image.rgba().to_vec())?;
+    encode_png(&image).inspect_err(|e| log::warn!("{e}")).ok()
+}
+
+/// Replaces the clipboard contents with every format given in `data`.
+/// Files must be in the app's file scope.
+#[tauri::command]
+#[specta::specta]
+pub fn write_clipboard(app: AppHandle, data: ClipboardData) -> Result<(), AppError> {
+    validate_clipboard_data(&data)?;
+
+    let image = data.png.as_deref().map(decode_png).transpose()?;
+    let files = data
+        .files
+        .iter()
+        .flatten()
+        .map(|path| {
+            resolve_scoped_path(&app, path)
+                .map(|path| path.to_string_lossy().into_owned())
+                .map_err(AppError::from)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    log::debug!("Writing to the clipboard");
+    platform::write(&data, image.as_ref(), &files)
+}
+
+/// Reads every supported format on the clipboard. Images in other formats
+/// are converted to PNG.
+#[tauri::command]
+#[specta::specta]
+pub fn read_clipboard(app: AppHandle) -> Result<ClipboardData, AppError> {
+    let mut data = platform::read()?;
+    if data.png.is_none() {
+        data.png = read_image_as_png(&app);
+    }
+    Ok(data)
+}
+
+/// Dismisses a quick pane, returns focus to the app that was frontmost
+/// before it was shown and presses Cmd+V there. Write to the clipboard
+/// first. Needs the Accessibility permission, and the pane mustn't be
+/// pinned. macOS only.
+/// Defaults to the main quick pane when no label is given.
+#[tauri::command]
+#[specta::specta]
+pub fn paste_into_previous_app(app: AppHandle, label: Option<String>) -> Result<(), AppError> {
+    #[cfg(target_os = "macos")]
+    {
+        use super::quick_pane::{
+            dismiss_quick_pane_by_label, get_quick_pane_config, resolve_label, Dismissal,
+        };
+
+        let label = resolve_label(label);
+        if !platform::is_accessibility_trusted() {
+            return Err(AppError::Permission {
+                message: "Pasting into other apps needs the Accessibility permission".to_string(),
+            });
+        }
+        // Pinned panes don't hand focus back when they're dismissed
+        if get_quick_pane_config(&label)?.pinned {
+            return Err(AppError::validation(format!(
+                "Quick pane '{label}' is pinned"
+            )));
+        }
+
+        log::info!("Pasting from quick pane '{label}' into the previous app");
+        dismiss_quick_pane_by_label(&app, &label, Dismissal::Explicit)?;
+        std::thread::spawn(|| {
+            std::thread::sleep(PASTE_DELAY);
+            platform::send_paste_shortcut();
+        });
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = (app, label);
+        Err(AppError::internal(
+            "Pasting into other apps is only supported on macOS",
+        ))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use image::RgbaImage;
+    use objc2::rc::{autoreleasepool, Retained};
+    use objc2::runtime::ProtocolObject;
+    use objc2_app_kit::{
+        NSPasteboard, NSPasteboardItem, NSPasteboardTypeFileURL, NSPasteboardTypeHTML,
+        NSPasteboardTypePNG, NSPasteboardTypeRTF, NSPasteboardTypeString, NSPasteboardWriting,
+    };
+    use objc2_application_services::AXIsProcessTrusted;
+    use objc2_core_graphics::{CGEvent, CGEventFlags, CGEventTapLocation, CGKeyCode};
+    use objc2_foundation::{NSArray, NSData, NSString, NSURL};
+
+    use crate::types::{AppError, ClipboardData};
+
+    /// Virtual key code of the V key (`kVK_ANSI_V`).
+    const V_KEY_CODE: CGKeyCode = 9;
+
+    pub fn write(
+        data: &ClipboardData,
+        _image: Option<&RgbaImage>,
+        files: &[String],
+    ) -> Result<(), AppError> {
+        // SAFETY: the pasteboard type constants are immutable statics
+        let (string_type, html_type, rtf_type, png_type, file_url_type) = unsafe {
+            (
+                NSPasteboardTypeString,
+                NSPasteboardTypeHTML,
+                NSPasteboardTypeRTF,
+                NSPasteboardTypePNG,
+                NSPasteboardTypeFileURL,
+            )
+        };
+
+        autoreleasepool(|_| {
+            // Every format goes on the first item; each further file gets an
+            // item of its own
+            let items: Vec<Retained<NSPasteboardItem>> = (0..files.len().max(1))
+                .map(|_| NSPasteboardItem::new())
+                .collect();
+            let first = &items[0];
+
+            if let Some(text) = &data.text {
+                first.setString_forType(&NSString::from_str(text), string_type);
+            }
+            if let Some(html) = &data.html {
+                first.setString_forType(&NSString::from_str(html), html_type);
+            }
+            if let Some(rtf) = &data.rtf {
+                first.setData_forType(&NSData::with_bytes(rtf.as_bytes()), rtf_type);
+            }
+            if let Some(png) = &data.png {
+                first.setData_forType(&NSData::with_bytes(png), png_type);
+            }
+            for (item, path) in items.iter().zip(files) {
+                let url = NSURL::fileURLWithPath(&NSString::from_str(path));
+                if let Some(url) = url.absoluteString() {
+                    item.setString_forType(&url, file_url_type);
+                }
+            }
+
+            let objects: Vec<Retained<ProtocolObject<dyn NSPasteboardWriting>>> = items
+                .into_iter()
+                .map(ProtocolObject::from_retained)
+                .collect();
+            let pasteboard = NSPasteboard::generalPasteboard();
+            pasteboard.clearContents();
+            if pasteboard.writeObjects(&NSArray::from_retained_slice(&objects)) {
+                Ok(())
+            } else {
+                Err(AppError::internal("Failed to write to the clipboard"))
+            }
+        })
+    }
+
+    pub fn read() -> Result<ClipboardData, AppError> {
+        // SAFETY: the pasteboard type constants are immutable statics
+        let (string_type, html_type, rtf_type, png_type, file_url_type) = unsafe {
+            (
+                NSPasteboardTypeString,
+                NSPasteboardTypeHTML,
+                NSPasteboardTypeRTF,
+                NSPasteboardTypePNG,
+                NSPasteboardTypeFileURL,
+            )
+        };
+
+        Ok(autoreleasepool(|_| {
+            let pasteboard = NSPasteboard::generalPasteboard();
+            let files: Vec<String> = pasteboard
+                .pasteboardItems()
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter_map(|item| item.stringForType(file_url_type))
+                        .filter_map(|url| NSURL::URLWithString(&url))
+                        .filter_map(|url| url.path())
+                        .map(|path| path.to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            ClipboardData {
+                text: pasteboard.stringForType(string_type).map(|s| s.to_string()),
+                html: pasteboard.stringForType(html_type).map(|s| s.to_string()),
+                rtf: pasteboard
+                    .dataForType(rtf_type)
+                    .map(|rtf| String::from_utf8_lossy(&rtf.to_vec()).into_owned()),
+                png: pasteboard.dataForType(png_type).map(|png| png.to_vec()),
+                files: (!files.is_empty()).then_some(files),
+            }
+        }))
+    }
+
+    pub fn is_accessibility_trusted() -> bool {
+        unsafe { AXIsProcessTrusted() }
+    }
+
+    /// Presses Cmd+V in the frontmost app.
+    pub fn send_paste_shortcut() {
+        for key_down in [true, false] {
+            let event = CGEvent::new_keyboard_event(None, V_KEY_CODE, key_down);
+            CGEvent::set_flags(event.as_deref(), CGEventFlags::MaskCommand);
+            CGEvent::post(CGEventTapLocation::HIDEventTap, event.as_deref());
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use clipboard_win::formats::{Html, CF_HDROP, CF_UNICODETEXT};
+    use clipboard_win::options::NoClear;
+    use clipboard_win::{raw, Clipboard, ErrorCode};
+    use image::{ImageFormat, RgbaImage};
+    use std::io::Cursor;
+
+    use crate::types::{AppError, ClipboardData};
+
+    /// Other apps can hold the clipboard open briefly, so opening is retried.
+    const OPEN_ATTEMPTS: usize = 10;
+
+    fn open() -> Result<Clipboard, AppError> {
+        Clipboard::new_attempts(OPEN_ATTEMPTS)
+            .map_err(|e| AppError::internal(format!("Failed to open clipboard: {e}")))
+    }
+
+    fn register_format(name: &str) -> Result<u32, AppError> {
+        raw::register_format(name)
+            .map(|format| format.get())
+            .ok_or_else(|| {
+                AppError::internal(format!("Failed to register clipboard format {name}"))
+            })
+    }
+
+    fn write_failed(e: ErrorCode) -> AppError {
+        AppError::internal(format!("Failed to write to the clipboard: {e}"))
+    }
+
+    /// Reads a format's raw bytes, if it's on the clipboard.
+    fn read_format(format: u32) -> Option<Vec<u8>> {
+        if !raw::is_format_avail(format) {
+            return None;
+        }
+        let mut bytes = Vec::new();
+        raw::get_vec(format, &mut bytes).ok().map(|_| bytes)
+    }
+
+    pub fn write(
+        data: &ClipboardData,
+        image: Option<&RgbaImage>,
+        files: &[String],
+    ) -> Result<(), AppError> {
+        let _clipboard = open()?;
+        raw::empty()
+            .map_err(|e| AppError::internal(format!("Failed to clear the clipboard: {e}")))?;
+
+        if let Some(text) = &data.text {
+            raw::set_string_with(text, NoClear).map_err(write_failed)?;
+        }
+        if let Some(html) = &data.html {
+            let format = Html::new().ok_or_else(|| {
+                AppError::internal("Failed to register clipboard format HTML Format")
+            })?;
+            raw::set_html(format.code(), html).map_err(write_failed)?;
+        }
+        if let Some(rtf) = &data.rtf {
+            raw::set_without_clear(register_format("Rich Text Format")?, rtf.as_bytes())
+                .map_err(write_failed)?;
+        }
+        if let (Some(png), Some(image)) = (&data.png, image) {
+            raw::set_without_clear(register_format("PNG")?, png).map_err(write_failed)?;
+            // Most apps only paste bitmaps
+            let mut bmp = Cursor::new(Vec::new());
+            image.write_to(&mut bmp, ImageFormat::Bmp).map_err(|e| {
+                AppError::internal(format!("Failed to encode clipboard image: {e}"))
+            })?;
+            raw::set_bitmap_with(&bmp.into_inner(), NoClear).map_err(write_failed)?;
+        }
+        if !files.is_empty() {
+            raw::set_file_list_with(files, NoClear).map_err(write_failed)?;
+        }
+        Ok(())
+    }
+
+    pub fn read() -> Result<ClipboardData, AppError> {
+        let _clipboard = open()?;
+
+        let text = raw::is_format_avail(CF_UNICODETEXT)
+            .then(|| {
+                let mut bytes = Vec::new();
+                raw::get_string(&mut bytes).ok()?;
+                String::from_utf8(bytes).ok()
+            })
+            .flatten();
+        let html = Html::new()
+            .filter(|format| raw::is_format_avail(format.code()))
+            .and_then(|format| {
+                let mut bytes = Vec::new();
+                raw::get_html(format.code(), &mut bytes).ok()?;
+                String::from_utf8(bytes).ok()
+            });
+        // RTF is 7-bit ASCII, NUL-terminated by most apps
+        let rtf = register_format("Rich Text Format")
+            .ok()
+            .and_then(read_format)
+            .map(|rtf| {
+                String::from_utf8_lossy(&rtf)
+                    .trim_end_matches('\0')
+                    .to_string()
+            });
+        let png = register_format("PNG").ok().and_then(read_format);
+        let files = raw::is_format_avail(CF_HDROP)
+            .then(|| {
+                let mut files = Vec::new();
+                raw::get_file_list(&mut files).ok().map(|_| files)
+            })
+            .flatten()
+            .filter(|files| !files.is_empty());
+
+        Ok(ClipboardData {
+            text,
+            html,
+            rtf,
+            png,
+            files,
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use arboard::{Clipboard, ImageData};
+    use image::RgbaImage;
+    use std::borrow::Cow;
+
+    use crate::types::{AppError, ClipboardData};
+
+    fn open() -> Result<Clipboard, AppError> {
+        Clipboard::new().map_err(|e| AppError::internal(format!("Failed to open clipboard: {e}")))
+    }
+
+    pub fn write(
+        data: &ClipboardData,
+        image: Option<&RgbaImage>,
+        files: &[String],
+    ) -> Result<(), AppError> {
+        let mut clipboard = open()?;
+
+        // Only one format can be served, so the richest one wins
+        let result = if !files.is_empty() {
+            clipboard.set().file_list(files)
+        } else if let Some(image) = image {
+            clipboard.set().image(ImageData {
+                width: image.width() as usize,
+                height: image.height() as usize,
+                bytes: Cow::Borrowed(image.as_raw()),
+            })
+        } else if let Some(html) = &data.html {
+            clipboard.set().html(html.as_str(), data.text.as_deref())
+        } else if let Some(text) = &data.text {
+            clipboard.set().text(text.as_str())
+        } else {
+            return Err(AppError::internal(
+                "RTF can't be written to the clipboard on Linux",
+            ));
+        };
+        result.map_err(|e| AppError::internal(format!("Failed to write to the clipboard: {e}")))
+    }
+
+    pub fn read() -> Result<ClipboardData, AppError> {
+        let mut clipboard = open()?;
+
+        let files = clipboard
+            .get()
+            .file_list()
+            .ok()
+            .filter(|files| !files.is_empty())
+            .map(|files| {
+                files
+                    .iter()
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .collect()
+            });
+
+        Ok(ClipboardData {
+            text: clipboard.get_text().ok(),
+            html: clipboard.get().html().ok(),
+            rtf: None,
+            png: None,
+            files,
+        })
+    }
+}
+
+#[cfg(mobile)]
+mod platform {
+    use image::RgbaImage;
+
+    use crate::types::{AppError, ClipboardData};
+
+    pub fn write(
+        _data: &ClipboardData,
+        _image: Option<&RgbaImage>,
+        _files: &[String],
+    ) -> Result<(), AppError> {
+        Err(AppError::internal(
+            "Rich clipboard formats are only supported on desktop",
+        ))
+    }
+
+    pub fn read() -> Result<ClipboardData, AppError> {
+        Err(AppError::internal(
+            "Rich clipboard formats are only supported on desktop",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_requires_a_format() {
+        assert!(validate_clipboard_data(&ClipboardData::default()).is_err());
+
+        let empty_files = ClipboardData {
+            files: Some(vec![]),
+            ..Default::default()
+        };
+        assert!(validate_clipboard_data(&empty_files).is_err());
+
+        let text = ClipboardData {
+            text: Some("hello".to_string()),
+            ..Default::default()
+        };
+        assert!(validate_clipboard_data(&text).is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_large_png() {
+        let data = ClipboardData {
+            png: Some(vec![0; MAX_PNG_SIZE + 1]),
+            ..Default::default()
+        };
+        assert!(validate_clipboard_data(&data).is_err());
+    }
+
+    #[test]
+    fn test_png_round_trip() {
+        let image = RgbaImage::from_pixel(3, 2, image::Rgba([10, 20, 30, 255]));
+        let png = encode_png(&image).unwrap();
+        assert_eq!(decode_png(&png).unwrap(), image);
+        assert!(decode_png(b"not a png").is_err());
+    }
+}
@@ -0,0 +1,468 @@
+//! Clipboard history.
+//!
+//! When `WATCH_CLIPBOARD` is on, `start_clipboard_watcher` polls the
+//! clipboard every `POLL_INTERVAL` and records anything new in
+//! `clipboard-history.json` in the app data directory, broadcasting it to
+//! all windows as `clipboard-changed`. On macOS and Windows the clipboard's
+//! change counter is checked first, so the contents are only read after a
+//! copy. Linux has no such counter, so the contents are read and compared
+//! on every poll.
+//!
+//! Only text is recorded unless `CLIPBOARD_HISTORY_IMAGES` is on, in which
+//! case images are saved as PNGs in `clipboard-images/` next to the history
+//! file. Content that password managers mark as concealed is skipped.
+
+use image::{ImageFormat, RgbaImage};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+use super::files::write_atomic;
+use crate::types::{AppError, ClipboardContent, ClipboardEntry};
+
+/// Watch the clipboard and keep a history of what was copied.
+pub const WATCH_CLIPBOARD: bool = false;
+
+/// Keep copied images in the history too, not just text.
+pub const CLIPBOARD_HISTORY_IMAGES: bool = false;
+
+#[cfg_attr(mobile, allow(dead_code))]
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Maximum number of entries kept; the oldest are dropped first.
+const MAX_HISTORY_ENTRIES: usize = 100;
+
+/// Longer text (e.g. a whole copied file) isn't recorded.
+#[cfg_attr(mobile, allow(dead_code))]
+const MAX_TEXT_LENGTH: usize = 100 * 1024;
+
+/// Larger images aren't recorded.
+#[cfg_attr(mobile, allow(dead_code))]
+const MAX_IMAGE_PIXELS: u64 = 4096 * 4096;
+
+/// Serializes read-modify-write cycles on the history file.
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+/// Clipboard contents read by the watcher, before they're stored.
+#[cfg_attr(mobile, allow(dead_code))]
+enum Copied {
+    Text(String),
+    Image {
+        rgba: Vec<u8>,
+        width: u32,
+        height: u32,
+    },
+}
+
+impl Copied {
+    /// Identifies the content, so copying the same thing again moves its
+    /// entry to the top instead of adding another.
+    #[cfg_attr(mobile, allow(dead_code))]
+    fn id(&self) -> String {
+        let mut hasher = blake3::Hasher::new();
+        match self {
+            Copied::Text(text) => {
+                hasher.update(b"text");
+                hasher.update(text.as_bytes());
+            }
+            Copied::Image {
+                rgba,
+                width,
+                height,
+            } => {
+                hasher.update(b"image");
+                hasher.update(&width.to_le_bytes());
+                hasher.update(&height.to_le_bytes());
+                hasher.update(rgba);
+            }
+        }
+        hasher.finalize().to_hex()[..32].to_string()
+    }
+}
+
+#[cfg_attr(mobile, allow(dead_code))]
+fn now_millis() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as f64)
+        .unwrap_or(0.0)
+}
+
+/// Gets the path to the history file.
+fn get_history_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = crate::data_dir::app_data_dir(app)?;
+
+    // Ensure the directory exists
+    std::fs::create_dir_all(&app_data_dir)
+        .map_err(|e| AppError::io(format!("Failed to create app data directory: {e}")))?;
+
+    Ok(app_data_dir.join("clipboard-history.json"))
+}
+
+/// Gets the directory copied images are saved in. It may not exist yet.
+fn get_images_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    Ok(crate::data_dir::app_data_dir(app)?.join("clipboard-images"))
+}
+
+/// Reads the history file, returning empty history if it doesn't exist.
+fn read_history(app: &AppHandle) -> Result<Vec<ClipboardEntry>, AppError> {
+    let path = get_history_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::io(format!("Failed to read clipboard history: {e}")))?;
+    serde_json::from_str(&contents).map_err(|e| AppError::Serialization {
+        message: format!("Failed to parse clipboard history: {e}"),
+    })
+}
+
+/// Writes the history file using atomic write (temp file + rename).
+fn write_history(app: &AppHandle, history: &[ClipboardEntry]) -> Result<(), AppError> {
+    let path = get_history_path(app)?;
+    let json_content =
+        serde_json::to_string_pretty(history).map_err(|e| AppError::Serialization {
+            message: format!("Failed to serialize clipboard history: {e}"),
+        })?;
+
+    write_atomic(&path, json_content.as_bytes())
+        .map_err(|e| AppError::io(format!("Failed to write clipboard history: {e}")))
+}
+
+/// Adds an entry to the front of the history, moving it up if it already
+/// exists. Returns the oldest entries dropped beyond `max_entries`.
+#[cfg_attr(mobile, allow(dead_code))]
+fn push_history_entry(
+    entries: &mut Vec<ClipboardEntry>,
+    entry: ClipboardEntry,
+    max_entries: usize,
+) -> Vec<ClipboardEntry> {
+    entries.retain(|existing| existing.id != entry.id);
+    entries.insert(0, entry);
+    if entries.len() > max_entries {
+        entries.split_off(max_entries)
+    } else {
+        Vec::new()
+    }
+}
+
+/// Deletes the saved images of entries that are no longer in the history.
+#[cfg_attr(mobile, allow(dead_code))]
+fn remove_entry_images(entries: &[ClipboardEntry]) {
+    for entry in entries {
+        if let ClipboardContent::Image { path, .. } = &entry.content {
+            if let Err(e) = std::fs::remove_file(path) {
+                log::warn!("Failed to remove clipboard image {path}: {e}");
+            }
+        }
+    }
+}
+
+/// Saves a copied image as a PNG, named by its id. Returns its path.
+#[cfg_attr(mobile, allow(dead_code))]
+fn save_image(
+    images_dir: &Path,
+    id: &str,
+    rgba: Vec<u8>,
+    width: u32,
+    height: u32,
+) -> Result<PathBuf, AppError> {
+    let path = images_dir.join(format!("{id}.png"));
+    if path.exists() {
+        return Ok(path);
+    }
+
+    let image = RgbaImage::from_raw(width, height, rgba)
+        .ok_or_else(|| AppError::internal("Failed to save clipboard image: size doesn't match"))?;
+    let mut bytes = Cursor::new(Vec::new());
+    image
+        .write_to(&mut bytes, ImageFormat::Png)
+        .map_err(|e| AppError::internal(format!("Failed to encode clipboard image: {e}")))?;
+
+    std::fs::create_dir_all(images_dir)
+        .map_err(|e| AppError::io(format!("Failed to create clipboard images directory: {e}")))?;
+    write_atomic(&path, &bytes.into_inner())
+        .map_err(|e| AppError::io(format!("Failed to save clipboard image: {e}")))?;
+
+    Ok(path)
+}
+
+/// Records copied content at the top of the history.
+#[cfg_attr(mobile, allow(dead_code))]
+fn record_copied(app: &AppHandle, id: String, copied: Copied) -> Result<ClipboardEntry, AppError> {
+    let content = match copied {
+        Copied::Text(text) => ClipboardContent::Text { text },
+        Copied::Image {
+            rgba,
+            width,
+            height,
+        } => {
+            let path = save_image(&get_images_dir(app)?, &id, rgba, width, height)?;
+            ClipboardContent::Image {
+                path: path.to_string_lossy().into_owned(),
+                width,
+                height,
+            }
+        }
+    };
+    let entry = ClipboardEntry {
+        id,
+        content,
+        copied_at: now_millis(),
+    };
+
+    let _guard = HISTORY_LOCK
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock clipboard history: {e}")))?;
+
+    let mut history = read_history(app)?;
+    let dropped = push_history_entry(&mut history, entry.clone(), MAX_HISTORY_ENTRIES);
+    write_history(app, &history)?;
+    remove_entry_images(&dropped);
+
+    Ok(entry)
+}
+
+/// Reads the clipboard, preferring text. Returns None when it's empty or
+/// holds something that isn't recorded.
+#[cfg(desktop)]
+fn read_clipboard(app: &AppHandle) -> Option<Copied> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    if let Ok(text) = app.clipboard().read_text() {
+        if text.trim().is_empty() || text.len() > MAX_TEXT_LENGTH {
+            return None;
+        }
+        return Some(Copied::Text(text));
+    }
+
+    if !CLIPBOARD_HISTORY_IMAGES {
+        return None;
+    }
+    let image = app.clipboard().read_image().ok()?;
+    let (width, height) = (image.width(), image.height());
+    if u64::from(width) * u64::from(height) > MAX_IMAGE_PIXELS {
+        log::debug!("Not recording {width}x{height} clipboard image: too large");
+        return None;
+    }
+    Some(Copied::Image {
+        rgba: image.rgba().to_vec(),
+        width,
+        height,
+    })
+}
+
+/// Starts polling the clipboard for changes. Called during app setup when
+/// `WATCH_CLIPBOARD` is on.
+#[cfg(desktop)]
+pub fn start_clipboard_watcher(app: &AppHandle) {
+    use crate::types::ClipboardChanged;
+    use tauri::Manager;
+    use tauri_specta::Event;
+
+    if CLIPBOARD_HISTORY_IMAGES {
+        // Let the frontend display saved images with `convertFileSrc`, even
+        // if the data has been moved
+        let allowed = get_images_dir(app).and_then(|dir| {
+            app.asset_protocol_scope()
+                .allow_directory(dir, false)
+                .map_err(|e| AppError::internal(e.to_string()))
+        });
+        if let Err(e) = allowed {
+            log::warn!("Failed to allow clipboard images in the asset protocol: {e}");
+        }
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        // Whatever is on the clipboard at launch is recorded unless it's
+        // already the newest entry
+        let mut last_id = read_history(&app)
+            .ok()
+            .and_then(|history| history.into_iter().next())
+            .map(|entry| entry.id);
+        let mut last_change_count = None;
+
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+
+            let change_count = platform::change_count();
+            if change_count.is_some() && change_count == last_change_count {
+                continue;
+            }
+            last_change_count = change_count;
+
+            if platform::is_concealed() {
+                continue;
+            }
+            let Some(copied) = read_clipboard(&app) else {
+                continue;
+            };
+            let id = copied.id();
+            if last_id.as_ref() == Some(&id) {
+                continue;
+            }
+            last_id = Some(id.clone());
+
+            match record_copied(&app, id, copied) {
+                Ok(entry) => {
+                    if let Err(e) = (ClipboardChanged { entry }).emit(&app) {
+                        log::error!("Failed to emit clipboard-changed: {e}");
+                    }
+                }
+                Err(e) => log::error!("{e}"),
+            }
+        }
+    });
+}
+
+/// Returns the clipboard history, most recent first. Empty unless
+/// `WATCH_CLIPBOARD` is on.
+#[tauri::command]
+#[specta::specta]
+pub fn get_clipboard_history(app: AppHandle) -> Result<Vec<ClipboardEntry>, AppError> {
+    let _guard = HISTORY_LOCK
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock clipboard history: {e}")))?;
+
+    read_history(&app)
+}
+
+/// Clears the clipboard history and deletes its saved images. The
+/// clipboard itself is left alone.
+#[tauri::command]
+#[specta::specta]
+pub fn clear_clipboard_history(app: AppHandle) -> Result<(), AppError> {
+    log::info!("Clearing clipboard history");
+
+    let _guard = HISTORY_LOCK
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock clipboard history: {e}")))?;
+
+    write_history(&app, &[])?;
+
+    let images_dir = get_images_dir(&app)?;
+    if images_dir.exists() {
+        std::fs::remove_dir_all(&images_dir)
+            .map_err(|e| AppError::io(format!("Failed to remove clipboard images: {e}")))?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use objc2::rc::autoreleasepool;
+    use objc2_app_kit::NSPasteboard;
+
+    /// Marks content password managers don't want recorded
+    /// (see nspasteboard.org).
+    const CONCEALED_TYPE: &str = "org.nspasteboard.ConcealedType";
+
+    /// Bumped by the system on every copy.
+    pub fn change_count() -> Option<i64> {
+        Some(NSPasteboard::generalPasteboard().changeCount() as i64)
+    }
+
+    pub fn is_concealed() -> bool {
+        autoreleasepool(|_| {
+            NSPasteboard::generalPasteboard()
+                .types()
+                .is_some_and(|types| types.iter().any(|t| t.to_string() == CONCEALED_TYPE))
+        })
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::sync::LazyLock;
+    use windows_sys::core::w;
+    use windows_sys::Win32::System::DataExchange::{
+        GetClipboardSequenceNumber, IsClipboardFormatAvailable, RegisterClipboardFormatW,
+    };
+
+    /// Format password managers add to content they don't want recorded.
+    static EXCLUDE_FORMAT: LazyLock<u32> = LazyLock::new(|| unsafe {
+        RegisterClipboardFormatW(w!("ExcludeClipboardContentFromMonitorProcessing"))
+    });
+
+    /// Bumped by the system on every copy.
+    pub fn change_count() -> Option<i64> {
+        Some(i64::from(unsafe { GetClipboardSequenceNumber() }))
+    }
+
+    pub fn is_concealed() -> bool {
+        *EXCLUDE_FORMAT != 0 && unsafe { IsClipboardFormatAvailable(*EXCLUDE_FORMAT) } != 0
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    /// Not available, so the contents are compared instead.
+    #[cfg_attr(mobile, allow(dead_code))]
+    pub fn change_count() -> Option<i64> {
+        None
+    }
+
+    #[cfg_attr(mobile, allow(dead_code))]
+    pub fn is_concealed() -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_entry(text: &str) -> ClipboardEntry {
+        let copied = Copied::Text(text.to_string());
+        ClipboardEntry {
+            id: copied.id(),
+            content: ClipboardContent::Text {
+                text: text.to_string(),
+            },
+            copied_at: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_push_adds_most_recent_first() {
+        let mut entries = vec![text_entry("older")];
+        let dropped = push_history_entry(&mut entries, text_entry("newer"), 10);
+        assert_eq!(entries, vec![text_entry("newer"), text_entry("older")]);
+        assert!(dropped.is_empty());
+    }
+
+    #[test]
+    fn test_push_moves_duplicate_to_front() {
+        let mut entries = vec![text_entry("a"), text_entry("b"), text_entry("c")];
+        let dropped = push_history_entry(&mut entries, text_entry("c"), 10);
+        assert_eq!(
+            entries,
+            vec![text_entry("c"), text_entry("a"), text_entry("b")]
+        );
+        assert!(dropped.is_empty());
+    }
+
+    #[test]
+    fn test_push_returns_dropped_entries() {
+        let mut entries = vec![text_entry("a"), text_entry("b")];
+        let dropped = push_history_entry(&mut entries, text_entry("c"), 2);
+        assert_eq!(entries, vec![text_entry("c"), text_entry("a")]);
+        assert_eq!(dropped, vec![text_entry("b")]);
+    }
+
+    #[test]
+    fn test_id_distinguishes_text_from_image() {
+        let text = Copied::Text("ab".to_string());
+        let image = Copied::Image {
+            rgba: b"ab".to_vec(),
+            width: 0,
+            height: 0,
+        };
+        assert_ne!(text.id(), image.id());
+        assert_eq!(text.id(), Copied::Text("ab".to_string()).id());
+    }
+}
@@ -0,0 +1,286 @@
+//! Crash reports.
+//!
+//! A panic hook writes a structured report (message, backtrace, app and system
+//! info, and the last lines of the log) to `crash-reports/` in the app data
+//! directory. On the next launch, once the frontend is ready, the newest report
+//! is announced with `crash-report-available` so the UI can offer to send it.
+//! Reports are also included in diagnostics bundles until dismissed.
+//!
+//! Uncaught frontend errors are sent with `report_frontend_error`, which logs
+//! them and saves them to the same store, so a bundle holds backend and
+//! frontend failures together. Those aren't announced, since the app kept
+//! running.
+
+use std::backtrace::Backtrace;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, Window};
+use tauri_specta::Event;
+
+use super::diagnostics::{redact_json, redact_text, system_info};
+use super::files::write_atomic;
+use super::logging::last_log_lines;
+use super::window::MAIN_WINDOW_LABEL;
+use crate::types::{
+    validate_filename, validate_string_input, AppError, CrashReport, CrashReportAvailable,
+    CrashReportKind,
+};
+
+/// Log lines included in a report.
+const LOG_LINES: usize = 200;
+
+/// Older reports are deleted once there are more than this.
+const MAX_CRASH_REPORTS: usize = 10;
+
+/// Frontend errors saved as reports per launch. Later ones are only logged,
+/// so an error thrown in a loop can't push real crashes out of the store.
+const MAX_FRONTEND_REPORTS: u32 = 5;
+
+const MAX_ERROR_LENGTH: usize = 10_000;
+const MAX_STACK_LENGTH: usize = 50_000;
+const MAX_CONTEXT_BYTES: usize = 64 * 1024;
+
+/// Set by `install_panic_hook`, so the hook can find the app's directories.
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+/// Set once the pending report has been announced this launch.
+static ANNOUNCED: AtomicBool = AtomicBool::new(false);
+
+static FRONTEND_REPORTS: AtomicU32 = AtomicU32::new(0);
+
+/// Gets the crash reports directory, creating it if needed.
+fn get_crash_reports_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = crate::data_dir::app_data_dir(app)?;
+
+    let dir = app_data_dir.join("crash-reports");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| AppError::io(format!("Failed to create crash reports directory: {e}")))?;
+    Ok(dir)
+}
+
+/// Installs a panic hook that writes a crash report before the default hook
+/// runs. Called at the start of setup, right after logging is set up.
+pub fn install_panic_hook(app: &AppHandle) {
+    let _ = APP_HANDLE.set(app.clone());
+
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Unknown panic".to_string());
+        let location = info.location().map(|location| location.to_string());
+
+        write_report(message, location);
+        default_hook(info);
+    }));
+}
+
+/// Records a fatal error that isn't a panic (e.g. the app failing to build)
+/// as a crash report, then exits.
+pub fn exit_with_error(message: &str) -> ! {
+    log::error!("{message}");
+    write_report(message.to_string(), None);
+    std::process::exit(1);
+}
+
+/// Writes a crash report for the current thread. Does nothing before
+/// `install_panic_hook`, since the app's directories aren't known yet.
+fn write_report(message: String, location: Option<String>) {
+    let Some(app) = APP_HANDLE.get() else {
+        return;
+    };
+
+    let report = CrashReport {
+        location,
+        thread: std::thread::current().name().map(String::from),
+        backtrace: Backtrace::force_capture().to_string(),
+        ..new_report(app, CrashReportKind::Panic, &message)
+    };
+
+    // Logging may be what panicked, so failures go to stderr
+    if let Err(e) = save_report(app, &report) {
+        eprintln!("Failed to write crash report: {e}");
+    }
+}
+
+/// A report with the message, app and system info, and recent log lines
+/// filled in. The message and log lines are redacted.
+fn new_report(app: &AppHandle, kind: CrashReportKind, message: &str) -> CrashReport {
+    let home_dir = app.path().home_dir().ok();
+    let home_dir = home_dir.as_deref();
+    let created_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    let system = system_info(app);
+
+    CrashReport {
+        id: format!("crash-{created_at}"),
+        kind,
+        created_at: created_at as f64,
+        message: redact_text(message, home_dir),
+        location: None,
+        thread: None,
+        backtrace: String::new(),
+        window: None,
+        context: None,
+        app_version: system.app_version,
+        os: system.os.to_string(),
+        os_version: system.os_version,
+        arch: system.arch.to_string(),
+        log_lines: last_log_lines(app, LOG_LINES)
+            .unwrap_or_default()
+            .iter()
+            .map(|line| redact_text(line, home_dir))
+            .collect(),
+    }
+}
+
+/// Saves a report, then deletes the oldest beyond `MAX_CRASH_REPORTS`.
+/// Uses atomic write (temp file + rename).
+fn save_report(app: &AppHandle, report: &CrashReport) -> Result<(), AppError> {
+    let dir = get_crash_reports_dir(app)?;
+    let json = serde_json::to_string_pretty(report).map_err(|e| AppError::Serialization {
+        message: format!("Failed to serialize crash report: {e}"),
+    })?;
+
+    let path = dir.join(format!("{}.json", report.id));
+    write_atomic(&path, json.as_bytes())
+        .map_err(|e| AppError::io(format!("Failed to save crash report: {e}")))?;
+
+    // Ids are `crash-<millis>`, so names sort by age. Doesn't parse reports or
+    // log, since this runs inside the panic hook.
+    let mut names: Vec<_> = std::fs::read_dir(&dir)
+        .map_err(|e| AppError::io(format!("Failed to read crash reports: {e}")))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.file_name())
+        .filter(|name| name.to_string_lossy().ends_with(".json"))
+        .collect();
+    names.sort_by(|a, b| b.cmp(a));
+    for name in names.iter().skip(MAX_CRASH_REPORTS) {
+        let _ = std::fs::remove_file(dir.join(name));
+    }
+    Ok(())
+}
+
+/// Loads the saved crash reports, newest first. Unreadable reports are skipped.
+pub fn load_crash_reports(app: &AppHandle) -> Vec<CrashReport> {
+    let Ok(entries) = get_crash_reports_dir(app).and_then(|dir| {
+        std::fs::read_dir(dir)
+            .map_err(|e| AppError::io(format!("Failed to read crash reports: {e}")))
+    }) else {
+        return Vec::new();
+    };
+
+    let mut reports: Vec<CrashReport> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let contents = std::fs::read_to_string(&path).ok()?;
+            serde_json::from_str(&contents)
+                .inspect_err(|e| log::warn!("Failed to parse crash report {path:?}: {e}"))
+                .ok()
+        })
+        .collect();
+    reports.sort_by(|a, b| b.created_at.total_cmp(&a.created_at));
+    reports
+}
+
+/// Emits `crash-report-available` for the newest panic report, once per
+/// launch. Called when the frontend finishes starting up, so a listener is
+/// ready.
+pub fn announce_pending_report(app: &AppHandle) {
+    if ANNOUNCED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let Some(report) = load_crash_reports(app)
+        .into_iter()
+        .find(|report| report.kind == CrashReportKind::Panic)
+    else {
+        return;
+    };
+
+    log::info!("Found crash report from a previous launch: {}", report.id);
+    if let Err(e) = (CrashReportAvailable { report }).emit_to(app, MAIN_WINDOW_LABEL) {
+        log::warn!("Failed to emit crash report: {e}");
+    }
+}
+
+/// Logs an uncaught frontend error and saves it as a crash report, with the
+/// window it came from and any app state in `context`.
+#[tauri::command]
+#[specta::specta]
+pub fn report_frontend_error(
+    app: AppHandle,
+    window: Window,
+    error: String,
+    stack: Option<String>,
+    context: Option<serde_json::Value>,
+) -> Result<(), AppError> {
+    validate_string_input(&error, MAX_ERROR_LENGTH, "Error").map_err(AppError::validation)?;
+    if let Some(stack) = &stack {
+        validate_string_input(stack, MAX_STACK_LENGTH, "Stack").map_err(AppError::validation)?;
+    }
+    let mut context = context.filter(|context| !context.is_null());
+    if let Some(context) = &context {
+        let size = serde_json::to_vec(context)
+            .map_err(|e| AppError::Serialization {
+                message: format!("Failed to serialize context: {e}"),
+            })?
+            .len();
+        if size > MAX_CONTEXT_BYTES {
+            return Err(AppError::validation(format!(
+                "Context too large ({size} bytes, max {MAX_CONTEXT_BYTES})"
+            )));
+        }
+    }
+
+    let label = window.label().to_string();
+    log::error!(target: "frontend", "Uncaught error in {label} window: {error}");
+    if let Some(stack) = &stack {
+        log::debug!(target: "frontend", "{stack}");
+    }
+
+    if FRONTEND_REPORTS.fetch_add(1, Ordering::SeqCst) >= MAX_FRONTEND_REPORTS {
+        return Ok(());
+    }
+
+    let home_dir = app.path().home_dir().ok();
+    let home_dir = home_dir.as_deref();
+    if let Some(context) = &mut context {
+        redact_json(context, home_dir);
+    }
+    let report = CrashReport {
+        backtrace: stack
+            .map(|stack| redact_text(&stack, home_dir))
+            .unwrap_or_default(),
+        window: Some(label),
+        context,
+        ..new_report(&app, CrashReportKind::Frontend, &error)
+    };
+    save_report(&app, &report)
+}
+
+/// Deletes a crash report once it has been sent or the user declined to send
+/// it, so it isn't announced again.
+#[tauri::command]
+#[specta::specta]
+pub fn dismiss_crash_report(app: AppHandle, id: String) -> Result<(), AppError> {
+    validate_filename(&id).map_err(AppError::validation)?;
+
+    let path = get_crash_reports_dir(&app)?.join(format!("{id}.json"));
+    match std::fs::remove_file(&path) {
+        Ok(()) => {
+            log::info!("Dismissed crash report: {id}");
+            Ok(())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(AppError::io(format!("Failed to delete crash report: {e}"))),
+    }
+}
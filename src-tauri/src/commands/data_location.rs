@@ -0,0 +1,211 @@
+//! Commands for moving the app's data to another directory.
+//!
+//! `move_app_data` copies the data (see `data_dir::DATA_ENTRIES`) and logs to
+//! the new directory, records it in the pointer file and restarts the app.
+//! The originals are removed by `data_dir::remove_moved_data` once the app has
+//! started from the new location, so a failed move leaves them untouched.
+
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+use crate::data_dir::{self, DataLocation, DATA_ENTRIES};
+use crate::db::Database;
+use crate::types::{AppError, DataLocationInfo};
+
+const DATABASE_NAME: &str = "app.db";
+
+fn copy_recursive(src: &Path, dest: &Path) -> std::io::Result<()> {
+    if src.is_dir() {
+        std::fs::create_dir_all(dest)?;
+        for entry in std::fs::read_dir(src)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &dest.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        std::fs::copy(src, dest).map(|_| ())
+    }
+}
+
+/// Checks `target` can hold the data from `current`, creating it if needed.
+/// Returns its canonical path.
+fn prepare_target(target: &Path, current: &Path) -> Result<PathBuf, AppError> {
+    if !target.is_absolute() {
+        return Err(AppError::validation(
+            "Data location must be an absolute path",
+        ));
+    }
+    std::fs::create_dir_all(target)
+        .map_err(|e| AppError::io(format!("Failed to create {}: {e}", target.display())))?;
+    let target = std::fs::canonicalize(target)
+        .map_err(|e| AppError::io(format!("Failed to resolve {}: {e}", target.display())))?;
+    let current = std::fs::canonicalize(current).unwrap_or_else(|_| current.to_path_buf());
+
+    if target == current {
+        return Err(AppError::validation("Data is already stored there"));
+    }
+    if target.starts_with(&current) {
+        return Err(AppError::internal(
+            "Data can't be moved into its current directory",
+        ));
+    }
+    if let Some(name) = DATA_ENTRIES.iter().find(|name| target.join(name).exists()) {
+        return Err(AppError::validation(format!(
+            "{} already contains {name}",
+            target.display()
+        )));
+    }
+    Ok(target)
+}
+
+/// Copies the data in `current` to `target`. Returns the paths copied from,
+/// to be removed once the move takes effect.
+fn copy_data(
+    database: &Database,
+    current: &Path,
+    target: &Path,
+    log_dir: &Path,
+) -> Result<Vec<PathBuf>, AppError> {
+    // A snapshot, since the open database can't be copied safely
+    database.snapshot(&target.join(DATABASE_NAME))?;
+
+    let mut moved = Vec::new();
+    for name in DATA_ENTRIES {
+        let src = current.join(name);
+        if !src.exists() {
+            continue;
+        }
+        if !name.starts_with(DATABASE_NAME) {
+            copy_recursive(&src, &target.join(name))
+                .map_err(|e| AppError::io(format!("Failed to copy {name}: {e}")))?;
+        }
+        moved.push(src);
+    }
+
+    // Logs outside the data directory (macOS and Windows) move into `logs/`
+    if !log_dir.starts_with(current) && log_dir.is_dir() {
+        let entries = std::fs::read_dir(log_dir)
+            .map_err(|e| AppError::io(format!("Failed to read log directory: {e}")))?;
+        let target_logs = target.join("logs");
+        std::fs::create_dir_all(&target_logs)
+            .map_err(|e| AppError::io(format!("Failed to create log directory: {e}")))?;
+        for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+            if path.extension().is_some_and(|ext| ext == "log") {
+                if let Some(name) = path.file_name() {
+                    std::fs::copy(&path, target_logs.join(name))
+                        .map_err(|e| AppError::io(format!("Failed to copy logs: {e}")))?;
+                    moved.push(path);
+                }
+            }
+        }
+    }
+    Ok(moved)
+}
+
+/// Gets where the app's data is stored.
+#[tauri::command]
+#[specta::specta]
+pub fn get_data_location(app: AppHandle) -> Result<DataLocationInfo, AppError> {
+    Ok(DataLocationInfo {
+        path: data_dir::app_data_dir(&app)?.to_string_lossy().into_owned(),
+        is_default: !data_dir::is_relocated(&app)?,
+    })
+}
+
+/// Moves preferences, the database, recovery data and logs to
+/// `new_location` (an absolute path to an empty or new directory), then
+/// restarts the app to use them. Unsaved document changes are committed
+/// first.
+#[tauri::command]
+#[specta::specta]
+pub async fn move_app_data(
+    app: AppHandle,
+    database: State<'_, Database>,
+    new_location: String,
+) -> Result<(), AppError> {
+    log::info!("Moving app data to: {new_location}");
+    let current = data_dir::app_data_dir(&app)?;
+    let log_dir = data_dir::log_dir(&app)?;
+    let target = prepare_target(Path::new(&new_location), &current)?;
+
+    // Commit pending document saves so the copy includes them
+    super::documents::flush_pending_documents(&app);
+
+    let moved = match copy_data(&database, &current, &target, &log_dir) {
+        Ok(moved) => moved,
+        Err(e) => {
+            log::error!("Failed to move app data: {e}");
+            // The target had none of these before, so they're all partial copies
+            for name in DATA_ENTRIES {
+                let _ = std::fs::remove_dir_all(target.join(name));
+                let _ = std::fs::remove_file(target.join(name));
+            }
+            return Err(e);
+        }
+    };
+
+    data_dir::write_location(
+        &app,
+        &DataLocation {
+            path: target.clone(),
+            moved,
+            moved_from: vec![current, log_dir],
+        },
+    )?;
+
+    // Later saves would go to the old location, which is about to be removed
+    super::documents::discard_pending_documents();
+
+    log::info!(
+        "App data copied to {}; restarting to use it",
+        target.display()
+    );
+    app.request_restart();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_prepare_target_rejects_conflicts() {
+        let current = temp_dir("data-location-current");
+        let target = temp_dir("data-location-target");
+
+        assert!(prepare_target(Path::new("relative/dir"), &current).is_err());
+        assert!(prepare_target(&current, &current).is_err());
+        assert!(prepare_target(&current.join("nested"), &current).is_err());
+        assert!(prepare_target(&target, &current).is_ok());
+
+        std::fs::write(target.join("preferences.json"), "{}").unwrap();
+        assert!(prepare_target(&target, &current).is_err());
+
+        let _ = std::fs::remove_dir_all(current);
+        let _ = std::fs::remove_dir_all(target);
+    }
+
+    #[test]
+    fn test_copy_recursive() {
+        let src = temp_dir("data-location-copy-src");
+        let dest = temp_dir("data-location-copy-dest").join("recovery");
+        std::fs::create_dir_all(src.join("nested")).unwrap();
+        std::fs::write(src.join("nested/a.json"), "{}").unwrap();
+
+        copy_recursive(&src, &dest).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dest.join("nested/a.json")).unwrap(),
+            "{}"
+        );
+
+        let _ = std::fs::remove_dir_all(src);
+        let _ = std::fs::remove_dir_all(dest.parent().unwrap());
+    }
+}
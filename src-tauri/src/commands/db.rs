@@ -0,0 +1,269 @@
+//! Database commands.
+//!
+//! `db_query` and `db_execute` run arbitrary SQL with JSON parameters, for
+//! prototyping and simple apps. They give the frontend full access to the app
+//! database, so prefer typed commands like `list_notes` for real features and
+//! remove these if you don't need them.
+//!
+//! Both run with an authorizer that denies `ATTACH`, `DETACH` and `PRAGMA`,
+//! so the frontend can't create database files elsewhere on disk, change the
+//! encryption key or rewind the migration version.
+
+use rusqlite::hooks::{AuthAction, AuthContext, Authorization};
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::{params_from_iter, Connection};
+use serde_json::Value;
+use tauri::State;
+
+use crate::db::{notes, Database};
+use crate::types::{validate_string_input, AppError, DbExecuteResult, DbQueryResult, Note};
+
+/// `db_query` fails rather than returning more rows than this.
+const MAX_QUERY_ROWS: usize = 10_000;
+
+const MAX_NOTE_TITLE_LENGTH: usize = 200;
+const MAX_NOTE_BODY_LENGTH: usize = 1_000_000;
+
+/// Converts a JSON parameter to a SQL value. Booleans become 0/1; arrays and
+/// objects aren't allowed.
+fn to_sql_value(value: &Value) -> Result<SqlValue, AppError> {
+    Ok(match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(i64::from(*b)),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        Value::Array(_) | Value::Object(_) => {
+            return Err(AppError::validation(
+                "Query parameters must be strings, numbers, booleans or null",
+            ))
+        }
+    })
+}
+
+/// Converts a SQL value to JSON. Blobs become arrays of bytes.
+fn to_json_value(value: ValueRef) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => Value::from(i),
+        ValueRef::Real(f) => Value::from(f),
+        ValueRef::Text(text) => Value::from(String::from_utf8_lossy(text).into_owned()),
+        ValueRef::Blob(bytes) => Value::from(bytes.to_vec()),
+    }
+}
+
+fn sql_params(params: Option<Vec<Value>>) -> Result<Vec<SqlValue>, AppError> {
+    params
+        .unwrap_or_default()
+        .iter()
+        .map(to_sql_value)
+        .collect()
+}
+
+/// Denies statements that reach beyond the app's tables.
+fn authorize(context: AuthContext<'_>) -> Authorization {
+    match context.action {
+        AuthAction::Attach { .. } | AuthAction::Detach { .. } | AuthAction::Pragma { .. } => {
+            Authorization::Deny
+        }
+        _ => Authorization::Allow,
+    }
+}
+
+/// Runs `f` with [`authorize`] installed on `conn`. The connection is shared
+/// with the rest of the app, so the authorizer is removed afterwards.
+fn restricted<T>(conn: &Connection, f: impl FnOnce(&Connection) -> T) -> T {
+    conn.authorizer(Some(authorize));
+    let result = f(conn);
+    conn.authorizer(None::<fn(AuthContext<'_>) -> Authorization>);
+    result
+}
+
+fn run_query(conn: &Connection, sql: &str, params: &[SqlValue]) -> Result<DbQueryResult, AppError> {
+    let mut stmt = conn
+        .prepare(sql)
+        .map_err(|e| AppError::validation(format!("Invalid query: {e}")))?;
+    if !stmt.readonly() {
+        return Err(AppError::validation(
+            "db_query only runs read-only statements; use db_execute",
+        ));
+    }
+
+    let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+    let mut rows = stmt
+        .query(params_from_iter(params))
+        .map_err(|e| AppError::internal(format!("Query failed: {e}")))?;
+
+    let mut result = Vec::new();
+    while let Some(row) = rows
+        .next()
+        .map_err(|e| AppError::internal(format!("Query failed: {e}")))?
+    {
+        if result.len() == MAX_QUERY_ROWS {
+            return Err(AppError::validation(format!(
+                "Query returned more than {MAX_QUERY_ROWS} rows; add a LIMIT"
+            )));
+        }
+        let values = (0..columns.len())
+            .map(|i| row.get_ref(i).map(to_json_value))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::internal(format!("Failed to read row: {e}")))?;
+        result.push(values);
+    }
+
+    Ok(DbQueryResult {
+        columns,
+        rows: result,
+    })
+}
+
+/// Runs a read-only SQL statement (e.g. `SELECT`) with positional `?`
+/// parameters and returns the rows. At most 10,000 rows.
+#[tauri::command]
+#[specta::specta]
+pub fn db_query(
+    database: State<'_, Database>,
+    sql: String,
+    params: Option<Vec<Value>>,
+) -> Result<DbQueryResult, AppError> {
+    let params = sql_params(params)?;
+    let conn = database.lock()?;
+    restricted(&conn, |conn| run_query(conn, &sql, &params))
+}
+
+fn run_execute(
+    conn: &Connection,
+    sql: &str,
+    params: &[SqlValue],
+) -> rusqlite::Result<DbExecuteResult> {
+    let rows_affected = conn.execute(sql, params_from_iter(params))?;
+    Ok(DbExecuteResult {
+        rows_affected: u32::try_from(rows_affected).unwrap_or(u32::MAX),
+        last_insert_id: conn.last_insert_rowid() as f64,
+    })
+}
+
+/// Runs a single SQL statement that changes data (`INSERT`, `UPDATE`,
+/// `DELETE`, ...) with positional `?` parameters.
+#[tauri::command]
+#[specta::specta]
+pub fn db_execute(
+    database: State<'_, Database>,
+    sql: String,
+    params: Option<Vec<Value>>,
+) -> Result<DbExecuteResult, AppError> {
+    let params = sql_params(params)?;
+    database.with_connection(|conn| restricted(conn, |conn| run_execute(conn, &sql, &params)))
+}
+
+/// Lists notes, most recently updated first. An example of a typed query.
+#[tauri::command]
+#[specta::specta]
+pub fn list_notes(database: State<'_, Database>) -> Result<Vec<Note>, AppError> {
+    database.with_connection(|conn| notes::list(conn))
+}
+
+/// Creates a note. An example of a typed write with validation.
+#[tauri::command]
+#[specta::specta]
+pub fn create_note(
+    database: State<'_, Database>,
+    title: String,
+    body: String,
+) -> Result<Note, AppError> {
+    validate_string_input(&title, MAX_NOTE_TITLE_LENGTH, "Title").map_err(AppError::validation)?;
+    if body.len() > MAX_NOTE_BODY_LENGTH {
+        return Err(AppError::validation(format!(
+            "Body too large (max {MAX_NOTE_BODY_LENGTH} bytes)"
+        )));
+    }
+
+    let note = database.with_connection(|conn| notes::create(conn, &title, &body))?;
+    log::info!("Created note {}", note.id);
+    Ok(note)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_query_round_trip() {
+        let database = Database::open_in_memory().unwrap();
+        database
+            .with_connection(|conn| notes::create(conn, "Hello", "World"))
+            .unwrap();
+
+        let result = run_query(
+            &database.lock().unwrap(),
+            "SELECT title, body, 1.5 AS n, NULL AS nothing FROM notes WHERE title = ?",
+            &[to_sql_value(&json!("Hello")).unwrap()],
+        )
+        .unwrap();
+        assert_eq!(result.columns, ["title", "body", "n", "nothing"]);
+        assert_eq!(
+            result.rows,
+            [vec![
+                json!("Hello"),
+                json!("World"),
+                json!(1.5),
+                Value::Null
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_query_rejects_writes() {
+        let database = Database::open_in_memory().unwrap();
+        let result = run_query(&database.lock().unwrap(), "DELETE FROM notes", &[]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_attach_and_pragma_denied() {
+        let database = Database::open_in_memory().unwrap();
+        let conn = database.lock().unwrap();
+        let path = std::env::temp_dir().join(format!("db-attach-test-{}.db", std::process::id()));
+        let attach = format!("ATTACH DATABASE '{}' AS other", path.display());
+
+        assert!(restricted(&conn, |conn| run_execute(conn, &attach, &[])).is_err());
+        assert!(!path.exists());
+        assert!(restricted(&conn, |conn| run_query(conn, &attach, &[])).is_err());
+        assert!(restricted(&conn, |conn| run_execute(
+            conn,
+            "PRAGMA user_version = 0",
+            &[]
+        ))
+        .is_err());
+        assert!(restricted(&conn, |conn| run_query(conn, "PRAGMA user_version", &[])).is_err());
+
+        // Ordinary statements still work, and the app's own use is unaffected
+        let inserted = restricted(&conn, |conn| {
+            run_execute(
+                conn,
+                "INSERT INTO notes (title, body, created_at, updated_at) VALUES (?, ?, 0, 0)",
+                &[
+                    SqlValue::Text("Title".into()),
+                    SqlValue::Text("Body".into()),
+                ],
+            )
+        })
+        .unwrap();
+        assert_eq!(inserted.rows_affected, 1);
+        assert!(conn
+            .pragma_query_value(None, "user_version", |row| row.get::<_, i64>(0))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_to_sql_value() {
+        assert_eq!(to_sql_value(&json!(true)).unwrap(), SqlValue::Integer(1));
+        assert_eq!(to_sql_value(&json!(42)).unwrap(), SqlValue::Integer(42));
+        assert_eq!(to_sql_value(&json!(0.5)).unwrap(), SqlValue::Real(0.5));
+        assert!(to_sql_value(&json!([1])).is_err());
+        assert!(to_sql_value(&json!({ "a": 1 })).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+//! Deep links through the app's custom URL scheme (`tauri-app://`).
+//!
+//! The scheme is registered with the OS by the deep-link plugin, configured
+//! under `plugins.deep-link` in `tauri.conf.json`. Incoming URLs are parsed
+//! into a [`DeepLink`] and emitted as `deep-link-opened` to the window that
+//! handles them, which is brought to the front:
+//!
+//! - `tauri-app://documents/<id>` opens a document in the main window
+//! - `tauri-app://new?title=<title>` creates a document in the main window
+//! - `tauri-app://quick-entry?text=<text>` shows the quick pane
+//! - anything else is emitted to the main window as `Other` for the app to route
+//!
+//! Links are sent through `event_queue`, so a link that launches the app (or
+//! creates the quick pane) is delivered once the window's frontend is ready.
+//! When the app is already running, a link launches a second instance on Windows and
+//! Linux; the single-instance plugin forwards it here and exits.
+
+use std::collections::HashMap;
+use tauri::{AppHandle, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use super::event_queue;
+use super::quick_pane::{show_quick_pane_by_label, QUICK_PANE_LABEL};
+use super::startup::is_startup_finished;
+use super::window::{activate_main_window, MAIN_WINDOW_LABEL};
+use crate::types::{DeepLink, DeepLinkOpened};
+
+/// The app's URL scheme. Keep in sync with `plugins.deep-link` in `tauri.conf.json`.
+pub const DEEP_LINK_SCHEME: &str = "tauri-app";
+
+/// Parses a URL in the app's scheme. The route is the host followed by the
+/// path, so `tauri-app://documents/42` and `tauri-app:documents/42` are the same.
+fn parse_deep_link(url: &Url) -> Option<DeepLink> {
+    if url.scheme() != DEEP_LINK_SCHEME {
+        return None;
+    }
+
+    let segments: Vec<&str> = url
+        .host_str()
+        .into_iter()
+        .chain(url.path().split('/'))
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+
+    let link = match segments.as_slice() {
+        ["documents", id] => match id.parse() {
+            Ok(id) => DeepLink::OpenDocument { id },
+            Err(_) => return None,
+        },
+        ["new"] => DeepLink::NewDocument {
+            title: query.get("title").cloned(),
+        },
+        ["quick-entry"] => DeepLink::QuickEntry {
+            text: query.get("text").cloned(),
+        },
+        _ => DeepLink::Other {
+            path: segments.join("/"),
+            query,
+        },
+    };
+    Some(link)
+}
+
+/// Brings the link's window to the front and emits the link to it. The main
+/// window is left hidden until `finish_startup` reveals it.
+fn emit_deep_link(app: &AppHandle, event: DeepLinkOpened) {
+    log::info!("Deep link opened: {}", event.url);
+    let target = match &event.link {
+        DeepLink::QuickEntry { .. } => {
+            if let Err(e) = show_quick_pane_by_label(app, QUICK_PANE_LABEL) {
+                log::error!("Failed to show quick pane for deep link: {e}");
+            }
+            QUICK_PANE_LABEL
+        }
+        _ => {
+            if is_startup_finished() {
+                if let Err(e) = activate_main_window(app) {
+                    log::error!("Failed to activate main window for deep link: {e}");
+                }
+            }
+            MAIN_WINDOW_LABEL
+        }
+    };
+    event_queue::emit_to(app, target, event);
+}
+
+/// Handles URLs received by the deep-link plugin. Links the app can't parse
+/// are logged and dropped.
+fn handle_urls(app: &AppHandle, urls: Vec<Url>) {
+    for url in urls {
+        let Some(link) = parse_deep_link(&url) else {
+            log::warn!("Ignoring unrecognised deep link: {url}");
+            continue;
+        };
+        let event = DeepLinkOpened {
+            url: url.to_string(),
+            link,
+        };
+        emit_deep_link(app, event);
+    }
+}
+
+/// Starts listening for deep links and handles the one the app was launched
+/// with, if any. Called during app setup.
+pub fn init(app: &AppHandle) {
+    let deep_link = app.deep_link();
+
+    // Installed builds register the scheme at install time; development builds
+    // on Windows and Linux register it at runtime
+    #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+    if let Err(e) = deep_link.register_all() {
+        log::warn!("Failed to register deep link scheme: {e}");
+    }
+
+    let handle = app.clone();
+    deep_link.on_open_url(move |event| handle_urls(&handle, event.urls()));
+
+    // macOS delivers the launch URL through `on_open_url`; elsewhere it's in
+    // the launch arguments
+    #[cfg(not(target_os = "macos"))]
+    match deep_link.get_current() {
+        Ok(Some(urls)) => handle_urls(app, urls),
+        Ok(None) => {}
+        Err(e) => log::warn!("Failed to read launch deep link: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(url: &str) -> Option<DeepLink> {
+        parse_deep_link(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn test_parse_deep_link() {
+        assert_eq!(
+            parse("tauri-app://documents/42"),
+            Some(DeepLink::OpenDocument { id: 42 })
+        );
+        assert_eq!(
+            parse("tauri-app:documents/42/"),
+            Some(DeepLink::OpenDocument { id: 42 })
+        );
+        assert_eq!(parse("tauri-app://documents/abc"), None);
+        assert_eq!(
+            parse("tauri-app://new?title=Hello%20world"),
+            Some(DeepLink::NewDocument {
+                title: Some("Hello world".to_string())
+            })
+        );
+        assert_eq!(
+            parse("tauri-app://quick-entry"),
+            Some(DeepLink::QuickEntry { text: None })
+        );
+        assert_eq!(
+            parse("tauri-app://settings/advanced?tab=data"),
+            Some(DeepLink::Other {
+                path: "settings/advanced".to_string(),
+                query: HashMap::from([("tab".to_string(), "data".to_string())]),
+            })
+        );
+        assert_eq!(parse("https://example.com/documents/42"), None);
+    }
+}
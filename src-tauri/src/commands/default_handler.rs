@@ -0,0 +1,363 @@
+//! Default app checks for URL schemes and file types.
+//!
+//! Apps that handle deep links or associated files can check whether they're
+//! the system default and offer to become it, like browsers and mail clients
+//! do. Targets are written the way Windows writes them: a scheme without
+//! `://` (`"tauri-app"`, `"mailto"`) or an extension with its leading dot
+//! (`".md"`). Extensions must be among the app's file associations.
+//!
+//! On macOS the app must be bundled for Launch Services to know it, so it
+//! can't become the default in `tauri dev`.
+
+use tauri::AppHandle;
+
+use super::file_associations::associated_mime_type;
+use crate::types::AppError;
+
+/// A URL scheme or associated file extension, lowercased.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(mobile, allow(dead_code))]
+enum HandlerTarget {
+    Scheme(String),
+    /// Without the leading dot.
+    Extension(String),
+}
+
+/// Parses `".md"` as an extension and anything else as a URL scheme.
+fn parse_target(target: &str) -> Result<HandlerTarget, AppError> {
+    let target = target.trim().to_ascii_lowercase();
+    if let Some(extension) = target.strip_prefix('.') {
+        if associated_mime_type(extension).is_none() {
+            return Err(AppError::internal(format!(
+                "The app isn't associated with .{extension} files"
+            )));
+        }
+        return Ok(HandlerTarget::Extension(extension.to_string()));
+    }
+
+    // RFC 3986: a letter followed by letters, digits, "+", "-" or "."
+    let mut chars = target.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    if !valid {
+        return Err(AppError::validation(format!(
+            "Invalid URL scheme or file extension: {target}"
+        )));
+    }
+    Ok(HandlerTarget::Scheme(target))
+}
+
+/// Returns whether the app is the system default for a URL scheme
+/// (`"mailto"`) or an associated file extension (`".md"`).
+#[tauri::command]
+#[specta::specta]
+pub async fn is_default_handler_for(
+    app: AppHandle,
+    scheme_or_extension: String,
+) -> Result<bool, AppError> {
+    let target = parse_target(&scheme_or_extension)?;
+    platform::is_default(&app, &target)
+}
+
+/// Makes the app the system default for a URL scheme or associated file
+/// extension, and returns whether it now is. macOS may ask the user to
+/// confirm. Windows only lets users change defaults for file types and
+/// well-known schemes, so there it opens Default apps in Settings and returns
+/// `false`; check again when the window regains focus.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_as_default_handler(
+    app: AppHandle,
+    scheme_or_extension: String,
+) -> Result<bool, AppError> {
+    let target = parse_target(&scheme_or_extension)?;
+    log::info!("Setting the app as the default handler for {scheme_or_extension}");
+    platform::set_default(&app, &target)
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use objc2_core_foundation::{CFRetained, CFString};
+    use std::ptr::NonNull;
+    use tauri::AppHandle;
+
+    use super::HandlerTarget;
+    use crate::types::AppError;
+
+    /// `kLSRolesAll`
+    const ROLES_ALL: u32 = 0xFFFF_FFFF;
+
+    #[link(name = "CoreServices", kind = "framework")]
+    extern "C" {
+        fn LSCopyDefaultHandlerForURLScheme(scheme: &CFString) -> Option<NonNull<CFString>>;
+        fn LSSetDefaultHandlerForURLScheme(scheme: &CFString, bundle_id: &CFString) -> i32;
+        fn LSCopyDefaultRoleHandlerForContentType(
+            content_type: &CFString,
+            role: u32,
+        ) -> Option<NonNull<CFString>>;
+        fn LSSetDefaultRoleHandlerForContentType(
+            content_type: &CFString,
+            role: u32,
+            bundle_id: &CFString,
+        ) -> i32;
+        fn UTTypeCreatePreferredIdentifierForTag(
+            tag_class: &CFString,
+            tag: &CFString,
+            conforming_to: Option<&CFString>,
+        ) -> Option<NonNull<CFString>>;
+    }
+
+    /// The uniform type identifier for an extension, e.g.
+    /// `net.daringfireball.markdown` for `md`.
+    fn content_type(extension: &str) -> Result<CFRetained<CFString>, AppError> {
+        let tag_class = CFString::from_static_str("public.filename-extension");
+        // SAFETY: both strings are valid CFStrings. The result follows the
+        // create rule, so it's owned.
+        unsafe {
+            UTTypeCreatePreferredIdentifierForTag(&tag_class, &CFString::from_str(extension), None)
+                .map(|ptr| CFRetained::from_raw(ptr))
+        }
+        .ok_or_else(|| AppError::internal(format!("No file type for .{extension}")))
+    }
+
+    pub fn is_default(app: &AppHandle, target: &HandlerTarget) -> Result<bool, AppError> {
+        // SAFETY: the arguments are valid CFStrings. The results follow the
+        // copy rule, so they're owned.
+        let handler = match target {
+            HandlerTarget::Scheme(scheme) => unsafe {
+                LSCopyDefaultHandlerForURLScheme(&CFString::from_str(scheme))
+            },
+            HandlerTarget::Extension(extension) => unsafe {
+                LSCopyDefaultRoleHandlerForContentType(&content_type(extension)?, ROLES_ALL)
+            },
+        };
+        // SAFETY: see above
+        let handler = handler.map(|ptr| unsafe { CFRetained::from_raw(ptr) }.to_string());
+        Ok(handler
+            .is_some_and(|bundle_id| bundle_id.eq_ignore_ascii_case(&app.config().identifier)))
+    }
+
+    pub fn set_default(app: &AppHandle, target: &HandlerTarget) -> Result<bool, AppError> {
+        let bundle_id = CFString::from_str(&app.config().identifier);
+        // SAFETY: the arguments are valid CFStrings.
+        let status = match target {
+            HandlerTarget::Scheme(scheme) => unsafe {
+                LSSetDefaultHandlerForURLScheme(&CFString::from_str(scheme), &bundle_id)
+            },
+            HandlerTarget::Extension(extension) => unsafe {
+                LSSetDefaultRoleHandlerForContentType(
+                    &content_type(extension)?,
+                    ROLES_ALL,
+                    &bundle_id,
+                )
+            },
+        };
+        if status != 0 {
+            return Err(AppError::internal(format!(
+                "Failed to set default handler (OSStatus {status})"
+            )));
+        }
+        is_default(app, target)
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::path::PathBuf;
+    use tauri::AppHandle;
+    use tauri_plugin_deep_link::DeepLinkExt;
+    use tauri_plugin_opener::OpenerExt;
+    use windows::core::{w, HSTRING, PWSTR};
+    use windows::Win32::UI::Shell::{
+        AssocQueryStringW, ASSOCF, ASSOCF_IS_PROTOCOL, ASSOCF_NONE, ASSOCSTR_EXECUTABLE,
+    };
+
+    use super::HandlerTarget;
+    use crate::types::AppError;
+
+    /// The executable that opens a scheme or extension, honouring the user's
+    /// choice in Settings.
+    fn default_executable(target: &HandlerTarget) -> Option<PathBuf> {
+        let (flags, assoc): (ASSOCF, HSTRING) = match target {
+            HandlerTarget::Scheme(scheme) => (ASSOCF_IS_PROTOCOL, scheme.as_str().into()),
+            HandlerTarget::Extension(extension) => (ASSOCF_NONE, format!(".{extension}").into()),
+        };
+
+        let mut len = 0u32;
+        // SAFETY: without an output buffer, this only writes the length needed
+        // (including the terminating null) to `len`.
+        let _ = unsafe {
+            AssocQueryStringW(
+                flags,
+                ASSOCSTR_EXECUTABLE,
+                &assoc,
+                w!("open"),
+                None,
+                &mut len,
+            )
+        };
+        if len == 0 {
+            return None;
+        }
+        let mut buffer = vec![0u16; len as usize];
+        // SAFETY: the buffer holds `len` characters.
+        unsafe {
+            AssocQueryStringW(
+                flags,
+                ASSOCSTR_EXECUTABLE,
+                &assoc,
+                w!("open"),
+                Some(PWSTR(buffer.as_mut_ptr())),
+                &mut len,
+            )
+        }
+        .ok()
+        .ok()?;
+        let end = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        Some(PathBuf::from(String::from_utf16_lossy(&buffer[..end])))
+    }
+
+    pub fn is_default(_app: &AppHandle, target: &HandlerTarget) -> Result<bool, AppError> {
+        let current = std::env::current_exe()
+            .map_err(|e| AppError::internal(format!("Failed to find the app executable: {e}")))?;
+        Ok(default_executable(target).is_some_and(|exe| {
+            exe.to_string_lossy()
+                .eq_ignore_ascii_case(&current.to_string_lossy())
+        }))
+    }
+
+    pub fn set_default(app: &AppHandle, target: &HandlerTarget) -> Result<bool, AppError> {
+        // Registering a scheme makes the app its default unless the user has
+        // already picked another app for it
+        if let HandlerTarget::Scheme(scheme) = target {
+            app.deep_link()
+                .register(scheme)
+                .map_err(|e| AppError::internal(format!("Failed to register {scheme}: {e}")))?;
+            if is_default(app, target)? {
+                return Ok(true);
+            }
+        }
+
+        app.opener()
+            .open_url("ms-settings:defaultapps", None::<&str>)
+            .map_err(|e| {
+                AppError::internal(format!("Failed to open Default apps settings: {e}"))
+            })?;
+        Ok(false)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::process::Command;
+    use tauri::AppHandle;
+    use tauri_plugin_deep_link::DeepLinkExt;
+
+    use super::HandlerTarget;
+    use crate::commands::file_associations::associated_mime_type;
+    use crate::types::AppError;
+
+    /// The desktop entry the .deb and .rpm packages install, named after the
+    /// executable. AppImages don't install one.
+    fn desktop_entry() -> Result<String, AppError> {
+        let exe = std::env::current_exe()
+            .map_err(|e| AppError::internal(format!("Failed to find the app executable: {e}")))?;
+        let name = exe
+            .file_name()
+            .ok_or_else(|| AppError::internal("The app executable has no file name"))?
+            .to_string_lossy();
+        Ok(format!("{name}.desktop"))
+    }
+
+    fn mime_type(extension: &str) -> Result<&'static str, AppError> {
+        associated_mime_type(extension).ok_or_else(|| {
+            AppError::internal(format!("The app isn't associated with .{extension} files"))
+        })
+    }
+
+    fn xdg_mime(args: &[&str]) -> Result<String, AppError> {
+        let output = Command::new("xdg-mime")
+            .args(args)
+            .output()
+            .map_err(|e| AppError::internal(format!("Failed to run xdg-mime: {e}")))?;
+        if !output.status.success() {
+            return Err(AppError::internal(format!(
+                "xdg-mime failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    pub fn is_default(app: &AppHandle, target: &HandlerTarget) -> Result<bool, AppError> {
+        match target {
+            // The deep link plugin registers its own `<exe>-handler.desktop`
+            HandlerTarget::Scheme(scheme) => app.deep_link().is_registered(scheme).map_err(|e| {
+                AppError::internal(format!("Failed to check the {scheme} handler: {e}"))
+            }),
+            HandlerTarget::Extension(extension) => {
+                Ok(xdg_mime(&["query", "default", mime_type(extension)?])? == desktop_entry()?)
+            }
+        }
+    }
+
+    pub fn set_default(app: &AppHandle, target: &HandlerTarget) -> Result<bool, AppError> {
+        match target {
+            HandlerTarget::Scheme(scheme) => app
+                .deep_link()
+                .register(scheme)
+                .map_err(|e| AppError::internal(format!("Failed to register {scheme}: {e}")))?,
+            HandlerTarget::Extension(extension) => {
+                xdg_mime(&["default", &desktop_entry()?, mime_type(extension)?])?;
+            }
+        }
+        is_default(app, target)
+    }
+}
+
+#[cfg(mobile)]
+mod platform {
+    use tauri::AppHandle;
+
+    use super::HandlerTarget;
+    use crate::types::AppError;
+
+    pub fn is_default(app: &AppHandle, target: &HandlerTarget) -> Result<bool, AppError> {
+        let _ = (app, target);
+        Err(AppError::internal(
+            "Default handlers aren't supported on this platform",
+        ))
+    }
+
+    pub fn set_default(app: &AppHandle, target: &HandlerTarget) -> Result<bool, AppError> {
+        let _ = (app, target);
+        Err(AppError::internal(
+            "Default handlers aren't supported on this platform",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(
+            parse_target("mailto"),
+            Ok(HandlerTarget::Scheme("mailto".to_string()))
+        );
+        assert_eq!(
+            parse_target(" Tauri-App "),
+            Ok(HandlerTarget::Scheme("tauri-app".to_string()))
+        );
+        assert_eq!(
+            parse_target(".MD"),
+            Ok(HandlerTarget::Extension("md".to_string()))
+        );
+        assert!(parse_target(".pdf").is_err());
+        assert!(parse_target("").is_err());
+        assert!(parse_target("1password").is_err());
+        assert!(parse_target("mailto://").is_err());
+    }
+}
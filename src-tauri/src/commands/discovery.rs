@@ -0,0 +1,384 @@
+//! Local network discovery with mDNS/DNS-SD.
+//!
+//! `start_advertising` announces a service on the LAN (e.g. a sync server the
+//! app runs on a local port), and `browse_services` finds other devices
+//! advertising the same type, as groundwork for device-to-device sync or
+//! pairing with a companion app. Peers are reported as `peer-found` and
+//! `peer-lost` events; the app's own services are left out.
+//!
+//! One `mdns-sd` daemon, started on first use, does the work on its own
+//! thread. Advertised services are withdrawn at exit, so peers see them go
+//! straight away rather than when their records expire.
+
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_specta::Event;
+
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+
+use crate::types::{AppError, LocalService, Peer, PeerFound, PeerLost};
+
+const MAX_INSTANCE_NAME_BYTES: usize = 63;
+/// TXT record strings (`key=value`) are limited to 255 bytes.
+const MAX_PROPERTY_BYTES: usize = 255;
+/// How long to wait at exit for each service's goodbye to go out.
+const UNREGISTER_TIMEOUT: Duration = Duration::from_millis(500);
+
+static DAEMON: Mutex<Option<ServiceDaemon>> = Mutex::new(None);
+
+/// Full names of the services this app advertises.
+static ADVERTISED: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Peers found so far by full name, for each service type being browsed (by
+/// domain, e.g. "_myapp._tcp.local.").
+static BROWSING: LazyLock<Mutex<HashMap<String, HashMap<String, Peer>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn daemon() -> Result<ServiceDaemon, AppError> {
+    let mut daemon = DAEMON
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock mDNS daemon: {e}")))?;
+    if let Some(daemon) = daemon.as_ref() {
+        return Ok(daemon.clone());
+    }
+    let started = ServiceDaemon::new()
+        .map_err(|e| AppError::internal(format!("Failed to start mDNS: {e}")))?;
+    *daemon = Some(started.clone());
+    Ok(started)
+}
+
+/// Turns a service type like "_myapp._tcp" into its domain,
+/// "_myapp._tcp.local.". The name must be 1-15 letters, digits or hyphens.
+fn service_domain(service_type: &str) -> Result<String, AppError> {
+    let trimmed = service_type
+        .trim_end_matches('.')
+        .trim_end_matches(".local");
+    let (name, protocol) = trimmed.split_once('.').unwrap_or((trimmed, ""));
+    let name = name.strip_prefix('_').unwrap_or_default();
+    let valid_name = (1..=15).contains(&name.len())
+        && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        && !name.starts_with('-')
+        && !name.ends_with('-');
+    if !valid_name || !matches!(protocol, "_tcp" | "_udp") {
+        return Err(AppError::validation(format!(
+            "Invalid service type {service_type}: expected e.g. \"_myapp._tcp\""
+        )));
+    }
+    Ok(format!("{trimmed}.local."))
+}
+
+fn validate_service(service: &LocalService) -> Result<String, AppError> {
+    let domain = service_domain(&service.service_type)?;
+    if service.name.is_empty() {
+        return Err(AppError::validation("Service name cannot be empty"));
+    }
+    if service.name.len() > MAX_INSTANCE_NAME_BYTES {
+        return Err(AppError::validation(format!(
+            "Service name too long (max {MAX_INSTANCE_NAME_BYTES} bytes)"
+        )));
+    }
+    for (key, value) in &service.properties {
+        if key.is_empty() || !key.bytes().all(|b| (0x20..0x7f).contains(&b) && b != b'=') {
+            return Err(AppError::validation(format!(
+                "Invalid property key {key:?}: must be printable ASCII without '='"
+            )));
+        }
+        if key.len() + 1 + value.len() > MAX_PROPERTY_BYTES {
+            return Err(AppError::validation(format!(
+                "Property {key} too long (max {MAX_PROPERTY_BYTES} bytes with its key)"
+            )));
+        }
+    }
+    Ok(domain)
+}
+
+/// A host name for the service's address records. Not the machine's own,
+/// which the OS already answers for, and with a random suffix so instances
+/// with the same name on different devices don't clash.
+fn host_name(name: &str) -> String {
+    let label: String = name
+        .chars()
+        .filter_map(|c| match c {
+            'a'..='z' | '0'..='9' => Some(c),
+            'A'..='Z' => Some(c.to_ascii_lowercase()),
+            ' ' | '-' | '_' => Some('-'),
+            _ => None,
+        })
+        .take(40)
+        .collect();
+    let label = label.trim_matches('-');
+    let mut suffix = [0u8; 3];
+    OsRng.fill_bytes(&mut suffix);
+    format!(
+        "{}-{}.local.",
+        if label.is_empty() { "peer" } else { label },
+        hex::encode(suffix)
+    )
+}
+
+fn to_peer(domain: &str, info: &ServiceInfo) -> Peer {
+    let full_name = info.get_fullname().to_string();
+    let name = full_name
+        .strip_suffix(domain)
+        .and_then(|name| name.strip_suffix('.'))
+        .unwrap_or(&full_name)
+        .to_string();
+    let mut addresses: Vec<String> = info
+        .get_addresses()
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    addresses.sort();
+    Peer {
+        service_type: domain.trim_end_matches(".local.").to_string(),
+        name,
+        full_name,
+        host: info.get_hostname().to_string(),
+        port: info.get_port(),
+        addresses,
+        properties: info
+            .get_properties()
+            .iter()
+            .map(|property| (property.key().to_string(), property.val_str().to_string()))
+            .collect(),
+    }
+}
+
+fn is_own_service(full_name: &str) -> bool {
+    ADVERTISED
+        .lock()
+        .is_ok_and(|advertised| advertised.contains(full_name))
+}
+
+/// Handles an event from a browse. Returns false once browsing has stopped.
+fn handle_event(app: &AppHandle, domain: &str, event: ServiceEvent) -> bool {
+    match event {
+        ServiceEvent::ServiceResolved(info) => {
+            if is_own_service(info.get_fullname()) {
+                return true;
+            }
+            let peer = to_peer(domain, &info);
+            {
+                let Ok(mut browsing) = BROWSING.lock() else {
+                    return true;
+                };
+                let Some(peers) = browsing.get_mut(domain) else {
+                    return false;
+                };
+                peers.insert(peer.full_name.clone(), peer.clone());
+            }
+            log::debug!(
+                "Found peer {} at {}:{}",
+                peer.full_name,
+                peer.host,
+                peer.port
+            );
+            if let Err(e) = (PeerFound { peer }).emit(app) {
+                log::warn!("Failed to emit peer-found: {e}");
+            }
+        }
+        ServiceEvent::ServiceRemoved(_, full_name) => {
+            let removed = BROWSING
+                .lock()
+                .ok()
+                .and_then(|mut browsing| browsing.get_mut(domain)?.remove(&full_name));
+            if let Some(peer) = removed {
+                log::debug!("Lost peer {full_name}");
+                let event = PeerLost {
+                    service_type: peer.service_type,
+                    full_name,
+                };
+                if let Err(e) = event.emit(app) {
+                    log::warn!("Failed to emit peer-lost: {e}");
+                }
+            }
+        }
+        ServiceEvent::SearchStopped(_) => return false,
+        _ => {}
+    }
+    true
+}
+
+/// Withdraws advertised services and stops the mDNS daemon. Called at exit.
+pub fn shutdown() {
+    let Some(daemon) = DAEMON.lock().ok().and_then(|mut daemon| daemon.take()) else {
+        return;
+    };
+    let advertised = ADVERTISED
+        .lock()
+        .map(|mut advertised| std::mem::take(&mut *advertised))
+        .unwrap_or_default();
+    for full_name in advertised {
+        match daemon.unregister(&full_name) {
+            Ok(status) => {
+                let _ = status.recv_timeout(UNREGISTER_TIMEOUT);
+            }
+            Err(e) => log::warn!("Failed to withdraw {full_name}: {e}"),
+        }
+    }
+    if let Err(e) = daemon.shutdown() {
+        log::warn!("Failed to stop mDNS daemon: {e}");
+    }
+}
+
+/// Advertises a service on the local network until `stop_advertising` or
+/// exit. Returns its full name, which peers see as `Peer.full_name`.
+#[tauri::command]
+#[specta::specta]
+pub fn start_advertising(service: LocalService) -> Result<String, AppError> {
+    let domain = validate_service(&service)?;
+    let info = ServiceInfo::new(
+        &domain,
+        &service.name,
+        &host_name(&service.name),
+        "",
+        service.port,
+        service.properties,
+    )
+    .map_err(|e| AppError::validation(format!("Invalid service: {e}")))?
+    // Announce every interface's addresses, following changes
+    .enable_addr_auto();
+    let full_name = info.get_fullname().to_string();
+
+    let daemon = daemon()?;
+    ADVERTISED
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock services: {e}")))?
+        .insert(full_name.clone());
+    if let Err(e) = daemon.register(info) {
+        if let Ok(mut advertised) = ADVERTISED.lock() {
+            advertised.remove(&full_name);
+        }
+        return Err(AppError::internal(format!(
+            "Failed to advertise {full_name}: {e}"
+        )));
+    }
+    log::info!("Advertising {full_name} on port {}", service.port);
+    Ok(full_name)
+}
+
+/// Stops advertising a service started with `start_advertising`. Returns
+/// whether it was being advertised.
+#[tauri::command]
+#[specta::specta]
+pub fn stop_advertising(full_name: String) -> Result<bool, AppError> {
+    let removed = ADVERTISED
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock services: {e}")))?
+        .remove(&full_name);
+    if !removed {
+        return Ok(false);
+    }
+    daemon()?
+        .unregister(&full_name)
+        .map_err(|e| AppError::internal(format!("Failed to withdraw {full_name}: {e}")))?;
+    log::info!("Stopped advertising {full_name}");
+    Ok(true)
+}
+
+/// Looks for peers advertising `service_type` (e.g. "_myapp._tcp") on the
+/// local network, until `stop_browsing`. Returns the peers already found if
+/// it's being browsed; after that, changes arrive as `peer-found` and
+/// `peer-lost` events.
+#[tauri::command]
+#[specta::specta]
+pub fn browse_services(app: AppHandle, service_type: String) -> Result<Vec<Peer>, AppError> {
+    let domain = service_domain(&service_type)?;
+    let mut browsing = BROWSING
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock browsing: {e}")))?;
+    if let Some(peers) = browsing.get(&domain) {
+        return Ok(peers.values().cloned().collect());
+    }
+
+    let events = daemon()?
+        .browse(&domain)
+        .map_err(|e| AppError::internal(format!("Failed to browse {service_type}: {e}")))?;
+    browsing.insert(domain.clone(), HashMap::new());
+    log::info!("Browsing for {domain}");
+
+    std::thread::spawn(move || {
+        for event in events.iter() {
+            if !handle_event(&app, &domain, event) {
+                break;
+            }
+        }
+    });
+    Ok(Vec::new())
+}
+
+/// Stops looking for peers advertising `service_type`. Returns whether it was
+/// being browsed.
+#[tauri::command]
+#[specta::specta]
+pub fn stop_browsing(service_type: String) -> Result<bool, AppError> {
+    let domain = service_domain(&service_type)?;
+    let removed = BROWSING
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock browsing: {e}")))?
+        .remove(&domain)
+        .is_some();
+    if removed {
+        daemon()?.stop_browse(&domain).map_err(|e| {
+            AppError::internal(format!("Failed to stop browsing {service_type}: {e}"))
+        })?;
+        log::info!("Stopped browsing for {domain}");
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_domain() {
+        assert_eq!(service_domain("_myapp._tcp").unwrap(), "_myapp._tcp.local.");
+        assert_eq!(
+            service_domain("_my-app._udp.local.").unwrap(),
+            "_my-app._udp.local."
+        );
+        assert!(service_domain("myapp._tcp").is_err());
+        assert!(service_domain("_myapp._sctp").is_err());
+        assert!(service_domain("_a-very-long-service._tcp").is_err());
+        assert!(service_domain("_my.app._tcp").is_err());
+    }
+
+    #[test]
+    fn test_validate_service() {
+        let service = LocalService {
+            service_type: "_myapp._tcp".to_string(),
+            name: "Alice's MacBook".to_string(),
+            port: 8080,
+            properties: HashMap::from([("id".to_string(), "abc".to_string())]),
+        };
+        assert!(validate_service(&service).is_ok());
+        assert!(validate_service(&LocalService {
+            properties: HashMap::from([("a=b".to_string(), String::new())]),
+            ..service.clone()
+        })
+        .is_err());
+        assert!(validate_service(&LocalService {
+            properties: HashMap::from([("id".to_string(), "x".repeat(253))]),
+            ..service.clone()
+        })
+        .is_err());
+        assert!(validate_service(&LocalService {
+            name: String::new(),
+            ..service
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_host_name() {
+        let host = host_name("Alice's MacBook");
+        assert!(host.starts_with("alices-macbook-"));
+        assert!(host.ends_with(".local."));
+        assert!(host_name("!!!").starts_with("peer-"));
+    }
+}
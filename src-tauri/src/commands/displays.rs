@@ -0,0 +1,100 @@
+//! Display and cursor information for window placement.
+//!
+//! Everything is in physical pixels in desktop coordinates, the one space
+//! that's consistent across displays with different scale factors. Place
+//! windows with `PhysicalPosition`, or divide by a display's `scale_factor`
+//! for logical pixels on that display.
+
+use tauri::{AppHandle, Monitor};
+
+use crate::types::{AppError, CursorPosition, Display, DisplayRect};
+
+fn display_rect(x: i32, y: i32, width: u32, height: u32) -> DisplayRect {
+    DisplayRect {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+/// Whether a point falls within a rectangle. The right and bottom edges
+/// belong to the next display over.
+fn rect_contains(rect: &DisplayRect, x: f64, y: f64) -> bool {
+    let (left, top) = (f64::from(rect.x), f64::from(rect.y));
+    x >= left && x < left + f64::from(rect.width) && y >= top && y < top + f64::from(rect.height)
+}
+
+fn bounds(monitor: &Monitor) -> DisplayRect {
+    let (position, size) = (monitor.position(), monitor.size());
+    display_rect(position.x, position.y, size.width, size.height)
+}
+
+/// Returns the connected displays, primary first.
+#[tauri::command]
+#[specta::specta]
+pub fn get_displays(app: AppHandle) -> Result<Vec<Display>, AppError> {
+    let monitors = app
+        .available_monitors()
+        .map_err(|e| AppError::internal(format!("Failed to get displays: {e}")))?;
+    let primary = app
+        .primary_monitor()
+        .map_err(|e| AppError::internal(format!("Failed to get primary display: {e}")))?
+        .map(|monitor| bounds(&monitor));
+    let cursor = app
+        .cursor_position()
+        .inspect_err(|e| log::debug!("Failed to get cursor position: {e}"))
+        .ok();
+
+    let mut displays: Vec<Display> = monitors
+        .iter()
+        .map(|monitor| {
+            let bounds = bounds(monitor);
+            let work_area = monitor.work_area();
+            Display {
+                name: monitor.name().cloned(),
+                is_primary: primary.as_ref() == Some(&bounds),
+                contains_cursor: cursor.is_some_and(|c| rect_contains(&bounds, c.x, c.y)),
+                work_area: display_rect(
+                    work_area.position.x,
+                    work_area.position.y,
+                    work_area.size.width,
+                    work_area.size.height,
+                ),
+                bounds,
+                scale_factor: monitor.scale_factor(),
+            }
+        })
+        .collect();
+    displays.sort_by_key(|display| !display.is_primary);
+
+    Ok(displays)
+}
+
+/// Returns where the mouse cursor is.
+#[tauri::command]
+#[specta::specta]
+pub fn get_cursor_position(app: AppHandle) -> Result<CursorPosition, AppError> {
+    let position = app
+        .cursor_position()
+        .map_err(|e| AppError::internal(format!("Failed to get cursor position: {e}")))?;
+    Ok(CursorPosition {
+        x: position.x,
+        y: position.y,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rect_contains() {
+        let rect = display_rect(-1920, 0, 1920, 1080);
+        assert!(rect_contains(&rect, -1920.0, 0.0));
+        assert!(rect_contains(&rect, -0.5, 1079.5));
+        assert!(!rect_contains(&rect, 0.0, 500.0));
+        assert!(!rect_contains(&rect, -100.0, 1080.0));
+        assert!(!rect_contains(&rect, -100.0, -1.0));
+    }
+}
@@ -0,0 +1,218 @@
+//! macOS Dock menu.
+//!
+//! The items shown when right-clicking the app's Dock icon, above the system's
+//! own (Options, Show All Windows, Quit...). Set from `src/lib/menu.ts` with
+//! `set_dock_menu` so labels are translated. Like the tray menu, clicks are
+//! forwarded to the main window as `menu-item-selected` events and dispatched
+//! by the app menu's handlers. Other platforms have no Dock menu, so
+//! `set_dock_menu` does nothing there.
+//!
+//! Tauri doesn't expose the Dock menu, so this answers AppKit's
+//! `applicationDockMenu:` delegate call with a plain `NSMenu`.
+
+use tauri::AppHandle;
+
+use crate::types::{AppError, MenuEntrySpec};
+
+/// Replaces the Dock menu. Items and submenus are supported; roles are skipped
+/// and accelerators ignored, since the Dock menu can't use them.
+#[tauri::command]
+#[specta::specta]
+pub fn set_dock_menu(app: AppHandle, items: Vec<MenuEntrySpec>) -> Result<(), AppError> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::set_dock_menu(&app, &items)
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = (app, items);
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyClass, AnyObject, Imp, Sel};
+    use objc2::{define_class, msg_send, sel, MainThreadOnly};
+    use objc2_app_kit::{
+        NSApplication, NSControlStateValueOff, NSControlStateValueOn, NSMenu, NSMenuItem,
+    };
+    use objc2_foundation::{MainThreadMarker, NSObject, NSString};
+    use std::cell::RefCell;
+    use std::sync::{Mutex, OnceLock};
+    use tauri::AppHandle;
+    use tauri_specta::Event;
+
+    use crate::commands::window::MAIN_WINDOW_LABEL;
+    use crate::types::{AppError, MenuEntrySpec, MenuItemSelected};
+
+    /// App handle used to emit events from AppKit callbacks.
+    static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+    /// Item ids by `NSMenuItem` tag.
+    static ITEM_IDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    thread_local! {
+        /// The current menu and the target its items send clicks to.
+        /// AppKit objects, so only touched on the main thread.
+        static DOCK_MENU: RefCell<Option<(Retained<NSMenu>, Retained<DockMenuTarget>)>> =
+            const { RefCell::new(None) };
+    }
+
+    define_class!(
+        // SAFETY: NSObject has no subclassing requirements and this class
+        // doesn't implement Drop.
+        #[unsafe(super(NSObject))]
+        #[thread_kind = MainThreadOnly]
+        #[name = "TauriTemplateDockMenuTarget"]
+        struct DockMenuTarget;
+
+        impl DockMenuTarget {
+            #[unsafe(method(itemSelected:))]
+            fn item_selected(&self, item: &NSMenuItem) {
+                let id = ITEM_IDS
+                    .lock()
+                    .ok()
+                    .and_then(|ids| ids.get(item.tag() as usize).cloned());
+                let (Some(id), Some(app)) = (id, APP_HANDLE.get()) else {
+                    return;
+                };
+
+                log::debug!("Dock menu item selected: {id}");
+                if let Err(e) = (MenuItemSelected { id }).emit_to(app, MAIN_WINDOW_LABEL) {
+                    log::error!("Failed to emit menu-item-selected: {e}");
+                }
+            }
+        }
+    );
+
+    /// `applicationDockMenu:` implementation added to the app delegate.
+    extern "C-unwind" fn application_dock_menu(
+        _this: &AnyObject,
+        _cmd: Sel,
+        _sender: *mut AnyObject,
+    ) -> *mut NSMenu {
+        DOCK_MENU.with(|menu| match &*menu.borrow() {
+            Some((menu, _)) => Retained::as_ptr(menu).cast_mut(),
+            None => std::ptr::null_mut(),
+        })
+    }
+
+    /// Adds `applicationDockMenu:` to the app delegate's class, once.
+    fn install_delegate_method(mtm: MainThreadMarker) -> Result<(), AppError> {
+        let delegate = NSApplication::sharedApplication(mtm)
+            .delegate()
+            .ok_or_else(|| AppError::not_found("App delegate not found"))?;
+        let delegate: &AnyObject = (*delegate).as_ref();
+        let class = delegate.class();
+
+        if class.instance_method(sel!(applicationDockMenu:)).is_some() {
+            return Ok(());
+        }
+
+        // SAFETY: the function matches the `- (NSMenu *)applicationDockMenu:(NSApplication *)`
+        // signature described by the type encoding, and returns an object kept
+        // alive by DOCK_MENU.
+        let added = unsafe {
+            let imp: Imp = std::mem::transmute(
+                application_dock_menu as extern "C-unwind" fn(_, _, _) -> *mut NSMenu,
+            );
+            objc2::ffi::class_addMethod(
+                (class as *const AnyClass).cast_mut(),
+                sel!(applicationDockMenu:),
+                imp,
+                c"@@:@".as_ptr(),
+            )
+        };
+        if !added.as_bool() {
+            return Err(AppError::internal(
+                "Failed to add Dock menu to app delegate",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Appends entries to an `NSMenu`, recursing into submenus.
+    fn add_entries(
+        mtm: MainThreadMarker,
+        menu: &NSMenu,
+        entries: &[MenuEntrySpec],
+        target: &DockMenuTarget,
+        ids: &mut Vec<String>,
+    ) {
+        for entry in entries {
+            match entry {
+                MenuEntrySpec::Item {
+                    id,
+                    text,
+                    enabled,
+                    checked,
+                    ..
+                } => {
+                    // SAFETY: the action selector is implemented by DockMenuTarget.
+                    let item = unsafe {
+                        NSMenuItem::initWithTitle_action_keyEquivalent(
+                            NSMenuItem::alloc(mtm),
+                            &NSString::from_str(text),
+                            Some(sel!(itemSelected:)),
+                            &NSString::new(),
+                        )
+                    };
+                    // SAFETY: the target is kept alive alongside the menu in DOCK_MENU.
+                    unsafe { item.setTarget(Some(target.as_ref())) };
+                    item.setTag(ids.len() as isize);
+                    item.setEnabled(enabled.unwrap_or(true));
+                    if let Some(checked) = checked {
+                        item.setState(if *checked {
+                            NSControlStateValueOn
+                        } else {
+                            NSControlStateValueOff
+                        });
+                    }
+                    ids.push(id.clone());
+                    menu.addItem(&item);
+                }
+                MenuEntrySpec::Submenu { text, items, .. } => {
+                    let title = NSString::from_str(text);
+                    let submenu = NSMenu::initWithTitle(NSMenu::alloc(mtm), &title);
+                    submenu.setAutoenablesItems(false);
+                    add_entries(mtm, &submenu, items, target, ids);
+
+                    let item = NSMenuItem::new(mtm);
+                    item.setTitle(&title);
+                    item.setSubmenu(Some(&submenu));
+                    menu.addItem(&item);
+                }
+                MenuEntrySpec::Role { role, .. } => {
+                    log::warn!("{role:?} items aren't supported in the Dock menu");
+                }
+                MenuEntrySpec::Separator => menu.addItem(&NSMenuItem::separatorItem(mtm)),
+            }
+        }
+    }
+
+    pub fn set_dock_menu(app: &AppHandle, entries: &[MenuEntrySpec]) -> Result<(), AppError> {
+        let mtm = MainThreadMarker::new()
+            .ok_or_else(|| AppError::internal("Dock menu must be set on the main thread"))?;
+        let _ = APP_HANDLE.set(app.clone());
+        install_delegate_method(mtm)?;
+
+        let menu = NSMenu::new(mtm);
+        menu.setAutoenablesItems(false);
+        // SAFETY: DockMenuTarget has no ivars, so plain `init` fully initializes it.
+        let target: Retained<DockMenuTarget> =
+            unsafe { msg_send![DockMenuTarget::alloc(mtm), init] };
+
+        let mut ids = Vec::new();
+        add_entries(mtm, &menu, entries, &target, &mut ids);
+        log::debug!("Dock menu set with {} item(s)", ids.len());
+
+        *ITEM_IDS
+            .lock()
+            .map_err(|e| AppError::internal(format!("Failed to lock Dock menu items: {e}")))? = ids;
+        DOCK_MENU.with(|dock_menu| *dock_menu.borrow_mut() = Some((menu, target)));
+        Ok(())
+    }
+}
@@ -0,0 +1,309 @@
+//! Document commands with autosave and revision history.
+//!
+//! The editor calls `save_document` as the user types. Each call immediately
+//! writes the buffer to the recovery store (`document-<id>`), so a crash loses
+//! nothing, and the save is committed to the database once the document has
+//! been idle for `AUTOSAVE_DELAY`. Each commit records a revision (see
+//! `db::documents`), updates the search index and clears the recovery buffer.
+//!
+//! Pending saves are flushed on exit and by `flush_documents` (e.g. for an
+//! explicit Save). If a buffer is still in the recovery store on the next
+//! launch, `load_document` returns it as `recovered` so the user can restore it.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, State};
+
+use crate::db::{documents, search as search_index, Database};
+use crate::recovery_store::SharedRecoveryStore;
+use crate::types::{
+    validate_string_input, AppError, Document, DocumentRevision, DocumentSummary,
+    RecoveredDocument, RevisionSummary, MAX_RECOVERY_DATA_BYTES,
+};
+
+/// How long a document must go without changes before it's committed.
+const AUTOSAVE_DELAY: Duration = Duration::from_secs(2);
+const AUTOSAVE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+const MAX_TITLE_LENGTH: usize = 200;
+/// Half the recovery limit, leaving room for JSON escaping.
+const MAX_CONTENT_SIZE: usize = MAX_RECOVERY_DATA_BYTES as usize / 2;
+
+struct PendingSave {
+    title: String,
+    content: String,
+    changed_at: Instant,
+}
+
+/// Saves waiting for `AUTOSAVE_DELAY`, by document id.
+static PENDING: LazyLock<Mutex<HashMap<u32, PendingSave>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Held while taking and committing pending saves, so an older save can't
+/// reach the database after a newer one.
+static COMMIT_LOCK: Mutex<()> = Mutex::new(());
+
+fn recovery_key(id: u32) -> String {
+    format!("document-{id}")
+}
+
+fn search_id(id: u32) -> String {
+    format!("document:{id}")
+}
+
+fn now_millis() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as f64)
+        .unwrap_or(0.0)
+}
+
+/// Writes a save to the database, indexes it and clears its recovery buffer.
+/// The buffer is kept on failure so the changes can still be recovered.
+fn commit(app: &AppHandle, id: u32, save: &PendingSave) -> Result<(), AppError> {
+    let database = app.state::<Database>();
+    let changed = database.with_connection(|conn| {
+        let changed = documents::save(conn, id, &save.title, &save.content)?;
+        if changed {
+            search_index::index(conn, &search_id(id), &save.title, &save.content)?;
+        }
+        Ok(changed)
+    })?;
+
+    // A newer save has its own buffer, which must survive until it's committed
+    let superseded = PENDING
+        .lock()
+        .map(|pending| pending.contains_key(&id))
+        .unwrap_or(true);
+    if !superseded {
+        if let Err(e) = app.state::<SharedRecoveryStore>().remove(&recovery_key(id)) {
+            log::warn!("Failed to clear recovery buffer for document {id}: {e}");
+        }
+    }
+
+    if changed {
+        log::debug!("Committed document {id}");
+    }
+    Ok(())
+}
+
+/// Commits pending saves: those idle for `AUTOSAVE_DELAY`, or all of them if
+/// `all`. Returns how many were committed.
+fn commit_pending(app: &AppHandle, all: bool) -> u32 {
+    let Ok(_guard) = COMMIT_LOCK.lock() else {
+        return 0;
+    };
+
+    let due: Vec<(u32, PendingSave)> = match PENDING.lock() {
+        Ok(mut pending) => {
+            let ids: Vec<u32> = pending
+                .iter()
+                .filter(|(_, save)| all || save.changed_at.elapsed() >= AUTOSAVE_DELAY)
+                .map(|(id, _)| *id)
+                .collect();
+            ids.into_iter()
+                .filter_map(|id| pending.remove(&id).map(|save| (id, save)))
+                .collect()
+        }
+        Err(_) => return 0,
+    };
+
+    let mut committed = 0;
+    for (id, save) in due {
+        match commit(app, id, &save) {
+            Ok(()) => committed += 1,
+            Err(e) => log::error!("Failed to save document {id}: {e}"),
+        }
+    }
+    committed
+}
+
+/// Commits all pending saves. Called on exit.
+pub fn flush_pending_documents(app: &AppHandle) {
+    let committed = commit_pending(app, true);
+    if committed > 0 {
+        log::info!("Saved {committed} pending documents");
+    }
+}
+
+/// Drops pending saves without committing them. Used before restoring a
+/// backup, so old edits aren't written over the restored data.
+pub fn discard_pending_documents() {
+    if let Ok(mut pending) = PENDING.lock() {
+        if !pending.is_empty() {
+            log::info!("Discarding {} pending document saves", pending.len());
+        }
+        pending.clear();
+    }
+}
+
+/// Commits idle documents every `AUTOSAVE_POLL_INTERVAL`.
+pub fn start_document_autosave(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(AUTOSAVE_POLL_INTERVAL);
+        commit_pending(&app, false);
+    });
+}
+
+/// Reads a document's recovery buffer, if it's newer than the saved document.
+fn recovered_buffer(store: &SharedRecoveryStore, document: &Document) -> Option<RecoveredDocument> {
+    let json = store.load(&recovery_key(document.id)).ok()?;
+    let recovered: RecoveredDocument = match serde_json::from_str(&json) {
+        Ok(recovered) => recovered,
+        Err(e) => {
+            log::warn!(
+                "Ignoring unreadable recovery buffer for document {}: {e}",
+                document.id
+            );
+            return None;
+        }
+    };
+    let differs = recovered.title != document.title || recovered.content != document.content;
+    (recovered.saved_at > document.updated_at && differs).then_some(recovered)
+}
+
+/// Creates an empty document.
+#[tauri::command]
+#[specta::specta]
+pub fn create_document(database: State<'_, Database>, title: String) -> Result<Document, AppError> {
+    validate_string_input(&title, MAX_TITLE_LENGTH, "Title").map_err(AppError::validation)?;
+    let document = database.with_connection(|conn| {
+        let document = documents::create(conn, &title)?;
+        search_index::index(conn, &search_id(document.id), &title, "")?;
+        Ok(document)
+    })?;
+    log::info!("Created document {}", document.id);
+    Ok(document)
+}
+
+/// Saves a document's title and content. The buffer goes to the recovery
+/// store straight away; the database write (and its revision) happens once
+/// the document has been idle for 2 seconds, so call this on every change.
+#[tauri::command]
+#[specta::specta]
+pub fn save_document(
+    store: State<'_, SharedRecoveryStore>,
+    id: u32,
+    title: String,
+    content: String,
+) -> Result<(), AppError> {
+    validate_string_input(&title, MAX_TITLE_LENGTH, "Title").map_err(AppError::validation)?;
+    if content.len() > MAX_CONTENT_SIZE {
+        return Err(AppError::validation(format!(
+            "Document too large (max {MAX_CONTENT_SIZE} bytes)"
+        )));
+    }
+
+    let buffer = RecoveredDocument {
+        title: title.clone(),
+        content: content.clone(),
+        saved_at: now_millis(),
+    };
+    let json = serde_json::to_string(&buffer).map_err(|e| AppError::Serialization {
+        message: format!("Failed to serialize document: {e}"),
+    })?;
+
+    // Queue before writing the buffer, so a commit finishing in between
+    // sees this save and leaves the buffer alone
+    PENDING
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to queue document save: {e}")))?
+        .insert(
+            id,
+            PendingSave {
+                title,
+                content,
+                changed_at: Instant::now(),
+            },
+        );
+
+    store
+        .save(&recovery_key(id), &json)
+        .map_err(|e| AppError::internal(format!("Failed to write recovery buffer: {e}")))
+}
+
+/// Commits all pending saves now, e.g. for an explicit Save or before
+/// closing a window. Returns how many documents were written.
+#[tauri::command]
+#[specta::specta]
+pub fn flush_documents(app: AppHandle) -> u32 {
+    commit_pending(&app, true)
+}
+
+/// Lists documents, most recently updated first.
+#[tauri::command]
+#[specta::specta]
+pub fn list_documents(database: State<'_, Database>) -> Result<Vec<DocumentSummary>, AppError> {
+    database.with_connection(|conn| documents::list(conn))
+}
+
+/// Loads a document, committing any pending save first. `recovered` is set
+/// if the recovery store holds newer changes that were never committed.
+#[tauri::command]
+#[specta::specta]
+pub fn load_document(
+    app: AppHandle,
+    database: State<'_, Database>,
+    store: State<'_, SharedRecoveryStore>,
+    id: u32,
+) -> Result<Document, AppError> {
+    commit_pending(&app, true);
+
+    let mut document = database
+        .with_connection(|conn| documents::load(conn, id))?
+        .ok_or_else(|| AppError::not_found(format!("Document {id} not found")))?;
+    document.recovered = recovered_buffer(&store, &document);
+    if document.recovered.is_some() {
+        log::info!("Found unsaved changes for document {id}");
+    }
+    Ok(document)
+}
+
+/// Deletes a document, its revisions and any unsaved changes.
+#[tauri::command]
+#[specta::specta]
+pub fn delete_document(
+    database: State<'_, Database>,
+    store: State<'_, SharedRecoveryStore>,
+    id: u32,
+) -> Result<bool, AppError> {
+    if let Ok(mut pending) = PENDING.lock() {
+        pending.remove(&id);
+    }
+
+    let existed = database.with_connection(|conn| {
+        search_index::remove(conn, &search_id(id))?;
+        documents::delete(conn, id)
+    })?;
+    store
+        .remove(&recovery_key(id))
+        .map_err(|e| AppError::internal(format!("Failed to remove recovery buffer: {e}")))?;
+
+    log::info!("Deleted document {id}");
+    Ok(existed)
+}
+
+/// Lists a document's revisions, newest first. The newest 50 are kept.
+#[tauri::command]
+#[specta::specta]
+pub fn list_revisions(
+    database: State<'_, Database>,
+    document_id: u32,
+) -> Result<Vec<RevisionSummary>, AppError> {
+    database.with_connection(|conn| documents::list_revisions(conn, document_id))
+}
+
+/// Loads a revision's title and content. To restore it, pass them to
+/// `save_document`, which records the restore as a new revision.
+#[tauri::command]
+#[specta::specta]
+pub fn load_revision(
+    database: State<'_, Database>,
+    revision_id: u32,
+) -> Result<DocumentRevision, AppError> {
+    database
+        .with_connection(|conn| documents::load_revision(conn, revision_id))?
+        .ok_or_else(|| AppError::not_found(format!("Revision {revision_id} not found")))
+}
@@ -0,0 +1,522 @@
+//! Download manager.
+//!
+//! `start_download` fetches a URL to a file in the background, for content
+//! too big for `fetch` to hold in memory. Data goes to `<destination>.part`,
+//! which is renamed once complete. At most `MAX_PARALLEL_DOWNLOADS` run at
+//! once; the rest wait as `Queued`.
+//!
+//! Paused and failed downloads continue where they stopped with an HTTP range
+//! request. `If-Range` makes the server send the whole file instead if it
+//! changed since, and servers without range support do the same. The list is
+//! saved to `downloads.json` in the app data directory, and downloads that
+//! were running when the app quit carry on at the next launch.
+//!
+//! Every change is broadcast to all windows as `download-changed`.
+
+use reqwest::header::{HeaderMap, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tauri_specta::Event;
+
+use super::files::{resolve_scoped_write_path, write_atomic};
+use super::http;
+use crate::types::{AppError, Download, DownloadChanged, DownloadStatus};
+
+/// Downloads running at once. More are queued.
+const MAX_PARALLEL_DOWNLOADS: usize = 3;
+
+/// Minimum time between progress events for a download.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A download and what's needed to resume it.
+#[derive(Serialize, Deserialize)]
+struct DownloadEntry {
+    download: Download,
+    /// Strong `ETag` or `Last-Modified` of the file, sent as `If-Range` when
+    /// resuming
+    validator: Option<String>,
+    /// Aborts the running download task
+    #[serde(skip)]
+    abort: Option<Box<dyn Fn() + Send>>,
+}
+
+static DOWNLOADS: Mutex<Vec<DownloadEntry>> = Mutex::new(Vec::new());
+
+static NEXT_DOWNLOAD_ID: AtomicU32 = AtomicU32::new(1);
+
+fn lock_downloads() -> Result<MutexGuard<'static, Vec<DownloadEntry>>, AppError> {
+    DOWNLOADS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock downloads: {e}")))
+}
+
+fn find_entry(entries: &mut [DownloadEntry], id: u32) -> Result<&mut DownloadEntry, AppError> {
+    entries
+        .iter_mut()
+        .find(|entry| entry.download.id == id)
+        .ok_or_else(|| AppError::not_found(format!("No download with id {id}")))
+}
+
+fn part_path(destination: &str) -> PathBuf {
+    PathBuf::from(format!("{destination}.part"))
+}
+
+/// Bytes already downloaded to the part file.
+fn part_size(destination: &str) -> u64 {
+    std::fs::metadata(part_path(destination)).map_or(0, |metadata| metadata.len())
+}
+
+/// The validator to resume with: a strong `ETag`, or `Last-Modified`. Weak
+/// ETags aren't allowed in `If-Range`.
+fn resume_validator(headers: &HeaderMap) -> Option<String> {
+    let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+    header(ETAG)
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| header(LAST_MODIFIED))
+        .map(str::to_string)
+}
+
+/// Gets the path to the downloads file.
+fn get_downloads_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = crate::data_dir::app_data_dir(app)?;
+    std::fs::create_dir_all(&app_data_dir)
+        .map_err(|e| AppError::io(format!("Failed to create app data directory: {e}")))?;
+    Ok(app_data_dir.join("downloads.json"))
+}
+
+fn read_downloads(app: &AppHandle) -> Result<Vec<DownloadEntry>, AppError> {
+    let path = get_downloads_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::io(format!("Failed to read downloads: {e}")))?;
+    serde_json::from_str(&contents).map_err(|e| AppError::Serialization {
+        message: format!("Failed to parse downloads: {e}"),
+    })
+}
+
+/// Writes the downloads file using atomic write (temp file + rename).
+fn write_downloads(app: &AppHandle, entries: &[DownloadEntry]) -> Result<(), AppError> {
+    let path = get_downloads_path(app)?;
+    let json_content =
+        serde_json::to_string_pretty(entries).map_err(|e| AppError::Serialization {
+            message: format!("Failed to serialize downloads: {e}"),
+        })?;
+
+    write_atomic(&path, json_content.as_bytes())
+        .map_err(|e| AppError::io(format!("Failed to write downloads: {e}")))
+}
+
+fn save_downloads(app: &AppHandle, entries: &[DownloadEntry]) {
+    if let Err(e) = write_downloads(app, entries) {
+        log::warn!("Failed to save downloads: {e}");
+    }
+}
+
+fn emit_changed(app: &AppHandle, download: &Download) {
+    let event = DownloadChanged {
+        download: download.clone(),
+    };
+    if let Err(e) = event.emit(app) {
+        log::warn!("Failed to emit download change: {e}");
+    }
+}
+
+/// Updates a download from its task and emits the change, saving if `save`.
+fn update_download(app: &AppHandle, id: u32, save: bool, update: impl FnOnce(&mut DownloadEntry)) {
+    let Ok(mut entries) = lock_downloads() else {
+        return;
+    };
+    let Ok(entry) = find_entry(&mut entries, id) else {
+        return;
+    };
+    update(entry);
+    emit_changed(app, &entry.download);
+    if save {
+        save_downloads(app, &entries);
+    }
+}
+
+/// Starts queued downloads, oldest first, while there are free slots.
+fn start_queued(app: &AppHandle, entries: &mut [DownloadEntry]) {
+    let running = entries
+        .iter()
+        .filter(|entry| entry.download.status == DownloadStatus::Downloading)
+        .count();
+    let free = MAX_PARALLEL_DOWNLOADS.saturating_sub(running);
+
+    for entry in entries
+        .iter_mut()
+        .filter(|entry| entry.download.status == DownloadStatus::Queued)
+        .take(free)
+    {
+        entry.download.status = DownloadStatus::Downloading;
+        entry.download.error = None;
+
+        // The task waits for the downloads lock held by the caller, so the
+        // abort handle is in place before it runs
+        let id = entry.download.id;
+        let task_app = app.clone();
+        let task = tauri::async_runtime::spawn(async move {
+            let result = run_download(&task_app, id).await;
+            finish_download(&task_app, id, result);
+        });
+        let abort = task.inner().abort_handle();
+        entry.abort = Some(Box::new(move || abort.abort()));
+        emit_changed(app, &entry.download);
+    }
+}
+
+/// Downloads to the part file, resuming from its end if the server allows,
+/// and renames it to the destination once complete.
+async fn run_download(app: &AppHandle, id: u32) -> Result<(), AppError> {
+    let (url, destination, validator) = {
+        let mut entries = lock_downloads()?;
+        let entry = find_entry(&mut entries, id)?;
+        (
+            entry.download.url.clone(),
+            entry.download.destination.clone(),
+            entry.validator.clone(),
+        )
+    };
+    let part = part_path(&destination);
+    let offset = part_size(&destination);
+    log::debug!("Downloading {destination} (download {id}, from byte {offset})");
+
+    let client = http::client(app)?;
+    let mut request = client.get(&url);
+    if offset > 0 {
+        request = request.header(RANGE, format!("bytes={offset}-"));
+        if let Some(validator) = &validator {
+            request = request.header(IF_RANGE, validator);
+        }
+    }
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to download: {e}")))?;
+    // The part file is already complete or longer than the file; start over
+    if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        response = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to download: {e}")))?;
+    }
+    let mut response = response
+        .error_for_status()
+        .map_err(|e| AppError::internal(format!("Failed to download: {e}")))?;
+
+    let resumed = response.status() == StatusCode::PARTIAL_CONTENT;
+    let start = if resumed { offset } else { 0 };
+    let total = response.content_length().map(|length| start + length);
+    let validator = resume_validator(response.headers());
+    update_download(app, id, true, |entry| {
+        entry.download.downloaded = start as f64;
+        entry.download.total = total.map(|total| total as f64);
+        entry.validator = validator;
+    });
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(&part)
+        .map_err(|e| AppError::io(format!("Failed to open {}: {e}", part.display())))?;
+    let mut downloaded = start;
+    let mut last_progress = Instant::now();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| AppError::internal(format!("Download interrupted: {e}")))?
+    {
+        file.write_all(&chunk)
+            .map_err(|e| AppError::io(format!("Failed to write {}: {e}", part.display())))?;
+        downloaded += chunk.len() as u64;
+        if last_progress.elapsed() >= PROGRESS_INTERVAL {
+            last_progress = Instant::now();
+            update_download(app, id, false, |entry| {
+                entry.download.downloaded = downloaded as f64;
+            });
+        }
+    }
+    file.sync_all()
+        .map_err(|e| AppError::io(format!("Failed to write {}: {e}", part.display())))?;
+    drop(file);
+
+    std::fs::rename(&part, &destination)
+        .map_err(|e| AppError::io(format!("Failed to move download to {destination}: {e}")))?;
+    update_download(app, id, false, |entry| {
+        entry.download.downloaded = downloaded as f64;
+        entry.download.total = Some(downloaded as f64);
+    });
+    Ok(())
+}
+
+/// Records how a download task ended and starts the next queued one. Tasks
+/// stopped by pause or cancel are aborted, so usually don't get here; one
+/// that completed just as it was stopped still counts as completed.
+fn finish_download(app: &AppHandle, id: u32, result: Result<(), AppError>) {
+    let Ok(mut entries) = lock_downloads() else {
+        return;
+    };
+    if let Ok(entry) = find_entry(&mut entries, id) {
+        entry.abort = None;
+        match result {
+            Ok(()) => {
+                log::info!("Download {id} finished: {}", entry.download.destination);
+                entry.download.status = DownloadStatus::Completed;
+                entry.download.error = None;
+                emit_changed(app, &entry.download);
+            }
+            Err(e) if entry.download.status == DownloadStatus::Downloading => {
+                log::warn!("Download {id} failed: {e}");
+                entry.download.status = DownloadStatus::Failed;
+                entry.download.error = Some(e.to_string());
+                emit_changed(app, &entry.download);
+            }
+            Err(_) => {}
+        }
+    }
+    start_queued(app, &mut entries);
+    save_downloads(app, &entries);
+}
+
+/// Loads saved downloads at startup. Downloads that were running or queued
+/// when the app quit are queued again; paused ones stay paused.
+pub fn restore_downloads(app: &AppHandle) {
+    let mut restored = match read_downloads(app) {
+        Ok(restored) => restored,
+        Err(e) => {
+            log::warn!("Failed to restore downloads: {e}");
+            return;
+        }
+    };
+    for entry in &mut restored {
+        let download = &mut entry.download;
+        if download.status == DownloadStatus::Downloading {
+            download.status = DownloadStatus::Queued;
+        }
+        if matches!(
+            download.status,
+            DownloadStatus::Queued | DownloadStatus::Paused
+        ) {
+            download.downloaded = part_size(&download.destination) as f64;
+        }
+    }
+    let next_id = restored
+        .iter()
+        .map(|entry| entry.download.id + 1)
+        .max()
+        .unwrap_or(1);
+    NEXT_DOWNLOAD_ID.fetch_max(next_id, Ordering::Relaxed);
+
+    let Ok(mut entries) = lock_downloads() else {
+        return;
+    };
+    *entries = restored;
+    start_queued(app, &mut entries);
+    save_downloads(app, &entries);
+}
+
+/// Downloads `url` to `destination` in the background, queued if
+/// `MAX_PARALLEL_DOWNLOADS` are already running. `destination` must be in the
+/// file scope and not exist yet. Follow it with `download-changed` events.
+#[tauri::command]
+#[specta::specta]
+pub fn start_download(
+    app: AppHandle,
+    url: String,
+    destination: String,
+) -> Result<Download, AppError> {
+    http::parse_url(&url)?;
+    let destination = resolve_scoped_write_path(&app, &destination)?;
+    if destination.exists() {
+        return Err(AppError::validation(format!(
+            "{} already exists",
+            destination.display()
+        )));
+    }
+    let destination = destination.to_string_lossy().into_owned();
+
+    let mut entries = lock_downloads()?;
+    let in_use = entries.iter().any(|entry| {
+        entry.download.destination == destination
+            && matches!(
+                entry.download.status,
+                DownloadStatus::Queued | DownloadStatus::Downloading | DownloadStatus::Paused
+            )
+    });
+    if in_use {
+        return Err(AppError::validation(format!(
+            "Already downloading to {destination}"
+        )));
+    }
+    // Left over from an earlier download that was cleared
+    let _ = std::fs::remove_file(part_path(&destination));
+
+    let id = NEXT_DOWNLOAD_ID.fetch_add(1, Ordering::Relaxed);
+    log::info!("Queued download {id}: {destination}");
+    let download = Download {
+        id,
+        url,
+        destination,
+        status: DownloadStatus::Queued,
+        downloaded: 0.0,
+        total: None,
+        error: None,
+    };
+    emit_changed(&app, &download);
+    entries.push(DownloadEntry {
+        download,
+        validator: None,
+        abort: None,
+    });
+    start_queued(&app, &mut entries);
+    save_downloads(&app, &entries);
+
+    Ok(find_entry(&mut entries, id)?.download.clone())
+}
+
+/// Pauses a queued or running download, keeping what's been downloaded.
+#[tauri::command]
+#[specta::specta]
+pub fn pause_download(app: AppHandle, id: u32) -> Result<(), AppError> {
+    let mut entries = lock_downloads()?;
+    let entry = find_entry(&mut entries, id)?;
+    if !matches!(
+        entry.download.status,
+        DownloadStatus::Queued | DownloadStatus::Downloading
+    ) {
+        return Err(AppError::validation(format!("Download {id} isn't running")));
+    }
+    if let Some(abort) = entry.abort.take() {
+        abort();
+    }
+    entry.download.status = DownloadStatus::Paused;
+    log::debug!("Paused download {id}");
+    emit_changed(&app, &entry.download);
+
+    start_queued(&app, &mut entries);
+    save_downloads(&app, &entries);
+    Ok(())
+}
+
+/// Resumes a paused or failed download from where it stopped. It's queued if
+/// `MAX_PARALLEL_DOWNLOADS` are already running.
+#[tauri::command]
+#[specta::specta]
+pub fn resume_download(app: AppHandle, id: u32) -> Result<(), AppError> {
+    let mut entries = lock_downloads()?;
+    let entry = find_entry(&mut entries, id)?;
+    if !matches!(
+        entry.download.status,
+        DownloadStatus::Paused | DownloadStatus::Failed
+    ) {
+        return Err(AppError::validation(format!(
+            "Download {id} isn't paused or failed"
+        )));
+    }
+    entry.download.status = DownloadStatus::Queued;
+    entry.download.error = None;
+    log::debug!("Resuming download {id}");
+    emit_changed(&app, &entry.download);
+
+    start_queued(&app, &mut entries);
+    save_downloads(&app, &entries);
+    Ok(())
+}
+
+/// Cancels a download and deletes what it downloaded.
+#[tauri::command]
+#[specta::specta]
+pub fn cancel_download(app: AppHandle, id: u32) -> Result<(), AppError> {
+    let mut entries = lock_downloads()?;
+    let entry = find_entry(&mut entries, id)?;
+    if matches!(
+        entry.download.status,
+        DownloadStatus::Completed | DownloadStatus::Cancelled
+    ) {
+        return Err(AppError::validation(format!(
+            "Download {id} already finished"
+        )));
+    }
+    if let Some(abort) = entry.abort.take() {
+        abort();
+    }
+    entry.download.status = DownloadStatus::Cancelled;
+    if let Err(e) = std::fs::remove_file(part_path(&entry.download.destination)) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            log::warn!("Failed to delete cancelled download: {e}");
+        }
+    }
+    log::info!("Cancelled download {id}");
+    emit_changed(&app, &entry.download);
+
+    start_queued(&app, &mut entries);
+    save_downloads(&app, &entries);
+    Ok(())
+}
+
+/// Returns all downloads, oldest first, including finished ones until
+/// `clear_finished_downloads`.
+#[tauri::command]
+#[specta::specta]
+pub fn list_downloads() -> Result<Vec<Download>, AppError> {
+    Ok(lock_downloads()?
+        .iter()
+        .map(|entry| entry.download.clone())
+        .collect())
+}
+
+/// Removes completed, failed and cancelled downloads from the list. The
+/// downloaded files stay; partial data from failed downloads is deleted.
+#[tauri::command]
+#[specta::specta]
+pub fn clear_finished_downloads(app: AppHandle) -> Result<(), AppError> {
+    let mut entries = lock_downloads()?;
+    entries.retain(|entry| match entry.download.status {
+        DownloadStatus::Completed | DownloadStatus::Cancelled => false,
+        DownloadStatus::Failed => {
+            let _ = std::fs::remove_file(part_path(&entry.download.destination));
+            false
+        }
+        _ => true,
+    });
+    save_downloads(&app, &entries);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_resume_validator() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(resume_validator(&headers), None);
+
+        headers.insert(
+            LAST_MODIFIED,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        headers.insert(ETAG, HeaderValue::from_static("W/\"weak\""));
+        assert_eq!(
+            resume_validator(&headers).as_deref(),
+            Some("Wed, 21 Oct 2015 07:28:00 GMT")
+        );
+
+        headers.insert(ETAG, HeaderValue::from_static("\"strong\""));
+        assert_eq!(resume_validator(&headers).as_deref(), Some("\"strong\""));
+    }
+}
@@ -0,0 +1,38 @@
+//! Command for turning local data encryption on or off.
+//!
+//! The setting is saved to preferences and applied by
+//! `encryption::migrate_local_data` on the next launch, before the database
+//! is opened, so this restarts the app.
+
+use tauri::AppHandle;
+
+use super::preferences::{load_preferences, save_preferences};
+use crate::types::AppError;
+
+/// Turns encryption of the database, preferences and recovery data on or
+/// off, then restarts the app to convert existing data. Turning it on fails
+/// if the OS keychain can't store the key.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_local_data_encryption(app: AppHandle, enabled: bool) -> Result<(), AppError> {
+    if enabled {
+        crate::encryption::ensure_key().map_err(|message| AppError::Permission { message })?;
+    }
+
+    let mut preferences = load_preferences(app.clone()).await?;
+    if preferences.encrypt_local_data == enabled {
+        return Ok(());
+    }
+    preferences.encrypt_local_data = enabled;
+    save_preferences(app.clone(), preferences).await?;
+
+    // Pending saves would be written in the old format after conversion
+    super::documents::flush_pending_documents(&app);
+
+    log::info!(
+        "Local data encryption turned {}; restarting to apply it",
+        if enabled { "on" } else { "off" }
+    );
+    app.request_restart();
+    Ok(())
+}
@@ -0,0 +1,204 @@
+//! Holding events until a window's frontend can receive them.
+//!
+//! An event emitted before a window's page has loaded and registered its
+//! listeners is lost, which matters for events sent during `setup()` or on
+//! activation: a file or deep link the app was launched with, or a quick
+//! entry link that creates the quick pane. [`emit_to`] sends such events
+//! straight away if the window's frontend is ready, and otherwise queues them
+//! until it calls `frontend_ready`, then sends them in order.
+//!
+//! A window is ready from its `frontend_ready` call until its page starts
+//! loading again (a reload), when events queue again. Queues of windows that
+//! are destroyed are dropped.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use tauri::{AppHandle, WebviewWindow};
+use tauri_specta::Event;
+
+use crate::types::AppError;
+
+/// Events queued per window before the oldest are dropped.
+const MAX_QUEUED_EVENTS: usize = 100;
+
+/// Sends a queued event to a window.
+type Emit = Box<dyn FnOnce(&AppHandle, &str) -> tauri::Result<()> + Send>;
+
+/// A queued event and its name, for logging.
+struct QueuedEvent {
+    name: &'static str,
+    emit: Emit,
+}
+
+/// Whether a window's frontend has completed the handshake.
+enum WindowState<T> {
+    Loading(Vec<T>),
+    Ready,
+}
+
+/// Queues by window label. Windows that haven't been seen yet are loading.
+struct Queues<T> {
+    windows: HashMap<String, WindowState<T>>,
+}
+
+impl<T> Queues<T> {
+    fn new() -> Self {
+        Self {
+            windows: HashMap::new(),
+        }
+    }
+
+    /// Queues `item` if the window isn't ready, dropping the oldest item if
+    /// the queue is full. Returns the item back if the window is ready, and
+    /// whether one was dropped.
+    fn push(&mut self, label: &str, item: T) -> (Option<T>, bool) {
+        let state = self
+            .windows
+            .entry(label.to_string())
+            .or_insert_with(|| WindowState::Loading(Vec::new()));
+        match state {
+            WindowState::Ready => (Some(item), false),
+            WindowState::Loading(queue) => {
+                let dropped = queue.len() >= MAX_QUEUED_EVENTS;
+                if dropped {
+                    queue.remove(0);
+                }
+                queue.push(item);
+                (None, dropped)
+            }
+        }
+    }
+
+    /// Marks the window ready, returning what was queued for it in order.
+    fn mark_ready(&mut self, label: &str) -> Vec<T> {
+        match self.windows.insert(label.to_string(), WindowState::Ready) {
+            Some(WindowState::Loading(queue)) => queue,
+            _ => Vec::new(),
+        }
+    }
+
+    /// Queues events again until the next `mark_ready`. Keeps anything
+    /// already queued.
+    fn mark_loading(&mut self, label: &str) {
+        self.windows
+            .entry(label.to_string())
+            .and_modify(|state| {
+                if let WindowState::Ready = state {
+                    *state = WindowState::Loading(Vec::new());
+                }
+            })
+            .or_insert_with(|| WindowState::Loading(Vec::new()));
+    }
+
+    fn remove(&mut self, label: &str) {
+        self.windows.remove(label);
+    }
+}
+
+static QUEUES: LazyLock<Mutex<Queues<QueuedEvent>>> = LazyLock::new(|| Mutex::new(Queues::new()));
+
+/// Emits `event` to the window `label` now if its frontend is ready, or
+/// queues it until the window calls `frontend_ready`. Use this instead of
+/// `Event::emit_to` for events that can happen before the window has loaded.
+pub fn emit_to<E>(app: &AppHandle, label: &str, event: E)
+where
+    E: Event + serde::Serialize + Clone + Send + 'static,
+{
+    let queued = QueuedEvent {
+        name: E::NAME,
+        emit: Box::new(move |app, label| event.emit_to(app, label)),
+    };
+    // Emit under the lock, so nothing overtakes events being flushed
+    let Ok(mut queues) = QUEUES.lock() else {
+        log::error!("Failed to lock event queues, dropping {}", E::NAME);
+        return;
+    };
+    match queues.push(label, queued) {
+        (Some(queued), _) => send(app, label, queued),
+        (None, dropped) => {
+            if dropped {
+                log::warn!("Too many events queued for '{label}', dropped the oldest");
+            }
+            log::debug!("Queued {} until '{label}' is ready", E::NAME);
+        }
+    }
+}
+
+fn send(app: &AppHandle, label: &str, queued: QueuedEvent) {
+    if let Err(e) = (queued.emit)(app, label) {
+        log::error!("Failed to emit {} event: {e}", queued.name);
+    }
+}
+
+/// Queues events for a webview again while its page (re)loads. Called from
+/// `on_page_load` in `lib.rs`.
+pub fn handle_page_load_started(label: &str) {
+    if let Ok(mut queues) = QUEUES.lock() {
+        queues.mark_loading(label);
+    }
+}
+
+/// Drops a destroyed window's queue. Called from the run loop in `lib.rs`.
+pub fn handle_window_destroyed(label: &str) {
+    if let Ok(mut queues) = QUEUES.lock() {
+        queues.remove(label);
+    }
+}
+
+/// Called by each window's frontend once its event listeners are registered.
+/// Sends the events queued for it, in order; later events are sent directly.
+#[tauri::command]
+#[specta::specta]
+pub fn frontend_ready(app: AppHandle, window: WebviewWindow) -> Result<(), AppError> {
+    let label = window.label();
+    let mut queues = QUEUES
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock event queues: {e}")))?;
+    let queued = queues.mark_ready(label);
+    log::debug!(
+        "Frontend of '{label}' ready, sending {} queued events",
+        queued.len()
+    );
+    for event in queued {
+        send(&app, label, event);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queues_until_ready() {
+        let mut queues = Queues::new();
+        assert_eq!(queues.push("main", 1), (None, false));
+        assert_eq!(queues.push("main", 2), (None, false));
+        assert_eq!(queues.push("quick-pane", 3), (None, false));
+
+        assert_eq!(queues.mark_ready("main"), vec![1, 2]);
+        assert_eq!(queues.push("main", 4), (Some(4), false));
+        assert!(queues.mark_ready("main").is_empty());
+
+        // A reload queues again until the next handshake
+        queues.mark_loading("main");
+        assert_eq!(queues.push("main", 5), (None, false));
+        queues.mark_loading("main");
+        assert_eq!(queues.mark_ready("main"), vec![5]);
+
+        queues.remove("quick-pane");
+        assert!(queues.mark_ready("quick-pane").is_empty());
+    }
+
+    #[test]
+    fn test_full_queue_drops_oldest() {
+        let mut queues = Queues::new();
+        for i in 0..MAX_QUEUED_EVENTS {
+            assert_eq!(queues.push("main", i), (None, false));
+        }
+        assert_eq!(queues.push("main", MAX_QUEUED_EVENTS), (None, true));
+        let queued = queues.mark_ready("main");
+        assert_eq!(queued.len(), MAX_QUEUED_EVENTS);
+        assert_eq!(queued[0], 1);
+    }
+}
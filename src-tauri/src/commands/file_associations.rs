@@ -0,0 +1,137 @@
+//! Opening files with the app from the OS: double-clicking an associated
+//! file, "Open With", or dropping a file on the Dock icon.
+//!
+//! File types are declared in `bundle.fileAssociations` in `tauri.conf.json`.
+//! The OS hands the files over differently per platform:
+//!
+//! - macOS sends `application:openFiles:`, which Tauri surfaces as
+//!   `RunEvent::Opened`, both at launch and while running
+//! - Windows and Linux pass the paths as command-line arguments, at launch or
+//!   (through the single-instance plugin) to the already running app
+//!
+//! Each file is added to the filesystem scope and emitted to the main window
+//! as `file-opened` through `event_queue`, so files opened at launch are
+//! delivered once the frontend is ready.
+
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, Url};
+use tauri_plugin_fs::FsExt;
+
+use super::event_queue;
+use super::startup::is_startup_finished;
+use super::window::MAIN_WINDOW_LABEL;
+use crate::types::FileOpened;
+
+/// Associated extensions and their MIME types. Command-line arguments are
+/// filtered by these. Keep in sync with `bundle.fileAssociations` in
+/// `tauri.conf.json`.
+const ASSOCIATED_TYPES: &[(&str, &str)] = &[
+    ("md", "text/markdown"),
+    ("markdown", "text/markdown"),
+    ("txt", "text/plain"),
+];
+
+/// The MIME type of an associated extension (case-insensitive, without the
+/// dot), or `None` if the app isn't associated with it.
+pub(crate) fn associated_mime_type(extension: &str) -> Option<&'static str> {
+    ASSOCIATED_TYPES
+        .iter()
+        .find(|(associated, _)| associated.eq_ignore_ascii_case(extension))
+        .map(|(_, mime_type)| *mime_type)
+}
+
+/// Whether `path` has one of the associated extensions (case-insensitive).
+fn is_associated(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| associated_mime_type(ext).is_some())
+}
+
+/// Finds associated files in command-line arguments. The first argument is
+/// the executable, and flags and URLs are skipped. Relative paths are resolved against
+/// `cwd`, the directory the app was launched from.
+fn parse_file_args(args: &[String], cwd: &Path) -> Vec<PathBuf> {
+    args.iter()
+        .skip(1)
+        // Flags and URLs (deep links are handled by `deep_link`)
+        .filter(|arg| !arg.starts_with('-') && !arg.contains("://"))
+        .map(|arg| cwd.join(arg))
+        .filter(|path| is_associated(path))
+        .collect()
+}
+
+fn emit_file_opened(app: &AppHandle, path: PathBuf) {
+    log::info!("File opened from the OS: {}", path.display());
+    if let Err(e) = app.fs_scope().allow_file(&path) {
+        log::warn!("Failed to allow opened file in scope: {e}");
+    }
+
+    // On macOS the main window may be hidden rather than closed. During
+    // startup it's left for `finish_startup` to reveal.
+    if is_startup_finished() {
+        if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+
+    let event = FileOpened {
+        path: path.to_string_lossy().into_owned(),
+    };
+    event_queue::emit_to(app, MAIN_WINDOW_LABEL, event);
+}
+
+fn open_files(app: &AppHandle, paths: Vec<PathBuf>) {
+    for path in paths {
+        if !path.is_file() {
+            log::warn!("Ignoring opened path that isn't a file: {}", path.display());
+            continue;
+        }
+        emit_file_opened(app, path);
+    }
+}
+
+/// Handles files passed as arguments on Windows and Linux. Called with the
+/// process arguments during setup, and from the single-instance callback.
+pub fn handle_launch_args(app: &AppHandle, args: &[String], cwd: &Path) {
+    open_files(app, parse_file_args(args, cwd));
+}
+
+/// Handles files opened through the OS on macOS (`RunEvent::Opened`).
+/// URLs other than `file://` ones are left for other handlers.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub fn handle_opened_urls(app: &AppHandle, urls: &[Url]) {
+    let paths = urls
+        .iter()
+        .filter(|url| url.scheme() == "file")
+        .filter_map(|url| url.to_file_path().ok())
+        .collect();
+    open_files(app, paths);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_parse_file_args() {
+        let cwd = Path::new("/home/user");
+        assert_eq!(
+            parse_file_args(
+                &args(&["app", "--flag", "notes.md", "/tmp/README.MD", "image.png"]),
+                cwd
+            ),
+            vec![
+                PathBuf::from("/home/user/notes.md"),
+                PathBuf::from("/tmp/README.MD")
+            ]
+        );
+        // The executable itself is never treated as a file
+        assert!(parse_file_args(&args(&["notes.md"]), cwd).is_empty());
+    }
+}
@@ -0,0 +1,121 @@
+//! Streaming large files to the frontend in chunks.
+//!
+//! `stream_file` sends a file over a Tauri channel as base64 chunks (see
+//! `commands::streams`), so files too big for a single invoke response can
+//! still be opened. The stream is paced by the frontend: at most
+//! `MAX_IN_FLIGHT_CHUNKS` chunks are sent ahead of `ack_stream` calls, so a
+//! slow consumer doesn't have the whole file queued in the webview.
+//!
+//! Paths are checked with `files::resolve_scoped_path`.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use std::fs::File;
+use std::io::Read;
+use tauri::ipc::Channel;
+use tauri::AppHandle;
+
+use super::files::resolve_scoped_path;
+use super::streams::{self, Pacing, StreamSender};
+use crate::types::{FileChunk, FileError, StreamMessage};
+
+/// Smallest and largest accepted chunk sizes. Requests outside are clamped.
+const MIN_CHUNK_SIZE: u32 = 4 * 1024;
+const MAX_CHUNK_SIZE: u32 = 4 * 1024 * 1024;
+
+/// Chunks sent but not yet acknowledged before reading pauses.
+const MAX_IN_FLIGHT_CHUNKS: u32 = 4;
+
+/// Fills `buf` from `reader`, stopping early only at end of file. Returns
+/// the number of bytes read.
+fn read_chunk(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Reads `file` and sends it in chunks until done or the stream stops.
+fn send_file(
+    mut file: File,
+    chunk_size: usize,
+    sender: &StreamSender<FileChunk, FileError>,
+) -> Result<(), FileError> {
+    let mut buf = vec![0u8; chunk_size];
+    let mut offset: u64 = 0;
+
+    loop {
+        let read = read_chunk(&mut file, &mut buf).map_err(|e| FileError::IoError {
+            message: e.to_string(),
+        })?;
+        if read == 0 {
+            log::debug!("File stream {} finished ({offset} bytes)", sender.id());
+            return Ok(());
+        }
+
+        let chunk = FileChunk {
+            offset: offset as f64,
+            data: BASE64.encode(&buf[..read]),
+        };
+        if !sender.send(chunk) {
+            return Ok(());
+        }
+        offset += read as u64;
+    }
+}
+
+/// Streams a file to `on_chunk` in chunks of `chunk_size` bytes (clamped to
+/// 4KB-4MB). Returns the stream id, which the frontend passes to
+/// `ack_stream` after handling each chunk and to `stop_stream` to cancel.
+#[tauri::command]
+#[specta::specta]
+pub async fn stream_file(
+    app: AppHandle,
+    path: String,
+    chunk_size: u32,
+    on_chunk: Channel<StreamMessage<FileChunk, FileError>>,
+) -> Result<u32, FileError> {
+    let resolved = resolve_scoped_path(&app, &path)?;
+    if !resolved.is_file() {
+        return Err(FileError::InvalidPath {
+            message: format!("Not a file: {path}"),
+        });
+    }
+    let file = File::open(&resolved).map_err(|e| FileError::IoError {
+        message: e.to_string(),
+    })?;
+
+    let chunk_size = chunk_size.clamp(MIN_CHUNK_SIZE, MAX_CHUNK_SIZE) as usize;
+    let id = streams::start(
+        on_chunk,
+        Pacing::Acked(MAX_IN_FLIGHT_CHUNKS),
+        move |sender| send_file(file, chunk_size, sender),
+    )
+    .map_err(|e| FileError::IoError {
+        message: e.to_string(),
+    })?;
+    log::debug!("Streaming {} (stream {id})", resolved.display());
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_chunk() {
+        let data = [1u8; 10];
+        let mut reader = &data[..];
+        let mut buf = [0u8; 4];
+        assert_eq!(read_chunk(&mut reader, &mut buf).unwrap(), 4);
+        assert_eq!(read_chunk(&mut reader, &mut buf).unwrap(), 4);
+        assert_eq!(read_chunk(&mut reader, &mut buf).unwrap(), 2);
+        assert_eq!(read_chunk(&mut reader, &mut buf).unwrap(), 0);
+    }
+}
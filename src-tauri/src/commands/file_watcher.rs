@@ -0,0 +1,215 @@
+//! Watching files and directories for changes made outside the app.
+//!
+//! `watch_path` starts a debounced `notify` watcher and returns its id.
+//! Changes are broadcast to all windows as `fs-change` events carrying that
+//! id, so apps can reload documents edited by other programs. Bursts of
+//! events (e.g. an editor's save-via-rename) are coalesced over
+//! `DEBOUNCE_INTERVAL`, and renames are reported as one `Renamed` change
+//! where the platform allows.
+//!
+//! Watches last until `unwatch` or app exit. Paths are checked with
+//! `files::resolve_scoped_path`.
+
+use notify_debouncer_full::notify::event::{ModifyKind, RenameMode};
+use notify_debouncer_full::notify::{EventKind, RecommendedWatcher, RecursiveMode};
+use notify_debouncer_full::{new_debouncer, DebounceEventResult, Debouncer, RecommendedCache};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_specta::Event;
+
+use super::files::resolve_scoped_path;
+use crate::types::{AppError, FsChange, FsChangeKind};
+
+/// How long changes are collected before being emitted.
+const DEBOUNCE_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Maximum number of active watches, to bound OS watch handles.
+const MAX_WATCHES: usize = 64;
+
+type FileWatcher = Debouncer<RecommendedWatcher, RecommendedCache>;
+
+/// Active watchers by id. Dropping a watcher stops it.
+static WATCHERS: LazyLock<Mutex<HashMap<u32, FileWatcher>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static NEXT_WATCH_ID: AtomicU32 = AtomicU32::new(1);
+
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+/// Converts a notify event into the changes reported to the frontend.
+/// Access events and events notify can't classify are dropped.
+fn to_changes(kind: &EventKind, paths: &[PathBuf]) -> Vec<FsChangeKind> {
+    let each = |change: fn(String) -> FsChangeKind| {
+        paths
+            .iter()
+            .map(|path| change(path_string(path)))
+            .collect::<Vec<_>>()
+    };
+    let created = |path| FsChangeKind::Created { path };
+    let removed = |path| FsChangeKind::Removed { path };
+
+    match kind {
+        EventKind::Create(_) => each(created),
+        EventKind::Remove(_) => each(removed),
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if paths.len() == 2 => {
+            vec![FsChangeKind::Renamed {
+                from: path_string(&paths[0]),
+                to: path_string(&paths[1]),
+            }]
+        }
+        // Halves of a rename whose other side is outside the watch
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => each(removed),
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => each(created),
+        // Some platforms (e.g. macOS FSEvents) don't say which side this is
+        EventKind::Modify(ModifyKind::Name(_)) => paths
+            .iter()
+            .map(|path| {
+                let path_str = path_string(path);
+                if path.exists() {
+                    FsChangeKind::Created { path: path_str }
+                } else {
+                    FsChangeKind::Removed { path: path_str }
+                }
+            })
+            .collect(),
+        EventKind::Modify(_) => each(|path| FsChangeKind::Modified { path }),
+        _ => Vec::new(),
+    }
+}
+
+fn emit_changes(app: &AppHandle, watch_id: u32, result: DebounceEventResult) {
+    let events = match result {
+        Ok(events) => events,
+        Err(errors) => {
+            for e in errors {
+                log::warn!("File watcher {watch_id} error: {e}");
+            }
+            return;
+        }
+    };
+
+    for event in events {
+        for change in to_changes(&event.kind, &event.paths) {
+            if let Err(e) = (FsChange { watch_id, change }).emit(app) {
+                log::warn!("Failed to emit {} event: {e}", FsChange::NAME);
+            }
+        }
+    }
+}
+
+/// Starts watching a file or directory (and, if `recursive`, everything
+/// below it). Returns an id for `unwatch` that's also included in each
+/// `fs-change` event.
+#[tauri::command]
+#[specta::specta]
+pub fn watch_path(app: AppHandle, path: String, recursive: bool) -> Result<u32, AppError> {
+    let path = resolve_scoped_path(&app, &path).map_err(|e| AppError::internal(e.to_string()))?;
+
+    let mut watchers = WATCHERS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock file watchers: {e}")))?;
+    if watchers.len() >= MAX_WATCHES {
+        return Err(AppError::validation(format!(
+            "Too many watched paths (max {MAX_WATCHES})"
+        )));
+    }
+
+    let watch_id = NEXT_WATCH_ID.fetch_add(1, Ordering::Relaxed);
+    let handle = app.clone();
+    let mut watcher = new_debouncer(DEBOUNCE_INTERVAL, None, move |result| {
+        emit_changes(&handle, watch_id, result)
+    })
+    .map_err(|e| AppError::internal(format!("Failed to create file watcher: {e}")))?;
+
+    let mode = if recursive {
+        RecursiveMode::Recursive
+    } else {
+        RecursiveMode::NonRecursive
+    };
+    watcher
+        .watch(&path, mode)
+        .map_err(|e| AppError::internal(format!("Failed to watch {}: {e}", path.display())))?;
+
+    log::debug!("Watching {} (id {watch_id})", path.display());
+    watchers.insert(watch_id, watcher);
+    Ok(watch_id)
+}
+
+/// Stops a watch started by `watch_path`. Returns false if there was no
+/// watch with this id.
+#[tauri::command]
+#[specta::specta]
+pub fn unwatch(id: u32) -> Result<bool, AppError> {
+    let watcher = WATCHERS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock file watchers: {e}")))?
+        .remove(&id);
+    // Stopping joins the debouncer thread, so do it outside the lock
+    let Some(watcher) = watcher else {
+        return Ok(false);
+    };
+    watcher.stop();
+    log::debug!("Stopped watch {id}");
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify_debouncer_full::notify::event::{AccessKind, CreateKind, DataChange, RemoveKind};
+
+    #[test]
+    fn test_to_changes() {
+        let a = PathBuf::from("/watched/a.md");
+        let b = PathBuf::from("/watched/b.md");
+        let only_a = std::slice::from_ref(&a);
+
+        assert_eq!(
+            to_changes(&EventKind::Create(CreateKind::File), only_a),
+            vec![FsChangeKind::Created {
+                path: path_string(&a)
+            }]
+        );
+        assert_eq!(
+            to_changes(
+                &EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+                only_a
+            ),
+            vec![FsChangeKind::Modified {
+                path: path_string(&a)
+            }]
+        );
+        assert_eq!(
+            to_changes(&EventKind::Remove(RemoveKind::Any), only_a),
+            vec![FsChangeKind::Removed {
+                path: path_string(&a)
+            }]
+        );
+        assert_eq!(
+            to_changes(
+                &EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+                &[a.clone(), b.clone()]
+            ),
+            vec![FsChangeKind::Renamed {
+                from: path_string(&a),
+                to: path_string(&b)
+            }]
+        );
+        assert_eq!(
+            to_changes(
+                &EventKind::Modify(ModifyKind::Name(RenameMode::From)),
+                only_a
+            ),
+            vec![FsChangeKind::Removed {
+                path: path_string(&a)
+            }]
+        );
+        assert!(to_changes(&EventKind::Access(AccessKind::Any), only_a).is_empty());
+    }
+}
@@ -0,0 +1,629 @@
+//! Scoped file access for the frontend.
+//!
+//! A hardened alternative to granting the webview `fs:` plugin permissions:
+//! every path is resolved to its canonical form and must be inside a temp
+//! workspace or the fs scope, which holds paths the user chose in a dialog,
+//! dropped on a window or opened with the app. The app's own data and log
+//! directories are always out of reach, even inside a granted folder, so the
+//! webview can't rewrite preferences, the database or the saved scope
+//! (`allowed-paths.json`). Reads are size-limited
+//! and writes are atomic (temp file, fsync, rename), so a crash mid-save
+//! never leaves a truncated file. Deletion goes through the OS trash, and
+//! files can be revealed in or opened from the file manager, previewed with
+//! Quick Look, or hashed.
+//!
+//! Other commands that take a path from the frontend use
+//! [`resolve_scoped_path`] for the same checks.
+
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tauri_plugin_fs::FsExt;
+use tauri_plugin_opener::OpenerExt;
+
+use super::tasks;
+use super::temp_dirs::temp_root;
+use crate::types::{FileError, HashAlgorithm};
+
+/// Maximum size of a file read or written as text.
+pub const MAX_TEXT_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Maximum size of a file read as bytes. Larger files should be streamed.
+pub const MAX_BINARY_FILE_SIZE: u64 = 25 * 1024 * 1024;
+
+/// Read buffer size when hashing.
+const HASH_CHUNK_SIZE: usize = 64 * 1024;
+
+fn io_error(e: std::io::Error) -> FileError {
+    FileError::IoError {
+        message: e.to_string(),
+    }
+}
+
+/// Whether `canonical` is inside `dir`. `dir` is canonicalized first, and a
+/// directory that doesn't exist contains nothing.
+fn is_inside(canonical: &Path, dir: &Path) -> bool {
+    std::fs::canonicalize(dir).is_ok_and(|dir| canonical.starts_with(dir))
+}
+
+/// Fails unless `canonical` is in a temp workspace or the fs scope, and
+/// outside the app's internal directories.
+fn check_scope(app: &AppHandle, canonical: &Path, path: &str) -> Result<(), FileError> {
+    let in_workspace = temp_root(app).is_ok_and(|root| is_inside(canonical, &root));
+    let internal = crate::data_dir::internal_dirs(app)
+        .iter()
+        .any(|dir| is_inside(canonical, dir));
+
+    if in_workspace || (!internal && app.fs_scope().is_allowed(canonical)) {
+        Ok(())
+    } else {
+        log::warn!("Rejected access to path outside scope: {path}");
+        Err(FileError::OutOfScope {
+            path: path.to_string(),
+        })
+    }
+}
+
+fn require_absolute(path: &str) -> Result<&Path, FileError> {
+    let absolute = Path::new(path);
+    if path.is_empty() || !absolute.is_absolute() {
+        return Err(FileError::InvalidPath {
+            message: format!("Path must be absolute: {path}"),
+        });
+    }
+    Ok(absolute)
+}
+
+/// Resolves an existing path from the frontend to its canonical form,
+/// checking it's somewhere the app may access. Canonicalizing first means
+/// `..` and symlinks can't escape the scope.
+pub fn resolve_scoped_path(app: &AppHandle, path: &str) -> Result<PathBuf, FileError> {
+    let canonical = std::fs::canonicalize(require_absolute(path)?).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            FileError::NotFound {
+                path: path.to_string(),
+            }
+        } else {
+            io_error(e)
+        }
+    })?;
+    check_scope(app, &canonical, path)?;
+    Ok(canonical)
+}
+
+/// Like [`resolve_scoped_path`], for a file that may not exist yet. Its
+/// directory must exist, and either the file or the directory must be in scope.
+pub fn resolve_scoped_write_path(app: &AppHandle, path: &str) -> Result<PathBuf, FileError> {
+    let absolute = require_absolute(path)?;
+    let (Some(parent), Some(name)) = (absolute.parent(), absolute.file_name()) else {
+        return Err(FileError::InvalidPath {
+            message: format!("Not a file path: {path}"),
+        });
+    };
+
+    let parent = std::fs::canonicalize(parent).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            FileError::NotFound {
+                path: parent.to_string_lossy().into_owned(),
+            }
+        } else {
+            io_error(e)
+        }
+    })?;
+    let target = parent.join(name);
+    if target.is_dir() {
+        return Err(FileError::InvalidPath {
+            message: format!("Path is a directory: {path}"),
+        });
+    }
+    // An existing file may be a symlink pointing out of scope
+    let canonical = std::fs::canonicalize(&target).unwrap_or(target);
+    check_scope(app, &canonical, path)?;
+    Ok(canonical)
+}
+
+/// Reads a file after checking it's a regular file within `max_bytes`.
+fn read_limited(path: &Path, max_bytes: u64) -> Result<Vec<u8>, FileError> {
+    let metadata = std::fs::metadata(path).map_err(io_error)?;
+    if !metadata.is_file() {
+        return Err(FileError::InvalidPath {
+            message: format!("Not a file: {}", path.display()),
+        });
+    }
+    if metadata.len() > max_bytes {
+        return Err(FileError::TooLarge {
+            max_bytes: max_bytes as u32,
+        });
+    }
+    std::fs::read(path).map_err(io_error)
+}
+
+/// Writes `contents` to `path` atomically: the data goes to a temporary file
+/// in the same directory, is flushed to disk, then renamed over `path`. Use
+/// this for every file the app rewrites, so a crash mid-write never leaves a
+/// truncated file behind.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    // A hidden sibling, so the rename stays on one file system and can't
+    // clobber another of the user's files
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp_path = path.with_file_name(format!(".{name}.tmp"));
+
+    let write = |temp_path: &Path| -> std::io::Result<()> {
+        let mut file = File::create(temp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        std::fs::rename(temp_path, path)
+    };
+
+    if let Err(e) = write(&temp_path) {
+        // Clean up the temp file to avoid leaving orphaned files on disk
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e);
+    }
+
+    // Persist the rename itself (not supported for directories on Windows)
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        if let Err(e) = File::open(parent).and_then(|dir| dir.sync_all()) {
+            log::warn!("Failed to sync directory after write: {e}");
+        }
+    }
+    Ok(())
+}
+
+/// Reads a UTF-8 text file (max 10MB) from a temp workspace or the fs scope.
+/// The app's own data and log directories are refused.
+#[tauri::command]
+#[specta::specta]
+pub async fn read_text_file(app: AppHandle, path: String) -> Result<String, FileError> {
+    let resolved = resolve_scoped_path(&app, &path)?;
+    let bytes = read_limited(&resolved, MAX_TEXT_FILE_SIZE)?;
+    String::from_utf8(bytes).map_err(|_| FileError::InvalidEncoding { path })
+}
+
+/// Reads a file's bytes (max 25MB) from a temp workspace or the fs scope.
+/// The app's own data and log directories are refused.
+#[tauri::command]
+#[specta::specta]
+pub async fn read_binary_file(app: AppHandle, path: String) -> Result<Vec<u8>, FileError> {
+    let resolved = resolve_scoped_path(&app, &path)?;
+    read_limited(&resolved, MAX_BINARY_FILE_SIZE)
+}
+
+/// Atomically writes a text file (max 10MB), creating or replacing it. The
+/// directory must exist, and the file must be in a temp workspace or the fs
+/// scope, outside the app's own data and log directories.
+#[tauri::command]
+#[specta::specta]
+pub async fn write_text_file(
+    app: AppHandle,
+    path: String,
+    contents: String,
+) -> Result<(), FileError> {
+    if contents.len() as u64 > MAX_TEXT_FILE_SIZE {
+        return Err(FileError::TooLarge {
+            max_bytes: MAX_TEXT_FILE_SIZE as u32,
+        });
+    }
+    let resolved = resolve_scoped_write_path(&app, &path)?;
+    write_atomic(&resolved, contents.as_bytes()).map_err(io_error)?;
+    log::debug!("Wrote {} bytes to {}", contents.len(), resolved.display());
+    Ok(())
+}
+
+/// Moves a file or directory to the OS trash (Recycle Bin on Windows), so
+/// the user can restore it. Prefer this to deleting files the user owns.
+#[tauri::command]
+#[specta::specta]
+pub async fn trash_file(app: AppHandle, path: String) -> Result<(), FileError> {
+    let resolved = resolve_scoped_path(&app, &path)?;
+
+    // Trashing the app data directory (or a parent) would take the app's own
+    // data with it
+    let data_dir = crate::data_dir::app_data_dir(&app)
+        .ok()
+        .and_then(|dir| std::fs::canonicalize(dir).ok());
+    if data_dir.is_some_and(|dir| dir.starts_with(&resolved)) {
+        return Err(FileError::InvalidPath {
+            message: format!("Can't move the app data directory to the trash: {path}"),
+        });
+    }
+
+    trash::delete(&resolved).map_err(|e| FileError::IoError {
+        message: format!("Failed to move to trash: {e}"),
+    })?;
+    log::info!("Moved {} to the trash", resolved.display());
+    Ok(())
+}
+
+/// Extensions `open_with_default_app` refuses, since "opening" them runs them.
+const EXECUTABLE_EXTENSIONS: &[&str] = &[
+    "app", "appimage", "appx", "bat", "cmd", "com", "command", "cpl", "deb", "desktop", "dmg",
+    "exe", "hta", "jar", "js", "jse", "lnk", "msi", "msix", "pif", "pkg", "ps1", "reg", "rpm",
+    "scpt", "scr", "sh", "terminal", "url", "vbe", "vbs", "wsf",
+];
+
+fn is_executable(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            EXECUTABLE_EXTENSIONS
+                .iter()
+                .any(|executable| executable.eq_ignore_ascii_case(ext))
+        })
+}
+
+/// Shows a file or directory in Finder, Explorer or the Linux file manager,
+/// with it selected in its folder.
+#[tauri::command]
+#[specta::specta]
+pub async fn reveal_in_file_manager(app: AppHandle, path: String) -> Result<(), FileError> {
+    let resolved = resolve_scoped_path(&app, &path)?;
+    app.opener()
+        .reveal_item_in_dir(&resolved)
+        .map_err(|e| FileError::IoError {
+            message: format!("Failed to reveal in file manager: {e}"),
+        })
+}
+
+/// Opens a file in its default app, or a directory in the file manager.
+/// Executables, scripts and installers are refused.
+#[tauri::command]
+#[specta::specta]
+pub async fn open_with_default_app(app: AppHandle, path: String) -> Result<(), FileError> {
+    let resolved = resolve_scoped_path(&app, &path)?;
+    open_resolved(&app, &resolved, &path)
+}
+
+fn open_resolved(app: &AppHandle, resolved: &Path, path: &str) -> Result<(), FileError> {
+    if is_executable(resolved) {
+        log::warn!("Refused to open executable: {}", resolved.display());
+        return Err(FileError::InvalidPath {
+            message: format!("Executable files can't be opened: {path}"),
+        });
+    }
+    app.opener()
+        .open_path(resolved.to_string_lossy(), None::<&str>)
+        .map_err(|e| FileError::IoError {
+            message: format!("Failed to open file: {e}"),
+        })
+}
+
+/// Shows a file or directory in the Quick Look panel on macOS, over the app
+/// like Finder's Space-bar preview. Other platforms have no Quick Look, so
+/// it's opened like `open_with_default_app` instead.
+#[tauri::command]
+#[specta::specta]
+pub fn preview_file(app: AppHandle, path: String) -> Result<(), FileError> {
+    let resolved = resolve_scoped_path(&app, &path)?;
+
+    #[cfg(target_os = "macos")]
+    {
+        log::debug!("Previewing {}", resolved.display());
+        quick_look::preview(&resolved).map_err(|message| FileError::IoError { message })
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    open_resolved(&app, &resolved, &path)
+}
+
+/// Hashes everything `reader` yields as lowercase hex. Data is streamed
+/// through the hasher, so memory use doesn't grow with the input.
+fn hash_reader(mut reader: impl Read, algorithm: HashAlgorithm) -> std::io::Result<String> {
+    match algorithm {
+        HashAlgorithm::Sha256 => {
+            let mut hasher = Sha256::new();
+            std::io::copy(&mut reader, &mut hasher)?;
+            Ok(hex::encode(hasher.finalize()))
+        }
+        HashAlgorithm::Blake3 => {
+            let mut hasher = blake3::Hasher::new();
+            std::io::copy(&mut reader, &mut hasher)?;
+            Ok(hasher.finalize().to_hex().to_string())
+        }
+    }
+}
+
+/// Hashes a file of any size, returning the digest as lowercase hex. The file
+/// is read in chunks on a background thread. Pass a `task_id` to be able to
+/// stop it with `cancel_task`.
+#[tauri::command]
+#[specta::specta]
+pub async fn hash_file(
+    app: AppHandle,
+    path: String,
+    algorithm: HashAlgorithm,
+    task_id: Option<String>,
+) -> Result<String, FileError> {
+    let resolved = resolve_scoped_path(&app, &path)?;
+    if !resolved.is_file() {
+        return Err(FileError::InvalidPath {
+            message: format!("Not a file: {path}"),
+        });
+    }
+
+    let task = tasks::register(task_id);
+    let token = task.token().clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let file = token.reader(File::open(&resolved)?);
+        hash_reader(BufReader::with_capacity(HASH_CHUNK_SIZE, file), algorithm)
+    })
+    .await
+    .map_err(|e| FileError::IoError {
+        message: format!("Hashing task failed: {e}"),
+    })?;
+    if task.token().is_cancelled() {
+        log::debug!("Hashing {path} cancelled");
+        return Err(FileError::Cancelled);
+    }
+    result.map_err(io_error)
+}
+
+/// The shared `QLPreviewPanel`. The panel shows whatever its controller
+/// provides, and looks for one along the responder chain when it opens, so
+/// the app delegate is taught to take control while a preview is pending.
+#[cfg(target_os = "macos")]
+mod quick_look {
+    use objc2::encode::Encode;
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyClass, AnyObject, Bool, Imp, Sel};
+    use objc2::{define_class, msg_send, sel, MainThreadOnly};
+    use objc2_app_kit::NSApplication;
+    use objc2_foundation::{MainThreadMarker, NSObject, NSString, NSURL};
+    use std::cell::RefCell;
+    use std::ffi::CString;
+    use std::path::Path;
+
+    #[link(name = "Quartz", kind = "framework")]
+    extern "C" {}
+
+    thread_local! {
+        /// The file being previewed and the data source handing it to the
+        /// panel. AppKit objects, so only touched on the main thread.
+        static PREVIEW: RefCell<Option<(Retained<NSURL>, Retained<QuickLookDataSource>)>> =
+            const { RefCell::new(None) };
+    }
+
+    define_class!(
+        // SAFETY: NSObject has no subclassing requirements and this class
+        // doesn't implement Drop.
+        #[unsafe(super(NSObject))]
+        #[thread_kind = MainThreadOnly]
+        #[name = "TauriTemplateQuickLookDataSource"]
+        struct QuickLookDataSource;
+
+        impl QuickLookDataSource {
+            #[unsafe(method(numberOfPreviewItemsInPreviewPanel:))]
+            fn number_of_preview_items(&self, _panel: &AnyObject) -> isize {
+                PREVIEW.with(|preview| isize::from(preview.borrow().is_some()))
+            }
+
+            /// NSURL is a `QLPreviewItem`.
+            #[unsafe(method(previewPanel:previewItemAtIndex:))]
+            fn preview_item(&self, _panel: &AnyObject, _index: isize) -> *mut NSURL {
+                PREVIEW.with(|preview| match &*preview.borrow() {
+                    Some((url, _)) => Retained::as_ptr(url).cast_mut(),
+                    None => std::ptr::null_mut(),
+                })
+            }
+        }
+    );
+
+    /// `acceptsPreviewPanelControl:`, taking control only for our previews.
+    extern "C-unwind" fn accepts_control(
+        _this: &AnyObject,
+        _cmd: Sel,
+        _panel: *mut AnyObject,
+    ) -> Bool {
+        Bool::new(PREVIEW.with(|preview| preview.borrow().is_some()))
+    }
+
+    /// `beginPreviewPanelControl:`
+    extern "C-unwind" fn begin_control(_this: &AnyObject, _cmd: Sel, panel: *mut AnyObject) {
+        PREVIEW.with(|preview| {
+            // SAFETY: AppKit passes the shared panel, which outlives the call.
+            if let (Some((_, source)), Some(panel)) =
+                (&*preview.borrow(), unsafe { panel.as_ref() })
+            {
+                let _: () = unsafe { msg_send![panel, setDataSource: &**source] };
+            }
+        });
+    }
+
+    /// `endPreviewPanelControl:`, when the panel closes.
+    extern "C-unwind" fn end_control(_this: &AnyObject, _cmd: Sel, panel: *mut AnyObject) {
+        // SAFETY: AppKit passes the shared panel, which outlives the call.
+        if let Some(panel) = unsafe { panel.as_ref() } {
+            let _: () = unsafe { msg_send![panel, setDataSource: std::ptr::null::<AnyObject>()] };
+        }
+        PREVIEW.with(|preview| preview.borrow_mut().take());
+    }
+
+    /// Adds the `QLPreviewPanelController` methods to the app delegate's
+    /// class, once.
+    fn install_delegate_methods(mtm: MainThreadMarker) -> Result<(), String> {
+        let delegate = NSApplication::sharedApplication(mtm)
+            .delegate()
+            .ok_or("App delegate not found")?;
+        let delegate: &AnyObject = (*delegate).as_ref();
+        let class = delegate.class();
+
+        if class
+            .instance_method(sel!(acceptsPreviewPanelControl:))
+            .is_some()
+        {
+            return Ok(());
+        }
+
+        let accepts_types = CString::new(format!("{}@:@", Bool::ENCODING))
+            .map_err(|e| format!("Failed to encode method types: {e}"))?;
+        // SAFETY: each function matches the signature described by its type
+        // encoding: `- (BOOL)acceptsPreviewPanelControl:(QLPreviewPanel *)` and
+        // `- (void)begin/endPreviewPanelControl:(QLPreviewPanel *)`.
+        let added = unsafe {
+            let accepts: Imp =
+                std::mem::transmute(accepts_control as extern "C-unwind" fn(_, _, _) -> Bool);
+            let begin: Imp = std::mem::transmute(begin_control as extern "C-unwind" fn(_, _, _));
+            let end: Imp = std::mem::transmute(end_control as extern "C-unwind" fn(_, _, _));
+            let class = (class as *const AnyClass).cast_mut();
+            objc2::ffi::class_addMethod(
+                class,
+                sel!(acceptsPreviewPanelControl:),
+                accepts,
+                accepts_types.as_ptr(),
+            )
+            .as_bool()
+                && objc2::ffi::class_addMethod(
+                    class,
+                    sel!(beginPreviewPanelControl:),
+                    begin,
+                    c"v@:@".as_ptr(),
+                )
+                .as_bool()
+                && objc2::ffi::class_addMethod(
+                    class,
+                    sel!(endPreviewPanelControl:),
+                    end,
+                    c"v@:@".as_ptr(),
+                )
+                .as_bool()
+        };
+        if !added {
+            return Err("Failed to add Quick Look control to app delegate".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn preview(path: &Path) -> Result<(), String> {
+        let mtm = MainThreadMarker::new().ok_or("Quick Look must be shown on the main thread")?;
+        install_delegate_methods(mtm)?;
+
+        let class = AnyClass::get(c"QLPreviewPanel").ok_or("Quick Look isn't available")?;
+        let panel: Retained<AnyObject> = unsafe { msg_send![class, sharedPreviewPanel] };
+
+        let url = NSURL::fileURLWithPath(&NSString::from_str(&path.to_string_lossy()));
+        PREVIEW.with(|preview| {
+            let mut preview = preview.borrow_mut();
+            let source = match preview.take() {
+                Some((_, source)) => source,
+                // SAFETY: QuickLookDataSource has no ivars, so plain `init` fully initializes it.
+                None => unsafe { msg_send![QuickLookDataSource::alloc(mtm), init] },
+            };
+            *preview = Some((url, source));
+        });
+
+        // An open panel is already ours, so it only needs the new file
+        let visible: bool = unsafe { msg_send![&panel, isVisible] };
+        if visible {
+            let _: () = unsafe { msg_send![&panel, reloadData] };
+        } else {
+            let _: () =
+                unsafe { msg_send![&panel, makeKeyAndOrderFront: std::ptr::null::<AnyObject>()] };
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("files-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_write_atomic_and_read_limited() {
+        let dir = temp_dir();
+        let path = dir.join("notes.md");
+
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+        assert_eq!(read_limited(&path, 100).unwrap(), b"second");
+        // Only the target is left behind
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        assert!(matches!(
+            read_limited(&path, 3),
+            Err(FileError::TooLarge { max_bytes: 3 })
+        ));
+        assert!(matches!(
+            read_limited(&dir, 100),
+            Err(FileError::InvalidPath { .. })
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_is_inside() {
+        let dir = std::env::temp_dir().join(format!("files-scope-test-{}", std::process::id()));
+        let data = dir.join("data");
+        std::fs::create_dir_all(data.join("recovery")).unwrap();
+        let canonical = std::fs::canonicalize(&dir).unwrap();
+
+        assert!(is_inside(&canonical.join("data/allowed-paths.json"), &data));
+        assert!(is_inside(&canonical.join("data/recovery"), &data));
+        assert!(!is_inside(&canonical.join("data-export.json"), &data));
+        // A directory that doesn't exist contains nothing
+        assert!(!is_inside(
+            &canonical.join("missing/file"),
+            &dir.join("missing")
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_require_absolute() {
+        assert!(require_absolute("").is_err());
+        assert!(require_absolute("relative/file.txt").is_err());
+        #[cfg(unix)]
+        assert!(require_absolute("/tmp/file.txt").is_ok());
+    }
+
+    #[test]
+    fn test_hash_reader() {
+        assert_eq!(
+            hash_reader(&b"abc"[..], HashAlgorithm::Sha256).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            hash_reader(&b"abc"[..], HashAlgorithm::Blake3).unwrap(),
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+        );
+        // Input spanning several chunks hashes the same as in one piece
+        let large = vec![7u8; HASH_CHUNK_SIZE * 3 + 5];
+        assert_eq!(
+            hash_reader(
+                BufReader::with_capacity(HASH_CHUNK_SIZE, &large[..]),
+                HashAlgorithm::Sha256
+            )
+            .unwrap(),
+            hex::encode(Sha256::digest(&large))
+        );
+    }
+
+    #[test]
+    fn test_is_executable() {
+        assert!(is_executable(Path::new("setup.EXE")));
+        assert!(is_executable(Path::new("/Applications/Foo.app")));
+        for name in [
+            "app.desktop",
+            "run.scpt",
+            "shell.terminal",
+            "setup.msix",
+            "setup.appx",
+            "script.vbe",
+            "old.PIF",
+        ] {
+            assert!(is_executable(Path::new(name)), "{name}");
+        }
+        assert!(!is_executable(Path::new("notes.md")));
+        assert!(!is_executable(Path::new("folder")));
+    }
+}
@@ -0,0 +1,304 @@
+//! HTTP requests from the backend.
+//!
+//! `fetch` sends requests with reqwest rather than the webview, so they
+//! aren't subject to CORS, and Rust commands can use `send` to add
+//! credentials (e.g. a token from `secrets`) without the frontend ever seeing
+//! them. Response bodies are read into memory, up to `MAX_RESPONSE_BYTES`;
+//! stream large files to disk with a download instead.
+//!
+//! Requests with a `cancel_token` can be stopped with `cancel_fetch`, which
+//! aborts the task running them.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{LazyLock, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::ipc::Channel;
+use tauri::AppHandle;
+
+use crate::types::{
+    validate_string_input, AppError, HttpBody, HttpError, HttpHeader, HttpMethod, HttpProgress,
+    HttpRequest, HttpResponse, HttpResponseBody, HttpResponseType,
+};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Largest response body `fetch` reads into memory.
+const MAX_RESPONSE_BYTES: u32 = 64 * 1024 * 1024;
+
+/// Minimum time between progress messages.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+const MAX_CANCEL_TOKEN_LENGTH: usize = 100;
+
+static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+type AbortFn = Box<dyn Fn() + Send>;
+
+/// In-flight requests by cancel token: a unique id, so a finished request
+/// doesn't remove a newer one that reused its token, and how to abort it.
+static IN_FLIGHT: LazyLock<Mutex<HashMap<String, (u32, AbortFn)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static NEXT_REQUEST_ID: AtomicU32 = AtomicU32::new(1);
+
+pub(crate) fn invalid_request(message: impl Into<String>) -> HttpError {
+    HttpError::InvalidRequest {
+        message: message.into(),
+    }
+}
+
+pub(crate) fn request_error(e: reqwest::Error) -> HttpError {
+    if e.is_timeout() {
+        HttpError::Timeout
+    } else if e.is_builder() {
+        invalid_request(e.to_string())
+    } else {
+        HttpError::NetworkError {
+            message: e.to_string(),
+        }
+    }
+}
+
+/// Parses the URL, allowing only `http` and `https`.
+pub(crate) fn parse_url(url: &str) -> Result<reqwest::Url, HttpError> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| invalid_request(format!("{url}: {e}")))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(invalid_request(format!(
+            "Only http and https URLs are supported: {url}"
+        )));
+    }
+    Ok(parsed)
+}
+
+/// The shared client, so connections are reused across requests.
+pub(crate) fn client(app: &AppHandle) -> Result<&'static reqwest::Client, HttpError> {
+    if let Some(client) = CLIENT.get() {
+        return Ok(client);
+    }
+
+    // reqwest is built without a TLS provider (like the updater plugin); this
+    // fails harmlessly if one is already installed
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    let package = app.package_info();
+    let client = reqwest::Client::builder()
+        .user_agent(format!("{}/{}", package.name, package.version))
+        .build()
+        .map_err(|e| HttpError::NetworkError {
+            message: format!("Failed to create HTTP client: {e}"),
+        })?;
+    Ok(CLIENT.get_or_init(|| client))
+}
+
+fn decode_body(
+    bytes: Vec<u8>,
+    response_type: HttpResponseType,
+) -> Result<HttpResponseBody, HttpError> {
+    let decode_error = |message: String| HttpError::DecodeError { message };
+    Ok(match response_type {
+        HttpResponseType::Text => HttpResponseBody::Text {
+            text: String::from_utf8(bytes).map_err(|e| decode_error(e.to_string()))?,
+        },
+        HttpResponseType::Json if bytes.is_empty() => HttpResponseBody::Json {
+            value: serde_json::Value::Null,
+        },
+        HttpResponseType::Json => HttpResponseBody::Json {
+            value: serde_json::from_slice(&bytes).map_err(|e| decode_error(e.to_string()))?,
+        },
+        HttpResponseType::Bytes => HttpResponseBody::Bytes { bytes },
+    })
+}
+
+/// Sends `request` and reads the response, reporting download progress to
+/// `on_progress`. For Rust commands that call APIs on the frontend's behalf.
+pub async fn send(
+    app: &AppHandle,
+    request: HttpRequest,
+    on_progress: Option<&Channel<HttpProgress>>,
+) -> Result<HttpResponse, HttpError> {
+    let url = parse_url(&request.url)?;
+    let method = match request.method {
+        HttpMethod::Get => reqwest::Method::GET,
+        HttpMethod::Post => reqwest::Method::POST,
+        HttpMethod::Put => reqwest::Method::PUT,
+        HttpMethod::Patch => reqwest::Method::PATCH,
+        HttpMethod::Delete => reqwest::Method::DELETE,
+        HttpMethod::Head => reqwest::Method::HEAD,
+        HttpMethod::Options => reqwest::Method::OPTIONS,
+    };
+    let timeout = request
+        .timeout_ms
+        .map_or(DEFAULT_TIMEOUT, |ms| Duration::from_millis(ms.into()));
+
+    log::debug!("{method} request to {}", url.host_str().unwrap_or_default());
+    let mut builder = client(app)?.request(method, url).timeout(timeout);
+    for header in &request.headers {
+        builder = builder.header(&header.name, &header.value);
+    }
+    builder = match request.body {
+        Some(HttpBody::Text { text }) => builder.body(text),
+        Some(HttpBody::Json { value }) => builder.json(&value),
+        Some(HttpBody::Bytes { bytes }) => builder.body(bytes),
+        None => builder,
+    };
+
+    let response = builder.send().await.map_err(request_error)?;
+    read_response(response, request.response_type, on_progress).await
+}
+
+/// Reads a response into an `HttpResponse`, up to `MAX_RESPONSE_BYTES`,
+/// reporting download progress to `on_progress`.
+pub(crate) async fn read_response(
+    mut response: reqwest::Response,
+    response_type: HttpResponseType,
+    on_progress: Option<&Channel<HttpProgress>>,
+) -> Result<HttpResponse, HttpError> {
+    let status = response.status();
+    let total = response.content_length();
+    if total.is_some_and(|total| total > u64::from(MAX_RESPONSE_BYTES)) {
+        return Err(HttpError::TooLarge {
+            max_bytes: MAX_RESPONSE_BYTES,
+        });
+    }
+    let final_url = response.url().to_string();
+    let headers = response
+        .headers()
+        .iter()
+        .map(|(name, value)| HttpHeader {
+            name: name.to_string(),
+            value: String::from_utf8_lossy(value.as_bytes()).into_owned(),
+        })
+        .collect();
+
+    let mut bytes = Vec::with_capacity(total.unwrap_or(0) as usize);
+    let mut last_progress: Option<Instant> = None;
+    while let Some(chunk) = response.chunk().await.map_err(request_error)? {
+        if bytes.len() + chunk.len() > MAX_RESPONSE_BYTES as usize {
+            return Err(HttpError::TooLarge {
+                max_bytes: MAX_RESPONSE_BYTES,
+            });
+        }
+        bytes.extend_from_slice(&chunk);
+
+        if let Some(on_progress) = on_progress {
+            if last_progress.is_none_or(|last| last.elapsed() >= PROGRESS_INTERVAL) {
+                last_progress = Some(Instant::now());
+                let _ = on_progress.send(HttpProgress {
+                    downloaded: bytes.len() as f64,
+                    total: total.map(|total| total as f64),
+                });
+            }
+        }
+    }
+    if let Some(on_progress) = on_progress {
+        let _ = on_progress.send(HttpProgress {
+            downloaded: bytes.len() as f64,
+            total: total.map(|total| total as f64),
+        });
+    }
+
+    Ok(HttpResponse {
+        status: status.as_u16(),
+        ok: status.is_success(),
+        url: final_url,
+        headers,
+        body: decode_body(bytes, response_type)?,
+    })
+}
+
+/// Sends an HTTP request from the backend, bypassing the webview's CORS
+/// rules, and returns the response whatever its status. Download progress is
+/// sent to `on_progress`. Pass a `cancel_token` in the request to be able to
+/// stop it with `cancel_fetch`.
+#[tauri::command]
+#[specta::specta]
+pub async fn fetch(
+    app: AppHandle,
+    request: HttpRequest,
+    on_progress: Channel<HttpProgress>,
+) -> Result<HttpResponse, HttpError> {
+    let Some(token) = request.cancel_token.clone() else {
+        return send(&app, request, Some(&on_progress)).await;
+    };
+    if token.is_empty() {
+        return Err(invalid_request("Cancel token cannot be empty"));
+    }
+    validate_string_input(&token, MAX_CANCEL_TOKEN_LENGTH, "Cancel token")
+        .map_err(invalid_request)?;
+
+    // Run as a task so `cancel_fetch` can abort it
+    let id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+    let task = {
+        let mut in_flight = IN_FLIGHT
+            .lock()
+            .map_err(|e| invalid_request(format!("Failed to lock requests: {e}")))?;
+        if in_flight.contains_key(&token) {
+            return Err(invalid_request(format!(
+                "A request with cancel token {token} is already running"
+            )));
+        }
+        let task =
+            tauri::async_runtime::spawn(
+                async move { send(&app, request, Some(&on_progress)).await },
+            );
+        let abort = task.inner().abort_handle();
+        in_flight.insert(token.clone(), (id, Box::new(move || abort.abort())));
+        task
+    };
+
+    let result = task.await;
+    if let Ok(mut in_flight) = IN_FLIGHT.lock() {
+        if in_flight
+            .get(&token)
+            .is_some_and(|(entry_id, _)| *entry_id == id)
+        {
+            in_flight.remove(&token);
+        }
+    }
+    // The task only fails to complete if it was aborted
+    result.unwrap_or(Err(HttpError::Cancelled))
+}
+
+/// Cancels the `fetch` started with `cancel_token`, which then fails with
+/// `Cancelled`. Returns false if it already finished.
+#[tauri::command]
+#[specta::specta]
+pub fn cancel_fetch(cancel_token: String) -> Result<bool, AppError> {
+    let abort = IN_FLIGHT
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock requests: {e}")))?
+        .remove(&cancel_token);
+    if let Some((_, abort)) = &abort {
+        log::debug!("Cancelling request {cancel_token}");
+        abort();
+    }
+    Ok(abort.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_url() {
+        assert!(parse_url("https://example.com/api?q=1").is_ok());
+        assert!(parse_url("http://localhost:8080").is_ok());
+        assert!(parse_url("file:///etc/passwd").is_err());
+        assert!(parse_url("not a url").is_err());
+    }
+
+    #[test]
+    fn test_decode_body() {
+        let json = decode_body(br#"{"a":1}"#.to_vec(), HttpResponseType::Json).unwrap();
+        assert!(matches!(json, HttpResponseBody::Json { value } if value["a"] == 1));
+        let empty = decode_body(Vec::new(), HttpResponseType::Json).unwrap();
+        assert!(matches!(empty, HttpResponseBody::Json { value } if value.is_null()));
+        assert!(decode_body(b"{".to_vec(), HttpResponseType::Json).is_err());
+        assert!(decode_body(vec![0xff], HttpResponseType::Text).is_err());
+        assert!(matches!(
+            decode_body(vec![0xff], HttpResponseType::Bytes).unwrap(),
+            HttpResponseBody::Bytes { bytes } if bytes == [0xff]
+        ));
+    }
+}
@@ -0,0 +1,237 @@
+//! Windows taskbar Jump List.
+//!
+//! The Windows counterpart to the macOS Dock menu: right-clicking the taskbar
+//! button lists recent documents (from `recent_documents`) and task shortcuts.
+//! Jump List entries can only launch the app, so each one relaunches it with
+//! an argument, which the single-instance plugin hands to the running app:
+//!
+//! - `--jump-list-task=<id>` emits `menu-item-selected`, running the app menu
+//!   action with that id
+//! - `--recent-document=<path>` emits `recent-document-selected`
+//!
+//! Events are sent through `event_queue`, so arguments received at launch are
+//! delivered once the frontend is ready.
+//! On other platforms the list itself isn't built, but arguments are still handled.
+
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+use super::event_queue;
+use super::window::MAIN_WINDOW_LABEL;
+use crate::types::{AppError, JumpListTask, MenuItemSelected, RecentDocumentSelected};
+
+/// Argument prefix for task entries.
+const TASK_ARG_PREFIX: &str = "--jump-list-task=";
+
+/// Argument prefix for recent document entries.
+const RECENT_DOCUMENT_ARG_PREFIX: &str = "--recent-document=";
+
+/// Tasks shown in the Jump List, in order.
+static JUMP_LIST_TASKS: Mutex<Vec<JumpListTask>> = Mutex::new(Vec::new());
+
+/// An entry chosen from the Jump List, parsed from the launch arguments.
+#[derive(Debug, Clone, PartialEq)]
+enum JumpListActivation {
+    Task(String),
+    RecentDocument(String),
+}
+
+/// Finds a Jump List activation in command-line arguments.
+fn parse_activation(args: &[String]) -> Option<JumpListActivation> {
+    args.iter().find_map(|arg| {
+        if let Some(id) = arg.strip_prefix(TASK_ARG_PREFIX) {
+            Some(JumpListActivation::Task(id.to_string()))
+        } else {
+            arg.strip_prefix(RECENT_DOCUMENT_ARG_PREFIX)
+                .map(|path| JumpListActivation::RecentDocument(path.to_string()))
+        }
+    })
+}
+
+fn emit_activation(app: &AppHandle, activation: JumpListActivation) {
+    log::debug!("Jump List entry activated: {activation:?}");
+    match activation {
+        JumpListActivation::Task(id) => {
+            event_queue::emit_to(app, MAIN_WINDOW_LABEL, MenuItemSelected { id })
+        }
+        JumpListActivation::RecentDocument(path) => {
+            event_queue::emit_to(app, MAIN_WINDOW_LABEL, RecentDocumentSelected { path })
+        }
+    }
+}
+
+/// Handles Jump List arguments from a launch or relaunch. Called with the
+/// process arguments during setup, and from the single-instance callback.
+pub fn handle_launch_args(app: &AppHandle, args: &[String]) {
+    let Some(activation) = parse_activation(args) else {
+        return;
+    };
+
+    emit_activation(app, activation);
+}
+
+/// Adds a task to the Jump List, replacing any task with the same id.
+pub fn register_jump_list_task(app: &AppHandle, task: JumpListTask) {
+    if let Ok(mut tasks) = JUMP_LIST_TASKS.lock() {
+        match tasks.iter_mut().find(|t| t.id == task.id) {
+            Some(existing) => *existing = task,
+            None => tasks.push(task),
+        }
+    }
+    refresh_jump_list(app);
+}
+
+/// Replaces the Jump List tasks. Does nothing outside Windows.
+#[tauri::command]
+#[specta::specta]
+pub fn set_jump_list_tasks(app: AppHandle, tasks: Vec<JumpListTask>) -> Result<(), AppError> {
+    *JUMP_LIST_TASKS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock Jump List tasks: {e}")))? = tasks;
+    refresh_jump_list(&app);
+    Ok(())
+}
+
+/// Rebuilds the Jump List from the current tasks and recent documents.
+#[cfg(target_os = "windows")]
+pub fn refresh_jump_list(app: &AppHandle) {
+    let tasks = JUMP_LIST_TASKS
+        .lock()
+        .map(|tasks| tasks.clone())
+        .unwrap_or_default();
+    let documents = match super::recent_documents::get_recent_documents(app.clone()) {
+        Ok(documents) => documents,
+        Err(e) => {
+            log::warn!("Failed to load recent documents for Jump List: {e}");
+            Vec::new()
+        }
+    };
+
+    if let Err(e) = windows_jump_list::commit(&tasks, &documents) {
+        log::error!("Failed to update Jump List: {e}");
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn refresh_jump_list(_app: &AppHandle) {}
+
+#[cfg(target_os = "windows")]
+mod windows_jump_list {
+    use std::path::Path;
+    use windows::core::{Interface, HSTRING, PROPVARIANT};
+    use windows::Win32::Storage::EnhancedStorage::PKEY_Title;
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED,
+    };
+    use windows::Win32::UI::Shell::Common::{IObjectArray, IObjectCollection};
+    use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
+    use windows::Win32::UI::Shell::{
+        DestinationList, EnumerableObjectCollection, ICustomDestinationList, IShellLinkW, ShellLink,
+    };
+
+    use super::{RECENT_DOCUMENT_ARG_PREFIX, TASK_ARG_PREFIX};
+    use crate::commands::recent_documents::menu_label;
+    use crate::types::{AppError, JumpListTask};
+
+    /// Title of the Jump List category listing recent documents.
+    const RECENT_CATEGORY: &str = "Recent";
+
+    /// Creates a link that relaunches this executable with `args`.
+    fn shell_link(
+        exe: &Path,
+        args: &str,
+        title: &str,
+        description: Option<&str>,
+    ) -> windows::core::Result<IShellLinkW> {
+        // SAFETY: COM is initialized by `commit` and every call receives valid strings.
+        unsafe {
+            let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+            link.SetPath(&HSTRING::from(exe.as_os_str()))?;
+            link.SetArguments(&HSTRING::from(args))?;
+            link.SetIconLocation(&HSTRING::from(exe.as_os_str()), 0)?;
+            if let Some(description) = description {
+                link.SetDescription(&HSTRING::from(description))?;
+            }
+
+            // The Jump List shows the link's title property, not its file name
+            let store: IPropertyStore = link.cast()?;
+            store.SetValue(&PKEY_Title, &PROPVARIANT::from(title))?;
+            store.Commit()?;
+            Ok(link)
+        }
+    }
+
+    /// Replaces the app's Jump List with the given tasks and documents.
+    pub fn commit(tasks: &[JumpListTask], documents: &[String]) -> Result<(), AppError> {
+        let exe = std::env::current_exe()
+            .map_err(|e| AppError::internal(format!("Failed to get executable path: {e}")))?;
+        build_list(&exe, tasks, documents).map_err(|e| AppError::internal(e.to_string()))
+    }
+
+    fn build_list(
+        exe: &Path,
+        tasks: &[JumpListTask],
+        documents: &[String],
+    ) -> windows::core::Result<()> {
+        // SAFETY: COM objects are created and used on this thread only.
+        // Re-initializing COM on a thread that already has it is harmless.
+        unsafe {
+            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+            let list: ICustomDestinationList =
+                CoCreateInstance(&DestinationList, None, CLSCTX_INPROC_SERVER)?;
+            let mut max_slots = 0u32;
+            let _removed: IObjectArray = list.BeginList(&mut max_slots)?;
+
+            if !documents.is_empty() {
+                let collection: IObjectCollection =
+                    CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)?;
+                for path in documents.iter().take(max_slots as usize) {
+                    let args = format!("{RECENT_DOCUMENT_ARG_PREFIX}\"{path}\"");
+                    let link = shell_link(exe, &args, &menu_label(path), Some(path))?;
+                    collection.AddObject(&link)?;
+                }
+                list.AppendCategory(
+                    &HSTRING::from(RECENT_CATEGORY),
+                    &collection.cast::<IObjectArray>()?,
+                )?;
+            }
+
+            if !tasks.is_empty() {
+                let collection: IObjectCollection =
+                    CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)?;
+                for task in tasks {
+                    let args = format!("{TASK_ARG_PREFIX}{}", task.id);
+                    let link = shell_link(exe, &args, &task.title, task.description.as_deref())?;
+                    collection.AddObject(&link)?;
+                }
+                list.AddUserTasks(&collection.cast::<IObjectArray>()?)?;
+            }
+
+            list.CommitList()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_activation() {
+        assert_eq!(
+            parse_activation(&args(&["app.exe", "--jump-list-task=new-quick-entry"])),
+            Some(JumpListActivation::Task("new-quick-entry".to_string()))
+        );
+        assert_eq!(
+            parse_activation(&args(&["app.exe", "--recent-document=C:\\Notes\\a b.md"])),
+            Some(JumpListActivation::RecentDocument(
+                "C:\\Notes\\a b.md".to_string()
+            ))
+        );
+        assert_eq!(parse_activation(&args(&["app.exe"])), None);
+    }
+}
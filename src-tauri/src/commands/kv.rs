@@ -0,0 +1,102 @@
+//! Key-value store commands.
+//!
+//! For small bits of app state (last-used options, dismissed hints, sync
+//! cursors) that don't warrant their own file or table. Values are any JSON
+//! and are stored in the `kv` table of the app database.
+
+use serde_json::Value;
+use tauri::State;
+
+use crate::db::{kv, Database};
+use crate::types::{validate_string_input, AppError, KvEntry};
+
+const MAX_KEY_LENGTH: usize = 256;
+/// Maximum size of a value, serialized as JSON.
+const MAX_VALUE_SIZE: usize = 64 * 1024;
+
+fn validate_key(key: &str) -> Result<(), AppError> {
+    if key.is_empty() {
+        return Err(AppError::Validation {
+            message: "Key cannot be empty".to_string(),
+        });
+    }
+    validate_string_input(key, MAX_KEY_LENGTH, "Key")
+        .map_err(|message| AppError::Validation { message })
+}
+
+fn parse_value(key: &str, json: &str) -> Result<Value, AppError> {
+    serde_json::from_str(json).map_err(|e| AppError::Serialization {
+        message: format!("Stored value for '{key}' is invalid: {e}"),
+    })
+}
+
+/// Gets the value stored under `key`, or null if there isn't one.
+#[tauri::command]
+#[specta::specta]
+pub fn kv_get(database: State<'_, Database>, key: String) -> Result<Option<Value>, AppError> {
+    validate_key(&key)?;
+    database
+        .with_connection(|conn| kv::get(conn, &key))?
+        .map(|json| parse_value(&key, &json))
+        .transpose()
+}
+
+/// Stores `value` under `key`, replacing any existing value. Values are
+/// limited to 64KB of JSON.
+#[tauri::command]
+#[specta::specta]
+pub fn kv_set(database: State<'_, Database>, key: String, value: Value) -> Result<(), AppError> {
+    validate_key(&key)?;
+    let json = serde_json::to_string(&value)?;
+    if json.len() > MAX_VALUE_SIZE {
+        return Err(AppError::Validation {
+            message: format!("Value too large (max {MAX_VALUE_SIZE} bytes)"),
+        });
+    }
+
+    database.with_connection(|conn| kv::set(conn, &key, &json))?;
+    log::debug!("Stored kv entry '{key}' ({} bytes)", json.len());
+    Ok(())
+}
+
+/// Removes `key`. Returns whether it existed.
+#[tauri::command]
+#[specta::specta]
+pub fn kv_delete(database: State<'_, Database>, key: String) -> Result<bool, AppError> {
+    validate_key(&key)?;
+    database.with_connection(|conn| kv::delete(conn, &key))
+}
+
+/// Lists entries whose keys start with `prefix` (all entries if omitted),
+/// sorted by key.
+#[tauri::command]
+#[specta::specta]
+pub fn kv_list(
+    database: State<'_, Database>,
+    prefix: Option<String>,
+) -> Result<Vec<KvEntry>, AppError> {
+    let prefix = prefix.unwrap_or_default();
+    validate_string_input(&prefix, MAX_KEY_LENGTH, "Prefix")
+        .map_err(|message| AppError::Validation { message })?;
+
+    database
+        .with_connection(|conn| kv::list(conn, &prefix))?
+        .into_iter()
+        .map(|(key, json)| {
+            let value = parse_value(&key, &json)?;
+            Ok(KvEntry { key, value })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_key() {
+        assert!(validate_key("ui.sidebar_width").is_ok());
+        assert!(validate_key("").is_err());
+        assert!(validate_key(&"k".repeat(MAX_KEY_LENGTH + 1)).is_err());
+    }
+}
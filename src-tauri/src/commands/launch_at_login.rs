@@ -0,0 +1,170 @@
+//! Launching the app when the user logs in.
+//!
+//! Uses each platform's own mechanism, so the setting shows up where users
+//! expect to manage it:
+//!
+//! - macOS 13+: `SMAppService.mainAppService`, listed under Login Items in
+//!   System Settings. Older versions fall back to a LaunchAgent
+//! - Windows: the `Run` key under `HKEY_CURRENT_USER`, listed in Task
+//!   Manager's Startup apps
+//! - Linux: an XDG autostart entry in `~/.config/autostart`
+//!
+//! The OS is the source of truth, since users can turn the setting off
+//! outside the app; `get_launch_at_login` always asks it. The last choice
+//! made in the app is also saved as the `launch_at_login` preference.
+
+#[cfg(desktop)]
+use auto_launch::{AutoLaunch, AutoLaunchBuilder};
+use tauri::AppHandle;
+#[cfg(target_os = "linux")]
+use tauri::Manager;
+
+use super::preferences::{load_preferences, save_preferences};
+
+use crate::types::AppError;
+
+/// Entry for the registry, XDG autostart or LaunchAgent backends.
+#[cfg(desktop)]
+fn auto_launch(app: &AppHandle) -> Result<AutoLaunch, AppError> {
+    let exe = std::env::current_exe()
+        .and_then(|exe| exe.canonicalize())
+        .map_err(|e| AppError::internal(format!("Failed to find the app executable: {e}")))?;
+
+    // An AppImage's executable is in a temporary mount; launch the image itself
+    #[cfg(target_os = "linux")]
+    let exe = app
+        .env()
+        .appimage
+        .map(std::path::PathBuf::from)
+        .unwrap_or(exe);
+
+    AutoLaunchBuilder::new()
+        .set_app_name(&app.package_info().name)
+        .set_app_path(&exe.to_string_lossy())
+        .set_use_launch_agent(true)
+        .build()
+        .map_err(|e| AppError::internal(format!("Failed to set up launch at login: {e}")))
+}
+
+#[cfg(target_os = "macos")]
+mod login_item {
+    use objc2::msg_send;
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyClass, AnyObject};
+    use objc2_foundation::NSError;
+
+    use crate::types::AppError;
+
+    #[link(name = "ServiceManagement", kind = "framework")]
+    extern "C" {}
+
+    /// `SMAppServiceStatusEnabled`
+    const STATUS_ENABLED: isize = 1;
+    /// `SMAppServiceStatusRequiresApproval`: registered, but the user has to
+    /// allow it in System Settings
+    const STATUS_REQUIRES_APPROVAL: isize = 2;
+
+    /// The app's login item service, or None before macOS 13.
+    fn main_app_service() -> Option<Retained<AnyObject>> {
+        let class = AnyClass::get(c"SMAppService")?;
+        unsafe { msg_send![class, mainAppService] }
+    }
+
+    pub fn is_available() -> bool {
+        main_app_service().is_some()
+    }
+
+    pub fn is_enabled() -> Result<bool, AppError> {
+        let service = main_app_service()
+            .ok_or_else(|| AppError::internal("Login items need macOS 13 or later"))?;
+        let status: isize = unsafe { msg_send![&service, status] };
+        Ok(status == STATUS_ENABLED || status == STATUS_REQUIRES_APPROVAL)
+    }
+
+    pub fn set_enabled(enabled: bool) -> Result<(), AppError> {
+        let service = main_app_service()
+            .ok_or_else(|| AppError::internal("Login items need macOS 13 or later"))?;
+        let result: Result<(), Retained<NSError>> = if enabled {
+            unsafe { msg_send![&service, registerAndReturnError: _] }
+        } else {
+            unsafe { msg_send![&service, unregisterAndReturnError: _] }
+        };
+        result.map_err(|e| AppError::internal(e.localizedDescription().to_string()))
+    }
+}
+
+fn is_enabled(app: &AppHandle) -> Result<bool, AppError> {
+    #[cfg(target_os = "macos")]
+    if login_item::is_available() {
+        return login_item::is_enabled();
+    }
+
+    #[cfg(desktop)]
+    {
+        auto_launch(app)?
+            .is_enabled()
+            .map_err(|e| AppError::internal(format!("Failed to check launch at login: {e}")))
+    }
+
+    #[cfg(mobile)]
+    {
+        let _ = app;
+        Err(AppError::internal(
+            "Launch at login is not supported on mobile",
+        ))
+    }
+}
+
+fn set_enabled(app: &AppHandle, enabled: bool) -> Result<(), AppError> {
+    #[cfg(target_os = "macos")]
+    if login_item::is_available() {
+        return login_item::set_enabled(enabled);
+    }
+
+    #[cfg(desktop)]
+    {
+        let auto_launch = auto_launch(app)?;
+        let result = if enabled {
+            auto_launch.enable()
+        } else {
+            auto_launch.disable()
+        };
+        result.map_err(|e| AppError::internal(e.to_string()))
+    }
+
+    #[cfg(mobile)]
+    {
+        let _ = (app, enabled);
+        Err(AppError::internal(
+            "Launch at login is not supported on mobile",
+        ))
+    }
+}
+
+/// Whether the app is set to launch when the user logs in, as the OS
+/// currently has it.
+#[tauri::command]
+#[specta::specta]
+pub fn get_launch_at_login(app: AppHandle) -> Result<bool, AppError> {
+    is_enabled(&app)
+}
+
+/// Turns launching at login on or off and saves the choice to preferences.
+/// On macOS the user may still need to allow the login item in System
+/// Settings.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_launch_at_login(app: AppHandle, enabled: bool) -> Result<(), AppError> {
+    set_enabled(&app, enabled)
+        .map_err(|e| AppError::internal(format!("Failed to change launch at login: {e}")))?;
+
+    let mut preferences = load_preferences(app.clone()).await?;
+    preferences.launch_at_login = enabled;
+    save_preferences(app, preferences).await?;
+
+    log::info!(
+        "Launch at login turned {}",
+        if enabled { "on" } else { "off" }
+    );
+    Ok(())
+}
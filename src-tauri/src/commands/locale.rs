@@ -0,0 +1,311 @@
+//! System locale and formatting settings.
+//!
+//! `navigator.language` in the webview only reflects the language, and the
+//! webview's `Intl` defaults don't follow the user's regional settings (a
+//! 24-hour clock with an English UI, or Monday as the first day of the week
+//! in the US). `get_locale_info` reads them from the OS instead: NSLocale on
+//! macOS, `GetLocaleInfoEx` on Windows and `locale` on Linux.
+
+use crate::types::{AppError, LocaleInfo, MeasurementSystem, Weekday};
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Monday,
+    Weekday::Tuesday,
+    Weekday::Wednesday,
+    Weekday::Thursday,
+    Weekday::Friday,
+    Weekday::Saturday,
+    Weekday::Sunday,
+];
+
+/// The formatting settings read per platform.
+#[cfg_attr(mobile, allow(dead_code))]
+struct Formats {
+    first_day_of_week: Weekday,
+    uses_24_hour_clock: bool,
+    decimal_separator: String,
+    measurement_system: MeasurementSystem,
+}
+
+/// Splits a locale tag into its language and region, e.g. `("zh", "CN")` for
+/// `"zh-Hans-CN"`. Accepts POSIX locales like `"en_GB.UTF-8"` too.
+fn parse_locale_tag(tag: &str) -> (String, Option<String>) {
+    // Drop a POSIX encoding and modifier
+    let tag = tag.split(['.', '@']).next().unwrap_or_default();
+    let mut subtags = tag.split(['-', '_']);
+    let language = subtags
+        .next()
+        .filter(|language| {
+            (2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_alphabetic())
+        })
+        // "C" and "POSIX"
+        .map_or_else(|| "en".to_string(), str::to_ascii_lowercase);
+    // Two letters, or three digits for a UN M.49 area like "419"
+    let region = subtags
+        .find(|subtag| {
+            (subtag.len() == 2 && subtag.chars().all(|c| c.is_ascii_alphabetic()))
+                || (subtag.len() == 3 && subtag.chars().all(|c| c.is_ascii_digit()))
+        })
+        .map(str::to_ascii_uppercase);
+    (language, region)
+}
+
+/// The weekday numbered from 1 for Sunday, as macOS and glibc do.
+#[cfg_attr(not(any(target_os = "macos", target_os = "linux")), allow(dead_code))]
+fn weekday_from_sunday(day: usize) -> Option<Weekday> {
+    (1..=7).contains(&day).then(|| WEEKDAYS[(day + 5) % 7])
+}
+
+/// Whether a CLDR or Windows time pattern uses a 24-hour clock: no `h` (1-12)
+/// or `K` (0-11) outside quoted literals, as in `"HH 'h' mm"`.
+#[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+fn is_24_hour_pattern(pattern: &str) -> bool {
+    pattern
+        .split('\'')
+        .step_by(2)
+        .all(|part| !part.contains(['h', 'K']))
+}
+
+/// Returns the OS's language, region and formatting settings, so dates and
+/// numbers can be formatted like the rest of the system.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_locale_info() -> Result<LocaleInfo, AppError> {
+    let locale = tauri_plugin_os::locale()
+        .ok_or_else(|| AppError::internal("Failed to get the system locale"))?;
+    let (language, region) = parse_locale_tag(&locale);
+    let formats = platform::formats()?;
+
+    Ok(LocaleInfo {
+        locale,
+        language,
+        region,
+        first_day_of_week: formats.first_day_of_week,
+        uses_24_hour_clock: formats.uses_24_hour_clock,
+        decimal_separator: formats.decimal_separator,
+        measurement_system: formats.measurement_system,
+    })
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use objc2_foundation::{
+        NSCalendar, NSDateFormatter, NSLocale, NSLocaleMeasurementSystem, NSString,
+    };
+
+    use super::{is_24_hour_pattern, weekday_from_sunday, Formats};
+    use crate::types::{AppError, MeasurementSystem, Weekday};
+
+    pub fn formats() -> Result<Formats, AppError> {
+        let locale = NSLocale::currentLocale();
+
+        // SAFETY: NSLocaleMeasurementSystem is an immutable constant.
+        let measurement = locale
+            .objectForKey(unsafe { NSLocaleMeasurementSystem })
+            .and_then(|value| value.downcast::<NSString>().ok())
+            .map(|value| value.to_string());
+        let measurement_system = match measurement.as_deref() {
+            Some("U.S.") => MeasurementSystem::UnitedStates,
+            Some("U.K.") => MeasurementSystem::UnitedKingdom,
+            _ => MeasurementSystem::Metric,
+        };
+
+        // "j" is the locale's preferred hour format, e.g. "h a" or "HH"
+        let hour_format = NSDateFormatter::dateFormatFromTemplate_options_locale(
+            &NSString::from_str("j"),
+            0,
+            Some(&locale),
+        )
+        .ok_or_else(|| AppError::internal("Failed to get the hour format"))?;
+
+        Ok(Formats {
+            first_day_of_week: weekday_from_sunday(NSCalendar::currentCalendar().firstWeekday())
+                .unwrap_or(Weekday::Sunday),
+            uses_24_hour_clock: is_24_hour_pattern(&hour_format.to_string()),
+            decimal_separator: locale.decimalSeparator().to_string(),
+            measurement_system,
+        })
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::core::PCWSTR;
+    use windows::Win32::Globalization::{
+        GetLocaleInfoEx, LOCALE_IFIRSTDAYOFWEEK, LOCALE_IMEASURE, LOCALE_SDECIMAL,
+        LOCALE_STIMEFORMAT,
+    };
+
+    use super::{is_24_hour_pattern, Formats, WEEKDAYS};
+    use crate::types::{AppError, MeasurementSystem, Weekday};
+
+    /// Reads a setting of the user's default locale, which includes their
+    /// changes in Region settings.
+    fn locale_info(kind: u32) -> Result<String, AppError> {
+        // SAFETY: a null locale name is LOCALE_NAME_USER_DEFAULT. Without a
+        // buffer, this only returns the length needed.
+        let len = unsafe { GetLocaleInfoEx(PCWSTR::null(), kind, None) };
+        if len <= 0 {
+            return Err(AppError::internal(format!(
+                "Failed to read locale setting {kind}"
+            )));
+        }
+        let mut buffer = vec![0u16; len as usize];
+        // SAFETY: as above, with a buffer of the length returned.
+        let len = unsafe { GetLocaleInfoEx(PCWSTR::null(), kind, Some(&mut buffer)) };
+        if len <= 0 {
+            return Err(AppError::internal(format!(
+                "Failed to read locale setting {kind}"
+            )));
+        }
+        // The length includes the terminating null
+        Ok(String::from_utf16_lossy(&buffer[..len as usize - 1]))
+    }
+
+    pub fn formats() -> Result<Formats, AppError> {
+        // 0 for Monday through 6 for Sunday
+        let first_day_of_week = locale_info(LOCALE_IFIRSTDAYOFWEEK)?
+            .parse::<usize>()
+            .ok()
+            .and_then(|day| WEEKDAYS.get(day).copied())
+            .unwrap_or(Weekday::Monday);
+        // 0 for metric, 1 for US
+        let measurement_system = match locale_info(LOCALE_IMEASURE)?.as_str() {
+            "1" => MeasurementSystem::UnitedStates,
+            _ => MeasurementSystem::Metric,
+        };
+
+        Ok(Formats {
+            first_day_of_week,
+            uses_24_hour_clock: is_24_hour_pattern(&locale_info(LOCALE_STIMEFORMAT)?),
+            decimal_separator: locale_info(LOCALE_SDECIMAL)?,
+            measurement_system,
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::process::Command;
+
+    use super::{weekday_from_sunday, Formats};
+    use crate::types::{AppError, MeasurementSystem, Weekday};
+
+    /// Whether a strftime time format uses a 24-hour clock.
+    fn is_24_hour_format(format: &str) -> bool {
+        !["%I", "%l", "%r", "%p"]
+            .iter()
+            .any(|directive| format.contains(directive))
+    }
+
+    /// Parses the output of `locale decimal_point t_fmt first_weekday
+    /// measurement`, one value per line. `first_weekday` counts from Sunday in
+    /// every glibc locale.
+    pub(super) fn parse_locale_output(output: &str) -> Result<Formats, AppError> {
+        let values: Vec<&str> = output.lines().collect();
+        let [decimal_point, time_format, first_weekday, measurement] = values[..] else {
+            return Err(AppError::internal(format!(
+                "Unexpected locale output: {output}"
+            )));
+        };
+
+        Ok(Formats {
+            first_day_of_week: first_weekday
+                .parse()
+                .ok()
+                .and_then(weekday_from_sunday)
+                .unwrap_or(Weekday::Monday),
+            uses_24_hour_clock: is_24_hour_format(time_format),
+            decimal_separator: decimal_point.to_string(),
+            // 1 for metric, 2 for US
+            measurement_system: if measurement == "2" {
+                MeasurementSystem::UnitedStates
+            } else {
+                MeasurementSystem::Metric
+            },
+        })
+    }
+
+    pub fn formats() -> Result<Formats, AppError> {
+        let output = Command::new("locale")
+            .args(["decimal_point", "t_fmt", "first_weekday", "measurement"])
+            .output()
+            .map_err(|e| AppError::internal(format!("Failed to run locale: {e}")))?;
+        if !output.status.success() {
+            return Err(AppError::internal(format!(
+                "locale failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        parse_locale_output(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+#[cfg(mobile)]
+mod platform {
+    use super::Formats;
+    use crate::types::AppError;
+
+    pub fn formats() -> Result<Formats, AppError> {
+        Err(AppError::internal(
+            "Locale info isn't supported on this platform",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_locale_tag() {
+        assert_eq!(
+            parse_locale_tag("en-GB"),
+            ("en".to_string(), Some("GB".to_string()))
+        );
+        assert_eq!(
+            parse_locale_tag("en_us.UTF-8@euro"),
+            ("en".to_string(), Some("US".to_string()))
+        );
+        assert_eq!(
+            parse_locale_tag("zh-Hans-CN"),
+            ("zh".to_string(), Some("CN".to_string()))
+        );
+        assert_eq!(
+            parse_locale_tag("es-419"),
+            ("es".to_string(), Some("419".to_string()))
+        );
+        assert_eq!(parse_locale_tag("fr"), ("fr".to_string(), None));
+        assert_eq!(parse_locale_tag("C"), ("en".to_string(), None));
+    }
+
+    #[test]
+    fn test_weekday_and_hour_patterns() {
+        assert_eq!(weekday_from_sunday(1), Some(Weekday::Sunday));
+        assert_eq!(weekday_from_sunday(2), Some(Weekday::Monday));
+        assert_eq!(weekday_from_sunday(7), Some(Weekday::Saturday));
+        assert_eq!(weekday_from_sunday(0), None);
+
+        assert!(is_24_hour_pattern("HH:mm:ss"));
+        assert!(is_24_hour_pattern("HH 'h' mm"));
+        assert!(!is_24_hour_pattern("h:mm:ss tt"));
+        assert!(!is_24_hour_pattern("K a"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_locale_output() {
+        let formats = platform::parse_locale_output(",\n%T\n2\n1\n").unwrap();
+        assert_eq!(formats.first_day_of_week, Weekday::Monday);
+        assert!(formats.uses_24_hour_clock);
+        assert_eq!(formats.decimal_separator, ",");
+        assert_eq!(formats.measurement_system, MeasurementSystem::Metric);
+
+        let formats = platform::parse_locale_output(".\n%r\n1\n2\n").unwrap();
+        assert_eq!(formats.first_day_of_week, Weekday::Sunday);
+        assert!(!formats.uses_24_hour_clock);
+        assert_eq!(formats.measurement_system, MeasurementSystem::UnitedStates);
+
+        assert!(platform::parse_locale_output("").is_err());
+    }
+}
@@ -0,0 +1,421 @@
+//! Logging setup and log file management.
+//!
+//! The log plugin is registered at the start of `setup()` rather than on the
+//! builder, because its rotation settings come from preferences, which can only
+//! be read once the app's data directory is known.
+//!
+//! The plugin itself passes every level through; what's actually logged is set
+//! with `log::set_max_level`, so `set_log_level` can turn on debug logging for
+//! a support session without a rebuild or restart.
+//!
+//! With the `Json` log format, the log file gets one JSON object per line
+//! (see `json_line`) for log ingestion tools, while stdout stays readable.
+//!
+//! `follow_logs` lets the frontend show the log in an in-app console. It
+//! streams the recent backlog, then new lines as they're written, over a
+//! channel (see `commands::streams`).
+
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::ipc::Channel;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Wry};
+use tauri_plugin_log::{RotationStrategy, Target, TargetKind};
+
+use super::preferences::{
+    load_log_format, load_log_level, load_log_limits, load_preferences, save_preferences,
+};
+use super::streams::{self, Pacing, StreamSender};
+use crate::types::{AppError, LogFormat, LogLevel, StreamMessage};
+
+const BYTES_PER_MB: u128 = 1024 * 1024;
+
+/// Most backlog lines `follow_logs` sends.
+const MAX_BACKLOG_LINES: u32 = 10_000;
+
+/// Lines per message when `follow_logs` sends the backlog.
+const BACKLOG_BATCH_LINES: usize = 500;
+
+/// How often followers check the log file for new lines.
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Rotation for log files: start a new file at `max_file_size_mb` and keep
+/// `retention_count` old ones, or only the current file for 0.
+fn rotation(max_file_size_mb: u32, retention_count: u32) -> (u128, RotationStrategy) {
+    let max_file_size = u128::from(max_file_size_mb.max(1)) * BYTES_PER_MB;
+    let strategy = match retention_count {
+        0 => RotationStrategy::KeepOne,
+        count => RotationStrategy::KeepSome(count as usize),
+    };
+    (max_file_size, strategy)
+}
+
+/// Formats a record as a single line of JSON:
+/// `{"timestamp":<unix ms>,"level":"INFO","target":"...","message":"...","fields":{...}}`.
+/// `fields` holds structured values, e.g. from `log::info!(count = 3; "...")`.
+fn json_line(record: &log::Record, message: &std::fmt::Arguments) -> String {
+    struct Fields(serde_json::Map<String, serde_json::Value>);
+
+    impl<'kvs> log::kv::VisitSource<'kvs> for Fields {
+        fn visit_pair(
+            &mut self,
+            key: log::kv::Key<'kvs>,
+            value: log::kv::Value<'kvs>,
+        ) -> Result<(), log::kv::Error> {
+            let value = if let Some(value) = value.to_bool() {
+                serde_json::Value::from(value)
+            } else if let Some(value) = value.to_i64() {
+                serde_json::Value::from(value)
+            } else if let Some(value) = value.to_f64() {
+                serde_json::Value::from(value)
+            } else {
+                serde_json::Value::from(value.to_string())
+            };
+            self.0.insert(key.to_string(), value);
+            Ok(())
+        }
+    }
+
+    let mut fields = Fields(serde_json::Map::new());
+    let _ = record.key_values().visit(&mut fields);
+
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default();
+    serde_json::json!({
+        "timestamp": timestamp,
+        "level": record.level().as_str(),
+        "target": record.target(),
+        "message": message.to_string(),
+        "fields": fields.0,
+    })
+    .to_string()
+}
+
+/// The log file target, in the format from preferences.
+// Only macOS logs to a file by default; add this target elsewhere to do the same
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn file_target(app: &AppHandle) -> Target {
+    // A moved data directory keeps its logs in `logs/`
+    let target = match crate::data_dir::log_dir(app) {
+        Ok(path) => Target::new(TargetKind::Folder {
+            path,
+            file_name: None,
+        }),
+        Err(_) => Target::new(TargetKind::LogDir { file_name: None }),
+    };
+    match load_log_format(app) {
+        LogFormat::Text => target,
+        LogFormat::Json => target.format(|out, message, record| {
+            out.finish(format_args!("{}", json_line(record, message)))
+        }),
+    }
+}
+
+/// Builds the log plugin with the size limit, retention and file format from
+/// preferences.
+pub fn log_plugin(app: &AppHandle) -> TauriPlugin<Wry> {
+    let (max_file_size_mb, retention_count) = load_log_limits(app);
+    let (max_file_size, rotation_strategy) = rotation(max_file_size_mb, retention_count);
+
+    #[allow(unused_mut)]
+    let mut targets = vec![
+        // Always log to stdout for development
+        Target::new(TargetKind::Stdout),
+        // Log to system logs on macOS (appears in Console.app)
+        #[cfg(target_os = "macos")]
+        file_target(app),
+    ];
+    // Log to webview console — excluded on Linux where the WebKitGTK webview
+    // doesn't exist during setup(), causing app.emit() to deadlock on the IPC socket.
+    #[cfg(not(target_os = "linux"))]
+    targets.push(Target::new(TargetKind::Webview));
+
+    tauri_plugin_log::Builder::new()
+        // Filtered by `apply_saved_log_level` instead
+        .level(log::LevelFilter::Trace)
+        .max_file_size(max_file_size)
+        .rotation_strategy(rotation_strategy)
+        .targets(targets)
+        .build()
+}
+
+/// Level used when none is saved: Debug in development, Info in production.
+fn default_log_level() -> LogLevel {
+    if cfg!(debug_assertions) {
+        LogLevel::Debug
+    } else {
+        LogLevel::Info
+    }
+}
+
+fn level_filter(level: LogLevel) -> log::LevelFilter {
+    match level {
+        LogLevel::Error => log::LevelFilter::Error,
+        LogLevel::Warn => log::LevelFilter::Warn,
+        LogLevel::Info => log::LevelFilter::Info,
+        LogLevel::Debug => log::LevelFilter::Debug,
+        LogLevel::Trace => log::LevelFilter::Trace,
+    }
+}
+
+/// Sets the log level from preferences. Called right after the log plugin is
+/// registered, since the plugin sets the level to its own (Trace).
+pub fn apply_saved_log_level(app: &AppHandle) {
+    let level = load_log_level(app).unwrap_or_else(default_log_level);
+    log::set_max_level(level_filter(level));
+}
+
+/// Gets the current log level.
+#[tauri::command]
+#[specta::specta]
+pub fn get_log_level() -> LogLevel {
+    match log::max_level() {
+        // Off isn't offered, so treat it as the most severe level
+        log::LevelFilter::Off | log::LevelFilter::Error => LogLevel::Error,
+        log::LevelFilter::Warn => LogLevel::Warn,
+        log::LevelFilter::Info => LogLevel::Info,
+        log::LevelFilter::Debug => LogLevel::Debug,
+        log::LevelFilter::Trace => LogLevel::Trace,
+    }
+}
+
+/// Changes the log level immediately and saves it to preferences, e.g. to
+/// turn on debug logging for a support session.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_log_level(app: AppHandle, level: LogLevel) -> Result<(), AppError> {
+    log::set_max_level(level_filter(level));
+
+    let mut preferences = load_preferences(app.clone()).await?;
+    preferences.log_level = Some(level);
+    save_preferences(app, preferences).await?;
+
+    log::info!("Log level set to {level:?}");
+    Ok(())
+}
+
+/// Gets the log files, including rotated ones, most recently written first.
+pub fn log_files(app: &AppHandle) -> Result<Vec<PathBuf>, AppError> {
+    let log_dir = crate::data_dir::log_dir(app)?;
+
+    let entries = match std::fs::read_dir(&log_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(AppError::io(format!("Failed to read log directory: {e}"))),
+    };
+
+    let mut files: Vec<(SystemTime, PathBuf)> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+        .filter_map(|path| Some((path.metadata().ok()?.modified().ok()?, path)))
+        .collect();
+    files.sort_by(|(a, _), (b, _)| b.cmp(a));
+    Ok(files.into_iter().map(|(_, path)| path).collect())
+}
+
+/// The last `count` lines of `bytes`, oldest first.
+fn last_lines(bytes: &[u8], count: usize) -> Vec<String> {
+    let contents = String::from_utf8_lossy(bytes);
+    let lines: Vec<&str> = contents.lines().collect();
+    lines[lines.len().saturating_sub(count)..]
+        .iter()
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// The last `count` lines of the current log file, oldest first. Empty if
+/// nothing has been logged to a file yet.
+pub fn last_log_lines(app: &AppHandle, count: usize) -> Result<Vec<String>, AppError> {
+    let Some(path) = log_files(app)?.into_iter().next() else {
+        return Ok(Vec::new());
+    };
+    let bytes =
+        std::fs::read(&path).map_err(|e| AppError::io(format!("Failed to read log file: {e}")))?;
+    Ok(last_lines(&bytes, count))
+}
+
+/// Streams the log to `on_lines` in batches: the last `backlog` lines (at
+/// most 10,000), then lines as they're appended, until `stop_stream` is
+/// called with the returned id.
+#[tauri::command]
+#[specta::specta]
+pub fn follow_logs(
+    app: AppHandle,
+    backlog: u32,
+    on_lines: Channel<StreamMessage<Vec<String>, String>>,
+) -> Result<u32, AppError> {
+    let path = log_files(&app)?.into_iter().next();
+    let bytes = match &path {
+        Some(path) => std::fs::read(path)
+            .map_err(|e| AppError::io(format!("Failed to read log file: {e}")))?,
+        None => Vec::new(),
+    };
+    // Follow from the end of the last complete line, so a line being written
+    // isn't split between the backlog and the first new batch
+    let offset = bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    let backlog = last_lines(&bytes[..offset], backlog.min(MAX_BACKLOG_LINES) as usize);
+
+    let id = streams::start(on_lines, Pacing::Unpaced, move |sender| {
+        for batch in backlog.chunks(BACKLOG_BATCH_LINES) {
+            if !sender.send(batch.to_vec()) {
+                return Ok(());
+            }
+        }
+        follow(&app, sender, path, offset as u64);
+        Ok(())
+    })?;
+    log::debug!("Following logs (stream {id})");
+    Ok(id)
+}
+
+/// Polls the current log file and sends complete new lines until the stream
+/// is stopped or the channel is closed (e.g. the window was reloaded). Starts
+/// over from the top when the file is rotated.
+fn follow(
+    app: &AppHandle,
+    sender: &StreamSender<Vec<String>, String>,
+    mut path: Option<PathBuf>,
+    mut offset: u64,
+) {
+    // Bytes after the last newline, held until the line is finished
+    let mut partial = Vec::new();
+
+    while !sender.is_stopped() {
+        std::thread::sleep(FOLLOW_INTERVAL);
+
+        let Some(current) = log_files(app)
+            .ok()
+            .and_then(|files| files.into_iter().next())
+        else {
+            continue;
+        };
+        let len = current
+            .metadata()
+            .map(|metadata| metadata.len())
+            .unwrap_or(0);
+        // A new file, or the same name truncated by rotation
+        if path.as_ref() != Some(&current) || len < offset {
+            path = Some(current.clone());
+            offset = 0;
+            partial.clear();
+        }
+        if len == offset {
+            continue;
+        }
+
+        match read_from(&current, offset) {
+            Ok(bytes) => {
+                offset += bytes.len() as u64;
+                partial.extend_from_slice(&bytes);
+            }
+            Err(e) => {
+                log::warn!("Failed to read log file while following: {e}");
+                continue;
+            }
+        }
+
+        let Some(end) = partial.iter().rposition(|&b| b == b'\n') else {
+            continue;
+        };
+        let complete: Vec<u8> = partial.drain(..=end).collect();
+        let lines: Vec<String> = String::from_utf8_lossy(&complete)
+            .lines()
+            .map(String::from)
+            .collect();
+        if !sender.send(lines) {
+            break;
+        }
+    }
+}
+
+/// Reads a file from `offset` to the end.
+fn read_from(path: &Path, offset: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = std::fs::File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Gets the total size in bytes of the files in the log directory, including
+/// rotated logs. Saturates at 4 GiB.
+#[tauri::command]
+#[specta::specta]
+pub fn get_log_directory_size(app: AppHandle) -> Result<u32, AppError> {
+    let log_dir = crate::data_dir::log_dir(&app)?;
+
+    let entries = match std::fs::read_dir(&log_dir) {
+        Ok(entries) => entries,
+        // Nothing has been logged to a file yet
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(AppError::io(format!("Failed to read log directory: {e}"))),
+    };
+
+    let total: u64 = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum();
+    Ok(u32::try_from(total).unwrap_or(u32::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_level_round_trip() {
+        for level in [
+            LogLevel::Error,
+            LogLevel::Warn,
+            LogLevel::Info,
+            LogLevel::Debug,
+            LogLevel::Trace,
+        ] {
+            log::set_max_level(level_filter(level));
+            assert_eq!(get_log_level(), level);
+        }
+    }
+
+    #[test]
+    fn test_last_lines() {
+        assert_eq!(last_lines(b"one\ntwo\r\nthree\n", 2), vec!["two", "three"]);
+        assert_eq!(last_lines(b"one\n", 5), vec!["one"]);
+        assert!(last_lines(b"", 5).is_empty());
+    }
+
+    #[test]
+    fn test_json_line() {
+        let record = log::Record::builder()
+            .level(log::Level::Warn)
+            .target("app::sync")
+            .key_values(&("count", 3i64))
+            .build();
+        let line: serde_json::Value =
+            serde_json::from_str(&json_line(&record, &format_args!("Retrying \"upload\"")))
+                .unwrap();
+
+        assert_eq!(line["level"], "WARN");
+        assert_eq!(line["target"], "app::sync");
+        assert_eq!(line["message"], "Retrying \"upload\"");
+        assert_eq!(line["fields"]["count"], 3);
+        assert!(line["timestamp"].as_u64().unwrap() > 0);
+    }
+
+    #[test]
+    fn test_rotation() {
+        let (size, strategy) = rotation(5, 3);
+        assert_eq!(size, 5 * BYTES_PER_MB);
+        assert!(matches!(strategy, RotationStrategy::KeepSome(3)));
+
+        // A zero limit would rotate on every write
+        let (size, strategy) = rotation(0, 0);
+        assert_eq!(size, BYTES_PER_MB);
+        assert!(matches!(strategy, RotationStrategy::KeepOne));
+    }
+}
@@ -0,0 +1,559 @@
+//! App menu building and runtime mutation.
+//!
+//! The app menu is described by a `MenuSpec` (sent from `src/lib/menu.ts` at
+//! startup, or passed to `set_app_menu_from_spec` from Rust) and rebuilt when
+//! the language changes, so items are looked up by id in the live menu on each
+//! call rather than held onto. This lets the frontend (or Rust) reflect app
+//! state in the menu, e.g. a checkmark on "Toggle Left Sidebar".
+//!
+//! Clicks on items built from a spec are forwarded as `menu-item-selected`
+//! events and dispatched by the app menu's handlers.
+//!
+//! Accelerators are checked whenever items are added, and a warning is logged
+//! if two items (including standard items like Copy) share one, since only one
+//! of them would ever fire.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Mutex;
+use tauri::menu::{
+    CheckMenuItem, IsMenuItem, Menu, MenuEvent, MenuItem, MenuItemKind, PredefinedMenuItem, Submenu,
+};
+use tauri::{AppHandle, Wry};
+use tauri_specta::Event;
+
+use super::recent_documents;
+use super::window::MAIN_WINDOW_LABEL;
+use crate::types::{AppError, MenuEntrySpec, MenuItemSelected, MenuRole, MenuSpec};
+
+/// Id of the spec submenu registered as the macOS Window menu, which lists open windows.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub const WINDOW_MENU_ID: &str = "window-menu";
+
+/// IDs of items built from a spec, whose clicks are forwarded to the frontend.
+static FORWARDED_ITEM_IDS: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Accelerators in the app menu as (item id, normalized accelerator) pairs.
+static MENU_ACCELERATORS: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+/// Recursively finds an item by id, along with the submenu containing it
+/// (None for top-level items).
+pub(crate) fn find_item(
+    items: Vec<MenuItemKind<Wry>>,
+    parent: Option<&Submenu<Wry>>,
+    id: &str,
+) -> Option<(Option<Submenu<Wry>>, MenuItemKind<Wry>)> {
+    for item in items {
+        if item.id().as_ref() == id {
+            return Some((parent.cloned(), item));
+        }
+        if let MenuItemKind::Submenu(submenu) = &item {
+            let Ok(children) = submenu.items() else {
+                continue;
+            };
+            if let Some(found) = find_item(children, Some(submenu), id) {
+                return Some(found);
+            }
+        }
+    }
+    None
+}
+
+fn get_app_menu(app: &AppHandle) -> Result<Menu<Wry>, AppError> {
+    app.menu()
+        .ok_or_else(|| AppError::internal("App menu has not been built"))
+}
+
+/// Finds an item in the app menu by id.
+fn get_menu_item(app: &AppHandle, id: &str) -> Result<MenuItemKind<Wry>, AppError> {
+    let menu = get_app_menu(app)?;
+    let items = menu
+        .items()
+        .map_err(|e| AppError::internal(format!("Failed to read menu items: {e}")))?;
+    find_item(items, None, id)
+        .map(|(_, item)| item)
+        .ok_or_else(|| AppError::not_found(format!("Menu item '{id}' not found")))
+}
+
+/// Creates a standard OS-provided menu item.
+fn build_role_item(
+    app: &AppHandle,
+    role: MenuRole,
+    text: Option<&str>,
+) -> tauri::Result<PredefinedMenuItem<Wry>> {
+    match role {
+        MenuRole::Hide => PredefinedMenuItem::hide(app, text),
+        MenuRole::HideOthers => PredefinedMenuItem::hide_others(app, text),
+        MenuRole::ShowAll => PredefinedMenuItem::show_all(app, text),
+        MenuRole::Services => PredefinedMenuItem::services(app, text),
+        MenuRole::Quit => PredefinedMenuItem::quit(app, text),
+        MenuRole::Undo => PredefinedMenuItem::undo(app, text),
+        MenuRole::Redo => PredefinedMenuItem::redo(app, text),
+        MenuRole::Cut => PredefinedMenuItem::cut(app, text),
+        MenuRole::Copy => PredefinedMenuItem::copy(app, text),
+        MenuRole::Paste => PredefinedMenuItem::paste(app, text),
+        MenuRole::SelectAll => PredefinedMenuItem::select_all(app, text),
+        MenuRole::Minimize => PredefinedMenuItem::minimize(app, text),
+        MenuRole::Zoom => PredefinedMenuItem::maximize(app, text),
+        MenuRole::Fullscreen => PredefinedMenuItem::fullscreen(app, text),
+        MenuRole::CloseWindow => PredefinedMenuItem::close_window(app, text),
+    }
+}
+
+// ============================================================================
+// Accelerator Conflicts
+// ============================================================================
+
+/// Normalizes an accelerator so equivalent spellings compare equal, e.g.
+/// `CmdOrCtrl+Shift+K` and `shift+CommandOrControl+k`. `CmdOrCtrl` resolves
+/// to Cmd on macOS and Ctrl elsewhere, like it does when the menu is built.
+fn normalize_accelerator(accelerator: &str, macos: bool) -> String {
+    let mut modifiers = BTreeSet::new();
+    let mut key = String::new();
+    for part in accelerator
+        .split('+')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+    {
+        let modifier = match part.to_ascii_lowercase().as_str() {
+            "cmdorctrl" | "cmdorcontrol" | "commandorctrl" | "commandorcontrol" => {
+                Some(if macos { "Cmd" } else { "Ctrl" })
+            }
+            "cmd" | "command" | "super" | "meta" => Some("Cmd"),
+            "ctrl" | "control" => Some("Ctrl"),
+            "alt" | "option" => Some("Alt"),
+            "shift" => Some("Shift"),
+            _ => None,
+        };
+        match modifier {
+            Some(modifier) => {
+                modifiers.insert(modifier);
+            }
+            None => key = part.to_ascii_uppercase(),
+        }
+    }
+
+    modifiers
+        .into_iter()
+        .chain(std::iter::once(key.as_str()))
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+/// The shortcut the OS gives a standard item, if any. Hide items only exist
+/// on macOS, so they don't take their shortcuts elsewhere.
+fn role_accelerator(role: MenuRole, macos: bool) -> Option<&'static str> {
+    match role {
+        MenuRole::Hide if macos => Some("CmdOrCtrl+H"),
+        MenuRole::HideOthers if macos => Some("CmdOrCtrl+Alt+H"),
+        MenuRole::Quit => Some("CmdOrCtrl+Q"),
+        MenuRole::Undo => Some("CmdOrCtrl+Z"),
+        MenuRole::Redo => Some("CmdOrCtrl+Shift+Z"),
+        MenuRole::Cut => Some("CmdOrCtrl+X"),
+        MenuRole::Copy => Some("CmdOrCtrl+C"),
+        MenuRole::Paste => Some("CmdOrCtrl+V"),
+        MenuRole::SelectAll => Some("CmdOrCtrl+A"),
+        MenuRole::Minimize => Some("CmdOrCtrl+M"),
+        MenuRole::CloseWindow => Some("CmdOrCtrl+W"),
+        _ => None,
+    }
+}
+
+/// Collects (id, normalized accelerator) pairs from entries, recursing into
+/// submenus. Standard items are identified by their role name.
+fn collect_accelerators(entries: &[MenuEntrySpec], accelerators: &mut Vec<(String, String)>) {
+    let macos = cfg!(target_os = "macos");
+    for entry in entries {
+        match entry {
+            MenuEntrySpec::Item {
+                id,
+                accelerator: Some(accelerator),
+                ..
+            } => accelerators.push((id.clone(), normalize_accelerator(accelerator, macos))),
+            MenuEntrySpec::Submenu { items, .. } => collect_accelerators(items, accelerators),
+            MenuEntrySpec::Role { role, .. } => {
+                if let Some(accelerator) = role_accelerator(*role, macos) {
+                    accelerators.push((
+                        format!("{role:?}"),
+                        normalize_accelerator(accelerator, macos),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns each accelerator used by more than one item, with the ids using it.
+fn find_accelerator_conflicts(accelerators: &[(String, String)]) -> Vec<(String, Vec<String>)> {
+    let mut by_accelerator: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for (id, accelerator) in accelerators {
+        by_accelerator
+            .entry(accelerator)
+            .or_default()
+            .push(id.clone());
+    }
+    by_accelerator
+        .into_iter()
+        .filter(|(_, ids)| ids.len() > 1)
+        .map(|(accelerator, ids)| (accelerator.to_string(), ids))
+        .collect()
+}
+
+/// Logs a warning for each conflict involving one of `new_ids`.
+fn warn_accelerator_conflicts(accelerators: &[(String, String)], new_ids: &[String]) {
+    for (accelerator, ids) in find_accelerator_conflicts(accelerators) {
+        if ids.iter().any(|id| new_ids.contains(id)) {
+            log::warn!(
+                "Menu shortcut {accelerator} is assigned to more than one item: {}",
+                ids.join(", ")
+            );
+        }
+    }
+}
+
+/// Records the accelerators of newly added entries and warns about conflicts.
+/// `replace` starts over, for a rebuilt menu.
+fn register_accelerators(entries: &[MenuEntrySpec], replace: bool) {
+    let mut added = Vec::new();
+    collect_accelerators(entries, &mut added);
+    let new_ids: Vec<String> = added.iter().map(|(id, _)| id.clone()).collect();
+
+    let Ok(mut accelerators) = MENU_ACCELERATORS.lock() else {
+        return;
+    };
+    if replace {
+        accelerators.clear();
+    }
+    accelerators.extend(added);
+    warn_accelerator_conflicts(&accelerators, &new_ids);
+}
+
+/// Collects the ids of an item and everything under it.
+fn collect_item_ids(item: &MenuItemKind<Wry>, ids: &mut Vec<String>) {
+    ids.push(item.id().as_ref().to_string());
+    if let MenuItemKind::Submenu(submenu) = item {
+        for child in submenu.items().unwrap_or_default() {
+            collect_item_ids(&child, ids);
+        }
+    }
+}
+
+// ============================================================================
+// Building
+// ============================================================================
+
+/// Builds a menu entry (recursing into submenus), recording the ids of
+/// clickable items in `item_ids`.
+fn build_entry(
+    app: &AppHandle,
+    entry: &MenuEntrySpec,
+    item_ids: &mut Vec<String>,
+) -> Result<Box<dyn IsMenuItem<Wry>>, AppError> {
+    let item: Box<dyn IsMenuItem<Wry>> = match entry {
+        MenuEntrySpec::Item {
+            id,
+            text,
+            accelerator,
+            enabled,
+            checked,
+        } => {
+            let enabled = enabled.unwrap_or(true);
+            item_ids.push(id.clone());
+            match checked {
+                Some(checked) => Box::new(
+                    CheckMenuItem::with_id(
+                        app,
+                        id,
+                        text,
+                        enabled,
+                        *checked,
+                        accelerator.as_deref(),
+                    )
+                    .map_err(|e| {
+                        AppError::internal(format!("Failed to create menu item '{id}': {e}"))
+                    })?,
+                ),
+                None => Box::new(
+                    MenuItem::with_id(app, id, text, enabled, accelerator.as_deref()).map_err(
+                        |e| AppError::internal(format!("Failed to create menu item '{id}': {e}")),
+                    )?,
+                ),
+            }
+        }
+        MenuEntrySpec::Submenu { id, text, items } => {
+            let submenu = match id {
+                Some(id) => Submenu::with_id(app, id, text, true),
+                None => Submenu::new(app, text, true),
+            }
+            .map_err(|e| AppError::internal(format!("Failed to create submenu '{text}': {e}")))?;
+            for child in items {
+                let child = build_entry(app, child, item_ids)?;
+                submenu.append(child.as_ref()).map_err(|e| {
+                    AppError::internal(format!("Failed to build submenu '{text}': {e}"))
+                })?;
+            }
+            Box::new(submenu)
+        }
+        MenuEntrySpec::Role { role, text } => {
+            Box::new(build_role_item(app, *role, text.as_deref()).map_err(|e| {
+                AppError::internal(format!("Failed to create {role:?} menu item: {e}"))
+            })?)
+        }
+        MenuEntrySpec::Separator => Box::new(
+            PredefinedMenuItem::separator(app)
+                .map_err(|e| AppError::internal(format!("Failed to create menu separator: {e}")))?,
+        ),
+    };
+    Ok(item)
+}
+
+/// Builds a menu from a spec and sets it as the app menu, replacing any
+/// existing one. Use this to define the menu from Rust instead of the frontend.
+pub fn set_app_menu_from_spec(app: &AppHandle, spec: &MenuSpec) -> Result<(), AppError> {
+    let menu =
+        Menu::new(app).map_err(|e| AppError::internal(format!("Failed to create menu: {e}")))?;
+    let mut item_ids = Vec::new();
+    for entry in &spec.items {
+        let item = build_entry(app, entry, &mut item_ids)?;
+        menu.append(item.as_ref())
+            .map_err(|e| AppError::internal(format!("Failed to build menu: {e}")))?;
+    }
+
+    #[cfg(target_os = "macos")]
+    if let Ok(items) = menu.items() {
+        if let Some((_, MenuItemKind::Submenu(window_menu))) =
+            find_item(items, None, WINDOW_MENU_ID)
+        {
+            if let Err(e) = window_menu.set_as_windows_menu_for_nsapp() {
+                log::warn!("Failed to set Window menu: {e}");
+            }
+        }
+    }
+
+    app.set_menu(menu)
+        .map_err(|e| AppError::internal(format!("Failed to set app menu: {e}")))?;
+    recent_documents::refresh_recent_documents_menu(app);
+
+    log::debug!("Built app menu with {} item(s)", item_ids.len());
+    register_accelerators(&spec.items, true);
+    *FORWARDED_ITEM_IDS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock menu item registry: {e}")))? =
+        item_ids.into_iter().collect();
+    Ok(())
+}
+
+/// Builds the app menu from a declarative spec, replacing any existing menu.
+/// Item clicks are delivered as `menu-item-selected` events with the item's id.
+#[tauri::command]
+#[specta::specta]
+pub fn create_app_menu(app: AppHandle, spec: MenuSpec) -> Result<(), AppError> {
+    set_app_menu_from_spec(&app, &spec)
+}
+
+/// Enables or disables a menu item or submenu.
+#[tauri::command]
+#[specta::specta]
+pub fn set_menu_item_enabled(app: AppHandle, id: String, enabled: bool) -> Result<(), AppError> {
+    let result = match get_menu_item(&app, &id)? {
+        MenuItemKind::MenuItem(item) => item.set_enabled(enabled),
+        MenuItemKind::Check(item) => item.set_enabled(enabled),
+        MenuItemKind::Icon(item) => item.set_enabled(enabled),
+        MenuItemKind::Submenu(item) => item.set_enabled(enabled),
+        MenuItemKind::Predefined(_) => {
+            return Err(AppError::validation(format!(
+                "Predefined menu item '{id}' can't be disabled"
+            )))
+        }
+    };
+    result.map_err(|e| AppError::internal(format!("Failed to set menu item enabled: {e}")))
+}
+
+/// Sets the checkmark on a check menu item.
+#[tauri::command]
+#[specta::specta]
+pub fn set_menu_item_checked(app: AppHandle, id: String, checked: bool) -> Result<(), AppError> {
+    match get_menu_item(&app, &id)? {
+        MenuItemKind::Check(item) => item
+            .set_checked(checked)
+            .map_err(|e| AppError::internal(format!("Failed to set menu item checked: {e}"))),
+        _ => Err(AppError::validation(format!(
+            "Menu item '{id}' is not a check item"
+        ))),
+    }
+}
+
+/// Changes the title of a menu item or submenu.
+#[tauri::command]
+#[specta::specta]
+pub fn set_menu_item_text(app: AppHandle, id: String, text: String) -> Result<(), AppError> {
+    let result = match get_menu_item(&app, &id)? {
+        MenuItemKind::MenuItem(item) => item.set_text(&text),
+        MenuItemKind::Check(item) => item.set_text(&text),
+        MenuItemKind::Icon(item) => item.set_text(&text),
+        MenuItemKind::Submenu(item) => item.set_text(&text),
+        MenuItemKind::Predefined(item) => item.set_text(&text),
+    };
+    result.map_err(|e| AppError::internal(format!("Failed to set menu item text: {e}")))
+}
+
+/// Inserts an item into a submenu (or the top level of the app menu when
+/// `parent_id` is None) at `position`, or at the end when it's None.
+/// Any entry type can be inserted, including whole submenus.
+/// Clicks are delivered as `menu-item-selected` events with the item's id.
+#[tauri::command]
+#[specta::specta]
+pub fn insert_menu_item(
+    app: AppHandle,
+    parent_id: Option<String>,
+    position: Option<u32>,
+    item: MenuEntrySpec,
+) -> Result<(), AppError> {
+    let menu = get_app_menu(&app)?;
+    let mut item_ids = Vec::new();
+    let new_item = build_entry(&app, &item, &mut item_ids)?;
+    if let Some(id) = item_ids.iter().find(|id| get_menu_item(&app, id).is_ok()) {
+        return Err(AppError::validation(format!(
+            "Menu item '{id}' already exists"
+        )));
+    }
+
+    let result = match parent_id.as_deref() {
+        Some(parent_id) => match get_menu_item(&app, parent_id)? {
+            MenuItemKind::Submenu(submenu) => match position {
+                Some(position) => submenu.insert(new_item.as_ref(), position as usize),
+                None => submenu.append(new_item.as_ref()),
+            },
+            _ => {
+                return Err(AppError::validation(format!(
+                    "Menu item '{parent_id}' is not a submenu"
+                )))
+            }
+        },
+        None => match position {
+            Some(position) => menu.insert(new_item.as_ref(), position as usize),
+            None => menu.append(new_item.as_ref()),
+        },
+    };
+    result.map_err(|e| AppError::internal(format!("Failed to insert menu item: {e}")))?;
+
+    log::debug!("Inserted {} menu item(s)", item_ids.len());
+    register_accelerators(std::slice::from_ref(&item), false);
+    FORWARDED_ITEM_IDS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock menu item registry: {e}")))?
+        .extend(item_ids);
+    Ok(())
+}
+
+/// Removes an item (or submenu) from the app menu by id.
+#[tauri::command]
+#[specta::specta]
+pub fn remove_menu_item(app: AppHandle, id: String) -> Result<(), AppError> {
+    let menu = get_app_menu(&app)?;
+    let items = menu
+        .items()
+        .map_err(|e| AppError::internal(format!("Failed to read menu items: {e}")))?;
+    let (parent, item) = find_item(items, None, &id)
+        .ok_or_else(|| AppError::not_found(format!("Menu item '{id}' not found")))?;
+    let mut removed_ids = Vec::new();
+    collect_item_ids(&item, &mut removed_ids);
+
+    let item: &dyn IsMenuItem<Wry> = match &item {
+        MenuItemKind::MenuItem(item) => item,
+        MenuItemKind::Check(item) => item,
+        MenuItemKind::Icon(item) => item,
+        MenuItemKind::Submenu(item) => item,
+        MenuItemKind::Predefined(item) => item,
+    };
+    match parent {
+        Some(parent) => parent.remove(item),
+        None => menu.remove(item),
+    }
+    .map_err(|e| AppError::internal(format!("Failed to remove menu item: {e}")))?;
+
+    if let Ok(mut ids) = FORWARDED_ITEM_IDS.lock() {
+        ids.retain(|id| !removed_ids.contains(id));
+    }
+    if let Ok(mut accelerators) = MENU_ACCELERATORS.lock() {
+        accelerators.retain(|(id, _)| !removed_ids.contains(id));
+    }
+    Ok(())
+}
+
+/// Forwards clicks on items built from a spec to the main window.
+/// Registered with `on_menu_event` in `lib.rs`.
+pub fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    let id = event.id().as_ref();
+    if recent_documents::handle_menu_event(app, id) {
+        return;
+    }
+    let forwarded = FORWARDED_ITEM_IDS
+        .lock()
+        .map(|ids| ids.contains(id))
+        .unwrap_or(false);
+    if !forwarded {
+        return;
+    }
+
+    let selected = MenuItemSelected { id: id.to_string() };
+    if let Err(e) = selected.emit_to(app, MAIN_WINDOW_LABEL) {
+        log::error!("Failed to emit menu-item-selected: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_accelerator() {
+        assert_eq!(
+            normalize_accelerator("shift+CommandOrControl+k", true),
+            "Cmd+Shift+K"
+        );
+        assert_eq!(
+            normalize_accelerator("CmdOrCtrl+Shift+K", false),
+            "Ctrl+Shift+K"
+        );
+        assert_eq!(normalize_accelerator("Option+Cmd+H", true), "Alt+Cmd+H");
+        assert_eq!(normalize_accelerator("F5", false), "F5");
+    }
+
+    #[test]
+    fn test_find_accelerator_conflicts() {
+        let spec = vec![
+            MenuEntrySpec::Item {
+                id: "find".to_string(),
+                text: "Find".to_string(),
+                accelerator: Some("CmdOrCtrl+F".to_string()),
+                enabled: None,
+                checked: None,
+            },
+            MenuEntrySpec::Submenu {
+                id: None,
+                text: "Edit".to_string(),
+                items: vec![
+                    MenuEntrySpec::Role {
+                        role: MenuRole::Copy,
+                        text: None,
+                    },
+                    MenuEntrySpec::Item {
+                        id: "copy-link".to_string(),
+                        text: "Copy Link".to_string(),
+                        accelerator: Some("cmdorctrl+c".to_string()),
+                        enabled: None,
+                        checked: None,
+                    },
+                ],
+            },
+        ];
+
+        let mut accelerators = Vec::new();
+        collect_accelerators(&spec, &mut accelerators);
+        let conflicts = find_accelerator_conflicts(&accelerators);
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].1, vec!["Copy", "copy-link"]);
+    }
+}
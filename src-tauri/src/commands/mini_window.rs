@@ -0,0 +1,351 @@
+//! Picture-in-picture style mini window mode.
+//!
+//! Shrinks a window into a small frameless view that floats above everything
+//! and sits in a corner of the screen, for timers, now-playing displays and
+//! similar glanceable UIs. The window's previous size, position and chrome are
+//! restored when it leaves mini mode.
+//!
+//! Windows also leave mini mode before they close and when the app exits, so
+//! the window-state plugin never saves the mini size and position for the
+//! next launch.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, LogicalSize, Manager, PhysicalPosition, PhysicalSize, WebviewWindow};
+
+use crate::types::{AppError, ScreenCorner};
+
+/// Logical size of a window in mini mode
+const MINI_WINDOW_WIDTH: f64 = 320.0;
+const MINI_WINDOW_HEIGHT: f64 = 180.0;
+
+/// Logical gap between a mini window and the edges of the screen
+const MINI_WINDOW_MARGIN: f64 = 16.0;
+
+/// What a window looked like before entering mini mode.
+#[derive(Debug, Clone, Copy)]
+struct SavedWindowState {
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+    decorated: bool,
+    resizable: bool,
+    always_on_top: bool,
+}
+
+/// Windows currently in mini mode, by label.
+static MINI_WINDOWS: Mutex<BTreeMap<String, SavedWindowState>> = Mutex::new(BTreeMap::new());
+
+fn get_window(app: &AppHandle, label: &str) -> Result<WebviewWindow, AppError> {
+    app.get_webview_window(label)
+        .ok_or_else(|| AppError::not_found(format!("Window '{label}' not found")))
+}
+
+/// Returns the corner of an area closest to a point.
+/// All values are physical pixels.
+fn nearest_corner(area_pos: (i32, i32), area_size: (i32, i32), point: (i32, i32)) -> ScreenCorner {
+    let left = point.0 < area_pos.0 + area_size.0 / 2;
+    let top = point.1 < area_pos.1 + area_size.1 / 2;
+    match (left, top) {
+        (true, true) => ScreenCorner::TopLeft,
+        (false, true) => ScreenCorner::TopRight,
+        (true, false) => ScreenCorner::BottomLeft,
+        (false, false) => ScreenCorner::BottomRight,
+    }
+}
+
+/// Computes the top-left position of a window snapped into a corner of an area,
+/// inset by `margin`. All values are physical pixels.
+fn corner_position(
+    corner: ScreenCorner,
+    area_pos: (i32, i32),
+    area_size: (i32, i32),
+    window_size: (i32, i32),
+    margin: i32,
+) -> (i32, i32) {
+    let (ax, ay) = area_pos;
+    let (aw, ah) = area_size;
+    let (ww, wh) = window_size;
+
+    let left = ax + margin;
+    let right = ax + aw - ww - margin;
+    let top = ay + margin;
+    let bottom = ay + ah - wh - margin;
+
+    match corner {
+        ScreenCorner::TopLeft => (left, top),
+        ScreenCorner::TopRight => (right, top),
+        ScreenCorner::BottomLeft => (left, bottom),
+        ScreenCorner::BottomRight => (right, bottom),
+    }
+}
+
+/// Moves a mini window into a corner of the work area of the monitor it's on.
+/// Uses the corner nearest the window's center when `corner` is None.
+fn snap_to_corner(
+    window: &WebviewWindow,
+    corner: Option<ScreenCorner>,
+) -> Result<ScreenCorner, AppError> {
+    let monitor = window
+        .current_monitor()
+        .map_err(|e| AppError::internal(format!("Failed to get monitor: {e}")))?
+        .ok_or_else(|| AppError::internal("Window is not on any monitor"))?;
+    let scale_factor = monitor.scale_factor();
+    let work_area = monitor.work_area();
+    let area_pos = (work_area.position.x, work_area.position.y);
+    let area_size = (work_area.size.width as i32, work_area.size.height as i32);
+
+    // Use the mini size rather than querying the window, which may still be resizing
+    let window_size = (
+        (MINI_WINDOW_WIDTH * scale_factor) as i32,
+        (MINI_WINDOW_HEIGHT * scale_factor) as i32,
+    );
+
+    let corner = match corner {
+        Some(corner) => corner,
+        None => {
+            let position = window
+                .outer_position()
+                .map_err(|e| AppError::internal(format!("Failed to get window position: {e}")))?;
+            let center = (
+                position.x + window_size.0 / 2,
+                position.y + window_size.1 / 2,
+            );
+            nearest_corner(area_pos, area_size, center)
+        }
+    };
+
+    let (x, y) = corner_position(
+        corner,
+        area_pos,
+        area_size,
+        window_size,
+        (MINI_WINDOW_MARGIN * scale_factor) as i32,
+    );
+    log::debug!("Snapping mini window to {corner:?} at ({x}, {y})");
+
+    window
+        .set_position(PhysicalPosition::new(x, y))
+        .map_err(|e| AppError::internal(format!("Failed to position window: {e}")))?;
+    Ok(corner)
+}
+
+/// Turns a window into a small always-on-top, frameless view in a screen corner.
+/// Uses the corner nearest the window when `corner` is None. Returns the corner used.
+/// Calling it again on a window already in mini mode just moves it.
+#[tauri::command]
+#[specta::specta]
+pub fn enter_mini_mode(
+    app: AppHandle,
+    label: String,
+    corner: Option<ScreenCorner>,
+) -> Result<ScreenCorner, AppError> {
+    let window = get_window(&app, &label)?;
+
+    let already_mini = MINI_WINDOWS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock mini window state: {e}")))?
+        .contains_key(&label);
+    if already_mini {
+        return snap_to_corner(&window, corner);
+    }
+
+    let saved = SavedWindowState {
+        position: window
+            .outer_position()
+            .map_err(|e| AppError::internal(format!("Failed to get window position: {e}")))?,
+        size: window
+            .inner_size()
+            .map_err(|e| AppError::internal(format!("Failed to get window size: {e}")))?,
+        decorated: window.is_decorated().unwrap_or(true),
+        resizable: window.is_resizable().unwrap_or(true),
+        always_on_top: window.is_always_on_top().unwrap_or(false),
+    };
+
+    log::info!("Window '{label}' entering mini mode");
+
+    // Minimum sizes from tauri.conf.json would stop the window shrinking
+    window
+        .set_min_size(None::<LogicalSize<f64>>)
+        .map_err(|e| AppError::internal(format!("Failed to clear minimum size: {e}")))?;
+    window
+        .set_decorations(false)
+        .map_err(|e| AppError::internal(format!("Failed to remove decorations: {e}")))?;
+    window
+        .set_resizable(false)
+        .map_err(|e| AppError::internal(format!("Failed to set resizable: {e}")))?;
+    window
+        .set_always_on_top(true)
+        .map_err(|e| AppError::internal(format!("Failed to set always on top: {e}")))?;
+    if let Err(e) = window.set_visible_on_all_workspaces(true) {
+        log::warn!("Failed to show mini window on all workspaces: {e}");
+    }
+    window
+        .set_size(LogicalSize::new(MINI_WINDOW_WIDTH, MINI_WINDOW_HEIGHT))
+        .map_err(|e| AppError::internal(format!("Failed to resize window: {e}")))?;
+
+    MINI_WINDOWS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock mini window state: {e}")))?
+        .insert(label, saved);
+
+    snap_to_corner(&window, corner)
+}
+
+/// Snaps a mini window to the nearest screen corner, e.g. after the user drags it.
+/// Returns the corner used.
+#[tauri::command]
+#[specta::specta]
+pub fn snap_mini_window(app: AppHandle, label: String) -> Result<ScreenCorner, AppError> {
+    let window = get_window(&app, &label)?;
+    snap_to_corner(&window, None)
+}
+
+/// Restores a window's size, position and chrome from before it entered mini mode.
+/// Does nothing if the window isn't in mini mode.
+#[tauri::command]
+#[specta::specta]
+pub fn exit_mini_mode(app: AppHandle, label: String) -> Result<(), AppError> {
+    restore(&app, &label)
+}
+
+/// Takes a window out of mini mode before it closes. Called from the run loop
+/// in `lib.rs` once the close is going ahead.
+pub fn handle_close_requested(app: &AppHandle, label: &str) {
+    if let Err(e) = restore(app, label) {
+        log::warn!("Failed to leave mini mode before closing '{label}': {e}");
+    }
+}
+
+/// Takes all windows out of mini mode and saves their window state again.
+/// Called on `RunEvent::Exit`, which the window-state plugin handles first,
+/// before the session is saved.
+pub fn handle_exit(app: &AppHandle) {
+    let labels: Vec<String> = match MINI_WINDOWS.lock() {
+        Ok(windows) => windows.keys().cloned().collect(),
+        Err(e) => {
+            log::warn!("Failed to lock mini window state: {e}");
+            return;
+        }
+    };
+    if labels.is_empty() {
+        return;
+    }
+    for label in &labels {
+        if let Err(e) = restore(app, label) {
+            log::warn!("Failed to leave mini mode for '{label}': {e}");
+        }
+    }
+
+    #[cfg(desktop)]
+    {
+        use tauri_plugin_window_state::{AppHandleExt, StateFlags};
+        if let Err(e) = app.save_window_state(StateFlags::all() - StateFlags::VISIBLE) {
+            log::warn!("Failed to save window state: {e}");
+        }
+    }
+}
+
+/// Forgets a destroyed window. Called from the run loop in `lib.rs`.
+pub fn handle_window_destroyed(label: &str) {
+    if let Ok(mut windows) = MINI_WINDOWS.lock() {
+        windows.remove(label);
+    }
+}
+
+fn restore(app: &AppHandle, label: &str) -> Result<(), AppError> {
+    let Some(saved) = MINI_WINDOWS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock mini window state: {e}")))?
+        .remove(label)
+    else {
+        return Ok(());
+    };
+    let window = get_window(app, label)?;
+
+    log::info!("Window '{label}' leaving mini mode");
+
+    if let Err(e) = window.set_visible_on_all_workspaces(false) {
+        log::warn!("Failed to restore workspace visibility: {e}");
+    }
+    window
+        .set_always_on_top(saved.always_on_top)
+        .map_err(|e| AppError::internal(format!("Failed to restore always on top: {e}")))?;
+    window
+        .set_decorations(saved.decorated)
+        .map_err(|e| AppError::internal(format!("Failed to restore decorations: {e}")))?;
+    window
+        .set_resizable(saved.resizable)
+        .map_err(|e| AppError::internal(format!("Failed to restore resizable: {e}")))?;
+    window
+        .set_size(saved.size)
+        .map_err(|e| AppError::internal(format!("Failed to restore size: {e}")))?;
+    window
+        .set_position(saved.position)
+        .map_err(|e| AppError::internal(format!("Failed to restore position: {e}")))?;
+
+    // Reapply the configured minimum size, if the window is defined in tauri.conf.json
+    let window_config = app.config().app.windows.iter().find(|w| w.label == label);
+    if let Some(config) = window_config {
+        if let (Some(min_width), Some(min_height)) = (config.min_width, config.min_height) {
+            window
+                .set_min_size(Some(LogicalSize::new(min_width, min_height)))
+                .map_err(|e| AppError::internal(format!("Failed to restore minimum size: {e}")))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_corner() {
+        let area = ((0, 0), (1000, 800));
+        assert_eq!(
+            nearest_corner(area.0, area.1, (100, 100)),
+            ScreenCorner::TopLeft
+        );
+        assert_eq!(
+            nearest_corner(area.0, area.1, (900, 100)),
+            ScreenCorner::TopRight
+        );
+        assert_eq!(
+            nearest_corner(area.0, area.1, (100, 700)),
+            ScreenCorner::BottomLeft
+        );
+        assert_eq!(
+            nearest_corner(area.0, area.1, (900, 700)),
+            ScreenCorner::BottomRight
+        );
+
+        // Offset monitor (e.g. a second display to the right)
+        assert_eq!(
+            nearest_corner((1000, 0), (1000, 800), (1100, 50)),
+            ScreenCorner::TopLeft
+        );
+    }
+
+    #[test]
+    fn test_corner_position() {
+        let area = ((0, 25), (1000, 775)); // work area below a menu bar
+        let size = (320, 180);
+        assert_eq!(
+            corner_position(ScreenCorner::TopLeft, area.0, area.1, size, 16),
+            (16, 41)
+        );
+        assert_eq!(
+            corner_position(ScreenCorner::TopRight, area.0, area.1, size, 16),
+            (664, 41)
+        );
+        assert_eq!(
+            corner_position(ScreenCorner::BottomLeft, area.0, area.1, size, 16),
+            (16, 604)
+        );
+        assert_eq!(
+            corner_position(ScreenCorner::BottomRight, area.0, area.1, size, 16),
+            (664, 604)
+        );
+    }
+}
@@ -3,7 +3,64 @@
 //! Each submodule contains related commands and their helper functions.
 //! Import specific commands via their submodule (e.g., `commands::preferences::greet`).
 
+pub mod allowed_paths;
+pub mod analytics;
+pub mod auth;
+pub mod backup;
+pub mod batch;
+pub mod cache;
+pub mod clipboard;
+pub mod clipboard_history;
+pub mod crash_reports;
+pub mod data_location;
+pub mod db;
+pub mod deep_link;
+pub mod default_handler;
+pub mod diagnostics;
+pub mod discovery;
+pub mod displays;
+pub mod dock_menu;
+pub mod documents;
+pub mod downloads;
+pub mod encryption;
+pub mod event_queue;
+pub mod file_associations;
+pub mod file_stream;
+pub mod file_watcher;
+pub mod files;
+pub mod http;
+pub mod jump_list;
+pub mod kv;
+pub mod launch_at_login;
+pub mod locale;
+pub mod logging;
+pub mod menu;
+pub mod mini_window;
 pub mod notifications;
+pub mod performance;
+pub mod permissions;
+pub mod power;
 pub mod preferences;
 pub mod quick_pane;
+pub mod quick_pane_history;
+pub mod recent_documents;
 pub mod recovery;
+pub mod release_notes;
+pub mod search;
+pub mod secrets;
+pub mod session;
+pub mod share;
+pub mod sse;
+pub mod startup;
+pub mod streams;
+pub mod sync;
+pub mod taskbar;
+pub mod tasks;
+pub mod temp_dirs;
+pub mod thumbnails;
+pub mod tray;
+pub mod undo;
+pub mod updater;
+pub mod uploads;
+pub mod websocket;
+pub mod window;
@@ -1,10 +1,102 @@
 //! Native notification commands.
 //!
-//! Provides cross-platform native notification support using the Tauri notification plugin.
+//! Provides cross-platform native notifications with optional action buttons.
+//! Clicking a notification emits `notification-clicked` to the main window and
+//! brings the app to the front; clicking one of its buttons emits
+//! `notification-action` with the button's id.
+//!
+//! The notification plugin doesn't report clicks on desktop, so desktop
+//! notifications are shown with the libraries it uses internally:
+//! mac-notification-sys on macOS, WinRT toasts on Windows and notify-rust
+//! (D-Bus) on Linux. Each waits for the user's response in the background.
+//! Other platforms fall back to the plugin, without click or action events.
+//!
+//! Notifications can play a sound and show an image. Sound files and images
+//! are validated up front: sounds must be bundled resources, and images must
+//! be in the file scope, like the files the file commands accept.
+//!
+//! Permission can be checked and requested up front, so the UI can point users
+//! to the system settings when notifications are turned off. Once checked, it's
+//! checked again whenever the main window gains focus (the user may have just
+//! changed it) and `notification-permission-changed` is emitted if it changed.
+
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_specta::Event;
+
+use super::files::resolve_scoped_path;
+use super::taskbar::count_notification;
+use super::window::{activate_main_window, MAIN_WINDOW_LABEL};
+use crate::types::{
+    AppError, NativeNotificationOptions, NotificationAction, NotificationActionButton,
+    NotificationClicked, NotificationPermission, NotificationPermissionChanged, NotificationSound,
+};
+
+/// Counter for generated notification ids.
+static NEXT_NOTIFICATION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Image formats every platform can show in a notification.
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif"];
+
+/// Permission as of the last check, or None if it hasn't been checked.
+static LAST_PERMISSION: Mutex<Option<NotificationPermission>> = Mutex::new(None);
+
+/// A notification ready to show, with its sound and image resolved.
+#[cfg_attr(mobile, allow(dead_code))]
+struct NotificationContent {
+    title: String,
+    body: Option<String>,
+    actions: Vec<NotificationActionButton>,
+    sound: Option<ResolvedSound>,
+    image: Option<PathBuf>,
+}
+
+/// A `NotificationSound` with resource paths resolved.
+#[cfg_attr(mobile, allow(dead_code))]
+enum ResolvedSound {
+    Default,
+    System(String),
+    File(PathBuf),
+}
 
-use tauri::AppHandle;
+/// How the user responded to a notification.
+#[allow(dead_code)] // Not every platform produces every response
+#[derive(Debug, Clone, PartialEq)]
+enum NotificationResponse {
+    Clicked,
+    Action(String),
+    Dismissed,
+}
+
+/// Emits the event for a notification response.
+#[allow(dead_code)] // Unused on platforms without click events
+fn handle_response(app: &AppHandle, notification_id: String, response: NotificationResponse) {
+    log::debug!("Notification {notification_id} response: {response:?}");
 
-/// Sends a native system notification.
+    let result = match response {
+        NotificationResponse::Clicked => {
+            if let Err(e) = activate_main_window(app) {
+                log::warn!("Failed to activate main window from notification: {e}");
+            }
+            NotificationClicked { notification_id }.emit_to(app, MAIN_WINDOW_LABEL)
+        }
+        NotificationResponse::Action(action_id) => NotificationAction {
+            notification_id,
+            action_id,
+        }
+        .emit_to(app, MAIN_WINDOW_LABEL),
+        NotificationResponse::Dismissed => return,
+    };
+
+    if let Err(e) = result {
+        log::error!("Failed to emit notification event: {e}");
+    }
+}
+
+/// Sends a native system notification and returns its id, which identifies it
+/// in `notification-clicked` and `notification-action` events.
 /// On mobile platforms, returns an error as notifications are not yet supported.
 #[tauri::command]
 #[specta::specta]
@@ -12,35 +104,638 @@ pub async fn send_native_notification(
     app: AppHandle,
     title: String,
     body: Option<String>,
-) -> Result<(), String> {
-    log::info!("Sending native notification: {title}");
+    options: Option<NativeNotificationOptions>,
+) -> Result<String, AppError> {
+    let options = options.unwrap_or_default();
+    let id = options.id.unwrap_or_else(|| {
+        format!(
+            "notification-{}",
+            NEXT_NOTIFICATION_ID.fetch_add(1, Ordering::Relaxed)
+        )
+    });
+    log::info!("Sending native notification {id}: {title}");
 
-    #[cfg(not(mobile))]
+    let content = NotificationContent {
+        title,
+        body,
+        actions: options.actions,
+        sound: options
+            .sound
+            .map(|sound| resolve_sound(&app, sound))
+            .transpose()?,
+        image: options
+            .image
+            .map(|path| resolve_image(&app, &path))
+            .transpose()?,
+    };
+
+    match show_notification(&app, &id, content) {
+        Ok(()) => {
+            log::info!("Native notification sent successfully");
+            count_notification(&app);
+            Ok(id)
+        }
+        Err(e) => {
+            log::error!("Failed to send native notification: {e}");
+            Err(AppError::internal(format!(
+                "Failed to send notification: {e}"
+            )))
+        }
+    }
+}
+
+// ============================================================================
+// Sounds and Images
+// ============================================================================
+
+/// Checks that a resource path stays inside the resource directory.
+fn resource_relative_path(path: &str) -> Result<&Path, AppError> {
+    let relative = Path::new(path);
+    if relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
     {
-        use tauri_plugin_notification::NotificationExt;
+        Ok(relative)
+    } else {
+        Err(AppError::validation(format!(
+            "Notification sounds must be relative to the resource directory: {path}"
+        )))
+    }
+}
 
-        let mut notification = app.notification().builder().title(title);
+fn has_image_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            IMAGE_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+        })
+}
 
-        if let Some(body_text) = body {
-            notification = notification.body(body_text);
+/// Resolves a sound, checking that resource files exist.
+fn resolve_sound(app: &AppHandle, sound: NotificationSound) -> Result<ResolvedSound, AppError> {
+    match sound {
+        NotificationSound::Default => Ok(ResolvedSound::Default),
+        NotificationSound::System { name } => Ok(ResolvedSound::System(name)),
+        NotificationSound::Resource { path } => {
+            let relative = resource_relative_path(&path)?;
+            let resource_dir = app.path().resource_dir().map_err(|e| {
+                AppError::internal(format!("Failed to get resource directory: {e}"))
+            })?;
+
+            let file = resource_dir.join(relative);
+            if !file.is_file() {
+                return Err(AppError::not_found(format!(
+                    "Notification sound not found in resources: {path}"
+                )));
+            }
+            Ok(ResolvedSound::File(file))
         }
+    }
+}
+
+/// Checks that an image is in a supported format and is somewhere the app may
+/// read (see `files::resolve_scoped_path`).
+fn resolve_image(app: &AppHandle, path: &str) -> Result<PathBuf, AppError> {
+    if !has_image_extension(Path::new(path)) {
+        return Err(AppError::validation(format!(
+            "Notification images must be PNG, JPEG or GIF: {path}"
+        )));
+    }
+    resolve_scoped_path(app, path)
+        .map_err(|e| AppError::validation(format!("Invalid notification image: {e}")))
+}
 
-        match notification.show() {
-            Ok(_) => {
-                log::info!("Native notification sent successfully");
-                Ok(())
+// ============================================================================
+// Platform Backends
+// ============================================================================
+
+/// Shows a notification through Notification Center. Responses are only
+/// reported while the call to `send` is waiting, so it runs on its own thread.
+#[cfg(target_os = "macos")]
+fn show_notification(
+    app: &AppHandle,
+    id: &str,
+    content: NotificationContent,
+) -> Result<(), AppError> {
+    use mac_notification_sys::{
+        MainButton, Notification, NotificationResponse as MacResponse, Sound,
+    };
+    use std::sync::Once;
+
+    /// Label of the drop-down holding the buttons when there's more than one.
+    const ACTIONS_LABEL: &str = "Options";
+
+    static SET_APPLICATION: Once = Once::new();
+    SET_APPLICATION.call_once(|| {
+        // Unbundled dev builds have no identifier registered with
+        // Notification Center, so notifications are sent as Terminal
+        let bundle_id = if tauri::is_dev() {
+            "com.apple.Terminal".to_string()
+        } else {
+            app.config().identifier.clone()
+        };
+        if let Err(e) = mac_notification_sys::set_application(&bundle_id) {
+            log::warn!("Failed to set notification application: {e}");
+        }
+    });
+
+    let (app, id) = (app.clone(), id.to_string());
+    std::thread::spawn(move || {
+        let NotificationContent {
+            title,
+            body,
+            actions,
+            sound,
+            image,
+        } = content;
+        let titles: Vec<&str> = actions.iter().map(|a| a.title.as_str()).collect();
+        let image = image.map(|path| path.to_string_lossy().into_owned());
+
+        let mut notification = Notification::new();
+        notification.title(&title).wait_for_click(true);
+        if let Some(body) = &body {
+            notification.message(body);
+        }
+        if let Some(image) = &image {
+            notification.content_image(image);
+        }
+        match sound {
+            Some(ResolvedSound::Default) => {
+                notification.sound(Sound::Default);
+            }
+            Some(ResolvedSound::System(name)) => {
+                notification.sound(Sound::Custom(name));
             }
+            // Notification Center looks sounds up by name in the app bundle's
+            // Resources folder (and the system sound folders)
+            Some(ResolvedSound::File(path)) => {
+                if let Some(name) = path.file_stem() {
+                    notification.sound(Sound::Custom(name.to_string_lossy().into_owned()));
+                }
+            }
+            None => {}
+        }
+        match titles.as_slice() {
+            [] => {}
+            [title] => {
+                notification.main_button(MainButton::SingleAction(title));
+            }
+            _ => {
+                notification.main_button(MainButton::DropdownActions(ACTIONS_LABEL, &titles));
+            }
+        }
+
+        let response = match notification.send() {
+            Ok(MacResponse::Click) => NotificationResponse::Clicked,
+            // Buttons are reported by title
+            Ok(MacResponse::ActionButton(title)) => match actions.iter().find(|a| a.title == title)
+            {
+                Some(action) => NotificationResponse::Action(action.id.clone()),
+                None => NotificationResponse::Dismissed,
+            },
+            Ok(_) => NotificationResponse::Dismissed,
             Err(e) => {
                 log::error!("Failed to send native notification: {e}");
-                Err(format!("Failed to send notification: {e}"))
+                return;
+            }
+        };
+        handle_response(&app, id, response);
+    });
+    Ok(())
+}
+
+/// The AppUserModelID toasts are sent as. It's registered by the installer,
+/// which dev builds don't have, so they're sent as PowerShell.
+#[cfg(target_os = "windows")]
+fn toast_app_id(app: &AppHandle) -> String {
+    if tauri::is_dev() {
+        tauri_winrt_notification::Toast::POWERSHELL_APP_ID.to_string()
+    } else {
+        app.config().identifier.clone()
+    }
+}
+
+/// Shows a notification as a WinRT toast.
+#[cfg(target_os = "windows")]
+fn show_notification(
+    app: &AppHandle,
+    id: &str,
+    content: NotificationContent,
+) -> Result<(), AppError> {
+    use tauri_winrt_notification::{Sound, Toast};
+
+    let mut toast = Toast::new(&toast_app_id(app)).title(&content.title);
+    if let Some(body) = &content.body {
+        toast = toast.text1(body);
+    }
+    if let Some(image) = &content.image {
+        toast = toast.image(image, &content.title);
+    }
+    for action in &content.actions {
+        toast = toast.add_button(&action.title, &action.id);
+    }
+    if let Some(sound) = &content.sound {
+        // Unpackaged apps can only play the built-in toast sounds
+        let sound = match sound {
+            ResolvedSound::System(name) => match name.as_str() {
+                "IM" => Sound::IM,
+                "Mail" => Sound::Mail,
+                "Reminder" => Sound::Reminder,
+                "SMS" => Sound::SMS,
+                _ => Sound::Default,
+            },
+            ResolvedSound::File(path) => {
+                log::warn!(
+                    "Toasts can't play sound files, using the default sound instead of {}",
+                    path.display()
+                );
+                Sound::Default
             }
+            ResolvedSound::Default => Sound::Default,
+        };
+        toast = toast.sound(Some(sound));
+    }
+
+    let (app, id) = (app.clone(), id.to_string());
+    toast
+        .on_activated(move |action| {
+            // Buttons pass their id as the activation argument; the body passes none
+            let response = match action {
+                Some(action_id) if !action_id.is_empty() => NotificationResponse::Action(action_id),
+                _ => NotificationResponse::Clicked,
+            };
+            handle_response(&app, id.clone(), response);
+            Ok(())
+        })
+        .show()
+        .map_err(|e| AppError::internal(e.to_string()))
+}
+
+/// Shows a notification over D-Bus. Servers that don't support actions show
+/// it without buttons.
+#[cfg(target_os = "linux")]
+fn show_notification(
+    app: &AppHandle,
+    id: &str,
+    content: NotificationContent,
+) -> Result<(), AppError> {
+    /// Action the notification server reports when the body is clicked.
+    const DEFAULT_ACTION: &str = "default";
+    /// Pseudo-action notify-rust reports when the notification is closed.
+    const CLOSED_ACTION: &str = "__closed";
+    /// Sound theme name of the usual notification sound.
+    const DEFAULT_SOUND: &str = "message-new-instant";
+
+    let mut notification = notify_rust::Notification::new();
+    notification
+        .appname(&app.package_info().name)
+        .summary(&content.title)
+        .action(DEFAULT_ACTION, "");
+    if let Some(body) = &content.body {
+        notification.body(body);
+    }
+    if let Some(image) = &content.image {
+        notification.image_path(&image.to_string_lossy());
+    }
+    for action in &content.actions {
+        notification.action(&action.id, &action.title);
+    }
+    match &content.sound {
+        Some(ResolvedSound::Default) => {
+            notification.sound_name(DEFAULT_SOUND);
+        }
+        Some(ResolvedSound::System(name)) => {
+            notification.sound_name(name);
+        }
+        Some(ResolvedSound::File(path)) => {
+            notification.hint(notify_rust::Hint::SoundFile(
+                path.to_string_lossy().into_owned(),
+            ));
+        }
+        None => {}
+    }
+
+    let handle = notification
+        .show()
+        .map_err(|e| AppError::internal(e.to_string()))?;
+    let (app, id) = (app.clone(), id.to_string());
+    std::thread::spawn(move || {
+        handle.wait_for_action(|action| {
+            let response = match action {
+                DEFAULT_ACTION => NotificationResponse::Clicked,
+                CLOSED_ACTION => NotificationResponse::Dismissed,
+                action_id => NotificationResponse::Action(action_id.to_string()),
+            };
+            handle_response(&app, id, response);
+        });
+    });
+    Ok(())
+}
+
+/// Shows a notification through the notification plugin, which can't report
+/// clicks or show buttons.
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn show_notification(
+    app: &AppHandle,
+    _id: &str,
+    content: NotificationContent,
+) -> Result<(), AppError> {
+    #[cfg(not(mobile))]
+    {
+        use tauri_plugin_notification::NotificationExt;
+
+        if !content.actions.is_empty() || content.image.is_some() {
+            log::warn!("Notification actions and images aren't supported on this platform");
+        }
+
+        let mut notification = app.notification().builder().title(content.title);
+
+        if let Some(body_text) = content.body {
+            notification = notification.body(body_text);
         }
+        if let Some(ResolvedSound::System(name)) = content.sound {
+            notification = notification.sound(name);
+        }
+
+        notification
+            .show()
+            .map_err(|e| AppError::internal(e.to_string()))
     }
 
     #[cfg(mobile)]
     {
-        let _ = (app, body);
+        let _ = (app, content);
         log::warn!("Native notifications not supported on mobile");
-        Err("Native notifications not supported on mobile".to_string())
+        Err(AppError::internal(
+            "Native notifications not supported on mobile",
+        ))
+    }
+}
+
+// ============================================================================
+// Permissions
+// ============================================================================
+
+/// Records a permission check, emitting `notification-permission-changed` if
+/// it differs from the previous one.
+fn record_permission(app: &AppHandle, permission: NotificationPermission) {
+    let Ok(mut last) = LAST_PERMISSION.lock() else {
+        return;
+    };
+    let previous = last.replace(permission);
+    if previous.is_some_and(|previous| previous != permission) {
+        log::info!("Notification permission changed to {permission:?}");
+        if let Err(e) = (NotificationPermissionChanged { permission }).emit(app) {
+            log::error!("Failed to emit notification-permission-changed: {e}");
+        }
+    }
+}
+
+/// Checks permission again in the background if it has been checked before.
+/// Called when the main window gains focus.
+pub fn refresh_notification_permission(app: &AppHandle) {
+    if !LAST_PERMISSION.lock().is_ok_and(|last| last.is_some()) {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match query_permission(&app).await {
+            Ok(permission) => record_permission(&app, permission),
+            Err(e) => log::warn!("Failed to check notification permission: {e}"),
+        }
+    });
+}
+
+/// Returns whether the app may show native notifications.
+#[tauri::command]
+#[specta::specta]
+pub async fn check_notification_permission(
+    app: AppHandle,
+) -> Result<NotificationPermission, AppError> {
+    let permission = query_permission(&app).await?;
+    record_permission(&app, permission);
+    Ok(permission)
+}
+
+/// Asks the user to allow notifications if they haven't been asked yet, and
+/// returns the resulting permission. The system only asks once, so a denied
+/// permission stays denied; use `open_notification_settings` instead.
+#[tauri::command]
+#[specta::specta]
+pub async fn request_notification_permission(
+    app: AppHandle,
+) -> Result<NotificationPermission, AppError> {
+    log::info!("Requesting notification permission");
+    let permission = prompt_permission(&app).await?;
+    record_permission(&app, permission);
+    Ok(permission)
+}
+
+/// Opens the system settings where the user can turn notifications on or off.
+#[tauri::command]
+#[specta::specta]
+pub fn open_notification_settings(app: AppHandle) -> Result<(), AppError> {
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    {
+        use tauri_plugin_opener::OpenerExt;
+
+        #[cfg(target_os = "macos")]
+        let url = format!(
+            "x-apple.systempreferences:com.apple.preference.notifications?id={}",
+            app.config().identifier
+        );
+        #[cfg(target_os = "windows")]
+        let url = "ms-settings:notifications".to_string();
+
+        app.opener()
+            .open_url(url, None::<&str>)
+            .map_err(|e| AppError::internal(format!("Failed to open notification settings: {e}")))
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+        Err(AppError::internal(
+            "Notification settings can't be opened on this platform",
+        ))
+    }
+}
+
+/// Queries Notification Center's authorization status for the app.
+#[cfg(target_os = "macos")]
+async fn query_permission(_app: &AppHandle) -> Result<NotificationPermission, AppError> {
+    macos_permission::query().await
+}
+
+/// Shows the macOS permission prompt if the user hasn't been asked yet.
+#[cfg(target_os = "macos")]
+async fn prompt_permission(_app: &AppHandle) -> Result<NotificationPermission, AppError> {
+    macos_permission::request().await
+}
+
+/// Reads whether toasts are enabled for the app. They can be turned off per
+/// app or system-wide, and are never prompted for.
+#[cfg(target_os = "windows")]
+async fn query_permission(app: &AppHandle) -> Result<NotificationPermission, AppError> {
+    use windows::core::HSTRING;
+    use windows::UI::Notifications::{NotificationSetting, ToastNotificationManager};
+
+    let setting =
+        ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(toast_app_id(app)))
+            .and_then(|notifier| notifier.Setting())
+            .map_err(|e| AppError::internal(format!("Failed to read notification setting: {e}")))?;
+
+    Ok(if setting == NotificationSetting::Enabled {
+        NotificationPermission::Granted
+    } else {
+        NotificationPermission::Denied
+    })
+}
+
+/// Asks the notification plugin, which prompts on mobile.
+#[cfg(mobile)]
+async fn query_permission(app: &AppHandle) -> Result<NotificationPermission, AppError> {
+    use tauri_plugin_notification::NotificationExt;
+
+    app.notification()
+        .permission_state()
+        .map(plugin_permission)
+        .map_err(|e| AppError::internal(format!("Failed to check notification permission: {e}")))
+}
+
+#[cfg(mobile)]
+async fn prompt_permission(app: &AppHandle) -> Result<NotificationPermission, AppError> {
+    use tauri_plugin_notification::NotificationExt;
+
+    app.notification()
+        .request_permission()
+        .map(plugin_permission)
+        .map_err(|e| AppError::internal(format!("Failed to request notification permission: {e}")))
+}
+
+#[cfg(mobile)]
+fn plugin_permission(state: tauri::plugin::PermissionState) -> NotificationPermission {
+    use tauri::plugin::PermissionState;
+
+    match state {
+        PermissionState::Granted => NotificationPermission::Granted,
+        PermissionState::Denied => NotificationPermission::Denied,
+        _ => NotificationPermission::NotDetermined,
+    }
+}
+
+/// Linux notification servers have no permission model.
+#[cfg(not(any(target_os = "macos", target_os = "windows", mobile)))]
+async fn query_permission(_app: &AppHandle) -> Result<NotificationPermission, AppError> {
+    Ok(NotificationPermission::Granted)
+}
+
+/// There's no prompt outside macOS and mobile, so this just checks.
+#[cfg(not(any(target_os = "macos", mobile)))]
+async fn prompt_permission(app: &AppHandle) -> Result<NotificationPermission, AppError> {
+    query_permission(app).await
+}
+
+#[cfg(target_os = "macos")]
+mod macos_permission {
+    use block2::RcBlock;
+    use objc2::runtime::Bool;
+    use objc2_foundation::{NSBundle, NSError};
+    use objc2_user_notifications::{
+        UNAuthorizationOptions, UNAuthorizationStatus, UNNotificationSettings,
+        UNUserNotificationCenter,
+    };
+    use std::ptr::NonNull;
+    use std::sync::mpsc;
+
+    use crate::types::{AppError, NotificationPermission};
+
+    /// UNUserNotificationCenter throws outside an app bundle (e.g. `tauri dev`),
+    /// where notifications are sent as Terminal anyway.
+    fn is_bundled() -> bool {
+        NSBundle::mainBundle()
+            .bundlePath()
+            .to_string()
+            .ends_with(".app")
+    }
+
+    /// Waits for a completion handler's result off the async runtime.
+    async fn wait<T: Send + 'static>(receiver: mpsc::Receiver<T>) -> Result<T, AppError> {
+        tauri::async_runtime::spawn_blocking(move || receiver.recv())
+            .await
+            .map_err(|e| {
+                AppError::internal(format!("Failed to wait for Notification Center: {e}"))
+            })?
+            .map_err(|_| AppError::internal("Notification Center didn't respond"))
+    }
+
+    pub async fn query() -> Result<NotificationPermission, AppError> {
+        if !is_bundled() {
+            return Ok(NotificationPermission::Granted);
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let handler = RcBlock::new(move |settings: NonNull<UNNotificationSettings>| {
+            // SAFETY: the settings object is valid for the duration of the handler.
+            let status = unsafe { settings.as_ref() }.authorizationStatus();
+            let _ = sender.send(status);
+        });
+        UNUserNotificationCenter::currentNotificationCenter()
+            .getNotificationSettingsWithCompletionHandler(&handler);
+
+        Ok(match wait(receiver).await? {
+            UNAuthorizationStatus::NotDetermined => NotificationPermission::NotDetermined,
+            UNAuthorizationStatus::Denied => NotificationPermission::Denied,
+            // Authorized, provisional and ephemeral all deliver notifications
+            _ => NotificationPermission::Granted,
+        })
+    }
+
+    pub async fn request() -> Result<NotificationPermission, AppError> {
+        if !is_bundled() {
+            return Ok(NotificationPermission::Granted);
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let handler = RcBlock::new(move |_granted: Bool, error: *mut NSError| {
+            // SAFETY: the error is either null or valid for the duration of the handler.
+            let error = unsafe { error.as_ref() }.map(|e| e.localizedDescription().to_string());
+            let _ = sender.send(error);
+        });
+        let options = UNAuthorizationOptions::Alert
+            | UNAuthorizationOptions::Sound
+            | UNAuthorizationOptions::Badge;
+        UNUserNotificationCenter::currentNotificationCenter()
+            .requestAuthorizationWithOptions_completionHandler(options, &handler);
+
+        if let Some(error) = wait(receiver).await? {
+            return Err(AppError::internal(format!(
+                "Failed to request notification permission: {error}"
+            )));
+        }
+        // The prompt only says whether it was granted, so read the full status
+        query().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resource_relative_path() {
+        assert!(resource_relative_path("sounds/ding.wav").is_ok());
+        assert!(resource_relative_path("../secret.wav").is_err());
+        assert!(resource_relative_path("sounds/../../secret.wav").is_err());
+        assert!(resource_relative_path("/etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_has_image_extension() {
+        assert!(has_image_extension(Path::new("/tmp/photo.PNG")));
+        assert!(has_image_extension(Path::new("/tmp/photo.jpeg")));
+        assert!(!has_image_extension(Path::new("/tmp/photo.svg")));
+        assert!(!has_image_extension(Path::new("/tmp/photo")));
     }
 }
@@ -0,0 +1,148 @@
+//! Command timing and startup metrics.
+//!
+//! The `Timing` middleware (see `crate::middleware`) counts every command and
+//! times its handler. Sync commands run inside the handler on the main thread,
+//! so this is how long they blocked it; async commands return once their task
+//! is spawned, so they only show the cost of parsing their arguments.
+//!
+//! Startup milestones are recorded with `mark` as the app starts. Both are
+//! available from `get_performance_metrics`.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::middleware::Middleware;
+use crate::types::{AppError, CommandTiming, PerformanceMetrics, StartupMilestone, StartupTiming};
+
+/// Approximately when the process started. Forced by `start_clock`.
+static STARTED_AT: LazyLock<Instant> = LazyLock::new(Instant::now);
+
+static MILESTONES: Mutex<Vec<StartupTiming>> = Mutex::new(Vec::new());
+
+static COMMANDS: LazyLock<Mutex<HashMap<String, CommandStats>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Default)]
+struct CommandStats {
+    count: u64,
+    total: Duration,
+    max: Duration,
+}
+
+/// Starts the clock milestones are measured from. Called first in `run()`.
+pub fn start_clock() {
+    LazyLock::force(&STARTED_AT);
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Records a startup milestone. Only the first time each is reached counts,
+/// e.g. for page loads after a reload.
+pub fn mark(milestone: StartupMilestone) {
+    let elapsed_ms = millis(STARTED_AT.elapsed());
+    let Ok(mut milestones) = MILESTONES.lock() else {
+        return;
+    };
+    if milestones
+        .iter()
+        .any(|timing| timing.milestone == milestone)
+    {
+        return;
+    }
+
+    log::debug!("Startup milestone {milestone:?} at {elapsed_ms:.1}ms");
+    milestones.push(StartupTiming {
+        milestone,
+        elapsed_ms,
+    });
+}
+
+fn record_command(command: &str, elapsed: Duration) {
+    let Ok(mut commands) = COMMANDS.lock() else {
+        return;
+    };
+    let stats = commands.entry(command.to_string()).or_default();
+    stats.count += 1;
+    stats.total += elapsed;
+    stats.max = stats.max.max(elapsed);
+}
+
+/// Middleware recording each command's count and handler time.
+pub struct Timing;
+
+impl Middleware for Timing {
+    fn after(&self, command: &str, _window: &str, elapsed: Duration) {
+        record_command(command, elapsed);
+    }
+}
+
+fn command_timings(commands: &HashMap<String, CommandStats>) -> Vec<CommandTiming> {
+    let mut timings: Vec<CommandTiming> = commands
+        .iter()
+        .map(|(command, stats)| CommandTiming {
+            command: command.clone(),
+            count: u32::try_from(stats.count).unwrap_or(u32::MAX),
+            total_ms: millis(stats.total),
+            average_ms: millis(stats.total) / stats.count.max(1) as f64,
+            max_ms: millis(stats.max),
+        })
+        .collect();
+    timings.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms));
+    timings
+}
+
+/// Gets startup milestones and per-command counts and timings since launch.
+#[tauri::command]
+#[specta::specta]
+pub fn get_performance_metrics() -> Result<PerformanceMetrics, AppError> {
+    let startup = MILESTONES
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock startup milestones: {e}")))?
+        .clone();
+    let commands = COMMANDS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock command metrics: {e}")))?;
+    let commands = command_timings(&commands);
+
+    Ok(PerformanceMetrics {
+        uptime_ms: millis(STARTED_AT.elapsed()),
+        startup,
+        commands,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_timings_sorted_by_total() {
+        let mut commands = HashMap::new();
+        commands.insert(
+            "fast".to_string(),
+            CommandStats {
+                count: 4,
+                total: Duration::from_millis(4),
+                max: Duration::from_millis(2),
+            },
+        );
+        commands.insert(
+            "slow".to_string(),
+            CommandStats {
+                count: 1,
+                total: Duration::from_millis(50),
+                max: Duration::from_millis(50),
+            },
+        );
+
+        let timings = command_timings(&commands);
+        assert_eq!(timings[0].command, "slow");
+        assert_eq!(timings[1].command, "fast");
+        assert_eq!(timings[1].count, 4);
+        assert!((timings[1].average_ms - 1.0).abs() < 1e-9);
+        assert!((timings[1].max_ms - 2.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,367 @@
+//! Permission checks and requests for privacy-protected features.
+//!
+//! macOS asks the user before an app can use the microphone, camera or
+//! automation, and accessibility and screen recording only work once the app
+//! has been allowed in System Settings. Checking up front lets the UI explain
+//! why a permission is needed and deep-link to the right Settings pane,
+//! instead of a feature failing silently later.
+//!
+//! Notifications go through `notifications.rs` on every platform. The other
+//! kinds are only gated on macOS, and are reported as `Granted` elsewhere.
+
+use tauri::AppHandle;
+
+use super::notifications;
+use crate::types::{AppError, NotificationPermission, PermissionKind, PermissionStatus};
+
+/// App whose Apple Events permission `PermissionKind::Automation` covers.
+/// System Events is what AppleScript uses for keystrokes and UI scripting;
+/// change it to the bundle id of the app you automate.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub const AUTOMATION_TARGET: &str = "com.apple.systemevents";
+
+fn notification_status(permission: NotificationPermission) -> PermissionStatus {
+    match permission {
+        NotificationPermission::Granted => PermissionStatus::Granted,
+        NotificationPermission::Denied => PermissionStatus::Denied,
+        NotificationPermission::NotDetermined => PermissionStatus::NotDetermined,
+    }
+}
+
+/// Reads a permission's status, first asking the user if `prompt` is set and
+/// the system allows it.
+async fn query(
+    app: AppHandle,
+    kind: PermissionKind,
+    prompt: bool,
+) -> Result<PermissionStatus, AppError> {
+    match kind {
+        PermissionKind::Notifications => {
+            let permission = if prompt {
+                notifications::request_notification_permission(app).await?
+            } else {
+                notifications::check_notification_permission(app).await?
+            };
+            Ok(notification_status(permission))
+        }
+        #[cfg(target_os = "macos")]
+        PermissionKind::Accessibility => Ok(macos::accessibility(prompt)),
+        #[cfg(target_os = "macos")]
+        PermissionKind::ScreenRecording => Ok(macos::screen_recording(prompt)),
+        #[cfg(target_os = "macos")]
+        PermissionKind::Microphone | PermissionKind::Camera => {
+            macos::capture_device(kind, prompt).await
+        }
+        #[cfg(target_os = "macos")]
+        PermissionKind::Automation => macos::automation(prompt).await,
+        // Only macOS gates the others behind a permission
+        #[cfg(not(target_os = "macos"))]
+        _ => Ok(PermissionStatus::Granted),
+    }
+}
+
+/// Returns whether the app has a permission, without asking the user.
+#[tauri::command]
+#[specta::specta]
+pub async fn check_permission(
+    app: AppHandle,
+    kind: PermissionKind,
+) -> Result<PermissionStatus, AppError> {
+    query(app, kind, false).await
+}
+
+/// Asks the user for a permission if the system allows it, and returns the
+/// resulting status. The system only asks once, so a denied permission stays
+/// denied; use `open_system_settings_pane` instead.
+#[tauri::command]
+#[specta::specta]
+pub async fn request_permission(
+    app: AppHandle,
+    kind: PermissionKind,
+) -> Result<PermissionStatus, AppError> {
+    log::info!("Requesting {kind:?} permission");
+    query(app, kind, true).await
+}
+
+/// The Privacy & Security pane for a permission.
+#[cfg(target_os = "macos")]
+fn settings_url(kind: PermissionKind) -> Option<&'static str> {
+    match kind {
+        PermissionKind::Notifications => None,
+        PermissionKind::Accessibility => {
+            Some("x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility")
+        }
+        PermissionKind::ScreenRecording => {
+            Some("x-apple.systempreferences:com.apple.preference.security?Privacy_ScreenCapture")
+        }
+        PermissionKind::Microphone => {
+            Some("x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone")
+        }
+        PermissionKind::Camera => {
+            Some("x-apple.systempreferences:com.apple.preference.security?Privacy_Camera")
+        }
+        PermissionKind::Automation => {
+            Some("x-apple.systempreferences:com.apple.preference.security?Privacy_Automation")
+        }
+    }
+}
+
+/// Windows has privacy settings for the microphone and camera, though they
+/// aren't checked.
+#[cfg(target_os = "windows")]
+fn settings_url(kind: PermissionKind) -> Option<&'static str> {
+    match kind {
+        PermissionKind::Microphone => Some("ms-settings:privacy-microphone"),
+        PermissionKind::Camera => Some("ms-settings:privacy-webcam"),
+        _ => None,
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn settings_url(_kind: PermissionKind) -> Option<&'static str> {
+    None
+}
+
+/// Opens the system settings pane where the user can grant or revoke a
+/// permission.
+#[tauri::command]
+#[specta::specta]
+pub fn open_system_settings_pane(app: AppHandle, kind: PermissionKind) -> Result<(), AppError> {
+    use tauri_plugin_opener::OpenerExt;
+
+    if kind == PermissionKind::Notifications {
+        return notifications::open_notification_settings(app);
+    }
+
+    let url = settings_url(kind).ok_or_else(|| {
+        AppError::internal(format!(
+            "{kind:?} settings can't be opened on this platform"
+        ))
+    })?;
+    app.opener()
+        .open_url(url, None::<&str>)
+        .map_err(|e| AppError::internal(format!("Failed to open {kind:?} settings: {e}")))
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use block2::RcBlock;
+    use objc2::runtime::Bool;
+    use objc2_application_services::{
+        kAXTrustedCheckOptionPrompt, AXIsProcessTrusted, AXIsProcessTrustedWithOptions,
+    };
+    use objc2_av_foundation::{
+        AVAuthorizationStatus, AVCaptureDevice, AVMediaType, AVMediaTypeAudio, AVMediaTypeVideo,
+    };
+    use objc2_core_foundation::{CFBoolean, CFDictionary, CFString};
+    use objc2_core_graphics::{CGPreflightScreenCaptureAccess, CGRequestScreenCaptureAccess};
+    use objc2_foundation::{NSBundle, NSString};
+    use std::ffi::c_void;
+    use std::sync::mpsc;
+
+    use super::AUTOMATION_TARGET;
+    use crate::types::{AppError, PermissionKind, PermissionStatus};
+
+    /// Accessibility and screen recording can't tell "not asked" from
+    /// "denied", so anything but trusted is reported as denied.
+    fn trusted_status(trusted: bool) -> PermissionStatus {
+        if trusted {
+            PermissionStatus::Granted
+        } else {
+            PermissionStatus::Denied
+        }
+    }
+
+    /// Without its usage description in Info.plist, asking for a permission
+    /// fails (or for the camera and microphone, kills the app).
+    fn require_usage_description(key: &str) -> Result<(), AppError> {
+        NSBundle::mainBundle()
+            .objectForInfoDictionaryKey(&NSString::from_str(key))
+            .map(|_| ())
+            .ok_or_else(|| {
+                AppError::internal(format!(
+                    "Add {key} to src-tauri/Info.plist to ask for this permission"
+                ))
+            })
+    }
+
+    /// Waits for a completion handler's result off the async runtime.
+    async fn wait<T: Send + 'static>(receiver: mpsc::Receiver<T>) -> Result<T, AppError> {
+        tauri::async_runtime::spawn_blocking(move || receiver.recv())
+            .await
+            .map_err(|e| {
+                AppError::internal(format!("Failed to wait for the permission prompt: {e}"))
+            })?
+            .map_err(|_| AppError::internal("The permission prompt didn't respond"))
+    }
+
+    /// Prompting adds the app to the Accessibility list and shows a dialog
+    /// that opens System Settings. It returns straight away.
+    pub fn accessibility(prompt: bool) -> PermissionStatus {
+        if !prompt {
+            return trusted_status(unsafe { AXIsProcessTrusted() });
+        }
+
+        // SAFETY: the prompt option key is a constant CFString.
+        let key = unsafe { kAXTrustedCheckOptionPrompt };
+        let options =
+            CFDictionary::<CFString, CFBoolean>::from_slices(&[key], &[CFBoolean::new(true)]);
+        // SAFETY: the options map the prompt key to a CFBoolean, as documented.
+        trusted_status(unsafe { AXIsProcessTrustedWithOptions(Some(options.as_opaque())) })
+    }
+
+    /// Prompting shows a dialog that opens System Settings, once. Access only
+    /// takes effect after the app is relaunched.
+    pub fn screen_recording(prompt: bool) -> PermissionStatus {
+        trusted_status(if prompt {
+            CGRequestScreenCaptureAccess()
+        } else {
+            CGPreflightScreenCaptureAccess()
+        })
+    }
+
+    fn media_type(kind: PermissionKind) -> Result<&'static AVMediaType, AppError> {
+        // SAFETY: the media type constants are immutable strings.
+        let media_type = unsafe {
+            if kind == PermissionKind::Camera {
+                AVMediaTypeVideo
+            } else {
+                AVMediaTypeAudio
+            }
+        };
+        media_type.ok_or_else(|| {
+            AppError::internal(format!("AVFoundation doesn't support {kind:?} here"))
+        })
+    }
+
+    fn capture_status(kind: PermissionKind) -> Result<PermissionStatus, AppError> {
+        // SAFETY: the media type is video or audio, the only ones allowed.
+        let status = unsafe { AVCaptureDevice::authorizationStatusForMediaType(media_type(kind)?) };
+        Ok(match status {
+            AVAuthorizationStatus::Authorized => PermissionStatus::Granted,
+            AVAuthorizationStatus::Denied => PermissionStatus::Denied,
+            AVAuthorizationStatus::Restricted => PermissionStatus::Restricted,
+            _ => PermissionStatus::NotDetermined,
+        })
+    }
+
+    /// Shows the camera or microphone prompt, sending whether it was granted.
+    fn request_capture_access(kind: PermissionKind) -> Result<mpsc::Receiver<bool>, AppError> {
+        let (sender, receiver) = mpsc::channel();
+        let handler = RcBlock::new(move |granted: Bool| {
+            let _ = sender.send(granted.as_bool());
+        });
+        // SAFETY: the media type is video or audio, and the handler may be
+        // called on any queue.
+        unsafe {
+            AVCaptureDevice::requestAccessForMediaType_completionHandler(
+                media_type(kind)?,
+                &handler,
+            );
+        }
+        Ok(receiver)
+    }
+
+    pub async fn capture_device(
+        kind: PermissionKind,
+        prompt: bool,
+    ) -> Result<PermissionStatus, AppError> {
+        let status = capture_status(kind)?;
+        if !prompt || status != PermissionStatus::NotDetermined {
+            return Ok(status);
+        }
+
+        require_usage_description(if kind == PermissionKind::Camera {
+            "NSCameraUsageDescription"
+        } else {
+            "NSMicrophoneUsageDescription"
+        })?;
+        wait(request_capture_access(kind)?).await?;
+        capture_status(kind)
+    }
+
+    // Apple Event Manager, part of CoreServices
+
+    #[repr(C)]
+    struct AEDesc {
+        descriptor_type: u32,
+        data_handle: *mut c_void,
+    }
+
+    #[link(name = "CoreServices", kind = "framework")]
+    extern "C" {
+        fn AECreateDesc(
+            type_code: u32,
+            data: *const c_void,
+            size: isize,
+            result: *mut AEDesc,
+        ) -> i16;
+        fn AEDisposeDesc(desc: *mut AEDesc) -> i16;
+        fn AEDeterminePermissionToAutomateTarget(
+            target: *const AEDesc,
+            event_class: u32,
+            event_id: u32,
+            ask_user_if_needed: u8,
+        ) -> i32;
+    }
+
+    const TYPE_APPLICATION_BUNDLE_ID: u32 = u32::from_be_bytes(*b"bund");
+    const TYPE_WILD_CARD: u32 = u32::from_be_bytes(*b"****");
+    const ERR_AE_EVENT_NOT_PERMITTED: i32 = -1743;
+    const ERR_AE_EVENT_WOULD_REQUIRE_USER_CONSENT: i32 = -1744;
+    const PROC_NOT_FOUND: i32 = -600;
+
+    /// Blocks until the user answers when prompting.
+    fn automation_status(prompt: bool) -> Result<PermissionStatus, AppError> {
+        let mut target = AEDesc {
+            descriptor_type: 0,
+            data_handle: std::ptr::null_mut(),
+        };
+        // SAFETY: the bundle id is copied into the descriptor, which is
+        // disposed of below.
+        let status = unsafe {
+            let err = AECreateDesc(
+                TYPE_APPLICATION_BUNDLE_ID,
+                AUTOMATION_TARGET.as_ptr().cast(),
+                AUTOMATION_TARGET.len() as isize,
+                &mut target,
+            );
+            if err != 0 {
+                return Err(AppError::internal(format!(
+                    "Failed to create Apple Event target: {err}"
+                )));
+            }
+            let status = AEDeterminePermissionToAutomateTarget(
+                &target,
+                TYPE_WILD_CARD,
+                TYPE_WILD_CARD,
+                u8::from(prompt),
+            );
+            AEDisposeDesc(&mut target);
+            status
+        };
+
+        match status {
+            0 => Ok(PermissionStatus::Granted),
+            ERR_AE_EVENT_NOT_PERMITTED => Ok(PermissionStatus::Denied),
+            ERR_AE_EVENT_WOULD_REQUIRE_USER_CONSENT => Ok(PermissionStatus::NotDetermined),
+            PROC_NOT_FOUND => Err(AppError::validation(format!(
+                "{AUTOMATION_TARGET} must be running to check automation permission"
+            ))),
+            status => Err(AppError::internal(format!(
+                "Failed to check automation permission: {status}"
+            ))),
+        }
+    }
+
+    pub async fn automation(prompt: bool) -> Result<PermissionStatus, AppError> {
+        if prompt {
+            require_usage_description("NSAppleEventsUsageDescription")?;
+        }
+        tauri::async_runtime::spawn_blocking(move || automation_status(prompt))
+            .await
+            .map_err(|e| {
+                AppError::internal(format!("Failed to check automation permission: {e}"))
+            })?
+    }
+}
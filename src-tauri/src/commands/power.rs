@@ -0,0 +1,837 @@
+//! System power events.
+//!
+//! `start_power_monitor` listens for sleep and wake with each platform's own
+//! notifications (NSWorkspace on macOS, suspend/resume notifications on
+//! Windows, logind's `PrepareForSleep` on Linux) and polls the power source,
+//! battery level and lid every `POLL_INTERVAL`. Changes are broadcast to all
+//! windows as `power-changed`.
+//!
+//! Pending document saves are flushed here before sleep, so nothing is lost
+//! if the frontend doesn't get to handle the event in time.
+//!
+//! `prevent_sleep` keeps the system (and optionally the display) awake for
+//! long-running work like exports or playback, until `allow_sleep` is called
+//! with the returned token. The OS drops any that are left when the app
+//! exits.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_specta::Event;
+
+use crate::types::{AppError, PowerChange, PowerChanged, PowerSource, PowerState};
+
+/// How often the power source, battery level and lid are checked.
+#[cfg_attr(mobile, allow(dead_code))]
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Battery percentage at or below which `LowBattery` is emitted.
+const LOW_BATTERY_PERCENT: u32 = 10;
+
+/// Longest accepted `reason`. It's shown to users by tools like `pmset -g
+/// assertions` and `powercfg /requests`.
+const MAX_REASON_LENGTH: usize = 200;
+
+/// Active `prevent_sleep` assertions by token. Each is released when dropped.
+static SLEEP_ASSERTIONS: LazyLock<Mutex<HashMap<u32, platform::SleepAssertion>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static NEXT_SLEEP_TOKEN: AtomicU32 = AtomicU32::new(1);
+
+fn is_low_battery(state: &PowerState) -> bool {
+    state.source == PowerSource::Battery
+        && state
+            .battery_percent
+            .is_some_and(|percent| percent <= LOW_BATTERY_PERCENT)
+}
+
+/// Changes between two polls, in the order they're emitted.
+#[cfg_attr(mobile, allow(dead_code))]
+fn state_changes(previous: &PowerState, current: &PowerState) -> Vec<PowerChange> {
+    let mut changes = Vec::new();
+    if current.source != previous.source && current.source != PowerSource::Unknown {
+        changes.push(PowerChange::PowerSourceChanged {
+            source: current.source,
+        });
+    }
+    match (previous.lid_closed, current.lid_closed) {
+        (Some(false), Some(true)) => changes.push(PowerChange::LidClosed),
+        (Some(true), Some(false)) => changes.push(PowerChange::LidOpened),
+        _ => {}
+    }
+    // Once per discharge, until the battery is charged above the level again
+    if let Some(percent) = current.battery_percent {
+        if is_low_battery(current) && !is_low_battery(previous) {
+            changes.push(PowerChange::LowBattery { percent });
+        }
+    }
+    changes
+}
+
+#[cfg_attr(mobile, allow(dead_code))]
+fn emit_change(app: &AppHandle, change: PowerChange, state: PowerState) {
+    log::info!("Power change: {change:?}");
+    if let Err(e) = (PowerChanged { change, state }).emit(app) {
+        log::error!("Failed to emit power-changed: {e}");
+    }
+}
+
+/// Saves pending work and announces that the system is about to sleep.
+#[cfg_attr(mobile, allow(dead_code))]
+fn handle_sleep(app: &AppHandle) {
+    super::documents::flush_pending_documents(app);
+    emit_change(app, PowerChange::Sleep, platform::read_power_state());
+}
+
+#[cfg_attr(mobile, allow(dead_code))]
+fn handle_wake(app: &AppHandle) {
+    emit_change(app, PowerChange::Wake, platform::read_power_state());
+}
+
+/// Starts listening for sleep and wake, and polling for power changes.
+/// Called during app setup.
+#[cfg(desktop)]
+pub fn start_power_monitor(app: &AppHandle) {
+    platform::watch_sleep(app);
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut previous = platform::read_power_state();
+        log::debug!("Power state at launch: {previous:?}");
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let current = platform::read_power_state();
+            for change in state_changes(&previous, &current) {
+                emit_change(&app, change, current.clone());
+            }
+            previous = current;
+        }
+    });
+}
+
+/// Returns the current power source, battery level and lid state.
+#[tauri::command]
+#[specta::specta]
+pub fn get_power_state() -> PowerState {
+    platform::read_power_state()
+}
+
+/// Keeps the system from sleeping while idle until `allow_sleep` is called
+/// with the returned token. With `keep_display_on`, the display stays on
+/// too, e.g. for playback. `reason` (e.g. "Exporting video") is shown in the
+/// OS's power diagnostics. Doesn't stop sleep the user asks for, like
+/// closing the lid.
+#[tauri::command]
+#[specta::specta]
+pub fn prevent_sleep(
+    app: AppHandle,
+    reason: String,
+    keep_display_on: bool,
+) -> Result<u32, AppError> {
+    let reason = reason.trim();
+    if reason.is_empty() || reason.chars().count() > MAX_REASON_LENGTH {
+        return Err(AppError::validation(format!(
+            "Reason must be between 1 and {MAX_REASON_LENGTH} characters"
+        )));
+    }
+
+    let assertion = platform::SleepAssertion::create(&app, reason, keep_display_on)
+        .map_err(|e| AppError::internal(format!("Failed to prevent sleep: {e}")))?;
+    let token = NEXT_SLEEP_TOKEN.fetch_add(1, Ordering::Relaxed);
+    SLEEP_ASSERTIONS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock sleep assertions: {e}")))?
+        .insert(token, assertion);
+
+    log::info!("Preventing sleep ({token}): {reason}");
+    Ok(token)
+}
+
+/// Releases a `prevent_sleep` assertion. Returns false if the token is
+/// unknown or was already released.
+#[tauri::command]
+#[specta::specta]
+pub fn allow_sleep(token: u32) -> Result<bool, AppError> {
+    let removed = SLEEP_ASSERTIONS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock sleep assertions: {e}")))?
+        .remove(&token);
+    if removed.is_some() {
+        log::info!("Allowing sleep ({token})");
+    }
+    Ok(removed.is_some())
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use block2::RcBlock;
+    use objc2_app_kit::{
+        NSWorkspace, NSWorkspaceDidWakeNotification, NSWorkspaceWillSleepNotification,
+    };
+    use objc2_core_foundation::{
+        CFArray, CFBoolean, CFDictionary, CFIndex, CFNumber, CFRetained, CFString, CFType,
+    };
+    use objc2_foundation::NSNotification;
+    use objc2_io_kit::{
+        kIOPMAssertionLevelOn, kIOPMUPSPowerKey, kIOPSACPowerValue, kIOPSBatteryPowerValue,
+        kIOPSCurrentCapacityKey, kIOPSInternalBatteryType, kIOPSMaxCapacityKey, kIOPSTypeKey,
+        IOObjectRelease, IOPMAssertionCreateWithName, IOPMAssertionID, IOPMAssertionRelease,
+        IOPSCopyPowerSourcesInfo, IOPSCopyPowerSourcesList, IOPSGetPowerSourceDescription,
+        IOPSGetProvidingPowerSourceType, IORegistryEntryCreateCFProperty,
+        IOServiceGetMatchingService, IOServiceMatching,
+    };
+    use std::ffi::CStr;
+    use std::ptr::NonNull;
+    use tauri::AppHandle;
+
+    use super::{handle_sleep, handle_wake};
+    use crate::types::{AppError, PowerSource, PowerState};
+
+    fn cf_string(value: &CStr) -> CFRetained<CFString> {
+        CFString::from_str(&value.to_string_lossy())
+    }
+
+    /// Looks up `key` in a power source description.
+    fn description_value<'a>(description: &'a CFDictionary, key: &CStr) -> Option<&'a CFType> {
+        let key = cf_string(key);
+        // SAFETY: descriptions map CFString keys to CF values, and aren't
+        // mutated while the reference is used
+        let value = unsafe { description.value((&*key as *const CFString).cast()) };
+        unsafe { value.cast::<CFType>().as_ref() }
+    }
+
+    fn internal_battery_percent(info: &CFType) -> Option<u32> {
+        // SAFETY: `info` is a power sources blob from IOPSCopyPowerSourcesInfo
+        let sources: CFRetained<CFArray> = unsafe { IOPSCopyPowerSourcesList(Some(info)) }?;
+        for index in 0..sources.len() {
+            // SAFETY: the index is in bounds and the list holds CF values
+            let source = unsafe { sources.value_at_index(index as CFIndex) };
+            let Some(source) = (unsafe { source.cast::<CFType>().as_ref() }) else {
+                continue;
+            };
+            let Some(description) =
+                (unsafe { IOPSGetPowerSourceDescription(Some(info), Some(source)) })
+            else {
+                continue;
+            };
+
+            let is_internal_battery = description_value(&description, kIOPSTypeKey)
+                .and_then(|value| value.downcast_ref::<CFString>())
+                .is_some_and(|kind| kind.to_string() == kIOPSInternalBatteryType.to_string_lossy());
+            if !is_internal_battery {
+                continue;
+            }
+            let capacity = |key| {
+                description_value(&description, key)
+                    .and_then(|value| value.downcast_ref::<CFNumber>())
+                    .and_then(|number| number.as_i64())
+            };
+            let (Some(current), Some(max)) = (
+                capacity(kIOPSCurrentCapacityKey),
+                capacity(kIOPSMaxCapacityKey),
+            ) else {
+                continue;
+            };
+            if max > 0 {
+                return u32::try_from(current * 100 / max).ok();
+            }
+        }
+        None
+    }
+
+    /// Reads `AppleClamshellState` from the power management root domain.
+    /// Macs without a lid don't have it.
+    fn lid_closed() -> Option<bool> {
+        // SAFETY: a valid, nul-terminated class name
+        let matching = unsafe { IOServiceMatching(c"IOPMrootDomain".as_ptr()) }?;
+        // SAFETY: a mutable dictionary is a dictionary. The matching
+        // dictionary is consumed; 0 is the default main port
+        let service =
+            unsafe { IOServiceGetMatchingService(0, Some(CFRetained::cast_unchecked(matching))) };
+        if service == 0 {
+            return None;
+        }
+        let key = CFString::from_static_str("AppleClamshellState");
+        // SAFETY: `service` is a valid registry entry until released below
+        let value = unsafe { IORegistryEntryCreateCFProperty(service, Some(&key), None, 0) };
+        IOObjectRelease(service);
+        value?
+            .downcast::<CFBoolean>()
+            .ok()
+            .map(|value| value.as_bool())
+    }
+
+    pub fn read_power_state() -> PowerState {
+        let Some(info) = IOPSCopyPowerSourcesInfo() else {
+            return PowerState::default();
+        };
+        // SAFETY: `info` is a power sources blob from IOPSCopyPowerSourcesInfo
+        let providing =
+            unsafe { IOPSGetProvidingPowerSourceType(Some(&info)) }.map(|kind| kind.to_string());
+        let source = match providing.as_deref() {
+            Some(kind) if kind == kIOPSACPowerValue.to_string_lossy() => PowerSource::Ac,
+            Some(kind)
+                if kind == kIOPSBatteryPowerValue.to_string_lossy()
+                    || kind == kIOPMUPSPowerKey.to_string_lossy() =>
+            {
+                PowerSource::Battery
+            }
+            _ => PowerSource::Unknown,
+        };
+
+        PowerState {
+            source,
+            battery_percent: internal_battery_percent(&info),
+            lid_closed: lid_closed(),
+        }
+    }
+
+    pub fn watch_sleep(app: &AppHandle) {
+        let sleep_app = app.clone();
+        let on_sleep = RcBlock::new(move |_: NonNull<NSNotification>| handle_sleep(&sleep_app));
+        let wake_app = app.clone();
+        let on_wake = RcBlock::new(move |_: NonNull<NSNotification>| handle_wake(&wake_app));
+
+        let center = NSWorkspace::sharedWorkspace().notificationCenter();
+        // SAFETY: the blocks only hold app handles, which are Send. Without a
+        // queue they run on the thread that posts the notification
+        let observers = unsafe {
+            [
+                center.addObserverForName_object_queue_usingBlock(
+                    Some(NSWorkspaceWillSleepNotification),
+                    None,
+                    None,
+                    &on_sleep,
+                ),
+                center.addObserverForName_object_queue_usingBlock(
+                    Some(NSWorkspaceDidWakeNotification),
+                    None,
+                    None,
+                    &on_wake,
+                ),
+            ]
+        };
+        // The observers live for the rest of the app's lifetime
+        std::mem::forget(observers);
+    }
+
+    /// An IOKit power assertion, released when dropped.
+    pub struct SleepAssertion(IOPMAssertionID);
+
+    impl SleepAssertion {
+        pub fn create(
+            _app: &AppHandle,
+            reason: &str,
+            keep_display_on: bool,
+        ) -> Result<Self, AppError> {
+            // Preventing display sleep also keeps the system awake
+            let kind = CFString::from_static_str(if keep_display_on {
+                "PreventUserIdleDisplaySleep"
+            } else {
+                "PreventUserIdleSystemSleep"
+            });
+            let reason = CFString::from_str(reason);
+            let mut id: IOPMAssertionID = 0;
+            // SAFETY: valid strings and a valid pointer to write the id to
+            let result = unsafe {
+                IOPMAssertionCreateWithName(
+                    Some(&kind),
+                    kIOPMAssertionLevelOn,
+                    Some(&reason),
+                    &mut id,
+                )
+            };
+            if result != 0 {
+                return Err(AppError::internal(format!("IOKit error {result:#x}")));
+            }
+            Ok(Self(id))
+        }
+    }
+
+    impl Drop for SleepAssertion {
+        fn drop(&mut self) {
+            let result = IOPMAssertionRelease(self.0);
+            if result != 0 {
+                log::warn!("Failed to release power assertion: IOKit error {result:#x}");
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::ffi::c_void;
+    use std::sync::atomic::{AtomicU8, Ordering};
+    use std::sync::mpsc::{self, Sender};
+    use std::sync::OnceLock;
+    use tauri::AppHandle;
+    use windows_sys::Win32::System::Power::{
+        GetSystemPowerStatus, PowerRegisterSuspendResumeNotification,
+        PowerSettingRegisterNotification, SetThreadExecutionState,
+        DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS, ES_CONTINUOUS, ES_DISPLAY_REQUIRED, ES_SYSTEM_REQUIRED,
+        POWERBROADCAST_SETTING, SYSTEM_POWER_STATUS,
+    };
+    use windows_sys::Win32::System::SystemServices::GUID_LIDSWITCH_STATE_CHANGE;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        DEVICE_NOTIFY_CALLBACK, PBT_APMRESUMEAUTOMATIC, PBT_APMSUSPEND, PBT_POWERSETTINGCHANGE,
+    };
+
+    use super::{handle_sleep, handle_wake};
+    use crate::types::{AppError, PowerSource, PowerState};
+
+    static APP: OnceLock<AppHandle> = OnceLock::new();
+
+    /// Lid state last reported by Windows: 0 closed, 1 open, or
+    /// `LID_UNKNOWN` if it never was (no lid).
+    static LID_STATE: AtomicU8 = AtomicU8::new(LID_UNKNOWN);
+    const LID_UNKNOWN: u8 = u8::MAX;
+
+    /// `SYSTEM_POWER_STATUS` values meaning "no battery" or "unknown".
+    const NO_SYSTEM_BATTERY: u8 = 128;
+    const UNKNOWN_STATUS: u8 = 255;
+
+    pub fn read_power_state() -> PowerState {
+        let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+        // SAFETY: `status` is a valid SYSTEM_POWER_STATUS to write to
+        if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+            return PowerState::default();
+        }
+
+        let source = match status.ACLineStatus {
+            0 => PowerSource::Battery,
+            1 => PowerSource::Ac,
+            _ => PowerSource::Unknown,
+        };
+        let has_battery =
+            status.BatteryFlag & NO_SYSTEM_BATTERY == 0 && status.BatteryFlag != UNKNOWN_STATUS;
+        let battery_percent = (has_battery && status.BatteryLifePercent != UNKNOWN_STATUS)
+            .then_some(u32::from(status.BatteryLifePercent));
+        let lid_closed = match LID_STATE.load(Ordering::Relaxed) {
+            LID_UNKNOWN => None,
+            state => Some(state == 0),
+        };
+
+        PowerState {
+            source,
+            battery_percent,
+            lid_closed,
+        }
+    }
+
+    unsafe extern "system" fn on_power_event(
+        _context: *const c_void,
+        event: u32,
+        setting: *const c_void,
+    ) -> u32 {
+        let Some(app) = APP.get() else {
+            return 0;
+        };
+        match event {
+            PBT_APMSUSPEND => handle_sleep(app),
+            PBT_APMRESUMEAUTOMATIC => handle_wake(app),
+            // Only the lid switch setting is registered. Windows reports its
+            // current value right after registration, then every change
+            PBT_POWERSETTINGCHANGE if !setting.is_null() => {
+                // SAFETY: Windows passes a POWERBROADCAST_SETTING for this event
+                let setting = unsafe { &*setting.cast::<POWERBROADCAST_SETTING>() };
+                if setting.DataLength >= 1 {
+                    LID_STATE.store(setting.Data[0], Ordering::Relaxed);
+                }
+            }
+            _ => {}
+        }
+        0
+    }
+
+    pub fn watch_sleep(app: &AppHandle) {
+        if APP.set(app.clone()).is_err() {
+            return;
+        }
+
+        // Windows keeps using the parameters, so they live for the rest of
+        // the app's lifetime, as do the registrations
+        let parameters = Box::leak(Box::new(DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS {
+            Callback: Some(on_power_event),
+            Context: std::ptr::null_mut(),
+        }));
+        let recipient = (parameters as *mut DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS).cast();
+
+        let mut registration = std::ptr::null_mut();
+        // SAFETY: `recipient` points to parameters that are never freed
+        let result = unsafe {
+            PowerRegisterSuspendResumeNotification(
+                DEVICE_NOTIFY_CALLBACK,
+                recipient,
+                &mut registration,
+            )
+        };
+        if result != 0 {
+            log::warn!("Failed to register for sleep and wake notifications: error {result}");
+        }
+
+        let mut registration = std::ptr::null_mut();
+        // SAFETY: as above
+        let result = unsafe {
+            PowerSettingRegisterNotification(
+                &GUID_LIDSWITCH_STATE_CHANGE,
+                DEVICE_NOTIFY_CALLBACK,
+                recipient,
+                &mut registration,
+            )
+        };
+        if result != 0 {
+            log::warn!("Failed to register for lid notifications: error {result}");
+        }
+    }
+
+    /// A change to the number of active assertions, sent to the execution
+    /// state thread.
+    struct AssertionChange {
+        keep_display_on: bool,
+        delta: i32,
+    }
+
+    /// Sends to the thread that owns the execution state. The state belongs
+    /// to the thread that sets it, so it's always set from the same one.
+    fn execution_state_thread() -> &'static Sender<AssertionChange> {
+        static SENDER: OnceLock<Sender<AssertionChange>> = OnceLock::new();
+        SENDER.get_or_init(|| {
+            let (sender, receiver) = mpsc::channel::<AssertionChange>();
+            std::thread::spawn(move || {
+                let (mut system, mut display) = (0i32, 0i32);
+                for change in receiver {
+                    system += change.delta;
+                    if change.keep_display_on {
+                        display += change.delta;
+                    }
+
+                    let mut state = ES_CONTINUOUS;
+                    if system > 0 {
+                        state |= ES_SYSTEM_REQUIRED;
+                    }
+                    if display > 0 {
+                        state |= ES_DISPLAY_REQUIRED;
+                    }
+                    // SAFETY: only sets flags for this thread
+                    if unsafe { SetThreadExecutionState(state) } == 0 {
+                        log::warn!("Failed to set thread execution state");
+                    }
+                }
+            });
+            sender
+        })
+    }
+
+    /// A hold on the thread execution state, released when dropped.
+    pub struct SleepAssertion {
+        keep_display_on: bool,
+    }
+
+    impl SleepAssertion {
+        pub fn create(
+            _app: &AppHandle,
+            _reason: &str,
+            keep_display_on: bool,
+        ) -> Result<Self, AppError> {
+            execution_state_thread()
+                .send(AssertionChange {
+                    keep_display_on,
+                    delta: 1,
+                })
+                .map_err(|e| AppError::internal(e.to_string()))?;
+            Ok(Self { keep_display_on })
+        }
+    }
+
+    impl Drop for SleepAssertion {
+        fn drop(&mut self) {
+            let change = AssertionChange {
+                keep_display_on: self.keep_display_on,
+                delta: -1,
+            };
+            if let Err(e) = execution_state_thread().send(change) {
+                log::warn!("Failed to allow sleep: {e}");
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::path::Path;
+    use std::time::Duration;
+    use tauri::AppHandle;
+    use zbus::blocking::{Connection, Proxy};
+    use zbus::zvariant::OwnedFd;
+
+    use super::{handle_sleep, handle_wake};
+    use crate::types::{AppError, PowerSource, PowerState};
+
+    const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+    const LID_DIR: &str = "/proc/acpi/button/lid";
+
+    /// How long logind waits after `PrepareForSleep` before sleeping, so the
+    /// frontend can act on the event. logind caps this at its own
+    /// `InhibitDelayMaxSec` (5 seconds by default).
+    const SLEEP_DELAY: Duration = Duration::from_secs(2);
+
+    fn read_trimmed(path: &Path) -> Option<String> {
+        std::fs::read_to_string(path)
+            .ok()
+            .map(|contents| contents.trim().to_string())
+    }
+
+    /// Reads the power state from sysfs (`power_supply_dir`) and the ACPI lid
+    /// button (`lid_dir`).
+    pub(super) fn read_sysfs_power_state(power_supply_dir: &Path, lid_dir: &Path) -> PowerState {
+        let mut on_mains = None;
+        let mut battery = None;
+        if let Ok(entries) = std::fs::read_dir(power_supply_dir) {
+            for entry in entries.flatten() {
+                let supply = entry.path();
+                match read_trimmed(&supply.join("type")).as_deref() {
+                    Some("Mains") => {
+                        let online = read_trimmed(&supply.join("online")).as_deref() == Some("1");
+                        on_mains = Some(on_mains.unwrap_or(false) || online);
+                    }
+                    // Device-scoped batteries belong to mice, keyboards etc.
+                    Some("Battery")
+                        if read_trimmed(&supply.join("scope")).as_deref() != Some("Device") =>
+                    {
+                        let percent = read_trimmed(&supply.join("capacity"))
+                            .and_then(|capacity| capacity.parse::<u32>().ok());
+                        let discharging =
+                            read_trimmed(&supply.join("status")).as_deref() == Some("Discharging");
+                        battery = battery.or(Some((percent, discharging)));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let source = match (on_mains, battery) {
+            (Some(true), _) => PowerSource::Ac,
+            (Some(false), Some(_)) => PowerSource::Battery,
+            (None, Some((_, true))) => PowerSource::Battery,
+            (None, Some((_, false))) => PowerSource::Ac,
+            _ => PowerSource::Unknown,
+        };
+
+        // e.g. "state:      closed"
+        let lid_closed = std::fs::read_dir(lid_dir)
+            .ok()
+            .and_then(|mut entries| entries.find_map(|entry| entry.ok()))
+            .and_then(|lid| read_trimmed(&lid.path().join("state")))
+            .and_then(|state| match state.split_whitespace().last() {
+                Some("closed") => Some(true),
+                Some("open") => Some(false),
+                _ => None,
+            });
+
+        PowerState {
+            source,
+            battery_percent: battery.and_then(|(percent, _)| percent),
+            lid_closed,
+        }
+    }
+
+    pub fn read_power_state() -> PowerState {
+        read_sysfs_power_state(Path::new(POWER_SUPPLY_DIR), Path::new(LID_DIR))
+    }
+
+    /// Asks logind to wait for us before sleeping. The delay lasts until the
+    /// returned descriptor is closed.
+    fn take_sleep_inhibitor(manager: &Proxy<'_>, app: &AppHandle) -> Option<OwnedFd> {
+        let who = app.package_info().name.clone();
+        manager
+            .call(
+                "Inhibit",
+                &("sleep", who.as_str(), "Saving work before sleep", "delay"),
+            )
+            .map_err(|e| log::warn!("Failed to delay sleep: {e}"))
+            .ok()
+    }
+
+    fn logind_manager(connection: &Connection) -> zbus::Result<Proxy<'_>> {
+        Proxy::new(
+            connection,
+            "org.freedesktop.login1",
+            "/org/freedesktop/login1",
+            "org.freedesktop.login1.Manager",
+        )
+    }
+
+    fn watch_logind(app: &AppHandle) -> zbus::Result<()> {
+        let connection = Connection::system()?;
+        let manager = logind_manager(&connection)?;
+
+        let mut inhibitor = take_sleep_inhibitor(&manager, app);
+        for message in manager.receive_signal("PrepareForSleep")? {
+            let Ok(going_to_sleep) = message.body().deserialize::<bool>() else {
+                continue;
+            };
+            if going_to_sleep {
+                handle_sleep(app);
+                if inhibitor.is_some() {
+                    std::thread::sleep(SLEEP_DELAY);
+                    inhibitor = None;
+                }
+            } else {
+                handle_wake(app);
+                inhibitor = take_sleep_inhibitor(&manager, app);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn watch_sleep(app: &AppHandle) {
+        let app = app.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = watch_logind(&app) {
+                log::warn!("Sleep and wake events unavailable: {e}");
+            }
+        });
+    }
+
+    /// A logind idle inhibitor, released when dropped (closing the
+    /// descriptor). Desktop environments that follow logind keep the screen
+    /// on while it's held, so `keep_display_on` needs nothing extra.
+    pub struct SleepAssertion {
+        _inhibitor: OwnedFd,
+    }
+
+    impl SleepAssertion {
+        pub fn create(
+            app: &AppHandle,
+            reason: &str,
+            _keep_display_on: bool,
+        ) -> Result<Self, AppError> {
+            let inhibit = || -> zbus::Result<OwnedFd> {
+                let connection = Connection::system()?;
+                let who = &app.package_info().name;
+                let manager = logind_manager(&connection)?;
+                manager.call("Inhibit", &("idle", who.as_str(), reason, "block"))
+            };
+            inhibit()
+                .map(|inhibitor| Self {
+                    _inhibitor: inhibitor,
+                })
+                .map_err(|e| AppError::internal(e.to_string()))
+        }
+    }
+}
+
+#[cfg(mobile)]
+mod platform {
+    use tauri::AppHandle;
+
+    use crate::types::{AppError, PowerState};
+
+    pub fn read_power_state() -> PowerState {
+        PowerState::default()
+    }
+
+    pub struct SleepAssertion;
+
+    impl SleepAssertion {
+        pub fn create(
+            _app: &AppHandle,
+            _reason: &str,
+            _keep_display_on: bool,
+        ) -> Result<Self, AppError> {
+            Err(AppError::internal("Not supported on mobile"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(
+        source: PowerSource,
+        battery_percent: Option<u32>,
+        lid_closed: Option<bool>,
+    ) -> PowerState {
+        PowerState {
+            source,
+            battery_percent,
+            lid_closed,
+        }
+    }
+
+    #[test]
+    fn test_state_changes() {
+        let plugged_in = state(PowerSource::Ac, Some(50), Some(false));
+        assert!(state_changes(&plugged_in, &plugged_in).is_empty());
+
+        let unplugged = state(PowerSource::Battery, Some(50), Some(true));
+        assert_eq!(
+            state_changes(&plugged_in, &unplugged),
+            vec![
+                PowerChange::PowerSourceChanged {
+                    source: PowerSource::Battery
+                },
+                PowerChange::LidClosed
+            ]
+        );
+
+        // Low battery is reported once per discharge
+        let low = state(PowerSource::Battery, Some(10), Some(true));
+        assert_eq!(
+            state_changes(&unplugged, &low),
+            vec![PowerChange::LowBattery { percent: 10 }]
+        );
+        let lower = state(PowerSource::Battery, Some(8), Some(true));
+        assert!(state_changes(&low, &lower).is_empty());
+        let charging = state(PowerSource::Ac, Some(8), Some(true));
+        assert!(!state_changes(&lower, &charging).contains(&PowerChange::LowBattery { percent: 8 }));
+
+        // Readings that fail aren't reported as changes
+        let unknown = state(PowerSource::Unknown, None, None);
+        assert!(state_changes(&plugged_in, &unknown).is_empty());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_read_sysfs_power_state() {
+        let root = std::env::temp_dir().join(format!("power-test-{}", std::process::id()));
+        let supplies = root.join("power_supply");
+        let lids = root.join("lid");
+        let write = |path: std::path::PathBuf, contents: &str| {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        };
+
+        // Nothing to read, e.g. a desktop or a container
+        assert_eq!(
+            platform::read_sysfs_power_state(&supplies, &lids),
+            PowerState::default()
+        );
+
+        write(supplies.join("AC/type"), "Mains\n");
+        write(supplies.join("AC/online"), "0\n");
+        write(supplies.join("BAT0/type"), "Battery\n");
+        write(supplies.join("BAT0/capacity"), "42\n");
+        write(supplies.join("BAT0/status"), "Discharging\n");
+        write(supplies.join("hid-mouse/type"), "Battery\n");
+        write(supplies.join("hid-mouse/scope"), "Device\n");
+        write(supplies.join("hid-mouse/capacity"), "90\n");
+        write(lids.join("LID0/state"), "state:      closed\n");
+        assert_eq!(
+            platform::read_sysfs_power_state(&supplies, &lids),
+            state(PowerSource::Battery, Some(42), Some(true))
+        );
+
+        write(supplies.join("AC/online"), "1\n");
+        write(lids.join("LID0/state"), "state:      open\n");
+        assert_eq!(
+            platform::read_sysfs_power_state(&supplies, &lids),
+            state(PowerSource::Ac, Some(42), Some(false))
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -3,48 +3,111 @@
 //! Handles loading and saving user preferences to disk.
 
 use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
-use crate::types::{validate_string_input, validate_theme, AppPreferences};
+use super::files::write_atomic;
+use crate::types::{
+    validate_string_input, validate_theme, AppError, AppPreferences, LogFormat, LogLevel,
+    UpdateChannel,
+};
 
 /// Gets the path to the preferences file.
-fn get_preferences_path(app: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+pub fn get_preferences_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = crate::data_dir::app_data_dir(app)?;
 
     // Ensure the directory exists
-    std::fs::create_dir_all(&app_data_dir)
-        .map_err(|e| format!("Failed to create app data directory: {e}"))?;
+    std::fs::create_dir_all(&app_data_dir).map_err(|e| AppError::Io {
+        message: format!("Failed to create app data directory: {e}"),
+    })?;
 
     Ok(app_data_dir.join("preferences.json"))
 }
 
-/// Load the saved quick pane shortcut from preferences, returning None on any failure.
+/// Load saved preferences synchronously, returning None on any failure.
 /// Used at startup before the full preferences system is available.
-pub fn load_quick_pane_shortcut(app: &AppHandle) -> Option<String> {
+fn load_saved_preferences(app: &AppHandle) -> Option<AppPreferences> {
     let path = get_preferences_path(app).ok()?;
     if !path.exists() {
         return None;
     }
     let contents = std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(crate::encryption::decrypt_if_encrypted)
         .inspect_err(|e| log::warn!("Failed to read preferences: {e}"))
         .ok()?;
     let prefs: AppPreferences = serde_json::from_str(&contents)
         .inspect_err(|e| log::warn!("Failed to parse preferences: {e}"))
         .ok()?;
-    prefs.quick_pane_shortcut
+    Some(prefs)
+}
+
+/// Load the saved quick pane shortcut from preferences, returning None on any failure.
+pub fn load_quick_pane_shortcut(app: &AppHandle) -> Option<String> {
+    load_saved_preferences(app)?.quick_pane_shortcut
+}
+
+/// Load the saved activate-app shortcut from preferences, returning None on any failure.
+pub fn load_activate_app_shortcut(app: &AppHandle) -> Option<String> {
+    load_saved_preferences(app)?.activate_app_shortcut
+}
+
+/// Load whether session restore is enabled, defaulting to enabled on any failure.
+pub fn load_restore_session(app: &AppHandle) -> bool {
+    load_saved_preferences(app).is_none_or(|prefs| prefs.restore_session)
+}
+
+/// Load the saved update channel, defaulting to stable on any failure.
+pub fn load_update_channel(app: &AppHandle) -> UpdateChannel {
+    load_saved_preferences(app)
+        .map(|prefs| prefs.update_channel)
+        .unwrap_or_default()
+}
+
+/// Load the automatic update check interval in hours (0 means off),
+/// defaulting to the preference default on any failure.
+pub fn load_update_check_interval_hours(app: &AppHandle) -> u32 {
+    load_saved_preferences(app)
+        .unwrap_or_default()
+        .update_check_interval_hours
+}
+
+/// Load the log file size limit in MB and the number of rotated log files to
+/// keep, defaulting to the preference defaults on any failure.
+pub fn load_log_limits(app: &AppHandle) -> (u32, u32) {
+    let prefs = load_saved_preferences(app).unwrap_or_default();
+    (prefs.log_max_file_size_mb, prefs.log_retention_count)
+}
+
+/// Load the saved log file format, returning the default on any failure.
+pub fn load_log_format(app: &AppHandle) -> LogFormat {
+    load_saved_preferences(app)
+        .map(|prefs| prefs.log_format)
+        .unwrap_or_default()
+}
+
+/// Load the saved log level, returning None if unset or on any failure.
+pub fn load_log_level(app: &AppHandle) -> Option<LogLevel> {
+    load_saved_preferences(app).and_then(|prefs| prefs.log_level)
+}
+
+/// Whether the user has opted in to analytics. False on any failure.
+pub fn load_analytics_enabled(app: &AppHandle) -> bool {
+    load_saved_preferences(app).is_some_and(|prefs| prefs.analytics_enabled)
+}
+
+/// Whether local data should be encrypted. False on any failure.
+pub fn load_encrypt_local_data(app: &AppHandle) -> bool {
+    load_saved_preferences(app).is_some_and(|prefs| prefs.encrypt_local_data)
 }
 
 /// Simple greeting command for demonstration purposes.
 #[tauri::command]
 #[specta::specta]
-pub fn greet(name: &str) -> Result<String, String> {
+pub fn greet(name: &str) -> Result<String, AppError> {
     // Input validation
-    validate_string_input(name, 100, "Name").map_err(|e| {
-        log::warn!("Invalid greet input: {e}");
-        e
+    validate_string_input(name, 100, "Name").map_err(|message| {
+        log::warn!("Invalid greet input: {message}");
+        AppError::Validation { message }
     })?;
 
     log::info!("Greeting user: {name}");
@@ -55,7 +118,7 @@ pub fn greet(name: &str) -> Result<String, String> {
 /// Returns default preferences if the file doesn't exist.
 #[tauri::command]
 #[specta::specta]
-pub async fn load_preferences(app: AppHandle) -> Result<AppPreferences, String> {
+pub async fn load_preferences(app: AppHandle) -> Result<AppPreferences, AppError> {
     log::debug!("Loading preferences from disk");
     let prefs_path = get_preferences_path(&app)?;
 
@@ -66,12 +129,21 @@ pub async fn load_preferences(app: AppHandle) -> Result<AppPreferences, String>
 
     let contents = std::fs::read_to_string(&prefs_path).map_err(|e| {
         log::error!("Failed to read preferences file: {e}");
-        format!("Failed to read preferences file: {e}")
+        AppError::Io {
+            message: format!("Failed to read preferences file: {e}"),
+        }
+    })?;
+    // Fails when the key can't be read from the keychain
+    let contents = crate::encryption::decrypt_if_encrypted(contents).map_err(|message| {
+        log::error!("{message}");
+        AppError::Permission { message }
     })?;
 
     let preferences: AppPreferences = serde_json::from_str(&contents).map_err(|e| {
         log::error!("Failed to parse preferences JSON: {e}");
-        format!("Failed to parse preferences: {e}")
+        AppError::Serialization {
+            message: format!("Failed to parse preferences: {e}"),
+        }
     })?;
 
     log::info!("Successfully loaded preferences");
@@ -82,34 +154,30 @@ pub async fn load_preferences(app: AppHandle) -> Result<AppPreferences, String>
 /// Uses atomic write (temp file + rename) to prevent corruption.
 #[tauri::command]
 #[specta::specta]
-pub async fn save_preferences(app: AppHandle, preferences: AppPreferences) -> Result<(), String> {
+pub async fn save_preferences(app: AppHandle, preferences: AppPreferences) -> Result<(), AppError> {
     // Validate theme value
-    validate_theme(&preferences.theme)?;
+    validate_theme(&preferences.theme).map_err(|message| AppError::Validation { message })?;
 
     log::debug!("Saving preferences to disk: {preferences:?}");
     let prefs_path = get_preferences_path(&app)?;
 
     let json_content = serde_json::to_string_pretty(&preferences).map_err(|e| {
         log::error!("Failed to serialize preferences: {e}");
-        format!("Failed to serialize preferences: {e}")
+        AppError::Serialization {
+            message: format!("Failed to serialize preferences: {e}"),
+        }
     })?;
+    let json_content = crate::encryption::encrypt_if_enabled(&json_content)
+        .map_err(|message| AppError::Permission { message })?;
 
-    // Write to a temporary file first, then rename (atomic operation)
-    let temp_path = prefs_path.with_extension("tmp");
-
-    std::fs::write(&temp_path, json_content).map_err(|e| {
+    write_atomic(&prefs_path, json_content.as_bytes()).map_err(|e| {
         log::error!("Failed to write preferences file: {e}");
-        format!("Failed to write preferences file: {e}")
+        AppError::Io {
+            message: format!("Failed to write preferences file: {e}"),
+        }
     })?;
 
-    if let Err(rename_err) = std::fs::rename(&temp_path, &prefs_path) {
-        log::error!("Failed to finalize preferences file: {rename_err}");
-        // Clean up the temp file to avoid leaving orphaned files on disk
-        if let Err(remove_err) = std::fs::remove_file(&temp_path) {
-            log::warn!("Failed to remove temp file after rename failure: {remove_err}");
-        }
-        return Err(format!("Failed to finalize preferences file: {rename_err}"));
-    }
+    super::analytics::set_enabled(preferences.analytics_enabled);
 
     log::info!("Successfully saved preferences to {prefs_path:?}");
     Ok(())
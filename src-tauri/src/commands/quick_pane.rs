@@ -1,36 +1,212 @@
 //! Quick pane window management commands.
 //!
-//! The quick pane is a floating panel (NSPanel on macOS, standard window elsewhere)
+//! A quick pane is a floating panel (NSPanel on macOS, standard window elsewhere)
 //! that provides quick entry functionality accessible via global shortcut.
+//!
+//! Panes are kept in a registry keyed by window label. The template registers a
+//! single default pane (`quick-pane`) at startup; apps can call `register_quick_pane`
+//! from `setup()` to add more (e.g. a command palette and a capture pane).
+//! The show/dismiss/toggle commands take an optional label and fall back to the
+//! default pane when it is omitted.
 
+use std::collections::BTreeMap;
 use std::sync::Mutex;
 use tauri::{AppHandle, Manager, WebviewUrl};
+use tauri_specta::Event;
 
-use crate::types::DEFAULT_QUICK_PANE_SHORTCUT;
+use crate::types::{
+    validate_string_input, AppError, QuickEntrySubmitted, QuickPanePosition, WindowLevel,
+    DEFAULT_QUICK_PANE_SHORTCUT,
+};
+#[cfg(target_os = "macos")]
+use crate::utils::macos_focus;
+
+use super::window::MAIN_WINDOW_LABEL;
 
 // ============================================================================
 // Constants
 // ============================================================================
 
-/// Window label for the quick pane
-const QUICK_PANE_LABEL: &str = "quick-pane";
+/// Window label for the default quick pane
+pub const QUICK_PANE_LABEL: &str = "quick-pane";
 
-/// Quick pane window dimensions
+/// Default quick pane window dimensions
 const QUICK_PANE_WIDTH: f64 = 500.0;
 const QUICK_PANE_HEIGHT: f64 = 72.0;
 
+/// Default maximum height when resizing to fit content
+const QUICK_PANE_MAX_HEIGHT: f64 = 480.0;
+
+/// Maximum length of a submitted quick pane entry (characters)
+const MAX_QUICK_ENTRY_LENGTH: usize = 10_000;
+
+/// Gap between the cursor and the pane in `NearCursor` mode (logical pixels)
+const NEAR_CURSOR_OFFSET: f64 = 16.0;
+
 /// Tracks the currently registered quick pane shortcut for selective unregistration.
 /// This allows us to unregister only our shortcut without affecting other shortcuts.
 static CURRENT_QUICK_PANE_SHORTCUT: Mutex<Option<String>> = Mutex::new(None);
 
+/// Registry of created quick panes, keyed by window label.
+static QUICK_PANES: Mutex<BTreeMap<String, QuickPaneConfig>> = Mutex::new(BTreeMap::new());
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// How a quick pane behaves across macOS Spaces. Ignored on other platforms.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QuickPaneSpaces {
+    /// Visible on every Space at once
+    #[default]
+    JoinAllSpaces,
+    /// Moves to whichever Space is active when shown
+    MoveToActiveSpace,
+}
+
+/// Native translucent background for a quick pane.
+/// On macOS this attaches an `NSVisualEffectView` behind the webview; on Windows
+/// `Hud` maps to acrylic and `Popover` to mica. Ignored on Linux.
+/// The frontend needs a transparent (or semi-transparent) background for it to show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickPaneVibrancy {
+    /// Dark, high-contrast material used by HUD windows (acrylic on Windows)
+    Hud,
+    /// Material used by popovers, follows the system appearance (mica on Windows)
+    Popover,
+}
+
+/// Describes a quick pane to create with `register_quick_pane`.
+#[derive(Debug, Clone)]
+pub struct QuickPaneConfig {
+    pub label: String,
+    /// Path of the HTML entry point, relative to the app's frontend dist
+    pub url: String,
+    pub width: f64,
+    /// Current height (updated by `resize_quick_pane`)
+    pub height: f64,
+    /// Height limits applied by `resize_quick_pane`
+    pub min_height: f64,
+    pub max_height: f64,
+    /// `Status` by default, so the pane appears above fullscreen apps on macOS
+    pub level: WindowLevel,
+    pub position: QuickPanePosition,
+    pub spaces: QuickPaneSpaces,
+    /// Native blur behind the webview (`None` keeps the plain transparent window)
+    pub vibrancy: Option<QuickPaneVibrancy>,
+    /// Physical position when the pane was last dismissed (for `Remembered` mode)
+    last_position: Option<tauri::PhysicalPosition<i32>>,
+    /// While pinned, the pane isn't dismissed on blur or submit (see `set_quick_pane_pinned`)
+    pinned: bool,
+}
+
+impl QuickPaneConfig {
+    /// Creates a config with the default quick pane size and level.
+    pub fn new(label: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            url: url.into(),
+            width: QUICK_PANE_WIDTH,
+            height: QUICK_PANE_HEIGHT,
+            min_height: QUICK_PANE_HEIGHT,
+            max_height: QUICK_PANE_MAX_HEIGHT,
+            level: WindowLevel::Status,
+            position: QuickPanePosition::default(),
+            spaces: QuickPaneSpaces::default(),
+            vibrancy: None,
+            last_position: None,
+            pinned: false,
+        }
+    }
+
+    /// Sets the logical size of the pane.
+    /// The height also becomes the minimum height for `resize_quick_pane`.
+    pub fn size(mut self, width: f64, height: f64) -> Self {
+        self.width = width;
+        self.height = height;
+        self.min_height = height;
+        self
+    }
+
+    /// Sets the height limits applied by `resize_quick_pane`.
+    pub fn height_range(mut self, min_height: f64, max_height: f64) -> Self {
+        self.min_height = min_height;
+        self.max_height = max_height.max(min_height);
+        self
+    }
+
+    /// Sets the window level of the pane.
+    pub fn level(mut self, level: WindowLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Sets where the pane appears when shown.
+    pub fn position(mut self, position: QuickPanePosition) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Sets how the pane behaves across macOS Spaces.
+    pub fn spaces(mut self, spaces: QuickPaneSpaces) -> Self {
+        self.spaces = spaces;
+        self
+    }
+
+    /// Gives the pane a native frosted background.
+    pub fn vibrancy(mut self, vibrancy: QuickPaneVibrancy) -> Self {
+        self.vibrancy = Some(vibrancy);
+        self
+    }
+}
+
+/// Returns the config for a registered quick pane.
+pub(crate) fn get_quick_pane_config(label: &str) -> Result<QuickPaneConfig, AppError> {
+    QUICK_PANES
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock quick pane registry: {e}")))?
+        .get(label)
+        .cloned()
+        .ok_or_else(|| AppError::not_found(format!("Quick pane '{label}' is not registered")))
+}
+
+/// Returns whether `label` belongs to a registered quick pane.
+/// Used to keep quick panes out of the window-state plugin (NSPanels crash it).
+pub fn is_quick_pane_label(label: &str) -> bool {
+    QUICK_PANES
+        .lock()
+        .map(|panes| panes.contains_key(label))
+        .unwrap_or(false)
+}
+
+/// Resolves an optional label from the frontend to a quick pane label.
+pub(crate) fn resolve_label(label: Option<String>) -> String {
+    label.unwrap_or_else(|| QUICK_PANE_LABEL.to_string())
+}
+
 // ============================================================================
 // macOS-specific: NSPanel support
 // ============================================================================
 
 #[cfg(target_os = "macos")]
-use tauri_nspanel::{
-    tauri_panel, CollectionBehavior, ManagerExt, PanelBuilder, PanelLevel, StyleMask,
-};
+use tauri_nspanel::{tauri_panel, CollectionBehavior, ManagerExt, PanelBuilder, StyleMask};
+
+/// Collection behavior for a quick pane panel. Pinned panes are also stationary
+/// (unaffected by Exposé/Mission Control) and skipped when cycling windows.
+#[cfg(target_os = "macos")]
+fn quick_pane_collection_behavior(config: &QuickPaneConfig) -> CollectionBehavior {
+    let behavior = CollectionBehavior::new().full_screen_auxiliary();
+    // AppKit treats these two as mutually exclusive
+    let behavior = match config.spaces {
+        QuickPaneSpaces::JoinAllSpaces => behavior.can_join_all_spaces(),
+        QuickPaneSpaces::MoveToActiveSpace => behavior.move_to_active_space(),
+    };
+    if config.pinned {
+        behavior.stationary().ignores_cycle()
+    } else {
+        behavior
+    }
+}
 
 // Define custom panel class for quick pane (macOS only)
 #[cfg(target_os = "macos")]
@@ -48,40 +224,64 @@ tauri_panel! {
 // Window Initialization
 // ============================================================================
 
-/// Creates the quick pane window at app startup.
+/// Creates the default quick pane window at app startup.
 /// Must be called from the main thread (e.g., in setup()).
 /// The window starts hidden and is shown via show_quick_pane command.
-pub fn init_quick_pane(app: &AppHandle) -> Result<(), String> {
-    #[cfg(target_os = "macos")]
+pub fn init_quick_pane(app: &AppHandle, position: QuickPanePosition) -> Result<(), AppError> {
+    register_quick_pane(
+        app,
+        QuickPaneConfig::new(QUICK_PANE_LABEL, "quick-pane.html").position(position),
+    )
+}
+
+/// Creates a quick pane (hidden) and adds it to the registry.
+/// Must be called from the main thread (e.g., in setup()).
+pub fn register_quick_pane(app: &AppHandle, config: QuickPaneConfig) -> Result<(), AppError> {
+    let label = config.label.clone();
+
+    // Register before creating the window: the window-state plugin checks
+    // is_quick_pane_label() as soon as the window is ready.
     {
-        init_quick_pane_macos(app)
+        let mut panes = QUICK_PANES
+            .lock()
+            .map_err(|e| AppError::internal(format!("Failed to lock quick pane registry: {e}")))?;
+        if panes.contains_key(&label) {
+            return Err(AppError::validation(format!(
+                "Quick pane '{label}' is already registered"
+            )));
+        }
+        panes.insert(label.clone(), config.clone());
     }
 
+    #[cfg(target_os = "macos")]
+    let result = init_quick_pane_macos(app, &config);
+
     #[cfg(not(target_os = "macos"))]
-    {
-        init_quick_pane_standard(app)
+    let result = init_quick_pane_standard(app, &config);
+
+    if result.is_err() {
+        if let Ok(mut panes) = QUICK_PANES.lock() {
+            panes.remove(&label);
+        }
     }
+
+    result
 }
 
-/// Creates the quick pane as an NSPanel on macOS (hidden).
+/// Creates a quick pane as an NSPanel on macOS (hidden).
 #[cfg(target_os = "macos")]
-fn init_quick_pane_macos(app: &AppHandle) -> Result<(), String> {
+fn init_quick_pane_macos(app: &AppHandle, config: &QuickPaneConfig) -> Result<(), AppError> {
     use tauri::{LogicalSize, Size};
 
-    log::debug!("Creating quick pane as NSPanel (macOS)");
+    log::debug!("Creating quick pane '{}' as NSPanel (macOS)", config.label);
 
-    let panel = PanelBuilder::<_, QuickPanePanel>::new(app, QUICK_PANE_LABEL)
-        .url(WebviewUrl::App("quick-pane.html".into()))
+    let panel = PanelBuilder::<_, QuickPanePanel>::new(app, &config.label)
+        .url(WebviewUrl::App(config.url.clone().into()))
         .title("Quick Entry")
-        .size(Size::Logical(LogicalSize::new(500.0, 72.0)))
-        .level(PanelLevel::Status) // Status level to appear above fullscreen apps
+        .size(Size::Logical(LogicalSize::new(config.width, config.height)))
         .transparent(true)
         .has_shadow(true)
-        .collection_behavior(
-            CollectionBehavior::new()
-                .full_screen_auxiliary()
-                .can_join_all_spaces(),
-        )
+        .collection_behavior(quick_pane_collection_behavior(config))
         .style_mask(StyleMask::empty().nonactivating_panel())
         .hides_on_deactivate(false)
         .works_when_modal(true)
@@ -93,39 +293,122 @@ fn init_quick_pane_macos(app: &AppHandle) -> Result<(), String> {
                 .center()
         })
         .build()
-        .map_err(|e| format!("Failed to create quick pane panel: {e}"))?;
+        .map_err(|e| AppError::internal(format!("Failed to create quick pane panel: {e}")))?;
+
+    let window = app.get_webview_window(&config.label).ok_or_else(|| {
+        AppError::not_found(format!("Quick pane window '{}' not found", config.label))
+    })?;
+    super::window::set_ns_window_level(&window, config.level)?;
+    if let Some(vibrancy) = config.vibrancy {
+        attach_visual_effect_view(&window, vibrancy)?;
+    }
+
+    install_escape_key_monitor(app);
 
     // Start hidden - will be shown via show_quick_pane command
     panel.hide();
-    log::info!("Quick pane NSPanel created (hidden)");
+    log::info!("Quick pane '{}' NSPanel created (hidden)", config.label);
     Ok(())
 }
 
-/// Creates the quick pane as a standard Tauri window (hidden) on non-macOS platforms.
+/// Inserts an `NSVisualEffectView` below the webview so the transparent panel
+/// gets a native frosted background. Done directly rather than via `window-vibrancy`,
+/// which conflicts with `tauri-nspanel`.
+#[cfg(target_os = "macos")]
+fn attach_visual_effect_view(
+    window: &tauri::WebviewWindow,
+    vibrancy: QuickPaneVibrancy,
+) -> Result<(), AppError> {
+    use objc2_app_kit::{
+        NSAutoresizingMaskOptions, NSVisualEffectBlendingMode, NSVisualEffectMaterial,
+        NSVisualEffectState, NSVisualEffectView, NSWindow, NSWindowOrderingMode,
+    };
+    use objc2_foundation::MainThreadMarker;
+
+    let mtm = MainThreadMarker::new().ok_or_else(|| {
+        AppError::internal("Quick pane vibrancy must be set up on the main thread")
+    })?;
+
+    let ns_window_ptr = window
+        .ns_window()
+        .map_err(|e| AppError::internal(format!("Failed to get native window: {e}")))?;
+    // SAFETY: ns_window() returns a valid NSWindow pointer that lives as long as the window
+    let ns_window: &NSWindow = unsafe { &*ns_window_ptr.cast::<NSWindow>() };
+    let content_view = ns_window
+        .contentView()
+        .ok_or_else(|| AppError::internal("Quick pane window has no content view"))?;
+
+    let material = match vibrancy {
+        QuickPaneVibrancy::Hud => NSVisualEffectMaterial::HUDWindow,
+        QuickPaneVibrancy::Popover => NSVisualEffectMaterial::Popover,
+    };
+
+    let effect_view =
+        NSVisualEffectView::initWithFrame(NSVisualEffectView::alloc(mtm), content_view.bounds());
+    effect_view.setMaterial(material);
+    effect_view.setBlendingMode(NSVisualEffectBlendingMode::BehindWindow);
+    // Stay active even though the non-activating panel never makes our app active
+    effect_view.setState(NSVisualEffectState::Active);
+    effect_view.setAutoresizingMask(
+        NSAutoresizingMaskOptions::ViewWidthSizable | NSAutoresizingMaskOptions::ViewHeightSizable,
+    );
+    content_view.addSubview_positioned_relativeTo(&effect_view, NSWindowOrderingMode::Below, None);
+
+    log::debug!("Attached {vibrancy:?} visual effect view to quick pane");
+    Ok(())
+}
+
+/// Creates a quick pane as a standard Tauri window (hidden) on non-macOS platforms.
 #[cfg(not(target_os = "macos"))]
-fn init_quick_pane_standard(app: &AppHandle) -> Result<(), String> {
+fn init_quick_pane_standard(app: &AppHandle, config: &QuickPaneConfig) -> Result<(), AppError> {
     use tauri::webview::WebviewWindowBuilder;
 
-    log::debug!("Creating quick pane as standard window");
+    log::debug!("Creating quick pane '{}' as standard window", config.label);
 
-    WebviewWindowBuilder::new(
+    // Floating and Status both map to always-on-top; finer window levels are macOS-only
+    #[cfg_attr(not(target_os = "windows"), allow(unused_mut))]
+    let mut builder = WebviewWindowBuilder::new(
         app,
-        QUICK_PANE_LABEL,
-        WebviewUrl::App("quick-pane.html".into()),
+        &config.label,
+        WebviewUrl::App(config.url.clone().into()),
     )
     .title("Quick Entry")
-    .inner_size(500.0, 72.0)
-    .always_on_top(true)
+    .inner_size(config.width, config.height)
+    .always_on_top(config.level != WindowLevel::Normal)
     .skip_taskbar(true)
     .decorations(false)
     .transparent(true)
     .visible(false) // Start hidden
     .resizable(false)
-    .center()
-    .build()
-    .map_err(|e| format!("Failed to create quick pane window: {e}"))?;
+    .center();
+
+    #[cfg(target_os = "windows")]
+    if let Some(vibrancy) = config.vibrancy {
+        use tauri::window::{Effect, EffectsBuilder};
+
+        let effect = match vibrancy {
+            QuickPaneVibrancy::Hud => Effect::Acrylic,
+            QuickPaneVibrancy::Popover => Effect::Mica,
+        };
+        builder = builder.effects(EffectsBuilder::new().effect(effect).build());
+    }
+
+    #[cfg(target_os = "linux")]
+    if config.vibrancy.is_some() {
+        log::debug!("Quick pane vibrancy is not supported on Linux, ignoring");
+    }
+
+    let window = builder
+        .build()
+        .map_err(|e| AppError::internal(format!("Failed to create quick pane window: {e}")))?;
 
-    log::info!("Quick pane window created (hidden)");
+    #[cfg(target_os = "windows")]
+    install_escape_accelerator(&window)?;
+
+    #[cfg(target_os = "linux")]
+    install_escape_key_handler(&window)?;
+
+    log::info!("Quick pane '{}' window created (hidden)", config.label);
     Ok(())
 }
 
@@ -151,11 +434,55 @@ fn get_monitor_for_cursor(
     }
 }
 
-/// Calculates the position to center a window on the monitor containing the cursor.
-/// Falls back to primary monitor if cursor monitor cannot be determined.
-fn get_centered_position_on_cursor_monitor(
+/// Computes the top-left physical position of a pane on a monitor.
+///
+/// All values are physical pixels. The result is clamped so the pane stays
+/// fully within the monitor bounds. `Remembered` is resolved by the caller and
+/// is treated as `Centered` here.
+fn compute_pane_position(
+    mode: QuickPanePosition,
+    monitor_pos: (i32, i32),
+    monitor_size: (i32, i32),
+    pane_size: (i32, i32),
+    cursor: (i32, i32),
+    cursor_offset: i32,
+) -> (i32, i32) {
+    let (mx, my) = monitor_pos;
+    let (mw, mh) = monitor_size;
+    let (pw, ph) = pane_size;
+
+    let (x, y) = match mode {
+        QuickPanePosition::Centered | QuickPanePosition::Remembered => {
+            (mx + (mw - pw) / 2, my + (mh - ph) / 2)
+        }
+        // Like Spotlight: horizontally centered, vertically centered on the top-third line
+        QuickPanePosition::TopThird => (mx + (mw - pw) / 2, my + mh / 3 - ph / 2),
+        QuickPanePosition::NearCursor => (cursor.0 - pw / 2, cursor.1 + cursor_offset),
+    };
+
+    // Keep the pane on screen (if it's larger than the monitor, pin to the top-left)
+    let x = x.min(mx + mw - pw).max(mx);
+    let y = y.min(my + mh - ph).max(my);
+    (x, y)
+}
+
+/// Calculates where to show a pane based on its position mode.
+/// Uses the monitor containing the cursor, falling back to primary monitor.
+fn get_quick_pane_position(
     app: &AppHandle,
+    config: &QuickPaneConfig,
 ) -> Option<tauri::PhysicalPosition<i32>> {
+    // Remembered mode: reuse the last position if it's still on a connected monitor
+    if config.position == QuickPanePosition::Remembered {
+        if let Some(last) = config.last_position {
+            if let Ok(Some(_)) = app.monitor_from_point(last.x as f64, last.y as f64) {
+                log::debug!("Using remembered position: ({}, {})", last.x, last.y);
+                return Some(last);
+            }
+            log::debug!("Remembered position is off-screen, falling back to centered");
+        }
+    }
+
     // Get cursor position
     let cursor_pos = match app.cursor_position() {
         Ok(pos) => pos,
@@ -183,23 +510,53 @@ fn get_centered_position_on_cursor_monitor(
         scale_factor
     );
 
-    // Calculate centered position on this monitor
     // Window size needs to be scaled by the monitor's scale factor
-    let scaled_width = (QUICK_PANE_WIDTH * scale_factor) as i32;
-    let scaled_height = (QUICK_PANE_HEIGHT * scale_factor) as i32;
+    let scaled_width = (config.width * scale_factor) as i32;
+    let scaled_height = (config.height * scale_factor) as i32;
 
-    let x = monitor_pos.x + (monitor_size.width as i32 - scaled_width) / 2;
-    let y = monitor_pos.y + (monitor_size.height as i32 - scaled_height) / 2;
+    let (x, y) = compute_pane_position(
+        config.position,
+        (monitor_pos.x, monitor_pos.y),
+        (monitor_size.width as i32, monitor_size.height as i32),
+        (scaled_width, scaled_height),
+        (cursor_pos.x as i32, cursor_pos.y as i32),
+        (NEAR_CURSOR_OFFSET * scale_factor) as i32,
+    );
 
-    log::debug!("Calculated position: ({x}, {y})");
+    log::debug!("Calculated position ({:?}): ({x}, {y})", config.position);
 
     Some(tauri::PhysicalPosition::new(x, y))
 }
 
-/// Positions the quick pane window centered on the monitor containing the cursor.
-fn position_quick_pane_on_cursor_monitor(app: &AppHandle) {
-    if let Some(position) = get_centered_position_on_cursor_monitor(app) {
-        if let Some(window) = app.get_webview_window(QUICK_PANE_LABEL) {
+/// Re-centers a quick pane on the monitor the user is working on (the one with
+/// the cursor), ignoring any remembered position. Useful when the pane is already
+/// visible but appeared on a different display. On macOS, pair with
+/// `QuickPaneSpaces::MoveToActiveSpace` to also follow the active Space.
+/// Defaults to the main quick pane when no label is given.
+#[tauri::command]
+#[specta::specta]
+pub fn recenter_quick_pane(app: AppHandle, label: Option<String>) -> Result<(), AppError> {
+    let label = resolve_label(label);
+    let mut config = get_quick_pane_config(&label)?;
+    if config.position == QuickPanePosition::Remembered {
+        config.position = QuickPanePosition::Centered;
+    }
+
+    log::info!("Re-centering quick pane '{label}' on the active monitor");
+    let position = get_quick_pane_position(&app, &config)
+        .ok_or_else(|| AppError::internal("Could not determine the active monitor"))?;
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| AppError::not_found(format!("Quick pane window '{label}' not found")))?;
+    window
+        .set_position(position)
+        .map_err(|e| AppError::internal(format!("Failed to set window position: {e}")))
+}
+
+/// Positions a quick pane window according to its position mode.
+fn position_quick_pane(app: &AppHandle, config: &QuickPaneConfig) {
+    if let Some(position) = get_quick_pane_position(app, config) {
+        if let Some(window) = app.get_webview_window(&config.label) {
             if let Err(e) = window.set_position(position) {
                 log::warn!("Failed to set window position: {e}");
             }
@@ -207,94 +564,366 @@ fn position_quick_pane_on_cursor_monitor(app: &AppHandle) {
     }
 }
 
+/// Records the pane's current position so `Remembered` mode can restore it.
+fn remember_quick_pane_position(app: &AppHandle, label: &str) {
+    let Some(window) = app.get_webview_window(label) else {
+        return;
+    };
+    let Ok(position) = window.outer_position() else {
+        return;
+    };
+    if let Ok(mut panes) = QUICK_PANES.lock() {
+        if let Some(config) = panes.get_mut(label) {
+            if config.position == QuickPanePosition::Remembered {
+                config.last_position = Some(position);
+            }
+        }
+    }
+}
+
+/// Sets how a quick pane is positioned the next time it's shown.
+/// Defaults to the main quick pane when no label is given.
+#[tauri::command]
+#[specta::specta]
+pub fn set_quick_pane_position(
+    label: Option<String>,
+    position: QuickPanePosition,
+) -> Result<(), AppError> {
+    let label = resolve_label(label);
+    log::info!("Setting quick pane '{label}' position mode to {position:?}");
+
+    let mut panes = QUICK_PANES
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock quick pane registry: {e}")))?;
+    let config = panes
+        .get_mut(&label)
+        .ok_or_else(|| AppError::not_found(format!("Quick pane '{label}' is not registered")))?;
+
+    config.position = position;
+    config.last_position = None;
+    Ok(())
+}
+
+// ============================================================================
+// Window Sizing
+// ============================================================================
+
+/// Clamps a requested pane height to the configured limits.
+/// Non-finite values (NaN, infinity) fall back to the minimum height.
+fn clamp_pane_height(height: f64, min_height: f64, max_height: f64) -> f64 {
+    if !height.is_finite() {
+        return min_height;
+    }
+    height.clamp(min_height, max_height.max(min_height))
+}
+
+/// Resizes the pane's NSWindow with AppKit's smooth resize animation,
+/// keeping the top edge fixed so the pane grows downward.
+#[cfg(target_os = "macos")]
+fn resize_window_animated(window: &tauri::WebviewWindow, height: f64) -> Result<(), AppError> {
+    use objc2_app_kit::NSWindow;
+    use objc2_foundation::{NSPoint, NSRect, NSSize};
+
+    let ns_window_ptr = window
+        .ns_window()
+        .map_err(|e| AppError::internal(format!("Failed to get native window: {e}")))?;
+    // SAFETY: ns_window() returns a valid NSWindow pointer that lives as long as the window
+    let ns_window: &NSWindow = unsafe { &*ns_window_ptr.cast::<NSWindow>() };
+
+    // AppKit's origin is bottom-left, so move the origin down by the height delta
+    let frame = ns_window.frame();
+    let delta = height - frame.size.height;
+    let new_frame = NSRect::new(
+        NSPoint::new(frame.origin.x, frame.origin.y - delta),
+        NSSize::new(frame.size.width, height),
+    );
+    ns_window.setFrame_display_animate(new_frame, true, true);
+    Ok(())
+}
+
+/// Resizes a quick pane to the given height (logical pixels), e.g. so a launcher can
+/// grow as results appear beneath the input. The height is clamped to the pane's
+/// min/max limits and the top edge stays fixed. Animated on macOS.
+/// Returns the applied height. Defaults to the main quick pane when no label is given.
+#[tauri::command]
+#[specta::specta]
+pub fn resize_quick_pane(
+    app: AppHandle,
+    label: Option<String>,
+    height: f64,
+) -> Result<f64, AppError> {
+    let label = resolve_label(label);
+    let config = get_quick_pane_config(&label)?;
+    let height = clamp_pane_height(height, config.min_height, config.max_height);
+
+    log::debug!("Resizing quick pane '{label}' to height {height}");
+
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| AppError::not_found(format!("Quick pane window '{label}' not found")))?;
+
+    #[cfg(target_os = "macos")]
+    resize_window_animated(&window, height)?;
+
+    #[cfg(not(target_os = "macos"))]
+    window
+        .set_size(tauri::LogicalSize::new(config.width, height))
+        .map_err(|e| AppError::internal(format!("Failed to resize window: {e}")))?;
+
+    // Keep the registry in sync so positioning uses the current size
+    if let Ok(mut panes) = QUICK_PANES.lock() {
+        if let Some(config) = panes.get_mut(&label) {
+            config.height = height;
+        }
+    }
+
+    Ok(height)
+}
+
+// ============================================================================
+// Native Escape Handling (macOS)
+// ============================================================================
+
+/// Dismisses whichever visible quick pane has keyboard focus.
+/// Returns true if a pane was dismissed.
+#[cfg(target_os = "macos")]
+fn dismiss_focused_quick_pane(app: &AppHandle) -> bool {
+    let labels: Vec<String> = match QUICK_PANES.lock() {
+        Ok(panes) => panes.keys().cloned().collect(),
+        Err(_) => return false,
+    };
+
+    let focused = labels.into_iter().find(|label| {
+        is_quick_pane_visible(app, label)
+            && app
+                .get_webview_window(label)
+                .and_then(|window| window.is_focused().ok())
+                .unwrap_or(false)
+    });
+
+    let Some(label) = focused else {
+        return false;
+    };
+
+    log::debug!("Escape pressed, dismissing quick pane '{label}'");
+    if let Err(e) = dismiss_quick_pane_by_label(app, &label, Dismissal::Explicit) {
+        log::error!("Failed to dismiss quick pane on Escape: {e}");
+    }
+    true
+}
+
+/// Installs an app-wide local key monitor (once) that dismisses the focused quick
+/// pane on Escape, so the panel can always be closed even if the webview hasn't
+/// loaded or lost keyboard focus. The event is swallowed only when a pane was dismissed.
+#[cfg(target_os = "macos")]
+fn install_escape_key_monitor(app: &AppHandle) {
+    use block2::RcBlock;
+    use objc2_app_kit::{NSEvent, NSEventMask};
+    use std::ptr::NonNull;
+    use std::sync::Once;
+
+    /// Virtual key code for Escape
+    const ESCAPE_KEY_CODE: u16 = 53;
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let app_handle = app.clone();
+        let handler = RcBlock::new(move |event: NonNull<NSEvent>| -> *mut NSEvent {
+            // SAFETY: AppKit passes a valid event for the duration of the callback
+            let key_code = unsafe { event.as_ref() }.keyCode();
+            if key_code == ESCAPE_KEY_CODE && dismiss_focused_quick_pane(&app_handle) {
+                return std::ptr::null_mut();
+            }
+            event.as_ptr()
+        });
+
+        // SAFETY: the handler returns either the event it was given or null
+        let monitor = unsafe {
+            NSEvent::addLocalMonitorForEventsMatchingMask_handler(NSEventMask::KeyDown, &handler)
+        };
+        match monitor {
+            // The monitor lives for the rest of the app's lifetime
+            Some(monitor) => std::mem::forget(monitor),
+            None => log::warn!("Failed to install quick pane Escape key monitor"),
+        }
+    });
+}
+
+// ============================================================================
+// Native Escape Handling (Windows/Linux)
+// ============================================================================
+
+/// Gives the pane window a hidden menu whose only item dismisses it on Escape,
+/// so the pane can always be closed even if the webview hasn't loaded. The
+/// accelerator only fires while the pane window has focus.
+#[cfg(target_os = "windows")]
+fn install_escape_accelerator(window: &tauri::WebviewWindow) -> Result<(), AppError> {
+    use tauri::menu::{Menu, MenuItem};
+
+    const DISMISS_ITEM_ID: &str = "quick-pane-dismiss";
+
+    let dismiss = MenuItem::with_id(window, DISMISS_ITEM_ID, "Dismiss", true, Some("Escape"))?;
+    window.set_menu(Menu::with_items(window, &[&dismiss])?)?;
+    // Accelerators keep working while the menu bar is hidden
+    window.hide_menu()?;
+
+    window.on_menu_event(|window, event| {
+        if event.id().as_ref() != DISMISS_ITEM_ID {
+            return;
+        }
+        let label = window.label();
+        log::debug!("Escape pressed, dismissing quick pane '{label}'");
+        if let Err(e) = dismiss_quick_pane_by_label(window.app_handle(), label, Dismissal::Explicit)
+        {
+            log::error!("Failed to dismiss quick pane on Escape: {e}");
+        }
+    });
+    Ok(())
+}
+
+/// Dismisses the pane on Escape from a key handler on its GTK window, which
+/// sees the key before the webview does. A hidden menu can't be used here, as
+/// GTK ignores accelerators on hidden menu bars.
+#[cfg(target_os = "linux")]
+fn install_escape_key_handler(window: &tauri::WebviewWindow) -> Result<(), AppError> {
+    use gtk::gdk::keys::constants::Escape;
+    use gtk::glib::Propagation;
+    use gtk::prelude::*;
+
+    let app = window.app_handle().clone();
+    let label = window.label().to_string();
+
+    window
+        .gtk_window()?
+        .connect_key_press_event(move |_, event| {
+            if event.keyval() != Escape {
+                return Propagation::Proceed;
+            }
+            log::debug!("Escape pressed, dismissing quick pane '{label}'");
+            if let Err(e) = dismiss_quick_pane_by_label(&app, &label, Dismissal::Explicit) {
+                log::error!("Failed to dismiss quick pane on Escape: {e}");
+            }
+            Propagation::Stop
+        });
+    Ok(())
+}
+
 // ============================================================================
 // Window Visibility
 // ============================================================================
 
-/// Returns whether the quick pane is currently visible.
-fn is_quick_pane_visible(app: &AppHandle) -> bool {
+/// Returns whether a quick pane is currently visible.
+fn is_quick_pane_visible(app: &AppHandle, label: &str) -> bool {
     #[cfg(target_os = "macos")]
     {
-        app.get_webview_panel(QUICK_PANE_LABEL)
+        app.get_webview_panel(label)
             .map(|panel| panel.is_visible())
             .unwrap_or(false)
     }
 
     #[cfg(not(target_os = "macos"))]
     {
-        app.get_webview_window(QUICK_PANE_LABEL)
+        app.get_webview_window(label)
             .and_then(|window| window.is_visible().ok())
             .unwrap_or(false)
     }
 }
 
-/// Shows the quick pane window and makes it the key window (for keyboard input).
-#[tauri::command]
-#[specta::specta]
-pub fn show_quick_pane(app: AppHandle) -> Result<(), String> {
-    log::info!("Showing quick pane window");
+/// Shows a quick pane and makes it the key window (for keyboard input).
+pub fn show_quick_pane_by_label(app: &AppHandle, label: &str) -> Result<(), AppError> {
+    log::info!("Showing quick pane window '{label}'");
 
-    position_quick_pane_on_cursor_monitor(&app);
+    let config = get_quick_pane_config(label)?;
+    position_quick_pane(app, &config);
 
     #[cfg(target_os = "macos")]
     {
         let panel = app
-            .get_webview_panel(QUICK_PANE_LABEL)
-            .map_err(|e| format!("Quick pane panel not found: {e:?}"))?;
+            .get_webview_panel(label)
+            .map_err(|e| AppError::not_found(format!("Quick pane panel not found: {e:?}")))?;
+        // Only capture when opening, so each capture pairs with one dismissal
+        if !panel.is_visible() {
+            macos_focus::capture_frontmost();
+        }
         panel.show_and_make_key();
         log::debug!("Quick pane panel shown (macOS)");
     }
 
     #[cfg(not(target_os = "macos"))]
     {
-        let window = app.get_webview_window(QUICK_PANE_LABEL).ok_or_else(|| {
-            "Quick pane window not found - was init_quick_pane called at startup?".to_string()
+        let window = app.get_webview_window(label).ok_or_else(|| {
+            AppError::not_found(format!(
+                "Quick pane window '{label}' not found - was it registered at startup?"
+            ))
         })?;
         window
             .show()
-            .map_err(|e| format!("Failed to show window: {e}"))?;
+            .map_err(|e| AppError::internal(format!("Failed to show window: {e}")))?;
         window
             .set_focus()
-            .map_err(|e| format!("Failed to focus window: {e}"))?;
+            .map_err(|e| AppError::internal(format!("Failed to focus window: {e}")))?;
         log::debug!("Quick pane window shown");
     }
 
     Ok(())
 }
 
-/// Dismisses the quick pane window.
+/// How a quick pane is being dismissed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dismissal {
+    /// Escape, the shortcut, submitting or pasting. On macOS, focus returns to
+    /// the app that was frontmost before the pane was shown.
+    Explicit,
+    /// The pane lost focus, usually to a click in another app, which keeps it.
+    Blur,
+}
+
+/// Dismisses a quick pane.
 /// On macOS, resigns key window status before hiding to avoid activating main window.
-#[tauri::command]
-#[specta::specta]
-pub fn dismiss_quick_pane(app: AppHandle) -> Result<(), String> {
+pub fn dismiss_quick_pane_by_label(
+    app: &AppHandle,
+    label: &str,
+    dismissal: Dismissal,
+) -> Result<(), AppError> {
     #[cfg(target_os = "macos")]
     {
-        if let Ok(panel) = app.get_webview_panel(QUICK_PANE_LABEL) {
+        if let Ok(panel) = app.get_webview_panel(label) {
             // Guard: resign_key_window triggers blur event which calls dismiss again
             if !panel.is_visible() {
                 return Ok(());
             }
-            log::info!("Dismissing quick pane window");
+            log::info!("Dismissing quick pane window '{label}'");
+            remember_quick_pane_position(app, label);
             // Resign key window BEFORE hiding to prevent macOS from
             // activating our main window (which would cause space switching)
             panel.resign_key_window();
             panel.hide();
+            // Return focus to the app the user was in, unless they clicked
+            // elsewhere or the pane is pinned (they've likely moved on)
+            let pinned = get_quick_pane_config(label).is_ok_and(|config| config.pinned);
+            if dismissal == Dismissal::Explicit && !pinned {
+                macos_focus::reactivate_previous();
+            } else {
+                macos_focus::discard_previous();
+            }
             log::debug!("Quick pane panel dismissed (macOS)");
         }
     }
 
     #[cfg(not(target_os = "macos"))]
     {
-        if let Some(window) = app.get_webview_window(QUICK_PANE_LABEL) {
+        let _ = dismissal;
+        if let Some(window) = app.get_webview_window(label) {
             let is_visible = window.is_visible().unwrap_or(false);
             if !is_visible {
                 log::debug!("Quick pane already hidden, skipping");
                 return Ok(());
             }
-            log::info!("Dismissing quick pane window");
+            log::info!("Dismissing quick pane window '{label}'");
+            remember_quick_pane_position(app, label);
             window
                 .hide()
-                .map_err(|e| format!("Failed to hide window: {e}"))?;
+                .map_err(|e| AppError::internal(format!("Failed to hide window: {e}")))?;
             log::debug!("Quick pane window hidden");
         }
     }
@@ -302,17 +931,151 @@ pub fn dismiss_quick_pane(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
-/// Toggles the quick pane window visibility.
+/// Toggles a quick pane's visibility.
+pub fn toggle_quick_pane_by_label(app: &AppHandle, label: &str) -> Result<(), AppError> {
+    log::info!("Toggling quick pane window '{label}'");
+
+    if is_quick_pane_visible(app, label) {
+        dismiss_quick_pane_by_label(app, label, Dismissal::Explicit)
+    } else {
+        show_quick_pane_by_label(app, label)
+    }
+}
+
+/// Hides every registered quick pane panel. Used during app teardown.
+#[cfg(target_os = "macos")]
+pub fn hide_all_quick_panes(app: &AppHandle) {
+    let labels: Vec<String> = QUICK_PANES
+        .lock()
+        .map(|panes| panes.keys().cloned().collect())
+        .unwrap_or_default();
+
+    for label in labels {
+        if let Ok(panel) = app.get_webview_panel(&label) {
+            panel.hide();
+        }
+    }
+}
+
+/// Shows a quick pane and makes it the key window (for keyboard input).
+/// Defaults to the main quick pane when no label is given.
 #[tauri::command]
 #[specta::specta]
-pub fn toggle_quick_pane(app: AppHandle) -> Result<(), String> {
-    log::info!("Toggling quick pane window");
+pub fn show_quick_pane(app: AppHandle, label: Option<String>) -> Result<(), AppError> {
+    show_quick_pane_by_label(&app, &resolve_label(label))
+}
 
-    if is_quick_pane_visible(&app) {
-        dismiss_quick_pane(app)
-    } else {
-        show_quick_pane(app)
+/// Dismisses a quick pane.
+/// On macOS, resigns key window status before hiding to avoid activating main window.
+/// Defaults to the main quick pane when no label is given.
+#[tauri::command]
+#[specta::specta]
+pub fn dismiss_quick_pane(app: AppHandle, label: Option<String>) -> Result<(), AppError> {
+    dismiss_quick_pane_by_label(&app, &resolve_label(label), Dismissal::Explicit)
+}
+
+/// Toggles a quick pane's visibility.
+/// Defaults to the main quick pane when no label is given.
+#[tauri::command]
+#[specta::specta]
+pub fn toggle_quick_pane(app: AppHandle, label: Option<String>) -> Result<(), AppError> {
+    toggle_quick_pane_by_label(&app, &resolve_label(label))
+}
+
+/// Submits an entry from a quick pane in a single backend operation: emits
+/// `quick-entry-submitted` to the main window, records it in the pane's history,
+/// hides the pane and (on macOS) reactivates the app that was frontmost before
+/// the pane was shown.
+/// Doing this in one call avoids the pane hiding before the payload is delivered.
+#[tauri::command]
+#[specta::specta]
+pub fn submit_quick_pane_entry(
+    app: AppHandle,
+    label: Option<String>,
+    text: String,
+) -> Result<(), AppError> {
+    let label = resolve_label(label);
+    validate_string_input(&text, MAX_QUICK_ENTRY_LENGTH, "Entry").map_err(AppError::validation)?;
+
+    log::info!("Submitting entry from quick pane '{label}'");
+
+    // History is a convenience; don't fail the submission over it
+    if let Err(e) = super::quick_pane_history::record_quick_pane_entry(&app, &label, &text) {
+        log::warn!("Failed to record quick pane history: {e}");
+    }
+
+    QuickEntrySubmitted {
+        label: label.clone(),
+        text,
+    }
+    .emit_to(&app, MAIN_WINDOW_LABEL)
+    .map_err(|e| AppError::internal(format!("Failed to emit quick-entry-submitted event: {e}")))?;
+
+    // Pinned panes stay open (and focused) for the next entry
+    if get_quick_pane_config(&label)?.pinned {
+        return Ok(());
+    }
+
+    dismiss_quick_pane_by_label(&app, &label, Dismissal::Explicit)
+}
+
+/// Dismisses a quick pane after it loses focus, unless it is pinned. Focus
+/// stays with whatever the user clicked, rather than returning to the app that
+/// was frontmost before the pane was shown.
+/// The pane's blur handler should call this rather than `dismiss_quick_pane`.
+/// Defaults to the main quick pane when no label is given.
+#[tauri::command]
+#[specta::specta]
+pub fn auto_dismiss_quick_pane(app: AppHandle, label: Option<String>) -> Result<(), AppError> {
+    let label = resolve_label(label);
+    if get_quick_pane_config(&label)?.pinned {
+        log::debug!("Quick pane '{label}' is pinned, ignoring auto-dismiss");
+        return Ok(());
+    }
+    dismiss_quick_pane_by_label(&app, &label, Dismissal::Blur)
+}
+
+/// Pins or unpins a quick pane. While pinned, the pane stays open on blur and
+/// after submitting, and doesn't reactivate the previous app, so it can be kept
+/// around during a longer task. Explicit dismissal (Escape, the shortcut) still works.
+/// Defaults to the main quick pane when no label is given.
+#[tauri::command]
+#[specta::specta]
+pub fn set_quick_pane_pinned(
+    app: AppHandle,
+    label: Option<String>,
+    pinned: bool,
+) -> Result<(), AppError> {
+    let label = resolve_label(label);
+
+    let config = {
+        let mut panes = QUICK_PANES
+            .lock()
+            .map_err(|e| AppError::internal(format!("Failed to lock quick pane registry: {e}")))?;
+        let config = panes.get_mut(&label).ok_or_else(|| {
+            AppError::not_found(format!("Quick pane '{label}' is not registered"))
+        })?;
+        config.pinned = pinned;
+        config.clone()
+    };
+
+    log::info!(
+        "Quick pane '{label}' {}",
+        if pinned { "pinned" } else { "unpinned" }
+    );
+
+    #[cfg(target_os = "macos")]
+    {
+        let panel = app
+            .get_webview_panel(&label)
+            .map_err(|e| AppError::not_found(format!("Quick pane panel not found: {e:?}")))?;
+        panel.set_collection_behavior(quick_pane_collection_behavior(&config).into());
     }
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = (app, config);
+
+    Ok(())
 }
 
 // ============================================================================
@@ -322,7 +1085,7 @@ pub fn toggle_quick_pane(app: AppHandle) -> Result<(), String> {
 /// Registers the quick pane global shortcut, unregistering any previously registered one.
 /// This helper is used by both setup() and update_quick_pane_shortcut() for consistency.
 #[cfg(desktop)]
-pub fn register_quick_pane_shortcut(app: &AppHandle, shortcut: &str) -> Result<(), String> {
+pub fn register_quick_pane_shortcut(app: &AppHandle, shortcut: &str) -> Result<(), AppError> {
     use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
 
     let global_shortcut = app.global_shortcut();
@@ -330,7 +1093,7 @@ pub fn register_quick_pane_shortcut(app: &AppHandle, shortcut: &str) -> Result<(
     // Lock the mutex to get the current shortcut and update it atomically
     let mut current_shortcut = CURRENT_QUICK_PANE_SHORTCUT
         .lock()
-        .map_err(|e| format!("Failed to lock shortcut mutex: {e}"))?;
+        .map_err(|e| AppError::internal(format!("Failed to lock shortcut mutex: {e}")))?;
 
     // Unregister the old shortcut if one exists
     if let Some(old_shortcut_str) = current_shortcut.take() {
@@ -357,12 +1120,14 @@ pub fn register_quick_pane_shortcut(app: &AppHandle, shortcut: &str) -> Result<(
             use tauri_plugin_global_shortcut::ShortcutState;
             if event.state == ShortcutState::Pressed {
                 log::info!("Quick pane shortcut triggered");
-                if let Err(e) = toggle_quick_pane(app_handle.clone()) {
+                if let Err(e) = toggle_quick_pane_by_label(&app_handle, QUICK_PANE_LABEL) {
                     log::error!("Failed to toggle quick pane: {e}");
                 }
             }
         })
-        .map_err(|e| format!("Failed to register shortcut '{shortcut}': {e}"))?;
+        .map_err(|e| {
+            AppError::internal(format!("Failed to register shortcut '{shortcut}': {e}"))
+        })?;
 
     // Store the new shortcut for future unregistration
     *current_shortcut = Some(shortcut.to_string());
@@ -382,7 +1147,10 @@ pub fn get_default_quick_pane_shortcut() -> String {
 /// Pass None to reset to default.
 #[tauri::command]
 #[specta::specta]
-pub fn update_quick_pane_shortcut(app: AppHandle, shortcut: Option<String>) -> Result<(), String> {
+pub fn update_quick_pane_shortcut(
+    app: AppHandle,
+    shortcut: Option<String>,
+) -> Result<(), AppError> {
     #[cfg(desktop)]
     {
         let new_shortcut = shortcut.as_deref().unwrap_or(DEFAULT_QUICK_PANE_SHORTCUT);
@@ -401,3 +1169,64 @@ pub fn update_quick_pane_shortcut(app: AppHandle, shortcut: Option<String>) -> R
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONITOR_POS: (i32, i32) = (0, 0);
+    const MONITOR_SIZE: (i32, i32) = (1920, 1080);
+    const PANE_SIZE: (i32, i32) = (500, 72);
+
+    fn position(mode: QuickPanePosition, cursor: (i32, i32)) -> (i32, i32) {
+        compute_pane_position(mode, MONITOR_POS, MONITOR_SIZE, PANE_SIZE, cursor, 16)
+    }
+
+    #[test]
+    fn test_centered_position() {
+        assert_eq!(position(QuickPanePosition::Centered, (0, 0)), (710, 504));
+    }
+
+    #[test]
+    fn test_top_third_position() {
+        assert_eq!(position(QuickPanePosition::TopThird, (0, 0)), (710, 324));
+    }
+
+    #[test]
+    fn test_near_cursor_position() {
+        assert_eq!(
+            position(QuickPanePosition::NearCursor, (1000, 500)),
+            (750, 516)
+        );
+    }
+
+    #[test]
+    fn test_near_cursor_clamped_to_monitor() {
+        assert_eq!(
+            position(QuickPanePosition::NearCursor, (1910, 1070)),
+            (1420, 1008)
+        );
+    }
+
+    #[test]
+    fn test_clamp_pane_height() {
+        assert_eq!(clamp_pane_height(200.0, 72.0, 480.0), 200.0);
+        assert_eq!(clamp_pane_height(10.0, 72.0, 480.0), 72.0);
+        assert_eq!(clamp_pane_height(1000.0, 72.0, 480.0), 480.0);
+        assert_eq!(clamp_pane_height(f64::NAN, 72.0, 480.0), 72.0);
+    }
+
+    #[test]
+    fn test_position_on_secondary_monitor() {
+        // Secondary monitor to the left of the primary, with negative coordinates
+        let (x, y) = compute_pane_position(
+            QuickPanePosition::Centered,
+            (-2560, -200),
+            (2560, 1440),
+            (1000, 144),
+            (-1000, 100),
+            32,
+        );
+        assert_eq!((x, y), (-1780, 448));
+    }
+}
@@ -0,0 +1,146 @@
+//! Quick pane input history.
+//!
+//! Keeps the most recent submissions for each quick pane in
+//! `quick-pane-history.json` in the app data directory, so panes can offer
+//! up-arrow recall across restarts. Entries are appended by
+//! `submit_quick_pane_entry`.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+use super::files::write_atomic;
+use super::quick_pane::resolve_label;
+use crate::types::AppError;
+
+/// Maximum number of entries kept per quick pane
+const MAX_HISTORY_ENTRIES: usize = 50;
+
+/// History entries keyed by quick pane label, most recent first.
+type QuickPaneHistory = BTreeMap<String, Vec<String>>;
+
+/// Serializes read-modify-write cycles on the history file.
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+/// Gets the path to the history file.
+fn get_history_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = crate::data_dir::app_data_dir(app)?;
+
+    // Ensure the directory exists
+    std::fs::create_dir_all(&app_data_dir)
+        .map_err(|e| AppError::io(format!("Failed to create app data directory: {e}")))?;
+
+    Ok(app_data_dir.join("quick-pane-history.json"))
+}
+
+/// Reads the history file, returning empty history if it doesn't exist.
+fn read_history(app: &AppHandle) -> Result<QuickPaneHistory, AppError> {
+    let path = get_history_path(app)?;
+    if !path.exists() {
+        return Ok(QuickPaneHistory::new());
+    }
+
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::io(format!("Failed to read quick pane history: {e}")))?;
+    serde_json::from_str(&contents).map_err(|e| AppError::Serialization {
+        message: format!("Failed to parse quick pane history: {e}"),
+    })
+}
+
+/// Writes the history file using atomic write (temp file + rename).
+fn write_history(app: &AppHandle, history: &QuickPaneHistory) -> Result<(), AppError> {
+    let path = get_history_path(app)?;
+    let json_content =
+        serde_json::to_string_pretty(history).map_err(|e| AppError::Serialization {
+            message: format!("Failed to serialize quick pane history: {e}"),
+        })?;
+
+    write_atomic(&path, json_content.as_bytes())
+        .map_err(|e| AppError::io(format!("Failed to write quick pane history: {e}")))
+}
+
+/// Adds an entry to the front of the list, moving it up if it already exists
+/// and dropping the oldest entries beyond `max_entries`.
+fn push_history_entry(entries: &mut Vec<String>, text: &str, max_entries: usize) {
+    entries.retain(|entry| entry != text);
+    entries.insert(0, text.to_string());
+    entries.truncate(max_entries);
+}
+
+/// Records a submission in a quick pane's history.
+pub fn record_quick_pane_entry(app: &AppHandle, label: &str, text: &str) -> Result<(), AppError> {
+    let _guard = HISTORY_LOCK
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock quick pane history: {e}")))?;
+
+    let mut history = read_history(app)?;
+    push_history_entry(
+        history.entry(label.to_string()).or_default(),
+        text,
+        MAX_HISTORY_ENTRIES,
+    );
+    write_history(app, &history)
+}
+
+/// Returns a quick pane's previous submissions, most recent first.
+/// Defaults to the main quick pane when no label is given.
+#[tauri::command]
+#[specta::specta]
+pub fn get_quick_pane_history(
+    app: AppHandle,
+    label: Option<String>,
+) -> Result<Vec<String>, AppError> {
+    let label = resolve_label(label);
+    let _guard = HISTORY_LOCK
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock quick pane history: {e}")))?;
+
+    let mut history = read_history(&app)?;
+    Ok(history.remove(&label).unwrap_or_default())
+}
+
+/// Clears a quick pane's submission history.
+/// Defaults to the main quick pane when no label is given.
+#[tauri::command]
+#[specta::specta]
+pub fn clear_quick_pane_history(app: AppHandle, label: Option<String>) -> Result<(), AppError> {
+    let label = resolve_label(label);
+    log::info!("Clearing history for quick pane '{label}'");
+
+    let _guard = HISTORY_LOCK
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock quick pane history: {e}")))?;
+
+    let mut history = read_history(&app)?;
+    if history.remove(&label).is_some() {
+        write_history(&app, &history)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_adds_most_recent_first() {
+        let mut entries = vec!["older".to_string()];
+        push_history_entry(&mut entries, "newer", 10);
+        assert_eq!(entries, vec!["newer", "older"]);
+    }
+
+    #[test]
+    fn test_push_moves_duplicate_to_front() {
+        let mut entries = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        push_history_entry(&mut entries, "c", 10);
+        assert_eq!(entries, vec!["c", "a", "b"]);
+    }
+
+    #[test]
+    fn test_push_truncates_to_max() {
+        let mut entries = vec!["a".to_string(), "b".to_string()];
+        push_history_entry(&mut entries, "c", 2);
+        assert_eq!(entries, vec!["c", "a"]);
+    }
+}
@@ -0,0 +1,262 @@
+//! Recently opened documents.
+//!
+//! Keeps the most recently opened file paths in `recent-documents.json` in the
+//! app data directory and mirrors them into the app menu's "Open Recent"
+//! submenu (any spec submenu with the id `open-recent`), the macOS Dock's
+//! recent items and the Windows Jump List. Choosing an entry emits `recent-document-selected`
+//! to the main window.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::menu::{MenuItem, MenuItemKind};
+use tauri::AppHandle;
+use tauri_specta::Event;
+
+use super::files::write_atomic;
+use super::jump_list::refresh_jump_list;
+use super::menu::find_item;
+use super::window::MAIN_WINDOW_LABEL;
+use crate::types::{AppError, RecentDocumentSelected};
+
+/// Maximum number of documents kept
+const MAX_RECENT_DOCUMENTS: usize = 10;
+
+/// Id of the app menu submenu that lists recent documents.
+pub const RECENT_DOCUMENTS_MENU_ID: &str = "open-recent";
+
+/// Id of the optional "Clear Menu" item, disabled while the list is empty.
+pub const CLEAR_RECENT_DOCUMENTS_ID: &str = "clear-recent-documents";
+
+/// Prefix for the ids of generated menu items; the rest of the id is the path.
+const RECENT_ITEM_PREFIX: &str = "recent-document:";
+
+/// Serializes read-modify-write cycles on the recent documents file.
+static RECENT_DOCUMENTS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Gets the path to the recent documents file.
+fn get_recent_documents_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = crate::data_dir::app_data_dir(app)?;
+
+    // Ensure the directory exists
+    std::fs::create_dir_all(&app_data_dir)
+        .map_err(|e| AppError::io(format!("Failed to create app data directory: {e}")))?;
+
+    Ok(app_data_dir.join("recent-documents.json"))
+}
+
+/// Reads the recent documents, most recent first.
+fn read_recent_documents(app: &AppHandle) -> Result<Vec<String>, AppError> {
+    let path = get_recent_documents_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::io(format!("Failed to read recent documents: {e}")))?;
+    serde_json::from_str(&contents).map_err(|e| AppError::Serialization {
+        message: format!("Failed to parse recent documents: {e}"),
+    })
+}
+
+/// Writes the recent documents file using atomic write (temp file + rename).
+fn write_recent_documents(app: &AppHandle, documents: &[String]) -> Result<(), AppError> {
+    let path = get_recent_documents_path(app)?;
+    let json_content =
+        serde_json::to_string_pretty(documents).map_err(|e| AppError::Serialization {
+            message: format!("Failed to serialize recent documents: {e}"),
+        })?;
+
+    write_atomic(&path, json_content.as_bytes())
+        .map_err(|e| AppError::io(format!("Failed to write recent documents: {e}")))
+}
+
+/// Moves a path to the front of the list, dropping the oldest beyond `max_documents`.
+fn push_recent_document(documents: &mut Vec<String>, path: &str, max_documents: usize) {
+    documents.retain(|document| document != path);
+    documents.insert(0, path.to_string());
+    documents.truncate(max_documents);
+}
+
+/// Menu label for a document: its file name, or the whole path if it has none.
+pub(crate) fn menu_label(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Rebuilds the document items in the "Open Recent" submenu, keeping any
+/// other items from the spec (e.g. a separator and "Clear Menu") below them.
+/// Does nothing if the app menu has no such submenu.
+pub fn refresh_recent_documents_menu(app: &AppHandle) {
+    let Some(menu) = app.menu() else {
+        return;
+    };
+    let Ok(items) = menu.items() else {
+        return;
+    };
+    let Some((_, MenuItemKind::Submenu(submenu))) =
+        find_item(items, None, RECENT_DOCUMENTS_MENU_ID)
+    else {
+        return;
+    };
+
+    let documents = match read_recent_documents(app) {
+        Ok(documents) => documents,
+        Err(e) => {
+            log::warn!("Failed to load recent documents: {e}");
+            Vec::new()
+        }
+    };
+
+    for item in submenu.items().unwrap_or_default() {
+        if item.id().as_ref().starts_with(RECENT_ITEM_PREFIX) {
+            if let Err(e) = submenu.remove(&item) {
+                log::warn!("Failed to remove recent document menu item: {e}");
+            }
+        }
+    }
+
+    for (position, path) in documents.iter().enumerate() {
+        let id = format!("{RECENT_ITEM_PREFIX}{path}");
+        let result = MenuItem::with_id(app, id, menu_label(path), true, None::<&str>)
+            .and_then(|item| submenu.insert(&item, position));
+        if let Err(e) = result {
+            log::warn!("Failed to add recent document menu item: {e}");
+        }
+    }
+
+    if let Ok(children) = submenu.items() {
+        if let Some((_, MenuItemKind::MenuItem(clear))) =
+            find_item(children, None, CLEAR_RECENT_DOCUMENTS_ID)
+        {
+            if let Err(e) = clear.set_enabled(!documents.is_empty()) {
+                log::warn!("Failed to update clear recent documents item: {e}");
+            }
+        }
+    }
+}
+
+/// Emits `recent-document-selected` for clicks on generated menu items.
+/// Returns whether the event was a recent document item.
+pub fn handle_menu_event(app: &AppHandle, id: &str) -> bool {
+    let Some(path) = id.strip_prefix(RECENT_ITEM_PREFIX) else {
+        return false;
+    };
+
+    let selected = RecentDocumentSelected {
+        path: path.to_string(),
+    };
+    if let Err(e) = selected.emit_to(app, MAIN_WINDOW_LABEL) {
+        log::error!("Failed to emit recent-document-selected: {e}");
+    }
+    true
+}
+
+/// Records an opened document, moving it to the top of the recent list.
+#[tauri::command]
+#[specta::specta]
+pub fn add_recent_document(app: AppHandle, path: String) -> Result<(), AppError> {
+    {
+        let _guard = RECENT_DOCUMENTS_LOCK
+            .lock()
+            .map_err(|e| AppError::internal(format!("Failed to lock recent documents: {e}")))?;
+
+        let mut documents = read_recent_documents(&app)?;
+        push_recent_document(&mut documents, &path, MAX_RECENT_DOCUMENTS);
+        write_recent_documents(&app, &documents)?;
+    }
+
+    note_recent_document(&path);
+    refresh_recent_documents_menu(&app);
+    refresh_jump_list(&app);
+    Ok(())
+}
+
+/// Returns the recently opened documents, most recent first.
+#[tauri::command]
+#[specta::specta]
+pub fn get_recent_documents(app: AppHandle) -> Result<Vec<String>, AppError> {
+    let _guard = RECENT_DOCUMENTS_LOCK
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock recent documents: {e}")))?;
+
+    read_recent_documents(&app)
+}
+
+/// Clears the recent documents list, menu and (on macOS) Dock items.
+#[tauri::command]
+#[specta::specta]
+pub fn clear_recent_documents(app: AppHandle) -> Result<(), AppError> {
+    log::info!("Clearing recent documents");
+    {
+        let _guard = RECENT_DOCUMENTS_LOCK
+            .lock()
+            .map_err(|e| AppError::internal(format!("Failed to lock recent documents: {e}")))?;
+
+        write_recent_documents(&app, &[])?;
+    }
+
+    clear_system_recent_documents();
+    refresh_recent_documents_menu(&app);
+    refresh_jump_list(&app);
+    Ok(())
+}
+
+// ============================================================================
+// System Recent Documents
+// ============================================================================
+
+/// Adds a document to the system's recent items, shown in the Dock menu.
+#[cfg(target_os = "macos")]
+fn note_recent_document(path: &str) {
+    use objc2_app_kit::NSDocumentController;
+    use objc2_foundation::{MainThreadMarker, NSString, NSURL};
+
+    let Some(mtm) = MainThreadMarker::new() else {
+        log::warn!("Recent documents can only be noted on the main thread");
+        return;
+    };
+    let url = NSURL::fileURLWithPath(&NSString::from_str(path));
+    NSDocumentController::sharedDocumentController(mtm).noteNewRecentDocumentURL(&url);
+}
+
+#[cfg(not(target_os = "macos"))]
+fn note_recent_document(_path: &str) {}
+
+/// Clears the system's recent items for this app.
+#[cfg(target_os = "macos")]
+fn clear_system_recent_documents() {
+    use objc2_app_kit::NSDocumentController;
+    use objc2_foundation::MainThreadMarker;
+
+    let Some(mtm) = MainThreadMarker::new() else {
+        log::warn!("Recent documents can only be cleared on the main thread");
+        return;
+    };
+    NSDocumentController::sharedDocumentController(mtm).clearRecentDocuments(None);
+}
+
+#[cfg(not(target_os = "macos"))]
+fn clear_system_recent_documents() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_recent_document_moves_existing_to_front() {
+        let mut documents = vec!["/a.txt".to_string(), "/b.txt".to_string()];
+        push_recent_document(&mut documents, "/b.txt", 10);
+        assert_eq!(documents, vec!["/b.txt", "/a.txt"]);
+
+        push_recent_document(&mut documents, "/c.txt", 2);
+        assert_eq!(documents, vec!["/c.txt", "/b.txt"]);
+    }
+
+    #[test]
+    fn test_menu_label_uses_file_name() {
+        assert_eq!(menu_label("/Users/me/Notes/todo.md"), "todo.md");
+        assert_eq!(menu_label("/"), "/");
+    }
+}
@@ -1,37 +1,46 @@
 //! Emergency data recovery commands.
 //!
 //! Provides a simple pattern for saving JSON data to disk for crash recovery
-//! or session persistence.
+//! or session persistence. Storage is delegated to the `RecoveryStore` in managed
+//! state (see `crate::recovery_store`), so the backend can be swapped in `lib.rs`.
 
 use serde_json::Value;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
-use tauri::{AppHandle, Manager};
-
-use crate::types::{validate_filename, RecoveryError, MAX_RECOVERY_DATA_BYTES};
-
-/// Gets the path to the recovery directory, creating it if necessary.
-fn get_recovery_dir(app: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
-
-    let recovery_dir = app_data_dir.join("recovery");
-
-    // Ensure the recovery directory exists
-    std::fs::create_dir_all(&recovery_dir)
-        .map_err(|e| format!("Failed to create recovery directory: {e}"))?;
+use tauri::{AppHandle, State};
+use tauri_specta::Event;
+
+use super::tasks::{self, CancellationToken};
+use crate::recovery_store::{get_recovery_dir, io_error, RecoveryEntry, SharedRecoveryStore};
+use crate::types::{
+    validate_filename, AppError, RecoveryArchiveEntry, RecoveryArchiveManifest, RecoveryCleaned,
+    RecoveryError, RecoveryLoadFailed, RecoverySaved, MAX_RECOVERY_DATA_BYTES,
+};
+use crate::utils::platform::normalize_path_for_serialization;
+
+/// Converts a `SystemTime` into a Unix timestamp in milliseconds.
+fn unix_millis(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as f64)
+        .unwrap_or(0.0)
+}
 
-    Ok(recovery_dir)
+/// Emits a recovery lifecycle event. Failures are logged, not propagated,
+/// since the underlying operation has already completed.
+fn emit_recovery_event<E: Event + serde::Serialize + Clone>(app: &AppHandle, event: E) {
+    if let Err(e) = event.emit(app) {
+        log::warn!("Failed to emit {} event: {e}", E::NAME);
+    }
 }
 
-/// Saves emergency data to a JSON file for later recovery.
+/// Saves emergency data for later recovery using the configured recovery store.
 /// Validates filename and enforces a 10MB size limit.
+/// Emits `recovery-saved` on success.
 #[tauri::command]
 #[specta::specta]
 pub async fn save_emergency_data(
     app: AppHandle,
+    store: State<'_, SharedRecoveryStore>,
     filename: String,
     data: Value,
 ) -> Result<(), RecoveryError> {
@@ -55,152 +64,248 @@ pub async fn save_emergency_data(
         });
     }
 
-    let recovery_dir = get_recovery_dir(&app).map_err(|e| RecoveryError::IoError { message: e })?;
-    let file_path = recovery_dir.join(format!("{filename}.json"));
+    store.save(&filename, &json_content)?;
 
-    // Write to a temporary file first, then rename (atomic operation)
-    let temp_path = file_path.with_extension("tmp");
+    emit_recovery_event(
+        &app,
+        RecoverySaved {
+            filename: filename.clone(),
+            saved_at: unix_millis(SystemTime::now()),
+        },
+    );
 
-    std::fs::write(&temp_path, json_content).map_err(|e| {
-        log::error!("Failed to write emergency data file: {e}");
-        RecoveryError::IoError {
-            message: e.to_string(),
-        }
-    })?;
-
-    if let Err(rename_err) = std::fs::rename(&temp_path, &file_path) {
-        log::error!("Failed to finalize emergency data file: {rename_err}");
-        // Clean up the temp file to avoid leaving orphaned files on disk
-        if let Err(remove_err) = std::fs::remove_file(&temp_path) {
-            log::warn!("Failed to remove temp file after rename failure: {remove_err}");
-        }
-        return Err(RecoveryError::IoError {
-            message: rename_err.to_string(),
-        });
-    }
-
-    log::info!("Successfully saved emergency data to {file_path:?}");
+    log::info!(
+        "Successfully saved emergency data: {filename} ({} backend)",
+        store.name()
+    );
     Ok(())
 }
 
-/// Loads emergency data from a previously saved JSON file.
-/// Returns FileNotFound if the file doesn't exist.
+/// Loads emergency data previously saved with `save_emergency_data`.
+/// Returns FileNotFound if no data exists for the filename.
+/// Emits `recovery-load-failed` for any other failure.
 #[tauri::command]
 #[specta::specta]
-pub async fn load_emergency_data(app: AppHandle, filename: String) -> Result<Value, RecoveryError> {
+pub async fn load_emergency_data(
+    app: AppHandle,
+    store: State<'_, SharedRecoveryStore>,
+    filename: String,
+) -> Result<Value, RecoveryError> {
     log::info!("Loading emergency data from file: {filename}");
 
     // Validate filename with proper security checks
     validate_filename(&filename).map_err(|e| RecoveryError::ValidationError { message: e })?;
 
-    let recovery_dir = get_recovery_dir(&app).map_err(|e| RecoveryError::IoError { message: e })?;
-    let file_path = recovery_dir.join(format!("{filename}.json"));
-
-    if !file_path.exists() {
-        log::info!("Recovery file not found: {file_path:?}");
-        return Err(RecoveryError::FileNotFound);
-    }
+    let result = store.load(&filename).and_then(|contents| {
+        serde_json::from_str::<Value>(&contents).map_err(|e| {
+            log::error!("Failed to parse recovery JSON: {e}");
+            RecoveryError::ParseError {
+                message: e.to_string(),
+            }
+        })
+    });
 
-    let contents = std::fs::read_to_string(&file_path).map_err(|e| {
-        log::error!("Failed to read recovery file: {e}");
-        RecoveryError::IoError {
-            message: e.to_string(),
+    match result {
+        Ok(data) => {
+            log::info!("Successfully loaded emergency data");
+            Ok(data)
         }
-    })?;
-
-    let data: Value = serde_json::from_str(&contents).map_err(|e| {
-        log::error!("Failed to parse recovery JSON: {e}");
-        RecoveryError::ParseError {
-            message: e.to_string(),
+        // A missing file is the expected case, not a failure worth surfacing
+        Err(RecoveryError::FileNotFound) => Err(RecoveryError::FileNotFound),
+        Err(error) => {
+            emit_recovery_event(
+                &app,
+                RecoveryLoadFailed {
+                    filename,
+                    error: error.clone(),
+                },
+            );
+            Err(error)
         }
-    })?;
-
-    log::info!("Successfully loaded emergency data");
-    Ok(data)
+    }
 }
 
-/// Removes recovery files older than 7 days.
-/// Returns the count of removed files.
+/// Removes recovery data older than 7 days.
+/// Returns the count of removed entries and emits `recovery-cleaned`.
 #[tauri::command]
 #[specta::specta]
-pub async fn cleanup_old_recovery_files(app: AppHandle) -> Result<u32, RecoveryError> {
+pub async fn cleanup_old_recovery_files(
+    app: AppHandle,
+    store: State<'_, SharedRecoveryStore>,
+) -> Result<u32, RecoveryError> {
     log::info!("Cleaning up old recovery files");
 
-    let recovery_dir = get_recovery_dir(&app).map_err(|e| RecoveryError::IoError { message: e })?;
-    let mut removed_count = 0;
-
     // Calculate cutoff time (7 days ago)
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
-        .map_err(|e| RecoveryError::IoError {
-            message: e.to_string(),
-        })?
+        .map_err(io_error)?
         .as_secs();
     let seven_days_ago = now - (7 * 24 * 60 * 60);
 
-    // Read directory and check each file
-    let entries = std::fs::read_dir(&recovery_dir).map_err(|e| {
-        log::error!("Failed to read recovery directory: {e}");
-        RecoveryError::IoError {
-            message: e.to_string(),
-        }
-    })?;
+    let removed_count = store.remove_older_than(seven_days_ago)?;
 
-    for entry in entries {
-        let entry = match entry {
-            Ok(e) => e,
-            Err(e) => {
-                log::warn!("Failed to read directory entry: {e}");
-                continue;
-            }
-        };
+    emit_recovery_event(&app, RecoveryCleaned { removed_count });
 
-        let path = entry.path();
+    log::info!("Cleanup complete. Removed {removed_count} old recovery files");
+    Ok(removed_count)
+}
 
-        // Only process JSON files
-        if path.extension().is_none_or(|ext| ext != "json") {
-            continue;
+// ============================================================================
+// Archive Export
+// ============================================================================
+
+/// Recursively collects all files under `dir`, skipping in-progress temp files.
+fn collect_recovery_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_recovery_files(&path, files)?;
+        } else if path.extension().is_none_or(|ext| ext != "tmp") {
+            files.push(path);
         }
+    }
+    Ok(())
+}
 
-        // Check file modification time
-        let metadata = match std::fs::metadata(&path) {
-            Ok(m) => m,
-            Err(e) => {
-                log::warn!("Failed to get file metadata: {e}");
-                continue;
+/// Lists the files under the recovery directory, for diagnostics bundles.
+pub fn list_recovery_files(app: &AppHandle) -> Result<Vec<RecoveryArchiveEntry>, AppError> {
+    let recovery_dir = get_recovery_dir(app)?;
+    let mut files = Vec::new();
+    collect_recovery_files(&recovery_dir, &mut files)
+        .map_err(|e| AppError::io(format!("Failed to read recovery directory: {e}")))?;
+    files.sort();
+
+    Ok(files
+        .iter()
+        .map(|path| {
+            let relative = path.strip_prefix(&recovery_dir).unwrap_or(path);
+            let metadata = std::fs::metadata(path).ok();
+            RecoveryArchiveEntry {
+                filename: normalize_path_for_serialization(relative),
+                modified_at: metadata
+                    .as_ref()
+                    .and_then(|m| m.modified().ok())
+                    .map(unix_millis)
+                    .unwrap_or(0.0),
+                size_bytes: metadata
+                    .map(|m| u32::try_from(m.len()).unwrap_or(u32::MAX))
+                    .unwrap_or(0),
             }
-        };
+        })
+        .collect())
+}
 
-        let modified = match metadata.modified() {
-            Ok(m) => m,
-            Err(e) => {
-                log::warn!("Failed to get file modification time: {e}");
-                continue;
-            }
-        };
+/// Writes the given recovery files plus a `manifest.json` into a zip at `archive_path`.
+fn write_recovery_archive(
+    stored: &[RecoveryEntry],
+    archive_path: &Path,
+    app_version: String,
+    token: &CancellationToken,
+) -> Result<RecoveryArchiveManifest, RecoveryError> {
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    let archive_file = std::fs::File::create(archive_path).map_err(io_error)?;
+    let mut zip = zip::ZipWriter::new(archive_file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    let mut entries = Vec::with_capacity(stored.len());
+    for entry in stored {
+        if token.is_cancelled() {
+            return Err(RecoveryError::Cancelled);
+        }
+        let filename = format!("{}.json", entry.key);
 
-        let modified_secs = match modified.duration_since(UNIX_EPOCH) {
-            Ok(d) => d.as_secs(),
-            Err(e) => {
-                log::warn!("Failed to convert modification time: {e}");
-                continue;
-            }
-        };
-
-        // Remove if older than 7 days
-        if modified_secs < seven_days_ago {
-            match std::fs::remove_file(&path) {
-                Ok(_) => {
-                    log::info!("Removed old recovery file: {path:?}");
-                    removed_count += 1;
-                }
-                Err(e) => {
-                    log::warn!("Failed to remove old recovery file: {e}");
-                }
-            }
+        zip.start_file(format!("recovery/{filename}"), options)
+            .map_err(io_error)?;
+        zip.write_all(entry.json.as_bytes()).map_err(io_error)?;
+
+        entries.push(RecoveryArchiveEntry {
+            filename,
+            modified_at: entry.modified_secs as f64 * 1000.0,
+            size_bytes: u32::try_from(entry.json.len()).unwrap_or(u32::MAX),
+        });
+    }
+
+    let manifest = RecoveryArchiveManifest {
+        created_at: unix_millis(SystemTime::now()),
+        app_version,
+        entries,
+    };
+    let manifest_json =
+        serde_json::to_string_pretty(&manifest).map_err(|e| RecoveryError::ParseError {
+            message: e.to_string(),
+        })?;
+
+    zip.start_file("manifest.json", options).map_err(io_error)?;
+    zip.write_all(manifest_json.as_bytes()).map_err(io_error)?;
+    zip.finish().map_err(io_error)?;
+
+    Ok(manifest)
+}
+
+/// Exports every entry in the recovery store into a single zip archive, one
+/// decrypted `recovery/<key>.json` file per entry. The archive includes a
+/// `manifest.json` listing each file and its timestamp.
+/// Useful for support tickets and manual backups. Pass a `task_id` to be able
+/// to stop it with `cancel_task`.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_recovery_archive(
+    app: AppHandle,
+    store: State<'_, SharedRecoveryStore>,
+    dest_path: String,
+    task_id: Option<String>,
+) -> Result<RecoveryArchiveManifest, RecoveryError> {
+    log::info!("Exporting recovery archive to: {dest_path}");
+
+    let dest_path = PathBuf::from(dest_path);
+    if !dest_path.is_absolute() {
+        return Err(RecoveryError::ValidationError {
+            message: "Destination path must be absolute".to_string(),
+        });
+    }
+    if dest_path.extension().is_none_or(|ext| ext != "zip") {
+        return Err(RecoveryError::ValidationError {
+            message: "Destination path must have a .zip extension".to_string(),
+        });
+    }
+
+    // Read through the store so entries are decrypted and SQLite is snapshotted
+    let mut stored = store.entries()?;
+    stored.sort_by(|a, b| a.key.cmp(&b.key));
+
+    // Write to a temporary file first, then rename (atomic operation)
+    let temp_path = dest_path.with_extension("zip.tmp");
+    let app_version = app.package_info().version.to_string();
+
+    let task = tasks::register(task_id);
+    let result = write_recovery_archive(&stored, &temp_path, app_version, task.token());
+    let manifest = match result {
+        Ok(manifest) => manifest,
+        Err(RecoveryError::Cancelled) => {
+            log::info!("Recovery archive export cancelled");
+            let _ = std::fs::remove_file(&temp_path);
+            return Err(RecoveryError::Cancelled);
+        }
+        Err(e) => {
+            log::error!("Failed to write recovery archive: {e}");
+            let _ = std::fs::remove_file(&temp_path);
+            return Err(e);
         }
+    };
+
+    if let Err(rename_err) = std::fs::rename(&temp_path, &dest_path) {
+        log::error!("Failed to finalize recovery archive: {rename_err}");
+        if let Err(remove_err) = std::fs::remove_file(&temp_path) {
+            log::warn!("Failed to remove temp file after rename failure: {remove_err}");
+        }
+        return Err(io_error(rename_err));
     }
 
-    log::info!("Cleanup complete. Removed {removed_count} old recovery files");
-    Ok(removed_count)
+    log::info!(
+        "Exported {} recovery files to {dest_path:?}",
+        manifest.entries.len()
+    );
+    Ok(manifest)
 }
@@ -0,0 +1,267 @@
+//! Release notes for updates.
+//!
+//! Fetches the changelog from GitHub Releases so an update or About dialog can
+//! show every version between the installed one and the update, not just the
+//! latest. Fetched releases are cached in the app cache directory for offline
+//! use; with no cache, the pending update's manifest notes are used instead.
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use super::files::write_atomic;
+use crate::types::{AppError, ReleaseNote, ReleaseNotes, ReleaseNotesSource};
+
+/// GitHub Releases API for the app's repository. Release bodies are used as
+/// markdown release notes and tags (`v1.2.3`) as versions.
+const RELEASES_URL: &str =
+    "https://api.github.com/repos/YOUR_USERNAME/YOUR_REPO/releases?per_page=100";
+
+/// Cached releases newer than this are used without fetching again.
+const CACHE_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Give up on the changelog (and fall back to the cache) after this long.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A release from the GitHub Releases API (only the fields used here).
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    body: Option<String>,
+    published_at: Option<String>,
+    #[serde(default)]
+    draft: bool,
+}
+
+/// Releases saved from the last successful fetch.
+#[derive(Debug, Serialize, Deserialize)]
+struct ReleaseCache {
+    /// Unix time in seconds of the fetch
+    fetched_at: u64,
+    releases: Vec<ReleaseNote>,
+}
+
+/// Gets the path to the release notes cache file.
+fn get_cache_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let cache_dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| AppError::internal(format!("Failed to get app cache directory: {e}")))?;
+
+    std::fs::create_dir_all(&cache_dir)
+        .map_err(|e| AppError::io(format!("Failed to create app cache directory: {e}")))?;
+
+    Ok(cache_dir.join("release-notes.json"))
+}
+
+fn load_cache(app: &AppHandle) -> Option<ReleaseCache> {
+    let path = get_cache_path(app).ok()?;
+    let contents = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents)
+        .inspect_err(|e| log::warn!("Failed to parse release notes cache: {e}"))
+        .ok()
+}
+
+/// Saves fetched releases. Uses atomic write (temp file + rename).
+fn save_cache(app: &AppHandle, cache: &ReleaseCache) -> Result<(), AppError> {
+    let path = get_cache_path(app)?;
+    let json = serde_json::to_string(cache).map_err(|e| AppError::Serialization {
+        message: format!("Failed to serialize release notes: {e}"),
+    })?;
+
+    write_atomic(&path, json.as_bytes())
+        .map_err(|e| AppError::io(format!("Failed to save release notes cache: {e}")))
+}
+
+fn unix_time(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Parses a version or release tag (`1.2.3` or `v1.2.3`).
+fn parse_version(version: &str) -> Option<Version> {
+    version.trim().trim_start_matches('v').parse().ok()
+}
+
+/// Notes for the versions after `from` up to and including `to`, newest first.
+/// Pre-releases are only included when updating to a pre-release, so users on
+/// the stable channel don't see notes for betas they never installed.
+fn notes_between(releases: &[ReleaseNote], from: &Version, to: &Version) -> Vec<ReleaseNote> {
+    let include_prereleases = !to.pre.is_empty();
+    let mut notes: Vec<(Version, ReleaseNote)> = releases
+        .iter()
+        .filter_map(|release| Some((parse_version(&release.version)?, release.clone())))
+        .filter(|(version, _)| version > from && version <= to)
+        .filter(|(version, _)| include_prereleases || version.pre.is_empty())
+        .collect();
+    notes.sort_by(|(a, _), (b, _)| b.cmp(a));
+    notes.into_iter().map(|(_, release)| release).collect()
+}
+
+/// Whether the releases include `version`, i.e. are new enough to describe it.
+fn includes_version(releases: &[ReleaseNote], version: &Version) -> bool {
+    releases
+        .iter()
+        .any(|release| parse_version(&release.version).as_ref() == Some(version))
+}
+
+async fn fetch_releases(app: &AppHandle) -> Result<Vec<ReleaseNote>, AppError> {
+    // reqwest is built without a TLS provider (like the updater plugin); this
+    // fails harmlessly if one is already installed
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    let package = app.package_info();
+    let client = reqwest::Client::builder()
+        .user_agent(format!("{}/{}", package.name, package.version))
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::internal(format!("Failed to create HTTP client: {e}")))?;
+
+    let releases: Vec<GithubRelease> = client
+        .get(RELEASES_URL)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| AppError::internal(format!("Failed to fetch release notes: {e}")))?
+        .json()
+        .await
+        .map_err(|e| AppError::Serialization {
+            message: format!("Failed to parse release notes: {e}"),
+        })?;
+
+    Ok(releases
+        .into_iter()
+        .filter(|release| !release.draft)
+        .filter_map(|release| {
+            Some(ReleaseNote {
+                version: parse_version(&release.tag_name)?.to_string(),
+                date: release.published_at,
+                notes: release.body.unwrap_or_default(),
+            })
+        })
+        .collect())
+}
+
+/// Gets the release notes for every version after `from_version` up to and
+/// including `to_version`, newest first, e.g. the installed version and a
+/// pending update. Uses cached notes when they're recent or the changelog
+/// can't be reached, then the update manifest's notes for `to_version`.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_release_notes(
+    app: AppHandle,
+    from_version: String,
+    to_version: String,
+) -> Result<ReleaseNotes, AppError> {
+    let from = parse_version(&from_version)
+        .ok_or_else(|| AppError::validation(format!("Invalid version: {from_version}")))?;
+    let to = parse_version(&to_version)
+        .ok_or_else(|| AppError::validation(format!("Invalid version: {to_version}")))?;
+
+    let now = SystemTime::now();
+    let cache = load_cache(&app);
+    let cache_is_fresh = cache.as_ref().is_some_and(|cache| {
+        now.duration_since(UNIX_EPOCH + Duration::from_secs(cache.fetched_at))
+            .is_ok_and(|age| age < CACHE_MAX_AGE)
+            && includes_version(&cache.releases, &to)
+    });
+
+    let fetched = if cache_is_fresh {
+        None
+    } else {
+        match fetch_releases(&app).await {
+            Ok(releases) => {
+                let fetched = ReleaseCache {
+                    fetched_at: unix_time(now),
+                    releases,
+                };
+                if let Err(e) = save_cache(&app, &fetched) {
+                    log::warn!("{e}");
+                }
+                Some(fetched)
+            }
+            Err(e) => {
+                log::warn!("{e}, using cached release notes");
+                None
+            }
+        }
+    };
+
+    let (releases, source) = match (fetched, cache) {
+        (Some(fetched), _) => (fetched.releases, ReleaseNotesSource::Changelog),
+        (None, Some(cache)) => (cache.releases, ReleaseNotesSource::Cache),
+        (None, None) => (Vec::new(), ReleaseNotesSource::Cache),
+    };
+    if includes_version(&releases, &to) {
+        return Ok(ReleaseNotes {
+            releases: notes_between(&releases, &from, &to),
+            source,
+        });
+    }
+
+    // Offline with no cache, or the release isn't on GitHub (e.g. manifests
+    // served from elsewhere): use the notes from the update manifest
+    let manifest_note = super::updater::pending_release_note()
+        .filter(|note| parse_version(&note.version).as_ref() == Some(&to))
+        .ok_or_else(|| {
+            AppError::not_found(format!("No release notes available for {to_version}"))
+        })?;
+    log::info!("Using update manifest notes for {to_version}");
+    Ok(ReleaseNotes {
+        releases: vec![manifest_note],
+        source: ReleaseNotesSource::Manifest,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(version: &str) -> ReleaseNote {
+        ReleaseNote {
+            version: version.to_string(),
+            date: None,
+            notes: format!("Notes for {version}"),
+        }
+    }
+
+    fn versions(notes: &[ReleaseNote]) -> Vec<&str> {
+        notes.iter().map(|note| note.version.as_str()).collect()
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("v1.2.3"), Some(Version::new(1, 2, 3)));
+        assert_eq!(parse_version("1.2.3"), Some(Version::new(1, 2, 3)));
+        assert!(parse_version("latest").is_none());
+    }
+
+    #[test]
+    fn test_notes_between() {
+        let releases = vec![
+            release("1.0.0"),
+            release("1.2.0"),
+            release("1.1.0"),
+            release("1.2.0-beta.1"),
+            release("1.3.0"),
+        ];
+        let from = Version::new(1, 0, 0);
+
+        // Newest first, excluding `from` and anything after `to`
+        assert_eq!(
+            versions(&notes_between(&releases, &from, &Version::new(1, 2, 0))),
+            ["1.2.0", "1.1.0"]
+        );
+
+        // Pre-releases only when updating to one
+        let beta = parse_version("1.2.0-beta.1").unwrap();
+        assert_eq!(
+            versions(&notes_between(&releases, &from, &beta)),
+            ["1.2.0-beta.1", "1.1.0"]
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! Full-text search commands.
+//!
+//! The frontend (or Rust code that saves app data) indexes items with
+//! `index_document` as they change and removes them with `remove_document`;
+//! `search` returns ranked matches with highlighted titles and snippets. Ids
+//! are opaque strings, so prefix them by kind (`note:42`) to mix kinds of data
+//! in one index.
+
+use tauri::State;
+
+use crate::db::{search as search_index, Database};
+use crate::types::{validate_string_input, AppError, SearchFields, SearchResult};
+
+const MAX_ID_LENGTH: usize = 256;
+const MAX_TITLE_LENGTH: usize = 1_000;
+const MAX_BODY_SIZE: usize = 1_000_000;
+const MAX_QUERY_LENGTH: usize = 500;
+
+const DEFAULT_LIMIT: u32 = 20;
+const MAX_LIMIT: u32 = 100;
+
+fn validate_id(id: &str) -> Result<(), AppError> {
+    if id.is_empty() {
+        return Err(AppError::validation("Id cannot be empty"));
+    }
+    validate_string_input(id, MAX_ID_LENGTH, "Id").map_err(AppError::validation)
+}
+
+/// Adds or replaces the indexed text for `id`.
+#[tauri::command]
+#[specta::specta]
+pub fn index_document(
+    database: State<'_, Database>,
+    id: String,
+    fields: SearchFields,
+) -> Result<(), AppError> {
+    validate_id(&id)?;
+    validate_string_input(&fields.title, MAX_TITLE_LENGTH, "Title")
+        .map_err(AppError::validation)?;
+    if fields.body.len() > MAX_BODY_SIZE {
+        return Err(AppError::validation(format!(
+            "Body too large (max {MAX_BODY_SIZE} bytes)"
+        )));
+    }
+
+    database.with_connection(|conn| search_index::index(conn, &id, &fields.title, &fields.body))
+}
+
+/// Removes `id` from the index. Returns whether it was indexed.
+#[tauri::command]
+#[specta::specta]
+pub fn remove_document(database: State<'_, Database>, id: String) -> Result<bool, AppError> {
+    validate_id(&id)?;
+    database.with_connection(|conn| search_index::remove(conn, &id))
+}
+
+/// Searches indexed items, best match first. Every term must match, and the
+/// last is matched as a prefix. `limit` defaults to 20 (max 100).
+#[tauri::command]
+#[specta::specta]
+pub fn search(
+    database: State<'_, Database>,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<SearchResult>, AppError> {
+    validate_string_input(&query, MAX_QUERY_LENGTH, "Query").map_err(AppError::validation)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    database.with_connection(|conn| search_index::search(conn, &query, limit))
+}
@@ -0,0 +1,141 @@
+//! Secret storage commands backed by the OS keychain.
+//!
+//! Use these for API tokens and credentials rather than preferences or
+//! recovery data, which are plain JSON on disk. Secrets are stored with the
+//! `keyring` crate in the macOS Keychain, Windows Credential Manager or the
+//! Secret Service on Linux.
+//!
+//! The keychain service name is always prefixed with the app identifier, so
+//! the frontend can only reach this app's entries, not other apps' (e.g. a
+//! browser's saved passwords).
+
+use keyring::Entry;
+use tauri::AppHandle;
+
+use crate::commands::auth;
+use crate::types::{validate_string_input, SecretError};
+
+const MAX_NAME_LENGTH: usize = 100;
+/// Windows Credential Manager's limit, so secrets behave the same everywhere.
+const MAX_SECRET_BYTES: usize = 2560;
+/// Services used by the backend itself (`auth` tokens).
+const RESERVED_SERVICES: &[&str] = &[auth::AUTH_SERVICE];
+
+fn validation_error(message: impl Into<String>) -> SecretError {
+    SecretError::ValidationError {
+        message: message.into(),
+    }
+}
+
+fn validate_name(value: &str, field_name: &str) -> Result<(), SecretError> {
+    if value.is_empty() {
+        return Err(validation_error(format!("{field_name} cannot be empty")));
+    }
+    validate_string_input(value, MAX_NAME_LENGTH, field_name).map_err(validation_error)
+}
+
+pub(crate) fn secret_error(e: keyring::Error) -> SecretError {
+    match e {
+        keyring::Error::NoEntry => SecretError::NotFound,
+        keyring::Error::NoStorageAccess(e) => SecretError::Locked {
+            message: e.to_string(),
+        },
+        keyring::Error::TooLong(..) | keyring::Error::Invalid(..) => {
+            validation_error(e.to_string())
+        }
+        e => SecretError::PlatformError {
+            message: e.to_string(),
+        },
+    }
+}
+
+/// The keychain entry for `key` under this app's namespaced `service`.
+pub(crate) fn entry(app: &AppHandle, service: &str, key: &str) -> Result<Entry, SecretError> {
+    validate_name(service, "Service")?;
+    validate_name(key, "Key")?;
+    let service = format!("{}.{service}", app.config().identifier);
+    Entry::new(&service, key).map_err(secret_error)
+}
+
+/// Like `entry`, but refuses services only Rust may read, so OAuth tokens
+/// never reach the webview.
+fn command_entry(app: &AppHandle, service: &str, key: &str) -> Result<Entry, SecretError> {
+    if RESERVED_SERVICES.contains(&service) {
+        return Err(validation_error(format!("Service {service} is reserved")));
+    }
+    entry(app, service, key)
+}
+
+/// Stores `value` in the OS keychain, replacing any existing secret.
+/// Values are limited to 2560 bytes.
+#[tauri::command]
+#[specta::specta]
+pub fn store_secret(
+    app: AppHandle,
+    service: String,
+    key: String,
+    value: String,
+) -> Result<(), SecretError> {
+    if value.len() > MAX_SECRET_BYTES {
+        return Err(validation_error(format!(
+            "Secret too large (max {MAX_SECRET_BYTES} bytes)"
+        )));
+    }
+
+    command_entry(&app, &service, &key)?
+        .set_password(&value)
+        .map_err(secret_error)?;
+    log::info!("Stored secret {service}/{key}");
+    Ok(())
+}
+
+/// Reads a secret from the OS keychain. Fails with `NotFound` if none is
+/// stored, and `Locked` if the keychain is locked or access was denied.
+#[tauri::command]
+#[specta::specta]
+pub fn get_secret(app: AppHandle, service: String, key: String) -> Result<String, SecretError> {
+    command_entry(&app, &service, &key)?
+        .get_password()
+        .map_err(secret_error)
+}
+
+/// Removes a secret from the OS keychain. Returns whether one was stored.
+#[tauri::command]
+#[specta::specta]
+pub fn delete_secret(app: AppHandle, service: String, key: String) -> Result<bool, SecretError> {
+    match command_entry(&app, &service, &key)?.delete_credential() {
+        Ok(()) => {
+            log::info!("Deleted secret {service}/{key}");
+            Ok(true)
+        }
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(secret_error(e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("github", "Service").is_ok());
+        assert!(matches!(
+            validate_name("", "Service"),
+            Err(SecretError::ValidationError { .. })
+        ));
+        assert!(validate_name(&"a".repeat(MAX_NAME_LENGTH + 1), "Key").is_err());
+    }
+
+    #[test]
+    fn test_secret_error_mapping() {
+        assert!(matches!(
+            secret_error(keyring::Error::NoEntry),
+            SecretError::NotFound
+        ));
+        assert!(matches!(
+            secret_error(keyring::Error::NoStorageAccess("locked".into())),
+            SecretError::Locked { .. }
+        ));
+    }
+}
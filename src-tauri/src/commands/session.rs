@@ -0,0 +1,258 @@
+//! Session restore.
+//!
+//! Remembers which app windows (those created with `create_app_window`) were
+//! open when the app quit, along with their position and size, and recreates
+//! them on the next launch. Controlled by the `restore_session` preference.
+//! Holding Shift at launch starts with a clean session instead (macOS and Windows).
+//!
+//! The main window's geometry is handled separately by the window-state plugin.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, WebviewWindow, WindowEvent};
+
+use super::files::write_atomic;
+use super::preferences::load_restore_session;
+use super::window::build_app_window;
+use crate::types::{AppError, WindowConfig};
+
+/// An open window as saved in the session file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SessionWindow {
+    /// Config used to create the window, with its current size
+    config: WindowConfig,
+    /// Logical position of the window's top-left corner
+    x: f64,
+    y: f64,
+}
+
+/// Logical bounds of a monitor as (x, y, width, height).
+type MonitorBounds = (f64, f64, f64, f64);
+
+/// Windows to save at quit, in the order they were opened.
+static OPEN_WINDOWS: Mutex<Vec<SessionWindow>> = Mutex::new(Vec::new());
+
+/// Gets the path to the session file.
+fn get_session_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = crate::data_dir::app_data_dir(app)?;
+
+    // Ensure the directory exists
+    std::fs::create_dir_all(&app_data_dir)
+        .map_err(|e| AppError::io(format!("Failed to create app data directory: {e}")))?;
+
+    Ok(app_data_dir.join("session.json"))
+}
+
+/// Reads the saved session, returning no windows if there isn't one.
+fn read_session(app: &AppHandle) -> Result<Vec<SessionWindow>, AppError> {
+    let path = get_session_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::io(format!("Failed to read session: {e}")))?;
+    serde_json::from_str(&contents).map_err(|e| AppError::Serialization {
+        message: format!("Failed to parse session: {e}"),
+    })
+}
+
+/// Writes the session file using atomic write (temp file + rename).
+fn write_session(app: &AppHandle, windows: &[SessionWindow]) -> Result<(), AppError> {
+    let path = get_session_path(app)?;
+    let json_content =
+        serde_json::to_string_pretty(windows).map_err(|e| AppError::Serialization {
+            message: format!("Failed to serialize session: {e}"),
+        })?;
+
+    write_atomic(&path, json_content.as_bytes())
+        .map_err(|e| AppError::io(format!("Failed to write session: {e}")))
+}
+
+/// Updates a session entry with the window's current position and size.
+/// Minimized windows are skipped since their reported position is meaningless.
+fn read_geometry(window: &WebviewWindow, entry: &mut SessionWindow) {
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    let Ok(scale_factor) = window.scale_factor() else {
+        return;
+    };
+
+    if let Ok(position) = window.outer_position() {
+        let position = position.to_logical::<f64>(scale_factor);
+        entry.x = position.x;
+        entry.y = position.y;
+    }
+    if let Ok(size) = window.inner_size() {
+        let size = size.to_logical::<f64>(scale_factor);
+        entry.config.width = Some(size.width);
+        entry.config.height = Some(size.height);
+    }
+}
+
+/// Starts tracking an app window so it is saved with the session.
+/// Geometry is kept up to date as the window moves, and the entry is dropped
+/// when the window is closed.
+pub(crate) fn track_window(window: &WebviewWindow, config: &WindowConfig) {
+    let mut entry = SessionWindow {
+        config: config.clone(),
+        x: 0.0,
+        y: 0.0,
+    };
+    read_geometry(window, &mut entry);
+
+    if let Ok(mut windows) = OPEN_WINDOWS.lock() {
+        windows.retain(|w| w.config.label != config.label);
+        windows.push(entry);
+    }
+
+    let app = window.app_handle().clone();
+    let label = config.label.clone();
+    window.on_window_event(move |event| match event {
+        WindowEvent::Moved(_) | WindowEvent::Resized(_) => {
+            let Some(window) = app.get_webview_window(&label) else {
+                return;
+            };
+            if let Ok(mut windows) = OPEN_WINDOWS.lock() {
+                if let Some(entry) = windows.iter_mut().find(|w| w.config.label == label) {
+                    read_geometry(&window, entry);
+                }
+            }
+        }
+        WindowEvent::Destroyed => {
+            if let Ok(mut windows) = OPEN_WINDOWS.lock() {
+                windows.retain(|w| w.config.label != label);
+            }
+        }
+        _ => {}
+    });
+}
+
+/// Saves the currently open app windows. Called when the app exits.
+/// Removes any saved session if session restore is turned off.
+pub fn save_session(app: &AppHandle) {
+    if !load_restore_session(app) {
+        if let Ok(path) = get_session_path(app) {
+            if path.exists() {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    log::warn!("Failed to remove session file: {e}");
+                }
+            }
+        }
+        return;
+    }
+
+    let windows = match OPEN_WINDOWS.lock() {
+        Ok(windows) => windows.clone(),
+        Err(e) => {
+            log::warn!("Failed to lock session windows: {e}");
+            return;
+        }
+    };
+
+    log::info!("Saving session with {} window(s)", windows.len());
+    if let Err(e) = write_session(app, &windows) {
+        log::error!("Failed to save session: {e}");
+    }
+}
+
+/// Recreates the windows from the last session. Called during app setup.
+/// Windows whose saved position is no longer on any monitor are centered instead.
+pub fn restore_session(app: &AppHandle) {
+    if !load_restore_session(app) {
+        log::debug!("Session restore is disabled");
+        return;
+    }
+    if shift_key_held() {
+        log::info!("Shift held at launch, starting with a clean session");
+        return;
+    }
+
+    let windows = match read_session(app) {
+        Ok(windows) => windows,
+        Err(e) => {
+            log::warn!("Failed to load session: {e}");
+            return;
+        }
+    };
+    if windows.is_empty() {
+        return;
+    }
+
+    log::info!("Restoring session with {} window(s)", windows.len());
+    let monitors = monitor_bounds(app);
+    for window in windows {
+        if app.get_webview_window(&window.config.label).is_some() {
+            continue;
+        }
+
+        let position =
+            is_on_any_monitor(&monitors, window.x, window.y).then_some((window.x, window.y));
+        if let Err(e) = build_app_window(app, &window.config, position) {
+            log::warn!("Failed to restore window '{}': {e}", window.config.label);
+        }
+    }
+}
+
+/// Returns the logical bounds of every connected monitor.
+fn monitor_bounds(app: &AppHandle) -> Vec<MonitorBounds> {
+    app.available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| {
+            let scale_factor = monitor.scale_factor();
+            let position = monitor.position().to_logical::<f64>(scale_factor);
+            let size = monitor.size().to_logical::<f64>(scale_factor);
+            (position.x, position.y, size.width, size.height)
+        })
+        .collect()
+}
+
+/// Whether a logical point lies on any of the given monitors.
+fn is_on_any_monitor(monitors: &[MonitorBounds], x: f64, y: f64) -> bool {
+    monitors
+        .iter()
+        .any(|&(mx, my, width, height)| x >= mx && x < mx + width && y >= my && y < my + height)
+}
+
+/// Whether Shift is held down right now, used to skip session restore at launch.
+#[cfg(target_os = "macos")]
+fn shift_key_held() -> bool {
+    use objc2_app_kit::{NSEvent, NSEventModifierFlags};
+
+    NSEvent::modifierFlags_class().contains(NSEventModifierFlags::Shift)
+}
+
+/// Whether Shift is held down right now, used to skip session restore at launch.
+#[cfg(target_os = "windows")]
+fn shift_key_held() -> bool {
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetAsyncKeyState, VK_SHIFT};
+
+    // The most significant bit is set while the key is down
+    unsafe { GetAsyncKeyState(VK_SHIFT as i32) < 0 }
+}
+
+/// Reading modifier state needs a display server connection on Linux,
+/// so the Shift escape hatch isn't supported there.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn shift_key_held() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_on_any_monitor() {
+        let monitors = [(0.0, 0.0, 1440.0, 900.0), (1440.0, -200.0, 1920.0, 1080.0)];
+
+        assert!(is_on_any_monitor(&monitors, 100.0, 100.0));
+        assert!(is_on_any_monitor(&monitors, 2000.0, -100.0));
+        assert!(!is_on_any_monitor(&monitors, -50.0, 100.0));
+        assert!(!is_on_any_monitor(&monitors, 100.0, 950.0));
+        assert!(!is_on_any_monitor(&[], 0.0, 0.0));
+    }
+}
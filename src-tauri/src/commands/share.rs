@@ -0,0 +1,285 @@
+//! Native share sheet.
+//!
+//! Hands text and files to the system's share UI, so users can send them
+//! with Mail, Messages, AirDrop or any other app that accepts shares, without
+//! per-app integrations. The share sheet is shown over the window that asked
+//! for it. Files must be in the app's file scope.
+
+use tauri::{Manager, WebviewWindow};
+
+use super::files::resolve_scoped_path;
+use crate::types::{validate_string_input, AppError, ShareAnchor, ShareItem};
+
+/// Most items shared at once.
+const MAX_ITEMS: usize = 100;
+
+/// Longest text item, in characters.
+const MAX_TEXT_LENGTH: usize = 1024 * 1024;
+
+fn validate_items(items: &[ShareItem]) -> Result<(), AppError> {
+    if items.is_empty() {
+        return Err(AppError::validation("Nothing to share"));
+    }
+    if items.len() > MAX_ITEMS {
+        return Err(AppError::validation(format!(
+            "Too many items to share ({}, max {MAX_ITEMS})",
+            items.len()
+        )));
+    }
+    for item in items {
+        if let ShareItem::Text { text } = item {
+            validate_string_input(text, MAX_TEXT_LENGTH, "Text").map_err(AppError::validation)?;
+        }
+    }
+    Ok(())
+}
+
+/// Shows the system share sheet for text and files, anchored to `anchor` (a
+/// rectangle in the calling webview, e.g. the share button's bounds) or the
+/// middle of the window. Returns once the share sheet is shown; the user
+/// picks where to send the items, or cancels, from there.
+#[tauri::command]
+#[specta::specta]
+pub fn share_items(
+    window: WebviewWindow,
+    items: Vec<ShareItem>,
+    anchor: Option<ShareAnchor>,
+) -> Result<(), AppError> {
+    validate_items(&items)?;
+
+    let app = window.app_handle();
+    let items = items
+        .into_iter()
+        .map(|item| match item {
+            ShareItem::File { path } => resolve_scoped_path(app, &path)
+                .map(|path| ShareItem::File {
+                    path: path.to_string_lossy().into_owned(),
+                })
+                .map_err(|e| AppError::internal(e.to_string())),
+            text => Ok(text),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    log::debug!(
+        "Sharing {} items from window '{}'",
+        items.len(),
+        window.label()
+    );
+    platform::share(&window, &items, anchor)
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use objc2::rc::Retained;
+    use objc2::runtime::AnyObject;
+    use objc2::AnyThread;
+    use objc2_app_kit::{NSSharingServicePicker, NSWindow};
+    use objc2_foundation::{NSArray, NSPoint, NSRect, NSRectEdge, NSSize, NSString, NSURL};
+    use tauri::WebviewWindow;
+
+    use crate::types::{AppError, ShareAnchor, ShareItem};
+
+    pub fn share(
+        window: &WebviewWindow,
+        items: &[ShareItem],
+        anchor: Option<ShareAnchor>,
+    ) -> Result<(), AppError> {
+        let ns_window_ptr = window
+            .ns_window()
+            .map_err(|e| AppError::internal(format!("Failed to get native window: {e}")))?;
+        // SAFETY: ns_window() returns a valid NSWindow pointer that lives as long as the window
+        let ns_window: &NSWindow = unsafe { &*ns_window_ptr.cast::<NSWindow>() };
+        let view = ns_window
+            .contentView()
+            .ok_or_else(|| AppError::internal("Window has no content view"))?;
+
+        let objects: Vec<Retained<AnyObject>> = items
+            .iter()
+            .map(|item| match item {
+                ShareItem::Text { text } => {
+                    Retained::into_super(Retained::into_super(NSString::from_str(text)))
+                }
+                ShareItem::File { path } => Retained::into_super(Retained::into_super(
+                    NSURL::fileURLWithPath(&NSString::from_str(path)),
+                )),
+            })
+            .collect();
+        let objects = NSArray::from_retained_slice(&objects);
+
+        // AppKit's origin is bottom-left unless the view is flipped
+        let bounds = view.bounds();
+        let flipped = view.isFlipped();
+        let rect = match anchor {
+            Some(anchor) => {
+                let y = if flipped {
+                    anchor.y
+                } else {
+                    bounds.size.height - anchor.y - anchor.height
+                };
+                NSRect::new(
+                    NSPoint::new(anchor.x, y),
+                    NSSize::new(anchor.width, anchor.height),
+                )
+            }
+            None => NSRect::new(
+                NSPoint::new(bounds.size.width / 2.0, bounds.size.height / 2.0),
+                NSSize::new(0.0, 0.0),
+            ),
+        };
+        // Below the anchor
+        let edge = if flipped {
+            NSRectEdge::MaxY
+        } else {
+            NSRectEdge::MinY
+        };
+
+        // SAFETY: the items are NSStrings and file NSURLs, which the picker accepts.
+        let picker = unsafe {
+            NSSharingServicePicker::initWithItems(NSSharingServicePicker::alloc(), &objects)
+        };
+        picker.showRelativeToRect_ofView_preferredEdge(rect, &view, edge);
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+    use tauri::{Manager, WebviewWindow};
+    use windows::core::{factory, Interface, Ref, HSTRING};
+    use windows::ApplicationModel::DataTransfer::{DataRequestedEventArgs, DataTransferManager};
+    use windows::Foundation::TypedEventHandler;
+    use windows::Storage::{IStorageItem, StorageFile, StorageFolder};
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::Shell::IDataTransferManagerInterop;
+    use windows_collections::IIterable;
+
+    use crate::types::{AppError, ShareAnchor, ShareItem};
+
+    /// Fills in the data the share UI asks for once it's shown.
+    fn provide_data(
+        args: Ref<DataRequestedEventArgs>,
+        title: &str,
+        items: &[ShareItem],
+    ) -> windows::core::Result<()> {
+        let data = args.ok()?.Request()?.Data()?;
+        data.Properties()?.SetTitle(&HSTRING::from(title))?;
+
+        let texts: Vec<&str> = items
+            .iter()
+            .filter_map(|item| match item {
+                ShareItem::Text { text } => Some(text.as_str()),
+                ShareItem::File { .. } => None,
+            })
+            .collect();
+        if !texts.is_empty() {
+            data.SetText(&HSTRING::from(texts.join("\n\n")))?;
+        }
+
+        let mut storage_items = Vec::new();
+        for item in items {
+            let ShareItem::File { path } = item else {
+                continue;
+            };
+            let hpath = HSTRING::from(path.as_str());
+            let storage_item: IStorageItem = if Path::new(path).is_dir() {
+                StorageFolder::GetFolderFromPathAsync(&hpath)?
+                    .get()?
+                    .cast()?
+            } else {
+                StorageFile::GetFileFromPathAsync(&hpath)?.get()?.cast()?
+            };
+            storage_items.push(Some(storage_item));
+        }
+        if !storage_items.is_empty() {
+            data.SetStorageItemsReadOnly(&IIterable::from(storage_items))?;
+        }
+        Ok(())
+    }
+
+    /// Windows places the share UI itself, so the anchor is ignored.
+    pub fn share(
+        window: &WebviewWindow,
+        items: &[ShareItem],
+        _anchor: Option<ShareAnchor>,
+    ) -> Result<(), AppError> {
+        let hwnd = HWND(
+            window
+                .hwnd()
+                .map_err(|e| AppError::internal(format!("Failed to get native window: {e}")))?
+                .0,
+        );
+        let interop = factory::<DataTransferManager, IDataTransferManagerInterop>()
+            .map_err(|e| AppError::internal(format!("Failed to get share manager: {e}")))?;
+        // SAFETY: the window handle is valid for the lifetime of the window.
+        let manager: DataTransferManager = unsafe { interop.GetForWindow(hwnd) }
+            .map_err(|e| AppError::internal(format!("Failed to get share manager: {e}")))?;
+
+        // The handler removes itself after one request, so handlers don't
+        // pile up on the window's manager.
+        let token = Arc::new(Mutex::new(None::<i64>));
+        let handler_token = Arc::clone(&token);
+        let title = window.app_handle().package_info().name.clone();
+        let items = items.to_vec();
+        let handler = TypedEventHandler::<DataTransferManager, DataRequestedEventArgs>::new(
+            move |manager, args| {
+                let registered = handler_token.lock().ok().and_then(|mut token| token.take());
+                if let (Ok(manager), Some(registered)) = (manager.ok(), registered) {
+                    let _ = manager.RemoveDataRequested(registered);
+                }
+                if let Err(e) = provide_data(args, &title, &items) {
+                    log::error!("Failed to provide share data: {e}");
+                }
+                Ok(())
+            },
+        );
+        let registered = manager
+            .DataRequested(&handler)
+            .map_err(|e| AppError::internal(format!("Failed to prepare share data: {e}")))?;
+        if let Ok(mut token) = token.lock() {
+            *token = Some(registered);
+        }
+
+        // SAFETY: the window handle is valid for the lifetime of the window.
+        unsafe { interop.ShowShareUIForWindow(hwnd) }
+            .map_err(|e| AppError::internal(format!("Failed to show share UI: {e}")))
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use tauri::WebviewWindow;
+
+    use crate::types::{AppError, ShareAnchor, ShareItem};
+
+    pub fn share(
+        window: &WebviewWindow,
+        items: &[ShareItem],
+        anchor: Option<ShareAnchor>,
+    ) -> Result<(), AppError> {
+        let _ = (window, items, anchor);
+        Err(AppError::internal(
+            "Sharing isn't supported on this platform",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str) -> ShareItem {
+        ShareItem::Text {
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_validate_items() {
+        assert!(validate_items(&[text("Hello")]).is_ok());
+        assert!(validate_items(&[]).is_err());
+        assert!(validate_items(&vec![text("a"); MAX_ITEMS + 1]).is_err());
+        assert!(validate_items(&[text(&"a".repeat(MAX_TEXT_LENGTH + 1))]).is_err());
+    }
+}
@@ -0,0 +1,371 @@
+//! Server-sent events from the backend.
+//!
+//! The webview's `EventSource` can't send headers, so APIs that need an
+//! `Authorization` header can't be subscribed to from JavaScript.
+//! `sse_subscribe` keeps the connection in Rust instead and forwards events
+//! through a channel, behaving like `EventSource`: it reconnects when the
+//! stream drops, sending `Last-Event-ID` so the server can resume, and
+//! honours the server's `retry` field.
+//!
+//! Each subscription runs as a task until `sse_unsubscribe` aborts it, or the
+//! server refuses the stream.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::ipc::Channel;
+use tauri::AppHandle;
+
+use crate::commands::http::{self, request_error};
+use crate::types::{AppError, HttpError, HttpHeader, SseEvent};
+
+/// Delay before reconnecting, unless the server sets one with `retry`.
+const DEFAULT_RETRY: Duration = Duration::from_secs(3);
+/// Cap on the delay when reconnecting keeps failing.
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+/// A stream with no data for this long (not even a comment) is treated as
+/// dropped.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+type AbortFn = Box<dyn Fn() + Send>;
+
+static SUBSCRIPTIONS: LazyLock<Mutex<HashMap<u32, AbortFn>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static NEXT_SUBSCRIPTION_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Parses a `text/event-stream` body as the `EventSource` spec does. The last
+/// event id and retry delay are kept across reconnections.
+#[derive(Debug, Default)]
+struct SseParser {
+    /// Bytes of the current, incomplete line
+    line: Vec<u8>,
+    /// Whether the last line ended with `\r`, so a `\n` next is part of it
+    after_cr: bool,
+    event_type: String,
+    data: String,
+    last_event_id: String,
+    retry: Option<Duration>,
+}
+
+impl SseParser {
+    fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        let mut events = Vec::new();
+        for &byte in chunk {
+            if std::mem::take(&mut self.after_cr) && byte == b'\n' {
+                continue;
+            }
+            if byte == b'\r' || byte == b'\n' {
+                self.after_cr = byte == b'\r';
+                let line = std::mem::take(&mut self.line);
+                events.extend(self.process_line(&String::from_utf8_lossy(&line)));
+            } else {
+                self.line.push(byte);
+            }
+        }
+        events
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        let (field, value) = match line.split_once(':') {
+            // A comment, often sent to keep the connection alive
+            Some(("", _)) => return None,
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event_type = value.to_string(),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => self.last_event_id = value.to_string(),
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                if let Ok(ms) = value.parse() {
+                    self.retry = Some(Duration::from_millis(ms));
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        let event_type = std::mem::take(&mut self.event_type);
+        let mut data = std::mem::take(&mut self.data);
+        if data.is_empty() {
+            return None;
+        }
+        data.pop();
+        Some(SseEvent::Message {
+            event: if event_type.is_empty() {
+                "message".to_string()
+            } else {
+                event_type
+            },
+            data,
+            id: (!self.last_event_id.is_empty()).then(|| self.last_event_id.clone()),
+        })
+    }
+
+    /// Drops a half-received event when the connection is lost.
+    fn reset(&mut self) {
+        self.line.clear();
+        self.after_cr = false;
+        self.event_type.clear();
+        self.data.clear();
+    }
+}
+
+/// How a connection ended.
+enum Ended {
+    /// Dropped or failed; worth reconnecting
+    Dropped { message: String, opened: bool },
+    /// The server refused the stream, so reconnecting won't help
+    Refused(String),
+    /// The channel is gone
+    Closed,
+}
+
+/// The delay before reconnecting after `failures` attempts in a row, doubling
+/// from the server's retry delay.
+fn reconnect_delay(retry: Duration, failures: u32) -> Duration {
+    let doublings = failures.saturating_sub(1).min(16);
+    retry
+        .saturating_mul(1 << doublings)
+        .min(MAX_RECONNECT_DELAY.max(retry))
+}
+
+async fn connect(
+    app: &AppHandle,
+    url: &reqwest::Url,
+    headers: &[HttpHeader],
+    parser: &mut SseParser,
+    on_event: &Channel<SseEvent>,
+) -> Ended {
+    let client = match http::client(app) {
+        Ok(client) => client,
+        Err(e) => return Ended::Refused(e.to_string()),
+    };
+    let mut builder = client
+        .get(url.clone())
+        .header(reqwest::header::ACCEPT, "text/event-stream")
+        .header(reqwest::header::CACHE_CONTROL, "no-cache");
+    for header in headers {
+        builder = builder.header(&header.name, &header.value);
+    }
+    if !parser.last_event_id.is_empty() {
+        builder = builder.header("Last-Event-ID", &parser.last_event_id);
+    }
+
+    let mut response = match builder.send().await {
+        Ok(response) => response,
+        Err(e) => {
+            let error = request_error(e);
+            if let HttpError::InvalidRequest { .. } = error {
+                return Ended::Refused(error.to_string());
+            }
+            return Ended::Dropped {
+                message: error.to_string(),
+                opened: false,
+            };
+        }
+    };
+    let status = response.status();
+    if status.is_server_error() || matches!(status.as_u16(), 408 | 429) {
+        return Ended::Dropped {
+            message: format!("Server responded with {status}"),
+            opened: false,
+        };
+    }
+    if status != reqwest::StatusCode::OK {
+        return Ended::Refused(format!("Server responded with {status}"));
+    }
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if !content_type.starts_with("text/event-stream") {
+        return Ended::Refused(format!("Expected text/event-stream, got {content_type:?}"));
+    }
+
+    parser.reset();
+    if on_event.send(SseEvent::Open).is_err() {
+        return Ended::Closed;
+    }
+    loop {
+        let message = match tokio::time::timeout(IDLE_TIMEOUT, response.chunk()).await {
+            Ok(Ok(Some(chunk))) => {
+                for event in parser.feed(&chunk) {
+                    if on_event.send(event).is_err() {
+                        return Ended::Closed;
+                    }
+                }
+                continue;
+            }
+            Ok(Ok(None)) => "Server closed the stream".to_string(),
+            Ok(Err(e)) => request_error(e).to_string(),
+            Err(_) => format!("No data for {} seconds", IDLE_TIMEOUT.as_secs()),
+        };
+        return Ended::Dropped {
+            message,
+            opened: true,
+        };
+    }
+}
+
+/// Keeps a subscription connected until it's refused or nobody listens.
+async fn run(
+    app: AppHandle,
+    id: u32,
+    url: reqwest::Url,
+    headers: Vec<HttpHeader>,
+    on_event: Channel<SseEvent>,
+) {
+    let mut parser = SseParser::default();
+    let mut failures = 0;
+    loop {
+        let message = match connect(&app, &url, &headers, &mut parser, &on_event).await {
+            Ended::Dropped { message, opened } => {
+                failures = if opened { 1 } else { failures + 1 };
+                message
+            }
+            Ended::Refused(message) => {
+                log::warn!("Event stream {id} refused: {message}");
+                let _ = on_event.send(SseEvent::Error {
+                    message,
+                    retry_in_ms: None,
+                });
+                break;
+            }
+            Ended::Closed => break,
+        };
+
+        let delay = reconnect_delay(parser.retry.unwrap_or(DEFAULT_RETRY), failures);
+        log::debug!("Event stream {id} dropped, reconnecting in {delay:?}: {message}");
+        let error = SseEvent::Error {
+            message,
+            retry_in_ms: Some(delay.as_millis().try_into().unwrap_or(u32::MAX)),
+        };
+        if on_event.send(error).is_err() {
+            break;
+        }
+        tokio::time::sleep(delay).await;
+    }
+
+    if let Ok(mut subscriptions) = SUBSCRIPTIONS.lock() {
+        subscriptions.remove(&id);
+    }
+}
+
+/// Subscribes to server-sent events at `url`, sending `headers` (e.g.
+/// `Authorization`) with each connection. Events arrive on `on_event` until
+/// `sse_unsubscribe`, reconnecting like `EventSource` when the stream drops.
+/// Returns the subscription id.
+#[tauri::command]
+#[specta::specta]
+pub fn sse_subscribe(
+    app: AppHandle,
+    url: String,
+    headers: Vec<HttpHeader>,
+    on_event: Channel<SseEvent>,
+) -> Result<u32, HttpError> {
+    let url = http::parse_url(&url)?;
+    let id = NEXT_SUBSCRIPTION_ID.fetch_add(1, Ordering::Relaxed);
+    log::info!(
+        "Subscribing to events from {}",
+        url.host_str().unwrap_or_default()
+    );
+
+    // Hold the lock while spawning, so the task can't finish and remove
+    // itself before it's added
+    let mut subscriptions = SUBSCRIPTIONS
+        .lock()
+        .map_err(|e| HttpError::InvalidRequest {
+            message: format!("Failed to lock subscriptions: {e}"),
+        })?;
+    let task = tauri::async_runtime::spawn(run(app, id, url, headers, on_event));
+    let abort = task.inner().abort_handle();
+    subscriptions.insert(id, Box::new(move || abort.abort()));
+    Ok(id)
+}
+
+/// Closes a subscription from `sse_subscribe`. Returns whether it was open.
+#[tauri::command]
+#[specta::specta]
+pub fn sse_unsubscribe(id: u32) -> Result<bool, AppError> {
+    let abort = SUBSCRIPTIONS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock subscriptions: {e}")))?
+        .remove(&id);
+    if let Some(abort) = &abort {
+        log::info!("Unsubscribing from event stream {id}");
+        abort();
+    }
+    Ok(abort.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(events: Vec<SseEvent>) -> Vec<(String, String, Option<String>)> {
+        events
+            .into_iter()
+            .filter_map(|event| match event {
+                SseEvent::Message { event, data, id } => Some((event, data, id)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parser() {
+        let mut parser = SseParser::default();
+        // Split mid-line and between \r and \n
+        assert!(parser.feed(b": keep-alive\r\ndata: hel").is_empty());
+        assert!(parser.feed(b"lo\r").is_empty());
+        let events = parser.feed(b"\ndata:world\r\n\r\nevent: update\nid: 7\ndata\n\n");
+        assert_eq!(
+            messages(events),
+            vec![
+                ("message".to_string(), "hello\nworld".to_string(), None),
+                ("update".to_string(), String::new(), Some("7".to_string())),
+            ]
+        );
+
+        // No data means no event, and the id sticks
+        assert!(parser.feed(b"event: ignored\nretry: 5000\n\n").is_empty());
+        assert_eq!(parser.retry, Some(Duration::from_secs(5)));
+        assert!(parser.feed(b"retry: soon\n\n").is_empty());
+        assert_eq!(parser.retry, Some(Duration::from_secs(5)));
+        assert_eq!(
+            messages(parser.feed(b"data: x\n\n")),
+            vec![(
+                "message".to_string(),
+                "x".to_string(),
+                Some("7".to_string())
+            )]
+        );
+
+        parser.feed(b"data: partial");
+        parser.reset();
+        assert_eq!(messages(parser.feed(b"data: y\n\n"))[0].1, "y");
+    }
+
+    #[test]
+    fn test_reconnect_delay() {
+        let retry = Duration::from_secs(3);
+        assert_eq!(reconnect_delay(retry, 1), retry);
+        assert_eq!(reconnect_delay(retry, 2), Duration::from_secs(6));
+        assert_eq!(reconnect_delay(retry, 10), MAX_RECONNECT_DELAY);
+        // A server asking for longer than the cap gets what it asked for
+        let slow = Duration::from_secs(120);
+        assert_eq!(reconnect_delay(slow, 3), slow);
+    }
+}
@@ -0,0 +1,98 @@
+//! Startup sequencing.
+//!
+//! The main window starts hidden (`"visible": false` in `tauri.conf.json`) and
+//! is only shown once the frontend calls `finish_startup` after its first
+//! render, so users never see a blank white window on slow machines.
+//! An optional splash window can be shown in the meantime.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, WebviewUrl};
+
+use super::window::MAIN_WINDOW_LABEL;
+use crate::types::{AppError, StartupMilestone};
+
+/// Show a splash window while the frontend loads. Edit `splash.html` to customize it.
+pub const SHOW_SPLASH_WINDOW: bool = false;
+
+/// Label of the splash window
+pub const SPLASH_WINDOW_LABEL: &str = "splash";
+
+/// Reveal the main window anyway if the frontend hasn't signalled readiness by then,
+/// so a frontend error can't leave the app invisible.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Set once the main window has been revealed.
+static STARTUP_FINISHED: AtomicBool = AtomicBool::new(false);
+
+/// Whether the main window has been revealed.
+pub fn is_startup_finished() -> bool {
+    STARTUP_FINISHED.load(Ordering::SeqCst)
+}
+
+/// Creates the splash window. Called during app setup.
+pub fn show_splash_window(app: &AppHandle) -> Result<(), AppError> {
+    use tauri::webview::WebviewWindowBuilder;
+
+    log::debug!("Showing splash window");
+    WebviewWindowBuilder::new(
+        app,
+        SPLASH_WINDOW_LABEL,
+        WebviewUrl::App("splash.html".into()),
+    )
+    .inner_size(400.0, 280.0)
+    .decorations(false)
+    .resizable(false)
+    .skip_taskbar(true)
+    .center()
+    .build()
+    .map_err(|e| AppError::internal(format!("Failed to create splash window: {e}")))?;
+
+    Ok(())
+}
+
+/// Reveals the main window after `STARTUP_TIMEOUT` if `finish_startup` hasn't been called.
+pub fn schedule_startup_timeout(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(STARTUP_TIMEOUT);
+        if !STARTUP_FINISHED.load(Ordering::SeqCst) {
+            log::warn!(
+                "Frontend didn't finish startup within {}s, showing main window",
+                STARTUP_TIMEOUT.as_secs()
+            );
+            reveal_main_window(&app);
+        }
+    });
+}
+
+/// Shows and focuses the main window and closes the splash. Only runs once.
+fn reveal_main_window(app: &AppHandle) {
+    if STARTUP_FINISHED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
+        if let Err(e) = window.show() {
+            log::error!("Failed to show main window: {e}");
+        }
+        let _ = window.set_focus();
+    }
+
+    if let Some(splash) = app.get_webview_window(SPLASH_WINDOW_LABEL) {
+        if let Err(e) = splash.close() {
+            log::warn!("Failed to close splash window: {e}");
+        }
+    }
+}
+
+/// Called by the frontend once it has rendered. Closes the splash window
+/// (if any) and reveals the main window. Later calls do nothing.
+#[tauri::command]
+#[specta::specta]
+pub fn finish_startup(app: AppHandle) {
+    log::info!("Frontend ready, finishing startup");
+    super::performance::mark(StartupMilestone::FrontendReady);
+    reveal_main_window(&app);
+    super::crash_reports::announce_pending_report(&app);
+}
@@ -0,0 +1,264 @@
+//! Streaming command results over IPC channels.
+//!
+//! For results that arrive over time (new log lines) or are too big for one
+//! response (a large file), a command takes a
+//! `Channel<StreamMessage<T, E>>` and passes it to [`start`] with a closure
+//! producing the items. The closure runs on its own thread and sends items
+//! with [`StreamSender::send`]; when it returns, its result is sent as the
+//! final `End` or `Error`. [`start`] returns the stream id straight away:
+//!
+//! ```ignore
+//! #[tauri::command]
+//! #[specta::specta]
+//! pub fn export_rows(
+//!     on_rows: Channel<StreamMessage<Vec<Row>, String>>,
+//! ) -> Result<u32, AppError> {
+//!     streams::start(on_rows, Pacing::Unpaced, move |sender| {
+//!         for page in pages() {
+//!             if !sender.send(page?) {
+//!                 break;
+//!             }
+//!         }
+//!         Ok(())
+//!     })
+//! }
+//! ```
+//!
+//! The frontend stops a stream with `stop_stream(id)`, after which nothing
+//! more is sent. With `Pacing::Acked`, only a few items are sent ahead of
+//! `ack_stream(id)` calls, so a slow consumer doesn't have everything queued
+//! in the webview; streams with no acks for 30 seconds are stopped. Streams
+//! also stop when the channel closes, e.g. because the window reloaded.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Condvar, LazyLock, Mutex};
+use std::time::Duration;
+use tauri::ipc::Channel;
+
+use crate::types::{AppError, StreamMessage};
+
+/// How long to wait for an ack before giving up on a paced stream.
+const ACK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How a stream waits for the frontend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pacing {
+    /// Items are sent as fast as they're produced
+    Unpaced,
+    /// At most this many items are sent but not yet acknowledged with
+    /// `ack_stream`
+    Acked(u32),
+}
+
+/// Pacing and stop state shared between a stream's thread and the commands.
+#[derive(Default)]
+struct StreamControl {
+    state: Mutex<ControlState>,
+    changed: Condvar,
+}
+
+#[derive(Default)]
+struct ControlState {
+    in_flight: u32,
+    stopped: bool,
+}
+
+impl StreamControl {
+    /// Waits until another item may be sent and counts it as in flight.
+    /// Returns false if the stream was stopped or no ack came in time.
+    fn acquire(&self, pacing: Pacing, timeout: Duration) -> bool {
+        let Ok(state) = self.state.lock() else {
+            return false;
+        };
+        let window = match pacing {
+            Pacing::Unpaced => return !state.stopped,
+            Pacing::Acked(window) => window.max(1),
+        };
+        let Ok((mut state, _)) = self.changed.wait_timeout_while(state, timeout, |state| {
+            !state.stopped && state.in_flight >= window
+        }) else {
+            return false;
+        };
+        if state.stopped || state.in_flight >= window {
+            return false;
+        }
+        state.in_flight += 1;
+        true
+    }
+
+    fn ack(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.in_flight = state.in_flight.saturating_sub(1);
+        }
+        self.changed.notify_one();
+    }
+
+    fn stop(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.stopped = true;
+        }
+        self.changed.notify_one();
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.state.lock().map_or(true, |state| state.stopped)
+    }
+}
+
+/// Running streams by id.
+static STREAMS: LazyLock<Mutex<HashMap<u32, Arc<StreamControl>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static NEXT_STREAM_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Sends a stream's items. See [`start`].
+pub struct StreamSender<T, E> {
+    id: u32,
+    channel: Channel<StreamMessage<T, E>>,
+    control: Arc<StreamControl>,
+    pacing: Pacing,
+}
+
+impl<T, E> StreamSender<T, E>
+where
+    T: serde::Serialize + Clone,
+    E: serde::Serialize + Clone,
+{
+    /// The id `start` returned.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Sends an item, first waiting for an ack if the stream is paced and
+    /// enough are in flight. Returns false if the stream has stopped, so the
+    /// producer should stop too.
+    pub fn send(&self, item: T) -> bool {
+        if !self.control.acquire(self.pacing, ACK_TIMEOUT) {
+            if !self.control.is_stopped() {
+                log::warn!("Stream {} timed out waiting for the frontend", self.id);
+                self.control.stop();
+            }
+            return false;
+        }
+        if let Err(e) = self.channel.send(StreamMessage::Item { data: item }) {
+            log::warn!("Stream {} closed: {e}", self.id);
+            self.control.stop();
+            return false;
+        }
+        true
+    }
+
+    /// Whether the stream was stopped, for producers that wait between items.
+    pub fn is_stopped(&self) -> bool {
+        self.control.is_stopped()
+    }
+}
+
+/// Starts a stream on `channel`, running `produce` on a new thread. When it
+/// returns, `End` or `Error` is sent, unless the stream was stopped. Returns
+/// the stream id for `stop_stream` and `ack_stream`.
+pub fn start<T, E>(
+    channel: Channel<StreamMessage<T, E>>,
+    pacing: Pacing,
+    produce: impl FnOnce(&StreamSender<T, E>) -> Result<(), E> + Send + 'static,
+) -> Result<u32, AppError>
+where
+    T: serde::Serialize + Clone + Send + 'static,
+    E: serde::Serialize + Clone + std::fmt::Display + Send + 'static,
+{
+    let id = NEXT_STREAM_ID.fetch_add(1, Ordering::Relaxed);
+    let control = Arc::new(StreamControl::default());
+    STREAMS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock streams: {e}")))?
+        .insert(id, control.clone());
+
+    let sender = StreamSender {
+        id,
+        channel,
+        control,
+        pacing,
+    };
+    std::thread::spawn(move || {
+        let result = produce(&sender);
+        if !sender.is_stopped() {
+            let message = match result {
+                Ok(()) => StreamMessage::End,
+                Err(error) => {
+                    log::warn!("Stream {id} failed: {error}");
+                    StreamMessage::Error { error }
+                }
+            };
+            let _ = sender.channel.send(message);
+        }
+        if let Ok(mut streams) = STREAMS.lock() {
+            streams.remove(&id);
+        }
+    });
+    Ok(id)
+}
+
+fn stream_control(id: u32) -> Result<Option<Arc<StreamControl>>, AppError> {
+    Ok(STREAMS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock streams: {e}")))?
+        .get(&id)
+        .cloned())
+}
+
+/// Acknowledges an item from a paced stream, letting the next one be sent.
+/// Unknown ids are ignored, since the stream may already have finished.
+#[tauri::command]
+#[specta::specta]
+pub fn ack_stream(id: u32) -> Result<(), AppError> {
+    if let Some(control) = stream_control(id)? {
+        control.ack();
+    }
+    Ok(())
+}
+
+/// Stops a stream. No further messages are sent, not even `End`. Unknown ids
+/// are ignored, since the stream may already have finished.
+#[tauri::command]
+#[specta::specta]
+pub fn stop_stream(id: u32) -> Result<(), AppError> {
+    if let Some(control) = stream_control(id)? {
+        control.stop();
+        log::debug!("Stopped stream {id}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paced_control() {
+        let control = StreamControl::default();
+        let pacing = Pacing::Acked(2);
+        let timeout = Duration::from_millis(10);
+        assert!(control.acquire(pacing, timeout));
+        assert!(control.acquire(pacing, timeout));
+        // The window is full until an item is acknowledged
+        assert!(!control.acquire(pacing, timeout));
+        control.ack();
+        assert!(control.acquire(pacing, timeout));
+
+        control.stop();
+        control.ack();
+        assert!(!control.acquire(pacing, timeout));
+        assert!(control.is_stopped());
+    }
+
+    #[test]
+    fn test_unpaced_control() {
+        let control = StreamControl::default();
+        for _ in 0..100 {
+            assert!(control.acquire(Pacing::Unpaced, Duration::ZERO));
+        }
+        control.stop();
+        assert!(!control.acquire(Pacing::Unpaced, Duration::ZERO));
+    }
+}
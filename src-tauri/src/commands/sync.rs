@@ -0,0 +1,99 @@
+//! Sync engine commands.
+//!
+//! The frontend queues changes to its records here; the engine in
+//! `crate::sync_engine` pushes them to the app's backend and reports progress
+//! as `sync-status` events.
+
+use std::time::Duration;
+use tauri::{AppHandle, State};
+
+use crate::sync_engine::{self, SyncEngine};
+use crate::types::{validate_string_input, AppError, SyncChange, SyncOperation, SyncStatus};
+
+const MAX_NAME_LENGTH: usize = 256;
+/// Maximum size of a payload, serialized as JSON.
+const MAX_PAYLOAD_SIZE: usize = 1024 * 1024;
+
+fn validate_change(change: &SyncChange) -> Result<(), AppError> {
+    for (value, field_name) in [
+        (&change.collection, "Collection"),
+        (&change.record_id, "Record id"),
+    ] {
+        if value.is_empty() {
+            return Err(AppError::validation(format!(
+                "{field_name} cannot be empty"
+            )));
+        }
+        validate_string_input(value, MAX_NAME_LENGTH, field_name).map_err(AppError::validation)?;
+    }
+    match (&change.operation, &change.payload) {
+        (SyncOperation::Upsert, None) => Err(AppError::validation("Upserts need a payload")),
+        (_, Some(payload)) if payload.to_string().len() > MAX_PAYLOAD_SIZE => {
+            Err(AppError::validation(format!(
+                "Payload too large (max {} KB)",
+                MAX_PAYLOAD_SIZE / 1024
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Queues a local change to a record for the next sync, replacing any change
+/// to the same record that hasn't been pushed yet.
+#[tauri::command]
+#[specta::specta]
+pub fn queue_sync_change(app: AppHandle, change: SyncChange) -> Result<(), AppError> {
+    validate_change(&change)?;
+    sync_engine::queue_change(&app, &change)
+}
+
+/// Syncs now rather than at the next scheduled time.
+#[tauri::command]
+#[specta::specta]
+pub fn sync_now(engine: State<'_, SyncEngine>) -> Result<(), AppError> {
+    if !engine.is_enabled() {
+        return Err(AppError::validation("Sync isn't configured"));
+    }
+    engine.request_sync(Duration::ZERO)
+}
+
+/// Returns the sync engine's current status.
+#[tauri::command]
+#[specta::specta]
+pub fn get_sync_status(engine: State<'_, SyncEngine>) -> Result<SyncStatus, AppError> {
+    engine.status()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_change() {
+        let change = SyncChange {
+            collection: "notes".to_string(),
+            record_id: "1".to_string(),
+            operation: SyncOperation::Upsert,
+            payload: Some(json!({ "title": "Hello" })),
+            base_version: None,
+        };
+        assert!(validate_change(&change).is_ok());
+        assert!(validate_change(&SyncChange {
+            payload: None,
+            ..change.clone()
+        })
+        .is_err());
+        assert!(validate_change(&SyncChange {
+            operation: SyncOperation::Delete,
+            payload: None,
+            ..change.clone()
+        })
+        .is_ok());
+        assert!(validate_change(&SyncChange {
+            collection: String::new(),
+            ..change
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,316 @@
+//! Dock and taskbar indicators.
+//!
+//! Shows progress for long-running work (exports, downloads) and badges for
+//! things like unread counts on the main window's dock or taskbar icon.
+//!
+//! | Platform | Progress                 | Badge                        |
+//! | -------- | ------------------------ | ---------------------------- |
+//! | macOS    | Dock icon progress bar   | Dock badge (count or text)   |
+//! | Windows  | Taskbar button progress  | Overlay icon (count only)    |
+//! | Linux    | Launcher entry (Unity)   | Launcher count (Unity)       |
+//!
+//! The unread count is a badge count kept here, so it can optionally count
+//! native notifications sent while the main window is in the background and
+//! clear when the user comes back to it.
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+use super::window::MAIN_WINDOW_LABEL;
+use crate::types::{AppBadge, AppError};
+
+/// Current unread count shown in the badge.
+static UNREAD_COUNT: AtomicU32 = AtomicU32::new(0);
+
+/// Whether notifications sent in the background increment the unread count.
+static AUTO_UNREAD_COUNT: AtomicBool = AtomicBool::new(false);
+
+fn get_main_window(app: &AppHandle) -> Result<WebviewWindow, AppError> {
+    app.get_webview_window(MAIN_WINDOW_LABEL)
+        .ok_or_else(|| AppError::not_found("Main window not found"))
+}
+
+/// Converts a 0.0–1.0 fraction to the whole percentage the platforms expect.
+/// Out-of-range values are clamped and non-finite values count as 0.
+fn progress_percent(fraction: f64) -> u64 {
+    if !fraction.is_finite() {
+        return 0;
+    }
+    (fraction.clamp(0.0, 1.0) * 100.0).round() as u64
+}
+
+/// Shows progress on the app's dock or taskbar icon.
+/// Pass a fraction from 0.0 to 1.0, or None to hide the progress bar.
+#[tauri::command]
+#[specta::specta]
+pub fn set_progress(app: AppHandle, fraction: Option<f64>) -> Result<(), AppError> {
+    let state = match fraction {
+        Some(fraction) => ProgressBarState {
+            status: Some(ProgressBarStatus::Normal),
+            progress: Some(progress_percent(fraction)),
+        },
+        None => ProgressBarState {
+            status: Some(ProgressBarStatus::None),
+            progress: None,
+        },
+    };
+
+    get_main_window(&app)?
+        .set_progress_bar(state)
+        .map_err(|e| AppError::internal(format!("Failed to set progress: {e}")))
+}
+
+/// Shows a badge on the app's dock or taskbar icon, or clears it when None.
+/// Text badges are macOS only; elsewhere they are ignored.
+#[tauri::command]
+#[specta::specta]
+pub fn set_badge(app: AppHandle, badge: Option<AppBadge>) -> Result<(), AppError> {
+    apply_badge(&get_main_window(&app)?, badge)
+}
+
+fn apply_badge(window: &WebviewWindow, badge: Option<AppBadge>) -> Result<(), AppError> {
+    log::debug!("Setting badge: {badge:?}");
+
+    #[cfg(target_os = "macos")]
+    {
+        let label = match badge {
+            Some(AppBadge::Count(0)) | None => None,
+            Some(AppBadge::Count(count)) => Some(count.to_string()),
+            Some(AppBadge::Text(text)) => Some(text),
+        };
+        window
+            .set_badge_label(label)
+            .map_err(|e| AppError::internal(format!("Failed to set badge: {e}")))?;
+    }
+
+    #[cfg(all(desktop, not(any(target_os = "macos", target_os = "windows"))))]
+    {
+        let count = match badge {
+            Some(AppBadge::Count(count)) if count > 0 => Some(i64::from(count)),
+            Some(AppBadge::Text(_)) => {
+                log::debug!("Text badges are only supported on macOS");
+                return Ok(());
+            }
+            _ => None,
+        };
+        window
+            .set_badge_count(count)
+            .map_err(|e| AppError::internal(format!("Failed to set badge: {e}")))?;
+    }
+
+    // Windows has no badges, so counts are drawn as the taskbar overlay icon
+    #[cfg(target_os = "windows")]
+    {
+        let icon = match badge {
+            Some(AppBadge::Count(count)) => overlay_icon(count),
+            Some(AppBadge::Text(_)) => {
+                log::debug!("Text badges are only supported on macOS");
+                return Ok(());
+            }
+            None => None,
+        };
+        window
+            .set_overlay_icon(icon)
+            .map_err(|e| AppError::internal(format!("Failed to set badge: {e}")))?;
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Unread Count
+// ============================================================================
+
+fn show_unread_count(app: &AppHandle, count: u32) -> Result<(), AppError> {
+    UNREAD_COUNT.store(count, Ordering::Relaxed);
+    apply_badge(&get_main_window(app)?, Some(AppBadge::Count(count)))
+}
+
+/// Sets the unread count shown in the dock or taskbar badge. Zero clears it.
+#[tauri::command]
+#[specta::specta]
+pub fn set_unread_count(app: AppHandle, count: u32) -> Result<(), AppError> {
+    show_unread_count(&app, count)
+}
+
+/// Turns automatic unread counting on or off. When on, each native
+/// notification sent while the main window is unfocused adds one to the
+/// unread count, and focusing the main window clears it.
+#[tauri::command]
+#[specta::specta]
+pub fn set_auto_unread_count(enabled: bool) {
+    log::debug!(
+        "Automatic unread count {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+    AUTO_UNREAD_COUNT.store(enabled, Ordering::Relaxed);
+}
+
+/// Counts a sent notification as unread if automatic counting is on and the
+/// user isn't looking at the main window. Called by `send_native_notification`.
+pub fn count_notification(app: &AppHandle) {
+    if !AUTO_UNREAD_COUNT.load(Ordering::Relaxed) {
+        return;
+    }
+    let focused = get_main_window(app)
+        .and_then(|window| {
+            window
+                .is_focused()
+                .map_err(|e| AppError::internal(e.to_string()))
+        })
+        .unwrap_or(false);
+    if focused {
+        return;
+    }
+
+    let count = UNREAD_COUNT.load(Ordering::Relaxed).saturating_add(1);
+    if let Err(e) = show_unread_count(app, count) {
+        log::warn!("Failed to update unread count: {e}");
+    }
+}
+
+/// Clears an automatic unread count. Called when the main window gains focus.
+pub fn clear_auto_unread_count(app: &AppHandle) {
+    if !AUTO_UNREAD_COUNT.load(Ordering::Relaxed) || UNREAD_COUNT.load(Ordering::Relaxed) == 0 {
+        return;
+    }
+    if let Err(e) = show_unread_count(app, 0) {
+        log::warn!("Failed to clear unread count: {e}");
+    }
+}
+
+// ============================================================================
+// Overlay Icon
+// ============================================================================
+
+/// Overlay icon size. Windows scales it to the taskbar's overlay size.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const OVERLAY_SIZE: u32 = 32;
+
+/// Badge background (red) and text (white) colours, as RGBA.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const OVERLAY_BACKGROUND: [u8; 4] = [0xE8, 0x11, 0x23, 0xFF];
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const OVERLAY_FOREGROUND: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
+
+/// 3x5 pixel glyphs for the overlay text, one row per entry.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn glyph(character: char) -> [u8; 5] {
+    match character {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+        _ => [0; 5],
+    }
+}
+
+/// Text drawn on the overlay icon: the count, or `9+` when it doesn't fit.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn overlay_text(count: u32) -> Option<String> {
+    match count {
+        0 => None,
+        1..=9 => Some(count.to_string()),
+        _ => Some("9+".to_string()),
+    }
+}
+
+/// Draws the count as a red circle with white text, as RGBA pixels.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn overlay_pixels(text: &str) -> Vec<u8> {
+    let size = OVERLAY_SIZE as usize;
+    let mut pixels = vec![0u8; size * size * 4];
+    let mut fill = |x: usize, y: usize, colour: [u8; 4]| {
+        if x < size && y < size {
+            let offset = (y * size + x) * 4;
+            pixels[offset..offset + 4].copy_from_slice(&colour);
+        }
+    };
+
+    let centre = (size as f64 - 1.0) / 2.0;
+    let radius = size as f64 / 2.0;
+    for y in 0..size {
+        for x in 0..size {
+            let (dx, dy) = (x as f64 - centre, y as f64 - centre);
+            if dx * dx + dy * dy <= radius * radius {
+                fill(x, y, OVERLAY_BACKGROUND);
+            }
+        }
+    }
+
+    // Single characters are drawn larger
+    let characters: Vec<char> = text.chars().collect();
+    let scale = if characters.len() == 1 { 4 } else { 3 };
+    let width = characters.len() * 4 * scale - scale;
+    let (left, top) = ((size - width) / 2, (size - 5 * scale) / 2);
+    for (index, character) in characters.iter().enumerate() {
+        for (row, bits) in glyph(*character).iter().enumerate() {
+            for column in 0..3 {
+                if bits & (0b100 >> column) == 0 {
+                    continue;
+                }
+                for (sx, sy) in (0..scale).flat_map(|sx| (0..scale).map(move |sy| (sx, sy))) {
+                    fill(
+                        left + (index * 4 + column) * scale + sx,
+                        top + row * scale + sy,
+                        OVERLAY_FOREGROUND,
+                    );
+                }
+            }
+        }
+    }
+    pixels
+}
+
+/// The overlay icon for a count, or None to clear it.
+#[cfg(target_os = "windows")]
+fn overlay_icon(count: u32) -> Option<tauri::image::Image<'static>> {
+    let text = overlay_text(count)?;
+    Some(tauri::image::Image::new_owned(
+        overlay_pixels(&text),
+        OVERLAY_SIZE,
+        OVERLAY_SIZE,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_percent() {
+        assert_eq!(progress_percent(0.0), 0);
+        assert_eq!(progress_percent(0.425), 43);
+        assert_eq!(progress_percent(1.0), 100);
+        assert_eq!(progress_percent(1.5), 100);
+        assert_eq!(progress_percent(-0.5), 0);
+        assert_eq!(progress_percent(f64::NAN), 0);
+    }
+
+    #[test]
+    fn test_overlay_text() {
+        assert_eq!(overlay_text(0), None);
+        assert_eq!(overlay_text(7).as_deref(), Some("7"));
+        assert_eq!(overlay_text(42).as_deref(), Some("9+"));
+    }
+
+    #[test]
+    fn test_overlay_pixels() {
+        let size = OVERLAY_SIZE as usize;
+        let pixels = overlay_pixels("9+");
+        assert_eq!(pixels.len(), size * size * 4);
+
+        let pixel = |x: usize, y: usize| &pixels[(y * size + x) * 4..(y * size + x) * 4 + 4];
+        assert_eq!(pixel(0, 0), [0, 0, 0, 0]);
+        assert_eq!(pixel(size / 2, 2), OVERLAY_BACKGROUND);
+        assert!(pixels.chunks(4).any(|p| p == OVERLAY_FOREGROUND));
+    }
+}
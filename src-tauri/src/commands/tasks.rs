@@ -0,0 +1,174 @@
+//! Cancelling long-running commands.
+//!
+//! A command that can take a while (hashing a large file, writing a backup
+//! or export) takes an optional `task_id`, generated by the frontend with
+//! `crypto.randomUUID()`. While it runs, `cancel_task(task_id)` cancels it and
+//! it fails with its error type's `Cancelled`.
+//!
+//! Commands call [`register`] and check the returned guard's token as they
+//! work, either between steps with [`CancellationToken::is_cancelled`] or by
+//! reading through [`CancellationToken::reader`], which fails once cancelled:
+//!
+//! ```ignore
+//! let task = tasks::register(task_id);
+//! let token = task.token().clone();
+//! let result = spawn_blocking(move || {
+//!     std::io::copy(&mut token.reader(File::open(&path)?), &mut hasher)
+//! })
+//! .await;
+//! if task.token().is_cancelled() {
+//!     return Err(FileError::Cancelled);
+//! }
+//! ```
+//!
+//! Downloads have their own `cancel_download`, since they outlive the
+//! command that starts them.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+
+use crate::types::AppError;
+
+/// Running tasks by id: a unique serial, so a finished task doesn't remove a
+/// newer one that reused its id, and its token.
+static TASKS: LazyLock<Mutex<HashMap<String, (u32, CancellationToken)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static NEXT_SERIAL: AtomicU32 = AtomicU32::new(1);
+
+/// A flag shared between a task and `cancel_task`. Clones share the flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Wraps `reader` so reads fail once the token is cancelled, which stops
+    /// `std::io::copy`, hashing, compression and the like within one read.
+    pub fn reader<R: Read>(&self, reader: R) -> CancellableReader<R> {
+        CancellableReader {
+            inner: reader,
+            token: self.clone(),
+        }
+    }
+}
+
+/// A reader that fails once its token is cancelled. See
+/// [`CancellationToken::reader`].
+pub struct CancellableReader<R> {
+    inner: R,
+    token: CancellationToken,
+}
+
+impl<R: Read> Read for CancellableReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.token.is_cancelled() {
+            return Err(std::io::Error::other("Cancelled"));
+        }
+        self.inner.read(buf)
+    }
+}
+
+/// A registered task. Unregisters it when dropped.
+pub struct TaskGuard {
+    /// The id and serial, if registered
+    entry: Option<(String, u32)>,
+    token: CancellationToken,
+}
+
+impl TaskGuard {
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+}
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        let Some((id, serial)) = &self.entry else {
+            return;
+        };
+        if let Ok(mut tasks) = TASKS.lock() {
+            if tasks.get(id).is_some_and(|(current, _)| current == serial) {
+                tasks.remove(id);
+            }
+        }
+    }
+}
+
+/// Registers a task so `cancel_task(task_id)` can cancel it until the
+/// returned guard is dropped. Without an id, the task can't be cancelled but
+/// the guard works the same. A task reusing a running task's id replaces it.
+pub fn register(task_id: Option<String>) -> TaskGuard {
+    let token = CancellationToken::default();
+    let entry = task_id.and_then(|id| {
+        let serial = NEXT_SERIAL.fetch_add(1, Ordering::Relaxed);
+        let mut tasks = TASKS
+            .lock()
+            .inspect_err(|e| log::warn!("Failed to register task {id}: {e}"))
+            .ok()?;
+        tasks.insert(id.clone(), (serial, token.clone()));
+        Some((id, serial))
+    });
+    TaskGuard { entry, token }
+}
+
+/// Cancels the task started with `task_id`. Returns false if it already
+/// finished (or never started).
+#[tauri::command]
+#[specta::specta]
+pub fn cancel_task(task_id: String) -> Result<bool, AppError> {
+    let task = TASKS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock tasks: {e}")))?
+        .remove(&task_id);
+    if let Some((_, token)) = &task {
+        log::debug!("Cancelling task {task_id}");
+        token.cancel();
+    }
+    Ok(task.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_task() {
+        let task = register(Some("test-cancel".to_string()));
+        assert!(!task.token().is_cancelled());
+        assert_eq!(cancel_task("test-cancel".to_string()), Ok(true));
+        assert!(task.token().is_cancelled());
+        assert_eq!(cancel_task("test-cancel".to_string()), Ok(false));
+    }
+
+    #[test]
+    fn test_finished_task_keeps_newer_one_with_same_id() {
+        let first = register(Some("test-reuse".to_string()));
+        let second = register(Some("test-reuse".to_string()));
+        drop(first);
+        assert_eq!(cancel_task("test-reuse".to_string()), Ok(true));
+        assert!(second.token().is_cancelled());
+
+        drop(register(Some("test-drop".to_string())));
+        assert_eq!(cancel_task("test-drop".to_string()), Ok(false));
+    }
+
+    #[test]
+    fn test_reader_stops_when_cancelled() {
+        let token = CancellationToken::default();
+        let mut reader = token.reader(&b"abcdef"[..]);
+        let mut buf = [0; 3];
+        assert_eq!(reader.read(&mut buf).unwrap(), 3);
+        token.cancel();
+        assert!(reader.read(&mut buf).is_err());
+        assert!(std::io::copy(&mut reader, &mut std::io::sink()).is_err());
+    }
+}
@@ -0,0 +1,209 @@
+//! Temporary workspaces for intermediate files.
+//!
+//! `create_temp_dir` makes a fresh directory under `temp/` in the OS cache
+//! directory for work like exports or unzipped archives, so none of it ends
+//! up in the app data directory (which may be synced or backed up). The
+//! scoped file commands accept paths inside it.
+//!
+//! Workspaces are removed with `remove_temp_dir` when the work is done.
+//! Anything left over, e.g. after a crash, is removed at the next launch.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::types::{validate_filename, AppError};
+
+/// Longest accepted `purpose`, which becomes part of the directory name.
+const MAX_PURPOSE_LENGTH: usize = 40;
+
+/// Where leftover workspaces are moved while they're deleted at launch. Each
+/// launch moves its leftovers into a new subdirectory, so anything left from
+/// a cleanup that was cut short is deleted with them.
+const STALE_DIR_NAME: &str = "temp-stale";
+
+static NEXT_WORKSPACE: AtomicU32 = AtomicU32::new(1);
+
+/// Directory holding all workspaces.
+pub fn temp_root(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let cache_dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| AppError::internal(format!("Failed to get app cache directory: {e}")))?;
+    Ok(cache_dir.join("temp"))
+}
+
+/// Directory name for a new workspace: the purpose plus a unique suffix.
+fn workspace_name(purpose: &str) -> Result<String, AppError> {
+    if purpose.contains('.') || purpose.chars().count() > MAX_PURPOSE_LENGTH {
+        return Err(AppError::validation(format!(
+            "Purpose must be at most {MAX_PURPOSE_LENGTH} letters, digits, dashes or underscores"
+        )));
+    }
+    validate_filename(purpose).map_err(AppError::validation)?;
+
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    let counter = NEXT_WORKSPACE.fetch_add(1, Ordering::Relaxed);
+    Ok(format!("{purpose}-{millis}-{counter}"))
+}
+
+/// Whether `path` is a workspace: a directory directly inside `root`.
+/// Both paths must be canonical.
+fn is_workspace(root: &Path, path: &Path) -> bool {
+    path.parent() == Some(root) && path.is_dir()
+}
+
+/// Removes everything in `root`. Returns the number of workspaces removed.
+fn remove_workspaces(root: &Path) -> u32 {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return 0;
+    };
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let result = if path.is_dir() {
+            std::fs::remove_dir_all(&path)
+        } else {
+            std::fs::remove_file(&path)
+        };
+        match result {
+            Ok(()) => removed += 1,
+            Err(e) => log::warn!("Failed to remove temp workspace {}: {e}", path.display()),
+        }
+    }
+    removed
+}
+
+/// Removes workspaces left over from earlier sessions. Called during app
+/// setup, before any workspace is created.
+///
+/// The old root is renamed out of the way first, so the (possibly slow)
+/// deletion can run in the background without touching new workspaces or
+/// delaying startup.
+pub fn cleanup_stale_temp_dirs(app: &AppHandle) {
+    let root = match temp_root(app) {
+        Ok(root) => root,
+        Err(e) => {
+            log::warn!("Failed to clean up temp workspaces: {e}");
+            return;
+        }
+    };
+    if !root.exists() {
+        return;
+    }
+
+    let stale_root = root.with_file_name(STALE_DIR_NAME);
+    let launched = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis());
+    let stale = stale_root.join(launched.to_string());
+    let moved = std::fs::create_dir_all(&stale_root).and_then(|()| std::fs::rename(&root, &stale));
+    if let Err(e) = moved {
+        log::warn!("Failed to move stale temp workspaces: {e}");
+        return;
+    }
+
+    std::thread::spawn(move || {
+        let removed = remove_workspaces(&stale);
+        // Also removes leftovers of earlier cleanups that were cut short
+        if let Err(e) = std::fs::remove_dir_all(&stale_root) {
+            log::warn!("Failed to remove stale temp workspaces: {e}");
+        }
+        if removed > 0 {
+            log::info!("Removed {removed} stale temp workspace(s)");
+        }
+    });
+}
+
+/// Creates an empty workspace directory and returns its path. `purpose`
+/// (e.g. "export" or "unzip") names the directory, to tell workspaces apart
+/// when debugging.
+#[tauri::command]
+#[specta::specta]
+pub fn create_temp_dir(app: AppHandle, purpose: String) -> Result<String, AppError> {
+    let name = workspace_name(&purpose)?;
+    let root = temp_root(&app)?;
+    std::fs::create_dir_all(&root)
+        .map_err(|e| AppError::io(format!("Failed to create temp directory: {e}")))?;
+
+    let dir = root.join(name);
+    std::fs::create_dir(&dir)
+        .map_err(|e| AppError::io(format!("Failed to create temp workspace: {e}")))?;
+    // Canonical, so paths built from it pass the file commands' scope check
+    let dir = std::fs::canonicalize(&dir)
+        .map_err(|e| AppError::io(format!("Failed to resolve temp workspace: {e}")))?;
+
+    log::debug!("Created temp workspace {}", dir.display());
+    Ok(dir.to_string_lossy().into_owned())
+}
+
+/// Removes a workspace from `create_temp_dir` and everything in it. Returns
+/// false if it no longer exists.
+#[tauri::command]
+#[specta::specta]
+pub fn remove_temp_dir(app: AppHandle, path: String) -> Result<bool, AppError> {
+    let Ok(dir) = std::fs::canonicalize(&path) else {
+        return Ok(false);
+    };
+    let root = std::fs::canonicalize(temp_root(&app)?)
+        .map_err(|e| AppError::io(format!("Failed to resolve temp directory: {e}")))?;
+    if !is_workspace(&root, &dir) {
+        return Err(AppError::validation(format!(
+            "Not a temp workspace: {path}"
+        )));
+    }
+
+    std::fs::remove_dir_all(&dir)
+        .map_err(|e| AppError::io(format!("Failed to remove temp workspace: {e}")))?;
+    log::debug!("Removed temp workspace {}", dir.display());
+    Ok(true)
+}
+
+/// Removes every workspace, including ones still in use, and returns how
+/// many were removed. For a "Clear temporary files" action.
+#[tauri::command]
+#[specta::specta]
+pub fn cleanup_temp_dirs(app: AppHandle) -> Result<u32, AppError> {
+    let removed = remove_workspaces(&temp_root(&app)?);
+    log::info!("Removed {removed} temp workspace(s)");
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_name() {
+        let first = workspace_name("export").unwrap();
+        assert!(first.starts_with("export-"));
+        assert_ne!(first, workspace_name("export").unwrap());
+
+        assert!(workspace_name("").is_err());
+        assert!(workspace_name("../data").is_err());
+        assert!(workspace_name("export.zip").is_err());
+        assert!(workspace_name(&"a".repeat(41)).is_err());
+    }
+
+    #[test]
+    fn test_remove_workspaces() {
+        let root = std::env::temp_dir().join(format!("temp-dirs-test-{}", std::process::id()));
+        let workspace = root.join("export-1-1");
+        std::fs::create_dir_all(workspace.join("nested")).unwrap();
+        std::fs::write(workspace.join("nested/file.txt"), "data").unwrap();
+        let root = std::fs::canonicalize(&root).unwrap();
+        let workspace = std::fs::canonicalize(&workspace).unwrap();
+
+        assert!(is_workspace(&root, &workspace));
+        assert!(!is_workspace(&root, &workspace.join("nested")));
+        assert!(!is_workspace(&root, &root));
+
+        assert_eq!(remove_workspaces(&root), 1);
+        assert!(!workspace.exists());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,207 @@
+//! Image thumbnails.
+//!
+//! `generate_thumbnail` decodes an image in Rust, off the main thread, so
+//! large photos never have to be loaded into the webview just to show a
+//! preview. Thumbnails are written to `thumbnails/` in the OS cache directory
+//! and the webview loads them through the asset protocol, which is scoped to
+//! that directory in `tauri.conf.json`.
+//!
+//! Files are named after a hash of the source path, its size and modification
+//! time, and the requested size, so an edited image gets a new thumbnail and
+//! repeat requests are served from disk. The oldest files are removed once
+//! there are more than `MAX_THUMBNAILS`.
+
+use image::{DynamicImage, ImageFormat, ImageReader};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::{AppHandle, Manager};
+
+use super::files::{resolve_scoped_path, write_atomic};
+use crate::types::FileError;
+
+/// Accepted range for `max_dimension`. Requests outside are clamped.
+const MIN_DIMENSION: u32 = 16;
+const MAX_DIMENSION: u32 = 1024;
+
+/// Thumbnails kept on disk before the oldest are removed.
+const MAX_THUMBNAILS: usize = 2000;
+
+fn io_error(e: std::io::Error) -> FileError {
+    FileError::IoError {
+        message: e.to_string(),
+    }
+}
+
+fn thumbnail_dir(app: &AppHandle) -> Result<PathBuf, FileError> {
+    let cache_dir = app.path().app_cache_dir().map_err(|e| FileError::IoError {
+        message: format!("Failed to get app cache directory: {e}"),
+    })?;
+    Ok(cache_dir.join("thumbnails"))
+}
+
+/// Cache file name (without extension) for `path` at `max_dimension`.
+fn thumbnail_key(path: &Path, max_dimension: u32) -> std::io::Result<String> {
+    let metadata = std::fs::metadata(path)?;
+    let modified = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(path.to_string_lossy().as_bytes());
+    hasher.update(&metadata.len().to_le_bytes());
+    hasher.update(&modified.as_nanos().to_le_bytes());
+    hasher.update(&max_dimension.to_le_bytes());
+    Ok(hasher.finalize().to_hex()[..32].to_string())
+}
+
+/// Decodes the image at `path` and scales it to fit within `max_dimension`
+/// (never upscaling). Returns the encoded thumbnail and its format: JPEG for
+/// opaque images, PNG for ones with transparency.
+fn render_thumbnail(path: &Path, max_dimension: u32) -> image::ImageResult<(Vec<u8>, ImageFormat)> {
+    let image = ImageReader::open(path)?.with_guessed_format()?.decode()?;
+    let image = if image.width() > max_dimension || image.height() > max_dimension {
+        image.thumbnail(max_dimension, max_dimension)
+    } else {
+        image
+    };
+
+    let mut bytes = Cursor::new(Vec::new());
+    let format = if image.color().has_alpha() {
+        image.write_to(&mut bytes, ImageFormat::Png)?;
+        ImageFormat::Png
+    } else {
+        DynamicImage::ImageRgb8(image.to_rgb8()).write_to(&mut bytes, ImageFormat::Jpeg)?;
+        ImageFormat::Jpeg
+    };
+    Ok((bytes.into_inner(), format))
+}
+
+/// Returns an existing thumbnail for `key`, in either format.
+fn find_thumbnail(dir: &Path, key: &str) -> Option<PathBuf> {
+    ["jpg", "png"]
+        .iter()
+        .map(|ext| dir.join(format!("{key}.{ext}")))
+        .find(|path| path.is_file())
+}
+
+/// Removes the least recently written thumbnails beyond `MAX_THUMBNAILS`.
+fn prune_thumbnails(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<_> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((modified, entry.path()))
+        })
+        .collect();
+    if files.len() <= MAX_THUMBNAILS {
+        return;
+    }
+
+    files.sort();
+    let excess = files.len() - MAX_THUMBNAILS;
+    for (_, path) in files.into_iter().take(excess) {
+        if let Err(e) = std::fs::remove_file(&path) {
+            log::warn!("Failed to remove thumbnail {}: {e}", path.display());
+        }
+    }
+}
+
+/// Creates (or reuses) a thumbnail no larger than `max_dimension` pixels on
+/// either side (clamped to 16-1024) and returns its path. Pass the path to
+/// `convertFileSrc` to display it. Supports PNG, JPEG, GIF, WebP and BMP.
+#[tauri::command]
+#[specta::specta]
+pub async fn generate_thumbnail(
+    app: AppHandle,
+    path: String,
+    max_dimension: u32,
+) -> Result<String, FileError> {
+    let source = resolve_scoped_path(&app, &path)?;
+    if !source.is_file() {
+        return Err(FileError::InvalidPath {
+            message: format!("Not a file: {path}"),
+        });
+    }
+    let dir = thumbnail_dir(&app)?;
+    let max_dimension = max_dimension.clamp(MIN_DIMENSION, MAX_DIMENSION);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let key = thumbnail_key(&source, max_dimension).map_err(io_error)?;
+        if let Some(existing) = find_thumbnail(&dir, &key) {
+            return Ok(existing);
+        }
+
+        let (bytes, format) =
+            render_thumbnail(&source, max_dimension).map_err(|e| FileError::InvalidImage {
+                path: path.clone(),
+                message: e.to_string(),
+            })?;
+        let ext = if format == ImageFormat::Png {
+            "png"
+        } else {
+            "jpg"
+        };
+        let thumbnail = dir.join(format!("{key}.{ext}"));
+
+        // Written atomically so a concurrent request never sees a partial file
+        std::fs::create_dir_all(&dir).map_err(io_error)?;
+        write_atomic(&thumbnail, &bytes).map_err(io_error)?;
+
+        log::debug!("Created thumbnail for {}", source.display());
+        prune_thumbnails(&dir);
+        Ok(thumbnail)
+    })
+    .await
+    .map_err(|e| FileError::IoError {
+        message: format!("Thumbnail task failed: {e}"),
+    })?
+    .map(|thumbnail| thumbnail.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage, Rgba, RgbaImage};
+
+    #[test]
+    fn test_render_thumbnail() {
+        let dir = std::env::temp_dir().join(format!("thumbnail-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let photo = dir.join("photo.png");
+        RgbImage::from_pixel(400, 200, Rgb([200, 100, 50]))
+            .save(&photo)
+            .unwrap();
+        let (bytes, format) = render_thumbnail(&photo, 100).unwrap();
+        assert_eq!(format, ImageFormat::Jpeg);
+        let thumbnail = image::load_from_memory(&bytes).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (100, 50));
+
+        // Transparency is kept, and small images aren't upscaled
+        let icon = dir.join("icon.png");
+        RgbaImage::from_pixel(32, 32, Rgba([0, 0, 0, 0]))
+            .save(&icon)
+            .unwrap();
+        let (bytes, format) = render_thumbnail(&icon, 100).unwrap();
+        assert_eq!(format, ImageFormat::Png);
+        let thumbnail = image::load_from_memory(&bytes).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (32, 32));
+
+        let text = dir.join("notes.png");
+        std::fs::write(&text, "not an image").unwrap();
+        assert!(render_thumbnail(&text, 100).is_err());
+
+        // The key changes with the requested size
+        assert_ne!(
+            thumbnail_key(&photo, 100).unwrap(),
+            thumbnail_key(&photo, 200).unwrap()
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,249 @@
+//! Optional system tray (menu bar) icon.
+//!
+//! Built from a `TrayConfig` during app setup when `SHOW_TRAY_ICON` is true.
+//! Tray menu items are forwarded to the main window as `menu-item-selected`
+//! events and dispatched by the same handlers as the app menu (`src/lib/menu.ts`),
+//! so an item with id `preferences` behaves exactly like the app menu's.
+//! The frontend replaces the menu with translated labels via `set_tray_menu`.
+
+use tauri::image::Image;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Wry};
+use tauri_specta::Event;
+
+use super::quick_pane::{toggle_quick_pane_by_label, QUICK_PANE_LABEL};
+use super::window::MAIN_WINDOW_LABEL;
+use crate::types::{AppError, MenuEntrySpec, MenuItemSelected};
+
+/// Show a tray icon. Customize it in `lib.rs` where `init_tray` is called.
+pub const SHOW_TRAY_ICON: bool = false;
+
+/// ID of the tray icon created by `init_tray`
+pub const TRAY_ID: &str = "main";
+
+/// Prefix for tray menu item IDs. Menu events are delivered to every menu
+/// handler, so this keeps tray items apart from app menu items with the same action.
+const TRAY_ITEM_PREFIX: &str = "tray:";
+
+/// An entry in the tray menu.
+#[derive(Debug, Clone)]
+pub enum TrayMenuEntry {
+    /// A clickable item; `id` is the app menu action it triggers (e.g. `preferences`)
+    Item {
+        id: String,
+        text: String,
+    },
+    Separator,
+}
+
+impl TrayMenuEntry {
+    pub fn item(id: impl Into<String>, text: impl Into<String>) -> Self {
+        Self::Item {
+            id: id.into(),
+            text: text.into(),
+        }
+    }
+}
+
+/// Describes the tray icon to create with `init_tray`.
+#[derive(Debug, Clone)]
+pub struct TrayConfig {
+    pub tooltip: Option<String>,
+    /// Text shown next to the icon (macOS menu bar only)
+    pub title: Option<String>,
+    /// Icon image (`None` uses the app icon)
+    pub icon: Option<Image<'static>>,
+    /// Let macOS tint the icon to match the menu bar. Use with a monochrome icon.
+    pub icon_as_template: bool,
+    pub menu: Vec<TrayMenuEntry>,
+    /// Left-click toggles the quick pane instead of opening the menu
+    pub left_click_toggles_quick_pane: bool,
+}
+
+impl TrayConfig {
+    /// Creates a config with the app icon and a Preferences/Quit menu.
+    pub fn new() -> Self {
+        Self {
+            tooltip: None,
+            title: None,
+            icon: None,
+            icon_as_template: false,
+            menu: vec![
+                TrayMenuEntry::item("preferences", "Preferences…"),
+                TrayMenuEntry::Separator,
+                TrayMenuEntry::item("quit", "Quit"),
+            ],
+            left_click_toggles_quick_pane: true,
+        }
+    }
+
+    /// Sets the hover tooltip.
+    pub fn tooltip(mut self, tooltip: impl Into<String>) -> Self {
+        self.tooltip = Some(tooltip.into());
+        self
+    }
+
+    /// Sets the text shown next to the icon (macOS only).
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets the icon, e.g. `tauri::include_image!("icons/tray-template.png")`.
+    /// Template icons are tinted by macOS to match the menu bar.
+    pub fn icon(mut self, icon: Image<'static>, as_template: bool) -> Self {
+        self.icon = Some(icon);
+        self.icon_as_template = as_template;
+        self
+    }
+
+    /// Replaces the tray menu.
+    pub fn menu(mut self, menu: Vec<TrayMenuEntry>) -> Self {
+        self.menu = menu;
+        self
+    }
+
+    /// Sets whether left-click toggles the quick pane (true) or opens the menu (false).
+    pub fn left_click_toggles_quick_pane(mut self, toggles: bool) -> Self {
+        self.left_click_toggles_quick_pane = toggles;
+        self
+    }
+}
+
+impl Default for TrayConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builds the tray menu, prefixing item ids with `TRAY_ITEM_PREFIX`.
+fn build_tray_menu(app: &AppHandle, entries: &[TrayMenuEntry]) -> Result<Menu<Wry>, AppError> {
+    let menu = Menu::new(app)
+        .map_err(|e| AppError::internal(format!("Failed to create tray menu: {e}")))?;
+    for entry in entries {
+        match entry {
+            TrayMenuEntry::Item { id, text } => {
+                let item = MenuItem::with_id(
+                    app,
+                    format!("{TRAY_ITEM_PREFIX}{id}"),
+                    text,
+                    true,
+                    None::<&str>,
+                )
+                .map_err(|e| AppError::internal(format!("Failed to create tray menu item: {e}")))?;
+                menu.append(&item)
+            }
+            TrayMenuEntry::Separator => {
+                let separator = PredefinedMenuItem::separator(app).map_err(|e| {
+                    AppError::internal(format!("Failed to create tray menu separator: {e}"))
+                })?;
+                menu.append(&separator)
+            }
+        }
+        .map_err(|e| AppError::internal(format!("Failed to build tray menu: {e}")))?;
+    }
+    Ok(menu)
+}
+
+/// Creates the tray icon. Called during app setup.
+pub fn init_tray(app: &AppHandle, config: TrayConfig) -> Result<(), AppError> {
+    let menu = build_tray_menu(app, &config.menu)?;
+
+    let toggles_quick_pane = config.left_click_toggles_quick_pane;
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&menu)
+        .show_menu_on_left_click(!toggles_quick_pane)
+        .on_menu_event(|app, event| {
+            let Some(id) = event.id().as_ref().strip_prefix(TRAY_ITEM_PREFIX) else {
+                return;
+            };
+            log::debug!("Tray menu item selected: {id}");
+            let selected = MenuItemSelected { id: id.to_string() };
+            if let Err(e) = selected.emit_to(app, MAIN_WINDOW_LABEL) {
+                log::error!("Failed to emit menu-item-selected: {e}");
+            }
+        })
+        .on_tray_icon_event(move |tray, event| {
+            if !toggles_quick_pane {
+                return;
+            }
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                if let Err(e) = toggle_quick_pane_by_label(tray.app_handle(), QUICK_PANE_LABEL) {
+                    log::error!("Failed to toggle quick pane from tray: {e}");
+                }
+            }
+        });
+
+    if let Some(icon) = config.icon.or_else(|| app.default_window_icon().cloned()) {
+        builder = builder.icon(icon).icon_as_template(config.icon_as_template);
+    }
+    if let Some(tooltip) = &config.tooltip {
+        builder = builder.tooltip(tooltip);
+    }
+    if let Some(title) = &config.title {
+        builder = builder.title(title);
+    }
+
+    builder
+        .build(app)
+        .map_err(|e| AppError::internal(format!("Failed to create tray icon: {e}")))?;
+
+    log::info!("Tray icon created");
+    Ok(())
+}
+
+fn get_tray(app: &AppHandle) -> Result<tauri::tray::TrayIcon, AppError> {
+    app.tray_by_id(TRAY_ID)
+        .ok_or_else(|| AppError::not_found("Tray icon is not enabled"))
+}
+
+/// Sets the text shown next to the tray icon, e.g. a timer or unread count.
+/// macOS only; ignored elsewhere. Pass None to clear it.
+#[tauri::command]
+#[specta::specta]
+pub fn set_tray_title(app: AppHandle, title: Option<String>) -> Result<(), AppError> {
+    get_tray(&app)?
+        .set_title(title)
+        .map_err(|e| AppError::internal(format!("Failed to set tray title: {e}")))
+}
+
+/// Replaces the tray menu, e.g. with translated labels from the frontend.
+/// Items and separators are supported. Does nothing if the tray icon isn't enabled.
+#[tauri::command]
+#[specta::specta]
+pub fn set_tray_menu(app: AppHandle, items: Vec<MenuEntrySpec>) -> Result<(), AppError> {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return Ok(());
+    };
+
+    let entries: Vec<TrayMenuEntry> = items
+        .into_iter()
+        .filter_map(|entry| match entry {
+            MenuEntrySpec::Item { id, text, .. } => Some(TrayMenuEntry::Item { id, text }),
+            MenuEntrySpec::Separator => Some(TrayMenuEntry::Separator),
+            other => {
+                log::warn!("Unsupported tray menu entry skipped: {other:?}");
+                None
+            }
+        })
+        .collect();
+
+    let menu = build_tray_menu(&app, &entries)?;
+    tray.set_menu(Some(menu))
+        .map_err(|e| AppError::internal(format!("Failed to set tray menu: {e}")))
+}
+
+/// Sets the tray icon's hover tooltip. Pass None to clear it.
+#[tauri::command]
+#[specta::specta]
+pub fn set_tray_tooltip(app: AppHandle, tooltip: Option<String>) -> Result<(), AppError> {
+    get_tray(&app)?
+        .set_tooltip(tooltip)
+        .map_err(|e| AppError::internal(format!("Failed to set tray tooltip: {e}")))
+}
@@ -0,0 +1,253 @@
+//! Undo/redo history shared by all windows.
+//!
+//! Each document (any string id) has an undo and a redo stack of entries:
+//! opaque JSON describing an edit, with enough detail for the frontend to
+//! reverse and reapply it. The frontend pushes an entry after each edit, and
+//! `undo`/`redo` hand back the entry to apply. Keeping the stacks here rather
+//! than in each webview gives every window the same history.
+//! `undo-state-changed` is emitted after each change so all windows can
+//! update their Undo/Redo menu items and buttons.
+//!
+//! History lives in memory unless `PERSIST_UNDO_HISTORY` is on, in which
+//! case it's kept in `app.db` (see `db::undo`) and survives restarts.
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use tauri::{AppHandle, Manager};
+use tauri_specta::Event;
+
+use crate::db::{undo as undo_entries, Database};
+use crate::types::{validate_string_input, AppError, UndoDepth, UndoStateChanged};
+
+/// Keep undo history in the database so it survives restarts.
+pub const PERSIST_UNDO_HISTORY: bool = false;
+
+/// Entries kept per document; the oldest are dropped first.
+const MAX_UNDO_DEPTH: usize = 100;
+/// Maximum size of an entry, serialized as JSON.
+const MAX_ENTRY_SIZE: usize = 64 * 1024;
+const MAX_DOCUMENT_ID_LENGTH: usize = 256;
+
+/// A document's undo and redo stacks of JSON entries, oldest first.
+#[derive(Debug, Default)]
+struct UndoHistory {
+    undo: Vec<String>,
+    redo: Vec<String>,
+    /// Bumped on every change, so callers can tell whether to persist.
+    revision: u64,
+}
+
+impl UndoHistory {
+    /// Records a new edit. Anything that could be redone is discarded.
+    fn push(&mut self, entry: String) {
+        self.redo.clear();
+        self.undo.push(entry);
+        if self.undo.len() > MAX_UNDO_DEPTH {
+            self.undo.remove(0);
+        }
+        self.revision += 1;
+    }
+
+    /// Moves the newest edit to the redo stack and returns it.
+    fn undo(&mut self) -> Option<String> {
+        let entry = self.undo.pop()?;
+        self.redo.push(entry.clone());
+        self.revision += 1;
+        Some(entry)
+    }
+
+    /// Moves the most recently undone edit back and returns it.
+    fn redo(&mut self) -> Option<String> {
+        let entry = self.redo.pop()?;
+        self.undo.push(entry.clone());
+        self.revision += 1;
+        Some(entry)
+    }
+
+    fn clear(&mut self) {
+        if !self.undo.is_empty() || !self.redo.is_empty() {
+            self.undo.clear();
+            self.redo.clear();
+            self.revision += 1;
+        }
+    }
+
+    fn depth(&self) -> UndoDepth {
+        UndoDepth {
+            undo_depth: u32::try_from(self.undo.len()).unwrap_or(u32::MAX),
+            redo_depth: u32::try_from(self.redo.len()).unwrap_or(u32::MAX),
+        }
+    }
+}
+
+/// Histories by document id, loaded from the database on first use when
+/// persistence is on.
+static HISTORIES: LazyLock<Mutex<HashMap<String, UndoHistory>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn validate_document_id(document_id: &str) -> Result<(), AppError> {
+    if document_id.is_empty() {
+        return Err(AppError::validation("Document id cannot be empty"));
+    }
+    validate_string_input(document_id, MAX_DOCUMENT_ID_LENGTH, "Document id")
+        .map_err(AppError::validation)
+}
+
+fn load_history(app: &AppHandle, document_id: &str) -> Result<UndoHistory, AppError> {
+    if !PERSIST_UNDO_HISTORY {
+        return Ok(UndoHistory::default());
+    }
+    let (undo, redo) = app
+        .state::<Database>()
+        .with_connection(|conn| undo_entries::load(conn, document_id))?;
+    Ok(UndoHistory {
+        undo,
+        redo,
+        revision: 0,
+    })
+}
+
+/// Runs `f` on a document's history. If `f` changes it, the change is
+/// persisted (when enabled) and `undo-state-changed` is emitted.
+fn with_history<T>(
+    app: &AppHandle,
+    document_id: &str,
+    f: impl FnOnce(&mut UndoHistory) -> T,
+) -> Result<(T, UndoDepth), AppError> {
+    validate_document_id(document_id)?;
+    let mut histories = HISTORIES
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock undo history: {e}")))?;
+    if !histories.contains_key(document_id) {
+        let history = load_history(app, document_id)?;
+        histories.insert(document_id.to_string(), history);
+    }
+    let Some(history) = histories.get_mut(document_id) else {
+        return Err(AppError::internal("Failed to load undo history"));
+    };
+
+    let revision = history.revision;
+    let result = f(history);
+    let depth = history.depth();
+
+    if history.revision != revision {
+        if PERSIST_UNDO_HISTORY {
+            let (undo, redo) = (&history.undo, &history.redo);
+            app.state::<Database>()
+                .with_connection(|conn| undo_entries::save(conn, document_id, undo, redo))?;
+        }
+        let event = UndoStateChanged {
+            document_id: document_id.to_string(),
+            undo_depth: depth.undo_depth,
+            redo_depth: depth.redo_depth,
+        };
+        if let Err(e) = event.emit(app) {
+            log::warn!("Failed to emit {} event: {e}", UndoStateChanged::NAME);
+        }
+    }
+    Ok((result, depth))
+}
+
+fn parse_entry(json: &str) -> Result<Value, AppError> {
+    serde_json::from_str(json).map_err(|e| AppError::Serialization {
+        message: format!("Stored undo entry is invalid: {e}"),
+    })
+}
+
+/// Records an edit to `document_id`. `entry` is whatever the frontend needs
+/// to reverse and reapply the edit (max 64KB of JSON). Clears the redo stack
+/// and keeps the newest 100 entries.
+#[tauri::command]
+#[specta::specta]
+pub fn push_undo_entry(
+    app: AppHandle,
+    document_id: String,
+    entry: Value,
+) -> Result<UndoDepth, AppError> {
+    let json = serde_json::to_string(&entry).map_err(|e| AppError::Serialization {
+        message: format!("Failed to serialize entry: {e}"),
+    })?;
+    if json.len() > MAX_ENTRY_SIZE {
+        return Err(AppError::validation(format!(
+            "Undo entry too large (max {MAX_ENTRY_SIZE} bytes)"
+        )));
+    }
+    let (_, depth) = with_history(&app, &document_id, |history| history.push(json))?;
+    Ok(depth)
+}
+
+/// Undoes the newest edit to `document_id`, returning its entry for the
+/// frontend to reverse, or null if there's nothing to undo.
+#[tauri::command]
+#[specta::specta]
+pub fn undo(app: AppHandle, document_id: String) -> Result<Option<Value>, AppError> {
+    let (entry, _) = with_history(&app, &document_id, UndoHistory::undo)?;
+    entry.as_deref().map(parse_entry).transpose()
+}
+
+/// Redoes the most recently undone edit to `document_id`, returning its
+/// entry for the frontend to reapply, or null if there's nothing to redo.
+#[tauri::command]
+#[specta::specta]
+pub fn redo(app: AppHandle, document_id: String) -> Result<Option<Value>, AppError> {
+    let (entry, _) = with_history(&app, &document_id, UndoHistory::redo)?;
+    entry.as_deref().map(parse_entry).transpose()
+}
+
+/// Gets how many edits to `document_id` can be undone and redone.
+#[tauri::command]
+#[specta::specta]
+pub fn get_undo_depth(app: AppHandle, document_id: String) -> Result<UndoDepth, AppError> {
+    let (_, depth) = with_history(&app, &document_id, |_| ())?;
+    Ok(depth)
+}
+
+/// Forgets all undo history for `document_id`, e.g. when it's closed or
+/// deleted.
+#[tauri::command]
+#[specta::specta]
+pub fn clear_undo_history(app: AppHandle, document_id: String) -> Result<(), AppError> {
+    with_history(&app, &document_id, UndoHistory::clear)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undo_redo() {
+        let mut history = UndoHistory::default();
+        assert_eq!(history.undo(), None);
+
+        history.push("1".to_string());
+        history.push("2".to_string());
+        assert_eq!(history.undo().as_deref(), Some("2"));
+        assert_eq!(history.undo().as_deref(), Some("1"));
+        assert_eq!(history.undo(), None);
+        assert_eq!(history.redo().as_deref(), Some("1"));
+        assert_eq!(
+            history.depth(),
+            UndoDepth {
+                undo_depth: 1,
+                redo_depth: 1
+            }
+        );
+
+        // A new edit discards what could be redone
+        history.push("3".to_string());
+        assert_eq!(history.redo(), None);
+        assert_eq!(history.undo.len(), 2);
+    }
+
+    #[test]
+    fn test_push_drops_oldest_past_limit() {
+        let mut history = UndoHistory::default();
+        for i in 0..=MAX_UNDO_DEPTH {
+            history.push(i.to_string());
+        }
+        assert_eq!(history.undo.len(), MAX_UNDO_DEPTH);
+        assert_eq!(history.undo[0], "1");
+    }
+}
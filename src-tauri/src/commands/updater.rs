@@ -0,0 +1,686 @@
+//! In-app updates.
+//!
+//! Updates are checked from Rust rather than the updater plugin's JS API so
+//! the endpoint can follow the user's release channel: each channel has its
+//! own manifest, built from `UPDATE_ENDPOINT_TEMPLATE`. The channel is saved
+//! in preferences, so downstream apps can ship beta builds to users who opt
+//! in without changing the updater wiring.
+//!
+//! A background scheduler also checks on an interval and after waking from
+//! sleep, announcing updates with `update-available` at most once a day.
+
+use serde::{Deserialize, Serialize};
+#[cfg(desktop)]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(desktop)]
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+#[cfg(desktop)]
+use std::path::{Path, PathBuf};
+#[cfg(desktop)]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(desktop)]
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+#[cfg(desktop)]
+use std::time::{Instant, UNIX_EPOCH};
+use tauri::AppHandle;
+#[cfg(desktop)]
+use tauri_specta::Event;
+
+#[cfg(desktop)]
+use super::files::write_atomic;
+use super::preferences::{load_preferences, save_preferences};
+#[cfg(desktop)]
+use super::preferences::{load_update_channel, load_update_check_interval_hours};
+#[cfg(desktop)]
+use super::window::MAIN_WINDOW_LABEL;
+use crate::types::{AppError, ReleaseNote, UpdateChannel, UpdateInfo};
+#[cfg(desktop)]
+use crate::types::{UpdateAvailable, UpdateProgress};
+
+/// Update manifest URL. `{{channel}}` is replaced with the channel's manifest
+/// name, so the stable channel reads the `latest.json` published by the
+/// release workflow. The updater plugin also fills in `{{target}}`, `{{arch}}`
+/// and `{{current_version}}`, for apps serving manifests from their own server.
+#[cfg_attr(mobile, allow(dead_code))]
+const UPDATE_ENDPOINT_TEMPLATE: &str =
+    "https://github.com/YOUR_USERNAME/YOUR_REPO/releases/latest/download/{{channel}}.json";
+
+/// Minimum time between `Downloading` progress events.
+#[cfg(desktop)]
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Update found by the last check, installed by `download_and_install_update`.
+#[cfg(desktop)]
+static PENDING_UPDATE: Mutex<Option<tauri_plugin_updater::Update>> = Mutex::new(None);
+
+/// Set while `download_and_install_update` is running.
+#[cfg(desktop)]
+static INSTALLING: AtomicBool = AtomicBool::new(false);
+
+/// Set once an update is installed and waiting for a restart.
+#[cfg(desktop)]
+static UPDATE_READY: AtomicBool = AtomicBool::new(false);
+
+/// Manifest file name (without extension) for a release channel.
+#[cfg_attr(mobile, allow(dead_code))]
+fn manifest_name(channel: UpdateChannel) -> &'static str {
+    match channel {
+        UpdateChannel::Stable => "latest",
+        UpdateChannel::Beta => "beta",
+        UpdateChannel::Nightly => "nightly",
+    }
+}
+
+/// Update manifest URL for a release channel.
+#[cfg_attr(mobile, allow(dead_code))]
+fn endpoint_for_channel(channel: UpdateChannel) -> String {
+    UPDATE_ENDPOINT_TEMPLATE.replace("{{channel}}", manifest_name(channel))
+}
+
+/// Describes an update for the frontend.
+#[cfg(desktop)]
+fn update_info(update: &tauri_plugin_updater::Update, channel: UpdateChannel) -> UpdateInfo {
+    UpdateInfo {
+        version: update.version.clone(),
+        current_version: update.current_version.clone(),
+        channel,
+        notes: update.body.clone(),
+        date: pub_date(update),
+    }
+}
+
+/// Publication date from an update's manifest (RFC 3339).
+#[cfg(desktop)]
+fn pub_date(update: &tauri_plugin_updater::Update) -> Option<String> {
+    update
+        .raw_json
+        .get("pub_date")
+        .and_then(|date| date.as_str())
+        .map(String::from)
+}
+
+/// Byte count for progress events, which use u32 like other sizes sent to
+/// the frontend (4 GiB is far beyond any update).
+#[cfg(desktop)]
+fn byte_count(bytes: u64) -> u32 {
+    u32::try_from(bytes).unwrap_or(u32::MAX)
+}
+
+/// Release notes from the manifest of the update found by the last check,
+/// for `get_release_notes` to fall back on when offline.
+pub fn pending_release_note() -> Option<ReleaseNote> {
+    #[cfg(desktop)]
+    {
+        let pending = PENDING_UPDATE.lock().ok()?;
+        let update = pending.as_ref()?;
+        Some(ReleaseNote {
+            version: update.version.clone(),
+            date: pub_date(update),
+            notes: update.body.clone()?,
+        })
+    }
+
+    #[cfg(mobile)]
+    {
+        None
+    }
+}
+
+/// Reports update progress to every window.
+#[cfg(desktop)]
+fn emit_progress(app: &AppHandle, progress: UpdateProgress) {
+    if let Err(e) = progress.emit(app) {
+        log::warn!("Failed to emit update progress: {e}");
+    }
+}
+
+/// Builds an updater that checks the given channel's manifest.
+#[cfg(desktop)]
+fn channel_updater(
+    app: &AppHandle,
+    channel: UpdateChannel,
+) -> Result<tauri_plugin_updater::Updater, AppError> {
+    use tauri_plugin_updater::UpdaterExt;
+
+    let endpoint: tauri::Url = endpoint_for_channel(channel)
+        .parse()
+        .map_err(|e| AppError::validation(format!("Invalid update endpoint: {e}")))?;
+
+    app.updater_builder()
+        .endpoints(vec![endpoint])
+        .and_then(|builder| builder.build())
+        .map_err(|e| AppError::internal(format!("Failed to create updater: {e}")))
+}
+
+/// Checks the saved release channel for a newer version.
+/// The update is remembered so `download_and_install_update` can install it.
+#[tauri::command]
+#[specta::specta]
+pub async fn check_for_update(app: AppHandle) -> Result<Option<UpdateInfo>, AppError> {
+    #[cfg(desktop)]
+    {
+        let channel = load_update_channel(&app);
+        log::info!("Checking for updates on the {channel:?} channel");
+
+        let update = channel_updater(&app, channel)?.check().await.map_err(|e| {
+            log::warn!("Update check failed: {e}");
+            AppError::internal(format!("Failed to check for updates: {e}"))
+        })?;
+
+        let info = update.as_ref().map(|update| update_info(update, channel));
+        match &info {
+            Some(info) => log::info!("Update available: {}", info.version),
+            None => log::info!("No update available"),
+        }
+
+        if let Ok(mut pending) = PENDING_UPDATE.lock() {
+            *pending = update;
+        }
+        Ok(info)
+    }
+
+    #[cfg(mobile)]
+    {
+        let _ = app;
+        Err(AppError::internal("Updates are not supported on mobile"))
+    }
+}
+
+/// Downloads and installs an update, reporting `update-progress` events as
+/// it goes. Installs the update found by the last `check_for_update`, or
+/// checks first if there isn't one. Returns None if the app is up to date.
+///
+/// On Windows the installer takes over and the app exits once the download
+/// finishes, so `ReadyToRestart` is only reported on macOS and Linux.
+#[tauri::command]
+#[specta::specta]
+pub async fn download_and_install_update(app: AppHandle) -> Result<Option<UpdateInfo>, AppError> {
+    #[cfg(desktop)]
+    {
+        if INSTALLING.swap(true, Ordering::SeqCst) {
+            return Err(AppError::validation("An update is already being installed"));
+        }
+        let result = install_update(&app).await;
+        INSTALLING.store(false, Ordering::SeqCst);
+        result
+    }
+
+    #[cfg(mobile)]
+    {
+        let _ = app;
+        Err(AppError::internal("Updates are not supported on mobile"))
+    }
+}
+
+#[cfg(desktop)]
+async fn install_update(app: &AppHandle) -> Result<Option<UpdateInfo>, AppError> {
+    let pending = PENDING_UPDATE
+        .lock()
+        .ok()
+        .and_then(|pending| pending.clone());
+    let update = match pending {
+        Some(update) => update,
+        None => {
+            emit_progress(app, UpdateProgress::Checking);
+            match check_for_update(app.clone()).await? {
+                Some(_) => PENDING_UPDATE
+                    .lock()
+                    .ok()
+                    .and_then(|pending| pending.clone())
+                    .ok_or_else(|| AppError::not_found("The update is no longer available"))?,
+                None => return Ok(None),
+            }
+        }
+    };
+
+    log::info!("Downloading update {}", update.version);
+    let mut downloaded: u64 = 0;
+    let mut last_reported: Option<Instant> = None;
+    update
+        .download_and_install(
+            |chunk_length, total| {
+                downloaded += chunk_length as u64;
+                let finished = total == Some(downloaded);
+                if finished || last_reported.is_none_or(|at| at.elapsed() >= PROGRESS_INTERVAL) {
+                    emit_progress(
+                        app,
+                        UpdateProgress::Downloading {
+                            downloaded: byte_count(downloaded),
+                            total: total.map(byte_count),
+                        },
+                    );
+                    last_reported = Some(Instant::now());
+                }
+            },
+            || {
+                log::info!("Download complete, installing update");
+                emit_progress(app, UpdateProgress::Installing);
+            },
+        )
+        .await
+        .map_err(|e| {
+            log::error!("Failed to install update: {e}");
+            AppError::internal(format!("Failed to install update: {e}"))
+        })?;
+
+    log::info!("Update {} installed, ready to restart", update.version);
+    UPDATE_READY.store(true, Ordering::SeqCst);
+    if let Ok(mut pending) = PENDING_UPDATE.lock() {
+        *pending = None;
+    }
+    emit_progress(
+        app,
+        UpdateProgress::ReadyToRestart {
+            version: update.version.clone(),
+        },
+    );
+
+    Ok(Some(update_info(&update, load_update_channel(app))))
+}
+
+/// Relaunches the app into an installed update. Windows with unsaved changes
+/// are asked to confirm first, as when quitting.
+#[tauri::command]
+#[specta::specta]
+pub fn restart_to_update(app: AppHandle) -> Result<(), AppError> {
+    #[cfg(desktop)]
+    {
+        if !UPDATE_READY.load(Ordering::SeqCst) {
+            return Err(AppError::validation("No update has been installed"));
+        }
+        log::info!("Restarting to finish updating");
+        super::window::request_app_restart(&app);
+        Ok(())
+    }
+
+    #[cfg(mobile)]
+    {
+        let _ = app;
+        Err(AppError::internal("Updates are not supported on mobile"))
+    }
+}
+
+/// Switches the release channel used for update checks and saves it to
+/// preferences. Moving to a less frequent channel doesn't downgrade; the
+/// next update comes when that channel has a newer version.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_update_channel(app: AppHandle, channel: UpdateChannel) -> Result<(), AppError> {
+    let mut preferences = load_preferences(app.clone()).await?;
+    if preferences.update_channel == channel {
+        return Ok(());
+    }
+
+    preferences.update_channel = channel;
+    save_preferences(app, preferences).await?;
+
+    // An update found on the previous channel shouldn't be installed from this one
+    #[cfg(desktop)]
+    if let Ok(mut pending) = PENDING_UPDATE.lock() {
+        *pending = None;
+    }
+
+    log::info!("Update channel set to {channel:?}");
+    Ok(())
+}
+
+// ============================================================================
+// Side-loaded Updates
+// ============================================================================
+
+/// Installs an update bundle downloaded by hand, for machines that can't reach
+/// the update server (behind a proxy, or air-gapped). Takes the updater bundle
+/// from a release (`.app.tar.gz`, `-setup.exe`, `.msi`, `.AppImage` or `.deb`)
+/// with its `.sig` file alongside, and only installs it if the signature
+/// matches the updater's public key. Any version is accepted, so this can also
+/// be used to roll back.
+///
+/// As with `download_and_install_update`, the app exits on Windows once the
+/// installer starts; elsewhere, call `restart_to_update` afterwards.
+#[tauri::command]
+#[specta::specta]
+pub async fn install_update_from_file(app: AppHandle, path: String) -> Result<(), AppError> {
+    #[cfg(desktop)]
+    {
+        if INSTALLING.swap(true, Ordering::SeqCst) {
+            return Err(AppError::validation("An update is already being installed"));
+        }
+        let result = install_local_update(&app, Path::new(&path)).await;
+        INSTALLING.store(false, Ordering::SeqCst);
+        result
+    }
+
+    #[cfg(mobile)]
+    {
+        let _ = (app, path);
+        Err(AppError::internal("Updates are not supported on mobile"))
+    }
+}
+
+/// The updater plugin only installs updates it has checked for and downloaded
+/// itself, so the bundle is served to it from a loopback server. That keeps
+/// signature verification and the platform-specific install in the plugin.
+#[cfg(desktop)]
+async fn install_local_update(app: &AppHandle, path: &Path) -> Result<(), AppError> {
+    use tauri_plugin_updater::UpdaterExt;
+
+    let mut signature_path = path.as_os_str().to_owned();
+    signature_path.push(".sig");
+    let signature_path = PathBuf::from(signature_path);
+
+    let bundle = std::fs::read(path).map_err(|e| AppError::Io {
+        message: format!("Failed to read update file: {e}"),
+    })?;
+    let signature = std::fs::read_to_string(&signature_path).map_err(|e| AppError::Io {
+        message: format!(
+            "Failed to read update signature {}: {e}",
+            signature_path.display()
+        ),
+    })?;
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .map_err(|e| AppError::internal(format!("Failed to start local update server: {e}")))?;
+    let address = listener
+        .local_addr()
+        .map_err(|e| AppError::internal(format!("Failed to start local update server: {e}")))?;
+    let endpoint: tauri::Url = format!("http://{address}/update.json")
+        .parse()
+        .map_err(|e| AppError::validation(format!("Invalid update endpoint: {e}")))?;
+    // The version is only used for comparison, which is skipped below
+    let manifest = serde_json::json!({
+        "version": app.package_info().version.to_string(),
+        "url": format!("http://{address}/update"),
+        "signature": signature.trim(),
+    });
+
+    log::info!("Installing update from {}", path.display());
+    let stop = Arc::new(AtomicBool::new(false));
+    {
+        let stop = stop.clone();
+        let manifest = manifest.to_string().into_bytes();
+        std::thread::spawn(move || serve_local_update(listener, &manifest, &bundle, &stop));
+    }
+
+    let result = async {
+        let update = app
+            .updater_builder()
+            .endpoints(vec![endpoint])
+            .map(|builder| builder.no_proxy().version_comparator(|_, _| true))
+            .and_then(|builder| builder.build())
+            .map_err(|e| AppError::internal(format!("Failed to create updater: {e}")))?
+            .check()
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to read update file: {e}")))?
+            .ok_or_else(|| AppError::internal("Failed to read update file"))?;
+
+        update
+            .download_and_install(|_, _| {}, || emit_progress(app, UpdateProgress::Installing))
+            .await
+            .map_err(|e| AppError::internal(format!("Failed to install update: {e}")))
+    }
+    .await;
+
+    // Wake the server so it sees the stop flag
+    stop.store(true, Ordering::SeqCst);
+    let _ = TcpStream::connect(address);
+
+    if let Err(e) = &result {
+        log::error!("{e}");
+        return result;
+    }
+
+    log::info!("Update installed from file, ready to restart");
+    UPDATE_READY.store(true, Ordering::SeqCst);
+    if let Ok(mut pending) = PENDING_UPDATE.lock() {
+        *pending = None;
+    }
+    Ok(())
+}
+
+/// Answers requests for a path ending in `.json` with the manifest and any
+/// other path with the bundle, until `stop` is set.
+#[cfg(desktop)]
+fn serve_local_update(listener: TcpListener, manifest: &[u8], bundle: &[u8], stop: &AtomicBool) {
+    for stream in listener.incoming() {
+        if stop.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(mut stream) = stream else {
+            continue;
+        };
+
+        // Read the whole request so closing the connection doesn't reset it
+        let mut request_line = String::new();
+        let mut reader = BufReader::new(&stream);
+        if reader.read_line(&mut request_line).is_err() {
+            continue;
+        }
+        let mut header = String::new();
+        while reader.read_line(&mut header).is_ok_and(|read| read > 2) {
+            header.clear();
+        }
+
+        let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+        let (content_type, body) = if path.ends_with(".json") {
+            ("application/json", manifest)
+        } else {
+            ("application/octet-stream", bundle)
+        };
+        let head = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            body.len()
+        );
+        if let Err(e) = stream
+            .write_all(head.as_bytes())
+            .and_then(|()| stream.write_all(body))
+        {
+            log::warn!("Failed to serve local update: {e}");
+        }
+    }
+}
+
+// ============================================================================
+// Scheduled Checks
+// ============================================================================
+
+/// How often the scheduler wakes to see whether a check is due.
+#[cfg_attr(mobile, allow(dead_code))]
+const SCHEDULER_TICK: Duration = Duration::from_secs(60);
+
+/// Delay before the first scheduled check, so it doesn't slow down launch.
+#[cfg(desktop)]
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(10);
+
+/// A gap between ticks this much longer than `SCHEDULER_TICK` means the
+/// computer was asleep (the thread's sleep doesn't count time suspended).
+#[cfg_attr(mobile, allow(dead_code))]
+const WAKE_THRESHOLD: Duration = Duration::from_secs(120);
+
+/// Scheduled checks prompt about an update at most this often.
+#[cfg_attr(mobile, allow(dead_code))]
+const PROMPT_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Persisted between launches so the once-a-day prompt limit survives restarts.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(mobile, allow(dead_code))]
+struct UpdatePromptState {
+    /// Unix time in seconds of the last `update-available` prompt
+    last_prompted_at: Option<u64>,
+}
+
+/// Gets the path to the update prompt state file.
+#[cfg(desktop)]
+fn get_prompt_state_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = crate::data_dir::app_data_dir(app)?;
+
+    std::fs::create_dir_all(&app_data_dir)
+        .map_err(|e| AppError::io(format!("Failed to create app data directory: {e}")))?;
+
+    Ok(app_data_dir.join("update-prompt.json"))
+}
+
+/// When the user was last prompted about an update, if ever.
+#[cfg(desktop)]
+fn load_last_prompted(app: &AppHandle) -> Option<SystemTime> {
+    let path = get_prompt_state_path(app).ok()?;
+    let contents = std::fs::read_to_string(path).ok()?;
+    let state: UpdatePromptState = serde_json::from_str(&contents)
+        .inspect_err(|e| log::warn!("Failed to parse update prompt state: {e}"))
+        .ok()?;
+    Some(UNIX_EPOCH + Duration::from_secs(state.last_prompted_at?))
+}
+
+/// Records a prompt. Uses atomic write (temp file + rename).
+#[cfg(desktop)]
+fn save_last_prompted(app: &AppHandle, at: SystemTime) -> Result<(), AppError> {
+    let path = get_prompt_state_path(app)?;
+    let state = UpdatePromptState {
+        last_prompted_at: at.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs()),
+    };
+    let json = serde_json::to_string_pretty(&state).map_err(|e| AppError::Serialization {
+        message: format!("Failed to serialize update prompt state: {e}"),
+    })?;
+
+    write_atomic(&path, json.as_bytes())
+        .map_err(|e| AppError::io(format!("Failed to save update prompt state: {e}")))
+}
+
+/// Time since `earlier`, or None if the clock has moved backwards.
+#[cfg_attr(mobile, allow(dead_code))]
+fn elapsed_since(earlier: SystemTime, now: SystemTime) -> Option<Duration> {
+    now.duration_since(earlier).ok()
+}
+
+/// Whether enough time has passed since the last scheduled check.
+#[cfg_attr(mobile, allow(dead_code))]
+fn check_due(last_check: Option<SystemTime>, now: SystemTime, interval: Duration) -> bool {
+    last_check.is_none_or(|last| elapsed_since(last, now).is_none_or(|e| e >= interval))
+}
+
+/// Whether a found update may be announced, given when the user was last prompted.
+#[cfg_attr(mobile, allow(dead_code))]
+fn prompt_allowed(last_prompted: Option<SystemTime>, now: SystemTime) -> bool {
+    last_prompted.is_none_or(|last| elapsed_since(last, now).is_none_or(|e| e >= PROMPT_INTERVAL))
+}
+
+/// Whether the wall clock jumped far enough between ticks that the computer
+/// must have been asleep.
+#[cfg_attr(mobile, allow(dead_code))]
+fn woke_from_sleep(last_tick: SystemTime, now: SystemTime) -> bool {
+    elapsed_since(last_tick, now).is_some_and(|gap| gap >= SCHEDULER_TICK + WAKE_THRESHOLD)
+}
+
+/// Starts checking for updates in the background, every
+/// `update_check_interval_hours` (read from preferences each time, so changes
+/// apply without a restart) and after waking from sleep. Found updates are
+/// announced with `update-available`, at most once a day.
+#[cfg(desktop)]
+pub fn start_update_scheduler(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(FIRST_CHECK_DELAY);
+
+        let mut last_check: Option<SystemTime> = None;
+        let mut last_tick = SystemTime::now();
+        loop {
+            let now = SystemTime::now();
+            let woke = woke_from_sleep(last_tick, now);
+            last_tick = now;
+
+            let interval_hours = load_update_check_interval_hours(&app);
+            let interval = Duration::from_secs(u64::from(interval_hours) * 60 * 60);
+            if interval_hours > 0 && (woke || check_due(last_check, now, interval)) {
+                if woke {
+                    log::info!("Woke from sleep, checking for updates");
+                }
+                last_check = Some(now);
+                tauri::async_runtime::block_on(scheduled_check(&app));
+            }
+
+            std::thread::sleep(SCHEDULER_TICK);
+        }
+    });
+}
+
+#[cfg(desktop)]
+async fn scheduled_check(app: &AppHandle) {
+    // Nothing to announce while an update is being installed or awaits a restart
+    if INSTALLING.load(Ordering::SeqCst) || UPDATE_READY.load(Ordering::SeqCst) {
+        return;
+    }
+
+    // Failures are logged by check_for_update; try again next time
+    let Ok(Some(update)) = check_for_update(app.clone()).await else {
+        return;
+    };
+
+    let now = SystemTime::now();
+    if !prompt_allowed(load_last_prompted(app), now) {
+        log::info!(
+            "Update {} available, already prompted in the last day",
+            update.version
+        );
+        return;
+    }
+
+    let event = UpdateAvailable { update };
+    if let Err(e) = event.emit_to(app, MAIN_WINDOW_LABEL) {
+        log::warn!("Failed to emit update-available: {e}");
+        return;
+    }
+    if let Err(e) = save_last_prompted(app, now) {
+        log::warn!("{e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_for_channel() {
+        assert_eq!(
+            endpoint_for_channel(UpdateChannel::Stable),
+            "https://github.com/YOUR_USERNAME/YOUR_REPO/releases/latest/download/latest.json"
+        );
+        assert_eq!(
+            endpoint_for_channel(UpdateChannel::Beta),
+            "https://github.com/YOUR_USERNAME/YOUR_REPO/releases/latest/download/beta.json"
+        );
+        assert!(endpoint_for_channel(UpdateChannel::Nightly).ends_with("/nightly.json"));
+    }
+
+    #[test]
+    fn test_check_due() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let hour = Duration::from_secs(60 * 60);
+
+        assert!(check_due(None, now, hour));
+        assert!(check_due(Some(now - hour), now, hour));
+        assert!(!check_due(Some(now - hour / 2), now, hour));
+        // Clock moved backwards
+        assert!(check_due(Some(now + hour), now, hour));
+    }
+
+    #[test]
+    fn test_prompt_allowed() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+        assert!(prompt_allowed(None, now));
+        assert!(prompt_allowed(Some(now - PROMPT_INTERVAL), now));
+        assert!(!prompt_allowed(
+            Some(now - PROMPT_INTERVAL + Duration::from_secs(1)),
+            now
+        ));
+    }
+
+    #[test]
+    fn test_woke_from_sleep() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+        assert!(!woke_from_sleep(now - SCHEDULER_TICK, now));
+        assert!(woke_from_sleep(now - Duration::from_secs(60 * 60), now));
+        assert!(!woke_from_sleep(now + SCHEDULER_TICK, now));
+    }
+}
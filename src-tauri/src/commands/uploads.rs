@@ -0,0 +1,419 @@
+//! File uploads from disk.
+//!
+//! `upload_file` sends a file as multipart/form-data, streaming it from disk
+//! in chunks, so large attachments never pass through the webview or sit in
+//! memory. Attempts that fail with a network error, 408, 429 or 5xx are
+//! retried with backoff.
+//!
+//! Servers that can resume an interrupted upload (tus, Google Cloud Storage,
+//! S3-style sessions) plug in through [`ResumableUpload`], so that a retry
+//! only sends the bytes the server doesn't have yet.
+
+use futures_util::future::{self, BoxFuture};
+use futures_util::{stream, Stream, StreamExt, TryStreamExt};
+use std::future::Future;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::ipc::Channel;
+use tauri::AppHandle;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+
+use crate::commands::files::resolve_scoped_path;
+use crate::commands::http::{self, invalid_request, request_error};
+use crate::types::{
+    validate_string_input, HttpError, HttpHeader, HttpResponse, HttpResponseType, UploadField,
+    UploadProgress,
+};
+
+const MAX_ATTEMPTS: u32 = 3;
+/// Delay before the first retry, doubled for each one after it.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+/// Longest `Retry-After` a server can ask for before we give up waiting.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Minimum time between progress messages.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Form field the file is sent as by `upload_file`.
+const FILE_FIELD: &str = "file";
+const MAX_FIELDS: usize = 100;
+const MAX_FIELD_NAME_LENGTH: usize = 256;
+const MAX_FIELD_VALUE_LENGTH: usize = 64 * 1024;
+
+/// A multipart/form-data upload, for Rust commands that call `upload` with
+/// credentials the frontend shouldn't see.
+#[derive(Debug, Clone)]
+pub struct UploadRequest {
+    pub url: String,
+    pub path: PathBuf,
+    /// Form field the file is sent as
+    pub file_field: String,
+    pub fields: Vec<UploadField>,
+    pub headers: Vec<HttpHeader>,
+}
+
+/// A resumable upload protocol, for `upload_resumable`. Implementations
+/// create the upload session beforehand and know its URL.
+pub trait ResumableUpload: Send + Sync {
+    /// How many bytes of the file the server already has, asked before each
+    /// retry. Return 0 to start over.
+    fn uploaded_bytes<'a>(
+        &'a self,
+        client: &'a reqwest::Client,
+    ) -> BoxFuture<'a, Result<u64, HttpError>>;
+
+    /// The request sending the file from `offset` of its `total` bytes, e.g.
+    /// a tus `PATCH` with `Upload-Offset`, or a `PUT` with `Content-Range`.
+    /// `upload_resumable` adds the body and its length.
+    fn request(&self, client: &reqwest::Client, offset: u64, total: u64)
+        -> reqwest::RequestBuilder;
+}
+
+/// Reports how much of a request body has been read, throttled to
+/// `PROGRESS_INTERVAL`.
+struct Progress {
+    channel: Option<Channel<UploadProgress>>,
+    uploaded: u64,
+    total: u64,
+    attempt: u32,
+    last_sent: Option<Instant>,
+}
+
+impl Progress {
+    fn advance(&mut self, bytes: usize) {
+        self.uploaded += bytes as u64;
+        let Some(channel) = &self.channel else {
+            return;
+        };
+        let finished = self.uploaded >= self.total;
+        if finished
+            || self
+                .last_sent
+                .is_none_or(|last| last.elapsed() >= PROGRESS_INTERVAL)
+        {
+            self.last_sent = Some(Instant::now());
+            let _ = channel.send(UploadProgress {
+                uploaded: self.uploaded as f64,
+                total: self.total as f64,
+                attempt: self.attempt,
+            });
+        }
+    }
+}
+
+/// Quotes a form field or file name the way browsers do.
+fn escape_quoted(value: &str) -> String {
+    value
+        .replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// The multipart/form-data framing around the file's contents: the text
+/// fields and the file part's headers before it, and the closing boundary
+/// after it.
+fn multipart_frame(
+    boundary: &str,
+    fields: &[UploadField],
+    file_field: &str,
+    file_name: &str,
+) -> (Vec<u8>, Vec<u8>) {
+    let mut prefix = String::new();
+    for field in fields {
+        prefix.push_str(&format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+            escape_quoted(&field.name),
+            field.value
+        ));
+    }
+    prefix.push_str(&format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\
+         Content-Type: application/octet-stream\r\n\r\n",
+        escape_quoted(file_field),
+        escape_quoted(file_name)
+    ));
+    (
+        prefix.into_bytes(),
+        format!("\r\n--{boundary}--\r\n").into_bytes(),
+    )
+}
+
+fn random_boundary() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    format!("----TauriUpload{}", hex::encode(bytes))
+}
+
+fn is_retryable_status(status: u16) -> bool {
+    status == 408 || status == 429 || status >= 500
+}
+
+/// How long the server asked us to wait with `Retry-After`, in seconds.
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    let seconds = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs(seconds).min(MAX_RETRY_AFTER))
+}
+
+async fn open(path: &Path) -> Result<(File, u64), HttpError> {
+    let read_error =
+        |e: std::io::Error| invalid_request(format!("Failed to read {}: {e}", path.display()));
+    let file = File::open(path).await.map_err(read_error)?;
+    let len = file.metadata().await.map_err(read_error)?.len();
+    Ok((file, len))
+}
+
+/// The file's next `len` bytes in chunks of `CHUNK_SIZE`.
+fn file_chunks(file: File, len: u64) -> impl Stream<Item = std::io::Result<Vec<u8>>> {
+    stream::unfold(Some(file.take(len)), |file| async move {
+        let mut file = file?;
+        let mut buffer = vec![0; CHUNK_SIZE];
+        match file.read(&mut buffer).await {
+            Ok(0) => None,
+            Ok(read) => {
+                buffer.truncate(read);
+                Some((Ok(buffer), Some(file)))
+            }
+            // End the stream after an error, which fails the request
+            Err(e) => Some((Err(e), None)),
+        }
+    })
+}
+
+fn body<S>(chunks: S, mut progress: Progress) -> reqwest::Body
+where
+    S: Stream<Item = std::io::Result<Vec<u8>>> + Send + 'static,
+{
+    reqwest::Body::wrap_stream(chunks.inspect_ok(move |chunk| progress.advance(chunk.len())))
+}
+
+/// Runs `attempt` (given its number, from 1) until it gets a response that
+/// doesn't need retrying, or `MAX_ATTEMPTS` is reached.
+async fn with_retries<F, Fut>(mut attempt: F) -> Result<HttpResponse, HttpError>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<reqwest::Response, HttpError>>,
+{
+    let mut number = 1;
+    loop {
+        let result = attempt(number).await;
+        let delay = match &result {
+            Ok(response) if is_retryable_status(response.status().as_u16()) => {
+                retry_after(response).unwrap_or(RETRY_DELAY * 2u32.pow(number - 1))
+            }
+            Err(HttpError::Timeout | HttpError::NetworkError { .. }) => {
+                RETRY_DELAY * 2u32.pow(number - 1)
+            }
+            _ => return http::read_response(result?, HttpResponseType::Text, None).await,
+        };
+        if number == MAX_ATTEMPTS {
+            return http::read_response(result?, HttpResponseType::Text, None).await;
+        }
+        match &result {
+            Ok(response) => log::warn!(
+                "Upload attempt {number} got {}, retrying",
+                response.status()
+            ),
+            Err(e) => log::warn!("Upload attempt {number} failed, retrying: {e}"),
+        }
+        tokio::time::sleep(delay).await;
+        number += 1;
+    }
+}
+
+/// Uploads a file as multipart/form-data, retrying failed attempts, and
+/// returns the final response as text whatever its status.
+pub async fn upload(
+    app: &AppHandle,
+    request: &UploadRequest,
+    on_progress: Option<&Channel<UploadProgress>>,
+) -> Result<HttpResponse, HttpError> {
+    let url = http::parse_url(&request.url)?;
+    let client = http::client(app)?;
+    let file_name = request
+        .path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    log::debug!(
+        "Uploading {file_name} to {}",
+        url.host_str().unwrap_or_default()
+    );
+    with_retries(|attempt| {
+        let url = url.clone();
+        let file_name = &file_name;
+        async move {
+            // Reopen the file each time, in case a failed attempt read part of it
+            let (file, len) = open(&request.path).await?;
+            let boundary = random_boundary();
+            let (prefix, suffix) =
+                multipart_frame(&boundary, &request.fields, &request.file_field, file_name);
+            let total = prefix.len() as u64 + len + suffix.len() as u64;
+            let chunks = stream::once(future::ready(Ok(prefix)))
+                .chain(file_chunks(file, len))
+                .chain(stream::once(future::ready(Ok(suffix))));
+            let progress = Progress {
+                channel: on_progress.cloned(),
+                uploaded: 0,
+                total,
+                attempt,
+                last_sent: None,
+            };
+
+            let mut builder = client
+                .post(url)
+                .header(
+                    reqwest::header::CONTENT_TYPE,
+                    format!("multipart/form-data; boundary={boundary}"),
+                )
+                .header(reqwest::header::CONTENT_LENGTH, total);
+            for header in &request.headers {
+                builder = builder.header(&header.name, &header.value);
+            }
+            builder
+                .body(body(chunks, progress))
+                .send()
+                .await
+                .map_err(request_error)
+        }
+    })
+    .await
+}
+
+/// Uploads a file with a resumable protocol, asking the server how much it
+/// already has before each retry and sending only the rest.
+pub async fn upload_resumable(
+    app: &AppHandle,
+    path: &Path,
+    protocol: &dyn ResumableUpload,
+    on_progress: Option<&Channel<UploadProgress>>,
+) -> Result<HttpResponse, HttpError> {
+    let client = http::client(app)?;
+    with_retries(|attempt| async move {
+        let (mut file, total) = open(path).await?;
+        let offset = if attempt == 1 {
+            0
+        } else {
+            protocol.uploaded_bytes(client).await?.min(total)
+        };
+        if offset > 0 {
+            log::info!("Resuming upload of {} at byte {offset}", path.display());
+            file.seek(SeekFrom::Start(offset))
+                .await
+                .map_err(|e| invalid_request(format!("Failed to read {}: {e}", path.display())))?;
+        }
+        let progress = Progress {
+            channel: on_progress.cloned(),
+            uploaded: offset,
+            total,
+            attempt,
+            last_sent: None,
+        };
+
+        protocol
+            .request(client, offset, total)
+            .header(reqwest::header::CONTENT_LENGTH, total - offset)
+            .body(body(file_chunks(file, total - offset), progress))
+            .send()
+            .await
+            .map_err(request_error)
+    })
+    .await
+}
+
+fn validate_fields(fields: &[UploadField]) -> Result<(), HttpError> {
+    if fields.len() > MAX_FIELDS {
+        return Err(invalid_request(format!(
+            "Too many fields (max {MAX_FIELDS})"
+        )));
+    }
+    for field in fields {
+        if field.name.is_empty() {
+            return Err(invalid_request("Field name cannot be empty"));
+        }
+        validate_string_input(&field.name, MAX_FIELD_NAME_LENGTH, "Field name")
+            .map_err(invalid_request)?;
+        validate_string_input(&field.value, MAX_FIELD_VALUE_LENGTH, "Field value")
+            .map_err(invalid_request)?;
+    }
+    Ok(())
+}
+
+/// Uploads a file from disk to `url` as multipart/form-data, with `fields`
+/// as extra text fields and the file in the `file` field. The file is
+/// streamed rather than read into memory, and failed attempts are retried.
+/// Progress is sent to `on_progress`. Returns the server's response as text,
+/// whatever its status.
+#[tauri::command]
+#[specta::specta]
+pub async fn upload_file(
+    app: AppHandle,
+    url: String,
+    path: String,
+    fields: Vec<UploadField>,
+    on_progress: Channel<UploadProgress>,
+) -> Result<HttpResponse, HttpError> {
+    validate_fields(&fields)?;
+    let path = resolve_scoped_path(&app, &path).map_err(|e| invalid_request(e.to_string()))?;
+    let request = UploadRequest {
+        url,
+        path,
+        file_field: FILE_FIELD.to_string(),
+        fields,
+        headers: Vec::new(),
+    };
+    upload(&app, &request, Some(&on_progress)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multipart_frame() {
+        let fields = [UploadField {
+            name: "title".to_string(),
+            value: "Holiday".to_string(),
+        }];
+        let (prefix, suffix) = multipart_frame("b", &fields, "file", "a \"quoted\".jpg");
+        assert_eq!(
+            String::from_utf8(prefix).unwrap(),
+            "--b\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nHoliday\r\n\
+             --b\r\nContent-Disposition: form-data; name=\"file\"; \
+             filename=\"a %22quoted%22.jpg\"\r\nContent-Type: application/octet-stream\r\n\r\n"
+        );
+        assert_eq!(String::from_utf8(suffix).unwrap(), "\r\n--b--\r\n");
+    }
+
+    #[test]
+    fn test_is_retryable_status() {
+        assert!(is_retryable_status(503));
+        assert!(is_retryable_status(429));
+        assert!(!is_retryable_status(404));
+        assert!(!is_retryable_status(201));
+    }
+
+    #[test]
+    fn test_validate_fields() {
+        let field = |name: &str| UploadField {
+            name: name.to_string(),
+            value: String::new(),
+        };
+        assert!(validate_fields(&[field("title")]).is_ok());
+        assert!(validate_fields(&[field("")]).is_err());
+        assert!(validate_fields(&vec![field("a"); MAX_FIELDS + 1]).is_err());
+    }
+}
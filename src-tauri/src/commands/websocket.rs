@@ -0,0 +1,389 @@
+//! Managed WebSocket connections.
+//!
+//! Connections opened with `ws_connect` live in the backend, so they survive
+//! webview reloads: after a reload, `ws_connections` lists them and the
+//! frontend only needs to listen for `ws-event` again. Each connection runs as
+//! a task that reconnects with exponential backoff when the connection drops,
+//! and pings the server every `HEARTBEAT_INTERVAL` to notice dead connections
+//! that never closed cleanly.
+//!
+//! The task handles everything through one queue (messages to send, incoming
+//! messages from a reader task, heartbeat ticks), so it never needs to wait on
+//! several things at once. Inputs from a previous connection are tagged with
+//! an older generation and ignored after a reconnect.
+
+use futures_util::stream::SplitSink;
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tauri_specta::Event;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use crate::types::{AppError, WsConnection, WsEvent, WsMessage, WsStatus};
+
+/// How often to ping the server. A connection that receives nothing for two
+/// intervals is treated as lost.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(20);
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+const MIN_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+type Sink = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
+
+/// Everything a connection's task reacts to. `u32`s are the generation of the
+/// connection the input came from.
+enum Input {
+    Send(WsMessage),
+    Close,
+    Received(u32, Message),
+    ReadFailed(u32, String),
+    Heartbeat(u32),
+}
+
+struct Connection {
+    url: String,
+    status: WsStatus,
+    sender: UnboundedSender<Input>,
+}
+
+static CONNECTIONS: LazyLock<Mutex<HashMap<u32, Connection>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static NEXT_CONNECTION_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Parses the URL, allowing only `ws` and `wss`.
+fn parse_url(url: &str) -> Result<reqwest::Url, AppError> {
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|e| AppError::validation(format!("Invalid URL {url}: {e}")))?;
+    if !matches!(parsed.scheme(), "ws" | "wss") {
+        return Err(AppError::validation(format!(
+            "Only ws and wss URLs are supported: {url}"
+        )));
+    }
+    Ok(parsed)
+}
+
+/// The delay before the next reconnection attempt: doubled each time, up to
+/// `MAX_RECONNECT_DELAY`.
+fn next_reconnect_delay(delay: Duration) -> Duration {
+    (delay * 2).min(MAX_RECONNECT_DELAY)
+}
+
+fn emit(app: &AppHandle, event: WsEvent) {
+    if let Err(e) = event.emit(app) {
+        log::warn!("Failed to emit ws-event: {e}");
+    }
+}
+
+fn set_status(app: &AppHandle, id: u32, status: WsStatus, error: Option<String>) {
+    if let Ok(mut connections) = CONNECTIONS.lock() {
+        if let Some(connection) = connections.get_mut(&id) {
+            connection.status = status;
+        }
+    }
+    emit(app, WsEvent::StatusChanged { id, status, error });
+}
+
+/// Connects, then reconnects whenever the connection is lost, until
+/// `ws_close`.
+async fn run_connection(
+    app: AppHandle,
+    id: u32,
+    url: reqwest::Url,
+    mut inputs: UnboundedReceiver<Input>,
+    sender: UnboundedSender<Input>,
+) {
+    // tokio-tungstenite is built without a TLS provider, like reqwest; this
+    // fails harmlessly if one is already installed
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    let mut delay = MIN_RECONNECT_DELAY;
+    let mut generation = 0;
+    loop {
+        generation += 1;
+        let connected = tokio::time::timeout(
+            CONNECT_TIMEOUT,
+            tokio_tungstenite::connect_async(url.as_str()),
+        )
+        .await
+        .map_err(|_| "Timed out connecting".to_string())
+        .and_then(|result| result.map_err(|e| format!("Failed to connect: {e}")));
+
+        let error = match connected {
+            Ok((stream, _)) => {
+                log::debug!(
+                    "WebSocket {id} connected to {}",
+                    url.host_str().unwrap_or_default()
+                );
+                delay = MIN_RECONNECT_DELAY;
+                set_status(&app, id, WsStatus::Open, None);
+                match run_open(&app, id, generation, stream, &mut inputs, &sender).await {
+                    Ok(()) => break,
+                    Err(e) => e,
+                }
+            }
+            Err(e) => e,
+        };
+        log::debug!("WebSocket {id} reconnecting in {delay:?}: {error}");
+        set_status(&app, id, WsStatus::Reconnecting, Some(error));
+
+        // Wait out the delay, still closing promptly if asked
+        let retry_at = tokio::time::Instant::now() + delay;
+        loop {
+            match tokio::time::timeout_at(retry_at, inputs.recv()).await {
+                Err(_) => break,
+                Ok(Some(Input::Close)) | Ok(None) => {
+                    remove_connection(&app, id);
+                    return;
+                }
+                // Sends are refused while reconnecting; anything else is from
+                // the lost connection
+                Ok(Some(_)) => {}
+            }
+        }
+        delay = next_reconnect_delay(delay);
+    }
+    remove_connection(&app, id);
+}
+
+fn remove_connection(app: &AppHandle, id: u32) {
+    if let Ok(mut connections) = CONNECTIONS.lock() {
+        connections.remove(&id);
+    }
+    log::debug!("WebSocket {id} closed");
+    emit(
+        app,
+        WsEvent::StatusChanged {
+            id,
+            status: WsStatus::Closed,
+            error: None,
+        },
+    );
+}
+
+/// Runs an open connection. Returns Ok once closed with `ws_close`, or why the
+/// connection was lost.
+async fn run_open(
+    app: &AppHandle,
+    id: u32,
+    generation: u32,
+    stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    inputs: &mut UnboundedReceiver<Input>,
+    sender: &UnboundedSender<Input>,
+) -> Result<(), String> {
+    let (mut sink, mut source) = stream.split();
+
+    let reader_sender = sender.clone();
+    let reader = tauri::async_runtime::spawn(async move {
+        while let Some(message) = source.next().await {
+            let input = match message {
+                Ok(message) => Input::Received(generation, message),
+                Err(e) => {
+                    let _ = reader_sender.send(Input::ReadFailed(generation, e.to_string()));
+                    return;
+                }
+            };
+            if reader_sender.send(input).is_err() {
+                return;
+            }
+        }
+        let _ = reader_sender.send(Input::ReadFailed(
+            generation,
+            "Connection closed by the server".to_string(),
+        ));
+    });
+
+    let heartbeat_sender = sender.clone();
+    let heartbeat = tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+        // The first tick is immediate
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if heartbeat_sender.send(Input::Heartbeat(generation)).is_err() {
+                return;
+            }
+        }
+    });
+
+    let result = handle_inputs(app, id, generation, &mut sink, inputs).await;
+    reader.abort();
+    heartbeat.abort();
+    result
+}
+
+async fn handle_inputs(
+    app: &AppHandle,
+    id: u32,
+    generation: u32,
+    sink: &mut Sink,
+    inputs: &mut UnboundedReceiver<Input>,
+) -> Result<(), String> {
+    let mut last_received = Instant::now();
+    while let Some(input) = inputs.recv().await {
+        match input {
+            Input::Send(message) => {
+                let message = match message {
+                    WsMessage::Text { text } => Message::text(text),
+                    WsMessage::Binary { bytes } => Message::binary(bytes),
+                };
+                sink.send(message)
+                    .await
+                    .map_err(|e| format!("Failed to send: {e}"))?;
+            }
+            Input::Close => {
+                let _ = sink.send(Message::Close(None)).await;
+                let _ = sink.close().await;
+                return Ok(());
+            }
+            Input::Received(from, _) | Input::ReadFailed(from, _) | Input::Heartbeat(from)
+                if from != generation => {}
+            Input::Received(_, message) => {
+                last_received = Instant::now();
+                let message = match message {
+                    Message::Text(text) => WsMessage::Text {
+                        text: text.to_string(),
+                    },
+                    Message::Binary(bytes) => WsMessage::Binary {
+                        bytes: bytes.to_vec(),
+                    },
+                    // tungstenite answers pings itself; the reader ends after
+                    // a close frame, which reports the connection as lost
+                    Message::Ping(_) | Message::Pong(_) | Message::Close(_) | Message::Frame(_) => {
+                        continue
+                    }
+                };
+                emit(app, WsEvent::Message { id, message });
+            }
+            Input::ReadFailed(_, e) => return Err(e),
+            Input::Heartbeat(_) => {
+                if last_received.elapsed() > HEARTBEAT_INTERVAL * 2 {
+                    return Err("No response to heartbeats".to_string());
+                }
+                sink.send(Message::Ping(Default::default()))
+                    .await
+                    .map_err(|e| format!("Failed to send heartbeat: {e}"))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Opens a WebSocket connection that reconnects automatically until
+/// `ws_close`. Returns its id; messages and status changes arrive as
+/// `ws-event`s.
+#[tauri::command]
+#[specta::specta]
+pub fn ws_connect(app: AppHandle, url: String) -> Result<u32, AppError> {
+    let url = parse_url(&url)?;
+    let id = NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed);
+    let (sender, inputs) = mpsc::unbounded_channel();
+
+    CONNECTIONS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock connections: {e}")))?
+        .insert(
+            id,
+            Connection {
+                url: url.to_string(),
+                status: WsStatus::Connecting,
+                sender: sender.clone(),
+            },
+        );
+    tauri::async_runtime::spawn(run_connection(app, id, url, inputs, sender));
+    Ok(id)
+}
+
+/// Sends a message on an open connection. Fails while it's connecting or
+/// reconnecting, rather than queueing messages that may go stale.
+#[tauri::command]
+#[specta::specta]
+pub fn ws_send(id: u32, message: WsMessage) -> Result<(), AppError> {
+    let connections = CONNECTIONS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock connections: {e}")))?;
+    let connection = connections
+        .get(&id)
+        .ok_or_else(|| AppError::not_found(format!("No WebSocket connection {id}")))?;
+    if connection.status != WsStatus::Open {
+        return Err(AppError::validation(format!(
+            "WebSocket connection {id} isn't open"
+        )));
+    }
+    connection
+        .sender
+        .send(Input::Send(message))
+        .map_err(|_| AppError::internal(format!("WebSocket connection {id} is closing")))
+}
+
+/// Closes a connection and stops reconnecting. `ws-event` reports `Closed`
+/// once it's done.
+#[tauri::command]
+#[specta::specta]
+pub fn ws_close(id: u32) -> Result<(), AppError> {
+    let connections = CONNECTIONS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock connections: {e}")))?;
+    let connection = connections
+        .get(&id)
+        .ok_or_else(|| AppError::not_found(format!("No WebSocket connection {id}")))?;
+    // Fails only if the task already finished
+    let _ = connection.sender.send(Input::Close);
+    Ok(())
+}
+
+/// Lists open connections, e.g. to pick them up again after a webview reload.
+#[tauri::command]
+#[specta::specta]
+pub fn ws_connections() -> Result<Vec<WsConnection>, AppError> {
+    let connections = CONNECTIONS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock connections: {e}")))?;
+    let mut list: Vec<WsConnection> = connections
+        .iter()
+        .map(|(id, connection)| WsConnection {
+            id: *id,
+            url: connection.url.clone(),
+            status: connection.status,
+        })
+        .collect();
+    list.sort_by_key(|connection| connection.id);
+    Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_url() {
+        assert!(parse_url("wss://example.com/socket").is_ok());
+        assert!(parse_url("ws://localhost:8080").is_ok());
+        assert!(parse_url("https://example.com").is_err());
+        assert!(parse_url("not a url").is_err());
+    }
+
+    #[test]
+    fn test_next_reconnect_delay() {
+        assert_eq!(
+            next_reconnect_delay(MIN_RECONNECT_DELAY),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            next_reconnect_delay(Duration::from_secs(20)),
+            MAX_RECONNECT_DELAY
+        );
+        assert_eq!(
+            next_reconnect_delay(MAX_RECONNECT_DELAY),
+            MAX_RECONNECT_DELAY
+        );
+    }
+}
@@ -0,0 +1,755 @@
+//! Window management commands.
+//!
+//! Provides a configurable "activate app" global shortcut that brings the
+//! main window to the front from anywhere, as menubar-style apps often need,
+//! and a window factory so the frontend can spawn additional windows.
+
+#[cfg(target_os = "macos")]
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, WebviewUrl};
+use tauri_specta::Event;
+
+use super::session;
+use crate::types::{
+    AppError, CloseRequested, TitleBarStyle, TrafficLightInset, WindowConfig, WindowLevel,
+    DEFAULT_ACTIVATE_APP_SHORTCUT,
+};
+
+/// Label of the main application window
+pub const MAIN_WINDOW_LABEL: &str = "main";
+
+/// Label of the native preferences window
+pub const PREFERENCES_WINDOW_LABEL: &str = "preferences";
+
+/// Logical size of the preferences window
+const PREFERENCES_WINDOW_WIDTH: f64 = 900.0;
+const PREFERENCES_WINDOW_HEIGHT: f64 = 600.0;
+
+/// Prefix of labels of windows created with `create_app_window`, which
+/// `capabilities/app-windows.json` grants permissions to
+const APP_WINDOW_LABEL_PREFIX: &str = "window-";
+
+/// Default logical size for windows created with `create_app_window`
+const DEFAULT_WINDOW_WIDTH: f64 = 800.0;
+const DEFAULT_WINDOW_HEIGHT: f64 = 600.0;
+
+/// Tracks the currently registered activate-app shortcut for selective unregistration.
+static CURRENT_ACTIVATE_APP_SHORTCUT: Mutex<Option<String>> = Mutex::new(None);
+
+/// Labels of windows with unsaved changes (see `set_window_dirty`).
+static DIRTY_WINDOWS: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Set while a quit is waiting on dirty windows to confirm.
+static QUIT_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// Set when the pending quit should relaunch the app (e.g. after an update).
+static RESTART_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// Traffic light insets applied with `set_traffic_light_inset`, by window label.
+/// AppKit resets the buttons on resize, so they're reapplied from here.
+#[cfg(target_os = "macos")]
+static TRAFFIC_LIGHT_INSETS: Mutex<BTreeMap<String, TrafficLightInset>> =
+    Mutex::new(BTreeMap::new());
+
+/// Unhides, un-minimizes and focuses the main window, bringing the app to the
+/// front (switching to the window's Space on macOS).
+pub fn activate_main_window(app: &AppHandle) -> Result<(), AppError> {
+    let window = app
+        .get_webview_window(MAIN_WINDOW_LABEL)
+        .ok_or_else(|| AppError::not_found("Main window not found"))?;
+
+    // Unhide the app itself (Cmd+H) before showing the window
+    #[cfg(target_os = "macos")]
+    app.show()
+        .map_err(|e| AppError::internal(format!("Failed to unhide application: {e}")))?;
+
+    if window.is_minimized().unwrap_or(false) {
+        window
+            .unminimize()
+            .map_err(|e| AppError::internal(format!("Failed to unminimize window: {e}")))?;
+    }
+    window
+        .show()
+        .map_err(|e| AppError::internal(format!("Failed to show window: {e}")))?;
+    window
+        .set_focus()
+        .map_err(|e| AppError::internal(format!("Failed to focus window: {e}")))?;
+
+    log::debug!("Main window activated");
+    Ok(())
+}
+
+/// Brings the main window to the front, e.g. from the Dock or tray menu.
+#[tauri::command]
+#[specta::specta]
+pub fn show_main_window(app: AppHandle) -> Result<(), AppError> {
+    activate_main_window(&app)
+}
+
+/// Registers the activate-app global shortcut, unregistering any previously registered one.
+/// Used by both setup() and update_activate_app_shortcut().
+#[cfg(desktop)]
+pub fn register_activate_app_shortcut(app: &AppHandle, shortcut: &str) -> Result<(), AppError> {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    let global_shortcut = app.global_shortcut();
+
+    let mut current_shortcut = CURRENT_ACTIVATE_APP_SHORTCUT
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock shortcut mutex: {e}")))?;
+
+    if let Some(old_shortcut) = current_shortcut.take() {
+        log::debug!("Unregistering old activate-app shortcut: {old_shortcut}");
+        if let Err(e) = global_shortcut.unregister(old_shortcut.as_str()) {
+            // Continue anyway - the old shortcut may have already been unregistered
+            log::warn!("Failed to unregister old shortcut '{old_shortcut}': {e}");
+        }
+    }
+
+    let app_handle = app.clone();
+    global_shortcut
+        .on_shortcut(shortcut, move |_app, _shortcut, event| {
+            use tauri_plugin_global_shortcut::ShortcutState;
+            if event.state == ShortcutState::Pressed {
+                log::info!("Activate-app shortcut triggered");
+                if let Err(e) = activate_main_window(&app_handle) {
+                    log::error!("Failed to activate main window: {e}");
+                }
+            }
+        })
+        .map_err(|e| {
+            AppError::internal(format!("Failed to register shortcut '{shortcut}': {e}"))
+        })?;
+
+    *current_shortcut = Some(shortcut.to_string());
+    log::debug!("Registered activate-app shortcut: {shortcut}");
+
+    Ok(())
+}
+
+/// Returns the default activate-app shortcut for frontend use.
+#[tauri::command]
+#[specta::specta]
+pub fn get_default_activate_app_shortcut() -> String {
+    DEFAULT_ACTIVATE_APP_SHORTCUT.to_string()
+}
+
+/// Updates the global shortcut that brings the main window to the front.
+/// Pass None to reset to default.
+#[tauri::command]
+#[specta::specta]
+pub fn update_activate_app_shortcut(
+    app: AppHandle,
+    shortcut: Option<String>,
+) -> Result<(), AppError> {
+    #[cfg(desktop)]
+    {
+        let new_shortcut = shortcut.as_deref().unwrap_or(DEFAULT_ACTIVATE_APP_SHORTCUT);
+        log::info!("Updating activate-app shortcut to: {new_shortcut}");
+
+        register_activate_app_shortcut(&app, new_shortcut)?;
+    }
+
+    #[cfg(not(desktop))]
+    {
+        let _ = (app, shortcut);
+        log::warn!("Global shortcuts not supported on this platform");
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Close Confirmation
+// ============================================================================
+
+/// Returns the labels of all windows currently marked dirty.
+fn dirty_window_labels() -> Vec<String> {
+    DIRTY_WINDOWS
+        .lock()
+        .map(|dirty| dirty.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Asks a dirty window's frontend to confirm closing.
+fn emit_close_requested(app: &AppHandle, label: &str, quitting: bool) {
+    let event = CloseRequested {
+        label: label.to_string(),
+        quitting,
+    };
+    if let Err(e) = event.emit_to(app, label) {
+        log::warn!("Failed to emit close-requested to '{label}': {e}");
+    }
+}
+
+/// Called from the window close-requested handler in `lib.rs`. If the window has
+/// unsaved changes, asks its frontend to confirm and returns true, meaning the
+/// close should be prevented for now.
+pub fn intercept_close_if_dirty(app: &AppHandle, label: &str) -> bool {
+    let is_dirty = DIRTY_WINDOWS
+        .lock()
+        .map(|dirty| dirty.contains(label))
+        .unwrap_or(false);
+    if is_dirty {
+        log::info!("Window '{label}' has unsaved changes, asking for confirmation");
+        emit_close_requested(app, label, false);
+    }
+    is_dirty
+}
+
+/// Exits the app, or relaunches it if the quit was a restart.
+fn exit_app(app: &AppHandle) {
+    if RESTART_PENDING.swap(false, Ordering::SeqCst) {
+        app.request_restart();
+    } else {
+        app.exit(0);
+    }
+}
+
+/// Exits the app if a quit is pending and no dirty windows remain.
+fn finish_pending_quit(app: &AppHandle) {
+    if QUIT_PENDING.load(Ordering::SeqCst) && dirty_window_labels().is_empty() {
+        log::info!("All windows confirmed, quitting");
+        exit_app(app);
+    }
+}
+
+/// Quits (or restarts) once every dirty window has confirmed.
+fn quit_when_confirmed(app: &AppHandle, restart: bool) {
+    RESTART_PENDING.store(restart, Ordering::SeqCst);
+
+    let dirty = dirty_window_labels();
+    if dirty.is_empty() {
+        log::info!("Quit requested, no unsaved changes");
+        exit_app(app);
+        return;
+    }
+
+    log::info!("Quit requested with {} dirty window(s)", dirty.len());
+    QUIT_PENDING.store(true, Ordering::SeqCst);
+    for label in dirty {
+        emit_close_requested(app, &label, true);
+    }
+}
+
+/// Forgets a window's unsaved changes. Returns whether it was dirty.
+fn forget_dirty_window(label: &str) -> bool {
+    DIRTY_WINDOWS
+        .lock()
+        .map(|mut dirty| dirty.remove(label))
+        .unwrap_or(false)
+}
+
+/// Forgets a destroyed window's unsaved changes, so a quit waiting on it can
+/// go ahead. Called from the run loop in `lib.rs`.
+pub fn handle_window_destroyed(app: &AppHandle, label: &str) {
+    if forget_dirty_window(label) {
+        log::debug!("Dirty window '{label}' was destroyed");
+        finish_pending_quit(app);
+    }
+}
+
+/// Restarts the app, first asking every dirty window to confirm like
+/// `request_app_quit`. Exit cleanup (such as saving the session) still runs.
+pub fn request_app_restart(app: &AppHandle) {
+    quit_when_confirmed(app, true);
+}
+
+/// Marks a window as having unsaved changes (or not). Dirty windows must
+/// confirm before they close or the app quits.
+#[tauri::command]
+#[specta::specta]
+pub fn set_window_dirty(label: String, dirty: bool) -> Result<(), AppError> {
+    let mut windows = DIRTY_WINDOWS
+        .lock()
+        .map_err(|e| AppError::internal(format!("Failed to lock dirty window set: {e}")))?;
+    if dirty {
+        windows.insert(label);
+    } else {
+        windows.remove(&label);
+    }
+    Ok(())
+}
+
+/// Quits the app, first asking every dirty window to confirm.
+/// Used by the Quit menu item instead of the predefined one, which bypasses
+/// exit handling on macOS (tauri-apps/tauri#9198).
+#[tauri::command]
+#[specta::specta]
+pub fn request_app_quit(app: AppHandle) {
+    quit_when_confirmed(&app, false);
+}
+
+/// Confirms that a dirty window may close, discarding its unsaved changes.
+/// Closes the window, or continues a pending quit once every window has confirmed.
+#[tauri::command]
+#[specta::specta]
+pub fn confirm_window_close(app: AppHandle, label: String) -> Result<(), AppError> {
+    set_window_dirty(label.clone(), false)?;
+
+    if QUIT_PENDING.load(Ordering::SeqCst) {
+        finish_pending_quit(&app);
+        return Ok(());
+    }
+
+    if let Some(window) = app.get_webview_window(&label) {
+        window
+            .close()
+            .map_err(|e| AppError::internal(format!("Failed to close window: {e}")))?;
+    }
+    Ok(())
+}
+
+/// Cancels a close (or quit) after the user chose to keep editing.
+#[tauri::command]
+#[specta::specta]
+pub fn cancel_window_close(label: String) {
+    log::info!("Close cancelled by window '{label}'");
+    QUIT_PENDING.store(false, Ordering::SeqCst);
+    RESTART_PENDING.store(false, Ordering::SeqCst);
+}
+
+// ============================================================================
+// Preferences Window
+// ============================================================================
+
+/// Opens the native preferences window, creating it on first use and
+/// focusing it afterwards. Follows macOS conventions: titled, centered,
+/// fixed size, and not minimizable or maximizable.
+#[tauri::command]
+#[specta::specta]
+pub async fn open_preferences_window(app: AppHandle) -> Result<(), AppError> {
+    use tauri::webview::WebviewWindowBuilder;
+
+    if let Some(window) = app.get_webview_window(PREFERENCES_WINDOW_LABEL) {
+        log::debug!("Preferences window already exists, focusing it");
+        if window.is_minimized().unwrap_or(false) {
+            let _ = window.unminimize();
+        }
+        window
+            .show()
+            .map_err(|e| AppError::internal(format!("Failed to show window: {e}")))?;
+        return window
+            .set_focus()
+            .map_err(|e| AppError::internal(format!("Failed to focus window: {e}")));
+    }
+
+    log::info!("Creating preferences window");
+    WebviewWindowBuilder::new(
+        &app,
+        PREFERENCES_WINDOW_LABEL,
+        WebviewUrl::App("preferences.html".into()),
+    )
+    .title("Preferences")
+    .inner_size(PREFERENCES_WINDOW_WIDTH, PREFERENCES_WINDOW_HEIGHT)
+    .resizable(false)
+    .minimizable(false)
+    .maximizable(false)
+    .center()
+    .focused(true)
+    .build()
+    .map_err(|e| AppError::internal(format!("Failed to create preferences window: {e}")))?;
+
+    Ok(())
+}
+
+// ============================================================================
+// Window Factory
+// ============================================================================
+
+/// Validates a window label. Tauri only accepts alphanumeric characters,
+/// `-`, `/`, `:` and `_`, and only `window-` labels get the app windows
+/// capability.
+fn validate_window_label(label: &str) -> Result<(), AppError> {
+    if label.is_empty() {
+        return Err(AppError::validation("Window label cannot be empty"));
+    }
+    if label
+        .strip_prefix(APP_WINDOW_LABEL_PREFIX)
+        .is_none_or(str::is_empty)
+    {
+        return Err(AppError::validation(format!(
+            "Invalid window label: must start with '{APP_WINDOW_LABEL_PREFIX}' followed by a name"
+        )));
+    }
+    if label.chars().count() > 100 {
+        return Err(AppError::validation(
+            "Window label too long (max 100 characters)",
+        ));
+    }
+    if !label
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '/' | ':' | '_'))
+    {
+        return Err(AppError::validation(
+            "Invalid window label: only alphanumeric characters, '-', '/', ':' and '_' allowed",
+        ));
+    }
+    Ok(())
+}
+
+/// Validates that a window URL is an app-relative path.
+/// External URLs are rejected so spawned windows can't load remote content with IPC access.
+fn validate_window_url(url: &str) -> Result<(), AppError> {
+    if url.is_empty() {
+        return Err(AppError::validation("Window URL cannot be empty"));
+    }
+    if url.contains("://") || url.starts_with("//") {
+        return Err(AppError::validation(
+            "Window URL must be an app-relative path, not an external URL",
+        ));
+    }
+    Ok(())
+}
+
+/// Builds an app window from a config, wiring up modal behavior and session tracking.
+/// `position` is a logical top-left position; the window is centered when None.
+pub(crate) fn build_app_window(
+    app: &AppHandle,
+    config: &WindowConfig,
+    position: Option<(f64, f64)>,
+) -> Result<(), AppError> {
+    use tauri::webview::WebviewWindowBuilder;
+
+    validate_window_label(&config.label)?;
+    validate_window_url(&config.url)?;
+
+    let modal = config.modal.unwrap_or(false);
+    let parent = match config.parent.as_deref() {
+        Some(parent_label) => Some(app.get_webview_window(parent_label).ok_or_else(|| {
+            AppError::not_found(format!("Parent window '{parent_label}' not found"))
+        })?),
+        None if modal => {
+            return Err(AppError::validation(
+                "Modal windows require a parent window",
+            ))
+        }
+        None => None,
+    };
+
+    log::info!("Creating window '{}' ({})", config.label, config.url);
+
+    let mut builder = WebviewWindowBuilder::new(
+        app,
+        &config.label,
+        WebviewUrl::App(config.url.clone().into()),
+    )
+    .title(config.title.as_deref().unwrap_or_default())
+    .inner_size(
+        config.width.unwrap_or(DEFAULT_WINDOW_WIDTH),
+        config.height.unwrap_or(DEFAULT_WINDOW_HEIGHT),
+    )
+    .decorations(config.decorations.unwrap_or(true));
+
+    #[cfg(target_os = "macos")]
+    {
+        if let Some(style) = config.title_bar_style {
+            builder = builder.title_bar_style(style.into());
+        }
+        if let Some(inset) = config.traffic_light_inset {
+            builder = builder.traffic_light_position(tauri::LogicalPosition::new(inset.x, inset.y));
+        }
+        builder = builder.hidden_title(config.hidden_title.unwrap_or(false));
+    }
+
+    // Without native overlay title bars, the app draws all of the chrome itself
+    #[cfg(not(target_os = "macos"))]
+    if config.title_bar_style == Some(TitleBarStyle::Overlay) {
+        builder = builder.decorations(false);
+    }
+
+    builder = match position {
+        Some((x, y)) => builder.position(x, y),
+        None => builder.center(),
+    };
+
+    if let Some(parent) = &parent {
+        builder = builder
+            .parent(parent)
+            .map_err(|e| AppError::internal(format!("Failed to set parent window: {e}")))?;
+    }
+
+    let window = builder
+        .build()
+        .map_err(|e| AppError::internal(format!("Failed to create window: {e}")))?;
+
+    // Modal windows are transient, so they aren't restored with the session
+    if !modal {
+        session::track_window(&window, config);
+    }
+
+    if let (true, Some(parent)) = (modal, parent) {
+        parent
+            .set_enabled(false)
+            .map_err(|e| AppError::internal(format!("Failed to disable parent window: {e}")))?;
+
+        // Re-enable the parent when the modal goes away
+        window.on_window_event(move |event| {
+            if let tauri::WindowEvent::Destroyed = event {
+                if let Err(e) = parent.set_enabled(true) {
+                    log::error!("Failed to re-enable parent window: {e}");
+                }
+                let _ = parent.set_focus();
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Creates an app window (document window, tool palette, detail view...) from a
+/// typed config. If a window with the same label already exists it is focused instead.
+/// Modal windows disable their parent until they close.
+#[tauri::command]
+#[specta::specta]
+pub async fn create_app_window(app: AppHandle, config: WindowConfig) -> Result<(), AppError> {
+    if let Some(existing) = app.get_webview_window(&config.label) {
+        log::debug!("Window '{}' already exists, focusing it", config.label);
+        return existing
+            .set_focus()
+            .map_err(|e| AppError::internal(format!("Failed to focus window: {e}")));
+    }
+
+    build_app_window(&app, &config, None)
+}
+
+// ============================================================================
+// Title Bar
+// ============================================================================
+
+#[cfg(target_os = "macos")]
+impl From<TitleBarStyle> for tauri::TitleBarStyle {
+    fn from(style: TitleBarStyle) -> Self {
+        match style {
+            TitleBarStyle::Visible => tauri::TitleBarStyle::Visible,
+            TitleBarStyle::Transparent => tauri::TitleBarStyle::Transparent,
+            TitleBarStyle::Overlay => tauri::TitleBarStyle::Overlay,
+        }
+    }
+}
+
+/// Changes a window's title bar style at runtime.
+/// On Windows and Linux, `Overlay` removes decorations and the other styles restore them.
+#[tauri::command]
+#[specta::specta]
+pub fn set_title_bar_style(
+    app: AppHandle,
+    label: String,
+    style: TitleBarStyle,
+) -> Result<(), AppError> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| AppError::not_found(format!("Window '{label}' not found")))?;
+
+    log::debug!("Setting title bar style of '{label}' to {style:?}");
+
+    #[cfg(target_os = "macos")]
+    window
+        .set_title_bar_style(style.into())
+        .map_err(|e| AppError::internal(format!("Failed to set title bar style: {e}")))?;
+
+    #[cfg(not(target_os = "macos"))]
+    window
+        .set_decorations(style != TitleBarStyle::Overlay)
+        .map_err(|e| AppError::internal(format!("Failed to set decorations: {e}")))?;
+
+    Ok(())
+}
+
+/// Moves the close, minimize and zoom buttons so the close button's top-left
+/// sits at `inset`, growing the title bar container to fit.
+#[cfg(target_os = "macos")]
+fn position_traffic_lights(window: &tauri::WebviewWindow, inset: TrafficLightInset) {
+    use objc2_app_kit::{NSWindow, NSWindowButton};
+
+    let Ok(ns_window_ptr) = window.ns_window() else {
+        return;
+    };
+    // SAFETY: ns_window() returns a valid NSWindow pointer that lives as long as the window
+    let ns_window: &NSWindow = unsafe { &*ns_window_ptr.cast::<NSWindow>() };
+
+    let (Some(close), Some(minimize), Some(zoom)) = (
+        ns_window.standardWindowButton(NSWindowButton::CloseButton),
+        ns_window.standardWindowButton(NSWindowButton::MiniaturizeButton),
+        ns_window.standardWindowButton(NSWindowButton::ZoomButton),
+    ) else {
+        return;
+    };
+    let Some(title_bar_container) = close.superview().and_then(|view| view.superview()) else {
+        return;
+    };
+
+    // AppKit's origin is bottom-left, so the container is pinned to the window's top edge
+    let close_frame = close.frame();
+    let title_bar_height = close_frame.size.height + inset.y;
+    let mut container_frame = title_bar_container.frame();
+    container_frame.size.height = title_bar_height;
+    container_frame.origin.y = ns_window.frame().size.height - title_bar_height;
+    title_bar_container.setFrame(container_frame);
+
+    let spacing = minimize.frame().origin.x - close_frame.origin.x;
+    for (index, button) in [close, minimize, zoom].iter().enumerate() {
+        let mut origin = button.frame().origin;
+        origin.x = inset.x + index as f64 * spacing;
+        button.setFrameOrigin(origin);
+    }
+}
+
+/// Repositions a window's traffic lights, e.g. to center them in a taller
+/// custom title bar. Does nothing on other platforms.
+#[tauri::command]
+#[specta::specta]
+pub fn set_traffic_light_inset(
+    app: AppHandle,
+    label: String,
+    inset: TrafficLightInset,
+) -> Result<(), AppError> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| AppError::not_found(format!("Window '{label}' not found")))?;
+
+    #[cfg(target_os = "macos")]
+    {
+        log::debug!("Setting traffic light inset of '{label}' to {inset:?}");
+        position_traffic_lights(&window, inset);
+
+        let mut insets = TRAFFIC_LIGHT_INSETS
+            .lock()
+            .map_err(|e| AppError::internal(format!("Failed to lock traffic light insets: {e}")))?;
+        let first_time = insets.insert(label.clone(), inset).is_none();
+
+        // AppKit lays the buttons out again on resize and fullscreen changes
+        if first_time {
+            let app = app.clone();
+            window.on_window_event(move |event| match event {
+                tauri::WindowEvent::Resized(_) | tauri::WindowEvent::ThemeChanged(_) => {
+                    let inset = TRAFFIC_LIGHT_INSETS
+                        .lock()
+                        .ok()
+                        .and_then(|insets| insets.get(&label).copied());
+                    if let (Some(inset), Some(window)) = (inset, app.get_webview_window(&label)) {
+                        position_traffic_lights(&window, inset);
+                    }
+                }
+                tauri::WindowEvent::Destroyed => {
+                    if let Ok(mut insets) = TRAFFIC_LIGHT_INSETS.lock() {
+                        insets.remove(&label);
+                    }
+                }
+                _ => {}
+            });
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = (window, inset);
+
+    Ok(())
+}
+
+// ============================================================================
+// Window Level
+// ============================================================================
+
+/// Keeps a window above all other windows, or returns it to the normal level.
+#[tauri::command]
+#[specta::specta]
+pub fn set_always_on_top(
+    app: AppHandle,
+    label: String,
+    always_on_top: bool,
+) -> Result<(), AppError> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| AppError::not_found(format!("Window '{label}' not found")))?;
+
+    log::debug!("Setting always-on-top of '{label}' to {always_on_top}");
+    window
+        .set_always_on_top(always_on_top)
+        .map_err(|e| AppError::internal(format!("Failed to set always on top: {e}")))
+}
+
+/// Sets the AppKit level of a window. Also used for quick pane panels.
+#[cfg(target_os = "macos")]
+pub(crate) fn set_ns_window_level(
+    window: &tauri::WebviewWindow,
+    level: WindowLevel,
+) -> Result<(), AppError> {
+    use objc2_app_kit::{
+        NSFloatingWindowLevel, NSNormalWindowLevel, NSStatusWindowLevel, NSWindow,
+    };
+
+    let ns_window_ptr = window
+        .ns_window()
+        .map_err(|e| AppError::internal(format!("Failed to get native window: {e}")))?;
+    // SAFETY: ns_window() returns a valid NSWindow pointer that lives as long as the window
+    let ns_window: &NSWindow = unsafe { &*ns_window_ptr.cast::<NSWindow>() };
+    ns_window.setLevel(match level {
+        WindowLevel::Normal => NSNormalWindowLevel,
+        WindowLevel::Floating => NSFloatingWindowLevel,
+        WindowLevel::Status => NSStatusWindowLevel,
+    });
+    Ok(())
+}
+
+/// Sets how high a window floats, e.g. `Floating` for tool palettes that
+/// should stay above document windows.
+/// Platforms without window levels treat `Floating` and `Status` as always-on-top.
+#[tauri::command]
+#[specta::specta]
+pub fn set_window_level(app: AppHandle, label: String, level: WindowLevel) -> Result<(), AppError> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| AppError::not_found(format!("Window '{label}' not found")))?;
+
+    log::debug!("Setting window level of '{label}' to {level:?}");
+
+    #[cfg(target_os = "macos")]
+    set_ns_window_level(&window, level)?;
+
+    #[cfg(not(target_os = "macos"))]
+    window
+        .set_always_on_top(level != WindowLevel::Normal)
+        .map_err(|e| AppError::internal(format!("Failed to set always on top: {e}")))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_window_label() {
+        assert!(validate_window_label("window-document-1").is_ok());
+        assert!(validate_window_label("window-tools/palette:main_2").is_ok());
+        assert!(validate_window_label("").is_err());
+        assert!(validate_window_label("window-has space").is_err());
+        // Only `window-*` labels are covered by the app windows capability
+        assert!(validate_window_label("main").is_err());
+        assert!(validate_window_label("document-1").is_err());
+        assert!(validate_window_label("window-").is_err());
+        assert!(validate_window_label(&"a".repeat(101)).is_err());
+    }
+
+    #[test]
+    fn test_forget_dirty_window() {
+        set_window_dirty("dirty-test".to_string(), true).unwrap();
+        assert!(dirty_window_labels().contains(&"dirty-test".to_string()));
+        assert!(forget_dirty_window("dirty-test"));
+        assert!(!dirty_window_labels().contains(&"dirty-test".to_string()));
+        assert!(!forget_dirty_window("dirty-test"));
+    }
+
+    #[test]
+    fn test_validate_window_url() {
+        assert!(validate_window_url("index.html").is_ok());
+        assert!(validate_window_url("index.html#/detail/42").is_ok());
+        assert!(validate_window_url("").is_err());
+        assert!(validate_window_url("https://example.com").is_err());
+        assert!(validate_window_url("//example.com/page").is_err());
+    }
+}
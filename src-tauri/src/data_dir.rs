@@ -0,0 +1,286 @@
+//! Location of the app's data directory.
+//!
+//! Data lives in the OS app data directory unless the user has moved it
+//! (e.g. to a synced folder) with `move_app_data`. The move is recorded in a
+//! pointer file, `data-location.json`, which always stays in the default
+//! directory. Every path helper (`get_preferences_path`, `get_recovery_dir`,
+//! `get_database_path`, ...) builds on `app_data_dir` here, so they all
+//! follow the pointer.
+//!
+//! The location is read once per launch: files are open from the moment the
+//! app starts, so a move only takes effect after a restart.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+
+use crate::commands::files::write_atomic;
+use crate::types::AppError;
+
+const POINTER_FILE: &str = "data-location.json";
+
+/// Files and directories in the data directory that belong to the app and
+/// move with it. Add new data files here (and to the backup in
+/// `commands/backup.rs`).
+pub const DATA_ENTRIES: &[&str] = &[
+    "preferences.json",
+    "app.db",
+    "app.db-wal",
+    "app.db-shm",
+    "recovery",
+    "crash-reports",
+    "recent-documents.json",
+    "session.json",
+    "quick-pane-history.json",
+    "analytics-queue.jsonl",
+    "update-prompt.json",
+    "allowed-paths.json",
+    "logs",
+];
+
+/// Contents of the pointer file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DataLocation {
+    /// Directory holding the app's data.
+    pub path: PathBuf,
+    /// Copies left behind by the last move, removed once the app has
+    /// started from the new location.
+    #[serde(default)]
+    pub moved: Vec<PathBuf>,
+    /// The data and log directories `moved` was copied from. Only entries
+    /// directly inside these (or the default directories) are removed.
+    #[serde(default)]
+    pub moved_from: Vec<PathBuf>,
+}
+
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+fn default_data_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::internal(format!("Failed to get app data directory: {e}")))
+}
+
+fn pointer_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    Ok(default_data_dir(app)?.join(POINTER_FILE))
+}
+
+/// Reads the pointer file, if the data directory has been moved.
+pub fn read_location(app: &AppHandle) -> Result<Option<DataLocation>, AppError> {
+    let path = pointer_path(app)?;
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(AppError::io(format!("Failed to read data location: {e}"))),
+    };
+    serde_json::from_str(&contents)
+        .map(Some)
+        .map_err(|e| AppError::Serialization {
+            message: format!("Failed to parse data location: {e}"),
+        })
+}
+
+/// Writes the pointer file atomically.
+pub fn write_location(app: &AppHandle, location: &DataLocation) -> Result<(), AppError> {
+    let path = pointer_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::io(format!("Failed to create app data directory: {e}")))?;
+    }
+    let json = serde_json::to_string_pretty(location).map_err(|e| AppError::Serialization {
+        message: format!("Failed to serialize data location: {e}"),
+    })?;
+
+    write_atomic(&path, json.as_bytes())
+        .map_err(|e| AppError::io(format!("Failed to save data location: {e}")))
+}
+
+/// The directory the pointer file names, or the default if there's no
+/// pointer. If the chosen directory is missing (e.g. an unmounted drive),
+/// the default is used for this launch and the pointer is left alone.
+fn resolve(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let default = default_data_dir(app)?;
+    match read_location(app) {
+        Ok(Some(location)) if location.path.is_dir() => Ok(location.path),
+        Ok(Some(location)) => {
+            log::error!(
+                "Data directory {} is unavailable; using {}",
+                location.path.display(),
+                default.display()
+            );
+            Ok(default)
+        }
+        Ok(None) => Ok(default),
+        Err(e) => {
+            log::error!("{e}; using {}", default.display());
+            Ok(default)
+        }
+    }
+}
+
+/// Gets the app data directory, honoring a moved location. The directory
+/// may not exist yet.
+pub fn app_data_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    if let Some(dir) = DATA_DIR.get() {
+        return Ok(dir.clone());
+    }
+    let dir = resolve(app)?;
+    Ok(DATA_DIR.get_or_init(|| dir).clone())
+}
+
+/// Whether the data has been moved out of the default directory.
+pub fn is_relocated(app: &AppHandle) -> Result<bool, AppError> {
+    Ok(app_data_dir(app)? != default_data_dir(app)?)
+}
+
+/// Gets the log directory: `logs/` in a moved data directory, otherwise
+/// the OS log directory.
+pub fn log_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    if is_relocated(app)? {
+        return Ok(app_data_dir(app)?.join("logs"));
+    }
+    app.path()
+        .app_log_dir()
+        .map_err(|e| AppError::internal(format!("Failed to get log directory: {e}")))
+}
+
+/// Directories holding the app's own state: the data directory, the default
+/// one (which keeps the pointer file) and the log directory. The frontend's
+/// file commands can't reach into these, so it can't edit preferences, the
+/// database or the saved fs scope behind the app's back.
+pub fn internal_dirs(app: &AppHandle) -> Vec<PathBuf> {
+    [app_data_dir(app), default_data_dir(app), log_dir(app)]
+        .into_iter()
+        .filter_map(Result::ok)
+        .collect()
+}
+
+fn remove_path(path: &Path) -> std::io::Result<()> {
+    let result = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    match result {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// The canonical form of a `moved` entry, if it's safe to remove: one of the
+/// `DATA_ENTRIES` or a `.log` file, directly inside one of `dirs`. Anything
+/// else in the pointer file (which is only ever written by `move_app_data`)
+/// is refused, so a tampered file can't delete arbitrary paths.
+fn removable_entry(entry: &Path, dirs: &[PathBuf]) -> Option<PathBuf> {
+    let canonical = std::fs::canonicalize(entry).ok()?;
+    let name = canonical.file_name()?.to_str()?;
+    let is_data = DATA_ENTRIES.contains(&name) || name.ends_with(".log");
+    let in_dir = canonical.parent().is_some_and(|parent| {
+        dirs.iter()
+            .filter_map(|dir| std::fs::canonicalize(dir).ok())
+            .any(|dir| dir == parent)
+    });
+    (is_data && in_dir).then_some(canonical)
+}
+
+/// Removes the copies left in the old location by the last move, once the
+/// app is running from the new one. Called in setup.
+pub fn remove_moved_data(app: &AppHandle) {
+    let location = match read_location(app) {
+        Ok(Some(location)) if !location.moved.is_empty() => location,
+        Ok(_) => return,
+        Err(e) => {
+            log::warn!("Failed to check for moved data: {e}");
+            return;
+        }
+    };
+    let Ok(current) = app_data_dir(app) else {
+        return;
+    };
+    // The move hasn't taken effect (e.g. the new location is unavailable)
+    if current != location.path {
+        return;
+    }
+
+    let mut dirs = location.moved_from.clone();
+    dirs.extend(default_data_dir(app));
+    dirs.extend(app.path().app_log_dir());
+
+    let mut failed = Vec::new();
+    for path in location.moved {
+        if path.starts_with(&current) || !path.exists() {
+            continue;
+        }
+        let Some(removable) = removable_entry(&path, &dirs) else {
+            log::warn!("Not removing unexpected moved path {}", path.display());
+            continue;
+        };
+        if let Err(e) = remove_path(&removable) {
+            log::warn!("Failed to remove old copy of {}: {e}", path.display());
+            failed.push(path);
+        }
+    }
+
+    let location = DataLocation {
+        path: location.path,
+        moved: failed,
+        moved_from: location.moved_from,
+    };
+    match write_location(app, &location) {
+        Ok(()) => log::info!("Removed data left in the previous location"),
+        Err(e) => log::warn!("{e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location_without_moved_parses() {
+        let location: DataLocation = serde_json::from_str(r#"{"path": "/data"}"#).unwrap();
+        assert_eq!(location.path, PathBuf::from("/data"));
+        assert!(location.moved.is_empty());
+    }
+
+    #[test]
+    fn test_removable_entry() {
+        let dir = std::env::temp_dir().join(format!("data-dir-moved-{}", std::process::id()));
+        let old = dir.join("old");
+        std::fs::create_dir_all(old.join("recovery")).unwrap();
+        std::fs::create_dir_all(dir.join("elsewhere")).unwrap();
+        for file in [
+            "old/preferences.json",
+            "old/app.log",
+            "old/notes.md",
+            "elsewhere/app.db",
+        ] {
+            std::fs::write(dir.join(file), "{}").unwrap();
+        }
+        let dirs = [old.clone()];
+
+        assert!(removable_entry(&old.join("preferences.json"), &dirs).is_some());
+        assert!(removable_entry(&old.join("recovery"), &dirs).is_some());
+        assert!(removable_entry(&old.join("app.log"), &dirs).is_some());
+        // Not app data, or not directly in the old directory
+        assert!(removable_entry(&old.join("notes.md"), &dirs).is_none());
+        assert!(removable_entry(&dir.join("elsewhere/app.db"), &dirs).is_none());
+        assert!(removable_entry(&old.join("../elsewhere/app.db"), &dirs).is_none());
+        assert!(removable_entry(&old, &dirs).is_none());
+        assert!(removable_entry(&old.join("missing.json"), &dirs).is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_remove_path_ignores_missing() {
+        let dir = std::env::temp_dir().join(format!("data-dir-test-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("nested/file.json"), "{}").unwrap();
+
+        remove_path(&dir).unwrap();
+        assert!(!dir.exists());
+        remove_path(&dir).unwrap();
+    }
+}
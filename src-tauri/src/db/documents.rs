@@ -0,0 +1,232 @@
+//! Queries against the `documents` and `document_revisions` tables.
+//!
+//! Every save that changes a document also records a revision, so the
+//! history can be browsed and restored. Only the newest `MAX_REVISIONS` are
+//! kept per document.
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use super::now_millis;
+use crate::types::{Document, DocumentRevision, DocumentSummary, RevisionSummary};
+
+/// Revisions kept per document; older ones are pruned on save.
+pub const MAX_REVISIONS: u32 = 50;
+
+fn document_from_row(row: &Row) -> rusqlite::Result<Document> {
+    Ok(Document {
+        id: row.get("id")?,
+        title: row.get("title")?,
+        content: row.get("content")?,
+        created_at: row.get::<_, i64>("created_at")? as f64,
+        updated_at: row.get::<_, i64>("updated_at")? as f64,
+        recovered: None,
+    })
+}
+
+fn insert_revision(
+    conn: &Connection,
+    document_id: u32,
+    title: &str,
+    content: &str,
+    now: i64,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO document_revisions (document_id, title, content, created_at)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![document_id, title, content, now],
+    )?;
+    conn.execute(
+        "DELETE FROM document_revisions WHERE document_id = ?1 AND id NOT IN (
+             SELECT id FROM document_revisions WHERE document_id = ?1 ORDER BY id DESC LIMIT ?2
+         )",
+        params![document_id, MAX_REVISIONS],
+    )?;
+    Ok(())
+}
+
+/// Creates an empty document with its first revision and returns it.
+pub fn create(conn: &mut Connection, title: &str) -> rusqlite::Result<Document> {
+    let now = now_millis();
+    let tx = conn.transaction()?;
+    tx.execute(
+        "INSERT INTO documents (title, created_at, updated_at) VALUES (?1, ?2, ?2)",
+        params![title, now],
+    )?;
+    let id = tx.last_insert_rowid() as u32;
+    insert_revision(&tx, id, title, "", now)?;
+    tx.commit()?;
+
+    load(conn, id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+}
+
+/// Saves a document and records a revision, unless nothing changed. Returns
+/// whether anything was written; fails with `QueryReturnedNoRows` if the
+/// document doesn't exist.
+pub fn save(conn: &mut Connection, id: u32, title: &str, content: &str) -> rusqlite::Result<bool> {
+    let tx = conn.transaction()?;
+    let (current_title, current_content): (String, String) = tx.query_row(
+        "SELECT title, content FROM documents WHERE id = ?1",
+        params![id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    if current_title == title && current_content == content {
+        return Ok(false);
+    }
+
+    let now = now_millis();
+    tx.execute(
+        "UPDATE documents SET title = ?2, content = ?3, updated_at = ?4 WHERE id = ?1",
+        params![id, title, content, now],
+    )?;
+    insert_revision(&tx, id, title, content, now)?;
+    tx.commit()?;
+    Ok(true)
+}
+
+/// Loads a document, or `None` if it doesn't exist.
+pub fn load(conn: &Connection, id: u32) -> rusqlite::Result<Option<Document>> {
+    conn.query_row(
+        "SELECT id, title, content, created_at, updated_at FROM documents WHERE id = ?1",
+        params![id],
+        document_from_row,
+    )
+    .optional()
+}
+
+/// Deletes a document and its revisions. Returns whether it existed.
+pub fn delete(conn: &Connection, id: u32) -> rusqlite::Result<bool> {
+    Ok(conn.execute("DELETE FROM documents WHERE id = ?1", params![id])? > 0)
+}
+
+/// All documents, most recently updated first.
+pub fn list(conn: &Connection) -> rusqlite::Result<Vec<DocumentSummary>> {
+    let mut stmt = conn.prepare(
+        "SELECT d.id, d.title, d.updated_at,
+                (SELECT COUNT(*) FROM document_revisions r WHERE r.document_id = d.id)
+         FROM documents d
+         ORDER BY d.updated_at DESC",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(DocumentSummary {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            updated_at: row.get::<_, i64>(2)? as f64,
+            revision_count: row.get(3)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// A document's revisions, newest first.
+pub fn list_revisions(
+    conn: &Connection,
+    document_id: u32,
+) -> rusqlite::Result<Vec<RevisionSummary>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, title, length(CAST(content AS BLOB)), created_at
+         FROM document_revisions
+         WHERE document_id = ?1
+         ORDER BY id DESC",
+    )?;
+    let rows = stmt.query_map(params![document_id], |row| {
+        Ok(RevisionSummary {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            size: row.get(2)?,
+            created_at: row.get::<_, i64>(3)? as f64,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Loads a revision, or `None` if it doesn't exist (or was pruned).
+pub fn load_revision(
+    conn: &Connection,
+    revision_id: u32,
+) -> rusqlite::Result<Option<DocumentRevision>> {
+    conn.query_row(
+        "SELECT id, document_id, title, content, created_at
+         FROM document_revisions WHERE id = ?1",
+        params![revision_id],
+        |row| {
+            Ok(DocumentRevision {
+                id: row.get(0)?,
+                document_id: row.get(1)?,
+                title: row.get(2)?,
+                content: row.get(3)?,
+                created_at: row.get::<_, i64>(4)? as f64,
+            })
+        },
+    )
+    .optional()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::migrate;
+
+    fn test_connection() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "foreign_keys", true).unwrap();
+        migrate(&mut conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_save_records_revisions() {
+        let mut conn = test_connection();
+        let document = create(&mut conn, "Draft").unwrap();
+
+        assert!(save(&mut conn, document.id, "Draft", "Hello").unwrap());
+        // Unchanged saves don't add revisions
+        assert!(!save(&mut conn, document.id, "Draft", "Hello").unwrap());
+        assert!(save(&mut conn, document.id, "Final", "Hello, world").unwrap());
+
+        let revisions = list_revisions(&conn, document.id).unwrap();
+        assert_eq!(revisions.len(), 3);
+        assert_eq!(revisions[0].title, "Final");
+        assert_eq!(revisions[0].size, 12);
+
+        let first = load_revision(&conn, revisions[1].id).unwrap().unwrap();
+        assert_eq!(first.content, "Hello");
+
+        let loaded = load(&conn, document.id).unwrap().unwrap();
+        assert_eq!(loaded.content, "Hello, world");
+        assert_eq!(list(&conn).unwrap()[0].revision_count, 3);
+    }
+
+    #[test]
+    fn test_revisions_are_pruned() {
+        let mut conn = test_connection();
+        let document = create(&mut conn, "Draft").unwrap();
+        for i in 0..MAX_REVISIONS + 5 {
+            save(&mut conn, document.id, "Draft", &i.to_string()).unwrap();
+        }
+
+        let revisions = list_revisions(&conn, document.id).unwrap();
+        assert_eq!(revisions.len(), MAX_REVISIONS as usize);
+        let newest = load_revision(&conn, revisions[0].id).unwrap().unwrap();
+        assert_eq!(newest.content, (MAX_REVISIONS + 4).to_string());
+    }
+
+    #[test]
+    fn test_save_missing_document_fails() {
+        let mut conn = test_connection();
+        assert!(matches!(
+            save(&mut conn, 42, "Title", ""),
+            Err(rusqlite::Error::QueryReturnedNoRows)
+        ));
+    }
+
+    #[test]
+    fn test_delete_removes_revisions() {
+        let mut conn = test_connection();
+        let document = create(&mut conn, "Draft").unwrap();
+        let revision_id = list_revisions(&conn, document.id).unwrap()[0].id;
+
+        assert!(delete(&conn, document.id).unwrap());
+        assert!(load(&conn, document.id).unwrap().is_none());
+        assert!(load_revision(&conn, revision_id).unwrap().is_none());
+    }
+}
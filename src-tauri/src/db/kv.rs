@@ -0,0 +1,86 @@
+//! Queries against the `kv` table: small values stored as JSON text under
+//! string keys.
+//!
+//! Callers are responsible for validating keys and value sizes; see
+//! `commands::kv`.
+
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::now_millis;
+
+/// The stored JSON for `key`, if any.
+pub fn get(conn: &Connection, key: &str) -> rusqlite::Result<Option<String>> {
+    conn.query_row("SELECT value FROM kv WHERE key = ?1", params![key], |row| {
+        row.get(0)
+    })
+    .optional()
+}
+
+/// Stores `value` under `key`, replacing any existing value.
+pub fn set(conn: &Connection, key: &str, value: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO kv (key, value, updated_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT (key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        params![key, value, now_millis()],
+    )?;
+    Ok(())
+}
+
+/// Removes `key`. Returns whether it existed.
+pub fn delete(conn: &Connection, key: &str) -> rusqlite::Result<bool> {
+    Ok(conn.execute("DELETE FROM kv WHERE key = ?1", params![key])? > 0)
+}
+
+/// Keys starting with `prefix` and their stored JSON, sorted by key.
+pub fn list(conn: &Connection, prefix: &str) -> rusqlite::Result<Vec<(String, String)>> {
+    // substr rather than LIKE, so `%` and `_` in the prefix aren't wildcards
+    let mut stmt = conn
+        .prepare("SELECT key, value FROM kv WHERE substr(key, 1, length(?1)) = ?1 ORDER BY key")?;
+    let rows = stmt.query_map(params![prefix], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::migrate;
+
+    fn test_connection() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_set_get_delete() {
+        let conn = test_connection();
+        assert_eq!(get(&conn, "a").unwrap(), None);
+
+        set(&conn, "a", "1").unwrap();
+        set(&conn, "a", "2").unwrap();
+        assert_eq!(get(&conn, "a").unwrap().as_deref(), Some("2"));
+
+        assert!(delete(&conn, "a").unwrap());
+        assert!(!delete(&conn, "a").unwrap());
+        assert_eq!(get(&conn, "a").unwrap(), None);
+    }
+
+    #[test]
+    fn test_list_prefix() {
+        let conn = test_connection();
+        for key in ["ui.sidebar", "ui.panel", "ui_other", "sync.cursor"] {
+            set(&conn, key, "null").unwrap();
+        }
+
+        let keys: Vec<String> = list(&conn, "ui.")
+            .unwrap()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(keys, ["ui.panel", "ui.sidebar"]);
+
+        // `_` is literal, not a wildcard
+        assert_eq!(list(&conn, "ui_").unwrap().len(), 1);
+        assert_eq!(list(&conn, "").unwrap().len(), 4);
+    }
+}
@@ -0,0 +1,143 @@
+//! Versioned schema migrations.
+//!
+//! Each migration's SQL is embedded from `migrations/` at compile time. The
+//! database's `user_version` records the last one applied, and `migrate` runs
+//! any newer ones in order, each in its own transaction.
+//!
+//! Never edit a migration that has shipped: users' databases have already run
+//! it. Add a new file and append it to `MIGRATIONS` instead.
+
+use rusqlite::Connection;
+
+use crate::types::AppError;
+
+/// A schema change, applied once per database.
+pub struct Migration {
+    /// Must be one more than the previous migration's version
+    pub version: u32,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+/// All migrations, oldest first.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "create_notes",
+        sql: include_str!("migrations/0001_create_notes.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "create_kv",
+        sql: include_str!("migrations/0002_create_kv.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "create_search_index",
+        sql: include_str!("migrations/0003_create_search_index.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "create_documents",
+        sql: include_str!("migrations/0004_create_documents.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "create_undo_entries",
+        sql: include_str!("migrations/0005_create_undo_entries.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "create_sync_queue",
+        sql: include_str!("migrations/0006_create_sync_queue.sql"),
+    },
+];
+
+/// The schema version after all migrations have run.
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+/// Applies migrations newer than the database's `user_version`. Fails if the
+/// database was created by a newer version of the app, since this version
+/// doesn't know its schema.
+pub fn migrate(conn: &mut Connection) -> Result<(), AppError> {
+    let current: u32 = conn
+        .pragma_query_value(None, "user_version", |row| row.get(0))
+        .map_err(|e| AppError::internal(format!("Failed to read database version: {e}")))?;
+    let latest = latest_version();
+
+    if current > latest {
+        return Err(AppError::internal(format!(
+            "Database version {current} is newer than this app supports ({latest}). Update the app to open it."
+        )));
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        log::info!(
+            "Applying database migration {} ({})",
+            migration.version,
+            migration.name
+        );
+
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::internal(format!("Failed to start migration: {e}")))?;
+        tx.execute_batch(migration.sql).map_err(|e| {
+            AppError::internal(format!(
+                "Failed to apply migration {} ({}): {e}",
+                migration.version, migration.name
+            ))
+        })?;
+        tx.pragma_update(None, "user_version", migration.version)
+            .map_err(|e| {
+                AppError::internal(format!(
+                    "Failed to record migration {}: {e}",
+                    migration.version
+                ))
+            })?;
+        tx.commit().map_err(|e| {
+            AppError::internal(format!(
+                "Failed to commit migration {}: {e}",
+                migration.version
+            ))
+        })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_version(conn: &Connection) -> u32 {
+        conn.pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_versions_are_sequential() {
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version as usize, i + 1, "{}", migration.name);
+        }
+    }
+
+    #[test]
+    fn test_migrate_is_idempotent() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        assert_eq!(user_version(&conn), MIGRATIONS.len() as u32);
+
+        // Running again applies nothing (and doesn't fail on existing tables)
+        migrate(&mut conn).unwrap();
+        assert_eq!(user_version(&conn), MIGRATIONS.len() as u32);
+    }
+
+    #[test]
+    fn test_migrate_rejects_newer_database() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", 9999).unwrap();
+        assert!(migrate(&mut conn).is_err());
+    }
+}
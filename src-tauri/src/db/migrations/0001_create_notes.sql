@@ -0,0 +1,10 @@
+-- Example table used by `db::notes`. Replace with your app's own schema.
+CREATE TABLE notes (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    title       TEXT NOT NULL,
+    body        TEXT NOT NULL DEFAULT '',
+    created_at  INTEGER NOT NULL,
+    updated_at  INTEGER NOT NULL
+);
+
+CREATE INDEX notes_updated_at ON notes (updated_at);
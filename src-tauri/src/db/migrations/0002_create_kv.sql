@@ -0,0 +1,6 @@
+-- Key-value store used by `db::kv`. Values are JSON text.
+CREATE TABLE kv (
+    key         TEXT PRIMARY KEY NOT NULL,
+    value       TEXT NOT NULL,
+    updated_at  INTEGER NOT NULL
+) WITHOUT ROWID;
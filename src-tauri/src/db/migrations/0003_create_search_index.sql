@@ -0,0 +1,8 @@
+-- Full-text index used by `db::search`. `id` is the caller's identifier for
+-- the indexed item; only `title` and `body` are searchable.
+CREATE VIRTUAL TABLE search_index USING fts5(
+    id UNINDEXED,
+    title,
+    body,
+    tokenize = 'porter unicode61 remove_diacritics 2'
+);
@@ -0,0 +1,21 @@
+-- Documents and their revision history, used by `db::documents`.
+CREATE TABLE documents (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    title       TEXT NOT NULL,
+    content     TEXT NOT NULL DEFAULT '',
+    created_at  INTEGER NOT NULL,
+    updated_at  INTEGER NOT NULL
+);
+
+CREATE INDEX documents_updated_at ON documents (updated_at);
+
+-- A snapshot per save; the newest matches the document itself
+CREATE TABLE document_revisions (
+    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+    document_id  INTEGER NOT NULL REFERENCES documents (id) ON DELETE CASCADE,
+    title        TEXT NOT NULL,
+    content      TEXT NOT NULL,
+    created_at   INTEGER NOT NULL
+);
+
+CREATE INDEX document_revisions_document_id ON document_revisions (document_id, id);
@@ -0,0 +1,9 @@
+-- Undo history kept by `db::undo` when `PERSIST_UNDO_HISTORY` is on.
+-- `stack` is 'undo' or 'redo'; the highest position is the top of the stack.
+CREATE TABLE undo_entries (
+    document_id  TEXT NOT NULL,
+    stack        TEXT NOT NULL CHECK (stack IN ('undo', 'redo')),
+    position     INTEGER NOT NULL,
+    entry        TEXT NOT NULL,
+    PRIMARY KEY (document_id, stack, position)
+) WITHOUT ROWID;
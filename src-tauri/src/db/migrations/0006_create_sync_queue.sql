@@ -0,0 +1,20 @@
+-- Local changes waiting to be pushed by the sync engine (`crate::sync_engine`).
+-- `payload` is JSON; only the newest change per record is kept.
+CREATE TABLE sync_queue (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    collection    TEXT NOT NULL,
+    record_id     TEXT NOT NULL,
+    operation     TEXT NOT NULL CHECK (operation IN ('upsert', 'delete')),
+    payload       TEXT,
+    base_version  TEXT,
+    created_at    INTEGER NOT NULL,
+    attempts      INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX sync_queue_record ON sync_queue (collection, record_id);
+
+-- The sync engine's own state, e.g. the cursor for the next pull.
+CREATE TABLE sync_state (
+    key    TEXT PRIMARY KEY,
+    value  TEXT NOT NULL
+) WITHOUT ROWID;
@@ -0,0 +1,130 @@
+//! Embedded SQLite database for app data.
+//!
+//! `open_app_database` opens `app.db` in the app data directory at startup and
+//! brings its schema up to date with the embedded [`migrations`]. The
+//! [`Database`] is held in managed state, so commands take
+//! `State<'_, Database>` and run queries through [`Database::with_connection`].
+//!
+//! App-specific data should get typed query functions like those in
+//! [`notes`], rather than going through the generic `db_query`/`db_execute`
+//! commands.
+//!
+//! # Example
+//!
+//! ```ignore
+//! #[tauri::command]
+//! #[specta::specta]
+//! pub fn list_notes(database: State<'_, Database>) -> Result<Vec<Note>, AppError> {
+//!     database.with_connection(|conn| notes::list(conn))
+//! }
+//! ```
+
+pub mod documents;
+pub mod kv;
+pub mod migrations;
+pub mod notes;
+pub mod search;
+pub mod sync;
+pub mod undo;
+
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+use crate::types::AppError;
+
+/// How long a write waits for another connection's lock before failing.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Current time as a Unix timestamp in milliseconds, for timestamp columns.
+pub fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+/// The app database, as held in Tauri managed state.
+pub struct Database {
+    conn: Mutex<Connection>,
+}
+
+impl Database {
+    /// Opens (or creates) the database at `path` and applies migrations.
+    /// Encrypted databases are unlocked with the key from the keychain (see
+    /// `crate::encryption`).
+    pub fn open(path: &Path) -> Result<Self, AppError> {
+        let conn = Connection::open(path)
+            .map_err(|e| AppError::internal(format!("Failed to open database: {e}")))?;
+        crate::encryption::key_database(&conn, path)
+            .map_err(|message| AppError::Permission { message })?;
+        // WAL lets reads continue during writes
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(|e| AppError::internal(format!("Failed to configure database: {e}")))?;
+        Self::with_migrations(conn)
+    }
+
+    /// Creates a migrated in-memory database.
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self, AppError> {
+        let conn = Connection::open_in_memory()
+            .map_err(|e| AppError::internal(format!("Failed to open database: {e}")))?;
+        Self::with_migrations(conn)
+    }
+
+    fn with_migrations(mut conn: Connection) -> Result<Self, AppError> {
+        conn.pragma_update(None, "foreign_keys", true)
+            .map_err(|e| AppError::internal(format!("Failed to configure database: {e}")))?;
+        conn.busy_timeout(BUSY_TIMEOUT)
+            .map_err(|e| AppError::internal(format!("Failed to configure database: {e}")))?;
+        migrations::migrate(&mut conn)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Locks the connection, for work that needs its own error handling.
+    /// Prefer `with_connection`.
+    pub fn lock(&self) -> Result<MutexGuard<'_, Connection>, AppError> {
+        self.conn
+            .lock()
+            .map_err(|e| AppError::internal(format!("Failed to lock database: {e}")))
+    }
+
+    /// Writes a consistent copy of the database to `dest`, which must not
+    /// exist. Safe while the app is running, unlike copying the file.
+    pub fn snapshot(&self, dest: &Path) -> Result<(), AppError> {
+        self.lock()?
+            .execute("VACUUM INTO ?1", [dest.to_string_lossy()])
+            .map_err(|e| AppError::internal(format!("Failed to snapshot database: {e}")))?;
+        Ok(())
+    }
+
+    /// Runs `f` with the connection, converting database errors to `AppError`
+    /// for commands. Queries are serialized, so keep `f` short.
+    pub fn with_connection<T>(
+        &self,
+        f: impl FnOnce(&mut Connection) -> rusqlite::Result<T>,
+    ) -> Result<T, AppError> {
+        f(&mut *self.lock()?).map_err(|e| AppError::internal(format!("Database error: {e}")))
+    }
+}
+
+/// Gets the path to `app.db`, creating the app data directory if needed.
+pub fn get_database_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = crate::data_dir::app_data_dir(app)?;
+
+    std::fs::create_dir_all(&app_data_dir)
+        .map_err(|e| AppError::io(format!("Failed to create app data directory: {e}")))?;
+
+    Ok(app_data_dir.join("app.db"))
+}
+
+/// Opens `app.db` in the app data directory.
+pub fn open_app_database(app: &AppHandle) -> Result<Database, AppError> {
+    let database = Database::open(&get_database_path(app)?)?;
+    log::info!("Database opened");
+    Ok(database)
+}
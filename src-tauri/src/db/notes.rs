@@ -0,0 +1,63 @@
+//! Example typed queries against the `notes` table.
+//!
+//! This is the pattern for app-specific data: plain functions that take a
+//! `&Connection` and return typed rows, called from commands through
+//! `Database::with_connection`. Replace it with your own tables.
+
+use rusqlite::{params, Connection, Row};
+
+use super::now_millis;
+use crate::types::Note;
+
+fn from_row(row: &Row) -> rusqlite::Result<Note> {
+    Ok(Note {
+        id: row.get("id")?,
+        title: row.get("title")?,
+        body: row.get("body")?,
+        created_at: row.get::<_, i64>("created_at")? as f64,
+        updated_at: row.get::<_, i64>("updated_at")? as f64,
+    })
+}
+
+/// All notes, most recently updated first.
+pub fn list(conn: &Connection) -> rusqlite::Result<Vec<Note>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, title, body, created_at, updated_at FROM notes ORDER BY updated_at DESC",
+    )?;
+    let rows = stmt.query_map([], from_row)?;
+    rows.collect()
+}
+
+/// Inserts a note and returns it.
+pub fn create(conn: &Connection, title: &str, body: &str) -> rusqlite::Result<Note> {
+    let now = now_millis();
+    conn.execute(
+        "INSERT INTO notes (title, body, created_at, updated_at) VALUES (?1, ?2, ?3, ?3)",
+        params![title, body, now],
+    )?;
+    conn.query_row(
+        "SELECT id, title, body, created_at, updated_at FROM notes WHERE id = ?1",
+        params![conn.last_insert_rowid()],
+        from_row,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::migrate;
+
+    #[test]
+    fn test_create_and_list() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+
+        let first = create(&conn, "First", "").unwrap();
+        let second = create(&conn, "Second", "Body").unwrap();
+        assert_ne!(first.id, second.id);
+        assert_eq!(second.body, "Body");
+
+        let notes = list(&conn).unwrap();
+        assert_eq!(notes.len(), 2);
+    }
+}
@@ -0,0 +1,189 @@
+//! Full-text search over the `search_index` FTS5 table.
+//!
+//! Items are indexed under a caller-chosen string id (e.g. `note:42`), so one
+//! index can cover several kinds of data. Titles are weighted well above
+//! bodies when ranking.
+
+use rusqlite::{params, Connection};
+
+use crate::types::{HighlightSegment, SearchResult};
+
+/// Private-use characters SQLite wraps matches in. They're stripped from
+/// indexed text so they can't appear in it otherwise.
+const HIGHLIGHT_START: char = '\u{E000}';
+const HIGHLIGHT_END: char = '\u{E001}';
+
+/// Approximate number of tokens in a body snippet.
+const SNIPPET_TOKENS: u32 = 16;
+
+fn strip_markers(text: &str) -> String {
+    text.replace([HIGHLIGHT_START, HIGHLIGHT_END], "")
+}
+
+/// Converts user input into an FTS5 query that matches all terms, with the
+/// last one as a prefix so results update while typing. Quoting every term
+/// means FTS5 syntax (`AND`, `"`, `*`, `:`) in the input is searched for
+/// literally rather than causing a syntax error. `None` if there are no terms.
+fn match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    if terms.is_empty() {
+        return None;
+    }
+    Some(format!("{}*", terms.join(" ")))
+}
+
+/// Splits text marked up by `highlight()` or `snippet()` into segments.
+fn segments(marked: &str) -> Vec<HighlightSegment> {
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut highlighted = false;
+
+    for c in marked.chars() {
+        if c == HIGHLIGHT_START || c == HIGHLIGHT_END {
+            if !text.is_empty() {
+                segments.push(HighlightSegment {
+                    text: std::mem::take(&mut text),
+                    highlighted,
+                });
+            }
+            highlighted = c == HIGHLIGHT_START;
+        } else {
+            text.push(c);
+        }
+    }
+    if !text.is_empty() {
+        segments.push(HighlightSegment { text, highlighted });
+    }
+    segments
+}
+
+/// Adds `id` to the index, replacing any existing entry for it.
+pub fn index(conn: &mut Connection, id: &str, title: &str, body: &str) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM search_index WHERE id = ?1", params![id])?;
+    tx.execute(
+        "INSERT INTO search_index (id, title, body) VALUES (?1, ?2, ?3)",
+        params![id, strip_markers(title), strip_markers(body)],
+    )?;
+    tx.commit()
+}
+
+/// Removes `id` from the index. Returns whether it was indexed.
+pub fn remove(conn: &Connection, id: &str) -> rusqlite::Result<bool> {
+    Ok(conn.execute("DELETE FROM search_index WHERE id = ?1", params![id])? > 0)
+}
+
+/// The best `limit` matches for `query`, best first.
+pub fn search(conn: &Connection, query: &str, limit: u32) -> rusqlite::Result<Vec<SearchResult>> {
+    let Some(expression) = match_expression(query) else {
+        return Ok(Vec::new());
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT id,
+                highlight(search_index, 1, ?2, ?3),
+                snippet(search_index, 2, ?2, ?3, '…', ?4),
+                bm25(search_index, 0.0, 10.0, 1.0) AS rank
+         FROM search_index
+         WHERE search_index MATCH ?1
+         ORDER BY rank
+         LIMIT ?5",
+    )?;
+    let rows = stmt.query_map(
+        params![
+            expression,
+            HIGHLIGHT_START.to_string(),
+            HIGHLIGHT_END.to_string(),
+            SNIPPET_TOKENS,
+            limit
+        ],
+        |row| {
+            Ok(SearchResult {
+                id: row.get(0)?,
+                title: segments(&row.get::<_, String>(1)?),
+                snippet: segments(&row.get::<_, String>(2)?),
+                // bm25 is lower for better matches; flip it so higher is better
+                score: -row.get::<_, f64>(3)?,
+            })
+        },
+    )?;
+    rows.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::migrate;
+
+    fn test_connection() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        conn
+    }
+
+    fn plain(segments: &[HighlightSegment]) -> String {
+        segments.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_match_expression() {
+        assert_eq!(match_expression("  "), None);
+        assert_eq!(match_expression("quick fox").unwrap(), "\"quick\" \"fox\"*");
+        assert_eq!(match_expression("say \"hi").unwrap(), "\"say\" \"\"\"hi\"*");
+    }
+
+    #[test]
+    fn test_segments() {
+        let marked = format!("a {HIGHLIGHT_START}quick{HIGHLIGHT_END} fox");
+        let result = segments(&marked);
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[1].text, "quick");
+        assert!(result[1].highlighted);
+        assert!(!result[2].highlighted);
+    }
+
+    #[test]
+    fn test_search_ranks_title_matches_first() {
+        let mut conn = test_connection();
+        index(
+            &mut conn,
+            "note:1",
+            "Groceries",
+            "Remember the rocket salad",
+        )
+        .unwrap();
+        index(&mut conn, "note:2", "Rocket launch", "Countdown notes").unwrap();
+
+        let results = search(&conn, "rock", 10).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].id, "note:2");
+        assert_eq!(plain(&results[0].title), "Rocket launch");
+        assert!(results[0].title[0].highlighted);
+        assert!(results[1].snippet.iter().any(|s| s.highlighted));
+    }
+
+    #[test]
+    fn test_reindex_and_remove() {
+        let mut conn = test_connection();
+        index(&mut conn, "a", "Old title", "").unwrap();
+        index(&mut conn, "a", "New title", "").unwrap();
+
+        assert!(search(&conn, "old", 10).unwrap().is_empty());
+        assert_eq!(search(&conn, "new", 10).unwrap().len(), 1);
+
+        assert!(remove(&conn, "a").unwrap());
+        assert!(!remove(&conn, "a").unwrap());
+        assert!(search(&conn, "new", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_query_syntax_is_literal() {
+        let mut conn = test_connection();
+        index(&mut conn, "a", "AND OR NOT", "").unwrap();
+        assert!(search(&conn, "AND (", 10).is_ok());
+        assert!(search(&conn, "\"unbalanced", 10).is_ok());
+    }
+}
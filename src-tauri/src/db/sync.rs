@@ -0,0 +1,241 @@
+//! Queries against `sync_queue`, local changes waiting for the sync engine
+//! (`crate::sync_engine`) to push them, and `sync_state`.
+//!
+//! Only the newest change per record is kept, since pushing the latest state
+//! is enough. A change keeps the `base_version` of the one it replaces unless
+//! it has its own, so conflicts are still detected against the version the
+//! user started editing from.
+
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use super::now_millis;
+use crate::types::{SyncChange, SyncOperation};
+
+/// A queued change, as read by the sync engine.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingChange {
+    pub id: i64,
+    pub change: SyncChange,
+    /// Failed pushes so far
+    pub attempts: u32,
+}
+
+fn operation_name(operation: SyncOperation) -> &'static str {
+    match operation {
+        SyncOperation::Upsert => "upsert",
+        SyncOperation::Delete => "delete",
+    }
+}
+
+fn from_row(row: &Row) -> rusqlite::Result<PendingChange> {
+    let operation = match row.get::<_, String>("operation")?.as_str() {
+        "delete" => SyncOperation::Delete,
+        _ => SyncOperation::Upsert,
+    };
+    let payload = row
+        .get::<_, Option<String>>("payload")?
+        .map(|payload| serde_json::from_str(&payload))
+        .transpose()
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, Type::Text, Box::new(e)))?;
+    Ok(PendingChange {
+        id: row.get("id")?,
+        change: SyncChange {
+            collection: row.get("collection")?,
+            record_id: row.get("record_id")?,
+            operation,
+            payload,
+            base_version: row.get("base_version")?,
+        },
+        attempts: row.get("attempts")?,
+    })
+}
+
+/// Queues `change`, replacing any pending change to the same record.
+pub fn enqueue(conn: &mut Connection, change: &SyncChange) -> rusqlite::Result<()> {
+    let payload = change.payload.as_ref().map(|payload| payload.to_string());
+    let tx = conn.transaction()?;
+    tx.execute(
+        "INSERT INTO sync_queue
+             (collection, record_id, operation, payload, base_version, created_at)
+         VALUES (?1, ?2, ?3, ?4, COALESCE(?5, (
+             SELECT base_version FROM sync_queue
+             WHERE collection = ?1 AND record_id = ?2 ORDER BY id DESC LIMIT 1
+         )), ?6)",
+        params![
+            change.collection,
+            change.record_id,
+            operation_name(change.operation),
+            payload,
+            change.base_version,
+            now_millis()
+        ],
+    )?;
+    tx.execute(
+        "DELETE FROM sync_queue WHERE collection = ?1 AND record_id = ?2 AND id < ?3",
+        params![change.collection, change.record_id, tx.last_insert_rowid()],
+    )?;
+    tx.commit()
+}
+
+/// The oldest `limit` pending changes.
+pub fn pending(conn: &Connection, limit: u32) -> rusqlite::Result<Vec<PendingChange>> {
+    let mut stmt = conn.prepare("SELECT * FROM sync_queue ORDER BY id LIMIT ?1")?;
+    let rows = stmt.query_map(params![limit], from_row)?;
+    rows.collect()
+}
+
+/// The pending change to a record, if any.
+pub fn pending_for_record(
+    conn: &Connection,
+    collection: &str,
+    record_id: &str,
+) -> rusqlite::Result<Option<PendingChange>> {
+    conn.query_row(
+        "SELECT * FROM sync_queue WHERE collection = ?1 AND record_id = ?2",
+        params![collection, record_id],
+        from_row,
+    )
+    .optional()
+}
+
+/// The number of pending changes.
+pub fn count(conn: &Connection) -> rusqlite::Result<u32> {
+    conn.query_row("SELECT COUNT(*) FROM sync_queue", [], |row| row.get(0))
+}
+
+/// Removes a change once pushed or discarded. Does nothing if it was
+/// replaced by a newer change in the meantime.
+pub fn remove(conn: &Connection, id: i64) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM sync_queue WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+/// Moves a change on top of `base_version` after a conflict, optionally with
+/// a merged payload (which makes it an upsert), so the next push overwrites
+/// the remote record.
+pub fn rebase(
+    conn: &Connection,
+    id: i64,
+    base_version: Option<&str>,
+    payload: Option<&serde_json::Value>,
+) -> rusqlite::Result<()> {
+    let payload = payload.map(|payload| payload.to_string());
+    conn.execute(
+        "UPDATE sync_queue SET base_version = ?2,
+             operation = CASE WHEN ?3 IS NULL THEN operation ELSE 'upsert' END,
+             payload = COALESCE(?3, payload)
+         WHERE id = ?1",
+        params![id, base_version, payload],
+    )?;
+    Ok(())
+}
+
+/// A value the sync engine stored under `key` in `sync_state`.
+pub fn get_state(conn: &Connection, key: &str) -> rusqlite::Result<Option<String>> {
+    conn.query_row(
+        "SELECT value FROM sync_state WHERE key = ?1",
+        params![key],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Stores `value` under `key` in `sync_state`.
+pub fn set_state(conn: &Connection, key: &str, value: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO sync_state (key, value) VALUES (?1, ?2)
+         ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+/// Counts a failed push of each change.
+pub fn record_attempts(conn: &Connection, ids: &[i64]) -> rusqlite::Result<()> {
+    let mut stmt = conn.prepare("UPDATE sync_queue SET attempts = attempts + 1 WHERE id = ?1")?;
+    for id in ids {
+        stmt.execute(params![id])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::migrate;
+    use serde_json::json;
+
+    fn test_connection() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        conn
+    }
+
+    fn change(
+        record_id: &str,
+        payload: serde_json::Value,
+        base_version: Option<&str>,
+    ) -> SyncChange {
+        SyncChange {
+            collection: "notes".to_string(),
+            record_id: record_id.to_string(),
+            operation: SyncOperation::Upsert,
+            payload: Some(payload),
+            base_version: base_version.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_enqueue_keeps_newest_change_per_record() {
+        let mut conn = test_connection();
+        enqueue(&mut conn, &change("a", json!({ "title": "1" }), Some("v1"))).unwrap();
+        enqueue(&mut conn, &change("b", json!({}), None)).unwrap();
+        enqueue(&mut conn, &change("a", json!({ "title": "2" }), None)).unwrap();
+        assert_eq!(count(&conn).unwrap(), 2);
+
+        let pending = pending(&conn, 10).unwrap();
+        assert_eq!(pending[0].change.record_id, "b");
+        // The replacement keeps the version the first edit started from
+        assert_eq!(
+            pending[1].change,
+            change("a", json!({ "title": "2" }), Some("v1"))
+        );
+
+        let delete = SyncChange {
+            operation: SyncOperation::Delete,
+            payload: None,
+            ..change("a", json!(null), None)
+        };
+        enqueue(&mut conn, &delete).unwrap();
+        let pending = pending_for_record(&conn, "notes", "a").unwrap().unwrap();
+        assert_eq!(pending.change.operation, SyncOperation::Delete);
+        assert_eq!(pending.change.payload, None);
+    }
+
+    #[test]
+    fn test_rebase_remove_and_attempts() {
+        let mut conn = test_connection();
+        enqueue(&mut conn, &change("a", json!({ "title": "1" }), Some("v1"))).unwrap();
+        let id = pending(&conn, 1).unwrap()[0].id;
+
+        rebase(&conn, id, Some("v2"), Some(&json!({ "title": "merged" }))).unwrap();
+        record_attempts(&conn, &[id]).unwrap();
+        let pending = pending_for_record(&conn, "notes", "a").unwrap().unwrap();
+        assert_eq!(pending.change.base_version.as_deref(), Some("v2"));
+        assert_eq!(pending.change.payload, Some(json!({ "title": "merged" })));
+        assert_eq!(pending.attempts, 1);
+
+        remove(&conn, id).unwrap();
+        assert_eq!(count(&conn).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_state() {
+        let conn = test_connection();
+        assert_eq!(get_state(&conn, "cursor").unwrap(), None);
+        set_state(&conn, "cursor", "1").unwrap();
+        set_state(&conn, "cursor", "2").unwrap();
+        assert_eq!(get_state(&conn, "cursor").unwrap().as_deref(), Some("2"));
+    }
+}
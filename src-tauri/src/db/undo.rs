@@ -0,0 +1,77 @@
+//! Queries against the `undo_entries` table, which persists undo history
+//! for `commands::undo` when `PERSIST_UNDO_HISTORY` is on.
+//!
+//! Entries are opaque JSON text. Each stack is stored oldest first, by
+//! position.
+
+use rusqlite::{params, Connection};
+
+/// A document's undo and redo stacks, oldest entry first.
+pub fn load(conn: &Connection, document_id: &str) -> rusqlite::Result<(Vec<String>, Vec<String>)> {
+    let mut stmt = conn.prepare(
+        "SELECT entry FROM undo_entries WHERE document_id = ?1 AND stack = ?2 ORDER BY position",
+    )?;
+    let mut stack = |name: &str| -> rusqlite::Result<Vec<String>> {
+        stmt.query_map(params![document_id, name], |row| row.get(0))?
+            .collect()
+    };
+    Ok((stack("undo")?, stack("redo")?))
+}
+
+/// Replaces a document's stored history with `undo` and `redo`.
+pub fn save(
+    conn: &mut Connection,
+    document_id: &str,
+    undo: &[String],
+    redo: &[String],
+) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+    tx.execute(
+        "DELETE FROM undo_entries WHERE document_id = ?1",
+        params![document_id],
+    )?;
+    {
+        let mut insert = tx.prepare(
+            "INSERT INTO undo_entries (document_id, stack, position, entry) VALUES (?1, ?2, ?3, ?4)",
+        )?;
+        for (name, entries) in [("undo", undo), ("redo", redo)] {
+            for (position, entry) in entries.iter().enumerate() {
+                insert.execute(params![document_id, name, position as i64, entry])?;
+            }
+        }
+    }
+    tx.commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::migrate;
+
+    fn test_connection() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_save_load() {
+        let mut conn = test_connection();
+        assert_eq!(load(&conn, "doc").unwrap(), (vec![], vec![]));
+
+        let undo = vec!["1".to_string(), "2".to_string()];
+        let redo = vec!["3".to_string()];
+        save(&mut conn, "doc", &undo, &redo).unwrap();
+        save(&mut conn, "other", &redo, &[]).unwrap();
+        assert_eq!(load(&conn, "doc").unwrap(), (undo.clone(), redo.clone()));
+
+        // Saving replaces rather than appends
+        save(&mut conn, "doc", &undo[..1], &[]).unwrap();
+        assert_eq!(load(&conn, "doc").unwrap(), (undo[..1].to_vec(), vec![]));
+
+        // Saving empty stacks clears the document's history only
+        save(&mut conn, "doc", &[], &[]).unwrap();
+        assert_eq!(load(&conn, "doc").unwrap(), (vec![], vec![]));
+        assert_eq!(load(&conn, "other").unwrap().0, redo);
+    }
+}
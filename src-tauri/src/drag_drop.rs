@@ -0,0 +1,173 @@
+//! Native file drops.
+//!
+//! Files dropped on a window are handled here, from the native
+//! `WindowEvent::DragDrop` event, rather than from webview drop events,
+//! which don't carry paths and break with some drag sources (e.g. files
+//! dragged out of archives or from some browsers). Each drop is emitted to
+//! the window it landed on as `files-dropped`, with metadata for each path.
+//!
+//! The fs plugin adds dropped paths to the filesystem scope before this runs,
+//! so paths still outside it are ones the scope explicitly denies. Those are
+//! left out of the event.
+
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, PhysicalPosition, WebviewWindow};
+use tauri_plugin_fs::FsExt;
+use tauri_specta::Event;
+
+use crate::types::{DroppedFile, FilesDropped};
+
+/// Paths described per drop. Anything past this is left out of the event.
+const MAX_DROPPED_FILES: usize = 1000;
+
+/// MIME types by lowercase extension, for files the frontend commonly handles.
+const MIME_TYPES: &[(&str, &str)] = &[
+    ("txt", "text/plain"),
+    ("md", "text/markdown"),
+    ("markdown", "text/markdown"),
+    ("csv", "text/csv"),
+    ("html", "text/html"),
+    ("htm", "text/html"),
+    ("css", "text/css"),
+    ("js", "text/javascript"),
+    ("json", "application/json"),
+    ("xml", "application/xml"),
+    ("pdf", "application/pdf"),
+    ("zip", "application/zip"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("svg", "image/svg+xml"),
+    ("bmp", "image/bmp"),
+    ("ico", "image/x-icon"),
+    ("heic", "image/heic"),
+    ("mp3", "audio/mpeg"),
+    ("wav", "audio/wav"),
+    ("mp4", "video/mp4"),
+    ("mov", "video/quicktime"),
+    ("webm", "video/webm"),
+];
+
+/// Image types every platform's webview can display.
+const PREVIEWABLE_IMAGES: &[&str] = &[
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "image/svg+xml",
+    "image/bmp",
+];
+
+/// Guesses a file's MIME type from its extension.
+fn mime_type(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    MIME_TYPES
+        .iter()
+        .find(|(known, _)| *known == ext)
+        .map(|(_, mime)| *mime)
+}
+
+/// Whether the frontend can show a preview of a file with this MIME type:
+/// text, or an image the webview can display.
+fn has_preview(mime_type: &str) -> bool {
+    mime_type.starts_with("text/") || PREVIEWABLE_IMAGES.contains(&mime_type)
+}
+
+/// Describes a dropped path. Returns None if it no longer exists.
+fn describe(path: &Path) -> Option<DroppedFile> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let is_directory = metadata.is_dir();
+    let mime = if is_directory { None } else { mime_type(path) };
+    Some(DroppedFile {
+        path: path.to_string_lossy().into_owned(),
+        name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        is_directory,
+        size: (!is_directory).then_some(metadata.len() as f64),
+        has_preview: mime.is_some_and(has_preview),
+        mime_type: mime.map(str::to_string),
+    })
+}
+
+/// Emits `files-dropped` to `window` for a native drop. Called from
+/// `window_events::forward_window_event`.
+pub fn handle_drop(
+    app: &AppHandle,
+    window: &WebviewWindow,
+    paths: &[PathBuf],
+    position: PhysicalPosition<f64>,
+) {
+    let scope = app.fs_scope();
+    let files: Vec<DroppedFile> = paths
+        .iter()
+        .filter(|path| {
+            let allowed = scope.is_allowed(path);
+            if !allowed {
+                log::warn!("Ignoring dropped path outside scope: {}", path.display());
+            }
+            allowed
+        })
+        .take(MAX_DROPPED_FILES)
+        .filter_map(|path| describe(path))
+        .collect();
+    if files.is_empty() {
+        return;
+    }
+
+    log::debug!("{} file(s) dropped on '{}'", files.len(), window.label());
+    let scale_factor = window.scale_factor().unwrap_or(1.0);
+    let position = position.to_logical::<f64>(scale_factor);
+    let event = FilesDropped {
+        label: window.label().to_string(),
+        files,
+        x: position.x,
+        y: position.y,
+    };
+    if let Err(e) = event.emit_to(app, window.label()) {
+        log::warn!("Failed to emit {} event: {e}", FilesDropped::NAME);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mime_type_and_preview() {
+        assert_eq!(mime_type(Path::new("notes.MD")), Some("text/markdown"));
+        assert_eq!(mime_type(Path::new("photo.jpeg")), Some("image/jpeg"));
+        assert_eq!(mime_type(Path::new("archive.tar.gz")), None);
+        assert_eq!(mime_type(Path::new("Makefile")), None);
+
+        assert!(has_preview("text/markdown"));
+        assert!(has_preview("image/png"));
+        assert!(!has_preview("image/heic"));
+        assert!(!has_preview("application/pdf"));
+    }
+
+    #[test]
+    fn test_describe() {
+        let dir = std::env::temp_dir().join(format!("drag-drop-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("image.png");
+        std::fs::write(&file, [0u8; 16]).unwrap();
+
+        let described = describe(&file).unwrap();
+        assert_eq!(described.name, "image.png");
+        assert_eq!(described.size, Some(16.0));
+        assert_eq!(described.mime_type.as_deref(), Some("image/png"));
+        assert!(described.has_preview);
+
+        let described = describe(&dir).unwrap();
+        assert!(described.is_directory);
+        assert_eq!(described.size, None);
+        assert_eq!(described.mime_type, None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(describe(&file).is_none());
+    }
+}
@@ -0,0 +1,339 @@
+//! Encryption of local data at rest.
+//!
+//! When the `encrypt_local_data` preference is on, `app.db` is a SQLCipher
+//! database and preferences and recovery data are written as JSON envelopes
+//! (`{"__encrypted__":"..."}`) holding XChaCha20-Poly1305 ciphertext. Both use
+//! one 256-bit key, generated on first use and kept in the OS keychain.
+//!
+//! Reads accept both forms, so data written before the setting changed stays
+//! readable. The setting takes effect at startup: `migrate_local_data`
+//! converts the database and rewrites preferences and recovery files before
+//! anything opens them.
+//!
+//! Logs, the disk cache and other JSON files in the data directory are not
+//! encrypted.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use keyring::Entry;
+use rusqlite::{Connection, DatabaseName};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use tauri::AppHandle;
+
+use crate::commands::files::write_atomic;
+use crate::commands::preferences::{get_preferences_path, load_encrypt_local_data};
+use crate::recovery_store::get_recovery_dir;
+
+/// Start of every encrypted envelope, unlikely to begin real data.
+const ENVELOPE_PREFIX: &str = "{\"__encrypted__\":\"";
+const NONCE_SIZE: usize = 24;
+const KEY_ENTRY_NAME: &str = "local-data-key";
+/// First bytes of an unencrypted SQLite database.
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+/// Keychain service, the bare app identifier. Secrets stored through the
+/// secret commands are always under `<identifier>.<service>`, so the key
+/// can't be read from the frontend.
+static SERVICE: OnceLock<String> = OnceLock::new();
+static KEY: Mutex<Option<[u8; 32]>> = Mutex::new(None);
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Records the keychain service and reads the preference. Must run first in
+/// setup, before preferences are read.
+pub fn init(app: &AppHandle) {
+    let _ = SERVICE.set(app.config().identifier.clone());
+    ENABLED.store(load_encrypt_local_data(app), Ordering::Relaxed);
+}
+
+/// Whether new writes are encrypted. Fixed for the life of the process.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+fn key_entry() -> Result<Entry, String> {
+    let service = SERVICE
+        .get()
+        .ok_or_else(|| "Encryption is not initialized".to_string())?;
+    Entry::new(service, KEY_ENTRY_NAME).map_err(|e| format!("Failed to access keychain: {e}"))
+}
+
+fn decode_key(hex: &str) -> Result<[u8; 32], String> {
+    hex::decode(hex)
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or_else(|| "Encryption key in the keychain is invalid".to_string())
+}
+
+/// Gets the data key, from the keychain on first use. With `create`, a new
+/// key is generated and stored if there isn't one.
+fn data_key(create: bool) -> Result<[u8; 32], String> {
+    let mut cached = KEY
+        .lock()
+        .map_err(|e| format!("Failed to lock encryption key: {e}"))?;
+    if let Some(key) = *cached {
+        return Ok(key);
+    }
+
+    let entry = key_entry()?;
+    let key = match entry.get_password() {
+        Ok(hex) => decode_key(&hex)?,
+        Err(keyring::Error::NoEntry) if create => {
+            let key: [u8; 32] = XChaCha20Poly1305::generate_key(&mut OsRng).into();
+            entry
+                .set_password(&hex::encode(key))
+                .map_err(|e| format!("Failed to store encryption key: {e}"))?;
+            log::info!("Created local data encryption key");
+            key
+        }
+        Err(keyring::Error::NoEntry) => {
+            return Err("Encryption key not found in the keychain".to_string())
+        }
+        Err(e) => return Err(format!("Failed to read encryption key: {e}")),
+    };
+    *cached = Some(key);
+    Ok(key)
+}
+
+/// Makes sure a data key exists, creating one if needed. Call before turning
+/// encryption on, so a missing keychain is reported straight away.
+pub fn ensure_key() -> Result<(), String> {
+    data_key(true).map(|_| ())
+}
+
+/// Whether `text` is an encrypted envelope.
+pub fn is_encrypted(text: &str) -> bool {
+    text.starts_with(ENVELOPE_PREFIX)
+}
+
+fn seal(key: &[u8; 32], plaintext: &str) -> Result<String, String> {
+    let cipher = XChaCha20Poly1305::new(Key::from_slice(key));
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| "Failed to encrypt data".to_string())?;
+
+    let mut payload = nonce.to_vec();
+    payload.extend_from_slice(&ciphertext);
+    Ok(format!("{ENVELOPE_PREFIX}{}\"}}", BASE64.encode(payload)))
+}
+
+fn unseal(key: &[u8; 32], envelope: &str) -> Result<String, String> {
+    let encoded = envelope
+        .strip_prefix(ENVELOPE_PREFIX)
+        .and_then(|rest| rest.trim_end().strip_suffix("\"}"))
+        .ok_or_else(|| "Encrypted data is malformed".to_string())?;
+    let payload = BASE64
+        .decode(encoded)
+        .map_err(|e| format!("Encrypted data is malformed: {e}"))?;
+    if payload.len() < NONCE_SIZE {
+        return Err("Encrypted data is malformed".to_string());
+    }
+
+    let (nonce, ciphertext) = payload.split_at(NONCE_SIZE);
+    let cipher = XChaCha20Poly1305::new(Key::from_slice(key));
+    let plaintext = cipher
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Failed to decrypt data: wrong key or corrupted data".to_string())?;
+    String::from_utf8(plaintext).map_err(|e| format!("Decrypted data is not text: {e}"))
+}
+
+/// Encrypts `text` if encryption is on; otherwise returns it unchanged.
+pub fn encrypt_if_enabled(text: &str) -> Result<String, String> {
+    if is_enabled() {
+        seal(&data_key(true)?, text)
+    } else {
+        Ok(text.to_string())
+    }
+}
+
+/// Decrypts `text` if it's an encrypted envelope; otherwise returns it
+/// unchanged.
+pub fn decrypt_if_encrypted(text: String) -> Result<String, String> {
+    if is_encrypted(&text) {
+        unseal(&data_key(false)?, &text)
+    } else {
+        Ok(text)
+    }
+}
+
+fn key_pragma(key: &[u8; 32]) -> String {
+    format!("x'{}'", hex::encode(key))
+}
+
+/// Whether the database file at `path` is encrypted. False if it doesn't
+/// exist or is empty.
+fn is_encrypted_database(path: &Path) -> std::io::Result<bool> {
+    use std::io::Read;
+
+    let mut header = [0u8; 16];
+    match std::fs::File::open(path) {
+        Ok(mut file) => match file.read_exact(&mut header) {
+            Ok(()) => Ok(header != SQLITE_HEADER),
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+            Err(e) => Err(e),
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Applies the key to a connection just opened on `path`, if the database
+/// is encrypted or is new and encryption is on. Must run before anything
+/// else touches the connection.
+pub fn key_database(conn: &Connection, path: &Path) -> Result<(), String> {
+    let encrypted =
+        is_encrypted_database(path).map_err(|e| format!("Failed to read database header: {e}"))?;
+    let is_new = std::fs::metadata(path).map_or(true, |m| m.len() == 0);
+    if encrypted || (is_new && is_enabled()) {
+        conn.pragma_update(None, "key", key_pragma(&data_key(!encrypted)?))
+            .map_err(|e| format!("Failed to unlock database: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Rewrites the database at `path` with or without encryption, via
+/// SQLCipher's `sqlcipher_export`.
+fn convert_database(path: &Path, encrypt: bool) -> Result<(), String> {
+    let key = key_pragma(&data_key(encrypt)?);
+    let converted_path = path.with_extension("db.converting");
+    let _ = std::fs::remove_file(&converted_path);
+
+    let result = (|| -> rusqlite::Result<()> {
+        let conn = Connection::open(path)?;
+        if !encrypt {
+            conn.pragma_update(None, "key", &key)?;
+        }
+        let target_key = if encrypt { key.as_str() } else { "" };
+        conn.execute(
+            "ATTACH DATABASE ?1 AS converted KEY ?2",
+            (converted_path.to_string_lossy(), target_key),
+        )?;
+        conn.query_row("SELECT sqlcipher_export('converted')", [], |_| Ok(()))?;
+        // Not copied by the export, and migrations depend on it
+        let version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        conn.pragma_update(
+            Some(DatabaseName::Attached("converted")),
+            "user_version",
+            version,
+        )?;
+        conn.execute("DETACH DATABASE converted", [])?;
+        conn.close().map_err(|(_, e)| e)
+    })();
+
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&converted_path);
+        return Err(format!("Failed to convert database: {e}"));
+    }
+
+    // The write-ahead log belongs to the old file and was merged on close
+    for suffix in ["-wal", "-shm"] {
+        let mut wal = path.as_os_str().to_owned();
+        wal.push(suffix);
+        let _ = std::fs::remove_file(wal);
+    }
+    std::fs::rename(&converted_path, path).map_err(|e| {
+        let _ = std::fs::remove_file(&converted_path);
+        format!("Failed to replace database: {e}")
+    })
+}
+
+/// Rewrites a JSON file encrypted or in plain text to match the setting.
+/// Returns whether it changed.
+fn convert_file(path: &Path) -> Result<bool, String> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(format!("Failed to read {}: {e}", path.display())),
+    };
+    if is_encrypted(&contents) == is_enabled() {
+        return Ok(false);
+    }
+
+    let converted = encrypt_if_enabled(&decrypt_if_encrypted(contents)?)?;
+    write_atomic(path, converted.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    Ok(true)
+}
+
+/// Brings the database, preferences and JSON recovery files in line with
+/// the `encrypt_local_data` setting. Must run in setup before the database
+/// and recovery store are opened. Recovery data in the SQLite backend is
+/// left as it is and re-encrypted as it's rewritten.
+pub fn migrate_local_data(app: &AppHandle) -> Result<(), String> {
+    let database_path = crate::db::get_database_path(app)?;
+    let encrypted = is_encrypted_database(&database_path)
+        .map_err(|e| format!("Failed to read database header: {e}"))?;
+    let has_data = std::fs::metadata(&database_path).is_ok_and(|m| m.len() > 0);
+    if has_data && encrypted != is_enabled() {
+        convert_database(&database_path, is_enabled())?;
+        log::info!(
+            "Database {}",
+            if is_enabled() {
+                "encrypted"
+            } else {
+                "decrypted"
+            }
+        );
+    }
+
+    let mut files = vec![get_preferences_path(app)?];
+    let recovery_dir = get_recovery_dir(app)?;
+    if let Ok(entries) = std::fs::read_dir(&recovery_dir) {
+        files.extend(
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json")),
+        );
+    }
+
+    let mut converted = 0;
+    for path in files {
+        match convert_file(&path) {
+            Ok(true) => converted += 1,
+            Ok(false) => {}
+            Err(e) => log::error!("{e}"),
+        }
+    }
+    if converted > 0 {
+        log::info!("Converted {converted} data files for the encryption setting");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_round_trip() {
+        let key = [7u8; 32];
+        let envelope = seal(&key, "{\n  \"theme\": \"dark\"\n}").unwrap();
+        assert!(is_encrypted(&envelope));
+        assert!(serde_json::from_str::<serde_json::Value>(&envelope).is_ok());
+        assert_eq!(
+            unseal(&key, &envelope).unwrap(),
+            "{\n  \"theme\": \"dark\"\n}"
+        );
+    }
+
+    #[test]
+    fn test_unseal_rejects_wrong_key_and_tampering() {
+        let envelope = seal(&[7u8; 32], "secret").unwrap();
+        assert!(unseal(&[8u8; 32], &envelope).is_err());
+
+        let mut tampered = envelope.clone();
+        tampered.replace_range(20..21, if &envelope[20..21] == "A" { "B" } else { "A" });
+        assert!(unseal(&[7u8; 32], &tampered).is_err());
+    }
+
+    #[test]
+    fn test_plain_json_is_not_encrypted() {
+        assert!(!is_encrypted("{\"encrypted\":\"\"}"));
+        assert!(!is_encrypted("[]"));
+    }
+}
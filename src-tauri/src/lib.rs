@@ -5,18 +5,48 @@
 //! and shared types are in the `types` module.
 
 mod bindings;
+mod cache;
 mod commands;
+mod data_dir;
+mod db;
+mod drag_drop;
+mod encryption;
+mod middleware;
+mod recovery_store;
+// Public so apps built on the template can plug in a backend and hooks
+pub mod sync_engine;
 mod types;
 mod utils;
+mod window_events;
 
+use tauri::webview::PageLoadEvent;
 use tauri::{Manager, RunEvent, WindowEvent};
 
 // Re-export only what's needed externally
-pub use types::DEFAULT_QUICK_PANE_SHORTCUT;
+pub use types::{DEFAULT_ACTIVATE_APP_SHORTCUT, DEFAULT_QUICK_PANE_SHORTCUT};
+
+// Configuration for the tray, extra quick panes and Jump List tasks. The
+// template doesn't use all of it in `setup()`, but apps built on it do.
+pub use commands::jump_list::register_jump_list_task;
+pub use commands::quick_pane::{
+    register_quick_pane, QuickPaneConfig, QuickPaneSpaces, QuickPaneVibrancy,
+};
+pub use commands::tray::{TrayConfig, TrayMenuEntry};
+pub use types::{JumpListTask, QuickPanePosition, WindowLevel};
+
+// For the Rust commands apps add to call their provider's API or upload to
+// a resumable endpoint.
+pub use commands::auth::access_token;
+pub use commands::uploads::{upload_resumable, ResumableUpload};
+
+// For apps with a lock screen, so guarded commands are rejected while it's
+// shown.
+pub use middleware::set_locked;
 
 /// Application entry point. Sets up all plugins and initializes the app.
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    commands::performance::start_clock();
     let builder = bindings::generate_bindings();
 
     // Export TypeScript bindings in debug builds
@@ -30,23 +60,35 @@ pub fn run() {
     // When user tries to open a second instance, focus the existing window instead
     #[cfg(desktop)]
     {
-        app_builder = app_builder.plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+        app_builder = app_builder.plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
             if let Some(window) = app.get_webview_window("main") {
                 let _ = window.set_focus();
                 let _ = window.unminimize();
             }
+            // Relaunches from the Windows Jump List carry the chosen entry
+            commands::jump_list::handle_launch_args(app, &args);
+            // Files opened with the app on Windows and Linux
+            commands::file_associations::handle_launch_args(app, &args, std::path::Path::new(&cwd));
         }));
     }
 
     // Window state plugin - saves/restores window position and size
-    // Note: quick-pane is denylisted because it's an NSPanel and calling is_maximized() on it crashes
+    // Note: quick panes are excluded because they're NSPanels and calling is_maximized() on them crashes
     // See: https://github.com/tauri-apps/plugins-workspace/issues/1546
     #[cfg(desktop)]
     {
         app_builder = app_builder.plugin(
             tauri_plugin_window_state::Builder::new()
-                .with_state_flags(tauri_plugin_window_state::StateFlags::all())
-                .with_denylist(&["quick-pane"])
+                // Visibility is excluded so the main window stays hidden until the
+                // frontend calls `finish_startup`
+                .with_state_flags(
+                    tauri_plugin_window_state::StateFlags::all()
+                        - tauri_plugin_window_state::StateFlags::VISIBLE,
+                )
+                .with_filter(|label| {
+                    !commands::quick_pane::is_quick_pane_label(label)
+                        && label != commands::startup::SPLASH_WINDOW_LABEL
+                })
                 .build(),
         );
     }
@@ -60,33 +102,8 @@ pub fn run() {
     app_builder = app_builder
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_notification::init())
-        .plugin({
-            #[allow(unused_mut)]
-            let mut targets = vec![
-                // Always log to stdout for development
-                tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::Stdout),
-                // Log to system logs on macOS (appears in Console.app)
-                #[cfg(target_os = "macos")]
-                tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::LogDir {
-                    file_name: None,
-                }),
-            ];
-            // Log to webview console — excluded on Linux where the WebKitGTK webview
-            // doesn't exist during setup(), causing app.emit() to deadlock on the IPC socket.
-            #[cfg(not(target_os = "linux"))]
-            targets.push(tauri_plugin_log::Target::new(
-                tauri_plugin_log::TargetKind::Webview,
-            ));
-            tauri_plugin_log::Builder::new()
-                // Use Debug level in development, Info in production
-                .level(if cfg!(debug_assertions) {
-                    log::LevelFilter::Debug
-                } else {
-                    log::LevelFilter::Info
-                })
-                .targets(targets)
-                .build()
-        });
+        // Custom URL scheme, after single-instance so links reach the running app
+        .plugin(tauri_plugin_deep_link::init());
 
     // macOS: Add NSPanel plugin for native panel behavior
     #[cfg(target_os = "macos")]
@@ -96,13 +113,59 @@ pub fn run() {
 
     app_builder
         .plugin(tauri_plugin_fs::init())
-        .plugin(tauri_plugin_persisted_scope::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_os::init())
-        .setup(|app| {
+        // Logs every command, limits disk-heavy ones, keeps the ones that
+        // copy out or replace all the user's data behind an unlocked app, and
+        // counts and times them for `get_performance_metrics`
+        .invoke_handler(
+            middleware::Builder::new()
+                .with(middleware::Logging)
+                .with(
+                    middleware::RateLimit::default()
+                        .limit("save_emergency_data", 10, 2.0)
+                        .limit("save_preferences", 10, 2.0),
+                )
+                .with(middleware::Guard::unlocked(&[
+                    "create_backup",
+                    "restore_backup",
+                    "export_diagnostics",
+                ]))
+                .with(commands::performance::Timing)
+                .build(builder.invoke_handler()),
+        )
+        .on_menu_event(commands::menu::handle_menu_event)
+        .on_page_load(|webview, payload| {
+            if let PageLoadEvent::Started = payload.event() {
+                commands::event_queue::handle_page_load_started(webview.label());
+            }
+            if webview.label() == commands::window::MAIN_WINDOW_LABEL {
+                commands::performance::mark(match payload.event() {
+                    PageLoadEvent::Started => types::StartupMilestone::MainPageLoadStarted,
+                    PageLoadEvent::Finished => types::StartupMilestone::MainPageLoaded,
+                });
+            }
+        })
+        .setup(move |app| {
+            commands::performance::mark(types::StartupMilestone::SetupStarted);
+
+            // Must come before anything reads preferences, which may be encrypted
+            encryption::init(app.handle());
+
+            // Registered here rather than on the builder so log rotation can
+            // follow preferences; anything logged before this point is dropped
+            app.handle()
+                .plugin(commands::logging::log_plugin(app.handle()))?;
+            commands::logging::apply_saved_log_level(app.handle());
+            commands::crash_reports::install_panic_hook(app.handle());
+
             log::info!("Application starting up");
+
+            // Register typed events so they can be emitted/listened to via tauri-specta
+            builder.mount_events(app);
+
             log::debug!(
                 "App handle initialized for package: {}",
                 app.package_info().name
@@ -131,21 +194,147 @@ pub fn run() {
                 )?;
             }
 
+            // Register the shortcut that brings the main window to the front
+            #[cfg(desktop)]
+            {
+                let saved_shortcut =
+                    commands::preferences::load_activate_app_shortcut(app.handle());
+                let shortcut_to_register = saved_shortcut
+                    .as_deref()
+                    .unwrap_or(DEFAULT_ACTIVATE_APP_SHORTCUT);
+
+                log::info!("Registering activate-app shortcut: {shortcut_to_register}");
+                if let Err(e) = commands::window::register_activate_app_shortcut(
+                    app.handle(),
+                    shortcut_to_register,
+                ) {
+                    // Non-fatal: the shortcut may already be taken by another app
+                    log::error!("Failed to register activate-app shortcut: {e}");
+                }
+            }
+
+            // Remove data left behind by a move to another directory
+            data_dir::remove_moved_data(app.handle());
+
+            // Finish restoring a backup chosen before the last restart
+            commands::backup::apply_pending_restore(app.handle());
+
+            // Encrypt or decrypt existing data if the setting changed
+            if let Err(e) = encryption::migrate_local_data(app.handle()) {
+                log::error!("Failed to apply the encryption setting: {e}");
+            }
+
+            // Open the app database and apply any pending migrations
+            app.manage(db::open_app_database(app.handle())?);
+
+            // Open the disk cache and start evicting expired entries
+            app.manage(cache::open_app_cache(app.handle())?);
+            commands::cache::start_cache_eviction(app.handle());
+
+            // Remove temp workspaces left over from the last session
+            commands::temp_dirs::cleanup_stale_temp_dirs(app.handle());
+
+            // Set up the recovery store used by the emergency data commands.
+            // JSON files by default; use RecoveryBackend::Sqlite for apps that snapshot frequently.
+            let recovery_store = recovery_store::Builder::new()
+                .backend(recovery_store::RecoveryBackend::JsonFiles)
+                .build(app.handle())?;
+            app.manage(recovery_store);
+
+            // Commit document saves once they've been idle (needs both stores above)
+            commands::documents::start_document_autosave(app.handle());
+
+            // The main window starts hidden and is revealed by `finish_startup`
+            if commands::startup::SHOW_SPLASH_WINDOW {
+                if let Err(e) = commands::startup::show_splash_window(app.handle()) {
+                    log::error!("Failed to show splash window: {e}");
+                }
+            }
+            commands::startup::schedule_startup_timeout(app.handle());
+
+            // Give back access to files and folders granted in earlier sessions
+            commands::allowed_paths::init(app.handle());
+
+            // Launched from a Windows Jump List entry; the event is queued in
+            // `event_queue` until the main window calls `frontend_ready`
+            let args: Vec<String> = std::env::args().collect();
+            commands::jump_list::handle_launch_args(app.handle(), &args);
+
+            // Launched to open a file on Windows or Linux; also queued until `frontend_ready`
+            if let Ok(cwd) = std::env::current_dir() {
+                commands::file_associations::handle_launch_args(app.handle(), &args, &cwd);
+            }
+
+            // Links in the app's URL scheme; the launch link is queued until the
+            // target window calls `frontend_ready`
+            commands::deep_link::init(app.handle());
+
+            // Reopen the windows from the last session (hold Shift at launch to skip)
+            commands::session::restore_session(app.handle());
+
             // Create the quick pane window (hidden) - must be done on main thread
-            if let Err(e) = commands::quick_pane::init_quick_pane(app.handle()) {
+            if let Err(e) = commands::quick_pane::init_quick_pane(
+                app.handle(),
+                types::QuickPanePosition::Centered,
+            ) {
                 log::error!("Failed to create quick pane: {e}");
                 // Non-fatal: app can still run without quick pane
             }
 
+            // Check for updates in the background (interval set in preferences)
+            #[cfg(desktop)]
+            commands::updater::start_update_scheduler(app.handle());
+
+            // Broadcast sleep, wake and power source changes
+            #[cfg(desktop)]
+            commands::power::start_power_monitor(app.handle());
+
+            // Optional clipboard history (enable with WATCH_CLIPBOARD in
+            // commands/clipboard_history.rs)
+            #[cfg(desktop)]
+            if commands::clipboard_history::WATCH_CLIPBOARD {
+                commands::clipboard_history::start_clipboard_watcher(app.handle());
+            }
+
+            // Send queued analytics, if the user opted in
+            commands::analytics::start_analytics_flusher(app.handle());
+
+            // Carry on with downloads that were running when the app quit
+            commands::downloads::restore_downloads(app.handle());
+
+            // Background sync; disabled until a backend is set (see sync_engine.rs)
+            app.manage(sync_engine::Builder::new().build());
+            sync_engine::start_sync_engine(app.handle());
+
+            // Optional tray icon (enable with SHOW_TRAY_ICON in commands/tray.rs)
+            if commands::tray::SHOW_TRAY_ICON {
+                let tray = commands::tray::TrayConfig::new().tooltip("Tauri Template");
+                if let Err(e) = commands::tray::init_tray(app.handle(), tray) {
+                    log::error!("Failed to create tray icon: {e}");
+                }
+            }
+
+            // Additional quick panes can be registered here, e.g.:
+            // commands::quick_pane::register_quick_pane(
+            //     app.handle(),
+            //     commands::quick_pane::QuickPaneConfig::new("command-palette", "palette.html")
+            //         .size(640.0, 400.0),
+            // )?;
+
             // NOTE: Application menu is built from JavaScript for i18n support
             // See src/lib/menu.ts for the menu implementation
 
+            commands::performance::mark(types::StartupMilestone::SetupFinished);
             Ok(())
         })
-        .invoke_handler(builder.invoke_handler())
         .build(tauri::generate_context!())
-        .expect("error while building tauri application")
+        .unwrap_or_else(|e| {
+            commands::crash_reports::exit_with_error(&format!(
+                "error while building tauri application: {e}"
+            ))
+        })
         .run(|app_handle, event| match &event {
+            // Dirty windows confirm before closing (see `set_window_dirty`).
             // macOS: Hide the main window instead of quitting so the dock icon can reopen it
             // and the quick-pane shortcut works independently of the main window.
             // On other platforms, the close proceeds normally and the app exits.
@@ -153,9 +342,18 @@ pub fn run() {
                 label,
                 event: WindowEvent::CloseRequested { api, .. },
                 ..
-            } if label == "main" => {
+            } => {
+                // Windows with unsaved changes ask the frontend to confirm first
+                if commands::window::intercept_close_if_dirty(app_handle, label) {
+                    api.prevent_close();
+                    return;
+                }
+
+                // So the window state is saved at its normal size
+                commands::mini_window::handle_close_requested(app_handle, label);
+
                 #[cfg(target_os = "macos")]
-                {
+                if label == "main" {
                     api.prevent_close();
 
                     // Save window state before hiding
@@ -192,20 +390,33 @@ pub fn run() {
                 }
             }
 
+            // macOS: Files opened with the app from Finder or the Dock
+            #[cfg(target_os = "macos")]
+            RunEvent::Opened { urls } => {
+                commands::file_associations::handle_opened_urls(app_handle, urls);
+            }
+
             // Cleanup on actual exit (Cmd+Q, menu Quit, or window close on non-macOS).
             // RunEvent::Exit fires reliably before the process exits, unlike ExitRequested
             // which doesn't fire for Cmd+Q on macOS (tauri-apps/tauri#9198).
             RunEvent::Exit => {
                 log::info!("Application exiting — performing cleanup");
 
-                // Hide the quick-pane panel to prevent crashes during teardown
+                // Write documents still waiting for autosave
+                commands::documents::flush_pending_documents(app_handle);
+
+                // Save windows in mini mode at their normal size
+                commands::mini_window::handle_exit(app_handle);
+
+                // Remember open windows for the next launch
+                commands::session::save_session(app_handle);
+
+                // Tell peers on the network our services are gone
+                commands::discovery::shutdown();
+
+                // Hide quick pane panels to prevent crashes during teardown
                 #[cfg(target_os = "macos")]
-                {
-                    use tauri_nspanel::ManagerExt;
-                    if let Ok(panel) = app_handle.get_webview_panel("quick-pane") {
-                        panel.hide();
-                    }
-                }
+                commands::quick_pane::hide_all_quick_panes(app_handle);
 
                 // Unregister global shortcuts
                 #[cfg(desktop)]
@@ -219,6 +430,24 @@ pub fn run() {
                 log::info!("Cleanup complete");
             }
 
+            // Forward everything else as typed window events
+            RunEvent::WindowEvent { label, event, .. } => {
+                // Notification permission may have changed in the system settings,
+                // and the user has now seen anything counted as unread
+                if label == commands::window::MAIN_WINDOW_LABEL
+                    && matches!(event, WindowEvent::Focused(true))
+                {
+                    commands::notifications::refresh_notification_permission(app_handle);
+                    commands::taskbar::clear_auto_unread_count(app_handle);
+                }
+                if let WindowEvent::Destroyed = event {
+                    commands::event_queue::handle_window_destroyed(label);
+                    commands::mini_window::handle_window_destroyed(label);
+                    commands::window::handle_window_destroyed(app_handle, label);
+                }
+                window_events::forward_window_event(app_handle, label, event);
+            }
+
             _ => {}
         });
 }
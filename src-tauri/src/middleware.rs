@@ -0,0 +1,418 @@
+//! Middleware around every command invocation.
+//!
+//! The invoke handler generated by tauri-specta is wrapped with a chain of
+//! [`Middleware`], so cross-cutting concerns don't need code in each command:
+//!
+//! - [`Logging`] logs each command with the names of its arguments. Values
+//!   aren't logged, since they can hold secrets or note contents.
+//! - `commands::performance::Timing` records counts and times for
+//!   `get_performance_metrics`.
+//! - [`RateLimit`] rejects bursts of calls to some commands, e.g. from a
+//!   frontend effect stuck in a loop.
+//! - [`Guard`] rejects calls to some commands unless a check passes, e.g.
+//!   [`Guard::unlocked`] while the app is locked.
+//!
+//! `before` hooks run in order and can reject a call with an [`AppError`],
+//! which the frontend receives as the command's error. `after` hooks run in
+//! reverse order once the handler returns. Sync commands run inside the
+//! handler, so that's when they finish; async commands return once their
+//! task is spawned, so `after` only sees the cost of parsing their arguments.
+//!
+//! # Example
+//!
+//! ```ignore
+//! // In run()
+//! .invoke_handler(
+//!     middleware::Builder::new()
+//!         .with(middleware::Logging)
+//!         .with(middleware::RateLimit::default().limit("save_preferences", 10, 2.0))
+//!         .with(middleware::Guard::unlocked(&["create_backup"]))
+//!         .build(builder.invoke_handler()),
+//! )
+//! ```
+
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::ipc::{Invoke, InvokeBody};
+use tauri::Runtime;
+
+use crate::types::AppError;
+
+/// A command call, as seen by `before` hooks.
+pub struct Invocation<'a> {
+    /// The command name, e.g. "save_preferences"
+    pub command: &'a str,
+    /// Label of the window that called it
+    pub window: &'a str,
+    /// The arguments, keyed by their camelCase names. None for commands
+    /// called with a raw binary body.
+    pub args: Option<&'a Value>,
+}
+
+/// A hook around command calls. Both methods default to doing nothing.
+pub trait Middleware: Send + Sync {
+    /// Runs before the command. An error rejects the call without running it.
+    fn before(&self, _call: &Invocation) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// Runs after the command's handler returns, if `before` let it run.
+    fn after(&self, _command: &str, _window: &str, _elapsed: Duration) {}
+}
+
+/// Builds the middleware chain around the invoke handler.
+#[derive(Default)]
+pub struct Builder {
+    middleware: Vec<Box<dyn Middleware>>,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `middleware` to the end of the chain.
+    pub fn with(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Box::new(middleware));
+        self
+    }
+
+    /// Wraps `handler` (from `tauri_specta::Builder::invoke_handler`) with
+    /// the chain.
+    pub fn build<R: Runtime>(
+        self,
+        handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+    ) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+        let middleware = self.middleware;
+        move |invoke| {
+            let command = invoke.message.command().to_string();
+            let window = invoke.message.webview_ref().label().to_string();
+            let call = Invocation {
+                command: &command,
+                window: &window,
+                args: match invoke.message.payload() {
+                    InvokeBody::Json(args) => Some(args),
+                    InvokeBody::Raw(_) => None,
+                },
+            };
+            if let Err(error) = middleware.iter().try_for_each(|m| m.before(&call)) {
+                log::warn!("Command {command} from '{window}' rejected: {error}");
+                invoke.resolver.reject(error);
+                return true;
+            }
+
+            let start = Instant::now();
+            let handled = handler(invoke);
+            let elapsed = start.elapsed();
+            for m in middleware.iter().rev() {
+                m.after(&command, &window, elapsed);
+            }
+            handled
+        }
+    }
+}
+
+/// Logs each command and the names of its arguments at debug level, and how
+/// long its handler took at trace level. Argument values are never logged.
+pub struct Logging;
+
+/// The top-level argument names of `call`, e.g. `{service, value}`.
+fn describe_args(call: &Invocation) -> String {
+    match call.args {
+        Some(Value::Object(args)) => {
+            let keys: Vec<&str> = args.keys().map(String::as_str).collect();
+            format!("{{{}}}", keys.join(", "))
+        }
+        Some(_) => "{}".to_string(),
+        None => "<binary>".to_string(),
+    }
+}
+
+impl Middleware for Logging {
+    fn before(&self, call: &Invocation) -> Result<(), AppError> {
+        log::debug!(
+            "Command {} from '{}': {}",
+            call.command,
+            call.window,
+            describe_args(call)
+        );
+        Ok(())
+    }
+
+    fn after(&self, command: &str, _window: &str, elapsed: Duration) {
+        log::trace!("Command {command} handler returned in {elapsed:?}");
+    }
+}
+
+/// A token bucket: allows `burst` calls at once, then `per_second`.
+#[derive(Debug, Clone, Copy)]
+struct Limit {
+    burst: f64,
+    per_second: f64,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Limits how often each window can call some commands, rejecting calls over
+/// the limit with `AppError::TooManyRequests`. Each command and window has its
+/// own token bucket, so one window stuck in a loop doesn't block the others.
+///
+/// Calls over the limit are rejected, not debounced: holding a call back
+/// would leave the frontend awaiting it, and dropping it would lose data.
+/// Commands that should coalesce rapid calls do it themselves, like
+/// `save_document`.
+pub struct RateLimit {
+    limits: HashMap<&'static str, Limit>,
+    buckets: Mutex<HashMap<(String, String), Bucket>>,
+    clock: fn() -> Instant,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            limits: HashMap::new(),
+            buckets: Mutex::new(HashMap::new()),
+            clock: Instant::now,
+        }
+    }
+}
+
+impl RateLimit {
+    /// Allows `burst` calls to `command` in quick succession, refilling at
+    /// `per_second` calls per second. Only limit commands whose error type is
+    /// `AppError`, or has a matching `TooManyRequests` variant like
+    /// `RecoveryError`, since the frontend expects a rejection in the
+    /// command's own error type.
+    pub fn limit(mut self, command: &'static str, burst: u32, per_second: f64) -> Self {
+        self.limits.insert(
+            command,
+            Limit {
+                burst: f64::from(burst.max(1)),
+                per_second,
+            },
+        );
+        self
+    }
+
+    /// Takes a token from the bucket for `command` and `window`. Returns how
+    /// long until one is available if the bucket is empty.
+    fn take(&self, command: &str, window: &str, now: Instant) -> Result<(), Duration> {
+        let Some(limit) = self.limits.get(command) else {
+            return Ok(());
+        };
+        // A poisoned lock means a panic mid-update; let calls through
+        let Ok(mut buckets) = self.buckets.lock() else {
+            return Ok(());
+        };
+        let bucket = buckets
+            .entry((command.to_string(), window.to_string()))
+            .or_insert(Bucket {
+                tokens: limit.burst,
+                updated: now,
+            });
+        let refilled =
+            now.saturating_duration_since(bucket.updated).as_secs_f64() * limit.per_second;
+        bucket.tokens = (bucket.tokens + refilled).min(limit.burst);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else if limit.per_second > 0.0 {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / limit.per_second,
+            ))
+        } else {
+            Err(Duration::MAX)
+        }
+    }
+}
+
+impl Middleware for RateLimit {
+    fn before(&self, call: &Invocation) -> Result<(), AppError> {
+        self.take(call.command, call.window, (self.clock)())
+            .map_err(|retry_after| {
+                let retry_after_ms =
+                    u32::try_from(retry_after.as_millis().max(1)).unwrap_or(u32::MAX);
+                AppError::TooManyRequests {
+                    message: format!(
+                        "{} was called too often, try again in {retry_after_ms}ms",
+                        call.command
+                    ),
+                    retry_after_ms,
+                }
+            })
+    }
+}
+
+/// Set while the app is locked, e.g. behind a lock screen.
+static LOCKED: AtomicBool = AtomicBool::new(false);
+
+/// Locks or unlocks the app. While it's locked, [`Guard::unlocked`] rejects
+/// the commands it lists. Apps with a lock screen call this as it's shown and
+/// dismissed; the template never locks.
+pub fn set_locked(locked: bool) {
+    LOCKED.store(locked, Ordering::SeqCst);
+}
+
+fn is_locked() -> bool {
+    LOCKED.load(Ordering::SeqCst)
+}
+
+type Check = Box<dyn Fn(&Invocation) -> Result<(), AppError> + Send + Sync>;
+
+/// Runs `check` before each of `commands`, rejecting the call if it fails,
+/// e.g. to require the app to be unlocked or the call to come from the main
+/// window. As with [`RateLimit`], only guard commands whose error type is
+/// `AppError`.
+pub struct Guard {
+    commands: HashSet<&'static str>,
+    check: Check,
+}
+
+impl Guard {
+    pub fn new(
+        commands: &[&'static str],
+        check: impl Fn(&Invocation) -> Result<(), AppError> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            commands: commands.iter().copied().collect(),
+            check: Box::new(check),
+        }
+    }
+
+    /// Rejects `commands` with `AppError::Permission` while the app is locked
+    /// (see [`set_locked`]).
+    pub fn unlocked(commands: &[&'static str]) -> Self {
+        Self::new(commands, |call| {
+            if is_locked() {
+                Err(AppError::Permission {
+                    message: format!("Unlock the app to use {}", call.command),
+                })
+            } else {
+                Ok(())
+            }
+        })
+    }
+}
+
+impl Middleware for Guard {
+    fn before(&self, call: &Invocation) -> Result<(), AppError> {
+        if self.commands.contains(call.command) {
+            (self.check)(call)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::LazyLock;
+
+    fn call<'a>(command: &'a str, args: &'a Value) -> Invocation<'a> {
+        Invocation {
+            command,
+            window: "main",
+            args: Some(args),
+        }
+    }
+
+    #[test]
+    fn test_logging_omits_values() {
+        let args = json!({ "service": "github", "value": "abc" });
+        let described = describe_args(&call("store_secret", &args));
+        assert!(described.contains("service") && described.contains("value"));
+        assert!(!described.contains("github") && !described.contains("abc"));
+        assert_eq!(describe_args(&call("greet", &json!({}))), "{}");
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let limit = RateLimit::default().limit("save_preferences", 2, 4.0);
+        let start = Instant::now();
+        assert!(limit.take("save_preferences", "main", start).is_ok());
+        assert!(limit.take("save_preferences", "main", start).is_ok());
+        assert_eq!(
+            limit.take("save_preferences", "main", start),
+            Err(Duration::from_millis(250))
+        );
+        // Other windows and unlimited commands aren't affected
+        assert!(limit.take("save_preferences", "quick-pane", start).is_ok());
+        for _ in 0..10 {
+            assert!(limit.take("greet", "main", start).is_ok());
+        }
+        // One token refills every 250ms, up to the burst size
+        let later = start + Duration::from_millis(250);
+        assert!(limit.take("save_preferences", "main", later).is_ok());
+        assert!(limit.take("save_preferences", "main", later).is_err());
+        let much_later = later + Duration::from_secs(60);
+        assert!(limit.take("save_preferences", "main", much_later).is_ok());
+        assert!(limit.take("save_preferences", "main", much_later).is_ok());
+        assert!(limit.take("save_preferences", "main", much_later).is_err());
+    }
+
+    #[test]
+    fn test_rate_limit_error() {
+        static START: LazyLock<Instant> = LazyLock::new(Instant::now);
+        let limit = RateLimit {
+            clock: || *START,
+            ..RateLimit::default().limit("save_preferences", 1, 0.5)
+        };
+        let args = json!({});
+        assert!(limit.before(&call("save_preferences", &args)).is_ok());
+        match limit.before(&call("save_preferences", &args)) {
+            Err(AppError::TooManyRequests { retry_after_ms, .. }) => {
+                assert_eq!(retry_after_ms, 2000)
+            }
+            other => panic!("Expected TooManyRequests, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_guard() {
+        let guard = Guard::new(&["restore_backup"], |call| {
+            if call.window == "main" {
+                Ok(())
+            } else {
+                Err(AppError::Permission {
+                    message: "Only the main window can restore backups".to_string(),
+                })
+            }
+        });
+        let args = json!({});
+        let mut restore = call("restore_backup", &args);
+        assert!(guard.before(&restore).is_ok());
+        restore.window = "quick-pane";
+        assert!(guard.before(&restore).is_err());
+        let other = Invocation {
+            window: "quick-pane",
+            ..call("greet", &args)
+        };
+        assert!(guard.before(&other).is_ok());
+    }
+
+    #[test]
+    fn test_guard_unlocked() {
+        let guard = Guard::unlocked(&["create_backup"]);
+        let args = json!({});
+        assert!(guard.before(&call("create_backup", &args)).is_ok());
+
+        set_locked(true);
+        let locked = guard.before(&call("create_backup", &args));
+        let other = guard.before(&call("greet", &args));
+        set_locked(false);
+        assert!(matches!(locked, Err(AppError::Permission { .. })));
+        assert!(other.is_ok());
+        assert!(guard.before(&call("create_backup", &args)).is_ok());
+    }
+}
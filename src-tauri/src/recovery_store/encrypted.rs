@@ -0,0 +1,56 @@
+//! Encryption wrapper around a recovery backend.
+//!
+//! Values are encrypted before they reach the inner store when local data
+//! encryption is on, and decrypted on load whenever they're encrypted, so
+//! entries written before the setting changed stay readable.
+
+use super::{io_error, RecoveryEntry, RecoveryStore, SharedRecoveryStore};
+use crate::encryption::{decrypt_if_encrypted, encrypt_if_enabled};
+use crate::types::RecoveryError;
+
+/// Encrypts values on their way into `inner`.
+pub struct EncryptedRecoveryStore {
+    inner: SharedRecoveryStore,
+}
+
+impl EncryptedRecoveryStore {
+    pub fn new(inner: SharedRecoveryStore) -> Self {
+        Self { inner }
+    }
+}
+
+impl RecoveryStore for EncryptedRecoveryStore {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn save(&self, key: &str, json: &str) -> Result<(), RecoveryError> {
+        let data = encrypt_if_enabled(json).map_err(io_error)?;
+        self.inner.save(key, &data)
+    }
+
+    fn load(&self, key: &str) -> Result<String, RecoveryError> {
+        decrypt_if_encrypted(self.inner.load(key)?).map_err(io_error)
+    }
+
+    fn remove(&self, key: &str) -> Result<(), RecoveryError> {
+        self.inner.remove(key)
+    }
+
+    fn remove_older_than(&self, cutoff_secs: u64) -> Result<u32, RecoveryError> {
+        self.inner.remove_older_than(cutoff_secs)
+    }
+
+    fn entries(&self) -> Result<Vec<RecoveryEntry>, RecoveryError> {
+        self.inner
+            .entries()?
+            .into_iter()
+            .map(|entry| {
+                Ok(RecoveryEntry {
+                    json: decrypt_if_encrypted(entry.json).map_err(io_error)?,
+                    ..entry
+                })
+            })
+            .collect()
+    }
+}
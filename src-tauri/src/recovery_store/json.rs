@@ -0,0 +1,178 @@
+//! JSON file recovery backend (default).
+//!
+//! Each key is stored as `<key>.json` in the recovery directory.
+
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+
+use super::{io_error, RecoveryEntry, RecoveryStore};
+use crate::commands::files::write_atomic;
+use crate::types::RecoveryError;
+
+/// Stores each recovery key as a pretty-printed JSON file.
+pub struct JsonFileRecoveryStore {
+    dir: PathBuf,
+}
+
+impl JsonFileRecoveryStore {
+    /// Creates a store rooted at `dir`. The directory must already exist.
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+}
+
+impl RecoveryStore for JsonFileRecoveryStore {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn save(&self, key: &str, json: &str) -> Result<(), RecoveryError> {
+        let file_path = self.path_for(key);
+
+        write_atomic(&file_path, json.as_bytes()).map_err(|e| {
+            log::error!("Failed to write emergency data file: {e}");
+            io_error(e)
+        })?;
+
+        log::debug!("Wrote recovery file {file_path:?}");
+        Ok(())
+    }
+
+    fn load(&self, key: &str) -> Result<String, RecoveryError> {
+        let file_path = self.path_for(key);
+
+        if !file_path.exists() {
+            log::info!("Recovery file not found: {file_path:?}");
+            return Err(RecoveryError::FileNotFound);
+        }
+
+        std::fs::read_to_string(&file_path).map_err(|e| {
+            log::error!("Failed to read recovery file: {e}");
+            io_error(e)
+        })
+    }
+
+    fn remove(&self, key: &str) -> Result<(), RecoveryError> {
+        match std::fs::remove_file(self.path_for(key)) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => {
+                log::error!("Failed to remove recovery file: {e}");
+                Err(io_error(e))
+            }
+        }
+    }
+
+    fn remove_older_than(&self, cutoff_secs: u64) -> Result<u32, RecoveryError> {
+        let mut removed_count = 0;
+
+        // Read directory and check each file
+        let entries = std::fs::read_dir(&self.dir).map_err(|e| {
+            log::error!("Failed to read recovery directory: {e}");
+            io_error(e)
+        })?;
+
+        for entry in entries {
+            let entry = match entry {
+                Ok(e) => e,
+                Err(e) => {
+                    log::warn!("Failed to read directory entry: {e}");
+                    continue;
+                }
+            };
+
+            let path = entry.path();
+
+            // Only process JSON files
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+
+            // Check file modification time
+            let metadata = match std::fs::metadata(&path) {
+                Ok(m) => m,
+                Err(e) => {
+                    log::warn!("Failed to get file metadata: {e}");
+                    continue;
+                }
+            };
+
+            let modified = match metadata.modified() {
+                Ok(m) => m,
+                Err(e) => {
+                    log::warn!("Failed to get file modification time: {e}");
+                    continue;
+                }
+            };
+
+            let modified_secs = match modified.duration_since(UNIX_EPOCH) {
+                Ok(d) => d.as_secs(),
+                Err(e) => {
+                    log::warn!("Failed to convert modification time: {e}");
+                    continue;
+                }
+            };
+
+            if modified_secs < cutoff_secs {
+                match std::fs::remove_file(&path) {
+                    Ok(_) => {
+                        log::info!("Removed old recovery file: {path:?}");
+                        removed_count += 1;
+                    }
+                    Err(e) => {
+                        log::warn!("Failed to remove old recovery file: {e}");
+                    }
+                }
+            }
+        }
+
+        Ok(removed_count)
+    }
+
+    fn entries(&self) -> Result<Vec<RecoveryEntry>, RecoveryError> {
+        let dir_entries = std::fs::read_dir(&self.dir).map_err(|e| {
+            log::error!("Failed to read recovery directory: {e}");
+            io_error(e)
+        })?;
+
+        let mut entries = Vec::new();
+        for dir_entry in dir_entries {
+            let path = dir_entry.map_err(io_error)?.path();
+
+            // Only JSON files are entries; this skips in-flight `.tmp` writes
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let Some(key) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+
+            let json = match std::fs::read_to_string(&path) {
+                Ok(json) => json,
+                // Removed since the directory was listed
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => {
+                    log::error!("Failed to read recovery file: {e}");
+                    return Err(io_error(e));
+                }
+            };
+            let modified_secs = std::fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |d| d.as_secs());
+
+            entries.push(RecoveryEntry {
+                key: key.to_string(),
+                json,
+                modified_secs,
+            });
+        }
+
+        Ok(entries)
+    }
+}
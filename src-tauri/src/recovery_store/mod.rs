@@ -0,0 +1,138 @@
+//! Pluggable storage backends for emergency recovery data.
+//!
+//! Recovery commands talk to a [`RecoveryStore`] held in managed state rather than
+//! touching the filesystem directly. Two backends are provided:
+//!
+//! - [`RecoveryBackend::JsonFiles`] (default): one pretty-printed JSON file per key
+//!   under `recovery/`, written atomically.
+//! - [`RecoveryBackend::Sqlite`]: a single `recovery/recovery.db` database. Better for
+//!   apps that snapshot frequently, as it avoids filesystem churn and gives
+//!   transactional writes.
+//!
+//! # Example
+//!
+//! ```ignore
+//! // In setup()
+//! let store = recovery_store::Builder::new()
+//!     .backend(recovery_store::RecoveryBackend::Sqlite)
+//!     .build(app.handle())?;
+//! app.manage(store);
+//! ```
+
+mod encrypted;
+mod json;
+mod sqlite;
+
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::types::{AppError, RecoveryError};
+
+pub use encrypted::EncryptedRecoveryStore;
+pub use json::JsonFileRecoveryStore;
+pub use sqlite::SqliteRecoveryStore;
+
+/// Storage backend for recovery snapshots.
+///
+/// Keys are pre-validated filenames (see `validate_filename`) and values are
+/// serialized JSON strings. Implementations must be safe to call from any thread.
+pub trait RecoveryStore: Send + Sync {
+    /// Short backend name used in log messages.
+    fn name(&self) -> &'static str;
+
+    /// Stores `json` under `key`, replacing any existing value.
+    fn save(&self, key: &str, json: &str) -> Result<(), RecoveryError>;
+
+    /// Loads the value stored under `key`.
+    /// Returns `RecoveryError::FileNotFound` if nothing is stored for the key.
+    fn load(&self, key: &str) -> Result<String, RecoveryError>;
+
+    /// Removes the value stored under `key`. Succeeds if there was none.
+    fn remove(&self, key: &str) -> Result<(), RecoveryError>;
+
+    /// Removes entries last modified before `cutoff_secs` (Unix seconds).
+    /// Returns the number of removed entries.
+    fn remove_older_than(&self, cutoff_secs: u64) -> Result<u32, RecoveryError>;
+
+    /// Returns every stored entry. Safe to call while saves are running: no
+    /// value is ever read half-written.
+    fn entries(&self) -> Result<Vec<RecoveryEntry>, RecoveryError>;
+}
+
+/// A stored recovery value, as returned by [`RecoveryStore::entries`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoveryEntry {
+    pub key: String,
+    pub json: String,
+    /// Last modification time in Unix seconds
+    pub modified_secs: u64,
+}
+
+/// Recovery store as held in Tauri managed state.
+pub type SharedRecoveryStore = Box<dyn RecoveryStore>;
+
+/// Which storage backend to use for recovery data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RecoveryBackend {
+    /// One JSON file per key in the recovery directory
+    #[default]
+    JsonFiles,
+    /// A single SQLite database in the recovery directory
+    #[allow(dead_code)] // Chosen in setup() by apps that want it
+    Sqlite,
+}
+
+/// Builds the recovery store used by the recovery commands.
+#[derive(Debug, Default)]
+pub struct Builder {
+    backend: RecoveryBackend,
+}
+
+impl Builder {
+    /// Creates a builder using the default JSON file backend.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Selects the storage backend.
+    pub fn backend(mut self, backend: RecoveryBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Creates the recovery directory and opens the selected backend, wrapped
+    /// to encrypt values when local data encryption is on.
+    pub fn build(self, app: &AppHandle) -> Result<SharedRecoveryStore, String> {
+        let recovery_dir = get_recovery_dir(app)?;
+
+        let store: SharedRecoveryStore = match self.backend {
+            RecoveryBackend::JsonFiles => Box::new(JsonFileRecoveryStore::new(recovery_dir)),
+            RecoveryBackend::Sqlite => Box::new(SqliteRecoveryStore::open(
+                &recovery_dir.join("recovery.db"),
+            )?),
+        };
+
+        log::info!("Recovery store initialized ({} backend)", store.name());
+        Ok(Box::new(EncryptedRecoveryStore::new(store)))
+    }
+}
+
+/// Gets the path to the recovery directory, creating it if necessary.
+pub fn get_recovery_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = crate::data_dir::app_data_dir(app)?;
+
+    let recovery_dir = app_data_dir.join("recovery");
+
+    // Ensure the recovery directory exists
+    std::fs::create_dir_all(&recovery_dir)
+        .map_err(|e| AppError::io(format!("Failed to create recovery directory: {e}")))?;
+
+    Ok(recovery_dir)
+}
+
+/// Converts an I/O or database error into a `RecoveryError::IoError`.
+pub(crate) fn io_error(e: impl std::fmt::Display) -> RecoveryError {
+    RecoveryError::IoError {
+        message: e.to_string(),
+    }
+}
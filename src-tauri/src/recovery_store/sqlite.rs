@@ -0,0 +1,240 @@
+//! SQLite recovery backend.
+//!
+//! All keys live in a single `recovery` table, so frequent snapshots are cheap
+//! upserts inside a transaction instead of temp-file + rename cycles.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{io_error, RecoveryEntry, RecoveryStore};
+use crate::types::RecoveryError;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS recovery (
+        key         TEXT PRIMARY KEY NOT NULL,
+        data        TEXT NOT NULL,
+        modified_at INTEGER NOT NULL
+    );
+";
+
+/// Stores recovery data in a SQLite database.
+pub struct SqliteRecoveryStore {
+    conn: Mutex<Connection>,
+    /// Where `entries` writes its temporary snapshot
+    snapshot_dir: PathBuf,
+}
+
+impl SqliteRecoveryStore {
+    /// Opens (or creates) the database at `path` and ensures the schema exists.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let conn =
+            Connection::open(path).map_err(|e| format!("Failed to open recovery database: {e}"))?;
+        // WAL keeps frequent small writes from blocking reads
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(|e| format!("Failed to configure recovery database: {e}"))?;
+        let snapshot_dir = path
+            .parent()
+            .map_or_else(std::env::temp_dir, Path::to_path_buf);
+        Self::with_connection(conn, snapshot_dir)
+    }
+
+    /// Creates a store backed by an in-memory database.
+    #[cfg(test)]
+    fn open_in_memory() -> Result<Self, String> {
+        let conn = Connection::open_in_memory()
+            .map_err(|e| format!("Failed to open recovery database: {e}"))?;
+        Self::with_connection(conn, std::env::temp_dir())
+    }
+
+    fn with_connection(conn: Connection, snapshot_dir: PathBuf) -> Result<Self, String> {
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| format!("Failed to initialize recovery database schema: {e}"))?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+            snapshot_dir,
+        })
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>, RecoveryError> {
+        self.conn.lock().map_err(|e| RecoveryError::IoError {
+            message: format!("Failed to lock recovery database: {e}"),
+        })
+    }
+}
+
+impl RecoveryStore for SqliteRecoveryStore {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn save(&self, key: &str, json: &str) -> Result<(), RecoveryError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(io_error)?
+            .as_secs() as i64;
+
+        self.lock()?
+            .execute(
+                "INSERT INTO recovery (key, data, modified_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(key) DO UPDATE SET data = excluded.data, modified_at = excluded.modified_at",
+                params![key, json, now],
+            )
+            .map_err(|e| {
+                log::error!("Failed to write recovery row: {e}");
+                io_error(e)
+            })?;
+
+        log::debug!("Wrote recovery row for key {key}");
+        Ok(())
+    }
+
+    fn load(&self, key: &str) -> Result<String, RecoveryError> {
+        self.lock()?
+            .query_row(
+                "SELECT data FROM recovery WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| {
+                log::error!("Failed to read recovery row: {e}");
+                io_error(e)
+            })?
+            .ok_or_else(|| {
+                log::info!("Recovery row not found: {key}");
+                RecoveryError::FileNotFound
+            })
+    }
+
+    fn remove(&self, key: &str) -> Result<(), RecoveryError> {
+        self.lock()?
+            .execute("DELETE FROM recovery WHERE key = ?1", params![key])
+            .map_err(|e| {
+                log::error!("Failed to remove recovery row: {e}");
+                io_error(e)
+            })?;
+        Ok(())
+    }
+
+    fn remove_older_than(&self, cutoff_secs: u64) -> Result<u32, RecoveryError> {
+        let removed = self
+            .lock()?
+            .execute(
+                "DELETE FROM recovery WHERE modified_at < ?1",
+                params![cutoff_secs as i64],
+            )
+            .map_err(|e| {
+                log::error!("Failed to remove old recovery rows: {e}");
+                io_error(e)
+            })?;
+
+        Ok(removed as u32)
+    }
+
+    /// Copies the database with `VACUUM INTO` (like `Database::snapshot`) and
+    /// reads the copy, so the rows come from one consistent state and saves
+    /// are only blocked for the copy, not while the rows are read.
+    fn entries(&self) -> Result<Vec<RecoveryEntry>, RecoveryError> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(io_error)?
+            .as_nanos();
+        let snapshot_path = self.snapshot_dir.join(format!(
+            "recovery-snapshot-{}-{nanos}.db",
+            std::process::id()
+        ));
+
+        self.lock()?
+            .execute("VACUUM INTO ?1", [snapshot_path.to_string_lossy()])
+            .map_err(|e| {
+                log::error!("Failed to snapshot recovery database: {e}");
+                io_error(e)
+            })?;
+
+        let result = read_entries(&snapshot_path);
+        if let Err(e) = std::fs::remove_file(&snapshot_path) {
+            log::warn!("Failed to remove recovery database snapshot: {e}");
+        }
+        result.map_err(|e| {
+            log::error!("Failed to read recovery database snapshot: {e}");
+            io_error(e)
+        })
+    }
+}
+
+/// Reads every row from the snapshot at `path`.
+fn read_entries(path: &Path) -> rusqlite::Result<Vec<RecoveryEntry>> {
+    let conn = Connection::open(path)?;
+    let mut stmt = conn.prepare("SELECT key, data, modified_at FROM recovery ORDER BY key")?;
+    let rows = stmt.query_map([], |row| {
+        Ok(RecoveryEntry {
+            key: row.get(0)?,
+            json: row.get(1)?,
+            modified_secs: row.get::<_, i64>(2)?.max(0) as u64,
+        })
+    })?;
+    rows.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let store = SqliteRecoveryStore::open_in_memory().unwrap();
+        store.save("draft", r#"{"a":1}"#).unwrap();
+        store.save("draft", r#"{"a":2}"#).unwrap();
+        assert_eq!(store.load("draft").unwrap(), r#"{"a":2}"#);
+    }
+
+    #[test]
+    fn test_load_missing_key_is_not_found() {
+        let store = SqliteRecoveryStore::open_in_memory().unwrap();
+        assert!(matches!(
+            store.load("missing"),
+            Err(RecoveryError::FileNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_remove() {
+        let store = SqliteRecoveryStore::open_in_memory().unwrap();
+        store.save("draft", "{}").unwrap();
+        store.remove("draft").unwrap();
+        assert!(store.load("draft").is_err());
+        // Removing a missing key is fine
+        store.remove("draft").unwrap();
+    }
+
+    #[test]
+    fn test_remove_older_than() {
+        let store = SqliteRecoveryStore::open_in_memory().unwrap();
+        store.save("old", "{}").unwrap();
+
+        // Nothing is older than the epoch
+        assert_eq!(store.remove_older_than(0).unwrap(), 0);
+        // Everything is older than the far future
+        assert_eq!(store.remove_older_than(u64::MAX / 2).unwrap(), 1);
+        assert!(store.load("old").is_err());
+    }
+
+    #[test]
+    fn test_entries_reads_a_snapshot() {
+        let store = SqliteRecoveryStore::open_in_memory().unwrap();
+        store.save("b", r#"{"b":1}"#).unwrap();
+        store.save("a", r#"{"a":1}"#).unwrap();
+
+        let entries = store.entries().unwrap();
+        let keys: Vec<_> = entries.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(keys, ["a", "b"]);
+        assert_eq!(entries[0].json, r#"{"a":1}"#);
+        assert!(entries[0].modified_secs > 0);
+
+        // Later snapshots see later saves
+        store.save("c", "{}").unwrap();
+        assert_eq!(store.entries().unwrap().len(), 3);
+    }
+}
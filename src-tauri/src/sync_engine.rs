@@ -0,0 +1,520 @@
+//! Background sync between local data and a remote backend.
+//!
+//! The template provides the orchestration; apps provide a [`SyncBackend`]
+//! that talks to their server. Local changes are queued in SQLite
+//! (`db::sync`) with [`queue_change`] or the `queue_sync_change` command, and
+//! a background thread syncs shortly after each change and every `interval`:
+//!
+//! 1. Pull remote changes since the last cursor and apply them with the
+//!    `on_remote_change` hook.
+//! 2. Push pending local changes, oldest first.
+//!
+//! A remote change to a record with a pending local change is a conflict, as
+//! is a push the backend rejects because the record changed remotely. The
+//! `on_conflict` hook decides what wins (the remote change, by default).
+//! Failed syncs are retried with exponential backoff, and every status change
+//! is broadcast as `sync-status`.
+//!
+//! Without a backend the engine is disabled, but changes are still queued.
+//!
+//! # Example
+//!
+//! ```ignore
+//! // In setup()
+//! let engine = sync_engine::Builder::new()
+//!     .backend(MyServerBackend::new())
+//!     .on_remote_change(|app, change| apply_to_notes(app, change))
+//!     .on_conflict(|_local, _remote| sync_engine::Resolution::KeepLocal)
+//!     .build();
+//! app.manage(engine);
+//! sync_engine::start_sync_engine(app.handle());
+//! ```
+
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tauri_specta::Event;
+
+use crate::db::sync as queue;
+use crate::db::{now_millis, Database};
+use crate::types::{AppError, SyncChange, SyncOperation, SyncState, SyncStatus};
+
+pub use crate::db::sync::PendingChange;
+
+/// How often to sync when nothing has changed locally.
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Wait after a local change before syncing, so a burst of edits goes in one
+/// push.
+const PUSH_DELAY: Duration = Duration::from_secs(2);
+
+const MIN_RETRY_DELAY: Duration = Duration::from_secs(5);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(10 * 60);
+
+/// Changes sent to the backend per `push` call.
+const PUSH_BATCH_SIZE: u32 = 100;
+
+/// `sync_state` key for the cursor returned by the last pull.
+const CURSOR_KEY: &str = "cursor";
+
+/// A change made remotely, from [`SyncBackend::pull`] or a push conflict.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteChange {
+    pub collection: String,
+    pub record_id: String,
+    pub operation: SyncOperation,
+    /// The record's remote state, for `Upsert`
+    pub payload: Option<serde_json::Value>,
+    /// The record's remote version, if the backend tracks them
+    pub version: Option<String>,
+}
+
+/// A page of remote changes.
+#[derive(Debug, Clone, Default)]
+pub struct PullResult {
+    pub changes: Vec<RemoteChange>,
+    /// Where the next pull starts; None keeps the previous cursor
+    pub cursor: Option<String>,
+    /// Whether to pull again straight away for the next page
+    pub has_more: bool,
+}
+
+/// What the backend did with one pushed change.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PushOutcome {
+    Accepted,
+    /// Rejected because the record changed remotely since the change's
+    /// `base_version`
+    Conflict(RemoteChange),
+}
+
+/// How to settle a conflict between a pending local change and a remote one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resolution {
+    /// Push the local change over the remote one
+    KeepLocal,
+    /// Drop the local change and apply the remote one
+    KeepRemote,
+    /// Apply this record locally and push it
+    Merged(serde_json::Value),
+}
+
+/// A remote server to sync with.
+///
+/// Methods run on the sync thread, so they can block (use
+/// `tauri::async_runtime::block_on` for async HTTP clients). Errors fail the
+/// sync, which is retried with backoff.
+pub trait SyncBackend: Send + Sync {
+    /// Short backend name used in log messages.
+    fn name(&self) -> &'static str;
+
+    /// Fetches changes made remotely since `cursor`, or all records when
+    /// None.
+    fn pull(&self, app: &AppHandle, cursor: Option<&str>) -> Result<PullResult, AppError>;
+
+    /// Sends local changes, oldest first. Returns one outcome per change, in
+    /// the same order.
+    fn push(
+        &self,
+        app: &AppHandle,
+        changes: &[PendingChange],
+    ) -> Result<Vec<PushOutcome>, AppError>;
+}
+
+type RemoteChangeHook =
+    Box<dyn Fn(&AppHandle, &RemoteChange) -> Result<(), AppError> + Send + Sync>;
+type ConflictHook = Box<dyn Fn(&PendingChange, &RemoteChange) -> Resolution + Send + Sync>;
+
+/// Builds the sync engine held in managed state.
+pub struct Builder {
+    backend: Option<Box<dyn SyncBackend>>,
+    on_remote_change: Option<RemoteChangeHook>,
+    on_conflict: Option<ConflictHook>,
+    interval: Duration,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            backend: None,
+            on_remote_change: None,
+            on_conflict: None,
+            interval: DEFAULT_INTERVAL,
+        }
+    }
+}
+
+impl Builder {
+    /// Creates a builder with no backend, which gives a disabled engine.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the server to sync with.
+    pub fn backend(mut self, backend: impl SyncBackend + 'static) -> Self {
+        self.backend = Some(Box::new(backend));
+        self
+    }
+
+    /// Applies a remote change to local data. Without this hook, remote
+    /// changes are logged and dropped.
+    pub fn on_remote_change(
+        mut self,
+        hook: impl Fn(&AppHandle, &RemoteChange) -> Result<(), AppError> + Send + Sync + 'static,
+    ) -> Self {
+        self.on_remote_change = Some(Box::new(hook));
+        self
+    }
+
+    /// Decides conflicts. The remote change wins without this hook.
+    pub fn on_conflict(
+        mut self,
+        hook: impl Fn(&PendingChange, &RemoteChange) -> Resolution + Send + Sync + 'static,
+    ) -> Self {
+        self.on_conflict = Some(Box::new(hook));
+        self
+    }
+
+    /// Sets how often to sync when nothing has changed locally.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn build(self) -> SyncEngine {
+        let state = if self.backend.is_some() {
+            SyncState::Idle
+        } else {
+            SyncState::Disabled
+        };
+        SyncEngine {
+            backend: self.backend,
+            on_remote_change: self.on_remote_change.unwrap_or_else(|| {
+                Box::new(|_, change| {
+                    log::warn!(
+                        "No on_remote_change hook; dropping remote change to {}/{}",
+                        change.collection,
+                        change.record_id
+                    );
+                    Ok(())
+                })
+            }),
+            on_conflict: self
+                .on_conflict
+                .unwrap_or_else(|| Box::new(|_, _| Resolution::KeepRemote)),
+            interval: self.interval,
+            schedule: Mutex::new(Schedule {
+                next_run: Instant::now(),
+                failures: 0,
+                status: SyncStatus {
+                    state,
+                    pending_changes: 0,
+                    last_synced_at: None,
+                    last_error: None,
+                    next_attempt_at: None,
+                },
+            }),
+            wake: Condvar::new(),
+        }
+    }
+}
+
+struct Schedule {
+    next_run: Instant,
+    /// Failed syncs in a row, for backoff
+    failures: u32,
+    status: SyncStatus,
+}
+
+/// The sync engine, as held in Tauri managed state.
+pub struct SyncEngine {
+    backend: Option<Box<dyn SyncBackend>>,
+    on_remote_change: RemoteChangeHook,
+    on_conflict: ConflictHook,
+    interval: Duration,
+    schedule: Mutex<Schedule>,
+    wake: Condvar,
+}
+
+impl SyncEngine {
+    /// Whether a backend is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.backend.is_some()
+    }
+
+    pub fn status(&self) -> Result<SyncStatus, AppError> {
+        Ok(self.lock()?.status.clone())
+    }
+
+    /// Schedules a sync within `delay`, unless one is due sooner.
+    pub fn request_sync(&self, delay: Duration) -> Result<(), AppError> {
+        let mut schedule = self.lock()?;
+        let at = Instant::now() + delay;
+        if at < schedule.next_run {
+            schedule.next_run = at;
+            self.wake.notify_one();
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Schedule>, AppError> {
+        self.schedule
+            .lock()
+            .map_err(|e| AppError::internal(format!("Failed to lock sync state: {e}")))
+    }
+
+    /// Changes the status and broadcasts it as `sync-status`.
+    fn update_status(&self, app: &AppHandle, update: impl FnOnce(&mut Schedule)) {
+        let status = match self.lock() {
+            Ok(mut schedule) => {
+                update(&mut schedule);
+                schedule.status.clone()
+            }
+            Err(e) => {
+                log::error!("{e}");
+                return;
+            }
+        };
+        if let Err(e) = status.emit(app) {
+            log::warn!("Failed to emit sync-status: {e}");
+        }
+    }
+
+    /// Blocks until the next sync is due.
+    fn wait_until_due(&self) {
+        let Ok(mut schedule) = self.lock() else {
+            return;
+        };
+        loop {
+            let now = Instant::now();
+            if now >= schedule.next_run {
+                return;
+            }
+            let timeout = schedule.next_run - now;
+            schedule = match self.wake.wait_timeout(schedule, timeout) {
+                Ok((schedule, _)) => schedule,
+                Err(_) => return,
+            };
+        }
+    }
+
+    /// Settles a conflict with the `on_conflict` hook.
+    fn resolve(
+        &self,
+        app: &AppHandle,
+        database: &Database,
+        local: &PendingChange,
+        remote: &RemoteChange,
+    ) -> Result<(), AppError> {
+        let resolution = (self.on_conflict)(local, remote);
+        log::info!(
+            "Sync conflict on {}/{}: {resolution:?}",
+            remote.collection,
+            remote.record_id
+        );
+        match resolution {
+            Resolution::KeepLocal => database.with_connection(|conn| {
+                queue::rebase(conn, local.id, remote.version.as_deref(), None)
+            }),
+            Resolution::KeepRemote => {
+                (self.on_remote_change)(app, remote)?;
+                database.with_connection(|conn| queue::remove(conn, local.id))
+            }
+            Resolution::Merged(payload) => {
+                let merged = RemoteChange {
+                    operation: SyncOperation::Upsert,
+                    payload: Some(payload.clone()),
+                    ..remote.clone()
+                };
+                (self.on_remote_change)(app, &merged)?;
+                database.with_connection(|conn| {
+                    queue::rebase(conn, local.id, remote.version.as_deref(), Some(&payload))
+                })
+            }
+        }
+    }
+
+    /// Pulls, then pushes. Returns whether conflicts left changes to push.
+    fn sync_once(
+        &self,
+        app: &AppHandle,
+        backend: &dyn SyncBackend,
+        database: &Database,
+    ) -> Result<bool, AppError> {
+        // Pull first, so conflicting remote edits are settled before pushing
+        // over them
+        loop {
+            let cursor = database.with_connection(|conn| queue::get_state(conn, CURSOR_KEY))?;
+            let page = backend.pull(app, cursor.as_deref())?;
+            for remote in &page.changes {
+                let local = database.with_connection(|conn| {
+                    queue::pending_for_record(conn, &remote.collection, &remote.record_id)
+                })?;
+                match local {
+                    Some(local) => self.resolve(app, database, &local, remote)?,
+                    None => (self.on_remote_change)(app, remote)?,
+                }
+            }
+            if let Some(cursor) = &page.cursor {
+                database.with_connection(|conn| queue::set_state(conn, CURSOR_KEY, cursor))?;
+            }
+            if !page.has_more {
+                break;
+            }
+        }
+
+        loop {
+            let batch = database.with_connection(|conn| queue::pending(conn, PUSH_BATCH_SIZE))?;
+            if batch.is_empty() {
+                return Ok(false);
+            }
+            let outcomes = match backend.push(app, &batch) {
+                Ok(outcomes) => outcomes,
+                Err(e) => {
+                    let ids: Vec<i64> = batch.iter().map(|change| change.id).collect();
+                    database.with_connection(|conn| queue::record_attempts(conn, &ids))?;
+                    return Err(e);
+                }
+            };
+            if outcomes.len() != batch.len() {
+                return Err(AppError::internal(format!(
+                    "Backend returned {} results for {} changes",
+                    outcomes.len(),
+                    batch.len()
+                )));
+            }
+
+            let mut conflicts = false;
+            for (local, outcome) in batch.iter().zip(outcomes) {
+                match outcome {
+                    // Does nothing if the change was replaced while pushing
+                    PushOutcome::Accepted => {
+                        database.with_connection(|conn| queue::remove(conn, local.id))?
+                    }
+                    PushOutcome::Conflict(remote) => {
+                        conflicts = true;
+                        self.resolve(app, database, local, &remote)?;
+                    }
+                }
+            }
+            // Rebased changes go in the next sync rather than looping here
+            if conflicts {
+                return Ok(true);
+            }
+            if batch.len() < PUSH_BATCH_SIZE as usize {
+                return Ok(false);
+            }
+        }
+    }
+}
+
+/// The wait before retrying after `failures` failed syncs in a row: doubled
+/// each time, up to `MAX_RETRY_DELAY`.
+fn retry_delay(failures: u32) -> Duration {
+    let doublings = failures.saturating_sub(1).min(16);
+    MIN_RETRY_DELAY
+        .saturating_mul(1 << doublings)
+        .min(MAX_RETRY_DELAY)
+}
+
+fn pending_count(app: &AppHandle) -> Result<u32, AppError> {
+    app.state::<Database>()
+        .with_connection(|conn| queue::count(conn))
+}
+
+/// Queues a local change for the next sync, which starts shortly after.
+pub fn queue_change(app: &AppHandle, change: &SyncChange) -> Result<(), AppError> {
+    let pending = app.state::<Database>().with_connection(|conn| {
+        queue::enqueue(conn, change)?;
+        queue::count(conn)
+    })?;
+    let engine = app.state::<SyncEngine>();
+    engine.update_status(app, |schedule| schedule.status.pending_changes = pending);
+    engine.request_sync(PUSH_DELAY)
+}
+
+/// Starts syncing in the background, if a backend is configured. Needs the
+/// database and the engine in managed state.
+pub fn start_sync_engine(app: &AppHandle) {
+    let engine = app.state::<SyncEngine>();
+    match pending_count(app) {
+        Ok(pending) => {
+            engine.update_status(app, |schedule| schedule.status.pending_changes = pending)
+        }
+        Err(e) => log::warn!("Failed to count pending sync changes: {e}"),
+    }
+    let Some(backend) = &engine.backend else {
+        log::info!("Sync disabled (no backend configured)");
+        return;
+    };
+    log::info!("Sync engine started ({} backend)", backend.name());
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let engine = app.state::<SyncEngine>();
+        let Some(backend) = engine.backend.as_deref() else {
+            return;
+        };
+        let database = app.state::<Database>();
+        loop {
+            engine.wait_until_due();
+            engine.update_status(&app, |schedule| {
+                // Syncs requested from now on move this earlier
+                schedule.next_run = Instant::now() + engine.interval;
+                schedule.status.state = SyncState::Syncing;
+                schedule.status.next_attempt_at = None;
+            });
+
+            let result = engine.sync_once(&app, backend, &database);
+            let pending = pending_count(&app);
+            engine.update_status(&app, |schedule| {
+                match &result {
+                    Ok(conflicts) => {
+                        schedule.failures = 0;
+                        schedule.status.state = SyncState::Idle;
+                        schedule.status.last_synced_at = Some(now_millis() as f64);
+                        schedule.status.last_error = None;
+                        if *conflicts {
+                            schedule.next_run = schedule.next_run.min(Instant::now() + PUSH_DELAY);
+                        }
+                    }
+                    Err(e) => {
+                        log::warn!("Sync failed: {e}");
+                        schedule.failures += 1;
+                        schedule.status.state = SyncState::Failed;
+                        schedule.status.last_error = Some(e.to_string());
+                        // Back off even if changes were queued meanwhile
+                        schedule.next_run = Instant::now() + retry_delay(schedule.failures);
+                    }
+                }
+                let next_run_in = schedule.next_run.saturating_duration_since(Instant::now());
+                schedule.status.next_attempt_at =
+                    Some((now_millis() + next_run_in.as_millis() as i64) as f64);
+                if let Ok(pending) = pending {
+                    schedule.status.pending_changes = pending;
+                }
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(1), MIN_RETRY_DELAY);
+        assert_eq!(retry_delay(2), MIN_RETRY_DELAY * 2);
+        assert_eq!(retry_delay(4), MIN_RETRY_DELAY * 8);
+        assert_eq!(retry_delay(20), MAX_RETRY_DELAY);
+        assert_eq!(retry_delay(u32::MAX), MAX_RETRY_DELAY);
+    }
+
+    #[test]
+    fn test_builder_without_backend_is_disabled() {
+        let engine = Builder::new().build();
+        assert!(!engine.is_enabled());
+        assert_eq!(engine.status().unwrap().state, SyncState::Disabled);
+    }
+}
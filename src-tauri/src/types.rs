@@ -3,11 +3,16 @@
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use specta::Type;
+use std::collections::HashMap;
 use std::sync::LazyLock;
+use tauri_specta::Event;
 
 /// Default shortcut for the quick pane
 pub const DEFAULT_QUICK_PANE_SHORTCUT: &str = "CommandOrControl+Shift+.";
 
+/// Default shortcut for bringing the main window to the front
+pub const DEFAULT_ACTIVATE_APP_SHORTCUT: &str = "CommandOrControl+Shift+Space";
+
 /// Maximum size for recovery data files (10MB)
 pub const MAX_RECOVERY_DATA_BYTES: u32 = 10_485_760;
 
@@ -30,9 +35,62 @@ pub struct AppPreferences {
     /// Global shortcut for quick pane (e.g., "CommandOrControl+Shift+.")
     /// If None, uses the default shortcut
     pub quick_pane_shortcut: Option<String>,
+    /// Global shortcut that brings the main window to the front
+    /// If None, uses the default shortcut
+    pub activate_app_shortcut: Option<String>,
     /// User's preferred language (e.g., "en", "es", "de")
     /// If None, uses system locale detection
     pub language: Option<String>,
+    /// Reopen the windows that were open at quit on the next launch
+    #[serde(default = "default_restore_session")]
+    pub restore_session: bool,
+    /// Release channel the updater checks for new versions
+    #[serde(default)]
+    pub update_channel: UpdateChannel,
+    /// Hours between automatic update checks, or 0 to only check manually
+    #[serde(default = "default_update_check_interval_hours")]
+    pub update_check_interval_hours: u32,
+    /// Size in MB at which the log file is rotated. Applies from the next launch.
+    #[serde(default = "default_log_max_file_size_mb")]
+    pub log_max_file_size_mb: u32,
+    /// Rotated log files to keep, or 0 to keep only the current file.
+    /// Applies from the next launch.
+    #[serde(default = "default_log_retention_count")]
+    pub log_retention_count: u32,
+    /// Minimum level written to the log. If None, uses Debug in development
+    /// and Info in production
+    #[serde(default)]
+    pub log_level: Option<LogLevel>,
+    /// Format of the log file. Applies from the next launch.
+    #[serde(default)]
+    pub log_format: LogFormat,
+    /// Send anonymous usage analytics. Off unless the user opts in
+    #[serde(default)]
+    pub analytics_enabled: bool,
+    /// Encrypt the database, preferences and recovery data with a key kept
+    /// in the OS keychain. Applies from the next launch.
+    #[serde(default)]
+    pub encrypt_local_data: bool,
+    /// Last launch at login choice made in the app. The OS setting is the
+    /// source of truth, since users can change it outside the app
+    #[serde(default)]
+    pub launch_at_login: bool,
+}
+
+fn default_restore_session() -> bool {
+    true
+}
+
+fn default_update_check_interval_hours() -> u32 {
+    6
+}
+
+fn default_log_max_file_size_mb() -> u32 {
+    5
+}
+
+fn default_log_retention_count() -> u32 {
+    5
 }
 
 impl Default for AppPreferences {
@@ -40,9 +98,741 @@ impl Default for AppPreferences {
         Self {
             theme: "system".to_string(),
             quick_pane_shortcut: None, // None means use default
-            language: None,            // None means use system locale
+            activate_app_shortcut: None,
+            language: None, // None means use system locale
+            restore_session: default_restore_session(),
+            update_channel: UpdateChannel::default(),
+            update_check_interval_hours: default_update_check_interval_hours(),
+            log_max_file_size_mb: default_log_max_file_size_mb(),
+            log_retention_count: default_log_retention_count(),
+            log_level: None,
+            log_format: LogFormat::default(),
+            analytics_enabled: false,
+            encrypt_local_data: false,
+            launch_at_login: false,
+        }
+    }
+}
+
+/// Event: the theme was changed in one window, for the others to follow.
+/// Emitted by the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct ThemeChanged {
+    /// "light", "dark" or "system"
+    pub theme: String,
+}
+
+/// Event: the language was changed in one window, for the others to follow.
+/// Emitted by the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct LanguageChanged {
+    pub language: String,
+}
+
+// ============================================================================
+// Logging
+// ============================================================================
+
+/// Minimum level of messages written to the log, most severe first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+/// Format of lines in the log file. Stdout always uses the readable text format.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum LogFormat {
+    /// `[date][time][target][LEVEL] message`
+    #[default]
+    Text,
+    /// One JSON object per line, for log ingestion tools
+    Json,
+}
+
+// ============================================================================
+// Updates
+// ============================================================================
+
+/// Release channel the updater checks. Each channel has its own update manifest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum UpdateChannel {
+    /// Full releases only
+    #[default]
+    Stable,
+    /// Pre-releases for users who opt in to test upcoming versions
+    Beta,
+    /// Builds from the latest development code
+    Nightly,
+}
+
+/// An update found by `check_for_update`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    pub channel: UpdateChannel,
+    /// Release notes from the update manifest
+    pub notes: Option<String>,
+    /// Publication date from the update manifest (RFC 3339)
+    pub date: Option<String>,
+}
+
+/// Release notes for one version, from `get_release_notes`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ReleaseNote {
+    pub version: String,
+    /// Publication date (RFC 3339)
+    pub date: Option<String>,
+    /// Release notes as markdown
+    pub notes: String,
+}
+
+/// Where `get_release_notes` found its notes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum ReleaseNotesSource {
+    /// Fetched from the changelog just now
+    Changelog,
+    /// Saved from an earlier fetch (recent, or the changelog couldn't be reached)
+    Cache,
+    /// Only the update manifest's notes for the newest version were available
+    Manifest,
+}
+
+/// Release notes for every version between two versions, newest first.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ReleaseNotes {
+    pub releases: Vec<ReleaseNote>,
+    pub source: ReleaseNotesSource,
+}
+
+/// Emitted to the main window when a scheduled check finds an update
+/// (`update-available`). Sent at most once a day.
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct UpdateAvailable {
+    pub update: UpdateInfo,
+}
+
+/// Progress of `download_and_install_update`, broadcast to all windows
+/// (`update-progress`).
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+#[serde(tag = "stage")]
+pub enum UpdateProgress {
+    /// Checking the update channel for a new version
+    Checking,
+    /// Downloading the update, in bytes. `total` is None if the server didn't
+    /// send a size.
+    Downloading { downloaded: u32, total: Option<u32> },
+    /// Verifying the signature and installing the downloaded update
+    Installing,
+    /// Installed; `restart_to_update` relaunches into the new version
+    ReadyToRestart { version: String },
+}
+
+// ============================================================================
+// Quick Pane
+// ============================================================================
+
+/// Where a quick pane appears when shown.
+/// Positions are computed on the monitor containing the cursor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum QuickPanePosition {
+    /// Centered on the monitor
+    #[default]
+    Centered,
+    /// Horizontally centered in the upper third, like Spotlight
+    TopThird,
+    /// Just below the mouse cursor
+    NearCursor,
+    /// Wherever the pane was last dismissed (centered the first time)
+    Remembered,
+}
+
+/// Emitted to the main window when an entry is submitted from a quick pane
+/// (`quick-entry-submitted`).
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct QuickEntrySubmitted {
+    /// Label of the quick pane the entry came from
+    pub label: String,
+    pub text: String,
+}
+
+// ============================================================================
+// Windows
+// ============================================================================
+
+/// Title bar appearance for app windows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum TitleBarStyle {
+    /// Standard native title bar
+    #[default]
+    Visible,
+    /// Native title bar drawn over a transparent background (macOS only)
+    Transparent,
+    /// Content extends under the title bar; the app draws its own chrome.
+    /// macOS keeps the traffic lights, other platforms hide decorations entirely.
+    Overlay,
+}
+
+/// Logical position of the macOS traffic lights, measured from the window's
+/// top-left corner to the top-left of the close button.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Type)]
+pub struct TrafficLightInset {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Describes an app window to create with `create_app_window`.
+/// Optional fields fall back to sensible defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct WindowConfig {
+    /// Unique window label starting with `window-` (alphanumeric, `-`, `/`, `:` and `_`)
+    pub label: String,
+    /// App-relative path of the page to load (e.g. `index.html#/detail/42`)
+    pub url: String,
+    pub title: Option<String>,
+    /// Logical width (default 800)
+    pub width: Option<f64>,
+    /// Logical height (default 600)
+    pub height: Option<f64>,
+    /// Show native title bar and borders (default true)
+    pub decorations: Option<bool>,
+    /// Label of the window this one belongs to (e.g. a tool palette's document)
+    pub parent: Option<String>,
+    /// Disable the parent window until this one closes (requires `parent`)
+    pub modal: Option<bool>,
+    /// Title bar appearance (default `Visible`)
+    pub title_bar_style: Option<TitleBarStyle>,
+    /// Hide the title text while keeping the title bar (macOS only)
+    pub hidden_title: Option<bool>,
+    /// Custom traffic light position, typically used with `Overlay` (macOS only)
+    pub traffic_light_inset: Option<TrafficLightInset>,
+}
+
+/// How high a window floats relative to other windows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum WindowLevel {
+    /// Regular document window
+    Normal,
+    /// Floats above normal windows, like tool palettes and inspectors
+    Floating,
+    /// Floats above floating windows, alongside the menu bar (macOS).
+    /// Same as `Floating` on other platforms.
+    Status,
+}
+
+/// Emitted when a window gains or loses focus (`window-focus-changed`).
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct WindowFocusChanged {
+    pub label: String,
+    pub focused: bool,
+}
+
+/// Emitted when a window moves (`window-moved`). Logical pixels.
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct WindowMoved {
+    pub label: String,
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Emitted when a window's inner size changes (`window-resized`). Logical pixels.
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct WindowResized {
+    pub label: String,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// System appearance reported for a window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum WindowTheme {
+    Light,
+    Dark,
+}
+
+/// Emitted when the system appearance of a window changes (`window-theme-changed`).
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct WindowThemeChanged {
+    pub label: String,
+    pub theme: WindowTheme,
+}
+
+/// Emitted when a window enters or exits fullscreen (`window-fullscreen-changed`).
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct WindowFullscreenChanged {
+    pub label: String,
+    pub fullscreen: bool,
+}
+
+/// A file or directory dropped on a window.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DroppedFile {
+    pub path: String,
+    /// File name, with extension
+    pub name: String,
+    pub is_directory: bool,
+    /// Size in bytes. None for directories.
+    pub size: Option<f64>,
+    /// Guessed from the extension. None for directories and unknown types.
+    pub mime_type: Option<String>,
+    /// Whether the webview can show the file directly: text, or a common image type
+    pub has_preview: bool,
+}
+
+/// Emitted to a window when files are dropped on it (`files-dropped`).
+/// The position is in logical pixels, relative to the window.
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct FilesDropped {
+    pub label: String,
+    pub files: Vec<DroppedFile>,
+    pub x: f64,
+    pub y: f64,
+}
+
+/// A change to a watched path, as reported by `fs-change`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "kind")]
+pub enum FsChangeKind {
+    Created {
+        path: String,
+    },
+    Modified {
+        path: String,
+    },
+    Removed {
+        path: String,
+    },
+    /// Reported as `Removed` and `Created` instead when the platform can't
+    /// pair the two sides, or one side is outside the watch
+    Renamed {
+        from: String,
+        to: String,
+    },
+}
+
+/// Broadcast to all windows when a path watched with `watch_path` changes
+/// (`fs-change`).
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct FsChange {
+    /// The id returned by `watch_path`
+    pub watch_id: u32,
+    pub change: FsChangeKind,
+}
+
+/// Where the computer is drawing power from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum PowerSource {
+    Ac,
+    Battery,
+    /// Couldn't be determined, e.g. a desktop that doesn't report it
+    #[default]
+    Unknown,
+}
+
+/// Power state returned by `get_power_state` and sent with `power-changed`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct PowerState {
+    pub source: PowerSource,
+    /// Charge of the internal battery in percent, or None without one
+    pub battery_percent: Option<u32>,
+    /// Whether the lid is closed, or None without a lid (or if it can't be read)
+    pub lid_closed: Option<bool>,
+}
+
+/// What changed, as reported by `power-changed`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "kind")]
+pub enum PowerChange {
+    /// The system is about to sleep. There are only a few seconds left, so
+    /// save anything important straight away
+    Sleep,
+    Wake,
+    LidClosed,
+    LidOpened,
+    PowerSourceChanged {
+        source: PowerSource,
+    },
+    /// The battery dropped to the low battery level while on battery power
+    LowBattery {
+        percent: u32,
+    },
+}
+
+/// Broadcast to all windows on sleep, wake and power changes
+/// (`power-changed`).
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct PowerChanged {
+    pub change: PowerChange,
+    /// Power state after the change
+    pub state: PowerState,
+}
+
+/// Clipboard contents in several formats, for `write_clipboard` and
+/// `read_clipboard`. Formats that aren't present are None.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ClipboardData {
+    pub text: Option<String>,
+    pub html: Option<String>,
+    pub rtf: Option<String>,
+    /// A PNG-encoded image
+    pub png: Option<Vec<u8>>,
+    /// Absolute paths of copied files
+    pub files: Option<Vec<String>>,
+}
+
+/// What was copied, as stored in the clipboard history.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "kind")]
+pub enum ClipboardContent {
+    Text {
+        text: String,
+    },
+    /// Saved as a PNG in the app data directory. Display it with
+    /// `convertFileSrc`
+    Image {
+        path: String,
+        width: u32,
+        height: u32,
+    },
+}
+
+/// An entry in the clipboard history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct ClipboardEntry {
+    /// Hash of the content. Copying the same thing again moves its entry to
+    /// the top rather than adding another
+    pub id: String,
+    pub content: ClipboardContent,
+    /// When it was copied, as a Unix timestamp in milliseconds
+    pub copied_at: f64,
+}
+
+/// Broadcast to all windows when something new is copied
+/// (`clipboard-changed`).
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct ClipboardChanged {
+    pub entry: ClipboardEntry,
+}
+
+/// Something to share with `share_items`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "kind")]
+pub enum ShareItem {
+    Text {
+        text: String,
+    },
+    /// An absolute path in the app's file scope
+    File {
+        path: String,
+    },
+}
+
+/// Where the share sheet points, in CSS pixels in the calling webview, e.g.
+/// a share button's `getBoundingClientRect()`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Type)]
+pub struct ShareAnchor {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// A rectangle in physical pixels, in desktop coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct DisplayRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A connected display, as returned by `get_displays`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct Display {
+    /// The OS's name for the display, if it has one
+    pub name: Option<String>,
+    pub bounds: DisplayRect,
+    /// The bounds minus the menu bar, Dock and taskbars
+    pub work_area: DisplayRect,
+    /// Physical pixels per logical pixel
+    pub scale_factor: f64,
+    pub is_primary: bool,
+    /// Whether the mouse cursor is on this display
+    pub contains_cursor: bool,
+}
+
+/// The mouse cursor's position in physical pixels, in desktop coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Type)]
+pub struct CursorPosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// A corner of the screen, used to place mini windows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum ScreenCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Badge shown on the app's dock or taskbar icon.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum AppBadge {
+    /// A number, such as an unread count. Zero clears the badge.
+    Count(u32),
+    /// Short text (macOS only)
+    Text(String),
+}
+
+/// Emitted to a window with unsaved changes when it (or the app) is asked to
+/// close (`close-requested`). The frontend should prompt the user, then call
+/// `confirm_window_close` or `cancel_window_close`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct CloseRequested {
+    pub label: String,
+    /// True when the whole app is quitting rather than just this window closing
+    pub quitting: bool,
+}
+
+// ============================================================================
+// Menus
+// ============================================================================
+
+/// Emitted to the main window when a Rust-built menu item (e.g. in the tray)
+/// is selected (`menu-item-selected`). `id` is the app menu action to run.
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct MenuItemSelected {
+    pub id: String,
+}
+
+/// Emitted to the main window when a document is chosen from the
+/// "Open Recent" menu (`recent-document-selected`).
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct RecentDocumentSelected {
+    pub path: String,
+}
+
+/// Emitted to the main window when the OS opens a file with the app
+/// (`file-opened`). The file is readable through the fs plugin.
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct FileOpened {
+    pub path: String,
+}
+
+/// A parsed `tauri-app://` link. See `commands::deep_link` for the URL forms.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(tag = "route")]
+pub enum DeepLink {
+    /// `tauri-app://documents/<id>`
+    OpenDocument { id: u32 },
+    /// `tauri-app://new?title=<title>`
+    NewDocument { title: Option<String> },
+    /// `tauri-app://quick-entry?text=<text>`, sent to the quick pane
+    QuickEntry { text: Option<String> },
+    /// Any other route, with its path (without the scheme) and query parameters
+    Other {
+        path: String,
+        query: HashMap<String, String>,
+    },
+}
+
+/// Emitted when the app is opened with a deep link (`deep-link-opened`), to
+/// the window that handles the link.
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct DeepLinkOpened {
+    /// The URL as received
+    pub url: String,
+    pub link: DeepLink,
+}
+
+/// Standard OS-provided menu items. Some only exist on macOS and are skipped elsewhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum MenuRole {
+    Hide,
+    HideOthers,
+    ShowAll,
+    Services,
+    Quit,
+    Undo,
+    Redo,
+    Cut,
+    Copy,
+    Paste,
+    SelectAll,
+    Minimize,
+    Zoom,
+    Fullscreen,
+    CloseWindow,
+}
+
+/// One entry in a declarative menu definition (see `create_app_menu`).
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "type")]
+pub enum MenuEntrySpec {
+    /// A clickable item. `id` is also the app menu action run when it's clicked.
+    Item {
+        id: String,
+        text: String,
+        /// Keyboard shortcut, e.g. `CmdOrCtrl+Shift+N`
+        accelerator: Option<String>,
+        /// Defaults to true
+        enabled: Option<bool>,
+        /// Makes this a check item with the given initial state
+        checked: Option<bool>,
+    },
+    Submenu {
+        id: Option<String>,
+        text: String,
+        items: Vec<MenuEntrySpec>,
+    },
+    /// A standard item; `text` overrides the OS label (e.g. for translations)
+    Role {
+        role: MenuRole,
+        text: Option<String>,
+    },
+    Separator,
+}
+
+/// A complete menu bar definition, built by `create_app_menu`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct MenuSpec {
+    /// Top-level entries (submenus on macOS)
+    pub items: Vec<MenuEntrySpec>,
+}
+
+/// A task in the Windows taskbar Jump List. Choosing it relaunches the app
+/// with `--jump-list-task=<id>`, which runs the app menu action with that id.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct JumpListTask {
+    pub id: String,
+    pub title: String,
+    /// Tooltip text
+    pub description: Option<String>,
+}
+
+impl JumpListTask {
+    pub fn new(id: impl Into<String>, title: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into(),
+            description: None,
         }
     }
+
+    /// Sets the tooltip text.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+}
+
+// ============================================================================
+// Notifications
+// ============================================================================
+
+/// A button shown on a native notification.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct NotificationActionButton {
+    /// Sent back in `notification-action` when the button is clicked
+    pub id: String,
+    pub title: String,
+}
+
+/// Sound played when a native notification is shown.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "type")]
+pub enum NotificationSound {
+    /// The platform's default notification sound
+    Default,
+    /// A sound provided by the OS, e.g. `Glass` on macOS, `Mail` on Windows or
+    /// `message-new-instant` on Linux. Unknown names play the default sound.
+    System { name: String },
+    /// A sound file bundled with the app, relative to the resource directory
+    Resource { path: String },
+}
+
+/// Optional settings for `send_native_notification`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct NativeNotificationOptions {
+    /// Identifies the notification in click and action events. Generated if not set.
+    pub id: Option<String>,
+    /// Buttons shown on the notification, in order
+    pub actions: Vec<NotificationActionButton>,
+    /// Sound to play. Silent or the platform's usual behaviour if not set.
+    pub sound: Option<NotificationSound>,
+    /// Absolute path to a PNG, JPEG or GIF image shown in the notification.
+    /// Must be in the app's resource, data or cache directory, or somewhere
+    /// the app has been given access to (e.g. a file picked in a dialog).
+    pub image: Option<String>,
+}
+
+/// Emitted to the main window when a native notification is clicked
+/// (`notification-clicked`).
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct NotificationClicked {
+    pub notification_id: String,
+}
+
+/// Emitted to the main window when an action button on a native notification
+/// is clicked (`notification-action`).
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct NotificationAction {
+    pub notification_id: String,
+    /// Id of the `NotificationActionButton` that was clicked
+    pub action_id: String,
+}
+
+/// Whether the app may show native notifications.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum NotificationPermission {
+    Granted,
+    /// Turned off by the user; only the system settings can turn it back on
+    Denied,
+    /// Not asked yet; `request_notification_permission` shows the prompt
+    NotDetermined,
+}
+
+/// Emitted when notification permission changes, e.g. after the prompt or
+/// in the system settings (`notification-permission-changed`).
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct NotificationPermissionChanged {
+    pub permission: NotificationPermission,
+}
+
+// ============================================================================
+// Permissions
+// ============================================================================
+
+/// A privacy-protected feature the app can ask permission for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum PermissionKind {
+    Notifications,
+    /// Controlling other apps, e.g. sending keystrokes (macOS)
+    Accessibility,
+    /// Capturing the screen or other apps' windows (macOS)
+    ScreenRecording,
+    Microphone,
+    Camera,
+    /// Sending Apple Events to `AUTOMATION_TARGET` in `permissions.rs` (macOS)
+    Automation,
+}
+
+/// Whether the app has a permission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum PermissionStatus {
+    Granted,
+    /// Turned off by the user; only the system settings can turn it back on
+    Denied,
+    /// Not asked yet; `request_permission` shows the prompt
+    NotDetermined,
+    /// Blocked by parental controls or device management
+    Restricted,
 }
 
 // ============================================================================
@@ -63,6 +853,14 @@ pub enum RecoveryError {
     IoError { message: String },
     /// JSON serialization/deserialization error
     ParseError { message: String },
+    /// Stopped with `cancel_task`
+    Cancelled,
+    /// Called too often. Sent by the `RateLimit` middleware as
+    /// `AppError::TooManyRequests`, which serializes the same way.
+    TooManyRequests {
+        message: String,
+        retry_after_ms: u32,
+    },
 }
 
 impl std::fmt::Display for RecoveryError {
@@ -75,48 +873,1320 @@ impl std::fmt::Display for RecoveryError {
             }
             RecoveryError::IoError { message } => write!(f, "IO error: {message}"),
             RecoveryError::ParseError { message } => write!(f, "Parse error: {message}"),
+            RecoveryError::Cancelled => write!(f, "Cancelled"),
+            RecoveryError::TooManyRequests { message, .. } => write!(f, "{message}"),
         }
     }
 }
 
 // ============================================================================
-// Validation Functions
+// Recovery Events
 // ============================================================================
 
-/// Validates a filename for safe file system operations.
-/// Only allows alphanumeric characters, dashes, underscores, and a single extension.
-pub fn validate_filename(filename: &str) -> Result<(), String> {
-    if filename.is_empty() {
-        return Err("Filename cannot be empty".to_string());
-    }
+/// Emitted after emergency data is saved (`recovery-saved`).
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct RecoverySaved {
+    pub filename: String,
+    /// Unix timestamp in milliseconds
+    pub saved_at: f64,
+}
 
-    if filename.chars().count() > 100 {
-        return Err("Filename too long (max 100 characters)".to_string());
-    }
+/// Emitted after old recovery data is cleaned up (`recovery-cleaned`).
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct RecoveryCleaned {
+    pub removed_count: u32,
+}
 
-    if !FILENAME_PATTERN.is_match(filename) {
-        return Err(
-            "Invalid filename: only alphanumeric characters, dashes, underscores, and dots allowed"
-                .to_string(),
-        );
-    }
+/// Emitted when loading recovery data fails for any reason other than
+/// the file not existing (`recovery-load-failed`).
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct RecoveryLoadFailed {
+    pub filename: String,
+    pub error: RecoveryError,
+}
 
-    Ok(())
+// ============================================================================
+// Recovery Archive
+// ============================================================================
+
+/// A single file included in a recovery archive export.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct RecoveryArchiveEntry {
+    /// Path relative to the recovery directory (forward slashes)
+    pub filename: String,
+    /// Last modification time as a Unix timestamp in milliseconds
+    pub modified_at: f64,
+    /// File size in bytes
+    pub size_bytes: u32,
 }
 
-/// Validates string input length (by character count, not bytes).
-pub fn validate_string_input(input: &str, max_len: usize, field_name: &str) -> Result<(), String> {
-    let char_count = input.chars().count();
-    if char_count > max_len {
-        return Err(format!("{field_name} too long (max {max_len} characters)"));
-    }
-    Ok(())
+/// Manifest written into a recovery archive as `manifest.json`.
+/// Also returned to the frontend so it can report what was exported.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct RecoveryArchiveManifest {
+    /// When the archive was created, as a Unix timestamp in milliseconds
+    pub created_at: f64,
+    /// Application version that produced the archive
+    pub app_version: String,
+    pub entries: Vec<RecoveryArchiveEntry>,
 }
 
-/// Validates theme value.
-pub fn validate_theme(theme: &str) -> Result<(), String> {
-    match theme {
-        "light" | "dark" | "system" => Ok(()),
-        _ => Err("Invalid theme: must be 'light', 'dark', or 'system'".to_string()),
+// ============================================================================
+// Backups
+// ============================================================================
+
+/// Manifest written into a backup as `manifest.json`, and returned by
+/// `create_backup` and `restore_backup`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct BackupManifest {
+    /// Layout version of the archive itself
+    pub format_version: u32,
+    /// When the backup was created, as a Unix timestamp in milliseconds
+    pub created_at: f64,
+    /// Application version that produced the backup
+    pub app_version: String,
+    /// Database schema version (the last migration applied)
+    pub schema_version: u32,
+    /// Paths of the files in the archive, besides the manifest
+    pub files: Vec<String>,
+}
+
+// ============================================================================
+// Data Location
+// ============================================================================
+
+/// Where the app's data is stored, from `get_data_location`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DataLocationInfo {
+    /// Absolute path of the data directory
+    pub path: String,
+    /// False if the user moved the data with `move_app_data`
+    pub is_default: bool,
+}
+
+// ============================================================================
+// Diagnostics
+// ============================================================================
+
+/// What `export_diagnostics` wrote, so the frontend can report it.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DiagnosticsSummary {
+    /// When the bundle was created, as a Unix timestamp in milliseconds
+    pub created_at: f64,
+    /// Paths of the files in the archive
+    pub files: Vec<String>,
+    /// Total size of the files before compression, in bytes
+    pub size_bytes: u32,
+    /// Whether older logs were left out or cut short to stay under the size cap
+    pub logs_truncated: bool,
+}
+
+/// App, system and path details for the About dialog and bug reports.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DebugInfo {
+    pub app_name: String,
+    pub app_version: String,
+    pub identifier: String,
+    pub tauri_version: String,
+    /// WebView2, WKWebView or WebKitGTK version, if it could be read
+    pub webview_version: Option<String>,
+    pub debug_build: bool,
+    pub os: String,
+    pub os_version: String,
+    /// `unix` or `windows`
+    pub os_family: String,
+    pub arch: String,
+    pub locale: Option<String>,
+    pub data_dir: String,
+    pub log_dir: String,
+    pub recovery_dir: String,
+    /// Optional features turned on in this build or by the user
+    pub features: Vec<String>,
+}
+
+// ============================================================================
+// Crash Reports
+// ============================================================================
+
+/// What produced a crash report.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum CrashReportKind {
+    /// A Rust panic or fatal runtime error
+    #[default]
+    Panic,
+    /// An uncaught JavaScript error sent with `report_frontend_error`
+    Frontend,
+}
+
+/// A crash recorded by the panic hook, a fatal runtime error, or an error
+/// reported by the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CrashReport {
+    /// Identifies the report for `dismiss_crash_report`
+    pub id: String,
+    #[serde(default)]
+    pub kind: CrashReportKind,
+    /// When the crash happened, as a Unix timestamp in milliseconds
+    pub created_at: f64,
+    /// Panic message or runtime error
+    pub message: String,
+    /// Where the panic happened (`file:line:column`)
+    pub location: Option<String>,
+    /// Name of the thread that panicked, if it had one
+    pub thread: Option<String>,
+    /// Rust backtrace, or the JavaScript stack for frontend errors
+    pub backtrace: String,
+    /// Label of the window a frontend error came from
+    #[serde(default)]
+    pub window: Option<String>,
+    /// App state the frontend attached to the error
+    #[serde(default)]
+    pub context: Option<serde_json::Value>,
+    pub app_version: String,
+    pub os: String,
+    pub os_version: String,
+    pub arch: String,
+    /// The last lines of the log before the crash
+    pub log_lines: Vec<String>,
+}
+
+/// Emitted to the main window at launch when the app panicked last time,
+/// so the UI can offer to send the report.
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct CrashReportAvailable {
+    pub report: CrashReport,
+}
+
+// ============================================================================
+// Database
+// ============================================================================
+
+/// Rows returned by `db_query`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DbQueryResult {
+    /// Column names, in the order of each row's values
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+}
+
+/// Returned by `db_execute`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DbExecuteResult {
+    pub rows_affected: u32,
+    /// Row id of the last successful `INSERT` on the connection
+    pub last_insert_id: f64,
+}
+
+/// An entry returned by `kv_list`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct KvEntry {
+    pub key: String,
+    pub value: serde_json::Value,
+}
+
+/// Searchable text for `index_document`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SearchFields {
+    /// Weighted above the body when ranking
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+}
+
+/// A run of text in a search result, marked if it matched the query.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct HighlightSegment {
+    pub text: String,
+    pub highlighted: bool,
+}
+
+/// A match returned by `search`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SearchResult {
+    pub id: String,
+    pub title: Vec<HighlightSegment>,
+    /// The best-matching part of the body, with `…` where it was cut
+    pub snippet: Vec<HighlightSegment>,
+    /// Relevance; higher is better. Only comparable within one search.
+    pub score: f64,
+}
+
+/// A document returned by `create_document` and `load_document`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Document {
+    pub id: u32,
+    pub title: String,
+    pub content: String,
+    /// Unix timestamp in milliseconds
+    pub created_at: f64,
+    /// Unix timestamp in milliseconds of the last committed save
+    pub updated_at: f64,
+    /// Unsaved changes captured by the recovery store that never reached the
+    /// database (e.g. the app crashed before autosave ran)
+    pub recovered: Option<RecoveredDocument>,
+}
+
+/// An unsaved document buffer found in the recovery store.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct RecoveredDocument {
+    pub title: String,
+    pub content: String,
+    /// Unix timestamp in milliseconds
+    pub saved_at: f64,
+}
+
+/// A document as listed by `list_documents`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DocumentSummary {
+    pub id: u32,
+    pub title: String,
+    /// Unix timestamp in milliseconds
+    pub updated_at: f64,
+    pub revision_count: u32,
+}
+
+/// A revision as listed by `list_revisions`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct RevisionSummary {
+    pub id: u32,
+    pub title: String,
+    /// Content size in bytes
+    pub size: u32,
+    /// Unix timestamp in milliseconds
+    pub created_at: f64,
+}
+
+/// A saved snapshot of a document, returned by `load_revision`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DocumentRevision {
+    pub id: u32,
+    pub document_id: u32,
+    pub title: String,
+    pub content: String,
+    /// Unix timestamp in milliseconds
+    pub created_at: f64,
+}
+
+/// A row in the example `notes` table.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Note {
+    pub id: u32,
+    pub title: String,
+    pub body: String,
+    /// Unix timestamp in milliseconds
+    pub created_at: f64,
+    /// Unix timestamp in milliseconds
+    pub updated_at: f64,
+}
+
+// ============================================================================
+// Files
+// ============================================================================
+
+/// Error types for scoped file commands (typed for frontend matching)
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "type")]
+pub enum FileError {
+    /// The file (or, when writing, its directory) doesn't exist
+    NotFound { path: String },
+    /// The path is outside the temp workspaces and the fs scope, or inside the
+    /// app's own data and log directories
+    OutOfScope { path: String },
+    /// The file or contents exceed the command's size limit
+    TooLarge { max_bytes: u32 },
+    /// The path is relative, or is a directory where a file was expected
+    InvalidPath { message: String },
+    /// The file isn't valid UTF-8 text
+    InvalidEncoding { path: String },
+    /// The file isn't an image in a supported format, or is corrupt
+    InvalidImage { path: String, message: String },
+    /// Any other file system error
+    IoError { message: String },
+    /// Stopped with `cancel_task`
+    Cancelled,
+}
+
+impl std::fmt::Display for FileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileError::NotFound { path } => write!(f, "File not found: {path}"),
+            FileError::OutOfScope { path } => write!(f, "Path is outside the app's scope: {path}"),
+            FileError::TooLarge { max_bytes } => {
+                write!(f, "File too large (max {max_bytes} bytes)")
+            }
+            FileError::InvalidPath { message } => write!(f, "Invalid path: {message}"),
+            FileError::InvalidEncoding { path } => write!(f, "File is not valid UTF-8: {path}"),
+            FileError::InvalidImage { path, message } => {
+                write!(f, "Could not read image {path}: {message}")
+            }
+            FileError::IoError { message } => write!(f, "IO error: {message}"),
+            FileError::Cancelled => write!(f, "Cancelled"),
+        }
+    }
+}
+
+/// A file or folder the user has given the app lasting access to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct AllowedPath {
+    pub path: String,
+    /// Folders give access to everything inside them
+    pub is_directory: bool,
+}
+
+/// Digest algorithm for `hash_file`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum HashAlgorithm {
+    Sha256,
+    /// Much faster than SHA-256 on large files; good for deduplication
+    Blake3,
+}
+
+/// A chunk of a file from `stream_file`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct FileChunk {
+    /// Byte offset of this chunk in the file
+    pub offset: f64,
+    /// The chunk's bytes, base64-encoded
+    pub data: String,
+}
+
+// ============================================================================
+// Streams
+// ============================================================================
+
+/// A message on a streaming command's channel (see `commands::streams`).
+/// Items arrive in order, then exactly one `End` or `Error`, unless the
+/// stream is stopped.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "type")]
+pub enum StreamMessage<T, E> {
+    Item { data: T },
+    End,
+    Error { error: E },
+}
+
+// ============================================================================
+// Undo History
+// ============================================================================
+
+/// How many edits to a document can be undone and redone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct UndoDepth {
+    pub undo_depth: u32,
+    pub redo_depth: u32,
+}
+
+/// Broadcast to all windows when a document's undo history changes
+/// (`undo-state-changed`).
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct UndoStateChanged {
+    pub document_id: String,
+    pub undo_depth: u32,
+    pub redo_depth: u32,
+}
+
+// ============================================================================
+// Secrets
+// ============================================================================
+
+/// Error types for secret storage (typed for frontend matching)
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "type")]
+pub enum SecretError {
+    /// No secret is stored for the service and key
+    NotFound,
+    /// The keychain is locked or the user denied access
+    Locked { message: String },
+    /// Service, key or value failed validation
+    ValidationError { message: String },
+    /// Any other keychain failure, including no keychain being available
+    PlatformError { message: String },
+}
+
+impl std::fmt::Display for SecretError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecretError::NotFound => write!(f, "Secret not found"),
+            SecretError::Locked { message } => write!(f, "Keychain locked: {message}"),
+            SecretError::ValidationError { message } => write!(f, "Validation error: {message}"),
+            SecretError::PlatformError { message } => write!(f, "Keychain error: {message}"),
+        }
+    }
+}
+
+// ============================================================================
+// Performance
+// ============================================================================
+
+/// A point in startup recorded by `get_performance_metrics`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum StartupMilestone {
+    /// The setup hook started. Windows from `tauri.conf.json` exist by now.
+    SetupStarted,
+    SetupFinished,
+    /// The main window's page started loading
+    MainPageLoadStarted,
+    /// The main window's page finished loading
+    MainPageLoaded,
+    /// The frontend called `finish_startup`
+    FrontendReady,
+}
+
+/// When a startup milestone was reached.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct StartupTiming {
+    pub milestone: StartupMilestone,
+    /// Milliseconds since the process started
+    pub elapsed_ms: f64,
+}
+
+/// Call counts and handler time for one command.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CommandTiming {
+    pub command: String,
+    pub count: u32,
+    pub total_ms: f64,
+    pub average_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Returned by `get_performance_metrics`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PerformanceMetrics {
+    /// Milliseconds since the process started
+    pub uptime_ms: f64,
+    /// Milestones reached so far, in order
+    pub startup: Vec<StartupTiming>,
+    /// Commands called so far, by total time (slowest first)
+    pub commands: Vec<CommandTiming>,
+}
+
+// ============================================================================
+// Locale
+// ============================================================================
+
+/// A day of the week.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+/// Units the user expects lengths, weights and temperatures in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum MeasurementSystem {
+    Metric,
+    /// US customary units (feet, pounds, Fahrenheit)
+    UnitedStates,
+    /// Metric, with miles and pints. Only reported on macOS.
+    UnitedKingdom,
+}
+
+/// The OS's locale and formatting settings, returned by `get_locale_info`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct LocaleInfo {
+    /// BCP 47 tag, e.g. "en-GB"
+    pub locale: String,
+    /// ISO 639 language code, e.g. "en"
+    pub language: String,
+    /// ISO 3166 region code, e.g. "GB", if the locale has one
+    pub region: Option<String>,
+    pub first_day_of_week: Weekday,
+    /// Whether times use a 24-hour clock rather than AM/PM
+    pub uses_24_hour_clock: bool,
+    pub decimal_separator: String,
+    pub measurement_system: MeasurementSystem,
+}
+
+// ============================================================================
+// HTTP
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HttpMethod {
+    #[default]
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+    Head,
+    Options,
+}
+
+/// A request or response header.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct HttpHeader {
+    pub name: String,
+    pub value: String,
+}
+
+/// A request body. `Json` sets `Content-Type: application/json`; set the
+/// content type in `headers` for the others.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "type")]
+pub enum HttpBody {
+    Text { text: String },
+    Json { value: serde_json::Value },
+    Bytes { bytes: Vec<u8> },
+}
+
+/// How `fetch` should decode the response body.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum HttpResponseType {
+    #[default]
+    Text,
+    Json,
+    Bytes,
+}
+
+/// A request for `fetch`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct HttpRequest {
+    /// An `http` or `https` URL
+    pub url: String,
+    pub method: HttpMethod,
+    pub headers: Vec<HttpHeader>,
+    pub body: Option<HttpBody>,
+    /// Limit for the whole request, including reading the body. Defaults to
+    /// 30 seconds.
+    pub timeout_ms: Option<u32>,
+    pub response_type: HttpResponseType,
+    /// Caller-chosen id (e.g. a UUID) that `cancel_fetch` takes
+    pub cancel_token: Option<String>,
+}
+
+/// A response body, decoded as the request's `response_type` asked.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "type")]
+pub enum HttpResponseBody {
+    Text {
+        text: String,
+    },
+    /// An empty body is `null`
+    Json {
+        value: serde_json::Value,
+    },
+    Bytes {
+        bytes: Vec<u8>,
+    },
+}
+
+/// Returned by `fetch` for any status, including 4xx and 5xx.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct HttpResponse {
+    pub status: u16,
+    /// Whether the status is 2xx
+    pub ok: bool,
+    /// The final URL, after redirects
+    pub url: String,
+    pub headers: Vec<HttpHeader>,
+    pub body: HttpResponseBody,
+}
+
+/// Download progress sent to `fetch`'s `on_progress` channel, in bytes.
+/// `total` is None if the server didn't send a size.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct HttpProgress {
+    pub downloaded: f64,
+    pub total: Option<f64>,
+}
+
+/// Error types for `fetch` (typed for frontend matching)
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "type")]
+pub enum HttpError {
+    /// The URL, a header or the body is invalid
+    InvalidRequest { message: String },
+    /// No response within the timeout
+    Timeout,
+    /// Cancelled with `cancel_fetch`
+    Cancelled,
+    /// The response body is bigger than `fetch` accepts
+    TooLarge { max_bytes: u32 },
+    /// The body isn't valid text or JSON for the requested `response_type`
+    DecodeError { message: String },
+    /// Connection, TLS or protocol failure
+    NetworkError { message: String },
+}
+
+impl std::fmt::Display for HttpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HttpError::InvalidRequest { message } => write!(f, "Invalid request: {message}"),
+            HttpError::Timeout => write!(f, "Request timed out"),
+            HttpError::Cancelled => write!(f, "Request cancelled"),
+            HttpError::TooLarge { max_bytes } => {
+                write!(f, "Response is larger than {max_bytes} bytes")
+            }
+            HttpError::DecodeError { message } => write!(f, "Failed to decode response: {message}"),
+            HttpError::NetworkError { message } => write!(f, "Network error: {message}"),
+        }
+    }
+}
+
+/// A text field sent alongside the file by `upload_file`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct UploadField {
+    pub name: String,
+    pub value: String,
+}
+
+/// Upload progress sent to `upload_file`'s `on_progress` channel, in bytes of
+/// the request body. Restarts from zero (or the resumed offset) on a retry.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct UploadProgress {
+    pub uploaded: f64,
+    pub total: f64,
+    /// 1 for the first attempt
+    pub attempt: u32,
+}
+
+/// A message on `sse_subscribe`'s channel.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "type")]
+pub enum SseEvent {
+    /// Connected, or reconnected
+    Open,
+    /// An event from the server. `event` is "message" unless the server
+    /// named it
+    Message {
+        event: String,
+        data: String,
+        id: Option<String>,
+    },
+    /// The connection failed or dropped. `retry_in_ms` is None if the server
+    /// refused the stream and the subscription has ended
+    Error {
+        message: String,
+        retry_in_ms: Option<u32>,
+    },
+}
+
+// ============================================================================
+// Auth
+// ============================================================================
+
+/// An OAuth provider to sign in with, for `start_auth`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AuthRequest {
+    /// Name the tokens are stored under, e.g. "github"
+    pub provider: String,
+    /// The provider's authorization endpoint
+    pub authorization_url: String,
+    /// The provider's token endpoint
+    pub token_url: String,
+    pub client_id: String,
+    pub scopes: Vec<String>,
+    /// Port for the redirect URI, for providers that need it registered
+    /// exactly. A random free port if None.
+    pub port: Option<u16>,
+}
+
+/// A signed-in provider. Tokens stay in the keychain; Rust commands read them
+/// with `auth::access_token`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AuthSession {
+    pub provider: String,
+    /// Scopes granted, if the provider said
+    pub scope: Option<String>,
+    /// When the access token expires, in milliseconds since the Unix epoch
+    pub expires_at: Option<f64>,
+    /// Whether the access token can be refreshed without signing in again
+    pub refreshable: bool,
+}
+
+/// Event: sign-in with `start_auth` finished.
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct AuthCompleted {
+    pub session: AuthSession,
+}
+
+/// Errors from signing in and reading tokens.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "type")]
+pub enum AuthError {
+    /// The request was invalid, e.g. a non-HTTPS endpoint
+    InvalidRequest { message: String },
+    /// Stopped by `cancel_auth` or a newer `start_auth`
+    Cancelled,
+    /// The browser didn't return within five minutes
+    Timeout,
+    /// The provider refused, e.g. the user denied access
+    Denied {
+        error: String,
+        description: Option<String>,
+    },
+    /// The token endpoint failed or returned an error
+    ExchangeFailed { message: String },
+    /// No tokens are stored for the provider, or they expired and can't be
+    /// refreshed
+    NotSignedIn,
+    /// Reading or writing the keychain failed
+    StorageError { message: String },
+}
+
+impl std::fmt::Display for AuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuthError::InvalidRequest { message } => write!(f, "Invalid request: {message}"),
+            AuthError::Cancelled => write!(f, "Sign-in cancelled"),
+            AuthError::Timeout => write!(f, "Sign-in timed out"),
+            AuthError::Denied { error, description } => match description {
+                Some(description) => write!(f, "Sign-in denied: {error} ({description})"),
+                None => write!(f, "Sign-in denied: {error}"),
+            },
+            AuthError::ExchangeFailed { message } => write!(f, "Token exchange failed: {message}"),
+            AuthError::NotSignedIn => write!(f, "Not signed in"),
+            AuthError::StorageError { message } => write!(f, "Keychain error: {message}"),
+        }
+    }
+}
+
+// ============================================================================
+// Discovery
+// ============================================================================
+
+/// A service to advertise on the local network with `start_advertising`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct LocalService {
+    /// DNS-SD service type, e.g. "_myapp._tcp"
+    pub service_type: String,
+    /// Instance name shown to peers, e.g. "Alice's MacBook"
+    pub name: String,
+    pub port: u16,
+    /// TXT record entries, e.g. a device id or protocol version
+    pub properties: HashMap<String, String>,
+}
+
+/// A service found on the local network by `browse_services`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Peer {
+    pub service_type: String,
+    /// Instance name, e.g. "Alice's MacBook"
+    pub name: String,
+    /// Unique name of the instance, e.g. "Alice's MacBook._myapp._tcp.local."
+    pub full_name: String,
+    pub host: String,
+    pub port: u16,
+    /// IPv4 and IPv6 addresses
+    pub addresses: Vec<String>,
+    pub properties: HashMap<String, String>,
+}
+
+/// Event: a peer appeared on the local network, or its details changed.
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct PeerFound {
+    pub peer: Peer,
+}
+
+/// Event: a peer left the local network or stopped advertising.
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct PeerLost {
+    pub service_type: String,
+    pub full_name: String,
+}
+
+// ============================================================================
+// Downloads
+// ============================================================================
+
+/// Where a download is up to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum DownloadStatus {
+    /// Waiting for a free slot
+    Queued,
+    Downloading,
+    Paused,
+    Completed,
+    /// Stopped by an error; `resume_download` tries again
+    Failed,
+    Cancelled,
+}
+
+/// A download started with `start_download`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Download {
+    pub id: u32,
+    pub url: String,
+    /// The file being downloaded to
+    pub destination: String,
+    pub status: DownloadStatus,
+    /// Bytes downloaded so far
+    pub downloaded: f64,
+    /// Total bytes, if the server sent a size
+    pub total: Option<f64>,
+    /// Why the download failed, for `Failed`
+    pub error: Option<String>,
+}
+
+/// Event: a download's status or progress changed. Progress is sent at most
+/// four times a second per download.
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct DownloadChanged {
+    pub download: Download,
+}
+
+// ============================================================================
+// Sync
+// ============================================================================
+
+/// What a change does to its record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum SyncOperation {
+    /// Created or updated; `payload` is the new state
+    Upsert,
+    Delete,
+}
+
+/// A local change to a record, queued for the sync engine to push.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct SyncChange {
+    /// The kind of record, e.g. "notes"
+    pub collection: String,
+    pub record_id: String,
+    pub operation: SyncOperation,
+    /// The record's new state, for `Upsert`
+    pub payload: Option<serde_json::Value>,
+    /// The remote version the change was made on top of, if known, so the
+    /// backend can detect conflicting edits
+    pub base_version: Option<String>,
+}
+
+/// What the sync engine is doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum SyncState {
+    /// No backend is configured
+    Disabled,
+    /// Waiting for the next scheduled sync
+    Idle,
+    Syncing,
+    /// The last sync failed; retrying at `next_attempt_at`
+    Failed,
+}
+
+/// The sync engine's status, from `get_sync_status` and broadcast as
+/// `sync-status` whenever it changes.
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct SyncStatus {
+    pub state: SyncState,
+    /// Local changes not pushed yet
+    pub pending_changes: u32,
+    /// When the last successful sync finished, in milliseconds since the Unix
+    /// epoch
+    pub last_synced_at: Option<f64>,
+    /// Why the last sync failed, for `Failed`
+    pub last_error: Option<String>,
+    /// When the next sync is scheduled, in milliseconds since the Unix epoch
+    pub next_attempt_at: Option<f64>,
+}
+
+// ============================================================================
+// WebSockets
+// ============================================================================
+
+/// A WebSocket message, sent with `ws_send` or received in a `WsEvent`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "type")]
+pub enum WsMessage {
+    Text { text: String },
+    Binary { bytes: Vec<u8> },
+}
+
+/// The state of a connection opened with `ws_connect`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum WsStatus {
+    /// Opening for the first time
+    Connecting,
+    Open,
+    /// Lost, and waiting to connect again
+    Reconnecting,
+    /// Closed with `ws_close`; the id is no longer valid
+    Closed,
+}
+
+/// A connection opened with `ws_connect`, from `ws_connections`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct WsConnection {
+    pub id: u32,
+    pub url: String,
+    pub status: WsStatus,
+}
+
+/// Event: a message arrived on a WebSocket connection, or its status changed.
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+#[serde(tag = "type")]
+pub enum WsEvent {
+    Message {
+        id: u32,
+        message: WsMessage,
+    },
+    StatusChanged {
+        id: u32,
+        status: WsStatus,
+        /// Why the connection was lost, for `Reconnecting`
+        error: Option<String>,
+    },
+}
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+/// The error type for commands. Serializes as `{ type, message }`, so the
+/// frontend can branch on the kind of failure and show `message` to the user.
+///
+/// `From` impls cover the common sources, so `?` works on I/O, JSON, database
+/// and Tauri errors and on the typed errors of older commands. `String` errors
+/// have no conversion, since their kind is unknown; map them to the variant
+/// that fits with `map_err`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "type")]
+pub enum AppError {
+    /// Reading or writing a file failed
+    Io { message: String },
+    /// The input was invalid; `message` says why
+    Validation { message: String },
+    /// The file, record or other thing asked for doesn't exist
+    NotFound { message: String },
+    /// JSON (or another format) couldn't be read or written
+    Serialization { message: String },
+    /// The OS, keychain or user denied access
+    Permission { message: String },
+    /// The command was called too often and was rejected without running
+    TooManyRequests {
+        message: String,
+        /// How long until a call would be accepted
+        retry_after_ms: u32,
+    },
+    /// Anything else
+    Internal { message: String },
+}
+
+impl AppError {
+    /// The user-facing description, without the kind.
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::Io { message }
+            | AppError::Validation { message }
+            | AppError::NotFound { message }
+            | AppError::Serialization { message }
+            | AppError::Permission { message }
+            | AppError::TooManyRequests { message, .. }
+            | AppError::Internal { message } => message,
+        }
+    }
+
+    /// A `Validation` error, e.g. `.map_err(AppError::validation)` on the
+    /// `validate_*` helpers.
+    pub fn validation(message: impl Into<String>) -> Self {
+        AppError::Validation {
+            message: message.into(),
+        }
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        AppError::Io {
+            message: message.into(),
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        AppError::NotFound {
+            message: message.into(),
+        }
+    }
+
+    /// An `Internal` error, for failures the caller can't do anything about.
+    pub fn internal(message: impl Into<String>) -> Self {
+        AppError::Internal {
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        let message = e.to_string();
+        match e.kind() {
+            std::io::ErrorKind::NotFound => AppError::NotFound { message },
+            std::io::ErrorKind::PermissionDenied => AppError::Permission { message },
+            _ => AppError::Io { message },
+        }
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        AppError::Serialization {
+            message: e.to_string(),
+        }
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(e: rusqlite::Error) -> Self {
+        let message = format!("Database error: {e}");
+        match e {
+            rusqlite::Error::QueryReturnedNoRows => AppError::NotFound { message },
+            _ => AppError::Internal { message },
+        }
+    }
+}
+
+impl From<tauri::Error> for AppError {
+    fn from(e: tauri::Error) -> Self {
+        AppError::Internal {
+            message: e.to_string(),
+        }
+    }
+}
+
+/// Lets `String`-returning code call commands that return `AppError`.
+impl From<AppError> for String {
+    fn from(e: AppError) -> Self {
+        e.to_string()
+    }
+}
+
+impl From<FileError> for AppError {
+    fn from(e: FileError) -> Self {
+        let message = e.to_string();
+        match e {
+            FileError::NotFound { .. } => AppError::NotFound { message },
+            FileError::OutOfScope { .. } => AppError::Permission { message },
+            FileError::IoError { .. } => AppError::Io { message },
+            FileError::TooLarge { .. }
+            | FileError::InvalidPath { .. }
+            | FileError::InvalidEncoding { .. }
+            | FileError::InvalidImage { .. } => AppError::Validation { message },
+            FileError::Cancelled => AppError::Internal { message },
+        }
+    }
+}
+
+impl From<RecoveryError> for AppError {
+    fn from(e: RecoveryError) -> Self {
+        let message = e.to_string();
+        match e {
+            RecoveryError::FileNotFound => AppError::NotFound { message },
+            RecoveryError::ValidationError { .. } | RecoveryError::DataTooLarge { .. } => {
+                AppError::Validation { message }
+            }
+            RecoveryError::IoError { .. } => AppError::Io { message },
+            RecoveryError::ParseError { .. } => AppError::Serialization { message },
+            RecoveryError::Cancelled => AppError::Internal { message },
+            RecoveryError::TooManyRequests { retry_after_ms, .. } => AppError::TooManyRequests {
+                message,
+                retry_after_ms,
+            },
+        }
+    }
+}
+
+impl From<SecretError> for AppError {
+    fn from(e: SecretError) -> Self {
+        let message = e.to_string();
+        match e {
+            SecretError::NotFound => AppError::NotFound { message },
+            SecretError::Locked { .. } => AppError::Permission { message },
+            SecretError::ValidationError { .. } => AppError::Validation { message },
+            SecretError::PlatformError { .. } => AppError::Internal { message },
+        }
+    }
+}
+
+impl From<HttpError> for AppError {
+    fn from(e: HttpError) -> Self {
+        let message = e.to_string();
+        match e {
+            HttpError::InvalidRequest { .. } | HttpError::TooLarge { .. } => {
+                AppError::Validation { message }
+            }
+            HttpError::DecodeError { .. } => AppError::Serialization { message },
+            HttpError::Timeout | HttpError::Cancelled | HttpError::NetworkError { .. } => {
+                AppError::Internal { message }
+            }
+        }
+    }
+}
+
+impl From<AuthError> for AppError {
+    fn from(e: AuthError) -> Self {
+        let message = e.to_string();
+        match e {
+            AuthError::InvalidRequest { .. } => AppError::Validation { message },
+            AuthError::Denied { .. } | AuthError::NotSignedIn => AppError::Permission { message },
+            AuthError::Cancelled
+            | AuthError::Timeout
+            | AuthError::ExchangeFailed { .. }
+            | AuthError::StorageError { .. } => AppError::Internal { message },
+        }
+    }
+}
+
+// ============================================================================
+// Batch
+// ============================================================================
+
+/// A command `batch` can run, with its arguments. Only commands that just
+/// read are allowed, so a batch can run them in any order.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "command")]
+pub enum BatchRequest {
+    LoadPreferences,
+    KvGet { key: String },
+    ListRecoveryFiles,
+    GetRecentDocuments,
+    GetLaunchAtLogin,
+    GetLocaleInfo,
+    GetPowerState,
+}
+
+/// The result of a `BatchRequest`. The variant matches the request's
+/// command, or is `Error` if it failed.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "type")]
+pub enum BatchResponse {
+    LoadPreferences { data: AppPreferences },
+    KvGet { data: Option<serde_json::Value> },
+    ListRecoveryFiles { data: Vec<RecoveryArchiveEntry> },
+    GetRecentDocuments { data: Vec<String> },
+    GetLaunchAtLogin { data: bool },
+    GetLocaleInfo { data: LocaleInfo },
+    GetPowerState { data: PowerState },
+    Error { error: AppError },
+}
+
+// ============================================================================
+// Validation Functions
+// ============================================================================
+
+/// Validates a filename for safe file system operations.
+/// Only allows alphanumeric characters, dashes, underscores, and a single extension.
+pub fn validate_filename(filename: &str) -> Result<(), String> {
+    if filename.is_empty() {
+        return Err("Filename cannot be empty".to_string());
+    }
+
+    if filename.chars().count() > 100 {
+        return Err("Filename too long (max 100 characters)".to_string());
+    }
+
+    if !FILENAME_PATTERN.is_match(filename) {
+        return Err(
+            "Invalid filename: only alphanumeric characters, dashes, underscores, and dots allowed"
+                .to_string(),
+        );
+    }
+
+    Ok(())
+}
+
+/// Validates string input length (by character count, not bytes).
+pub fn validate_string_input(input: &str, max_len: usize, field_name: &str) -> Result<(), String> {
+    let char_count = input.chars().count();
+    if char_count > max_len {
+        return Err(format!("{field_name} too long (max {max_len} characters)"));
+    }
+    Ok(())
+}
+
+/// Validates theme value.
+pub fn validate_theme(theme: &str) -> Result<(), String> {
+    match theme {
+        "light" | "dark" | "system" => Ok(()),
+        _ => Err("Invalid theme: must be 'light', 'dark', or 'system'".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_error_conversions() {
+        let missing = std::io::Error::new(std::io::ErrorKind::NotFound, "gone");
+        assert!(matches!(AppError::from(missing), AppError::NotFound { .. }));
+        let denied = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "no");
+        assert!(matches!(
+            AppError::from(denied),
+            AppError::Permission { .. }
+        ));
+        let other = std::io::Error::other("disk full");
+        assert_eq!(
+            AppError::from(other),
+            AppError::Io {
+                message: "disk full".to_string()
+            }
+        );
+
+        let json = serde_json::from_str::<u32>("x").unwrap_err();
+        assert!(matches!(
+            AppError::from(json),
+            AppError::Serialization { .. }
+        ));
+        assert!(matches!(
+            AppError::from(rusqlite::Error::QueryReturnedNoRows),
+            AppError::NotFound { .. }
+        ));
+
+        let file = FileError::OutOfScope {
+            path: "/etc".to_string(),
+        };
+        assert_eq!(
+            AppError::from(file),
+            AppError::Permission {
+                message: "Path is outside the app's scope: /etc".to_string()
+            }
+        );
+        assert!(matches!(
+            AppError::from(RecoveryError::FileNotFound),
+            AppError::NotFound { .. }
+        ));
+        assert!(matches!(
+            AppError::from(AuthError::NotSignedIn),
+            AppError::Permission { .. }
+        ));
+    }
+
+    #[test]
+    fn test_app_error_serialization() {
+        let error = AppError::Validation {
+            message: "Key cannot be empty".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({ "type": "Validation", "message": "Key cannot be empty" })
+        );
+        assert_eq!(String::from(error), "Key cannot be empty");
+    }
+
+    #[test]
+    fn test_rate_limit_rejection_is_a_recovery_error() {
+        // The middleware rejects `save_emergency_data` with an AppError, so it
+        // must read as the command's own error type
+        let rejection = AppError::TooManyRequests {
+            message: "save_emergency_data was called too often".to_string(),
+            retry_after_ms: 500,
+        };
+        let json = serde_json::to_value(&rejection).unwrap();
+        let error: RecoveryError = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&error).unwrap(), json);
+        assert_eq!(AppError::from(error), rejection);
     }
 }
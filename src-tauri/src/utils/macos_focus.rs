@@ -0,0 +1,140 @@
+//! Tracks which app was frontmost before our floating windows appeared (macOS).
+//!
+//! Non-activating panels (like the quick pane) take keyboard focus without
+//! activating our app. When they go away, focus should return to whatever the
+//! user was working in. Call `capture_frontmost()` before showing such a window
+//! and `reactivate_previous()` after hiding it.
+//!
+//! Captures are kept on a stack so nested windows unwind correctly, e.g.
+//! quick pane → preferences window → back to the quick pane → back to the
+//! original app. Each capture should be paired with exactly one
+//! `reactivate_previous()` or `discard_previous()`.
+//!
+//! The stack itself is platform-independent so it can be unit tested.
+
+#[cfg(target_os = "macos")]
+use std::sync::Mutex;
+
+/// Maximum number of nested captures kept. Older entries are dropped first.
+const MAX_FOCUS_DEPTH: usize = 8;
+
+/// What was frontmost when a capture was taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusEntry {
+    /// Another app, identified by process ID
+    App(i32),
+    /// Our own app (nothing to reactivate)
+    Ourselves,
+}
+
+/// Bounded stack of focus captures.
+#[derive(Debug, Default)]
+pub struct FocusStack {
+    entries: Vec<FocusEntry>,
+}
+
+impl FocusStack {
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Pushes a capture, dropping the oldest entry if the stack is full.
+    pub fn push(&mut self, entry: FocusEntry) {
+        if self.entries.len() >= MAX_FOCUS_DEPTH {
+            self.entries.remove(0);
+        }
+        self.entries.push(entry);
+    }
+
+    /// Pops the most recent capture.
+    pub fn pop(&mut self) -> Option<FocusEntry> {
+        self.entries.pop()
+    }
+}
+
+#[cfg(target_os = "macos")]
+static FOCUS_STACK: Mutex<FocusStack> = Mutex::new(FocusStack::new());
+
+/// Records the currently frontmost app so it can be reactivated later.
+#[cfg(target_os = "macos")]
+pub fn capture_frontmost() {
+    use objc2_app_kit::NSWorkspace;
+
+    let entry = match NSWorkspace::sharedWorkspace()
+        .frontmostApplication()
+        .map(|app| app.processIdentifier())
+    {
+        Some(pid) if pid as u32 != std::process::id() => FocusEntry::App(pid),
+        _ => FocusEntry::Ourselves,
+    };
+
+    log::debug!("Captured frontmost app: {entry:?}");
+    if let Ok(mut stack) = FOCUS_STACK.lock() {
+        stack.push(entry);
+    }
+}
+
+/// Pops the most recent capture and reactivates that app, if it's another app
+/// that is still running.
+#[cfg(target_os = "macos")]
+pub fn reactivate_previous() {
+    use objc2_app_kit::{NSApplicationActivationOptions, NSRunningApplication};
+
+    let Some(FocusEntry::App(pid)) = pop_entry() else {
+        return;
+    };
+
+    match NSRunningApplication::runningApplicationWithProcessIdentifier(pid) {
+        Some(previous) => {
+            log::debug!("Reactivating previous app (pid {pid})");
+            previous.activateWithOptions(NSApplicationActivationOptions::empty());
+        }
+        None => log::debug!("Previous app (pid {pid}) is no longer running"),
+    }
+}
+
+/// Pops the most recent capture without reactivating anything.
+#[cfg(target_os = "macos")]
+pub fn discard_previous() {
+    pop_entry();
+}
+
+#[cfg(target_os = "macos")]
+fn pop_entry() -> Option<FocusEntry> {
+    FOCUS_STACK.lock().ok().and_then(|mut stack| stack.pop())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stack_unwinds_nested_captures() {
+        let mut stack = FocusStack::new();
+        stack.push(FocusEntry::App(42));
+        stack.push(FocusEntry::Ourselves);
+
+        assert_eq!(stack.pop(), Some(FocusEntry::Ourselves));
+        assert_eq!(stack.pop(), Some(FocusEntry::App(42)));
+        assert_eq!(stack.pop(), None);
+    }
+
+    #[test]
+    fn test_stack_drops_oldest_when_full() {
+        let mut stack = FocusStack::new();
+        for pid in 0..(MAX_FOCUS_DEPTH as i32 + 2) {
+            stack.push(FocusEntry::App(pid));
+        }
+
+        assert_eq!(stack.entries.len(), MAX_FOCUS_DEPTH);
+        assert_eq!(
+            stack.pop(),
+            Some(FocusEntry::App(MAX_FOCUS_DEPTH as i32 + 1))
+        );
+        // The two oldest entries were dropped
+        let remaining: Vec<_> = std::iter::from_fn(|| stack.pop()).collect();
+        assert_eq!(remaining.last(), Some(&FocusEntry::App(2)));
+    }
+}
@@ -1,3 +1,5 @@
 //! Utility modules for cross-platform support and common operations.
 
+#[cfg(any(target_os = "macos", test))]
+pub mod macos_focus;
 pub mod platform;
@@ -0,0 +1,124 @@
+//! Typed native window events.
+//!
+//! Forwards native window events from the run loop to the frontend as
+//! tauri-specta events, each carrying the window's label, so stores can
+//! subscribe with `events.windowFocusChanged.listen(...)` instead of
+//! string event names. Positions and sizes are in logical pixels.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, DragDropEvent, Manager, WindowEvent};
+use tauri_specta::Event;
+
+use crate::types::{
+    WindowFocusChanged, WindowFullscreenChanged, WindowMoved, WindowResized, WindowTheme,
+    WindowThemeChanged,
+};
+
+/// Last known fullscreen state by window label.
+/// Tauri has no fullscreen event, so changes are detected on resize.
+static FULLSCREEN_STATE: Mutex<BTreeMap<String, bool>> = Mutex::new(BTreeMap::new());
+
+fn emit<E: Event + serde::Serialize + Clone>(app: &AppHandle, event: E) {
+    if let Err(e) = event.emit(app) {
+        log::warn!("Failed to emit window event: {e}");
+    }
+}
+
+/// Records a window's fullscreen state, returning it if it changed.
+fn fullscreen_changed(label: &str, fullscreen: bool) -> Option<bool> {
+    let mut states = FULLSCREEN_STATE.lock().ok()?;
+    let previous = states.insert(label.to_string(), fullscreen);
+    (previous.unwrap_or(false) != fullscreen).then_some(fullscreen)
+}
+
+/// Emits the typed event matching a native window event, if there is one.
+/// Called from the run loop in `lib.rs`.
+pub fn forward_window_event(app: &AppHandle, label: &str, event: &WindowEvent) {
+    let Some(window) = app.get_webview_window(label) else {
+        // Destroyed windows can't be queried any more
+        if let WindowEvent::Destroyed = event {
+            if let Ok(mut states) = FULLSCREEN_STATE.lock() {
+                states.remove(label);
+            }
+        }
+        return;
+    };
+    let label = label.to_string();
+
+    match event {
+        WindowEvent::Focused(focused) => emit(
+            app,
+            WindowFocusChanged {
+                label,
+                focused: *focused,
+            },
+        ),
+        WindowEvent::Moved(position) => {
+            let scale_factor = window.scale_factor().unwrap_or(1.0);
+            let position = position.to_logical::<f64>(scale_factor);
+            emit(
+                app,
+                WindowMoved {
+                    label,
+                    x: position.x,
+                    y: position.y,
+                },
+            );
+        }
+        WindowEvent::Resized(size) => {
+            let scale_factor = window.scale_factor().unwrap_or(1.0);
+            let size = size.to_logical::<f64>(scale_factor);
+
+            let fullscreen = window.is_fullscreen().unwrap_or(false);
+            if let Some(fullscreen) = fullscreen_changed(&label, fullscreen) {
+                emit(
+                    app,
+                    WindowFullscreenChanged {
+                        label: label.clone(),
+                        fullscreen,
+                    },
+                );
+            }
+
+            emit(
+                app,
+                WindowResized {
+                    label,
+                    width: size.width,
+                    height: size.height,
+                },
+            );
+        }
+        WindowEvent::ThemeChanged(theme) => {
+            let theme = match theme {
+                tauri::Theme::Dark => WindowTheme::Dark,
+                _ => WindowTheme::Light,
+            };
+            emit(app, WindowThemeChanged { label, theme });
+        }
+        WindowEvent::DragDrop(DragDropEvent::Drop { paths, position }) => {
+            crate::drag_drop::handle_drop(app, &window, paths, *position);
+        }
+        WindowEvent::Destroyed => {
+            if let Ok(mut states) = FULLSCREEN_STATE.lock() {
+                states.remove(&label);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fullscreen_changed_only_reports_transitions() {
+        let label = "test-fullscreen";
+        assert_eq!(fullscreen_changed(label, false), None);
+        assert_eq!(fullscreen_changed(label, true), Some(true));
+        assert_eq!(fullscreen_changed(label, true), None);
+        assert_eq!(fullscreen_changed(label, false), Some(false));
+    }
+}
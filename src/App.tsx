@@ -1,12 +1,19 @@
 import { useEffect } from 'react'
-import { check } from '@tauri-apps/plugin-updater'
-import { relaunch } from '@tauri-apps/plugin-process'
 import { initializeCommandSystem } from './lib/commands'
-import { buildAppMenu, setupMenuLanguageListener } from './lib/menu'
+import {
+  buildAppMenu,
+  setupMenuLanguageListener,
+  setupMenuStateSync,
+} from './lib/menu'
 import { initializeLanguage } from './i18n/language-init'
 import { logger } from './lib/logger'
+import {
+  setupCrashReportListener,
+  setupGlobalErrorReporting,
+} from './lib/diagnostics'
 import { cleanupOldFiles } from './lib/recovery'
 import { commands } from './lib/tauri-bindings'
+import { setupUpdateAvailableListener } from './lib/updates'
 import './App.css'
 import { MainWindow } from './components/layout/MainWindow'
 import { ThemeProvider } from './components/ThemeProvider'
@@ -37,9 +44,17 @@ function App() {
         await buildAppMenu()
         logger.debug('Application menu built')
         setupMenuLanguageListener()
+        setupMenuStateSync()
       } catch (error) {
         logger.warn('Failed to initialize language or menu', { error })
       }
+
+      // Deliver events held back while the page loaded (e.g. a file the app
+      // was opened with), now listeners are registered
+      await commands.frontendReady()
+
+      // Reveal the main window (and close the splash) now the UI is ready
+      await commands.finishStartup()
     }
 
     initLanguageAndMenu()
@@ -55,60 +70,21 @@ function App() {
       mode: import.meta.env.MODE,
     })
 
-    // Auto-updater logic - check for updates 5 seconds after app loads
-    const checkForUpdates = async () => {
-      try {
-        const update = await check()
-        if (update) {
-          logger.info(`Update available: ${update.version}`)
-
-          // Show confirmation dialog
-          const shouldUpdate = confirm(
-            `Update available: ${update.version}\n\nWould you like to install this update now?`
-          )
-
-          if (shouldUpdate) {
-            try {
-              // Download and install with progress logging
-              await update.downloadAndInstall(event => {
-                switch (event.event) {
-                  case 'Started':
-                    logger.info(`Downloading ${event.data.contentLength} bytes`)
-                    break
-                  case 'Progress':
-                    logger.info(`Downloaded: ${event.data.chunkLength} bytes`)
-                    break
-                  case 'Finished':
-                    logger.info('Download complete, installing...')
-                    break
-                }
-              })
-
-              // Ask if user wants to restart now
-              const shouldRestart = confirm(
-                'Update completed successfully!\n\nWould you like to restart the app now to use the new version?'
-              )
-
-              if (shouldRestart) {
-                await relaunch()
-              }
-            } catch (updateError) {
-              logger.error(`Update installation failed: ${String(updateError)}`)
-              alert(
-                `Update failed: There was a problem with the automatic download.\n\n${String(updateError)}`
-              )
-            }
-          }
-        }
-      } catch (checkError) {
-        logger.error(`Update check failed: ${String(checkError)}`)
-        // Silent fail for update checks - don't bother user with network issues
-      }
-    }
+    // Uncaught errors go to the Rust log and crash report store
+    const cleanupErrorReporting = setupGlobalErrorReporting()
+
+    // Crash reports from the last run are announced once startup finishes
+    const cleanupCrashReportListener = setupCrashReportListener()
 
-    // Check for updates 5 seconds after app loads
-    const updateTimer = setTimeout(checkForUpdates, 5000)
-    return () => clearTimeout(updateTimer)
+    // Updates are checked in the background by Rust, which announces them
+    // with `update-available`
+    const cleanupUpdateListener = setupUpdateAvailableListener()
+
+    return () => {
+      cleanupErrorReporting()
+      cleanupCrashReportListener()
+      cleanupUpdateListener()
+    }
   }, [])
 
   return (
@@ -1,4 +1,5 @@
 import { Component, type ErrorInfo, type ReactNode } from 'react'
+import { reportFrontendError } from '@/lib/diagnostics'
 import { saveCrashState } from '@/lib/recovery'
 import { logger } from '@/lib/logger'
 
@@ -40,6 +41,10 @@ export class ErrorBoundary extends Component<Props, State> {
 
     this.setState({ errorInfo })
 
+    void reportFrontendError(error, {
+      componentStack: errorInfo.componentStack ?? null,
+    })
+
     // Save crash state asynchronously (don't block error UI)
     this.saveCrashData(error, errorInfo)
   }
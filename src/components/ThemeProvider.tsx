@@ -1,6 +1,6 @@
 import { useEffect, useLayoutEffect, useState, useRef } from 'react'
-import { emit } from '@tauri-apps/api/event'
 import { ThemeProviderContext, type Theme } from '@/lib/theme-context'
+import { events } from '@/lib/tauri-bindings'
 import { usePreferences } from '@/services/preferences'
 
 interface ThemeProviderProps {
@@ -34,6 +34,17 @@ export function ThemeProvider({
     }
   }, [preferences?.theme])
 
+  // Follow theme changes made in other windows (e.g., the preferences window)
+  useEffect(() => {
+    const unlisten = events.themeChanged.listen(event => {
+      setTheme(event.payload.theme as Theme)
+    })
+
+    return () => {
+      unlisten.then(fn => fn())
+    }
+  }, [])
+
   useEffect(() => {
     const root = window.document.documentElement
     const mediaQuery = window.matchMedia('(prefers-color-scheme: dark)')
@@ -60,7 +71,7 @@ export function ThemeProvider({
       localStorage.setItem(storageKey, newTheme)
       setTheme(newTheme)
       // Notify other windows (e.g., quick pane) of theme change
-      emit('theme-changed', { theme: newTheme })
+      events.themeChanged.emit({ theme: newTheme })
     },
   }
 
@@ -0,0 +1,47 @@
+import { useEffect } from 'react'
+import { Toaster } from 'sonner'
+import { ThemeProvider } from '@/components/ThemeProvider'
+import { initializeLanguage } from '@/i18n/language-init'
+import { commands } from '@/lib/tauri-bindings'
+import { logger } from '@/lib/logger'
+import { PreferencesContent } from './PreferencesContent'
+
+/**
+ * PreferencesWindowApp - Root of the native preferences window.
+ *
+ * Opened via the "Preferences…" menu item (see `open_preferences_window` in
+ * src-tauri/src/commands/window.rs). Renders the same panes as
+ * PreferencesDialog.
+ * Theme and language changes are broadcast to other windows as events.
+ */
+export default function PreferencesWindowApp() {
+  // Match the main window's language
+  useEffect(() => {
+    const initLanguage = async () => {
+      const result = await commands.loadPreferences()
+      const savedLanguage = result.status === 'ok' ? result.data.language : null
+      await initializeLanguage(savedLanguage)
+    }
+
+    initLanguage().catch(error => {
+      logger.warn('Failed to initialize preferences window language', {
+        error,
+      })
+    })
+  }, [])
+
+  // Deliver events held back while the page loaded. Keep this after the
+  // effects that register listeners.
+  useEffect(() => {
+    commands.frontendReady()
+  }, [])
+
+  return (
+    <ThemeProvider>
+      <div className="flex h-screen w-screen overflow-hidden bg-background font-sans">
+        <PreferencesContent paneClassName="h-[calc(100vh-4rem)]" />
+      </div>
+      <Toaster position="bottom-right" className="toaster group" />
+    </ThemeProvider>
+  )
+}
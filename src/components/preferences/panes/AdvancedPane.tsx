@@ -1,5 +1,6 @@
 import { useState } from 'react'
 import { useTranslation } from 'react-i18next'
+import { Button } from '@/components/ui/button'
 import { Label } from '@/components/ui/label'
 import { Switch } from '@/components/ui/switch'
 import {
@@ -10,9 +11,109 @@ import {
   SelectValue,
 } from '@/components/ui/select'
 import { SettingsField, SettingsSection } from '../shared/SettingsComponents'
+import {
+  usePreferences,
+  useSavePreferences,
+  useSetUpdateChannel,
+} from '@/services/preferences'
+import {
+  useLogDirectorySize,
+  useLogLevel,
+  useSetLogLevel,
+} from '@/services/logs'
+import { useDataLocation } from '@/services/data-location'
+import type { LogFormat, LogLevel, UpdateChannel } from '@/lib/tauri-bindings'
+import { purgeAnalyticsData } from '@/lib/analytics'
+import { restoreFromBackup, saveBackup } from '@/lib/backup'
+import { moveDataLocation } from '@/lib/data-location'
+import { setLocalDataEncryption } from '@/lib/encryption'
+import { saveDiagnosticsBundle } from '@/lib/diagnostics'
 
 export function AdvancedPane() {
   const { t } = useTranslation()
+  const { data: preferences } = usePreferences()
+  const savePreferences = useSavePreferences()
+  const setUpdateChannel = useSetUpdateChannel()
+  const { data: logDirectorySize } = useLogDirectorySize()
+  const { data: logLevel } = useLogLevel()
+  const setLogLevel = useSetLogLevel()
+  const { data: dataLocation } = useDataLocation()
+
+  const updateChannelLabels: Record<UpdateChannel, string> = {
+    Stable: t('preferences.advanced.updateChannelStable'),
+    Beta: t('preferences.advanced.updateChannelBeta'),
+    Nightly: t('preferences.advanced.updateChannelNightly'),
+  }
+
+  // Hours between automatic update checks; 0 turns them off
+  const updateCheckIntervals: [hours: string, label: string][] = [
+    ['1', t('preferences.advanced.updateCheckHourly')],
+    ['6', t('preferences.advanced.updateCheckSixHours')],
+    ['24', t('preferences.advanced.updateCheckDaily')],
+    ['0', t('preferences.advanced.updateCheckNever')],
+  ]
+
+  const handleUpdateCheckIntervalChange = (value: string) => {
+    if (!preferences) return
+    savePreferences.mutate({
+      ...preferences,
+      update_check_interval_hours: Number(value),
+    })
+  }
+
+  const logLevelLabels: Record<LogLevel, string> = {
+    Error: t('preferences.advanced.logLevelError'),
+    Warn: t('preferences.advanced.logLevelWarn'),
+    Info: t('preferences.advanced.logLevelInfo'),
+    Debug: t('preferences.advanced.logLevelDebug'),
+    Trace: t('preferences.advanced.logLevelTrace'),
+  }
+
+  const megabytes = (size: number) =>
+    t('preferences.advanced.megabytes', { size })
+
+  // Rotation settings are read at launch, so changes apply after a restart
+  const logMaxFileSizes = ['1', '5', '10', '50']
+  const logRetentionCounts: [count: string, label: string][] = [
+    ['0', t('preferences.advanced.logRetentionNone')],
+    ['1', '1'],
+    ['5', '5'],
+    ['10', '10'],
+  ]
+
+  const handleLogMaxFileSizeChange = (value: string) => {
+    if (!preferences) return
+    savePreferences.mutate({
+      ...preferences,
+      log_max_file_size_mb: Number(value),
+    })
+  }
+
+  const logFormatLabels: Record<LogFormat, string> = {
+    Text: t('preferences.advanced.logFormatText'),
+    Json: t('preferences.advanced.logFormatJson'),
+  }
+
+  const handleLogFormatChange = (value: string) => {
+    if (!preferences) return
+    savePreferences.mutate({ ...preferences, log_format: value as LogFormat })
+  }
+
+  const handleLogRetentionChange = (value: string) => {
+    if (!preferences) return
+    savePreferences.mutate({
+      ...preferences,
+      log_retention_count: Number(value),
+    })
+  }
+
+  const handleAnalyticsChange = (checked: boolean) => {
+    if (!preferences) return
+    savePreferences.mutate({ ...preferences, analytics_enabled: checked })
+    // Turning analytics off also deletes anything not yet sent
+    if (!checked) void purgeAnalyticsData()
+  }
+
   // Example local state - these are NOT persisted to disk
   // To add persistent preferences:
   // 1. Add the field to AppPreferences in both Rust and TypeScript
@@ -22,6 +123,255 @@ export function AdvancedPane() {
 
   return (
     <div className="space-y-6">
+      <SettingsSection title={t('preferences.advanced.updates')}>
+        <SettingsField
+          label={t('preferences.advanced.updateChannel')}
+          description={t('preferences.advanced.updateChannelDescription')}
+        >
+          <Select
+            value={preferences?.update_channel ?? 'Stable'}
+            onValueChange={value =>
+              setUpdateChannel.mutate(value as UpdateChannel)
+            }
+            disabled={!preferences || setUpdateChannel.isPending}
+          >
+            <SelectTrigger>
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {Object.entries(updateChannelLabels).map(([channel, label]) => (
+                <SelectItem key={channel} value={channel}>
+                  {label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        </SettingsField>
+
+        <SettingsField
+          label={t('preferences.advanced.updateCheck')}
+          description={t('preferences.advanced.updateCheckDescription')}
+        >
+          <Select
+            value={String(preferences?.update_check_interval_hours ?? 6)}
+            onValueChange={handleUpdateCheckIntervalChange}
+            disabled={!preferences || savePreferences.isPending}
+          >
+            <SelectTrigger>
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {updateCheckIntervals.map(([hours, label]) => (
+                <SelectItem key={hours} value={hours}>
+                  {label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        </SettingsField>
+      </SettingsSection>
+
+      <SettingsSection title={t('preferences.advanced.logs')}>
+        <SettingsField
+          label={t('preferences.advanced.logLevel')}
+          description={t('preferences.advanced.logLevelDescription')}
+        >
+          <Select
+            value={logLevel}
+            onValueChange={value => setLogLevel.mutate(value as LogLevel)}
+            disabled={!logLevel || setLogLevel.isPending}
+          >
+            <SelectTrigger>
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {Object.entries(logLevelLabels).map(([level, label]) => (
+                <SelectItem key={level} value={level}>
+                  {label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        </SettingsField>
+
+        <SettingsField
+          label={t('preferences.advanced.logMaxFileSize')}
+          description={t('preferences.advanced.logMaxFileSizeDescription')}
+        >
+          <Select
+            value={String(preferences?.log_max_file_size_mb ?? 5)}
+            onValueChange={handleLogMaxFileSizeChange}
+            disabled={!preferences || savePreferences.isPending}
+          >
+            <SelectTrigger>
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {logMaxFileSizes.map(size => (
+                <SelectItem key={size} value={size}>
+                  {megabytes(Number(size))}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        </SettingsField>
+
+        <SettingsField
+          label={t('preferences.advanced.logRetention')}
+          description={t('preferences.advanced.logRetentionDescription')}
+        >
+          <Select
+            value={String(preferences?.log_retention_count ?? 5)}
+            onValueChange={handleLogRetentionChange}
+            disabled={!preferences || savePreferences.isPending}
+          >
+            <SelectTrigger>
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {logRetentionCounts.map(([count, label]) => (
+                <SelectItem key={count} value={count}>
+                  {label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        </SettingsField>
+
+        <SettingsField
+          label={t('preferences.advanced.logFormat')}
+          description={t('preferences.advanced.logFormatDescription')}
+        >
+          <Select
+            value={preferences?.log_format ?? 'Text'}
+            onValueChange={handleLogFormatChange}
+            disabled={!preferences || savePreferences.isPending}
+          >
+            <SelectTrigger>
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {Object.entries(logFormatLabels).map(([format, label]) => (
+                <SelectItem key={format} value={format}>
+                  {label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        </SettingsField>
+
+        <SettingsField
+          label={t('preferences.advanced.logDiskUsage')}
+          description={t('preferences.advanced.logDiskUsageDescription')}
+        >
+          <p className="text-sm text-muted-foreground">
+            {logDirectorySize === undefined
+              ? '—'
+              : megabytes(
+                  Math.round((logDirectorySize / 1024 / 1024) * 10) / 10
+                )}
+          </p>
+        </SettingsField>
+
+        <SettingsField
+          label={t('preferences.advanced.diagnostics')}
+          description={t('preferences.advanced.diagnosticsDescription')}
+        >
+          <Button
+            variant="outline"
+            size="sm"
+            onClick={() => void saveDiagnosticsBundle()}
+          >
+            {t('preferences.advanced.exportDiagnostics')}
+          </Button>
+        </SettingsField>
+      </SettingsSection>
+
+      <SettingsSection title={t('preferences.advanced.data')}>
+        <SettingsField
+          label={t('preferences.advanced.backup')}
+          description={t('preferences.advanced.backupDescription')}
+        >
+          <div className="flex items-center gap-2">
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={() => void saveBackup()}
+            >
+              {t('preferences.advanced.createBackup')}
+            </Button>
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={() => void restoreFromBackup()}
+            >
+              {t('preferences.advanced.restoreBackup')}
+            </Button>
+          </div>
+        </SettingsField>
+
+        <SettingsField
+          label={t('preferences.advanced.dataLocation')}
+          description={t('preferences.advanced.dataLocationDescription')}
+        >
+          <div className="flex items-center gap-2">
+            <p
+              className="truncate text-sm text-muted-foreground"
+              title={dataLocation?.path}
+            >
+              {dataLocation?.path ?? '—'}
+            </p>
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={() => void moveDataLocation()}
+            >
+              {t('preferences.advanced.moveData')}
+            </Button>
+          </div>
+        </SettingsField>
+      </SettingsSection>
+
+      <SettingsSection title={t('preferences.advanced.privacy')}>
+        <SettingsField
+          label={t('preferences.advanced.analytics')}
+          description={t('preferences.advanced.analyticsDescription')}
+        >
+          <div className="flex items-center space-x-2">
+            <Switch
+              id="analytics-enabled"
+              checked={preferences?.analytics_enabled ?? false}
+              onCheckedChange={handleAnalyticsChange}
+              disabled={!preferences || savePreferences.isPending}
+            />
+            <Label htmlFor="analytics-enabled" className="text-sm">
+              {(preferences?.analytics_enabled ?? false)
+                ? t('common.enabled')
+                : t('common.disabled')}
+            </Label>
+          </div>
+        </SettingsField>
+
+        <SettingsField
+          label={t('preferences.advanced.encryptLocalData')}
+          description={t('preferences.advanced.encryptLocalDataDescription')}
+        >
+          <div className="flex items-center space-x-2">
+            <Switch
+              id="encrypt-local-data"
+              checked={preferences?.encrypt_local_data ?? false}
+              onCheckedChange={checked => void setLocalDataEncryption(checked)}
+              disabled={!preferences}
+            />
+            <Label htmlFor="encrypt-local-data" className="text-sm">
+              {(preferences?.encrypt_local_data ?? false)
+                ? t('common.enabled')
+                : t('common.disabled')}
+            </Label>
+          </div>
+        </SettingsField>
+      </SettingsSection>
+
       <SettingsSection title={t('preferences.advanced.title')}>
         <SettingsField
           label={t('preferences.advanced.toggle')}
@@ -13,6 +13,7 @@ import { SettingsField, SettingsSection } from '../shared/SettingsComponents'
 import { usePreferences, useSavePreferences } from '@/services/preferences'
 import { availableLanguages } from '@/i18n'
 import { logger } from '@/lib/logger'
+import { events } from '@/lib/tauri-bindings'
 
 // Language display names (native names)
 const languageNames: Record<string, string> = {
@@ -53,6 +54,8 @@ export function AppearancePane() {
           : 'en'
         await i18n.changeLanguage(targetLang)
       }
+      // Notify other windows (e.g., main window when in the preferences window)
+      await events.languageChanged.emit({ language: i18n.language })
     } catch (error) {
       logger.error('Failed to change language', { error })
       toast.error(t('toast.error.generic'))
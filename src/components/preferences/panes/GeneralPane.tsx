@@ -2,13 +2,23 @@ import { useState } from 'react'
 import { useTranslation } from 'react-i18next'
 import { useQuery } from '@tanstack/react-query'
 import { toast } from 'sonner'
+import { Button } from '@/components/ui/button'
 import { Switch } from '@/components/ui/switch'
 import { Input } from '@/components/ui/input'
 import { Label } from '@/components/ui/label'
 import { ShortcutPicker } from '../ShortcutPicker'
 import { SettingsField, SettingsSection } from '../shared/SettingsComponents'
-import { usePreferences, useSavePreferences } from '@/services/preferences'
-import { commands } from '@/lib/tauri-bindings'
+import {
+  useLaunchAtLogin,
+  usePreferences,
+  useSavePreferences,
+  useSetLaunchAtLogin,
+} from '@/services/preferences'
+import {
+  useNotificationPermission,
+  useRequestNotificationPermission,
+} from '@/services/notifications'
+import { commands, type AppError, type Result } from '@/lib/tauri-bindings'
 import { logger } from '@/lib/logger'
 
 export function GeneralPane() {
@@ -24,7 +34,7 @@ export function GeneralPane() {
   const { data: preferences } = usePreferences()
   const savePreferences = useSavePreferences()
 
-  // Get the default shortcut from the backend
+  // Get the default shortcuts from the backend
   const { data: defaultShortcut } = useQuery({
     queryKey: ['default-quick-pane-shortcut'],
     queryFn: async () => {
@@ -33,21 +43,37 @@ export function GeneralPane() {
     staleTime: Infinity, // Never refetch - this is a constant
   })
 
-  const handleShortcutChange = async (newShortcut: string | null) => {
+  const { data: defaultActivateAppShortcut } = useQuery({
+    queryKey: ['default-activate-app-shortcut'],
+    queryFn: async () => {
+      return await commands.getDefaultActivateAppShortcut()
+    },
+    staleTime: Infinity, // Never refetch - this is a constant
+  })
+
+  /**
+   * Registers a new global shortcut with the backend, then persists it.
+   * Rolls back the registration if saving preferences fails.
+   */
+  const changeShortcut = async (
+    key: 'quick_pane_shortcut' | 'activate_app_shortcut',
+    register: (shortcut: string | null) => Promise<Result<null, AppError>>,
+    newShortcut: string | null
+  ) => {
     if (!preferences) return
 
     // Capture old shortcut for rollback if save fails
-    const oldShortcut = preferences.quick_pane_shortcut
+    const oldShortcut = preferences[key]
 
-    logger.info('Updating quick pane shortcut', { oldShortcut, newShortcut })
+    logger.info('Updating shortcut', { key, oldShortcut, newShortcut })
 
     // First, try to register the new shortcut
-    const result = await commands.updateQuickPaneShortcut(newShortcut)
+    const result = await register(newShortcut)
 
     if (result.status === 'error') {
       logger.error('Failed to register shortcut', { error: result.error })
       toast.error(t('toast.error.shortcutFailed'), {
-        description: result.error,
+        description: result.error.message,
       })
       return
     }
@@ -56,16 +82,17 @@ export function GeneralPane() {
     try {
       await savePreferences.mutateAsync({
         ...preferences,
-        quick_pane_shortcut: newShortcut,
+        [key]: newShortcut,
       })
     } catch {
       // Save failed - roll back the backend registration
       logger.warn('Save failed, rolling back shortcut registration', {
+        key,
         oldShortcut,
         newShortcut,
       })
 
-      const rollbackResult = await commands.updateQuickPaneShortcut(oldShortcut)
+      const rollbackResult = await register(oldShortcut)
 
       if (rollbackResult.status === 'error') {
         logger.error(
@@ -85,6 +112,48 @@ export function GeneralPane() {
     }
   }
 
+  const handleShortcutChange = (newShortcut: string | null) =>
+    changeShortcut(
+      'quick_pane_shortcut',
+      commands.updateQuickPaneShortcut,
+      newShortcut
+    )
+
+  const handleActivateAppShortcutChange = (newShortcut: string | null) =>
+    changeShortcut(
+      'activate_app_shortcut',
+      commands.updateActivateAppShortcut,
+      newShortcut
+    )
+
+  const handleRestoreSessionChange = (checked: boolean) => {
+    if (!preferences) return
+    savePreferences.mutate({ ...preferences, restore_session: checked })
+  }
+
+  const { data: launchAtLogin } = useLaunchAtLogin()
+  const setLaunchAtLogin = useSetLaunchAtLogin()
+
+  // Notification permission, so users can fix it when notifications are off
+  const { data: notificationPermission } = useNotificationPermission()
+  const requestNotificationPermission = useRequestNotificationPermission()
+
+  const notificationStatusLabels = {
+    Granted: t('preferences.general.notificationsGranted'),
+    Denied: t('preferences.general.notificationsDenied'),
+    NotDetermined: t('preferences.general.notificationsNotDetermined'),
+  }
+
+  const handleOpenNotificationSettings = async () => {
+    const result = await commands.openNotificationSettings()
+    if (result.status === 'error') {
+      logger.error('Failed to open notification settings', {
+        error: result.error,
+      })
+      toast.error(result.error.message)
+    }
+  }
+
   return (
     <div className="space-y-6">
       <SettingsSection title={t('preferences.general.keyboardShortcuts')}>
@@ -100,6 +169,99 @@ export function GeneralPane() {
             disabled={!preferences || savePreferences.isPending}
           />
         </SettingsField>
+
+        <SettingsField
+          label={t('preferences.general.activateAppShortcut')}
+          description={t('preferences.general.activateAppShortcutDescription')}
+        >
+          <ShortcutPicker
+            value={preferences?.activate_app_shortcut ?? null}
+            // Fallback matches DEFAULT_ACTIVATE_APP_SHORTCUT in src-tauri/src/types.rs
+            defaultValue={
+              defaultActivateAppShortcut ?? 'CommandOrControl+Shift+Space'
+            }
+            onChange={handleActivateAppShortcutChange}
+            disabled={!preferences || savePreferences.isPending}
+          />
+        </SettingsField>
+      </SettingsSection>
+
+      <SettingsSection title={t('preferences.general.windows')}>
+        <SettingsField
+          label={t('preferences.general.restoreSession')}
+          description={t('preferences.general.restoreSessionDescription')}
+        >
+          <div className="flex items-center space-x-2">
+            <Switch
+              id="restore-session"
+              checked={preferences?.restore_session ?? true}
+              onCheckedChange={handleRestoreSessionChange}
+              disabled={!preferences || savePreferences.isPending}
+            />
+            <Label htmlFor="restore-session" className="text-sm">
+              {(preferences?.restore_session ?? true)
+                ? t('common.enabled')
+                : t('common.disabled')}
+            </Label>
+          </div>
+        </SettingsField>
+
+        <SettingsField
+          label={t('preferences.general.launchAtLogin')}
+          description={t('preferences.general.launchAtLoginDescription')}
+        >
+          <div className="flex items-center space-x-2">
+            <Switch
+              id="launch-at-login"
+              checked={launchAtLogin ?? false}
+              onCheckedChange={checked => setLaunchAtLogin.mutate(checked)}
+              disabled={
+                launchAtLogin === undefined || setLaunchAtLogin.isPending
+              }
+            />
+            <Label htmlFor="launch-at-login" className="text-sm">
+              {(launchAtLogin ?? false)
+                ? t('common.enabled')
+                : t('common.disabled')}
+            </Label>
+          </div>
+        </SettingsField>
+      </SettingsSection>
+
+      <SettingsSection title={t('preferences.general.notifications')}>
+        <SettingsField
+          label={t('preferences.general.notificationPermission')}
+          description={t(
+            'preferences.general.notificationPermissionDescription'
+          )}
+        >
+          <div className="flex items-center gap-3">
+            {notificationPermission && (
+              <span className="text-sm">
+                {notificationStatusLabels[notificationPermission]}
+              </span>
+            )}
+            {notificationPermission === 'NotDetermined' && (
+              <Button
+                variant="outline"
+                size="sm"
+                onClick={() => requestNotificationPermission.mutate()}
+                disabled={requestNotificationPermission.isPending}
+              >
+                {t('preferences.general.allowNotifications')}
+              </Button>
+            )}
+            {notificationPermission === 'Denied' && (
+              <Button
+                variant="outline"
+                size="sm"
+                onClick={() => void handleOpenNotificationSettings()}
+              >
+                {t('preferences.general.openNotificationSettings')}
+              </Button>
+            )}
+          </div>
+        </SettingsField>
       </SettingsSection>
 
       <SettingsSection title={t('preferences.general.exampleSettings')}>
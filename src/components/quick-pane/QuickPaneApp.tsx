@@ -1,12 +1,21 @@
 import { useState, useEffect, useRef } from 'react'
-import { emit, listen } from '@tauri-apps/api/event'
 import { getCurrentWindow } from '@tauri-apps/api/window'
-import { commands } from '@/lib/tauri-bindings'
+import { commands, events } from '@/lib/tauri-bindings'
 import { logger } from '@/lib/logger'
 
+/** Load previous submissions (most recent first), logging any errors */
+async function loadHistory(): Promise<string[]> {
+  const result = await commands.getQuickPaneHistory(null)
+  if (result.status === 'error') {
+    logger.error('Failed to load quick pane history', { error: result.error })
+    return []
+  }
+  return result.data
+}
+
 /** Dismiss the quick pane window, logging any errors */
 async function dismissQuickPane() {
-  const result = await commands.dismissQuickPane()
+  const result = await commands.dismissQuickPane(null)
   if (result.status === 'error') {
     logger.error('Failed to dismiss quick pane', { error: result.error })
   }
@@ -17,9 +26,10 @@ async function dismissQuickPane() {
  *
  * This component demonstrates the quick pane pattern:
  * - Single text input with submit on Enter
- * - Emits 'quick-pane-submit' event with the entered text
+ * - Submits the entered text via submitQuickPaneEntry (delivered to the main window)
  * - Theme synced with main window via localStorage
  * - Hides window on submit or Escape
+ * - Up/Down arrows recall previous submissions (history persisted in Rust)
  */
 // Apply theme from localStorage to document
 function applyTheme() {
@@ -41,13 +51,16 @@ function applyTheme() {
 
 export default function QuickPaneApp() {
   const [text, setText] = useState('')
+  const [history, setHistory] = useState<string[]>([])
+  // -1 means the user is editing a new entry rather than browsing history
+  const [historyIndex, setHistoryIndex] = useState(-1)
   const inputRef = useRef<HTMLInputElement>(null)
 
   // Apply theme on mount and listen for theme changes from main window
   useEffect(() => {
     applyTheme()
 
-    const unlisten = listen('theme-changed', () => {
+    const unlisten = events.themeChanged.listen(() => {
       applyTheme()
     })
 
@@ -65,10 +78,16 @@ export default function QuickPaneApp() {
           // Re-apply theme in case it changed while hidden
           applyTheme()
           inputRef.current?.focus()
+          setHistory(await loadHistory())
+          setHistoryIndex(-1)
         } else {
-          // Hide window when it loses focus (dismiss on blur)
-          // Use dismiss command for consistent behavior (no animation)
-          await dismissQuickPane()
+          // Hide window when it loses focus (dismiss on blur), unless pinned
+          const result = await commands.autoDismissQuickPane(null)
+          if (result.status === 'error') {
+            logger.error('Failed to auto-dismiss quick pane', {
+              error: result.error,
+            })
+          }
         }
       }
     )
@@ -91,17 +110,40 @@ export default function QuickPaneApp() {
     return () => window.removeEventListener('keydown', handleKeyDown)
   }, [])
 
+  // Deliver events held back while the page loaded, such as a quick entry
+  // deep link. Keep this after the effects that register listeners.
+  useEffect(() => {
+    commands.frontendReady()
+  }, [])
+
   const handleSubmit = async (e: React.FormEvent) => {
     e.preventDefault()
 
-    if (text.trim()) {
-      // Emit the event for main window to handle
-      await emit('quick-pane-submit', { text: text.trim() })
-      setText('')
+    if (!text.trim()) {
+      // Use dismiss command to avoid space switching on macOS
+      await dismissQuickPane()
+      return
+    }
+
+    // Delivers the entry to the main window and hides the pane in one call
+    const result = await commands.submitQuickPaneEntry(null, text.trim())
+    if (result.status === 'error') {
+      logger.error('Failed to submit quick pane entry', { error: result.error })
+      return
     }
+    setText('')
+  }
+
+  const handleInputKeyDown = (e: React.KeyboardEvent<HTMLInputElement>) => {
+    if (e.key !== 'ArrowUp' && e.key !== 'ArrowDown') return
+    e.preventDefault()
 
-    // Use dismiss command to avoid space switching on macOS
-    await dismissQuickPane()
+    const nextIndex =
+      e.key === 'ArrowUp'
+        ? Math.min(historyIndex + 1, history.length - 1)
+        : Math.max(historyIndex - 1, -1)
+    setHistoryIndex(nextIndex)
+    setText(nextIndex === -1 ? '' : (history[nextIndex] ?? ''))
   }
 
   return (
@@ -113,7 +155,11 @@ export default function QuickPaneApp() {
         ref={inputRef}
         type="text"
         value={text}
-        onChange={e => setText(e.target.value)}
+        onChange={e => {
+          setText(e.target.value)
+          setHistoryIndex(-1)
+        }}
+        onKeyDown={handleInputKeyDown}
         placeholder="Enter text..."
         className="w-full bg-transparent text-lg text-foreground placeholder:text-muted-foreground outline-none"
         autoComplete="off"
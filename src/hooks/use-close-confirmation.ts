@@ -0,0 +1,87 @@
+import { useEffect } from 'react'
+import { getCurrentWindow } from '@tauri-apps/api/window'
+import { ask } from '@tauri-apps/plugin-dialog'
+import i18n from '@/i18n/config'
+import { commands, events } from '@/lib/tauri-bindings'
+import { logger } from '@/lib/logger'
+
+/**
+ * Guards the current window against losing unsaved changes.
+ *
+ * Keeps the backend's dirty flag for this window in sync with `isDirty`. While
+ * dirty, closing the window (or quitting via the app menu) emits
+ * `close-requested` instead, and this hook asks the user whether to discard
+ * their changes before confirming or cancelling the close.
+ */
+export function useCloseConfirmation(isDirty: boolean) {
+  useEffect(() => {
+    const label = getCurrentWindow().label
+
+    commands.setWindowDirty(label, isDirty).then(result => {
+      if (result.status === 'error') {
+        logger.error('Failed to update window dirty state', {
+          error: result.error,
+        })
+      }
+    })
+
+    // Clear the flag when the component unmounts so the window can close
+    return () => {
+      if (isDirty) {
+        void commands.setWindowDirty(label, false)
+      }
+    }
+  }, [isDirty])
+
+  useEffect(() => {
+    const label = getCurrentWindow().label
+    let isMounted = true
+    let unlisten: (() => void) | null = null
+
+    events.closeRequested
+      .listen(async event => {
+        if (event.payload.label !== label) return
+
+        const t = i18n.t.bind(i18n)
+        const discard = await ask(
+          t(
+            event.payload.quitting
+              ? 'closeConfirm.quitMessage'
+              : 'closeConfirm.message'
+          ),
+          {
+            title: t('closeConfirm.title'),
+            kind: 'warning',
+            okLabel: t('closeConfirm.discard'),
+            cancelLabel: t('closeConfirm.cancel'),
+          }
+        )
+
+        if (discard) {
+          const result = await commands.confirmWindowClose(label)
+          if (result.status === 'error') {
+            logger.error('Failed to close window', { error: result.error })
+          }
+        } else {
+          await commands.cancelWindowClose(label)
+        }
+      })
+      .then(unlistenFn => {
+        if (!isMounted) {
+          unlistenFn()
+        } else {
+          unlisten = unlistenFn
+        }
+      })
+      .catch(error => {
+        logger.error('Failed to setup close-requested listener', { error })
+      })
+
+    return () => {
+      isMounted = false
+      if (unlisten) {
+        unlisten()
+      }
+    }
+  }, [])
+}
@@ -0,0 +1,46 @@
+import { useEffect, useRef } from 'react'
+import { getCurrentWindow } from '@tauri-apps/api/window'
+import { logger } from '@/lib/logger'
+
+/** Elements inside a drag region that should stay clickable. */
+const NO_DRAG_SELECTOR =
+  'button, a, input, textarea, select, [role="button"], [data-no-drag]'
+
+/**
+ * Makes an element (and its non-interactive children) drag the window.
+ *
+ * Unlike `data-tauri-drag-region`, which only applies to the element itself,
+ * this covers the whole subtree, so custom title bars can contain titles,
+ * icons and spacers without marking each one. Buttons, links, form fields and
+ * anything with `data-no-drag` keep working normally. Double-clicking toggles
+ * maximize, matching native title bars.
+ *
+ * @example
+ * const dragRef = useWindowDragRegion<HTMLDivElement>()
+ * return <header ref={dragRef}>...</header>
+ */
+export function useWindowDragRegion<T extends HTMLElement>() {
+  const ref = useRef<T>(null)
+
+  useEffect(() => {
+    const element = ref.current
+    if (!element) return
+
+    const handleMouseDown = (event: MouseEvent) => {
+      if (event.button !== 0) return
+      if ((event.target as Element).closest(NO_DRAG_SELECTOR)) return
+
+      const window = getCurrentWindow()
+      const action =
+        event.detail === 2 ? window.toggleMaximize() : window.startDragging()
+      action.catch(error => {
+        logger.error('Failed to drag window', { error })
+      })
+    }
+
+    element.addEventListener('mousedown', handleMouseDown)
+    return () => element.removeEventListener('mousedown', handleMouseDown)
+  }, [])
+
+  return ref
+}
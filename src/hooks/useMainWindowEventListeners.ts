@@ -1,5 +1,7 @@
 import { useEffect } from 'react'
-import { listen } from '@tauri-apps/api/event'
+import i18n from '@/i18n/config'
+import { handleMenuItemSelected } from '@/lib/menu'
+import { events } from '@/lib/tauri-bindings'
 import { useCommandContext } from './use-command-context'
 import { useKeyboardShortcuts } from './use-keyboard-shortcuts'
 import { useUIStore } from '@/store/ui-store'
@@ -11,6 +13,8 @@ import { logger } from '@/lib/logger'
  * This hook composes specialized hooks for different event types:
  * - useKeyboardShortcuts: Global keyboard shortcuts (Cmd+, Cmd+1, Cmd+2)
  * - Quick pane submit listener: Cross-window communication from quick pane
+ * - Language listener: Follows language changes made in the preferences window
+ * - Menu item listener: Runs actions for Rust-built menu items (e.g. the tray)
  */
 export function useMainWindowEventListeners() {
   const commandContext = useCommandContext()
@@ -22,13 +26,15 @@ export function useMainWindowEventListeners() {
     let isMounted = true
     let unlisten: (() => void) | null = null
 
-    listen<{ text: string }>('quick-pane-submit', event => {
-      logger.debug('Quick pane submit event received', {
-        text: event.payload.text,
+    events.quickEntrySubmitted
+      .listen(event => {
+        logger.debug('Quick entry submitted', {
+          label: event.payload.label,
+          text: event.payload.text,
+        })
+        const { setLastQuickPaneEntry } = useUIStore.getState()
+        setLastQuickPaneEntry(event.payload.text)
       })
-      const { setLastQuickPaneEntry } = useUIStore.getState()
-      setLastQuickPaneEntry(event.payload.text)
-    })
       .then(unlistenFn => {
         if (!isMounted) {
           unlistenFn()
@@ -37,7 +43,70 @@ export function useMainWindowEventListeners() {
         }
       })
       .catch(error => {
-        logger.error('Failed to setup quick-pane-submit listener', { error })
+        logger.error('Failed to setup quick-entry-submitted listener', {
+          error,
+        })
+      })
+
+    return () => {
+      isMounted = false
+      if (unlisten) {
+        unlisten()
+      }
+    }
+  }, [])
+
+  // Follow language changes made in other windows (e.g., the preferences window).
+  // Changing the language also rebuilds the native menu via its i18n listener.
+  useEffect(() => {
+    let isMounted = true
+    let unlisten: (() => void) | null = null
+
+    events.languageChanged
+      .listen(event => {
+        if (event.payload.language !== i18n.language) {
+          i18n.changeLanguage(event.payload.language).catch(error => {
+            logger.error('Failed to apply language change', { error })
+          })
+        }
+      })
+      .then(unlistenFn => {
+        if (!isMounted) {
+          unlistenFn()
+        } else {
+          unlisten = unlistenFn
+        }
+      })
+      .catch(error => {
+        logger.error('Failed to setup language-changed listener', { error })
+      })
+
+    return () => {
+      isMounted = false
+      if (unlisten) {
+        unlisten()
+      }
+    }
+  }, [])
+
+  // Run actions for menu items built in Rust (e.g. the tray menu)
+  useEffect(() => {
+    let isMounted = true
+    let unlisten: (() => void) | null = null
+
+    events.menuItemSelected
+      .listen(event => {
+        handleMenuItemSelected(event.payload.id)
+      })
+      .then(unlistenFn => {
+        if (!isMounted) {
+          unlistenFn()
+        } else {
+          unlisten = unlistenFn
+        }
+      })
+      .catch(error => {
+        logger.error('Failed to setup menu-item-selected listener', { error })
       })
 
     return () => {
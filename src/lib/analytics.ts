@@ -0,0 +1,33 @@
+/**
+ * Opt-in usage analytics.
+ *
+ * Events are only recorded when the user has turned on analytics in
+ * Preferences → Advanced; otherwise `trackEvent` does nothing. Rust checks the
+ * event's shape (snake_case names, at most 20 flat props) and queues it to be
+ * sent in batches. Never put user content (document text, file names, emails)
+ * in props.
+ */
+import { logger } from '@/lib/logger'
+import { commands } from '@/lib/tauri-bindings'
+
+type EventProps = Record<string, string | number | boolean | null>
+
+/** Record an analytics event, e.g. `trackEvent('document.exported', { format: 'pdf' })`. */
+export async function trackEvent(
+  name: string,
+  props?: EventProps
+): Promise<void> {
+  const result = await commands.trackEvent(name, props ?? null)
+  if (result.status === 'error') {
+    // Usually an invalid name or props, which is a bug in the caller
+    logger.warn('Failed to track event', { name, error: result.error })
+  }
+}
+
+/** Delete analytics events that haven't been sent yet. */
+export async function purgeAnalyticsData(): Promise<void> {
+  const result = await commands.purgeAnalyticsData()
+  if (result.status === 'error') {
+    logger.error('Failed to purge analytics data', { error: result.error })
+  }
+}
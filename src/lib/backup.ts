@@ -0,0 +1,67 @@
+/**
+ * Backing up and restoring all app data.
+ *
+ * Rust writes preferences, the database and recovery files to a zip with
+ * `createBackup`. `restoreBackup` checks the backup is compatible, then
+ * restarts the app to load it, so nothing needs reloading here.
+ */
+import { ask, open, save } from '@tauri-apps/plugin-dialog'
+import { toast } from 'sonner'
+import i18n from '@/i18n/config'
+import { logger } from '@/lib/logger'
+import { commands } from '@/lib/tauri-bindings'
+
+/**
+ * Ask where to save a backup and write it there.
+ * Returns whether a backup was saved.
+ */
+export async function saveBackup(): Promise<boolean> {
+  const date = new Date().toISOString().slice(0, 10)
+  const path = await save({
+    defaultPath: `backup-${date}.zip`,
+    filters: [{ name: 'Zip', extensions: ['zip'] }],
+  })
+  if (!path) return false
+
+  const result = await commands.createBackup(path, null)
+  if (result.status === 'error') {
+    logger.error('Failed to create backup', { error: result.error })
+    toast.error(
+      i18n.t('toast.error.backupFailed', { message: result.error.message })
+    )
+    return false
+  }
+
+  logger.info('Backup created', { files: result.data.files.length })
+  toast.success(i18n.t('toast.success.backupCreated'))
+  return true
+}
+
+/**
+ * Ask for a backup and, once the user confirms, restore it. The app
+ * restarts on success.
+ */
+export async function restoreFromBackup(): Promise<void> {
+  const path = await open({
+    multiple: false,
+    filters: [{ name: 'Zip', extensions: ['zip'] }],
+  })
+  if (!path) return
+
+  const t = i18n.t.bind(i18n)
+  const confirmed = await ask(t('backup.restoreConfirmMessage'), {
+    title: t('backup.restoreConfirmTitle'),
+    kind: 'warning',
+    okLabel: t('backup.restore'),
+    cancelLabel: t('backup.cancel'),
+  })
+  if (!confirmed) return
+
+  const result = await commands.restoreBackup(path)
+  if (result.status === 'error') {
+    logger.error('Failed to restore backup', { error: result.error })
+    toast.error(
+      t('toast.error.restoreFailed', { message: result.error.message })
+    )
+  }
+}
@@ -11,7 +11,7 @@ export const commands = {
 /**
  * Simple greeting command for demonstration purposes.
  */
-async greet(name: string) : Promise<Result<string, string>> {
+async greet(name: string) : Promise<Result<string, AppError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("greet", { name }) };
 } catch (e) {
@@ -23,7 +23,7 @@ async greet(name: string) : Promise<Result<string, string>> {
  * Loads user preferences from disk.
  * Returns default preferences if the file doesn't exist.
  */
-async loadPreferences() : Promise<Result<AppPreferences, string>> {
+async loadPreferences() : Promise<Result<AppPreferences, AppError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("load_preferences") };
 } catch (e) {
@@ -35,7 +35,7 @@ async loadPreferences() : Promise<Result<AppPreferences, string>> {
  * Saves user preferences to disk.
  * Uses atomic write (temp file + rename) to prevent corruption.
  */
-async savePreferences(preferences: AppPreferences) : Promise<Result<null, string>> {
+async savePreferences(preferences: AppPreferences) : Promise<Result<null, AppError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("save_preferences", { preferences }) };
 } catch (e) {
@@ -44,20 +44,93 @@ async savePreferences(preferences: AppPreferences) : Promise<Result<null, string
 }
 },
 /**
- * Sends a native system notification.
+ * Sends a native system notification and returns its id, which identifies it
+ * in `notification-clicked` and `notification-action` events.
  * On mobile platforms, returns an error as notifications are not yet supported.
  */
-async sendNativeNotification(title: string, body: string | null) : Promise<Result<null, string>> {
+async sendNativeNotification(title: string, body: string | null, options: NativeNotificationOptions | null) : Promise<Result<string, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("send_native_notification", { title, body }) };
+    return { status: "ok", data: await TAURI_INVOKE("send_native_notification", { title, body, options }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Saves emergency data to a JSON file for later recovery.
+ * Returns whether the app may show native notifications.
+ */
+async checkNotificationPermission() : Promise<Result<NotificationPermission, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_notification_permission") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Asks the user to allow notifications if they haven't been asked yet, and
+ * returns the resulting permission. The system only asks once, so a denied
+ * permission stays denied; use `open_notification_settings` instead.
+ */
+async requestNotificationPermission() : Promise<Result<NotificationPermission, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("request_notification_permission") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Opens the system settings where the user can turn notifications on or off.
+ */
+async openNotificationSettings() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_notification_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns whether the app has a permission, without asking the user.
+ */
+async checkPermission(kind: PermissionKind) : Promise<Result<PermissionStatus, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_permission", { kind }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Asks the user for a permission if the system allows it, and returns the
+ * resulting status. The system only asks once, so a denied permission stays
+ * denied; use `open_system_settings_pane` instead.
+ */
+async requestPermission(kind: PermissionKind) : Promise<Result<PermissionStatus, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("request_permission", { kind }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Opens the system settings pane where the user can grant or revoke a
+ * permission.
+ */
+async openSystemSettingsPane(kind: PermissionKind) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_system_settings_pane", { kind }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Saves emergency data for later recovery using the configured recovery store.
  * Validates filename and enforces a 10MB size limit.
+ * Emits `recovery-saved` on success.
  */
 async saveEmergencyData(filename: string, data: JsonValue) : Promise<Result<null, RecoveryError>> {
     try {
@@ -68,8 +141,9 @@ async saveEmergencyData(filename: string, data: JsonValue) : Promise<Result<null
 }
 },
 /**
- * Loads emergency data from a previously saved JSON file.
- * Returns FileNotFound if the file doesn't exist.
+ * Loads emergency data previously saved with `save_emergency_data`.
+ * Returns FileNotFound if no data exists for the filename.
+ * Emits `recovery-load-failed` for any other failure.
  */
 async loadEmergencyData(filename: string) : Promise<Result<JsonValue, RecoveryError>> {
     try {
@@ -80,8 +154,8 @@ async loadEmergencyData(filename: string) : Promise<Result<JsonValue, RecoveryEr
 }
 },
 /**
- * Removes recovery files older than 7 days.
- * Returns the count of removed files.
+ * Removes recovery data older than 7 days.
+ * Returns the count of removed entries and emits `recovery-cleaned`.
  */
 async cleanupOldRecoveryFiles() : Promise<Result<number, RecoveryError>> {
     try {
@@ -92,109 +166,3716 @@ async cleanupOldRecoveryFiles() : Promise<Result<number, RecoveryError>> {
 }
 },
 /**
- * Shows the quick pane window and makes it the key window (for keyboard input).
+ * Exports every entry in the recovery store into a single zip archive, one
+ * decrypted `recovery/<key>.json` file per entry. The archive includes a
+ * `manifest.json` listing each file and its timestamp.
+ * Useful for support tickets and manual backups. Pass a `task_id` to be able
+ * to stop it with `cancel_task`.
  */
-async showQuickPane() : Promise<Result<null, string>> {
+async exportRecoveryArchive(destPath: string, taskId: string | null) : Promise<Result<RecoveryArchiveManifest, RecoveryError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("show_quick_pane") };
+    return { status: "ok", data: await TAURI_INVOKE("export_recovery_archive", { destPath, taskId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Dismisses the quick pane window.
- * On macOS, resigns key window status before hiding to avoid activating main window.
+ * Backs up preferences, the database and recovery files into a zip at
+ * `dest_path` (absolute, ending in `.zip`). Pass a `task_id` to be able to
+ * stop it with `cancel_task`.
  */
-async dismissQuickPane() : Promise<Result<null, string>> {
+async createBackup(destPath: string, taskId: string | null) : Promise<Result<BackupManifest, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("dismiss_quick_pane") };
+    return { status: "ok", data: await TAURI_INVOKE("create_backup", { destPath, taskId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Toggles the quick pane window visibility.
+ * Restores a backup made by `create_backup`, then restarts the app to load
+ * it. Fails without changing anything if the backup is from a newer version
+ * of the app. Unsaved document changes are discarded.
  */
-async toggleQuickPane() : Promise<Result<null, string>> {
+async restoreBackup(srcPath: string) : Promise<Result<BackupManifest, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("toggle_quick_pane") };
+    return { status: "ok", data: await TAURI_INVOKE("restore_backup", { srcPath }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Returns the default shortcut constant for frontend use.
+ * Gets where the app's data is stored.
  */
-async getDefaultQuickPaneShortcut() : Promise<string> {
-    return await TAURI_INVOKE("get_default_quick_pane_shortcut");
+async getDataLocation() : Promise<Result<DataLocationInfo, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_data_location") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
 },
 /**
- * Updates the global shortcut for the quick pane.
- * Pass None to reset to default.
+ * Moves preferences, the database, recovery data and logs to
+ * `new_location` (an absolute path to an empty or new directory), then
+ * restarts the app to use them. Unsaved document changes are committed
+ * first.
  */
-async updateQuickPaneShortcut(shortcut: string | null) : Promise<Result<null, string>> {
+async moveAppData(newLocation: string) : Promise<Result<null, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("update_quick_pane_shortcut", { shortcut }) };
+    return { status: "ok", data: await TAURI_INVOKE("move_app_data", { newLocation }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
+},
+/**
+ * Runs a read-only SQL statement (e.g. `SELECT`) with positional `?`
+ * parameters and returns the rows. At most 10,000 rows.
+ */
+async dbQuery(sql: string, params: JsonValue[] | null) : Promise<Result<DbQueryResult, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("db_query", { sql, params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
 }
+},
+/**
+ * Runs a single SQL statement that changes data (`INSERT`, `UPDATE`,
+ * `DELETE`, ...) with positional `?` parameters.
+ */
+async dbExecute(sql: string, params: JsonValue[] | null) : Promise<Result<DbExecuteResult, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("db_execute", { sql, params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
 }
-
-/** user-defined events **/
-
-
-
-/** user-defined constants **/
-
-
-
-/** user-defined types **/
-
+},
 /**
- * Application preferences that persist to disk.
- * Only contains settings that should be saved between sessions.
+ * Lists notes, most recently updated first. An example of a typed query.
  */
-export type AppPreferences = { theme: string; 
+async listNotes() : Promise<Result<Note[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_notes") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Global shortcut for quick pane (e.g., "CommandOrControl+Shift+.")
- * If None, uses the default shortcut
+ * Creates a note. An example of a typed write with validation.
  */
-quick_pane_shortcut: string | null; 
+async createNote(title: string, body: string) : Promise<Result<Note, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_note", { title, body }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * User's preferred language (e.g., "en", "es", "de")
- * If None, uses system locale detection
+ * Gets the value stored under `key`, or null if there isn't one.
  */
-language: string | null }
-export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
+async kvGet(key: string) : Promise<Result<JsonValue | null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("kv_get", { key }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Error types for recovery operations (typed for frontend matching)
+ * Stores `value` under `key`, replacing any existing value. Values are
+ * limited to 64KB of JSON.
  */
-export type RecoveryError = 
+async kvSet(key: string, value: JsonValue) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("kv_set", { key, value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * File does not exist (expected case, not a failure)
+ * Removes `key`. Returns whether it existed.
  */
-{ type: "FileNotFound" } | 
+async kvDelete(key: string) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("kv_delete", { key }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Filename validation failed
+ * Lists entries whose keys start with `prefix` (all entries if omitted),
+ * sorted by key.
  */
-{ type: "ValidationError"; message: string } | 
+async kvList(prefix: string | null) : Promise<Result<KvEntry[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("kv_list", { prefix }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Data exceeds size limit
+ * Adds or replaces the indexed text for `id`.
  */
-{ type: "DataTooLarge"; max_bytes: number } | 
+async indexDocument(id: string, fields: SearchFields) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("index_document", { id, fields }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * File system read/write error
+ * Removes `id` from the index. Returns whether it was indexed.
  */
-{ type: "IoError"; message: string } | 
+async removeDocument(id: string) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_document", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * JSON serialization/deserialization error
+ * Searches indexed items, best match first. Every term must match, and the
+ * last is matched as a prefix. `limit` defaults to 20 (max 100).
+ */
+async search(query: string, limit: number | null) : Promise<Result<SearchResult[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("search", { query, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stores `bytes` under `key`, replacing any existing entry. With
+ * `ttl_seconds` the entry expires after that long; otherwise it stays
+ * until it's evicted for space. Entries are limited to 10MB.
+ */
+async cachePut(key: string, bytes: number[], ttlSeconds: number | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cache_put", { key, bytes, ttlSeconds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Gets the bytes cached under `key`, or null if they're missing or expired.
+ */
+async cacheGet(key: string) : Promise<Result<number[] | null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cache_get", { key }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Removes `key` from the cache. Returns whether it was cached.
+ */
+async cacheDelete(key: string) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cache_delete", { key }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Removes every cache entry. Returns how many were removed.
+ */
+async cacheClear() : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cache_clear") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Creates an empty document.
+ */
+async createDocument(title: string) : Promise<Result<Document, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_document", { title }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Saves a document's title and content. The buffer goes to the recovery
+ * store straight away; the database write (and its revision) happens once
+ * the document has been idle for 2 seconds, so call this on every change.
+ */
+async saveDocument(id: number, title: string, content: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_document", { id, title, content }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Commits all pending saves now, e.g. for an explicit Save or before
+ * closing a window. Returns how many documents were written.
+ */
+async flushDocuments() : Promise<number> {
+    return await TAURI_INVOKE("flush_documents");
+},
+/**
+ * Lists documents, most recently updated first.
+ */
+async listDocuments() : Promise<Result<DocumentSummary[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_documents") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Loads a document, committing any pending save first. `recovered` is set
+ * if the recovery store holds newer changes that were never committed.
+ */
+async loadDocument(id: number) : Promise<Result<Document, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("load_document", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Deletes a document, its revisions and any unsaved changes.
+ */
+async deleteDocument(id: number) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_document", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Lists a document's revisions, newest first. The newest 50 are kept.
+ */
+async listRevisions(documentId: number) : Promise<Result<RevisionSummary[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_revisions", { documentId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Loads a revision's title and content. To restore it, pass them to
+ * `save_document`, which records the restore as a new revision.
+ */
+async loadRevision(revisionId: number) : Promise<Result<DocumentRevision, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("load_revision", { revisionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Records an edit to `document_id`. `entry` is whatever the frontend needs
+ * to reverse and reapply the edit (max 64KB of JSON). Clears the redo stack
+ * and keeps the newest 100 entries.
+ */
+async pushUndoEntry(documentId: string, entry: JsonValue) : Promise<Result<UndoDepth, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("push_undo_entry", { documentId, entry }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Undoes the newest edit to `document_id`, returning its entry for the
+ * frontend to reverse, or null if there's nothing to undo.
+ */
+async undo(documentId: string) : Promise<Result<JsonValue | null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("undo", { documentId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Redoes the most recently undone edit to `document_id`, returning its
+ * entry for the frontend to reapply, or null if there's nothing to redo.
+ */
+async redo(documentId: string) : Promise<Result<JsonValue | null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("redo", { documentId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Gets how many edits to `document_id` can be undone and redone.
+ */
+async getUndoDepth(documentId: string) : Promise<Result<UndoDepth, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_undo_depth", { documentId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Forgets all undo history for `document_id`, e.g. when it's closed or
+ * deleted.
+ */
+async clearUndoHistory(documentId: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_undo_history", { documentId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stores `value` in the OS keychain, replacing any existing secret.
+ * Values are limited to 2560 bytes.
+ */
+async storeSecret(service: string, key: string, value: string) : Promise<Result<null, SecretError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("store_secret", { service, key, value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reads a secret from the OS keychain. Fails with `NotFound` if none is
+ * stored, and `Locked` if the keychain is locked or access was denied.
+ */
+async getSecret(service: string, key: string) : Promise<Result<string, SecretError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_secret", { service, key }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Removes a secret from the OS keychain. Returns whether one was stored.
+ */
+async deleteSecret(service: string, key: string) : Promise<Result<boolean, SecretError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_secret", { service, key }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Turns encryption of the database, preferences and recovery data on or
+ * off, then restarts the app to convert existing data. Turning it on fails
+ * if the OS keychain can't store the key.
+ */
+async setLocalDataEncryption(enabled: boolean) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_local_data_encryption", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Whether the app is set to launch when the user logs in, as the OS
+ * currently has it.
+ */
+async getLaunchAtLogin() : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_launch_at_login") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Turns launching at login on or off and saves the choice to preferences.
+ * On macOS the user may still need to allow the login item in System
+ * Settings.
+ */
+async setLaunchAtLogin(enabled: boolean) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_launch_at_login", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reads a UTF-8 text file (max 10MB) from a temp workspace or the fs scope.
+ * The app's own data and log directories are refused.
+ */
+async readTextFile(path: string) : Promise<Result<string, FileError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_text_file", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reads a file's bytes (max 25MB) from a temp workspace or the fs scope.
+ * The app's own data and log directories are refused.
+ */
+async readBinaryFile(path: string) : Promise<Result<number[], FileError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_binary_file", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Atomically writes a text file (max 10MB), creating or replacing it. The
+ * directory must exist, and the file must be in a temp workspace or the fs
+ * scope, outside the app's own data and log directories.
+ */
+async writeTextFile(path: string, contents: string) : Promise<Result<null, FileError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("write_text_file", { path, contents }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Moves a file or directory to the OS trash (Recycle Bin on Windows), so
+ * the user can restore it. Prefer this to deleting files the user owns.
+ */
+async trashFile(path: string) : Promise<Result<null, FileError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("trash_file", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Shows a file or directory in Finder, Explorer or the Linux file manager,
+ * with it selected in its folder.
+ */
+async revealInFileManager(path: string) : Promise<Result<null, FileError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reveal_in_file_manager", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Opens a file in its default app, or a directory in the file manager.
+ * Executables, scripts and installers are refused.
+ */
+async openWithDefaultApp(path: string) : Promise<Result<null, FileError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_with_default_app", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Shows a file or directory in the Quick Look panel on macOS, over the app
+ * like Finder's Space-bar preview. Other platforms have no Quick Look, so
+ * it's opened like `open_with_default_app` instead.
+ */
+async previewFile(path: string) : Promise<Result<null, FileError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_file", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Hashes a file of any size, returning the digest as lowercase hex. The file
+ * is read in chunks on a background thread. Pass a `task_id` to be able to
+ * stop it with `cancel_task`.
+ */
+async hashFile(path: string, algorithm: HashAlgorithm, taskId: string | null) : Promise<Result<string, FileError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("hash_file", { path, algorithm, taskId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Cancels the task started with `task_id`. Returns false if it already
+ * finished (or never started).
+ */
+async cancelTask(taskId: string) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_task", { taskId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Starts watching a file or directory (and, if `recursive`, everything
+ * below it). Returns an id for `unwatch` that's also included in each
+ * `fs-change` event.
+ */
+async watchPath(path: string, recursive: boolean) : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("watch_path", { path, recursive }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stops a watch started by `watch_path`. Returns false if there was no
+ * watch with this id.
+ */
+async unwatch(id: number) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unwatch", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Streams a file to `on_chunk` in chunks of `chunk_size` bytes (clamped to
+ * 4KB-4MB). Returns the stream id, which the frontend passes to
+ * `ack_stream` after handling each chunk and to `stop_stream` to cancel.
+ */
+async streamFile(path: string, chunkSize: number, onChunk: TAURI_CHANNEL<StreamMessage<FileChunk, FileError>>) : Promise<Result<number, FileError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stream_file", { path, chunkSize, onChunk }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Acknowledges an item from a paced stream, letting the next one be sent.
+ * Unknown ids are ignored, since the stream may already have finished.
+ */
+async ackStream(id: number) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("ack_stream", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stops a stream. No further messages are sent, not even `End`. Unknown ids
+ * are ignored, since the stream may already have finished.
+ */
+async stopStream(id: number) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_stream", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Creates (or reuses) a thumbnail no larger than `max_dimension` pixels on
+ * either side (clamped to 16-1024) and returns its path. Pass the path to
+ * `convertFileSrc` to display it. Supports PNG, JPEG, GIF, WebP and BMP.
+ */
+async generateThumbnail(path: string, maxDimension: number) : Promise<Result<string, FileError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("generate_thumbnail", { path, maxDimension }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Lists the files and folders the app has been given access to, oldest
+ * first. Folders include everything inside them.
+ */
+async listAllowedPaths() : Promise<Result<AllowedPath[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_allowed_paths") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Shows a native folder picker, starting in `default_path` if given, and
+ * gives the app lasting access to the chosen folder. Returns None if the
+ * user cancelled. Access is only ever granted through the picker, so the
+ * frontend can't grant itself arbitrary paths.
+ */
+async addAllowedPath(defaultPath: string | null) : Promise<Result<AllowedPath | null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_allowed_path", { defaultPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Removes access to a path returned by `list_allowed_paths`. Takes effect
+ * immediately; the path can't be granted again until the app restarts.
+ * Returns false if the path wasn't in the list.
+ */
+async revokeAllowedPath(path: string) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("revoke_allowed_path", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Creates an empty workspace directory and returns its path. `purpose`
+ * (e.g. "export" or "unzip") names the directory, to tell workspaces apart
+ * when debugging.
+ */
+async createTempDir(purpose: string) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_temp_dir", { purpose }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Removes a workspace from `create_temp_dir` and everything in it. Returns
+ * false if it no longer exists.
+ */
+async removeTempDir(path: string) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_temp_dir", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Removes every workspace, including ones still in use, and returns how
+ * many were removed. For a "Clear temporary files" action.
+ */
+async cleanupTempDirs() : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cleanup_temp_dirs") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Gets app, system and path details for the About dialog and bug reports.
+ * Diagnostics bundles include the same details, redacted, as `system.json`.
+ */
+async getDebugInfo() : Promise<Result<DebugInfo, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_debug_info") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Writes a diagnostics bundle for support tickets to `dest_path` (an absolute
+ * `.zip` path): recent logs, the preferences file, app and system info, and
+ * the recovery file listing. Secrets, email addresses and the home directory
+ * are redacted, and logs are capped at 20MB in total, newest first.
+ */
+async exportDiagnostics(destPath: string) : Promise<Result<DiagnosticsSummary, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_diagnostics", { destPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Records an analytics event, if the user has turned analytics on. Does
+ * nothing otherwise, so callers don't need to check. Invalid events are
+ * rejected whether or not analytics are on, so mistakes show up in
+ * development.
+ */
+async trackEvent(name: string, props: Partial<{ [key in string]: JsonValue }> | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("track_event", { name, props }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Deletes all queued analytics events. Called when the user turns analytics
+ * off, or to honour a request to delete collected data.
+ */
+async purgeAnalyticsData() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("purge_analytics_data") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Logs an uncaught frontend error and saves it as a crash report, with the
+ * window it came from and any app state in `context`.
+ */
+async reportFrontendError(error: string, stack: string | null, context: JsonValue | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("report_frontend_error", { error, stack, context }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Deletes a crash report once it has been sent or the user declined to send
+ * it, so it isn't announced again.
+ */
+async dismissCrashReport(id: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("dismiss_crash_report", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Shows a quick pane and makes it the key window (for keyboard input).
+ * Defaults to the main quick pane when no label is given.
+ */
+async showQuickPane(label: string | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("show_quick_pane", { label }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Dismisses a quick pane.
+ * On macOS, resigns key window status before hiding to avoid activating main window.
+ * Defaults to the main quick pane when no label is given.
+ */
+async dismissQuickPane(label: string | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("dismiss_quick_pane", { label }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Toggles a quick pane's visibility.
+ * Defaults to the main quick pane when no label is given.
+ */
+async toggleQuickPane(label: string | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("toggle_quick_pane", { label }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Submits an entry from a quick pane in a single backend operation: emits
+ * `quick-entry-submitted` to the main window, records it in the pane's history,
+ * hides the pane and (on macOS) reactivates the app that was frontmost before
+ * the pane was shown.
+ * Doing this in one call avoids the pane hiding before the payload is delivered.
+ */
+async submitQuickPaneEntry(label: string | null, text: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("submit_quick_pane_entry", { label, text }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Dismisses a quick pane after it loses focus, unless it is pinned. Focus
+ * stays with whatever the user clicked, rather than returning to the app that
+ * was frontmost before the pane was shown.
+ * The pane's blur handler should call this rather than `dismiss_quick_pane`.
+ * Defaults to the main quick pane when no label is given.
+ */
+async autoDismissQuickPane(label: string | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("auto_dismiss_quick_pane", { label }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Pins or unpins a quick pane. While pinned, the pane stays open on blur and
+ * after submitting, and doesn't reactivate the previous app, so it can be kept
+ * around during a longer task. Explicit dismissal (Escape, the shortcut) still works.
+ * Defaults to the main quick pane when no label is given.
+ */
+async setQuickPanePinned(label: string | null, pinned: boolean) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_quick_pane_pinned", { label, pinned }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sets how a quick pane is positioned the next time it's shown.
+ * Defaults to the main quick pane when no label is given.
+ */
+async setQuickPanePosition(label: string | null, position: QuickPanePosition) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_quick_pane_position", { label, position }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Re-centers a quick pane on the monitor the user is working on (the one with
+ * the cursor), ignoring any remembered position. Useful when the pane is already
+ * visible but appeared on a different display. On macOS, pair with
+ * `QuickPaneSpaces::MoveToActiveSpace` to also follow the active Space.
+ * Defaults to the main quick pane when no label is given.
+ */
+async recenterQuickPane(label: string | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("recenter_quick_pane", { label }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Resizes a quick pane to the given height (logical pixels), e.g. so a launcher can
+ * grow as results appear beneath the input. The height is clamped to the pane's
+ * min/max limits and the top edge stays fixed. Animated on macOS.
+ * Returns the applied height. Defaults to the main quick pane when no label is given.
+ */
+async resizeQuickPane(label: string | null, height: number) : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("resize_quick_pane", { label, height }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the default shortcut constant for frontend use.
+ */
+async getDefaultQuickPaneShortcut() : Promise<string> {
+    return await TAURI_INVOKE("get_default_quick_pane_shortcut");
+},
+/**
+ * Updates the global shortcut for the quick pane.
+ * Pass None to reset to default.
+ */
+async updateQuickPaneShortcut(shortcut: string | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_quick_pane_shortcut", { shortcut }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns a quick pane's previous submissions, most recent first.
+ * Defaults to the main quick pane when no label is given.
+ */
+async getQuickPaneHistory(label: string | null) : Promise<Result<string[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_quick_pane_history", { label }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Clears a quick pane's submission history.
+ * Defaults to the main quick pane when no label is given.
+ */
+async clearQuickPaneHistory(label: string | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_quick_pane_history", { label }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Records an opened document, moving it to the top of the recent list.
+ */
+async addRecentDocument(path: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_recent_document", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the recently opened documents, most recent first.
+ */
+async getRecentDocuments() : Promise<Result<string[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_recent_documents") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Clears the recent documents list, menu and (on macOS) Dock items.
+ */
+async clearRecentDocuments() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_recent_documents") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the default activate-app shortcut for frontend use.
+ */
+async getDefaultActivateAppShortcut() : Promise<string> {
+    return await TAURI_INVOKE("get_default_activate_app_shortcut");
+},
+/**
+ * Updates the global shortcut that brings the main window to the front.
+ * Pass None to reset to default.
+ */
+async updateActivateAppShortcut(shortcut: string | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_activate_app_shortcut", { shortcut }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Brings the main window to the front, e.g. from the Dock or tray menu.
+ */
+async showMainWindow() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("show_main_window") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Opens the native preferences window, creating it on first use and
+ * focusing it afterwards. Follows macOS conventions: titled, centered,
+ * fixed size, and not minimizable or maximizable.
+ */
+async openPreferencesWindow() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_preferences_window") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Creates an app window (document window, tool palette, detail view...) from a
+ * typed config. If a window with the same label already exists it is focused instead.
+ * Modal windows disable their parent until they close.
+ */
+async createAppWindow(config: WindowConfig) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_app_window", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Marks a window as having unsaved changes (or not). Dirty windows must
+ * confirm before they close or the app quits.
+ */
+async setWindowDirty(label: string, dirty: boolean) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_window_dirty", { label, dirty }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Quits the app, first asking every dirty window to confirm.
+ * Used by the Quit menu item instead of the predefined one, which bypasses
+ * exit handling on macOS (tauri-apps/tauri#9198).
+ */
+async requestAppQuit() : Promise<null> {
+    return await TAURI_INVOKE("request_app_quit");
+},
+/**
+ * Confirms that a dirty window may close, discarding its unsaved changes.
+ * Closes the window, or continues a pending quit once every window has confirmed.
+ */
+async confirmWindowClose(label: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("confirm_window_close", { label }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Cancels a close (or quit) after the user chose to keep editing.
+ */
+async cancelWindowClose(label: string) : Promise<null> {
+    return await TAURI_INVOKE("cancel_window_close", { label });
+},
+/**
+ * Changes a window's title bar style at runtime.
+ * On Windows and Linux, `Overlay` removes decorations and the other styles restore them.
+ */
+async setTitleBarStyle(label: string, style: TitleBarStyle) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_title_bar_style", { label, style }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Repositions a window's traffic lights, e.g. to center them in a taller
+ * custom title bar. Does nothing on other platforms.
+ */
+async setTrafficLightInset(label: string, inset: TrafficLightInset) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_traffic_light_inset", { label, inset }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Keeps a window above all other windows, or returns it to the normal level.
+ */
+async setAlwaysOnTop(label: string, alwaysOnTop: boolean) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_always_on_top", { label, alwaysOnTop }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sets how high a window floats, e.g. `Floating` for tool palettes that
+ * should stay above document windows.
+ * Platforms without window levels treat `Floating` and `Status` as always-on-top.
+ */
+async setWindowLevel(label: string, level: WindowLevel) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_window_level", { label, level }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Builds the app menu from a declarative spec, replacing any existing menu.
+ * Item clicks are delivered as `menu-item-selected` events with the item's id.
+ */
+async createAppMenu(spec: MenuSpec) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_app_menu", { spec }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Replaces the Dock menu. Items and submenus are supported; roles are skipped
+ * and accelerators ignored, since the Dock menu can't use them.
+ */
+async setDockMenu(items: MenuEntrySpec[]) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_dock_menu", { items }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Replaces the Jump List tasks. Does nothing outside Windows.
+ */
+async setJumpListTasks(tasks: JumpListTask[]) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_jump_list_tasks", { tasks }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Enables or disables a menu item or submenu.
+ */
+async setMenuItemEnabled(id: string, enabled: boolean) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_menu_item_enabled", { id, enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sets the checkmark on a check menu item.
+ */
+async setMenuItemChecked(id: string, checked: boolean) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_menu_item_checked", { id, checked }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Changes the title of a menu item or submenu.
+ */
+async setMenuItemText(id: string, text: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_menu_item_text", { id, text }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Inserts an item into a submenu (or the top level of the app menu when
+ * `parent_id` is None) at `position`, or at the end when it's None.
+ * Any entry type can be inserted, including whole submenus.
+ * Clicks are delivered as `menu-item-selected` events with the item's id.
+ */
+async insertMenuItem(parentId: string | null, position: number | null, item: MenuEntrySpec) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("insert_menu_item", { parentId, position, item }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Removes an item (or submenu) from the app menu by id.
+ */
+async removeMenuItem(id: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_menu_item", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Turns a window into a small always-on-top, frameless view in a screen corner.
+ * Uses the corner nearest the window when `corner` is None. Returns the corner used.
+ * Calling it again on a window already in mini mode just moves it.
+ */
+async enterMiniMode(label: string, corner: ScreenCorner | null) : Promise<Result<ScreenCorner, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("enter_mini_mode", { label, corner }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Restores a window's size, position and chrome from before it entered mini mode.
+ * Does nothing if the window isn't in mini mode.
+ */
+async exitMiniMode(label: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("exit_mini_mode", { label }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Snaps a mini window to the nearest screen corner, e.g. after the user drags it.
+ * Returns the corner used.
+ */
+async snapMiniWindow(label: string) : Promise<Result<ScreenCorner, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("snap_mini_window", { label }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Called by the frontend once it has rendered. Closes the splash window
+ * (if any) and reveals the main window. Later calls do nothing.
+ */
+async finishStartup() : Promise<null> {
+    return await TAURI_INVOKE("finish_startup");
+},
+/**
+ * Called by each window's frontend once its event listeners are registered.
+ * Sends the events queued for it, in order; later events are sent directly.
+ */
+async frontendReady() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("frontend_ready") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Shows progress on the app's dock or taskbar icon.
+ * Pass a fraction from 0.0 to 1.0, or None to hide the progress bar.
+ */
+async setProgress(fraction: number | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_progress", { fraction }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Shows a badge on the app's dock or taskbar icon, or clears it when None.
+ * Text badges are macOS only; elsewhere they are ignored.
+ */
+async setBadge(badge: AppBadge | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_badge", { badge }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sets the unread count shown in the dock or taskbar badge. Zero clears it.
+ */
+async setUnreadCount(count: number) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_unread_count", { count }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Turns automatic unread counting on or off. When on, each native
+ * notification sent while the main window is unfocused adds one to the
+ * unread count, and focusing the main window clears it.
+ */
+async setAutoUnreadCount(enabled: boolean) : Promise<null> {
+    return await TAURI_INVOKE("set_auto_unread_count", { enabled });
+},
+/**
+ * Replaces the tray menu, e.g. with translated labels from the frontend.
+ * Items and separators are supported. Does nothing if the tray icon isn't enabled.
+ */
+async setTrayMenu(items: MenuEntrySpec[]) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_tray_menu", { items }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sets the text shown next to the tray icon, e.g. a timer or unread count.
+ * macOS only; ignored elsewhere. Pass None to clear it.
+ */
+async setTrayTitle(title: string | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_tray_title", { title }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sets the tray icon's hover tooltip. Pass None to clear it.
+ */
+async setTrayTooltip(tooltip: string | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_tray_tooltip", { tooltip }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Checks the saved release channel for a newer version.
+ * The update is remembered so `download_and_install_update` can install it.
+ */
+async checkForUpdate() : Promise<Result<UpdateInfo | null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_for_update") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Downloads and installs an update, reporting `update-progress` events as
+ * it goes. Installs the update found by the last `check_for_update`, or
+ * checks first if there isn't one. Returns None if the app is up to date.
+ *
+ * On Windows the installer takes over and the app exits once the download
+ * finishes, so `ReadyToRestart` is only reported on macOS and Linux.
+ */
+async downloadAndInstallUpdate() : Promise<Result<UpdateInfo | null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("download_and_install_update") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Relaunches the app into an installed update. Windows with unsaved changes
+ * are asked to confirm first, as when quitting.
+ */
+async restartToUpdate() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restart_to_update") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Installs an update bundle downloaded by hand, for machines that can't reach
+ * the update server (behind a proxy, or air-gapped). Takes the updater bundle
+ * from a release (`.app.tar.gz`, `-setup.exe`, `.msi`, `.AppImage` or `.deb`)
+ * with its `.sig` file alongside, and only installs it if the signature
+ * matches the updater's public key. Any version is accepted, so this can also
+ * be used to roll back.
+ *
+ * As with `download_and_install_update`, the app exits on Windows once the
+ * installer starts; elsewhere, call `restart_to_update` afterwards.
+ */
+async installUpdateFromFile(path: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("install_update_from_file", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Switches the release channel used for update checks and saves it to
+ * preferences. Moving to a less frequent channel doesn't downgrade; the
+ * next update comes when that channel has a newer version.
+ */
+async setUpdateChannel(channel: UpdateChannel) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_update_channel", { channel }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Gets the release notes for every version after `from_version` up to and
+ * including `to_version`, newest first, e.g. the installed version and a
+ * pending update. Uses cached notes when they're recent or the changelog
+ * can't be reached, then the update manifest's notes for `to_version`.
+ */
+async getReleaseNotes(fromVersion: string, toVersion: string) : Promise<Result<ReleaseNotes, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_release_notes", { fromVersion, toVersion }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Gets startup milestones and per-command counts and timings since launch.
+ */
+async getPerformanceMetrics() : Promise<Result<PerformanceMetrics, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_performance_metrics") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the connected displays, primary first.
+ */
+async getDisplays() : Promise<Result<Display[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_displays") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns where the mouse cursor is.
+ */
+async getCursorPosition() : Promise<Result<CursorPosition, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_cursor_position") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Shows the system share sheet for text and files, anchored to `anchor` (a
+ * rectangle in the calling webview, e.g. the share button's bounds) or the
+ * middle of the window. Returns once the share sheet is shown; the user
+ * picks where to send the items, or cancels, from there.
+ */
+async shareItems(items: ShareItem[], anchor: ShareAnchor | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("share_items", { items, anchor }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns whether the app is the system default for a URL scheme
+ * (`"mailto"`) or an associated file extension (`".md"`).
+ */
+async isDefaultHandlerFor(schemeOrExtension: string) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("is_default_handler_for", { schemeOrExtension }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Makes the app the system default for a URL scheme or associated file
+ * extension, and returns whether it now is. macOS may ask the user to
+ * confirm. Windows only lets users change defaults for file types and
+ * well-known schemes, so there it opens Default apps in Settings and returns
+ * `false`; check again when the window regains focus.
+ */
+async setAsDefaultHandler(schemeOrExtension: string) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_as_default_handler", { schemeOrExtension }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the OS's language, region and formatting settings, so dates and
+ * numbers can be formatted like the rest of the system.
+ */
+async getLocaleInfo() : Promise<Result<LocaleInfo, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_locale_info") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sends an HTTP request from the backend, bypassing the webview's CORS
+ * rules, and returns the response whatever its status. Download progress is
+ * sent to `on_progress`. Pass a `cancel_token` in the request to be able to
+ * stop it with `cancel_fetch`.
+ */
+async fetch(request: HttpRequest, onProgress: TAURI_CHANNEL<HttpProgress>) : Promise<Result<HttpResponse, HttpError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("fetch", { request, onProgress }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Cancels the `fetch` started with `cancel_token`, which then fails with
+ * `Cancelled`. Returns false if it already finished.
+ */
+async cancelFetch(cancelToken: string) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_fetch", { cancelToken }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Uploads a file from disk to `url` as multipart/form-data, with `fields`
+ * as extra text fields and the file in the `file` field. The file is
+ * streamed rather than read into memory, and failed attempts are retried.
+ * Progress is sent to `on_progress`. Returns the server's response as text,
+ * whatever its status.
+ */
+async uploadFile(url: string, path: string, fields: UploadField[], onProgress: TAURI_CHANNEL<UploadProgress>) : Promise<Result<HttpResponse, HttpError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("upload_file", { url, path, fields, onProgress }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Subscribes to server-sent events at `url`, sending `headers` (e.g.
+ * `Authorization`) with each connection. Events arrive on `on_event` until
+ * `sse_unsubscribe`, reconnecting like `EventSource` when the stream drops.
+ * Returns the subscription id.
+ */
+async sseSubscribe(url: string, headers: HttpHeader[], onEvent: TAURI_CHANNEL<SseEvent>) : Promise<Result<number, HttpError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sse_subscribe", { url, headers, onEvent }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Closes a subscription from `sse_subscribe`. Returns whether it was open.
+ */
+async sseUnsubscribe(id: number) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sse_unsubscribe", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Downloads `url` to `destination` in the background, queued if
+ * `MAX_PARALLEL_DOWNLOADS` are already running. `destination` must be in the
+ * file scope and not exist yet. Follow it with `download-changed` events.
+ */
+async startDownload(url: string, destination: string) : Promise<Result<Download, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_download", { url, destination }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Pauses a queued or running download, keeping what's been downloaded.
+ */
+async pauseDownload(id: number) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("pause_download", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Resumes a paused or failed download from where it stopped. It's queued if
+ * `MAX_PARALLEL_DOWNLOADS` are already running.
+ */
+async resumeDownload(id: number) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("resume_download", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Cancels a download and deletes what it downloaded.
+ */
+async cancelDownload(id: number) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_download", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns all downloads, oldest first, including finished ones until
+ * `clear_finished_downloads`.
+ */
+async listDownloads() : Promise<Result<Download[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_downloads") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Removes completed, failed and cancelled downloads from the list. The
+ * downloaded files stay; partial data from failed downloads is deleted.
+ */
+async clearFinishedDownloads() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_finished_downloads") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Opens a WebSocket connection that reconnects automatically until
+ * `ws_close`. Returns its id; messages and status changes arrive as
+ * `ws-event`s.
+ */
+async wsConnect(url: string) : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("ws_connect", { url }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sends a message on an open connection. Fails while it's connecting or
+ * reconnecting, rather than queueing messages that may go stale.
+ */
+async wsSend(id: number, message: WsMessage) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("ws_send", { id, message }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Closes a connection and stops reconnecting. `ws-event` reports `Closed`
+ * once it's done.
+ */
+async wsClose(id: number) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("ws_close", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Lists open connections, e.g. to pick them up again after a webview reload.
+ */
+async wsConnections() : Promise<Result<WsConnection[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("ws_connections") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Signs in with an OAuth provider in the browser and stores the tokens in
+ * the keychain. Resolves once signed in (also sent as `auth-completed`), or
+ * fails after five minutes. Starting another sign-in cancels this one.
+ */
+async startAuth(request: AuthRequest) : Promise<Result<AuthSession, AuthError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_auth", { request }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Cancels the sign-in in progress, which then fails with `Cancelled`.
+ * Returns false if there wasn't one.
+ */
+async cancelAuth() : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_auth") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the session for `provider`, or None if not signed in.
+ */
+async getAuthSession(provider: string) : Promise<Result<AuthSession | null, AuthError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_auth_session", { provider }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Deletes the stored tokens for `provider`. Returns whether it was signed in.
+ * Doesn't revoke the tokens with the provider.
+ */
+async signOut(provider: string) : Promise<Result<boolean, AuthError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sign_out", { provider }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Queues a local change to a record for the next sync, replacing any change
+ * to the same record that hasn't been pushed yet.
+ */
+async queueSyncChange(change: SyncChange) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("queue_sync_change", { change }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Syncs now rather than at the next scheduled time.
+ */
+async syncNow() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sync_now") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the sync engine's current status.
+ */
+async getSyncStatus() : Promise<Result<SyncStatus, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_sync_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Advertises a service on the local network until `stop_advertising` or
+ * exit. Returns its full name, which peers see as `Peer.full_name`.
+ */
+async startAdvertising(service: LocalService) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_advertising", { service }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stops advertising a service started with `start_advertising`. Returns
+ * whether it was being advertised.
+ */
+async stopAdvertising(fullName: string) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_advertising", { fullName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Looks for peers advertising `service_type` (e.g. "_myapp._tcp") on the
+ * local network, until `stop_browsing`. Returns the peers already found if
+ * it's being browsed; after that, changes arrive as `peer-found` and
+ * `peer-lost` events.
+ */
+async browseServices(serviceType: string) : Promise<Result<Peer[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("browse_services", { serviceType }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stops looking for peers advertising `service_type`. Returns whether it was
+ * being browsed.
+ */
+async stopBrowsing(serviceType: string) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_browsing", { serviceType }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the current power source, battery level and lid state.
+ */
+async getPowerState() : Promise<PowerState> {
+    return await TAURI_INVOKE("get_power_state");
+},
+/**
+ * Keeps the system from sleeping while idle until `allow_sleep` is called
+ * with the returned token. With `keep_display_on`, the display stays on
+ * too, e.g. for playback. `reason` (e.g. "Exporting video") is shown in the
+ * OS's power diagnostics. Doesn't stop sleep the user asks for, like
+ * closing the lid.
+ */
+async preventSleep(reason: string, keepDisplayOn: boolean) : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("prevent_sleep", { reason, keepDisplayOn }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Releases a `prevent_sleep` assertion. Returns false if the token is
+ * unknown or was already released.
+ */
+async allowSleep(token: number) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("allow_sleep", { token }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Replaces the clipboard contents with every format given in `data`.
+ * Files must be in the app's file scope.
+ */
+async writeClipboard(data: ClipboardData) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("write_clipboard", { data }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reads every supported format on the clipboard. Images in other formats
+ * are converted to PNG.
+ */
+async readClipboard() : Promise<Result<ClipboardData, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_clipboard") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Dismisses a quick pane, returns focus to the app that was frontmost
+ * before it was shown and presses Cmd+V there. Write to the clipboard
+ * first. Needs the Accessibility permission, and the pane mustn't be
+ * pinned. macOS only.
+ * Defaults to the main quick pane when no label is given.
+ */
+async pasteIntoPreviousApp(label: string | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("paste_into_previous_app", { label }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the clipboard history, most recent first. Empty unless
+ * `WATCH_CLIPBOARD` is on.
+ */
+async getClipboardHistory() : Promise<Result<ClipboardEntry[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_clipboard_history") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Clears the clipboard history and deletes its saved images. The
+ * clipboard itself is left alone.
+ */
+async clearClipboardHistory() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_clipboard_history") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Gets the total size in bytes of the files in the log directory, including
+ * rotated logs. Saturates at 4 GiB.
+ */
+async getLogDirectorySize() : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_log_directory_size") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Streams the log to `on_lines` in batches: the last `backlog` lines (at
+ * most 10,000), then lines as they're appended, until `stop_stream` is
+ * called with the returned id.
+ */
+async followLogs(backlog: number, onLines: TAURI_CHANNEL<StreamMessage<string[], string>>) : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("follow_logs", { backlog, onLines }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Gets the current log level.
+ */
+async getLogLevel() : Promise<LogLevel> {
+    return await TAURI_INVOKE("get_log_level");
+},
+/**
+ * Changes the log level immediately and saves it to preferences, e.g. to
+ * turn on debug logging for a support session.
+ */
+async setLogLevel(level: LogLevel) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_log_level", { level }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Runs `requests` and returns their responses in the same order. With
+ * `parallel`, requests run concurrently; otherwise one after another.
+ */
+async batch(requests: BatchRequest[], parallel: boolean) : Promise<Result<BatchResponse[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("batch", { requests, parallel }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+}
+}
+
+/** user-defined events **/
+
+
+export const events = __makeEvents__<{
+authCompleted: AuthCompleted,
+clipboardChanged: ClipboardChanged,
+closeRequested: CloseRequested,
+crashReportAvailable: CrashReportAvailable,
+deepLinkOpened: DeepLinkOpened,
+downloadChanged: DownloadChanged,
+fileOpened: FileOpened,
+filesDropped: FilesDropped,
+fsChange: FsChange,
+languageChanged: LanguageChanged,
+menuItemSelected: MenuItemSelected,
+notificationAction: NotificationAction,
+notificationClicked: NotificationClicked,
+notificationPermissionChanged: NotificationPermissionChanged,
+peerFound: PeerFound,
+peerLost: PeerLost,
+powerChanged: PowerChanged,
+quickEntrySubmitted: QuickEntrySubmitted,
+recentDocumentSelected: RecentDocumentSelected,
+recoveryCleaned: RecoveryCleaned,
+recoveryLoadFailed: RecoveryLoadFailed,
+recoverySaved: RecoverySaved,
+syncStatus: SyncStatus,
+themeChanged: ThemeChanged,
+undoStateChanged: UndoStateChanged,
+updateAvailable: UpdateAvailable,
+updateProgress: UpdateProgress,
+windowFocusChanged: WindowFocusChanged,
+windowFullscreenChanged: WindowFullscreenChanged,
+windowMoved: WindowMoved,
+windowResized: WindowResized,
+windowThemeChanged: WindowThemeChanged,
+wsEvent: WsEvent
+}>({
+authCompleted: "auth-completed",
+clipboardChanged: "clipboard-changed",
+closeRequested: "close-requested",
+crashReportAvailable: "crash-report-available",
+deepLinkOpened: "deep-link-opened",
+downloadChanged: "download-changed",
+fileOpened: "file-opened",
+filesDropped: "files-dropped",
+fsChange: "fs-change",
+languageChanged: "language-changed",
+menuItemSelected: "menu-item-selected",
+notificationAction: "notification-action",
+notificationClicked: "notification-clicked",
+notificationPermissionChanged: "notification-permission-changed",
+peerFound: "peer-found",
+peerLost: "peer-lost",
+powerChanged: "power-changed",
+quickEntrySubmitted: "quick-entry-submitted",
+recentDocumentSelected: "recent-document-selected",
+recoveryCleaned: "recovery-cleaned",
+recoveryLoadFailed: "recovery-load-failed",
+recoverySaved: "recovery-saved",
+syncStatus: "sync-status",
+themeChanged: "theme-changed",
+undoStateChanged: "undo-state-changed",
+updateAvailable: "update-available",
+updateProgress: "update-progress",
+windowFocusChanged: "window-focus-changed",
+windowFullscreenChanged: "window-fullscreen-changed",
+windowMoved: "window-moved",
+windowResized: "window-resized",
+windowThemeChanged: "window-theme-changed",
+wsEvent: "ws-event"
+})
+
+/** user-defined constants **/
+
+
+
+/** user-defined types **/
+
+/**
+ * A file or folder the user has given the app lasting access to.
+ */
+export type AllowedPath = { path: string; 
+/**
+ * Folders give access to everything inside them
+ */
+is_directory: boolean }
+/**
+ * Badge shown on the app's dock or taskbar icon.
+ */
+export type AppBadge = 
+/**
+ * A number, such as an unread count. Zero clears the badge.
+ */
+{ Count: number } | 
+/**
+ * Short text (macOS only)
+ */
+{ Text: string }
+/**
+ * The error type for commands. Serializes as `{ type, message }`, so the
+ * frontend can branch on the kind of failure and show `message` to the user.
+ *
+ * `From` impls cover the common sources, so `?` works on I/O, JSON, database
+ * and Tauri errors and on the typed errors of older commands. `String` errors
+ * have no conversion, since their kind is unknown; map them to the variant
+ * that fits with `map_err`.
+ */
+export type AppError = 
+/**
+ * Reading or writing a file failed
+ */
+{ type: "Io"; message: string } | 
+/**
+ * The input was invalid; `message` says why
+ */
+{ type: "Validation"; message: string } | 
+/**
+ * The file, record or other thing asked for doesn't exist
+ */
+{ type: "NotFound"; message: string } | 
+/**
+ * JSON (or another format) couldn't be read or written
+ */
+{ type: "Serialization"; message: string } | 
+/**
+ * The OS, keychain or user denied access
+ */
+{ type: "Permission"; message: string } | 
+/**
+ * The command was called too often and was rejected without running
+ */
+{ type: "TooManyRequests"; message: string; 
+/**
+ * How long until a call would be accepted
+ */
+retry_after_ms: number } | 
+/**
+ * Anything else
+ */
+{ type: "Internal"; message: string }
+/**
+ * Application preferences that persist to disk.
+ * Only contains settings that should be saved between sessions.
+ */
+export type AppPreferences = { theme: string; 
+/**
+ * Global shortcut for quick pane (e.g., "CommandOrControl+Shift+.")
+ * If None, uses the default shortcut
+ */
+quick_pane_shortcut: string | null; 
+/**
+ * Global shortcut that brings the main window to the front
+ * If None, uses the default shortcut
+ */
+activate_app_shortcut: string | null; 
+/**
+ * User's preferred language (e.g., "en", "es", "de")
+ * If None, uses system locale detection
+ */
+language: string | null; 
+/**
+ * Reopen the windows that were open at quit on the next launch
+ */
+restore_session: boolean; 
+/**
+ * Release channel the updater checks for new versions
+ */
+update_channel: UpdateChannel; 
+/**
+ * Hours between automatic update checks, or 0 to only check manually
+ */
+update_check_interval_hours: number; 
+/**
+ * Size in MB at which the log file is rotated. Applies from the next launch.
+ */
+log_max_file_size_mb: number; 
+/**
+ * Rotated log files to keep, or 0 to keep only the current file.
+ * Applies from the next launch.
+ */
+log_retention_count: number; 
+/**
+ * Minimum level written to the log. If None, uses Debug in development
+ * and Info in production
+ */
+log_level: LogLevel | null; 
+/**
+ * Format of the log file. Applies from the next launch.
+ */
+log_format: LogFormat; 
+/**
+ * Send anonymous usage analytics. Off unless the user opts in
+ */
+analytics_enabled: boolean; 
+/**
+ * Encrypt the database, preferences and recovery data with a key kept
+ * in the OS keychain. Applies from the next launch.
+ */
+encrypt_local_data: boolean; 
+/**
+ * Last launch at login choice made in the app. The OS setting is the
+ * source of truth, since users can change it outside the app
+ */
+launch_at_login: boolean }
+/**
+ * Event: sign-in with `start_auth` finished.
+ */
+export type AuthCompleted = { session: AuthSession }
+/**
+ * Errors from signing in and reading tokens.
+ */
+export type AuthError = 
+/**
+ * The request was invalid, e.g. a non-HTTPS endpoint
+ */
+{ type: "InvalidRequest"; message: string } | 
+/**
+ * Stopped by `cancel_auth` or a newer `start_auth`
+ */
+{ type: "Cancelled" } | 
+/**
+ * The browser didn't return within five minutes
+ */
+{ type: "Timeout" } | 
+/**
+ * The provider refused, e.g. the user denied access
+ */
+{ type: "Denied"; error: string; description: string | null } | 
+/**
+ * The token endpoint failed or returned an error
+ */
+{ type: "ExchangeFailed"; message: string } | 
+/**
+ * No tokens are stored for the provider, or they expired and can't be
+ * refreshed
+ */
+{ type: "NotSignedIn" } | 
+/**
+ * Reading or writing the keychain failed
+ */
+{ type: "StorageError"; message: string }
+/**
+ * An OAuth provider to sign in with, for `start_auth`.
+ */
+export type AuthRequest = { 
+/**
+ * Name the tokens are stored under, e.g. "github"
+ */
+provider: string; 
+/**
+ * The provider's authorization endpoint
+ */
+authorization_url: string; 
+/**
+ * The provider's token endpoint
+ */
+token_url: string; client_id: string; scopes: string[]; 
+/**
+ * Port for the redirect URI, for providers that need it registered
+ * exactly. A random free port if None.
+ */
+port: number | null }
+/**
+ * A signed-in provider. Tokens stay in the keychain; Rust commands read them
+ * with `auth::access_token`.
+ */
+export type AuthSession = { provider: string; 
+/**
+ * Scopes granted, if the provider said
+ */
+scope: string | null; 
+/**
+ * When the access token expires, in milliseconds since the Unix epoch
+ */
+expires_at: number | null; 
+/**
+ * Whether the access token can be refreshed without signing in again
+ */
+refreshable: boolean }
+/**
+ * Manifest written into a backup as `manifest.json`, and returned by
+ * `create_backup` and `restore_backup`.
+ */
+export type BackupManifest = { 
+/**
+ * Layout version of the archive itself
+ */
+format_version: number; 
+/**
+ * When the backup was created, as a Unix timestamp in milliseconds
+ */
+created_at: number; 
+/**
+ * Application version that produced the backup
+ */
+app_version: string; 
+/**
+ * Database schema version (the last migration applied)
+ */
+schema_version: number; 
+/**
+ * Paths of the files in the archive, besides the manifest
+ */
+files: string[] }
+/**
+ * A command `batch` can run, with its arguments. Only commands that just
+ * read are allowed, so a batch can run them in any order.
+ */
+export type BatchRequest = { command: "LoadPreferences" } | { command: "KvGet"; key: string } | { command: "ListRecoveryFiles" } | { command: "GetRecentDocuments" } | { command: "GetLaunchAtLogin" } | { command: "GetLocaleInfo" } | { command: "GetPowerState" }
+/**
+ * The result of a `BatchRequest`. The variant matches the request's
+ * command, or is `Error` if it failed.
+ */
+export type BatchResponse = { type: "LoadPreferences"; data: AppPreferences } | { type: "KvGet"; data: JsonValue | null } | { type: "ListRecoveryFiles"; data: RecoveryArchiveEntry[] } | { type: "GetRecentDocuments"; data: string[] } | { type: "GetLaunchAtLogin"; data: boolean } | { type: "GetLocaleInfo"; data: LocaleInfo } | { type: "GetPowerState"; data: PowerState } | { type: "Error"; error: AppError }
+/**
+ * Broadcast to all windows when something new is copied
+ * (`clipboard-changed`).
+ */
+export type ClipboardChanged = { entry: ClipboardEntry }
+/**
+ * What was copied, as stored in the clipboard history.
+ */
+export type ClipboardContent = { kind: "Text"; text: string } | 
+/**
+ * Saved as a PNG in the app data directory. Display it with
+ * `convertFileSrc`
+ */
+{ kind: "Image"; path: string; width: number; height: number }
+/**
+ * Clipboard contents in several formats, for `write_clipboard` and
+ * `read_clipboard`. Formats that aren't present are None.
+ */
+export type ClipboardData = { text: string | null; html: string | null; rtf: string | null; 
+/**
+ * A PNG-encoded image
+ */
+png: number[] | null; 
+/**
+ * Absolute paths of copied files
+ */
+files: string[] | null }
+/**
+ * An entry in the clipboard history.
+ */
+export type ClipboardEntry = { 
+/**
+ * Hash of the content. Copying the same thing again moves its entry to
+ * the top rather than adding another
+ */
+id: string; content: ClipboardContent; 
+/**
+ * When it was copied, as a Unix timestamp in milliseconds
+ */
+copied_at: number }
+/**
+ * Emitted to a window with unsaved changes when it (or the app) is asked to
+ * close (`close-requested`). The frontend should prompt the user, then call
+ * `confirm_window_close` or `cancel_window_close`.
+ */
+export type CloseRequested = { label: string; 
+/**
+ * True when the whole app is quitting rather than just this window closing
+ */
+quitting: boolean }
+/**
+ * Call counts and handler time for one command.
+ */
+export type CommandTiming = { command: string; count: number; total_ms: number; average_ms: number; max_ms: number }
+/**
+ * A crash recorded by the panic hook, a fatal runtime error, or an error
+ * reported by the frontend.
+ */
+export type CrashReport = { 
+/**
+ * Identifies the report for `dismiss_crash_report`
+ */
+id: string; kind: CrashReportKind; 
+/**
+ * When the crash happened, as a Unix timestamp in milliseconds
+ */
+created_at: number; 
+/**
+ * Panic message or runtime error
+ */
+message: string; 
+/**
+ * Where the panic happened (`file:line:column`)
+ */
+location: string | null; 
+/**
+ * Name of the thread that panicked, if it had one
+ */
+thread: string | null; 
+/**
+ * Rust backtrace, or the JavaScript stack for frontend errors
+ */
+backtrace: string; 
+/**
+ * Label of the window a frontend error came from
+ */
+window: string | null; 
+/**
+ * App state the frontend attached to the error
+ */
+context: JsonValue | null; app_version: string; os: string; os_version: string; arch: string; 
+/**
+ * The last lines of the log before the crash
+ */
+log_lines: string[] }
+/**
+ * Emitted to the main window at launch when the app panicked last time,
+ * so the UI can offer to send the report.
+ */
+export type CrashReportAvailable = { report: CrashReport }
+/**
+ * What produced a crash report.
+ */
+export type CrashReportKind = 
+/**
+ * A Rust panic or fatal runtime error
+ */
+"Panic" | 
+/**
+ * An uncaught JavaScript error sent with `report_frontend_error`
+ */
+"Frontend"
+/**
+ * The mouse cursor's position in physical pixels, in desktop coordinates.
+ */
+export type CursorPosition = { x: number; y: number }
+/**
+ * Where the app's data is stored, from `get_data_location`.
+ */
+export type DataLocationInfo = { 
+/**
+ * Absolute path of the data directory
+ */
+path: string; 
+/**
+ * False if the user moved the data with `move_app_data`
+ */
+is_default: boolean }
+/**
+ * Returned by `db_execute`.
+ */
+export type DbExecuteResult = { rows_affected: number; 
+/**
+ * Row id of the last successful `INSERT` on the connection
+ */
+last_insert_id: number }
+/**
+ * Rows returned by `db_query`.
+ */
+export type DbQueryResult = { 
+/**
+ * Column names, in the order of each row's values
+ */
+columns: string[]; rows: JsonValue[][] }
+/**
+ * App, system and path details for the About dialog and bug reports.
+ */
+export type DebugInfo = { app_name: string; app_version: string; identifier: string; tauri_version: string; 
+/**
+ * WebView2, WKWebView or WebKitGTK version, if it could be read
+ */
+webview_version: string | null; debug_build: boolean; os: string; os_version: string; 
+/**
+ * `unix` or `windows`
+ */
+os_family: string; arch: string; locale: string | null; data_dir: string; log_dir: string; recovery_dir: string; 
+/**
+ * Optional features turned on in this build or by the user
+ */
+features: string[] }
+/**
+ * A parsed `tauri-app://` link. See `commands::deep_link` for the URL forms.
+ */
+export type DeepLink = 
+/**
+ * `tauri-app://documents/<id>`
+ */
+{ route: "OpenDocument"; id: number } | 
+/**
+ * `tauri-app://new?title=<title>`
+ */
+{ route: "NewDocument"; title: string | null } | 
+/**
+ * `tauri-app://quick-entry?text=<text>`, sent to the quick pane
+ */
+{ route: "QuickEntry"; text: string | null } | 
+/**
+ * Any other route, with its path (without the scheme) and query parameters
+ */
+{ route: "Other"; path: string; query: Partial<{ [key in string]: string }> }
+/**
+ * Emitted when the app is opened with a deep link (`deep-link-opened`), to
+ * the window that handles the link.
+ */
+export type DeepLinkOpened = { 
+/**
+ * The URL as received
+ */
+url: string; link: DeepLink }
+/**
+ * What `export_diagnostics` wrote, so the frontend can report it.
+ */
+export type DiagnosticsSummary = { 
+/**
+ * When the bundle was created, as a Unix timestamp in milliseconds
+ */
+created_at: number; 
+/**
+ * Paths of the files in the archive
+ */
+files: string[]; 
+/**
+ * Total size of the files before compression, in bytes
+ */
+size_bytes: number; 
+/**
+ * Whether older logs were left out or cut short to stay under the size cap
+ */
+logs_truncated: boolean }
+/**
+ * A connected display, as returned by `get_displays`.
+ */
+export type Display = { 
+/**
+ * The OS's name for the display, if it has one
+ */
+name: string | null; bounds: DisplayRect; 
+/**
+ * The bounds minus the menu bar, Dock and taskbars
+ */
+work_area: DisplayRect; 
+/**
+ * Physical pixels per logical pixel
+ */
+scale_factor: number; is_primary: boolean; 
+/**
+ * Whether the mouse cursor is on this display
+ */
+contains_cursor: boolean }
+/**
+ * A rectangle in physical pixels, in desktop coordinates.
+ */
+export type DisplayRect = { x: number; y: number; width: number; height: number }
+/**
+ * A document returned by `create_document` and `load_document`.
+ */
+export type Document = { id: number; title: string; content: string; 
+/**
+ * Unix timestamp in milliseconds
+ */
+created_at: number; 
+/**
+ * Unix timestamp in milliseconds of the last committed save
+ */
+updated_at: number; 
+/**
+ * Unsaved changes captured by the recovery store that never reached the
+ * database (e.g. the app crashed before autosave ran)
+ */
+recovered: RecoveredDocument | null }
+/**
+ * A saved snapshot of a document, returned by `load_revision`.
+ */
+export type DocumentRevision = { id: number; document_id: number; title: string; content: string; 
+/**
+ * Unix timestamp in milliseconds
+ */
+created_at: number }
+/**
+ * A document as listed by `list_documents`.
+ */
+export type DocumentSummary = { id: number; title: string; 
+/**
+ * Unix timestamp in milliseconds
+ */
+updated_at: number; revision_count: number }
+/**
+ * A download started with `start_download`.
+ */
+export type Download = { id: number; url: string; 
+/**
+ * The file being downloaded to
+ */
+destination: string; status: DownloadStatus; 
+/**
+ * Bytes downloaded so far
+ */
+downloaded: number; 
+/**
+ * Total bytes, if the server sent a size
+ */
+total: number | null; 
+/**
+ * Why the download failed, for `Failed`
+ */
+error: string | null }
+/**
+ * Event: a download's status or progress changed. Progress is sent at most
+ * four times a second per download.
+ */
+export type DownloadChanged = { download: Download }
+/**
+ * Where a download is up to.
+ */
+export type DownloadStatus = 
+/**
+ * Waiting for a free slot
+ */
+"Queued" | "Downloading" | "Paused" | "Completed" | 
+/**
+ * Stopped by an error; `resume_download` tries again
+ */
+"Failed" | "Cancelled"
+/**
+ * A file or directory dropped on a window.
+ */
+export type DroppedFile = { path: string; 
+/**
+ * File name, with extension
+ */
+name: string; is_directory: boolean; 
+/**
+ * Size in bytes. None for directories.
+ */
+size: number | null; 
+/**
+ * Guessed from the extension. None for directories and unknown types.
+ */
+mime_type: string | null; 
+/**
+ * Whether the webview can show the file directly: text, or a common image type
+ */
+has_preview: boolean }
+/**
+ * A chunk of a file from `stream_file`.
+ */
+export type FileChunk = { 
+/**
+ * Byte offset of this chunk in the file
+ */
+offset: number; 
+/**
+ * The chunk's bytes, base64-encoded
+ */
+data: string }
+/**
+ * Error types for scoped file commands (typed for frontend matching)
+ */
+export type FileError = 
+/**
+ * The file (or, when writing, its directory) doesn't exist
+ */
+{ type: "NotFound"; path: string } | 
+/**
+ * The path is outside the temp workspaces and the fs scope, or inside the
+ * app's own data and log directories
+ */
+{ type: "OutOfScope"; path: string } | 
+/**
+ * The file or contents exceed the command's size limit
+ */
+{ type: "TooLarge"; max_bytes: number } | 
+/**
+ * The path is relative, or is a directory where a file was expected
+ */
+{ type: "InvalidPath"; message: string } | 
+/**
+ * The file isn't valid UTF-8 text
+ */
+{ type: "InvalidEncoding"; path: string } | 
+/**
+ * The file isn't an image in a supported format, or is corrupt
+ */
+{ type: "InvalidImage"; path: string; message: string } | 
+/**
+ * Any other file system error
+ */
+{ type: "IoError"; message: string } | 
+/**
+ * Stopped with `cancel_task`
+ */
+{ type: "Cancelled" }
+/**
+ * Emitted to the main window when the OS opens a file with the app
+ * (`file-opened`). The file is readable through the fs plugin.
+ */
+export type FileOpened = { path: string }
+/**
+ * Emitted to a window when files are dropped on it (`files-dropped`).
+ * The position is in logical pixels, relative to the window.
+ */
+export type FilesDropped = { label: string; files: DroppedFile[]; x: number; y: number }
+/**
+ * Broadcast to all windows when a path watched with `watch_path` changes
+ * (`fs-change`).
+ */
+export type FsChange = { 
+/**
+ * The id returned by `watch_path`
+ */
+watch_id: number; change: FsChangeKind }
+/**
+ * A change to a watched path, as reported by `fs-change`.
+ */
+export type FsChangeKind = { kind: "Created"; path: string } | { kind: "Modified"; path: string } | { kind: "Removed"; path: string } | 
+/**
+ * Reported as `Removed` and `Created` instead when the platform can't
+ * pair the two sides, or one side is outside the watch
+ */
+{ kind: "Renamed"; from: string; to: string }
+/**
+ * Digest algorithm for `hash_file`.
+ */
+export type HashAlgorithm = "Sha256" | 
+/**
+ * Much faster than SHA-256 on large files; good for deduplication
+ */
+"Blake3"
+/**
+ * A run of text in a search result, marked if it matched the query.
+ */
+export type HighlightSegment = { text: string; highlighted: boolean }
+/**
+ * A request body. `Json` sets `Content-Type: application/json`; set the
+ * content type in `headers` for the others.
+ */
+export type HttpBody = { type: "Text"; text: string } | { type: "Json"; value: JsonValue } | { type: "Bytes"; bytes: number[] }
+/**
+ * Error types for `fetch` (typed for frontend matching)
+ */
+export type HttpError = 
+/**
+ * The URL, a header or the body is invalid
+ */
+{ type: "InvalidRequest"; message: string } | 
+/**
+ * No response within the timeout
+ */
+{ type: "Timeout" } | 
+/**
+ * Cancelled with `cancel_fetch`
+ */
+{ type: "Cancelled" } | 
+/**
+ * The response body is bigger than `fetch` accepts
+ */
+{ type: "TooLarge"; max_bytes: number } | 
+/**
+ * The body isn't valid text or JSON for the requested `response_type`
+ */
+{ type: "DecodeError"; message: string } | 
+/**
+ * Connection, TLS or protocol failure
+ */
+{ type: "NetworkError"; message: string }
+/**
+ * A request or response header.
+ */
+export type HttpHeader = { name: string; value: string }
+export type HttpMethod = "GET" | "POST" | "PUT" | "PATCH" | "DELETE" | "HEAD" | "OPTIONS"
+/**
+ * Download progress sent to `fetch`'s `on_progress` channel, in bytes.
+ * `total` is None if the server didn't send a size.
+ */
+export type HttpProgress = { downloaded: number; total: number | null }
+/**
+ * A request for `fetch`.
+ */
+export type HttpRequest = { 
+/**
+ * An `http` or `https` URL
+ */
+url: string; method: HttpMethod; headers: HttpHeader[]; body: HttpBody | null; 
+/**
+ * Limit for the whole request, including reading the body. Defaults to
+ * 30 seconds.
+ */
+timeout_ms: number | null; response_type: HttpResponseType; 
+/**
+ * Caller-chosen id (e.g. a UUID) that `cancel_fetch` takes
+ */
+cancel_token: string | null }
+/**
+ * Returned by `fetch` for any status, including 4xx and 5xx.
+ */
+export type HttpResponse = { status: number; 
+/**
+ * Whether the status is 2xx
+ */
+ok: boolean; 
+/**
+ * The final URL, after redirects
+ */
+url: string; headers: HttpHeader[]; body: HttpResponseBody }
+/**
+ * A response body, decoded as the request's `response_type` asked.
+ */
+export type HttpResponseBody = { type: "Text"; text: string } | 
+/**
+ * An empty body is `null`
+ */
+{ type: "Json"; value: JsonValue } | { type: "Bytes"; bytes: number[] }
+/**
+ * How `fetch` should decode the response body.
+ */
+export type HttpResponseType = "Text" | "Json" | "Bytes"
+export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
+/**
+ * A task in the Windows taskbar Jump List. Choosing it relaunches the app
+ * with `--jump-list-task=<id>`, which runs the app menu action with that id.
+ */
+export type JumpListTask = { id: string; title: string; 
+/**
+ * Tooltip text
+ */
+description: string | null }
+/**
+ * An entry returned by `kv_list`.
+ */
+export type KvEntry = { key: string; value: JsonValue }
+/**
+ * Event: the language was changed in one window, for the others to follow.
+ * Emitted by the frontend.
+ */
+export type LanguageChanged = { language: string }
+/**
+ * A service to advertise on the local network with `start_advertising`.
+ */
+export type LocalService = { 
+/**
+ * DNS-SD service type, e.g. "_myapp._tcp"
+ */
+service_type: string; 
+/**
+ * Instance name shown to peers, e.g. "Alice's MacBook"
+ */
+name: string; port: number; 
+/**
+ * TXT record entries, e.g. a device id or protocol version
+ */
+properties: Partial<{ [key in string]: string }> }
+/**
+ * The OS's locale and formatting settings, returned by `get_locale_info`.
+ */
+export type LocaleInfo = { 
+/**
+ * BCP 47 tag, e.g. "en-GB"
+ */
+locale: string; 
+/**
+ * ISO 639 language code, e.g. "en"
+ */
+language: string; 
+/**
+ * ISO 3166 region code, e.g. "GB", if the locale has one
+ */
+region: string | null; first_day_of_week: Weekday; 
+/**
+ * Whether times use a 24-hour clock rather than AM/PM
+ */
+uses_24_hour_clock: boolean; decimal_separator: string; measurement_system: MeasurementSystem }
+/**
+ * Format of lines in the log file. Stdout always uses the readable text format.
+ */
+export type LogFormat = 
+/**
+ * `[date][time][target][LEVEL] message`
+ */
+"Text" | 
+/**
+ * One JSON object per line, for log ingestion tools
+ */
+"Json"
+/**
+ * Minimum level of messages written to the log, most severe first.
+ */
+export type LogLevel = "Error" | "Warn" | "Info" | "Debug" | "Trace"
+/**
+ * Units the user expects lengths, weights and temperatures in.
+ */
+export type MeasurementSystem = "Metric" | 
+/**
+ * US customary units (feet, pounds, Fahrenheit)
+ */
+"UnitedStates" | 
+/**
+ * Metric, with miles and pints. Only reported on macOS.
+ */
+"UnitedKingdom"
+/**
+ * One entry in a declarative menu definition (see `create_app_menu`).
+ */
+export type MenuEntrySpec = 
+/**
+ * A clickable item. `id` is also the app menu action run when it's clicked.
+ */
+{ type: "Item"; id: string; text: string; 
+/**
+ * Keyboard shortcut, e.g. `CmdOrCtrl+Shift+N`
+ */
+accelerator: string | null; 
+/**
+ * Defaults to true
+ */
+enabled: boolean | null; 
+/**
+ * Makes this a check item with the given initial state
+ */
+checked: boolean | null } | { type: "Submenu"; id: string | null; text: string; items: MenuEntrySpec[] } | 
+/**
+ * A standard item; `text` overrides the OS label (e.g. for translations)
+ */
+{ type: "Role"; role: MenuRole; text: string | null } | { type: "Separator" }
+/**
+ * Emitted to the main window when a Rust-built menu item (e.g. in the tray)
+ * is selected (`menu-item-selected`). `id` is the app menu action to run.
+ */
+export type MenuItemSelected = { id: string }
+/**
+ * Standard OS-provided menu items. Some only exist on macOS and are skipped elsewhere.
+ */
+export type MenuRole = "Hide" | "HideOthers" | "ShowAll" | "Services" | "Quit" | "Undo" | "Redo" | "Cut" | "Copy" | "Paste" | "SelectAll" | "Minimize" | "Zoom" | "Fullscreen" | "CloseWindow"
+/**
+ * A complete menu bar definition, built by `create_app_menu`.
+ */
+export type MenuSpec = { 
+/**
+ * Top-level entries (submenus on macOS)
+ */
+items: MenuEntrySpec[] }
+/**
+ * Optional settings for `send_native_notification`.
+ */
+export type NativeNotificationOptions = { 
+/**
+ * Identifies the notification in click and action events. Generated if not set.
+ */
+id: string | null; 
+/**
+ * Buttons shown on the notification, in order
+ */
+actions: NotificationActionButton[]; 
+/**
+ * Sound to play. Silent or the platform's usual behaviour if not set.
+ */
+sound: NotificationSound | null; 
+/**
+ * Absolute path to a PNG, JPEG or GIF image shown in the notification.
+ * Must be in the app's resource, data or cache directory, or somewhere
+ * the app has been given access to (e.g. a file picked in a dialog).
+ */
+image: string | null }
+/**
+ * A row in the example `notes` table.
+ */
+export type Note = { id: number; title: string; body: string; 
+/**
+ * Unix timestamp in milliseconds
+ */
+created_at: number; 
+/**
+ * Unix timestamp in milliseconds
+ */
+updated_at: number }
+/**
+ * Emitted to the main window when an action button on a native notification
+ * is clicked (`notification-action`).
+ */
+export type NotificationAction = { notification_id: string; 
+/**
+ * Id of the `NotificationActionButton` that was clicked
+ */
+action_id: string }
+/**
+ * A button shown on a native notification.
+ */
+export type NotificationActionButton = { 
+/**
+ * Sent back in `notification-action` when the button is clicked
+ */
+id: string; title: string }
+/**
+ * Emitted to the main window when a native notification is clicked
+ * (`notification-clicked`).
+ */
+export type NotificationClicked = { notification_id: string }
+/**
+ * Whether the app may show native notifications.
+ */
+export type NotificationPermission = "Granted" | 
+/**
+ * Turned off by the user; only the system settings can turn it back on
+ */
+"Denied" | 
+/**
+ * Not asked yet; `request_notification_permission` shows the prompt
+ */
+"NotDetermined"
+/**
+ * Emitted when notification permission changes, e.g. after the prompt or
+ * in the system settings (`notification-permission-changed`).
+ */
+export type NotificationPermissionChanged = { permission: NotificationPermission }
+/**
+ * Sound played when a native notification is shown.
+ */
+export type NotificationSound = 
+/**
+ * The platform's default notification sound
+ */
+{ type: "Default" } | 
+/**
+ * A sound provided by the OS, e.g. `Glass` on macOS, `Mail` on Windows or
+ * `message-new-instant` on Linux. Unknown names play the default sound.
+ */
+{ type: "System"; name: string } | 
+/**
+ * A sound file bundled with the app, relative to the resource directory
+ */
+{ type: "Resource"; path: string }
+/**
+ * A service found on the local network by `browse_services`.
+ */
+export type Peer = { service_type: string; 
+/**
+ * Instance name, e.g. "Alice's MacBook"
+ */
+name: string; 
+/**
+ * Unique name of the instance, e.g. "Alice's MacBook._myapp._tcp.local."
+ */
+full_name: string; host: string; port: number; 
+/**
+ * IPv4 and IPv6 addresses
+ */
+addresses: string[]; properties: Partial<{ [key in string]: string }> }
+/**
+ * Event: a peer appeared on the local network, or its details changed.
+ */
+export type PeerFound = { peer: Peer }
+/**
+ * Event: a peer left the local network or stopped advertising.
+ */
+export type PeerLost = { service_type: string; full_name: string }
+/**
+ * Returned by `get_performance_metrics`.
+ */
+export type PerformanceMetrics = { 
+/**
+ * Milliseconds since the process started
+ */
+uptime_ms: number; 
+/**
+ * Milestones reached so far, in order
+ */
+startup: StartupTiming[]; 
+/**
+ * Commands called so far, by total time (slowest first)
+ */
+commands: CommandTiming[] }
+/**
+ * A privacy-protected feature the app can ask permission for.
+ */
+export type PermissionKind = "Notifications" | 
+/**
+ * Controlling other apps, e.g. sending keystrokes (macOS)
+ */
+"Accessibility" | 
+/**
+ * Capturing the screen or other apps' windows (macOS)
+ */
+"ScreenRecording" | "Microphone" | "Camera" | 
+/**
+ * Sending Apple Events to `AUTOMATION_TARGET` in `permissions.rs` (macOS)
+ */
+"Automation"
+/**
+ * Whether the app has a permission.
+ */
+export type PermissionStatus = "Granted" | 
+/**
+ * Turned off by the user; only the system settings can turn it back on
+ */
+"Denied" | 
+/**
+ * Not asked yet; `request_permission` shows the prompt
+ */
+"NotDetermined" | 
+/**
+ * Blocked by parental controls or device management
+ */
+"Restricted"
+/**
+ * What changed, as reported by `power-changed`.
+ */
+export type PowerChange = 
+/**
+ * The system is about to sleep. There are only a few seconds left, so
+ * save anything important straight away
+ */
+{ kind: "Sleep" } | { kind: "Wake" } | { kind: "LidClosed" } | { kind: "LidOpened" } | { kind: "PowerSourceChanged"; source: PowerSource } | 
+/**
+ * The battery dropped to the low battery level while on battery power
+ */
+{ kind: "LowBattery"; percent: number }
+/**
+ * Broadcast to all windows on sleep, wake and power changes
+ * (`power-changed`).
+ */
+export type PowerChanged = { change: PowerChange; 
+/**
+ * Power state after the change
+ */
+state: PowerState }
+/**
+ * Where the computer is drawing power from.
+ */
+export type PowerSource = "Ac" | "Battery" | 
+/**
+ * Couldn't be determined, e.g. a desktop that doesn't report it
+ */
+"Unknown"
+/**
+ * Power state returned by `get_power_state` and sent with `power-changed`.
+ */
+export type PowerState = { source: PowerSource; 
+/**
+ * Charge of the internal battery in percent, or None without one
+ */
+battery_percent: number | null; 
+/**
+ * Whether the lid is closed, or None without a lid (or if it can't be read)
+ */
+lid_closed: boolean | null }
+/**
+ * Emitted to the main window when an entry is submitted from a quick pane
+ * (`quick-entry-submitted`).
+ */
+export type QuickEntrySubmitted = { 
+/**
+ * Label of the quick pane the entry came from
+ */
+label: string; text: string }
+/**
+ * Where a quick pane appears when shown.
+ * Positions are computed on the monitor containing the cursor.
+ */
+export type QuickPanePosition = 
+/**
+ * Centered on the monitor
+ */
+"Centered" | 
+/**
+ * Horizontally centered in the upper third, like Spotlight
+ */
+"TopThird" | 
+/**
+ * Just below the mouse cursor
+ */
+"NearCursor" | 
+/**
+ * Wherever the pane was last dismissed (centered the first time)
+ */
+"Remembered"
+/**
+ * Emitted to the main window when a document is chosen from the
+ * "Open Recent" menu (`recent-document-selected`).
+ */
+export type RecentDocumentSelected = { path: string }
+/**
+ * An unsaved document buffer found in the recovery store.
+ */
+export type RecoveredDocument = { title: string; content: string; 
+/**
+ * Unix timestamp in milliseconds
+ */
+saved_at: number }
+/**
+ * A single file included in a recovery archive export.
+ */
+export type RecoveryArchiveEntry = { 
+/**
+ * Path relative to the recovery directory (forward slashes)
+ */
+filename: string; 
+/**
+ * Last modification time as a Unix timestamp in milliseconds
+ */
+modified_at: number; 
+/**
+ * File size in bytes
+ */
+size_bytes: number }
+/**
+ * Manifest written into a recovery archive as `manifest.json`.
+ * Also returned to the frontend so it can report what was exported.
+ */
+export type RecoveryArchiveManifest = { 
+/**
+ * When the archive was created, as a Unix timestamp in milliseconds
+ */
+created_at: number; 
+/**
+ * Application version that produced the archive
+ */
+app_version: string; entries: RecoveryArchiveEntry[] }
+/**
+ * Emitted after old recovery data is cleaned up (`recovery-cleaned`).
+ */
+export type RecoveryCleaned = { removed_count: number }
+/**
+ * Error types for recovery operations (typed for frontend matching)
+ */
+export type RecoveryError = 
+/**
+ * File does not exist (expected case, not a failure)
+ */
+{ type: "FileNotFound" } | 
+/**
+ * Filename validation failed
+ */
+{ type: "ValidationError"; message: string } | 
+/**
+ * Data exceeds size limit
+ */
+{ type: "DataTooLarge"; max_bytes: number } | 
+/**
+ * File system read/write error
+ */
+{ type: "IoError"; message: string } | 
+/**
+ * JSON serialization/deserialization error
+ */
+{ type: "ParseError"; message: string } | 
+/**
+ * Stopped with `cancel_task`
+ */
+{ type: "Cancelled" } | 
+/**
+ * Called too often. Sent by the `RateLimit` middleware as
+ * `AppError::TooManyRequests`, which serializes the same way.
+ */
+{ type: "TooManyRequests"; message: string; retry_after_ms: number }
+/**
+ * Emitted when loading recovery data fails for any reason other than
+ * the file not existing (`recovery-load-failed`).
+ */
+export type RecoveryLoadFailed = { filename: string; error: RecoveryError }
+/**
+ * Emitted after emergency data is saved (`recovery-saved`).
+ */
+export type RecoverySaved = { filename: string; 
+/**
+ * Unix timestamp in milliseconds
+ */
+saved_at: number }
+/**
+ * Release notes for one version, from `get_release_notes`.
+ */
+export type ReleaseNote = { version: string; 
+/**
+ * Publication date (RFC 3339)
+ */
+date: string | null; 
+/**
+ * Release notes as markdown
+ */
+notes: string }
+/**
+ * Release notes for every version between two versions, newest first.
+ */
+export type ReleaseNotes = { releases: ReleaseNote[]; source: ReleaseNotesSource }
+/**
+ * Where `get_release_notes` found its notes.
+ */
+export type ReleaseNotesSource = 
+/**
+ * Fetched from the changelog just now
+ */
+"Changelog" | 
+/**
+ * Saved from an earlier fetch (recent, or the changelog couldn't be reached)
+ */
+"Cache" | 
+/**
+ * Only the update manifest's notes for the newest version were available
+ */
+"Manifest"
+/**
+ * A revision as listed by `list_revisions`.
+ */
+export type RevisionSummary = { id: number; title: string; 
+/**
+ * Content size in bytes
+ */
+size: number; 
+/**
+ * Unix timestamp in milliseconds
+ */
+created_at: number }
+/**
+ * A corner of the screen, used to place mini windows.
+ */
+export type ScreenCorner = "TopLeft" | "TopRight" | "BottomLeft" | "BottomRight"
+/**
+ * Searchable text for `index_document`.
+ */
+export type SearchFields = { 
+/**
+ * Weighted above the body when ranking
+ */
+title: string; body: string }
+/**
+ * A match returned by `search`.
+ */
+export type SearchResult = { id: string; title: HighlightSegment[]; 
+/**
+ * The best-matching part of the body, with `…` where it was cut
+ */
+snippet: HighlightSegment[]; 
+/**
+ * Relevance; higher is better. Only comparable within one search.
+ */
+score: number }
+/**
+ * Error types for secret storage (typed for frontend matching)
+ */
+export type SecretError = 
+/**
+ * No secret is stored for the service and key
+ */
+{ type: "NotFound" } | 
+/**
+ * The keychain is locked or the user denied access
+ */
+{ type: "Locked"; message: string } | 
+/**
+ * Service, key or value failed validation
+ */
+{ type: "ValidationError"; message: string } | 
+/**
+ * Any other keychain failure, including no keychain being available
+ */
+{ type: "PlatformError"; message: string }
+/**
+ * Where the share sheet points, in CSS pixels in the calling webview, e.g.
+ * a share button's `getBoundingClientRect()`.
+ */
+export type ShareAnchor = { x: number; y: number; width: number; height: number }
+/**
+ * Something to share with `share_items`.
+ */
+export type ShareItem = { kind: "Text"; text: string } | 
+/**
+ * An absolute path in the app's file scope
+ */
+{ kind: "File"; path: string }
+/**
+ * A message on `sse_subscribe`'s channel.
+ */
+export type SseEvent = 
+/**
+ * Connected, or reconnected
+ */
+{ type: "Open" } | 
+/**
+ * An event from the server. `event` is "message" unless the server
+ * named it
+ */
+{ type: "Message"; event: string; data: string; id: string | null } | 
+/**
+ * The connection failed or dropped. `retry_in_ms` is None if the server
+ * refused the stream and the subscription has ended
+ */
+{ type: "Error"; message: string; retry_in_ms: number | null }
+/**
+ * A point in startup recorded by `get_performance_metrics`.
+ */
+export type StartupMilestone = 
+/**
+ * The setup hook started. Windows from `tauri.conf.json` exist by now.
+ */
+"SetupStarted" | "SetupFinished" | 
+/**
+ * The main window's page started loading
+ */
+"MainPageLoadStarted" | 
+/**
+ * The main window's page finished loading
+ */
+"MainPageLoaded" | 
+/**
+ * The frontend called `finish_startup`
+ */
+"FrontendReady"
+/**
+ * When a startup milestone was reached.
+ */
+export type StartupTiming = { milestone: StartupMilestone; 
+/**
+ * Milliseconds since the process started
+ */
+elapsed_ms: number }
+/**
+ * A message on a streaming command's channel (see `commands::streams`).
+ * Items arrive in order, then exactly one `End` or `Error`, unless the
+ * stream is stopped.
+ */
+export type StreamMessage<T, E> = { type: "Item"; data: T } | { type: "End" } | { type: "Error"; error: E }
+/**
+ * A local change to a record, queued for the sync engine to push.
+ */
+export type SyncChange = { 
+/**
+ * The kind of record, e.g. "notes"
+ */
+collection: string; record_id: string; operation: SyncOperation; 
+/**
+ * The record's new state, for `Upsert`
+ */
+payload: JsonValue | null; 
+/**
+ * The remote version the change was made on top of, if known, so the
+ * backend can detect conflicting edits
+ */
+base_version: string | null }
+/**
+ * What a change does to its record.
+ */
+export type SyncOperation = 
+/**
+ * Created or updated; `payload` is the new state
+ */
+"Upsert" | "Delete"
+/**
+ * What the sync engine is doing.
+ */
+export type SyncState = 
+/**
+ * No backend is configured
+ */
+"Disabled" | 
+/**
+ * Waiting for the next scheduled sync
+ */
+"Idle" | "Syncing" | 
+/**
+ * The last sync failed; retrying at `next_attempt_at`
+ */
+"Failed"
+/**
+ * The sync engine's status, from `get_sync_status` and broadcast as
+ * `sync-status` whenever it changes.
+ */
+export type SyncStatus = { state: SyncState; 
+/**
+ * Local changes not pushed yet
+ */
+pending_changes: number; 
+/**
+ * When the last successful sync finished, in milliseconds since the Unix
+ * epoch
+ */
+last_synced_at: number | null; 
+/**
+ * Why the last sync failed, for `Failed`
+ */
+last_error: string | null; 
+/**
+ * When the next sync is scheduled, in milliseconds since the Unix epoch
+ */
+next_attempt_at: number | null }
+/**
+ * Event: the theme was changed in one window, for the others to follow.
+ * Emitted by the frontend.
+ */
+export type ThemeChanged = { 
+/**
+ * "light", "dark" or "system"
+ */
+theme: string }
+/**
+ * Title bar appearance for app windows.
+ */
+export type TitleBarStyle = 
+/**
+ * Standard native title bar
+ */
+"Visible" | 
+/**
+ * Native title bar drawn over a transparent background (macOS only)
+ */
+"Transparent" | 
+/**
+ * Content extends under the title bar; the app draws its own chrome.
+ * macOS keeps the traffic lights, other platforms hide decorations entirely.
+ */
+"Overlay"
+/**
+ * Logical position of the macOS traffic lights, measured from the window's
+ * top-left corner to the top-left of the close button.
+ */
+export type TrafficLightInset = { x: number; y: number }
+/**
+ * How many edits to a document can be undone and redone.
+ */
+export type UndoDepth = { undo_depth: number; redo_depth: number }
+/**
+ * Broadcast to all windows when a document's undo history changes
+ * (`undo-state-changed`).
+ */
+export type UndoStateChanged = { document_id: string; undo_depth: number; redo_depth: number }
+/**
+ * Emitted to the main window when a scheduled check finds an update
+ * (`update-available`). Sent at most once a day.
+ */
+export type UpdateAvailable = { update: UpdateInfo }
+/**
+ * Release channel the updater checks. Each channel has its own update manifest.
+ */
+export type UpdateChannel = 
+/**
+ * Full releases only
+ */
+"Stable" | 
+/**
+ * Pre-releases for users who opt in to test upcoming versions
+ */
+"Beta" | 
+/**
+ * Builds from the latest development code
+ */
+"Nightly"
+/**
+ * An update found by `check_for_update`.
+ */
+export type UpdateInfo = { version: string; current_version: string; channel: UpdateChannel; 
+/**
+ * Release notes from the update manifest
+ */
+notes: string | null; 
+/**
+ * Publication date from the update manifest (RFC 3339)
+ */
+date: string | null }
+/**
+ * Progress of `download_and_install_update`, broadcast to all windows
+ * (`update-progress`).
+ */
+export type UpdateProgress = 
+/**
+ * Checking the update channel for a new version
+ */
+{ stage: "Checking" } | 
+/**
+ * Downloading the update, in bytes. `total` is None if the server didn't
+ * send a size.
+ */
+{ stage: "Downloading"; downloaded: number; total: number | null } | 
+/**
+ * Verifying the signature and installing the downloaded update
+ */
+{ stage: "Installing" } | 
+/**
+ * Installed; `restart_to_update` relaunches into the new version
+ */
+{ stage: "ReadyToRestart"; version: string }
+/**
+ * A text field sent alongside the file by `upload_file`.
+ */
+export type UploadField = { name: string; value: string }
+/**
+ * Upload progress sent to `upload_file`'s `on_progress` channel, in bytes of
+ * the request body. Restarts from zero (or the resumed offset) on a retry.
+ */
+export type UploadProgress = { uploaded: number; total: number; 
+/**
+ * 1 for the first attempt
+ */
+attempt: number }
+/**
+ * A day of the week.
+ */
+export type Weekday = "Monday" | "Tuesday" | "Wednesday" | "Thursday" | "Friday" | "Saturday" | "Sunday"
+/**
+ * Describes an app window to create with `create_app_window`.
+ * Optional fields fall back to sensible defaults.
+ */
+export type WindowConfig = { 
+/**
+ * Unique window label starting with `window-` (alphanumeric, `-`, `/`, `:` and `_`)
+ */
+label: string; 
+/**
+ * App-relative path of the page to load (e.g. `index.html#/detail/42`)
+ */
+url: string; title: string | null; 
+/**
+ * Logical width (default 800)
+ */
+width: number | null; 
+/**
+ * Logical height (default 600)
+ */
+height: number | null; 
+/**
+ * Show native title bar and borders (default true)
+ */
+decorations: boolean | null; 
+/**
+ * Label of the window this one belongs to (e.g. a tool palette's document)
+ */
+parent: string | null; 
+/**
+ * Disable the parent window until this one closes (requires `parent`)
+ */
+modal: boolean | null; 
+/**
+ * Title bar appearance (default `Visible`)
+ */
+title_bar_style: TitleBarStyle | null; 
+/**
+ * Hide the title text while keeping the title bar (macOS only)
+ */
+hidden_title: boolean | null; 
+/**
+ * Custom traffic light position, typically used with `Overlay` (macOS only)
+ */
+traffic_light_inset: TrafficLightInset | null }
+/**
+ * Emitted when a window gains or loses focus (`window-focus-changed`).
+ */
+export type WindowFocusChanged = { label: string; focused: boolean }
+/**
+ * Emitted when a window enters or exits fullscreen (`window-fullscreen-changed`).
+ */
+export type WindowFullscreenChanged = { label: string; fullscreen: boolean }
+/**
+ * How high a window floats relative to other windows.
+ */
+export type WindowLevel = 
+/**
+ * Regular document window
+ */
+"Normal" | 
+/**
+ * Floats above normal windows, like tool palettes and inspectors
+ */
+"Floating" | 
+/**
+ * Floats above floating windows, alongside the menu bar (macOS).
+ * Same as `Floating` on other platforms.
+ */
+"Status"
+/**
+ * Emitted when a window moves (`window-moved`). Logical pixels.
+ */
+export type WindowMoved = { label: string; x: number; y: number }
+/**
+ * Emitted when a window's inner size changes (`window-resized`). Logical pixels.
+ */
+export type WindowResized = { label: string; width: number; height: number }
+/**
+ * System appearance reported for a window.
+ */
+export type WindowTheme = "Light" | "Dark"
+/**
+ * Emitted when the system appearance of a window changes (`window-theme-changed`).
+ */
+export type WindowThemeChanged = { label: string; theme: WindowTheme }
+/**
+ * A connection opened with `ws_connect`, from `ws_connections`.
+ */
+export type WsConnection = { id: number; url: string; status: WsStatus }
+/**
+ * Event: a message arrived on a WebSocket connection, or its status changed.
+ */
+export type WsEvent = { type: "Message"; id: number; message: WsMessage } | { type: "StatusChanged"; id: number; status: WsStatus; 
+/**
+ * Why the connection was lost, for `Reconnecting`
+ */
+error: string | null }
+/**
+ * A WebSocket message, sent with `ws_send` or received in a `WsEvent`.
+ */
+export type WsMessage = { type: "Text"; text: string } | { type: "Binary"; bytes: number[] }
+/**
+ * The state of a connection opened with `ws_connect`.
+ */
+export type WsStatus = 
+/**
+ * Opening for the first time
+ */
+"Connecting" | "Open" | 
+/**
+ * Lost, and waiting to connect again
+ */
+"Reconnecting" | 
+/**
+ * Closed with `ws_close`; the id is no longer valid
  */
-{ type: "ParseError"; message: string }
+"Closed"
 
 /** tauri-specta globals **/
 
@@ -84,14 +84,24 @@ describe('context-menu utilities', () => {
     await showEditContextMenu()
 
     // Should create Cut, Copy, Paste, Separator, SelectAll
-    expect(mockPredefinedMenuItemNew).toHaveBeenCalledWith({ item: 'Cut' })
-    expect(mockPredefinedMenuItemNew).toHaveBeenCalledWith({ item: 'Copy' })
-    expect(mockPredefinedMenuItemNew).toHaveBeenCalledWith({ item: 'Paste' })
+    expect(mockPredefinedMenuItemNew).toHaveBeenCalledWith({
+      item: 'Cut',
+      text: 'Cut',
+    })
+    expect(mockPredefinedMenuItemNew).toHaveBeenCalledWith({
+      item: 'Copy',
+      text: 'Copy',
+    })
+    expect(mockPredefinedMenuItemNew).toHaveBeenCalledWith({
+      item: 'Paste',
+      text: 'Paste',
+    })
     expect(mockPredefinedMenuItemNew).toHaveBeenCalledWith({
       item: 'Separator',
     })
     expect(mockPredefinedMenuItemNew).toHaveBeenCalledWith({
       item: 'SelectAll',
+      text: 'Select All',
     })
     expect(mockPopup).toHaveBeenCalled()
   })
@@ -102,8 +112,14 @@ describe('context-menu utilities', () => {
     await showTextInputContextMenu()
 
     // Should include Undo and Redo
-    expect(mockPredefinedMenuItemNew).toHaveBeenCalledWith({ item: 'Undo' })
-    expect(mockPredefinedMenuItemNew).toHaveBeenCalledWith({ item: 'Redo' })
+    expect(mockPredefinedMenuItemNew).toHaveBeenCalledWith({
+      item: 'Undo',
+      text: 'Undo',
+    })
+    expect(mockPredefinedMenuItemNew).toHaveBeenCalledWith({
+      item: 'Redo',
+      text: 'Redo',
+    })
     expect(mockPopup).toHaveBeenCalled()
   })
 })
@@ -1,9 +1,11 @@
 import { Menu, MenuItem, PredefinedMenuItem } from '@tauri-apps/api/menu'
+import { roleLabel } from '@/lib/menu-items'
 
 /**
  * Context menu utilities for native right-click menus.
  *
- * Uses Tauri's built-in Menu API (no plugin required).
+ * Uses Tauri's built-in Menu API (no plugin required). Standard items are
+ * labelled in the current language, matching the app menu's Edit menu.
  *
  * @example
  * ```typescript
@@ -43,6 +45,15 @@ function isSeparator(item: ContextMenuEntry): item is ContextMenuSeparator {
   return 'type' in item && item.type === 'separator'
 }
 
+type EditItem = 'Undo' | 'Redo' | 'Cut' | 'Copy' | 'Paste' | 'SelectAll'
+
+/**
+ * Create a native edit item with the same translated label as the app menu.
+ */
+function editItem(item: EditItem) {
+  return PredefinedMenuItem.new({ item, text: roleLabel(item) })
+}
+
 /**
  * Show a custom context menu at the current cursor position.
  */
@@ -75,11 +86,11 @@ export async function showContextMenu(
 export async function showEditContextMenu(): Promise<void> {
   const menu = await Menu.new({
     items: [
-      await PredefinedMenuItem.new({ item: 'Cut' }),
-      await PredefinedMenuItem.new({ item: 'Copy' }),
-      await PredefinedMenuItem.new({ item: 'Paste' }),
+      await editItem('Cut'),
+      await editItem('Copy'),
+      await editItem('Paste'),
       await PredefinedMenuItem.new({ item: 'Separator' }),
-      await PredefinedMenuItem.new({ item: 'SelectAll' }),
+      await editItem('SelectAll'),
     ],
   })
   await menu.popup()
@@ -92,14 +103,14 @@ export async function showEditContextMenu(): Promise<void> {
 export async function showTextInputContextMenu(): Promise<void> {
   const menu = await Menu.new({
     items: [
-      await PredefinedMenuItem.new({ item: 'Undo' }),
-      await PredefinedMenuItem.new({ item: 'Redo' }),
+      await editItem('Undo'),
+      await editItem('Redo'),
       await PredefinedMenuItem.new({ item: 'Separator' }),
-      await PredefinedMenuItem.new({ item: 'Cut' }),
-      await PredefinedMenuItem.new({ item: 'Copy' }),
-      await PredefinedMenuItem.new({ item: 'Paste' }),
+      await editItem('Cut'),
+      await editItem('Copy'),
+      await editItem('Paste'),
       await PredefinedMenuItem.new({ item: 'Separator' }),
-      await PredefinedMenuItem.new({ item: 'SelectAll' }),
+      await editItem('SelectAll'),
     ],
   })
   await menu.popup()
@@ -0,0 +1,37 @@
+/**
+ * Moving the app's data to another folder, e.g. one synced between machines.
+ *
+ * Rust copies the data with `moveAppData` and restarts the app to use it, so
+ * nothing needs reloading here. The old copy is removed after the restart.
+ */
+import { ask, open } from '@tauri-apps/plugin-dialog'
+import { toast } from 'sonner'
+import i18n from '@/i18n/config'
+import { logger } from '@/lib/logger'
+import { commands } from '@/lib/tauri-bindings'
+
+/**
+ * Ask for a folder and, once the user confirms, move the app's data there.
+ * The app restarts on success.
+ */
+export async function moveDataLocation(): Promise<void> {
+  const path = await open({ directory: true, multiple: false })
+  if (!path) return
+
+  const t = i18n.t.bind(i18n)
+  const confirmed = await ask(t('dataLocation.moveConfirmMessage', { path }), {
+    title: t('dataLocation.moveConfirmTitle'),
+    kind: 'warning',
+    okLabel: t('dataLocation.move'),
+    cancelLabel: t('dataLocation.cancel'),
+  })
+  if (!confirmed) return
+
+  const result = await commands.moveAppData(path)
+  if (result.status === 'error') {
+    logger.error('Failed to move app data', { error: result.error })
+    toast.error(
+      t('toast.error.moveDataFailed', { message: result.error.message })
+    )
+  }
+}
@@ -0,0 +1,163 @@
+/**
+ * Diagnostics bundles and crash reports.
+ *
+ * Rust writes a crash report when the app panics and announces it with
+ * `crash-report-available` on the next launch. This offers to save it in a
+ * diagnostics bundle for a support ticket, then dismisses it either way so
+ * the user is only asked once.
+ *
+ * Uncaught frontend errors are sent to Rust with `reportFrontendError`, so
+ * they're in the app log and in diagnostics bundles alongside backend ones.
+ */
+import { save } from '@tauri-apps/plugin-dialog'
+import { toast } from 'sonner'
+import i18n from '@/i18n/config'
+import { logger } from '@/lib/logger'
+import {
+  commands,
+  events,
+  type CrashReport,
+  type DebugInfo,
+  type JsonValue,
+} from '@/lib/tauri-bindings'
+
+/**
+ * Ask where to save a diagnostics bundle and write it there.
+ * Returns whether a bundle was saved.
+ */
+export async function saveDiagnosticsBundle(): Promise<boolean> {
+  const path = await save({
+    defaultPath: 'diagnostics.zip',
+    filters: [{ name: 'Zip', extensions: ['zip'] }],
+  })
+  if (!path) return false
+
+  const result = await commands.exportDiagnostics(path)
+  if (result.status === 'error') {
+    logger.error('Failed to export diagnostics', { error: result.error })
+    toast.error(
+      i18n.t('toast.error.diagnosticsExportFailed', {
+        message: result.error.message,
+      })
+    )
+    return false
+  }
+
+  logger.info('Diagnostics exported', { files: result.data.files.length })
+  toast.success(i18n.t('toast.success.diagnosticsExported'))
+  return true
+}
+
+/** Format debug info as plain text for the About dialog and bug reports. */
+export function formatDebugInfo(info: DebugInfo): string {
+  return [
+    `${info.app_name} ${info.app_version}${info.debug_build ? ' (debug)' : ''}`,
+    `Identifier: ${info.identifier}`,
+    `OS: ${info.os} ${info.os_version} (${info.arch})`,
+    `Tauri: ${info.tauri_version}`,
+    `WebView: ${info.webview_version ?? 'unknown'}`,
+    `Locale: ${info.locale ?? 'unknown'}`,
+    `Features: ${info.features.join(', ') || 'none'}`,
+    `Data: ${info.data_dir}`,
+    `Logs: ${info.log_dir}`,
+    `Recovery: ${info.recovery_dir}`,
+  ].join('\n')
+}
+
+async function promptToSendCrashReport(report: CrashReport): Promise<void> {
+  logger.warn(`App crashed on the last run: ${report.message}`)
+
+  const shouldSave = confirm(
+    'The app quit unexpectedly last time.\n\nWould you like to save a diagnostics bundle with the crash report to send to support?'
+  )
+  if (shouldSave) {
+    await saveDiagnosticsBundle()
+  }
+
+  const result = await commands.dismissCrashReport(report.id)
+  if (result.status === 'error') {
+    logger.error('Failed to dismiss crash report', { error: result.error })
+  }
+}
+
+/**
+ * Offer to send crash reports from the previous launch.
+ * Returns an unsubscribe function for cleanup.
+ */
+export function setupCrashReportListener(): () => void {
+  let disposed = false
+  let unlisten: (() => void) | null = null
+
+  events.crashReportAvailable
+    .listen(event => {
+      promptToSendCrashReport(event.payload.report).catch(error => {
+        logger.error('Crash report prompt failed', { error })
+      })
+    })
+    .then(unlistenFn => {
+      if (disposed) {
+        unlistenFn()
+      } else {
+        unlisten = unlistenFn
+      }
+    })
+    .catch(error => {
+      logger.error('Failed to setup crash report listener', { error })
+    })
+
+  return () => {
+    disposed = true
+    unlisten?.()
+  }
+}
+
+/**
+ * Send an uncaught error to Rust to be logged and saved as a crash report.
+ * `context` is extra app state to attach; it must be JSON-serializable.
+ */
+export async function reportFrontendError(
+  error: unknown,
+  context: Record<string, unknown> = {}
+): Promise<void> {
+  const message =
+    error instanceof Error ? `${error.name}: ${error.message}` : String(error)
+  const stack = error instanceof Error ? (error.stack ?? null) : null
+
+  try {
+    const result = await commands.reportFrontendError(message, stack, {
+      path: window.location.pathname,
+      ...context,
+    } as JsonValue)
+    if (result.status === 'error') {
+      logger.error('Failed to report frontend error', { error: result.error })
+    }
+  } catch (reportError) {
+    // e.g. context that can't be serialized
+    logger.error('Failed to report frontend error', { error: reportError })
+  }
+}
+
+/**
+ * Report errors and unhandled promise rejections that nothing caught.
+ * Errors caught by `ErrorBoundary` are reported there instead.
+ * Returns a cleanup function.
+ */
+export function setupGlobalErrorReporting(): () => void {
+  const handleError = (event: ErrorEvent) => {
+    void reportFrontendError(event.error ?? event.message, {
+      source: event.filename,
+      line: event.lineno,
+    })
+  }
+  const handleRejection = (event: PromiseRejectionEvent) => {
+    void reportFrontendError(event.reason, { unhandledRejection: true })
+  }
+
+  window.addEventListener('error', handleError)
+  window.addEventListener('unhandledrejection', handleRejection)
+
+  return () => {
+    window.removeEventListener('error', handleError)
+    window.removeEventListener('unhandledrejection', handleRejection)
+  }
+}
@@ -0,0 +1,43 @@
+/**
+ * Turning encryption of local data on or off.
+ *
+ * Rust saves the setting and restarts the app, which converts the database,
+ * preferences and recovery data to match before opening them.
+ */
+import { ask } from '@tauri-apps/plugin-dialog'
+import { toast } from 'sonner'
+import i18n from '@/i18n/config'
+import { logger } from '@/lib/logger'
+import { commands } from '@/lib/tauri-bindings'
+
+/**
+ * Once the user confirms, turn local data encryption on or off. The app
+ * restarts on success.
+ */
+export async function setLocalDataEncryption(enabled: boolean): Promise<void> {
+  const t = i18n.t.bind(i18n)
+  const confirmed = await ask(
+    t(
+      enabled
+        ? 'encryption.enableConfirmMessage'
+        : 'encryption.disableConfirmMessage'
+    ),
+    {
+      title: t('encryption.confirmTitle'),
+      kind: 'warning',
+      okLabel: t('encryption.restart'),
+      cancelLabel: t('encryption.cancel'),
+    }
+  )
+  if (!confirmed) return
+
+  const result = await commands.setLocalDataEncryption(enabled)
+  if (result.status === 'error') {
+    logger.error('Failed to change local data encryption', {
+      error: result.error,
+    })
+    toast.error(
+      t('toast.error.encryptionFailed', { message: result.error.message })
+    )
+  }
+}
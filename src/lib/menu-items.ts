@@ -0,0 +1,76 @@
+/**
+ * Shared labels and shortcuts for menu items.
+ *
+ * Every menu that shows an action (app menu, tray, Dock menu, Jump List and
+ * context menus) takes its translated label and accelerator from here by id,
+ * so the same action can't end up with different names or shortcuts in
+ * different menus or languages.
+ */
+import i18n from '@/i18n/config'
+import type { MenuRole } from '@/lib/tauri-bindings'
+import type en from '../../locales/en.json'
+
+export const APP_NAME = 'Tauri Template'
+
+type TranslationKey = keyof typeof en
+
+interface MenuItemDefinition {
+  labelKey: TranslationKey
+  accelerator?: string
+}
+
+/** Menu actions by id. Handlers live in `menuActions` in `menu.ts`. */
+const menuItemDefinitions: Record<string, MenuItemDefinition> = {
+  about: { labelKey: 'menu.about' },
+  'check-updates': { labelKey: 'menu.checkForUpdates' },
+  'clear-recent-documents': { labelKey: 'menu.clearRecent' },
+  'install-update-from-file': { labelKey: 'menu.installUpdateFromFile' },
+  'new-quick-entry': { labelKey: 'menu.newQuickEntry' },
+  preferences: { labelKey: 'menu.preferences', accelerator: 'CmdOrCtrl+,' },
+  quit: { labelKey: 'menu.quit', accelerator: 'CmdOrCtrl+Q' },
+  'show-main-window': { labelKey: 'menu.showMainWindow' },
+  'toggle-left-sidebar': {
+    labelKey: 'menu.toggleLeftSidebar',
+    accelerator: 'CmdOrCtrl+1',
+  },
+  'toggle-right-sidebar': {
+    labelKey: 'menu.toggleRightSidebar',
+    accelerator: 'CmdOrCtrl+2',
+  },
+}
+
+/** Translated labels for standard items, which otherwise use English. */
+const roleLabelKeys: Partial<Record<MenuRole, TranslationKey>> = {
+  Hide: 'menu.hide',
+  HideOthers: 'menu.hideOthers',
+  ShowAll: 'menu.showAll',
+  Quit: 'menu.quit',
+  Undo: 'menu.undo',
+  Redo: 'menu.redo',
+  Cut: 'menu.cut',
+  Copy: 'menu.copy',
+  Paste: 'menu.paste',
+  SelectAll: 'menu.selectAll',
+  Minimize: 'menu.minimize',
+  Zoom: 'menu.zoom',
+}
+
+/**
+ * Translated label for a menu action. Falls back to the id so a missing
+ * definition shows up in the menu rather than as a blank item.
+ */
+export function menuItemLabel(id: string): string {
+  const definition = menuItemDefinitions[id]
+  return definition ? i18n.t(definition.labelKey, { appName: APP_NAME }) : id
+}
+
+/** Keyboard shortcut for a menu action, if it has one. */
+export function menuItemAccelerator(id: string): string | undefined {
+  return menuItemDefinitions[id]?.accelerator
+}
+
+/** Translated label for a standard item, or undefined to use the OS label. */
+export function roleLabel(role: MenuRole): string | undefined {
+  const key = roleLabelKeys[role]
+  return key ? i18n.t(key, { appName: APP_NAME }) : undefined
+}
@@ -1,106 +1,168 @@
 /**
- * Application menu builder using Tauri's JavaScript API.
+ * Application menu definition.
  *
- * This module creates native menus from JavaScript, enabling i18n support
- * through react-i18next. Menus are rebuilt when the language changes.
+ * The menu is described declaratively as a `MenuSpec` and built natively by
+ * the `create_app_menu` command, so adding an item is one line in
+ * `getAppMenuSpec()`, a label in `menu-items.ts` and a handler in
+ * `menuActions`. Labels are translated through react-i18next and the menus
+ * are rebuilt when the language changes.
+ * Clicks arrive as `menu-item-selected` events and are dispatched by
+ * `handleMenuItemSelected`.
  */
-import {
-  Menu,
-  MenuItem,
-  Submenu,
-  PredefinedMenuItem,
-} from '@tauri-apps/api/menu'
-import { check } from '@tauri-apps/plugin-updater'
 import i18n from '@/i18n/config'
 import { useUIStore } from '@/store/ui-store'
+import { formatDebugInfo } from '@/lib/diagnostics'
 import { logger } from '@/lib/logger'
 import { notifications } from '@/lib/notifications'
+import { installUpdateFromFile, promptToInstallUpdate } from '@/lib/updates'
+import {
+  APP_NAME,
+  menuItemAccelerator,
+  menuItemLabel,
+  roleLabel,
+} from '@/lib/menu-items'
+import {
+  commands,
+  type MenuEntrySpec,
+  type MenuRole,
+  type MenuSpec,
+} from '@/lib/tauri-bindings'
+
+// Spec helpers. Labels and accelerators come from `menu-items.ts`.
+
+function item(
+  id: string,
+  options: { checked?: boolean } = {}
+): MenuEntrySpec {
+  return {
+    type: 'Item',
+    id,
+    text: menuItemLabel(id),
+    accelerator: menuItemAccelerator(id) ?? null,
+    enabled: null,
+    checked: options.checked ?? null,
+  }
+}
+
+function submenu(
+  id: string,
+  text: string,
+  items: MenuEntrySpec[]
+): MenuEntrySpec {
+  return { type: 'Submenu', id, text, items }
+}
 
-const APP_NAME = 'Tauri Template'
+function role(menuRole: MenuRole): MenuEntrySpec {
+  return { type: 'Role', role: menuRole, text: roleLabel(menuRole) ?? null }
+}
+
+const separator: MenuEntrySpec = { type: 'Separator' }
 
 /**
- * Build and set the application menu with translated labels.
+ * Describe the application menu. Each item id needs a definition in
+ * `menu-items.ts` and a handler in `menuActions`.
  */
-export async function buildAppMenu(): Promise<Menu> {
+export function getAppMenuSpec(): MenuSpec {
   const t = i18n.t.bind(i18n)
+  // Sidebar items are kept in sync with the store by setupMenuStateSync()
+  const { leftSidebarVisible, rightSidebarVisible } = useUIStore.getState()
 
-  try {
-    // Build the main application submenu (appears as app name on macOS)
-    const appSubmenu = await Submenu.new({
-      text: APP_NAME,
-      items: [
-        await MenuItem.new({
-          id: 'about',
-          text: t('menu.about', { appName: APP_NAME }),
-          action: handleAbout,
-        }),
-        await PredefinedMenuItem.new({ item: 'Separator' }),
-        await MenuItem.new({
-          id: 'check-updates',
-          text: t('menu.checkForUpdates'),
-          action: handleCheckForUpdates,
-        }),
-        await PredefinedMenuItem.new({ item: 'Separator' }),
-        await MenuItem.new({
-          id: 'preferences',
-          text: t('menu.preferences'),
-          accelerator: 'CmdOrCtrl+,',
-          action: handleOpenPreferences,
-        }),
-        await PredefinedMenuItem.new({ item: 'Separator' }),
-        await PredefinedMenuItem.new({
-          item: 'Hide',
-          text: t('menu.hide', { appName: APP_NAME }),
-        }),
-        await PredefinedMenuItem.new({
-          item: 'HideOthers',
-          text: t('menu.hideOthers'),
-        }),
-        await PredefinedMenuItem.new({
-          item: 'ShowAll',
-          text: t('menu.showAll'),
-        }),
-        await PredefinedMenuItem.new({ item: 'Separator' }),
-        await PredefinedMenuItem.new({
-          item: 'Quit',
-          text: t('menu.quit', { appName: APP_NAME }),
-        }),
-      ],
-    })
+  return {
+    items: [
+      // Appears as the app name on macOS
+      submenu('app-menu', APP_NAME, [
+        item('about'),
+        separator,
+        item('check-updates'),
+        item('install-update-from-file'),
+        separator,
+        item('preferences'),
+        separator,
+        role('Hide'),
+        role('HideOthers'),
+        role('ShowAll'),
+        separator,
+        // Custom item rather than the Quit role so windows with unsaved
+        // changes get a chance to confirm first
+        item('quit'),
+      ]),
+      // Without these roles, Cmd+C/Cmd+V etc. don't work in text fields on macOS
+      submenu('edit-menu', t('menu.edit'), [
+        role('Undo'),
+        role('Redo'),
+        separator,
+        role('Cut'),
+        role('Copy'),
+        role('Paste'),
+        role('SelectAll'),
+      ]),
+      submenu('view-menu', t('menu.view'), [
+        item('toggle-left-sidebar', { checked: leftSidebarVisible }),
+        item('toggle-right-sidebar', { checked: rightSidebarVisible }),
+      ]),
+      // macOS also lists open windows and adds Bring All to Front here
+      submenu('window-menu', t('menu.window'), [
+        role('Minimize'),
+        role('Zoom'),
+      ]),
+    ],
+  }
+}
 
-    // Build the View submenu
-    const viewSubmenu = await Submenu.new({
-      text: t('menu.view'),
-      items: [
-        await MenuItem.new({
-          id: 'toggle-left-sidebar',
-          text: t('menu.toggleLeftSidebar'),
-          accelerator: 'CmdOrCtrl+1',
-          action: handleToggleLeftSidebar,
-        }),
-        await MenuItem.new({
-          id: 'toggle-right-sidebar',
-          text: t('menu.toggleRightSidebar'),
-          accelerator: 'CmdOrCtrl+2',
-          action: handleToggleRightSidebar,
-        }),
-      ],
-    })
+/**
+ * Describe the macOS Dock menu (shown when right-clicking the Dock icon).
+ * Items use the same ids and `menuActions` handlers as the app menu.
+ */
+export function getDockMenuSpec(): MenuEntrySpec[] {
+  return [item('new-quick-entry'), item('show-main-window')]
+}
 
-    // Build the complete menu
-    const menu = await Menu.new({
-      items: [appSubmenu, viewSubmenu],
-    })
+/**
+ * Describe the tray menu, used when the tray icon is enabled in Rust.
+ */
+export function getTrayMenuSpec(): MenuEntrySpec[] {
+  return [item('preferences'), separator, item('quit')]
+}
 
-    // Set as the application menu
-    await menu.setAsAppMenu()
+/**
+ * Build and set the application menu, Dock menu, Jump List and tray menu
+ * with translated labels.
+ */
+export async function buildAppMenu(): Promise<void> {
+  const result = await commands.createAppMenu(getAppMenuSpec())
+  if (result.status === 'error') {
+    logger.error('Failed to build application menu', { error: result.error })
+    throw new Error(result.error.message)
+  }
 
-    logger.info('Application menu built successfully')
-    return menu
-  } catch (error) {
-    logger.error('Failed to build application menu', { error })
-    throw error
+  // Does nothing on platforms without a Dock
+  const dockMenu = getDockMenuSpec()
+  const dockResult = await commands.setDockMenu(dockMenu)
+  if (dockResult.status === 'error') {
+    logger.warn('Failed to set Dock menu', { error: dockResult.error })
   }
+
+  // The Windows Jump List mirrors the Dock menu's items as tasks
+  const jumpListResult = await commands.setJumpListTasks(
+    dockMenu.flatMap(entry =>
+      entry.type === 'Item'
+        ? [{ id: entry.id, title: entry.text, description: null }]
+        : []
+    )
+  )
+  if (jumpListResult.status === 'error') {
+    logger.warn('Failed to set Jump List tasks', {
+      error: jumpListResult.error,
+    })
+  }
+
+  // Does nothing unless the tray icon is enabled
+  const trayResult = await commands.setTrayMenu(getTrayMenuSpec())
+  if (trayResult.status === 'error') {
+    logger.warn('Failed to set tray menu', { error: trayResult.error })
+  }
+
+  logger.info('Application menu built successfully')
 }
 
 /**
@@ -120,36 +182,121 @@ export function setupMenuLanguageListener(): () => void {
   return () => i18n.off('languageChanged', handler)
 }
 
+/**
+ * Keep menu checkmarks in sync with app state, however it changes (menu,
+ * keyboard shortcut, command palette...). Returns an unsubscribe function.
+ */
+export function setupMenuStateSync(): () => void {
+  const setChecked = async (id: string, checked: boolean) => {
+    const result = await commands.setMenuItemChecked(id, checked)
+    if (result.status === 'error') {
+      logger.warn('Failed to update menu checkmark', {
+        id,
+        error: result.error,
+      })
+    }
+  }
+
+  return useUIStore.subscribe((state, prevState) => {
+    if (state.leftSidebarVisible !== prevState.leftSidebarVisible) {
+      void setChecked('toggle-left-sidebar', state.leftSidebarVisible)
+    }
+    if (state.rightSidebarVisible !== prevState.rightSidebarVisible) {
+      void setChecked('toggle-right-sidebar', state.rightSidebarVisible)
+    }
+  })
+}
+
+/**
+ * Run a menu action by item id. Clicks on app menu and tray menu items arrive
+ * as `menu-item-selected` events, so they share these handlers.
+ */
+export function handleMenuItemSelected(id: string): void {
+  const action = menuActions[id]
+  if (!action) {
+    logger.warn('No handler for menu item', { id })
+    return
+  }
+  Promise.resolve(action()).catch(error => {
+    logger.error('Menu action failed', { id, error })
+  })
+}
+
 // Menu action handlers
 
-function handleAbout(): void {
+async function handleAbout(): Promise<void> {
   logger.info('About menu item clicked')
-  alert(
-    `${APP_NAME}\n\nVersion: ${__APP_VERSION__}\n\nBuilt with Tauri v2 + React + TypeScript`
-  )
+  const result = await commands.getDebugInfo()
+  if (result.status === 'error') {
+    logger.error('Failed to get debug info', { error: result.error })
+    alert(
+      `${APP_NAME}\n\nVersion: ${__APP_VERSION__}\n\nBuilt with Tauri v2 + React + TypeScript`
+    )
+    return
+  }
+
+  const info = formatDebugInfo(result.data)
+  if (confirm(`${info}\n\nCopy these details for a bug report?`)) {
+    await navigator.clipboard.writeText(info)
+  }
 }
 
 async function handleCheckForUpdates(): Promise<void> {
   logger.info('Check for Updates menu item clicked')
-  try {
-    const update = await check()
-    if (update) {
-      notifications.info(
-        'Update Available',
-        `Version ${update.version} is available`
-      )
-    } else {
-      notifications.success('Up to Date', 'You are running the latest version')
-    }
-  } catch (error) {
-    logger.error('Update check failed', { error })
+  const result = await commands.checkForUpdate()
+  if (result.status === 'error') {
+    logger.error('Update check failed', { error: result.error })
     notifications.error('Update Check Failed', 'Could not check for updates')
+    return
+  }
+
+  if (result.data) {
+    await promptToInstallUpdate(result.data)
+  } else {
+    notifications.success('Up to Date', 'You are running the latest version')
   }
 }
 
-function handleOpenPreferences(): void {
+async function handleInstallUpdateFromFile(): Promise<void> {
+  logger.info('Install Update from File menu item clicked')
+  await installUpdateFromFile()
+}
+
+async function handleOpenPreferences(): Promise<void> {
   logger.info('Preferences menu item clicked')
-  useUIStore.getState().setPreferencesOpen(true)
+  const result = await commands.openPreferencesWindow()
+  if (result.status === 'error') {
+    logger.error('Failed to open preferences window', { error: result.error })
+  }
+}
+
+async function handleNewQuickEntry(): Promise<void> {
+  logger.info('New Quick Entry menu item clicked')
+  const result = await commands.showQuickPane(null)
+  if (result.status === 'error') {
+    logger.error('Failed to show quick pane', { error: result.error })
+  }
+}
+
+async function handleShowMainWindow(): Promise<void> {
+  logger.info('Show Main Window menu item clicked')
+  const result = await commands.showMainWindow()
+  if (result.status === 'error') {
+    logger.error('Failed to show main window', { error: result.error })
+  }
+}
+
+async function handleQuit(): Promise<void> {
+  logger.info('Quit menu item clicked')
+  await commands.requestAppQuit()
+}
+
+async function handleClearRecentDocuments(): Promise<void> {
+  logger.info('Clear Recent Documents menu item clicked')
+  const result = await commands.clearRecentDocuments()
+  if (result.status === 'error') {
+    logger.error('Failed to clear recent documents', { error: result.error })
+  }
 }
 
 function handleToggleLeftSidebar(): void {
@@ -161,3 +308,17 @@ function handleToggleRightSidebar(): void {
   logger.info('Toggle Right Sidebar menu item clicked')
   useUIStore.getState().toggleRightSidebar()
 }
+
+/** Handlers by menu item id, for `handleMenuItemSelected`. */
+const menuActions: Record<string, () => void | Promise<void>> = {
+  about: handleAbout,
+  'check-updates': handleCheckForUpdates,
+  'clear-recent-documents': handleClearRecentDocuments,
+  'install-update-from-file': handleInstallUpdateFromFile,
+  'new-quick-entry': handleNewQuickEntry,
+  preferences: handleOpenPreferences,
+  quit: handleQuit,
+  'show-main-window': handleShowMainWindow,
+  'toggle-left-sidebar': handleToggleLeftSidebar,
+  'toggle-right-sidebar': handleToggleRightSidebar,
+}
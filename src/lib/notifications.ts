@@ -1,10 +1,18 @@
 /**
  * Simple notification system supporting both in-app toasts and native system notifications
+ *
+ * Native notifications can have action buttons. Clicks are delivered as
+ * `notification-clicked` and `notification-action` events, listened to with
+ * `events.notificationClicked` and `events.notificationAction`.
  */
 
 import { toast } from 'sonner'
 import { logger } from './logger'
-import { commands } from './tauri-bindings'
+import {
+  commands,
+  type NotificationActionButton,
+  type NotificationSound,
+} from './tauri-bindings'
 
 type NotificationType = 'success' | 'error' | 'info' | 'warning'
 
@@ -15,6 +23,14 @@ interface NotificationOptions {
   native?: boolean
   /** Duration in milliseconds for toasts (0 = no auto-dismiss) */
   duration?: number
+  /** Identifies a native notification in click and action events */
+  id?: string
+  /** Buttons shown on a native notification */
+  actions?: NotificationActionButton[]
+  /** Sound played with a native notification */
+  sound?: NotificationSound
+  /** Absolute path to a PNG, JPEG or GIF shown in a native notification */
+  image?: string
 }
 
 /**
@@ -34,6 +50,13 @@ interface NotificationOptions {
  *
  * // Native system notification
  * notify('Update Available', 'A new version is ready to install', { native: true })
+ *
+ * // Native notification with buttons
+ * notify('Update Available', 'Version 2.0 is ready', {
+ *   native: true,
+ *   id: 'update-available',
+ *   actions: [{ id: 'install', title: 'Install Now' }],
+ * })
  * ```
  */
 export async function notify(
@@ -41,7 +64,15 @@ export async function notify(
   message?: string,
   options: NotificationOptions = {}
 ): Promise<void> {
-  const { type = 'info', native = false, duration } = options
+  const {
+    type = 'info',
+    native = false,
+    duration,
+    id,
+    actions,
+    sound,
+    image,
+  } = options
 
   try {
     if (native) {
@@ -49,10 +80,16 @@ export async function notify(
       logger.debug('Sending native notification', { title, message, type })
       const result = await commands.sendNativeNotification(
         title,
-        message ?? null
+        message ?? null,
+        {
+          id: id ?? null,
+          actions: actions ?? [],
+          sound: sound ?? null,
+          image: image ?? null,
+        }
       )
       if (result.status === 'error') {
-        throw new Error(result.error)
+        throw new Error(result.error.message)
       }
     } else {
       // Send in-app toast notification
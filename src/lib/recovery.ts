@@ -18,6 +18,10 @@ function formatRecoveryError(error: RecoveryError): string {
       return `IO error: ${error.message}`
     case 'ParseError':
       return `Parse error: ${error.message}`
+    case 'Cancelled':
+      return 'Cancelled'
+    case 'TooManyRequests':
+      return error.message
   }
 }
 
@@ -14,15 +14,108 @@
  * // In event handlers - explicit error handling
  * const result = await commands.savePreferences(prefs)
  * if (result.status === 'error') {
- *   toast.error(result.error)
+ *   toast.error(result.error.message)
  * }
  * ```
  *
  * @see docs/developer/tauri-commands.md for full documentation
  */
 
-export { commands, type Result } from './bindings'
-export type { AppPreferences, JsonValue, RecoveryError } from './bindings'
+export { commands, events, type Result } from './bindings'
+export type {
+  AllowedPath,
+  AppError,
+  AppPreferences,
+  AuthError,
+  AuthRequest,
+  AuthSession,
+  BackupManifest,
+  BatchRequest,
+  BatchResponse,
+  ClipboardContent,
+  ClipboardData,
+  ClipboardEntry,
+  CommandTiming,
+  CrashReport,
+  CursorPosition,
+  Display,
+  DisplayRect,
+  CrashReportKind,
+  DataLocationInfo,
+  DbExecuteResult,
+  DbQueryResult,
+  DebugInfo,
+  DiagnosticsSummary,
+  Document,
+  DocumentRevision,
+  DocumentSummary,
+  Download,
+  DownloadStatus,
+  FileChunk,
+  FileError,
+  HashAlgorithm,
+  HighlightSegment,
+  HttpBody,
+  HttpError,
+  HttpHeader,
+  HttpMethod,
+  HttpProgress,
+  HttpRequest,
+  HttpResponse,
+  HttpResponseBody,
+  HttpResponseType,
+  JsonValue,
+  KvEntry,
+  LocalService,
+  LocaleInfo,
+  LogFormat,
+  LogLevel,
+  MeasurementSystem,
+  MenuEntrySpec,
+  MenuRole,
+  MenuSpec,
+  Note,
+  NotificationActionButton,
+  NotificationPermission,
+  NotificationSound,
+  Peer,
+  PerformanceMetrics,
+  PermissionKind,
+  PermissionStatus,
+  PowerChange,
+  PowerSource,
+  PowerState,
+  RecoveredDocument,
+  RecoveryError,
+  ReleaseNote,
+  ReleaseNotes,
+  ReleaseNotesSource,
+  RevisionSummary,
+  SearchFields,
+  SearchResult,
+  SecretError,
+  ShareAnchor,
+  ShareItem,
+  StartupMilestone,
+  SseEvent,
+  StartupTiming,
+  StreamMessage,
+  SyncChange,
+  SyncOperation,
+  SyncState,
+  SyncStatus,
+  UndoDepth,
+  UpdateChannel,
+  UpdateInfo,
+  UpdateProgress,
+  UploadField,
+  UploadProgress,
+  Weekday,
+  WsConnection,
+  WsEvent,
+  WsMessage,
+  WsStatus,
+} from './bindings'
 
 /**
  * Helper to unwrap a Result type, throwing on error
@@ -0,0 +1,132 @@
+/**
+ * Update prompts.
+ *
+ * Checks run in Rust (see `commands/updater.rs`): a background scheduler
+ * announces new versions with `update-available`, at most once a day. This
+ * asks the user, installs the update and offers to restart.
+ */
+import { open } from '@tauri-apps/plugin-dialog'
+import { logger } from '@/lib/logger'
+import { commands, events, type UpdateInfo } from '@/lib/tauri-bindings'
+
+/**
+ * Release notes for every version between the installed one and the update,
+ * as plain text. Falls back to the update manifest's notes.
+ */
+async function getReleaseNotesText(update: UpdateInfo): Promise<string> {
+  const result = await commands.getReleaseNotes(
+    update.current_version,
+    update.version
+  )
+  if (result.status === 'error') {
+    logger.warn('Failed to get release notes', { error: result.error })
+    return update.notes ?? ''
+  }
+  return result.data.releases
+    .map(release => `${release.version}\n${release.notes}`)
+    .join('\n\n')
+}
+
+/**
+ * Ask whether to install an update, then download, install and offer to
+ * restart into it.
+ */
+export async function promptToInstallUpdate(update: UpdateInfo): Promise<void> {
+  logger.info(`Update available: ${update.version}`)
+
+  const releaseNotes = await getReleaseNotesText(update)
+  const notes = releaseNotes ? `\n\n${releaseNotes}` : ''
+  const shouldUpdate = confirm(
+    `Update available: ${update.version}${notes}\n\nWould you like to install this update now?`
+  )
+  if (!shouldUpdate) return
+
+  // Download and install with progress logging
+  const unlistenProgress = await events.updateProgress.listen(event => {
+    const progress = event.payload
+    switch (progress.stage) {
+      case 'Downloading':
+        logger.debug(
+          `Downloaded ${progress.downloaded} of ${progress.total ?? '?'} bytes`
+        )
+        break
+      case 'Installing':
+        logger.info('Download complete, installing...')
+        break
+    }
+  })
+  const installResult = await commands.downloadAndInstallUpdate()
+  unlistenProgress()
+
+  if (installResult.status === 'error') {
+    logger.error(`Update installation failed: ${installResult.error.message}`)
+    alert(
+      `Update failed: There was a problem with the automatic download.\n\n${installResult.error.message}`
+    )
+    return
+  }
+
+  await promptToRestart()
+}
+
+/**
+ * Pick an update bundle downloaded by hand (with its `.sig` file next to it)
+ * and install it, for machines that can't reach the update server.
+ */
+export async function installUpdateFromFile(): Promise<void> {
+  const path = await open({ multiple: false, directory: false })
+  if (!path) return
+
+  const result = await commands.installUpdateFromFile(path)
+  if (result.status === 'error') {
+    logger.error(`Update installation failed: ${result.error.message}`)
+    alert(`Update failed: ${result.error.message}`)
+    return
+  }
+
+  await promptToRestart()
+}
+
+async function promptToRestart(): Promise<void> {
+  const shouldRestart = confirm(
+    'Update completed successfully!\n\nWould you like to restart the app now to use the new version?'
+  )
+
+  if (shouldRestart) {
+    const restartResult = await commands.restartToUpdate()
+    if (restartResult.status === 'error') {
+      logger.error(`Restart failed: ${restartResult.error.message}`)
+    }
+  }
+}
+
+/**
+ * Prompt to install updates found by scheduled checks.
+ * Returns an unsubscribe function for cleanup.
+ */
+export function setupUpdateAvailableListener(): () => void {
+  let disposed = false
+  let unlisten: (() => void) | null = null
+
+  events.updateAvailable
+    .listen(event => {
+      promptToInstallUpdate(event.payload.update).catch(error => {
+        logger.error('Update prompt failed', { error })
+      })
+    })
+    .then(unlistenFn => {
+      if (disposed) {
+        unlistenFn()
+      } else {
+        unlisten = unlistenFn
+      }
+    })
+    .catch(error => {
+      logger.error('Failed to setup update listener', { error })
+    })
+
+  return () => {
+    disposed = true
+    unlisten?.()
+  }
+}
@@ -0,0 +1,12 @@
+import ReactDOM from 'react-dom/client'
+import { QueryClientProvider } from '@tanstack/react-query'
+import './i18n'
+import PreferencesWindowApp from './components/preferences/PreferencesWindowApp'
+import { queryClient } from './lib/query-client'
+import './App.css'
+
+ReactDOM.createRoot(document.getElementById('root') as HTMLElement).render(
+  <QueryClientProvider client={queryClient}>
+    <PreferencesWindowApp />
+  </QueryClientProvider>
+)
@@ -0,0 +1,86 @@
+import { useEffect } from 'react'
+import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query'
+import { logger } from '@/lib/logger'
+import {
+  commands,
+  events,
+  unwrapResult,
+  type ClipboardEntry,
+} from '@/lib/tauri-bindings'
+
+// Query keys for clipboard history
+export const clipboardQueryKeys = {
+  all: ['clipboard'] as const,
+  history: () => [...clipboardQueryKeys.all, 'history'] as const,
+}
+
+/**
+ * Clipboard history, most recent first, kept current by
+ * `clipboard-changed`. Empty unless `WATCH_CLIPBOARD` is on in
+ * `commands/clipboard_history.rs`.
+ */
+export function useClipboardHistory() {
+  const queryClient = useQueryClient()
+
+  useEffect(() => {
+    let isMounted = true
+    let unlisten: (() => void) | null = null
+
+    events.clipboardChanged
+      .listen(event => {
+        const { entry } = event.payload
+        queryClient.setQueryData<ClipboardEntry[]>(
+          clipboardQueryKeys.history(),
+          history => [
+            entry,
+            ...(history ?? []).filter(existing => existing.id !== entry.id),
+          ]
+        )
+      })
+      .then(unlistenFn => {
+        if (!isMounted) {
+          unlistenFn()
+        } else {
+          unlisten = unlistenFn
+        }
+      })
+      .catch(error => {
+        logger.error('Failed to setup clipboard history listener', { error })
+      })
+
+    return () => {
+      isMounted = false
+      if (unlisten) {
+        unlisten()
+      }
+    }
+  }, [queryClient])
+
+  return useQuery({
+    queryKey: clipboardQueryKeys.history(),
+    queryFn: async (): Promise<ClipboardEntry[]> =>
+      unwrapResult(await commands.getClipboardHistory()),
+  })
+}
+
+export function useClearClipboardHistory() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async () => {
+      const result = await commands.clearClipboardHistory()
+      if (result.status === 'error') {
+        logger.error('Failed to clear clipboard history', {
+          error: result.error,
+        })
+        throw new Error(result.error.message)
+      }
+    },
+    onSuccess: () => {
+      queryClient.setQueryData<ClipboardEntry[]>(
+        clipboardQueryKeys.history(),
+        []
+      )
+    },
+  })
+}
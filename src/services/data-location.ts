@@ -0,0 +1,23 @@
+import { useQuery } from '@tanstack/react-query'
+import { logger } from '@/lib/logger'
+import { commands, type DataLocationInfo } from '@/lib/tauri-bindings'
+
+// Query keys for the data location
+export const dataLocationQueryKeys = {
+  all: ['data-location'] as const,
+}
+
+/** Where the app's data is stored, and whether the user has moved it. */
+export function useDataLocation() {
+  return useQuery({
+    queryKey: dataLocationQueryKeys.all,
+    queryFn: async (): Promise<DataLocationInfo> => {
+      const result = await commands.getDataLocation()
+      if (result.status === 'error') {
+        logger.error('Failed to get data location', { error: result.error })
+        throw new Error(result.error.message)
+      }
+      return result.data
+    },
+  })
+}
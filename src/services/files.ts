@@ -0,0 +1,121 @@
+import { Channel } from '@tauri-apps/api/core'
+import { logger } from '@/lib/logger'
+import {
+  commands,
+  type FileChunk,
+  type FileError,
+  type StreamMessage,
+} from '@/lib/tauri-bindings'
+
+function decodeBase64(data: string): Uint8Array {
+  const binary = atob(data)
+  const bytes = new Uint8Array(binary.length)
+  for (let i = 0; i < binary.length; i++) {
+    bytes[i] = binary.charCodeAt(i)
+  }
+  return bytes
+}
+
+export interface FileStream {
+  /** Resolves with the file size once every chunk was handled. */
+  done: Promise<number>
+  /** Stops the stream. `done` rejects with an `Error('cancelled')`. */
+  cancel: () => void
+}
+
+/**
+ * Read a file of any size in chunks. `onChunk` is called in order; the next
+ * chunk isn't requested until the previous call (and its promise) finishes,
+ * so slow consumers aren't flooded.
+ */
+export function streamFile(
+  path: string,
+  onChunk: (bytes: Uint8Array, offset: number) => void | Promise<void>,
+  chunkSize = 1024 * 1024
+): FileStream {
+  let streamId: number | null = null
+  let unsentAcks = 0
+  let size = 0
+  let cancelled = false
+  let queue = Promise.resolve()
+
+  let resolveDone: (size: number) => void = () => undefined
+  let rejectDone: (error: unknown) => void = () => undefined
+  const done = new Promise<number>((resolve, reject) => {
+    resolveDone = resolve
+    rejectDone = reject
+  })
+
+  // Chunks can arrive before `streamFile` returns the id, so acks for them
+  // are held until it does
+  const ack = () => {
+    if (streamId === null) {
+      unsentAcks++
+      return
+    }
+    commands.ackStream(streamId).catch(error => {
+      logger.error('Failed to acknowledge file chunk', { error })
+    })
+  }
+
+  const cancelBackend = (id: number) => {
+    commands.stopStream(id).catch(error => {
+      logger.error('Failed to cancel file stream', { error })
+    })
+  }
+
+  const channel = new Channel<StreamMessage<FileChunk, FileError>>()
+  channel.onmessage = message => {
+    queue = queue.then(async () => {
+      if (cancelled) return
+      switch (message.type) {
+        case 'Item':
+          try {
+            const bytes = decodeBase64(message.data.data)
+            await onChunk(bytes, message.data.offset)
+            size = message.data.offset + bytes.length
+            ack()
+          } catch (error) {
+            stream.cancel()
+            rejectDone(error)
+          }
+          break
+        case 'End':
+          resolveDone(size)
+          break
+        case 'Error':
+          rejectDone(message.error)
+          break
+      }
+    })
+  }
+
+  const stream: FileStream = {
+    done,
+    cancel: () => {
+      if (cancelled) return
+      cancelled = true
+      rejectDone(new Error('cancelled'))
+      if (streamId !== null) cancelBackend(streamId)
+    },
+  }
+
+  commands
+    .streamFile(path, chunkSize, channel)
+    .then(result => {
+      if (result.status === 'error') {
+        logger.error('Failed to stream file', { error: result.error, path })
+        rejectDone(result.error)
+        return
+      }
+      streamId = result.data
+      if (cancelled) {
+        cancelBackend(streamId)
+        return
+      }
+      for (; unsentAcks > 0; unsentAcks--) ack()
+    })
+    .catch(rejectDone)
+
+  return stream
+}
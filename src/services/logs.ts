@@ -0,0 +1,118 @@
+import { Channel } from '@tauri-apps/api/core'
+import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query'
+import { toast } from 'sonner'
+import { logger } from '@/lib/logger'
+import {
+  commands,
+  type LogLevel,
+  type StreamMessage,
+} from '@/lib/tauri-bindings'
+import { preferencesQueryKeys } from '@/services/preferences'
+
+// Query keys for log files
+export const logQueryKeys = {
+  all: ['logs'] as const,
+  directorySize: () => [...logQueryKeys.all, 'directory-size'] as const,
+  level: () => [...logQueryKeys.all, 'level'] as const,
+}
+
+/** Total size in bytes of the log files, including rotated ones. */
+export function useLogDirectorySize() {
+  return useQuery({
+    queryKey: logQueryKeys.directorySize(),
+    queryFn: async (): Promise<number> => {
+      const result = await commands.getLogDirectorySize()
+      if (result.status === 'error') {
+        logger.error('Failed to get log directory size', {
+          error: result.error,
+        })
+        throw new Error(result.error.message)
+      }
+      return result.data
+    },
+  })
+}
+
+/** The level currently being logged. */
+export function useLogLevel() {
+  return useQuery({
+    queryKey: logQueryKeys.level(),
+    queryFn: (): Promise<LogLevel> => commands.getLogLevel(),
+  })
+}
+
+/**
+ * Change the log level. Applies immediately and is saved to preferences by
+ * the backend, so cached preferences are refetched.
+ */
+export function useSetLogLevel() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async (level: LogLevel) => {
+      const result = await commands.setLogLevel(level)
+      if (result.status === 'error') {
+        logger.error('Failed to set log level', { error: result.error, level })
+        toast.error('Failed to change log level', {
+          description: result.error.message,
+        })
+        throw new Error(result.error.message)
+      }
+      logger.info('Log level changed', { level })
+    },
+    onSuccess: (_, level) => {
+      queryClient.setQueryData(logQueryKeys.level(), level)
+      queryClient.invalidateQueries({
+        queryKey: preferencesQueryKeys.preferences(),
+      })
+    },
+  })
+}
+
+/**
+ * Stream the log into a console view: sends the last `backlog` lines, then
+ * new lines as they're written. Returns a function that stops following.
+ */
+export function followLogs(
+  onLines: (lines: string[]) => void,
+  backlog = 500
+): () => void {
+  let disposed = false
+  let streamId: number | null = null
+
+  const start = async () => {
+    const channel = new Channel<StreamMessage<string[], string>>()
+    channel.onmessage = message => {
+      if (disposed) return
+      if (message.type === 'Item') {
+        onLines(message.data)
+      } else if (message.type === 'Error') {
+        logger.error('Stopped following logs', { error: message.error })
+      }
+    }
+    const result = await commands.followLogs(backlog, channel)
+    if (result.status === 'error') {
+      logger.error('Failed to follow logs', { error: result.error })
+      return
+    }
+
+    if (disposed) {
+      await commands.stopStream(result.data)
+    } else {
+      streamId = result.data
+    }
+  }
+
+  start().catch(error => {
+    logger.error('Failed to start following logs', { error })
+  })
+
+  return () => {
+    disposed = true
+    if (streamId !== null) {
+      commands.stopStream(streamId).catch(error => {
+        logger.error('Failed to stop following logs', { error })
+      })
+    }
+  }
+}
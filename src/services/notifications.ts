@@ -0,0 +1,95 @@
+import { useEffect } from 'react'
+import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query'
+import { logger } from '@/lib/logger'
+import {
+  commands,
+  events,
+  type NotificationPermission,
+} from '@/lib/tauri-bindings'
+
+// Query keys for notification permission
+export const notificationQueryKeys = {
+  all: ['notifications'] as const,
+  permission: () => [...notificationQueryKeys.all, 'permission'] as const,
+}
+
+/**
+ * Native notification permission, kept current by
+ * `notification-permission-changed` (e.g. after changing it in System Settings).
+ */
+export function useNotificationPermission() {
+  const queryClient = useQueryClient()
+
+  useEffect(() => {
+    let isMounted = true
+    let unlisten: (() => void) | null = null
+
+    events.notificationPermissionChanged
+      .listen(event => {
+        queryClient.setQueryData(
+          notificationQueryKeys.permission(),
+          event.payload.permission
+        )
+      })
+      .then(unlistenFn => {
+        if (!isMounted) {
+          unlistenFn()
+        } else {
+          unlisten = unlistenFn
+        }
+      })
+      .catch(error => {
+        logger.error('Failed to setup notification permission listener', {
+          error,
+        })
+      })
+
+    return () => {
+      isMounted = false
+      if (unlisten) {
+        unlisten()
+      }
+    }
+  }, [queryClient])
+
+  return useQuery({
+    queryKey: notificationQueryKeys.permission(),
+    queryFn: async (): Promise<NotificationPermission> => {
+      const result = await commands.checkNotificationPermission()
+      if (result.status === 'error') {
+        logger.error('Failed to check notification permission', {
+          error: result.error,
+        })
+        throw new Error(result.error.message)
+      }
+      return result.data
+    },
+  })
+}
+
+/**
+ * Show the system prompt asking to allow notifications. Only prompts once;
+ * after that, use `commands.openNotificationSettings()`.
+ */
+export function useRequestNotificationPermission() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async (): Promise<NotificationPermission> => {
+      const result = await commands.requestNotificationPermission()
+      if (result.status === 'error') {
+        logger.error('Failed to request notification permission', {
+          error: result.error,
+        })
+        throw new Error(result.error.message)
+      }
+      logger.info('Notification permission requested', {
+        permission: result.data,
+      })
+      return result.data
+    },
+    onSuccess: permission => {
+      queryClient.setQueryData(notificationQueryKeys.permission(), permission)
+    },
+  })
+}
@@ -0,0 +1,77 @@
+import { useEffect } from 'react'
+import { useQuery, useQueryClient } from '@tanstack/react-query'
+import { logger } from '@/lib/logger'
+import { commands, events, type PowerState } from '@/lib/tauri-bindings'
+
+// Query keys for power state
+export const powerQueryKeys = {
+  all: ['power'] as const,
+  state: () => [...powerQueryKeys.all, 'state'] as const,
+}
+
+/**
+ * Power source, battery level and lid state, kept current by
+ * `power-changed`. To react to sleep and wake, listen to
+ * `events.powerChanged` directly.
+ */
+export function usePowerState() {
+  const queryClient = useQueryClient()
+
+  useEffect(() => {
+    let isMounted = true
+    let unlisten: (() => void) | null = null
+
+    events.powerChanged
+      .listen(event => {
+        queryClient.setQueryData(powerQueryKeys.state(), event.payload.state)
+      })
+      .then(unlistenFn => {
+        if (!isMounted) {
+          unlistenFn()
+        } else {
+          unlisten = unlistenFn
+        }
+      })
+      .catch(error => {
+        logger.error('Failed to setup power state listener', { error })
+      })
+
+    return () => {
+      isMounted = false
+      if (unlisten) {
+        unlisten()
+      }
+    }
+  }, [queryClient])
+
+  return useQuery({
+    queryKey: powerQueryKeys.state(),
+    queryFn: (): Promise<PowerState> => commands.getPowerState(),
+  })
+}
+
+/**
+ * Keep the system from sleeping while `work` runs, e.g. a long export. Pass
+ * `keepDisplayOn` for things the user watches, like playback. If sleep can't
+ * be prevented, `work` runs anyway.
+ */
+export async function whileAwake<T>(
+  reason: string,
+  work: () => Promise<T>,
+  keepDisplayOn = false
+): Promise<T> {
+  const result = await commands.preventSleep(reason, keepDisplayOn)
+  if (result.status === 'error') {
+    logger.warn('Failed to prevent sleep', { error: result.error, reason })
+    return work()
+  }
+
+  try {
+    return await work()
+  } finally {
+    const released = await commands.allowSleep(result.data)
+    if (released.status === 'error') {
+      logger.error('Failed to allow sleep', { error: released.error })
+    }
+  }
+}
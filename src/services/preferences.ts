@@ -1,12 +1,18 @@
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query'
 import { toast } from 'sonner'
 import { logger } from '@/lib/logger'
-import { commands, type AppPreferences } from '@/lib/tauri-bindings'
+import {
+  commands,
+  type AppPreferences,
+  type UpdateChannel,
+} from '@/lib/tauri-bindings'
 
 // Query keys for preferences
 export const preferencesQueryKeys = {
   all: ['preferences'] as const,
   preferences: () => [...preferencesQueryKeys.all] as const,
+  launchAtLogin: () =>
+    [...preferencesQueryKeys.all, 'launch-at-login'] as const,
 }
 
 // TanStack Query hooks following the architectural patterns
@@ -22,7 +28,22 @@ export function usePreferences() {
         logger.warn('Failed to load preferences, using defaults', {
           error: result.error,
         })
-        return { theme: 'system', quick_pane_shortcut: null, language: null }
+        return {
+          theme: 'system',
+          quick_pane_shortcut: null,
+          activate_app_shortcut: null,
+          language: null,
+          restore_session: true,
+          update_channel: 'Stable',
+          update_check_interval_hours: 6,
+          log_max_file_size_mb: 5,
+          log_retention_count: 5,
+          log_level: null,
+          log_format: 'Text',
+          analytics_enabled: false,
+          encrypt_local_data: false,
+          launch_at_login: false,
+        }
       }
 
       logger.info('Preferences loaded successfully', {
@@ -48,8 +69,10 @@ export function useSavePreferences() {
           error: result.error,
           preferences,
         })
-        toast.error('Failed to save preferences', { description: result.error })
-        throw new Error(result.error)
+        toast.error('Failed to save preferences', {
+          description: result.error.message,
+        })
+        throw new Error(result.error.message)
       }
 
       logger.info('Preferences saved successfully')
@@ -62,3 +85,89 @@ export function useSavePreferences() {
     },
   })
 }
+
+/**
+ * Switch the release channel used for update checks. Saved by the backend,
+ * so the cached preferences are updated to match.
+ */
+export function useSetUpdateChannel() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async (channel: UpdateChannel) => {
+      const result = await commands.setUpdateChannel(channel)
+      if (result.status === 'error') {
+        logger.error('Failed to set update channel', {
+          error: result.error,
+          channel,
+        })
+        toast.error('Failed to change update channel', {
+          description: result.error.message,
+        })
+        throw new Error(result.error.message)
+      }
+      logger.info('Update channel changed', { channel })
+    },
+    onSuccess: (_, channel) => {
+      queryClient.setQueryData<AppPreferences>(
+        preferencesQueryKeys.preferences(),
+        preferences =>
+          preferences && { ...preferences, update_channel: channel }
+      )
+    },
+  })
+}
+
+/**
+ * Whether the app launches at login, as the OS currently has it. Refetched
+ * on focus, since it can be changed in System Settings or Task Manager.
+ */
+export function useLaunchAtLogin() {
+  return useQuery({
+    queryKey: preferencesQueryKeys.launchAtLogin(),
+    queryFn: async (): Promise<boolean> => {
+      const result = await commands.getLaunchAtLogin()
+      if (result.status === 'error') {
+        logger.error('Failed to check launch at login', {
+          error: result.error,
+        })
+        throw new Error(result.error.message)
+      }
+      return result.data
+    },
+    refetchOnWindowFocus: true,
+  })
+}
+
+/**
+ * Turn launching at login on or off. The backend also saves the choice to
+ * preferences, so the cached preferences are updated to match.
+ */
+export function useSetLaunchAtLogin() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async (enabled: boolean) => {
+      const result = await commands.setLaunchAtLogin(enabled)
+      if (result.status === 'error') {
+        logger.error('Failed to set launch at login', {
+          error: result.error,
+          enabled,
+        })
+        toast.error('Failed to change launch at login', {
+          description: result.error.message,
+        })
+        throw new Error(result.error.message)
+      }
+      logger.info('Launch at login changed', { enabled })
+    },
+    onSuccess: (_, enabled) => {
+      queryClient.setQueryData(preferencesQueryKeys.launchAtLogin(), enabled)
+      queryClient.setQueryData<AppPreferences>(
+        preferencesQueryKeys.preferences(),
+        preferences =>
+          preferences && { ...preferences, launch_at_login: enabled }
+      )
+    },
+  })
+}
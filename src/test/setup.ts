@@ -23,10 +23,6 @@ vi.mock('@tauri-apps/api/event', () => ({
   }),
 }))
 
-vi.mock('@tauri-apps/plugin-updater', () => ({
-  check: vi.fn().mockResolvedValue(null),
-}))
-
 // Mock typed Tauri bindings (tauri-specta generated)
 vi.mock('@/lib/tauri-bindings', () => ({
   commands: {
@@ -37,12 +33,371 @@ vi.mock('@/lib/tauri-bindings', () => ({
     savePreferences: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
     sendNativeNotification: vi
       .fn()
-      .mockResolvedValue({ status: 'ok', data: null }),
+      .mockResolvedValue({ status: 'ok', data: 'notification-1' }),
     saveEmergencyData: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
     loadEmergencyData: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
     cleanupOldRecoveryFiles: vi
       .fn()
       .mockResolvedValue({ status: 'ok', data: 0 }),
+    finishStartup: vi.fn().mockResolvedValue(null),
+    batch: vi.fn().mockResolvedValue({ status: 'ok', data: [] }),
+    frontendReady: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    createAppMenu: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    setDockMenu: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    setTrayMenu: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    setJumpListTasks: vi
+      .fn()
+      .mockResolvedValue({ status: 'ok', data: null }),
+    setMenuItemChecked: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    checkForUpdate: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    downloadAndInstallUpdate: vi
+      .fn()
+      .mockResolvedValue({ status: 'ok', data: null }),
+    restartToUpdate: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    getDebugInfo: vi.fn().mockResolvedValue({
+      status: 'ok',
+      data: {
+        app_name: 'tauri-app',
+        app_version: '0.1.0',
+        identifier: 'com.example.app',
+        tauri_version: '2.0.0',
+        webview_version: null,
+        debug_build: true,
+        os: 'macos',
+        os_version: '15.0',
+        os_family: 'unix',
+        arch: 'aarch64',
+        locale: 'en-US',
+        data_dir: '/tmp/data',
+        log_dir: '/tmp/logs',
+        recovery_dir: '/tmp/data/recovery',
+        features: [],
+      },
+    }),
+    getPerformanceMetrics: vi.fn().mockResolvedValue({
+      status: 'ok',
+      data: { uptime_ms: 0, startup: [], commands: [] },
+    }),
+    getPowerState: vi.fn().mockResolvedValue({
+      source: 'Unknown',
+      battery_percent: null,
+      lid_closed: null,
+    }),
+    preventSleep: vi.fn().mockResolvedValue({ status: 'ok', data: 1 }),
+    allowSleep: vi.fn().mockResolvedValue({ status: 'ok', data: true }),
+    writeClipboard: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    readClipboard: vi.fn().mockResolvedValue({
+      status: 'ok',
+      data: { text: null, html: null, rtf: null, png: null, files: null },
+    }),
+    pasteIntoPreviousApp: vi
+      .fn()
+      .mockResolvedValue({ status: 'ok', data: null }),
+    checkPermission: vi
+      .fn()
+      .mockResolvedValue({ status: 'ok', data: 'Granted' }),
+    requestPermission: vi
+      .fn()
+      .mockResolvedValue({ status: 'ok', data: 'Granted' }),
+    openSystemSettingsPane: vi
+      .fn()
+      .mockResolvedValue({ status: 'ok', data: null }),
+    getDisplays: vi.fn().mockResolvedValue({ status: 'ok', data: [] }),
+    getLocaleInfo: vi.fn().mockResolvedValue({
+      status: 'ok',
+      data: {
+        locale: 'en-US',
+        language: 'en',
+        region: 'US',
+        first_day_of_week: 'Sunday',
+        uses_24_hour_clock: false,
+        decimal_separator: '.',
+        measurement_system: 'UnitedStates',
+      },
+    }),
+    fetch: vi.fn().mockResolvedValue({
+      status: 'ok',
+      data: {
+        status: 200,
+        ok: true,
+        url: '',
+        headers: [],
+        body: { type: 'Text', text: '' },
+      },
+    }),
+    cancelFetch: vi.fn().mockResolvedValue({ status: 'ok', data: false }),
+    uploadFile: vi.fn().mockResolvedValue({
+      status: 'ok',
+      data: {
+        status: 200,
+        ok: true,
+        url: '',
+        headers: [],
+        body: { type: 'Text', text: '' },
+      },
+    }),
+    sseSubscribe: vi.fn().mockResolvedValue({ status: 'ok', data: 1 }),
+    sseUnsubscribe: vi.fn().mockResolvedValue({ status: 'ok', data: false }),
+    startDownload: vi.fn().mockResolvedValue({
+      status: 'ok',
+      data: {
+        id: 1,
+        url: '',
+        destination: '',
+        status: 'Queued',
+        downloaded: 0,
+        total: null,
+        error: null,
+      },
+    }),
+    pauseDownload: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    resumeDownload: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    cancelDownload: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    listDownloads: vi.fn().mockResolvedValue({ status: 'ok', data: [] }),
+    clearFinishedDownloads: vi
+      .fn()
+      .mockResolvedValue({ status: 'ok', data: null }),
+    wsConnect: vi.fn().mockResolvedValue({ status: 'ok', data: 1 }),
+    wsSend: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    wsClose: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    wsConnections: vi.fn().mockResolvedValue({ status: 'ok', data: [] }),
+    getCursorPosition: vi
+      .fn()
+      .mockResolvedValue({ status: 'ok', data: { x: 0, y: 0 } }),
+    shareItems: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    getClipboardHistory: vi.fn().mockResolvedValue({ status: 'ok', data: [] }),
+    clearClipboardHistory: vi
+      .fn()
+      .mockResolvedValue({ status: 'ok', data: null }),
+    getLogDirectorySize: vi.fn().mockResolvedValue({ status: 'ok', data: 0 }),
+    followLogs: vi.fn().mockResolvedValue({ status: 'ok', data: 1 }),
+    getLogLevel: vi.fn().mockResolvedValue('Info'),
+    setLogLevel: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    trackEvent: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    purgeAnalyticsData: vi
+      .fn()
+      .mockResolvedValue({ status: 'ok', data: null }),
+    reportFrontendError: vi
+      .fn()
+      .mockResolvedValue({ status: 'ok', data: null }),
+    dbQuery: vi.fn().mockResolvedValue({
+      status: 'ok',
+      data: { columns: [], rows: [] },
+    }),
+    dbExecute: vi.fn().mockResolvedValue({
+      status: 'ok',
+      data: { rows_affected: 0, last_insert_id: 0 },
+    }),
+    listNotes: vi.fn().mockResolvedValue({ status: 'ok', data: [] }),
+    createNote: vi.fn().mockResolvedValue({
+      status: 'ok',
+      data: { id: 1, title: '', body: '', created_at: 0, updated_at: 0 },
+    }),
+    kvGet: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    kvSet: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    kvDelete: vi.fn().mockResolvedValue({ status: 'ok', data: false }),
+    kvList: vi.fn().mockResolvedValue({ status: 'ok', data: [] }),
+    indexDocument: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    removeDocument: vi.fn().mockResolvedValue({ status: 'ok', data: false }),
+    search: vi.fn().mockResolvedValue({ status: 'ok', data: [] }),
+    cachePut: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    cacheGet: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    cacheDelete: vi.fn().mockResolvedValue({ status: 'ok', data: false }),
+    cacheClear: vi.fn().mockResolvedValue({ status: 'ok', data: 0 }),
+    createDocument: vi.fn().mockResolvedValue({
+      status: 'ok',
+      data: {
+        id: 1,
+        title: '',
+        content: '',
+        created_at: 0,
+        updated_at: 0,
+        recovered: null,
+      },
+    }),
+    saveDocument: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    flushDocuments: vi.fn().mockResolvedValue(0),
+    listDocuments: vi.fn().mockResolvedValue({ status: 'ok', data: [] }),
+    listRevisions: vi.fn().mockResolvedValue({ status: 'ok', data: [] }),
+    pushUndoEntry: vi.fn().mockResolvedValue({
+      status: 'ok',
+      data: { undo_depth: 1, redo_depth: 0 },
+    }),
+    undo: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    redo: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    getUndoDepth: vi.fn().mockResolvedValue({
+      status: 'ok',
+      data: { undo_depth: 0, redo_depth: 0 },
+    }),
+    clearUndoHistory: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    storeSecret: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    getSecret: vi.fn().mockResolvedValue({
+      status: 'error',
+      error: { type: 'NotFound' },
+    }),
+    deleteSecret: vi.fn().mockResolvedValue({ status: 'ok', data: false }),
+    startAuth: vi.fn().mockResolvedValue({
+      status: 'error',
+      error: { type: 'Cancelled' },
+    }),
+    cancelAuth: vi.fn().mockResolvedValue({ status: 'ok', data: false }),
+    getAuthSession: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    signOut: vi.fn().mockResolvedValue({ status: 'ok', data: false }),
+    queueSyncChange: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    syncNow: vi.fn().mockResolvedValue({
+      status: 'error',
+      error: "Sync isn't configured",
+    }),
+    getSyncStatus: vi.fn().mockResolvedValue({
+      status: 'ok',
+      data: {
+        state: 'Disabled',
+        pending_changes: 0,
+        last_synced_at: null,
+        last_error: null,
+        next_attempt_at: null,
+      },
+    }),
+    startAdvertising: vi.fn().mockResolvedValue({ status: 'ok', data: '' }),
+    stopAdvertising: vi.fn().mockResolvedValue({ status: 'ok', data: false }),
+    browseServices: vi.fn().mockResolvedValue({ status: 'ok', data: [] }),
+    stopBrowsing: vi.fn().mockResolvedValue({ status: 'ok', data: false }),
+    createBackup: vi.fn().mockResolvedValue({
+      status: 'ok',
+      data: {
+        format_version: 1,
+        created_at: 0,
+        app_version: '0.1.0',
+        schema_version: 1,
+        files: [],
+      },
+    }),
+    restoreBackup: vi.fn().mockResolvedValue({
+      status: 'error',
+      error: 'Not a valid backup',
+    }),
+    getDataLocation: vi.fn().mockResolvedValue({
+      status: 'ok',
+      data: { path: '/mock/app-data', is_default: true },
+    }),
+    moveAppData: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    setLocalDataEncryption: vi
+      .fn()
+      .mockResolvedValue({ status: 'ok', data: null }),
+    getLaunchAtLogin: vi.fn().mockResolvedValue({ status: 'ok', data: false }),
+    setLaunchAtLogin: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    isDefaultHandlerFor: vi
+      .fn()
+      .mockResolvedValue({ status: 'ok', data: false }),
+    setAsDefaultHandler: vi
+      .fn()
+      .mockResolvedValue({ status: 'ok', data: false }),
+    readTextFile: vi.fn().mockResolvedValue({ status: 'ok', data: '' }),
+    readBinaryFile: vi.fn().mockResolvedValue({ status: 'ok', data: [] }),
+    writeTextFile: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    trashFile: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    revealInFileManager: vi
+      .fn()
+      .mockResolvedValue({ status: 'ok', data: null }),
+    openWithDefaultApp: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    previewFile: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    hashFile: vi.fn().mockResolvedValue({ status: 'ok', data: '' }),
+    cancelTask: vi.fn().mockResolvedValue({ status: 'ok', data: false }),
+    streamFile: vi.fn().mockResolvedValue({ status: 'ok', data: 1 }),
+    ackStream: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    stopStream: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    generateThumbnail: vi.fn().mockResolvedValue({ status: 'ok', data: '' }),
+    listAllowedPaths: vi.fn().mockResolvedValue({ status: 'ok', data: [] }),
+    addAllowedPath: vi.fn().mockResolvedValue({ status: 'ok', data: null }),
+    revokeAllowedPath: vi.fn().mockResolvedValue({ status: 'ok', data: true }),
+    createTempDir: vi.fn().mockResolvedValue({ status: 'ok', data: '' }),
+    removeTempDir: vi.fn().mockResolvedValue({ status: 'ok', data: true }),
+    cleanupTempDirs: vi.fn().mockResolvedValue({ status: 'ok', data: 0 }),
+    watchPath: vi.fn().mockResolvedValue({ status: 'ok', data: 1 }),
+    unwatch: vi.fn().mockResolvedValue({ status: 'ok', data: true }),
+    getReleaseNotes: vi.fn().mockResolvedValue({
+      status: 'ok',
+      data: { releases: [], source: 'Changelog' },
+    }),
+  },
+  events: {
+    quickEntrySubmitted: {
+      listen: vi.fn().mockResolvedValue(() => {
+        // Mock unlisten function
+      }),
+    },
+    menuItemSelected: {
+      listen: vi.fn().mockResolvedValue(() => {
+        // Mock unlisten function
+      }),
+    },
+    crashReportAvailable: {
+      listen: vi.fn().mockResolvedValue(() => {
+        // Mock unlisten function
+      }),
+    },
+    updateAvailable: {
+      listen: vi.fn().mockResolvedValue(() => {
+        // Mock unlisten function
+      }),
+    },
+    updateProgress: {
+      listen: vi.fn().mockResolvedValue(() => {
+        // Mock unlisten function
+      }),
+    },
+    powerChanged: {
+      listen: vi.fn().mockResolvedValue(() => {
+        // Mock unlisten function
+      }),
+    },
+    clipboardChanged: {
+      listen: vi.fn().mockResolvedValue(() => {
+        // Mock unlisten function
+      }),
+    },
+    downloadChanged: {
+      listen: vi.fn().mockResolvedValue(() => {
+        // Mock unlisten function
+      }),
+    },
+    wsEvent: {
+      listen: vi.fn().mockResolvedValue(() => {
+        // Mock unlisten function
+      }),
+    },
+    authCompleted: {
+      listen: vi.fn().mockResolvedValue(() => {
+        // Mock unlisten function
+      }),
+    },
+    syncStatus: {
+      listen: vi.fn().mockResolvedValue(() => {
+        // Mock unlisten function
+      }),
+    },
+    peerFound: {
+      listen: vi.fn().mockResolvedValue(() => {
+        // Mock unlisten function
+      }),
+    },
+    peerLost: {
+      listen: vi.fn().mockResolvedValue(() => {
+        // Mock unlisten function
+      }),
+    },
+    themeChanged: {
+      listen: vi.fn().mockResolvedValue(() => {
+        // Mock unlisten function
+      }),
+      emit: vi.fn().mockResolvedValue(undefined),
+    },
+    languageChanged: {
+      listen: vi.fn().mockResolvedValue(() => {
+        // Mock unlisten function
+      }),
+      emit: vi.fn().mockResolvedValue(undefined),
+    },
   },
   unwrapResult: vi.fn((result: { status: string; data?: unknown }) => {
     if (result.status === 'ok') return result.data
@@ -30,6 +30,8 @@ export default defineConfig(async () => ({
       input: {
         main: resolve(__dirname, 'index.html'),
         'quick-pane': resolve(__dirname, 'quick-pane.html'),
+        preferences: resolve(__dirname, 'preferences.html'),
+        splash: resolve(__dirname, 'splash.html'),
       },
     },
   },